@@ -47,7 +47,7 @@ use starknet_api::execution_resources::GasAmount;
 use starknet_api::state::ThinStateDiff;
 use starknet_api::transaction::{TransactionHash, TransactionOffsetInBlock};
 use thiserror::Error;
-use tokio::sync::{Mutex, MutexGuard};
+use tokio::sync::{watch, Mutex, MutexGuard};
 use tracing::{debug, error, info, trace, warn};
 
 use crate::block_builder::FailOnErrorCause::L1HandlerTransactionValidationFailed;
@@ -646,6 +646,9 @@ pub struct BlockBuilderFactory {
     pub contract_class_manager: ContractClassManager,
     pub class_manager_client: SharedClassManagerClient,
     pub worker_pool: BatcherWorkerPool,
+    // If set, each block is built with the latest bouncer config it holds instead of the one in
+    // `block_builder_config`.
+    pub bouncer_config_receiver: Option<watch::Receiver<BouncerConfig>>,
 }
 
 impl BlockBuilderFactory {
@@ -659,6 +662,10 @@ impl BlockBuilderFactory {
     > {
         let height = block_metadata.block_info.block_number;
         let block_builder_config = self.block_builder_config.clone();
+        let bouncer_config = match &self.bouncer_config_receiver {
+            Some(bouncer_config_receiver) => bouncer_config_receiver.borrow().clone(),
+            None => block_builder_config.bouncer_config,
+        };
         let versioned_constants = VersionedConstants::get_versioned_constants(
             block_builder_config.versioned_constants_overrides,
         );
@@ -666,7 +673,7 @@ impl BlockBuilderFactory {
             block_metadata.block_info,
             block_builder_config.chain_info,
             versioned_constants,
-            block_builder_config.bouncer_config,
+            bouncer_config,
         );
 
         let class_reader = Some(ClassReader { reader: self.class_manager_client.clone(), runtime });
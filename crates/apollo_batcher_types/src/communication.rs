@@ -1,7 +1,12 @@
 use std::sync::Arc;
 
 use apollo_infra::component_client::{ClientError, LocalComponentClient, RemoteComponentClient};
-use apollo_infra::component_definitions::{ComponentClient, ComponentRequestAndResponseSender};
+use apollo_infra::component_definitions::{
+    ComponentClient,
+    ComponentRequestAndResponseSender,
+    PrioritizedRequest,
+    RequestPriority,
+};
 use apollo_infra::impl_debug_for_infra_requests_and_responses;
 use apollo_proc_macros::handle_all_response_variants;
 use apollo_state_sync_types::state_sync_types::SyncBlock;
@@ -93,6 +98,23 @@ pub enum BatcherRequest {
 }
 impl_debug_for_infra_requests_and_responses!(BatcherRequest);
 
+impl PrioritizedRequest for BatcherRequest {
+    // Building, validating and deciding on blocks are on the consensus path.
+    fn priority(&self) -> RequestPriority {
+        match self {
+            BatcherRequest::ProposeBlock(_)
+            | BatcherRequest::GetProposalContent(_)
+            | BatcherRequest::ValidateBlock(_)
+            | BatcherRequest::SendProposalContent(_)
+            | BatcherRequest::StartHeight(_)
+            | BatcherRequest::DecisionReached(_) => RequestPriority::High,
+            BatcherRequest::GetCurrentHeight
+            | BatcherRequest::AddSyncBlock(_)
+            | BatcherRequest::RevertBlock(_) => RequestPriority::Normal,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, AsRefStr)]
 pub enum BatcherResponse {
     ProposeBlock(BatcherResult<()>),
@@ -0,0 +1,284 @@
+#[cfg(test)]
+#[path = "block_verification_test.rs"]
+mod block_verification_test;
+
+use std::time::Instant;
+
+use apollo_state_sync_metrics::metrics::CENTRAL_SYNC_BLOCK_VERIFICATION_LATENCY;
+use starknet_api::block::{Block, BlockHash, BlockHeader, BlockNumber, StarknetVersion};
+use starknet_api::block_hash::block_hash_calculator::{
+    calculate_block_hash,
+    calculate_legacy_block_commitments,
+    calculate_legacy_block_hash,
+    calculate_pre_v0_7_block_hash,
+    calculate_transactions_data_commitments,
+    concat_counts,
+    BlockHashVersion,
+    BlockHeaderCommitments,
+    TransactionHashingData,
+    TransactionOutputForHash,
+    TransactionsDataCommitments,
+};
+use starknet_api::block_hash::state_diff_hash::calculate_state_diff_hash;
+use starknet_api::core::ChainId;
+use starknet_api::state::ThinStateDiff;
+use starknet_api::transaction::fields::TransactionSignature;
+use starknet_api::transaction::Transaction;
+use starknet_api::StarknetApiError;
+use starknet_types_core::felt::Felt;
+
+#[derive(thiserror::Error, Debug)]
+pub enum BlockVerificationError {
+    #[error("The header of block {block_number} is missing the {field}.")]
+    MissingHeaderField { block_number: BlockNumber, field: &'static str },
+    #[error(
+        "The {commitment_name} of block {block_number} is {calculated:#x}, but its header \
+         contains {received:#x}."
+    )]
+    CommitmentMismatch {
+        block_number: BlockNumber,
+        commitment_name: &'static str,
+        calculated: Felt,
+        received: Felt,
+    },
+    #[error(
+        "The state diff of block {block_number} has length {calculated}, but its header contains \
+         {received}."
+    )]
+    StateDiffLengthMismatch { block_number: BlockNumber, calculated: usize, received: usize },
+    #[error(
+        "The hash of block {block_number} is {calculated}, but its header contains {received}."
+    )]
+    BlockHashMismatch { block_number: BlockNumber, calculated: BlockHash, received: BlockHash },
+    #[error(transparent)]
+    StarknetApiError(#[from] StarknetApiError),
+}
+
+type BlockVerificationResult = Result<(), BlockVerificationError>;
+
+/// Recalculates the commitments and the hash of the block and verifies that they match its
+/// header, and records how long it took.
+pub(crate) fn verify_block(block: &Block, chain_id: &ChainId) -> BlockVerificationResult {
+    let verification_start = Instant::now();
+    let result = if is_legacy_block(&block.header) {
+        verify_legacy_block(block, chain_id)
+    } else {
+        verify_block_with_receipts_and_state_diff(block)
+    };
+    CENTRAL_SYNC_BLOCK_VERIFICATION_LATENCY.record(verification_start.elapsed().as_secs_f64());
+    result
+}
+
+/// Returns whether the block is from before Starknet version 0.13.2, so its hash doesn't commit to
+/// its receipts and state diff.
+fn is_legacy_block(header: &BlockHeader) -> bool {
+    // Old blocks that don't report their Starknet version get the latest one, but unlike newer
+    // blocks they don't have a receipt commitment.
+    BlockHashVersion::try_from(header.block_header_without_hash.starknet_version).is_err()
+        || header.receipt_commitment.is_none()
+}
+
+// The state diff commitment is taken from the header, since the state diff is downloaded
+// separately, and is verified in [`verify_state_diff`].
+fn verify_block_with_receipts_and_state_diff(block: &Block) -> BlockVerificationResult {
+    let header = &block.header;
+    let starknet_version = header.block_header_without_hash.starknet_version;
+    let block_number = header.block_header_without_hash.block_number;
+
+    let transactions_data = transactions_hashing_data(block);
+    let TransactionsDataCommitments {
+        transaction_commitment,
+        event_commitment,
+        receipt_commitment,
+        n_events,
+    } = calculate_transactions_data_commitments(&transactions_data, &starknet_version);
+    verify_commitment(
+        block_number,
+        "transaction commitment",
+        transaction_commitment.0,
+        header.transaction_commitment.map(|commitment| commitment.0),
+    )?;
+    verify_commitment(
+        block_number,
+        "event commitment",
+        event_commitment.0,
+        header.event_commitment.map(|commitment| commitment.0),
+    )?;
+    verify_commitment(
+        block_number,
+        "receipt commitment",
+        receipt_commitment.0,
+        header.receipt_commitment.map(|commitment| commitment.0),
+    )?;
+
+    let state_diff_commitment =
+        header.state_diff_commitment.ok_or(BlockVerificationError::MissingHeaderField {
+            block_number,
+            field: "state diff commitment",
+        })?;
+    let state_diff_length =
+        header.state_diff_length.ok_or(BlockVerificationError::MissingHeaderField {
+            block_number,
+            field: "state diff length",
+        })?;
+    let concatenated_counts = concat_counts(
+        transactions_data.len(),
+        n_events,
+        state_diff_length,
+        header.block_header_without_hash.l1_da_mode,
+    );
+    let calculated_block_hash = calculate_block_hash(
+        header.block_header_without_hash.clone(),
+        BlockHeaderCommitments {
+            transaction_commitment,
+            event_commitment,
+            receipt_commitment,
+            state_diff_commitment,
+            concatenated_counts,
+        },
+    )?;
+    verify_block_hash(block_number, calculated_block_hash, header.block_hash)
+}
+
+// Blocks from before Starknet version 0.7.0 are reported with zero commitments, and their hash
+// commits to the chain instead of the sequencer, the timestamp and the events.
+fn verify_legacy_block(block: &Block, chain_id: &ChainId) -> BlockVerificationResult {
+    let header = &block.header;
+    let block_number = header.block_header_without_hash.block_number;
+    let commitments = calculate_legacy_block_commitments(&legacy_transactions_hashing_data(block));
+    let calculated_block_hash =
+        calculate_legacy_block_hash(&header.block_header_without_hash, &commitments);
+    if calculated_block_hash != header.block_hash
+        && calculate_pre_v0_7_block_hash(&header.block_header_without_hash, &commitments, chain_id)?
+            == header.block_hash
+    {
+        return Ok(());
+    }
+    verify_block_hash(block_number, calculated_block_hash, header.block_hash)?;
+    verify_commitment(
+        block_number,
+        "transaction commitment",
+        commitments.transaction_commitment.0,
+        header.transaction_commitment.map(|commitment| commitment.0),
+    )?;
+    verify_commitment(
+        block_number,
+        "event commitment",
+        commitments.event_commitment.0,
+        header.event_commitment.map(|commitment| commitment.0),
+    )
+}
+
+/// Verifies that the state diff matches the commitment and length in the header of its block,
+/// which were verified as part of the block hash in [`verify_block`]. Blocks from before Starknet
+/// version 0.13.2 don't commit to their state diff, so their state diff isn't verified.
+pub(crate) fn verify_state_diff(
+    header: &BlockHeader,
+    state_diff: &ThinStateDiff,
+) -> BlockVerificationResult {
+    if is_legacy_block(header) {
+        return Ok(());
+    }
+    let block_number = header.block_header_without_hash.block_number;
+    verify_commitment(
+        block_number,
+        "state diff commitment",
+        calculate_state_diff_hash(state_diff).0.0,
+        header.state_diff_commitment.map(|commitment| commitment.0.0),
+    )?;
+    let received_length =
+        header.state_diff_length.ok_or(BlockVerificationError::MissingHeaderField {
+            block_number,
+            field: "state diff length",
+        })?;
+    if state_diff.len() != received_length {
+        return Err(BlockVerificationError::StateDiffLengthMismatch {
+            block_number,
+            calculated: state_diff.len(),
+            received: received_length,
+        });
+    }
+    Ok(())
+}
+
+fn verify_block_hash(
+    block_number: BlockNumber,
+    calculated: BlockHash,
+    received: BlockHash,
+) -> BlockVerificationResult {
+    if calculated != received {
+        return Err(BlockVerificationError::BlockHashMismatch {
+            block_number,
+            calculated,
+            received,
+        });
+    }
+    Ok(())
+}
+
+fn verify_commitment(
+    block_number: BlockNumber,
+    commitment_name: &'static str,
+    calculated: Felt,
+    received: Option<Felt>,
+) -> BlockVerificationResult {
+    let received = received.ok_or(BlockVerificationError::MissingHeaderField {
+        block_number,
+        field: commitment_name,
+    })?;
+    if calculated != received {
+        return Err(BlockVerificationError::CommitmentMismatch {
+            block_number,
+            commitment_name,
+            calculated,
+            received,
+        });
+    }
+    Ok(())
+}
+
+fn transactions_hashing_data(block: &Block) -> Vec<TransactionHashingData> {
+    let body = &block.body;
+    body.transactions
+        .iter()
+        .zip(&body.transaction_outputs)
+        .zip(&body.transaction_hashes)
+        .map(|((transaction, transaction_output), transaction_hash)| TransactionHashingData {
+            transaction_signature: transaction_signature(transaction),
+            transaction_output: TransactionOutputForHash {
+                actual_fee: transaction_output.actual_fee(),
+                events: transaction_output.events().to_vec(),
+                execution_status: transaction_output.execution_status().clone(),
+                gas_consumed: transaction_output.execution_resources().gas_consumed,
+                messages_sent: transaction_output.messages_sent().clone(),
+            },
+            transaction_hash: *transaction_hash,
+        })
+        .collect()
+}
+
+// Before Starknet version 0.11.1, the block hash commits only to the signatures of invoke
+// transactions. Old blocks that don't report their Starknet version are from before it too.
+fn legacy_transactions_hashing_data(block: &Block) -> Vec<TransactionHashingData> {
+    let mut transactions_data = transactions_hashing_data(block);
+    let starknet_version = block.header.block_header_without_hash.starknet_version;
+    if !(StarknetVersion::V0_11_1..StarknetVersion::V0_13_2).contains(&starknet_version) {
+        for (transaction_data, transaction) in
+            transactions_data.iter_mut().zip(&block.body.transactions)
+        {
+            if !matches!(transaction, Transaction::Invoke(_)) {
+                transaction_data.transaction_signature = TransactionSignature::default();
+            }
+        }
+    }
+    transactions_data
+}
+
+fn transaction_signature(transaction: &Transaction) -> TransactionSignature {
+    match transaction {
+        Transaction::Declare(tx) => tx.signature(),
+        Transaction::DeployAccount(tx) => tx.signature(),
+        Transaction::Invoke(tx) => tx.signature(),
+        Transaction::Deploy(_) | Transaction::L1Handler(_) => TransactionSignature::default(),
+    }
+}
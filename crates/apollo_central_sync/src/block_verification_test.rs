@@ -0,0 +1,229 @@
+use assert_matches::assert_matches;
+use indexmap::indexmap;
+use starknet_api::block::{
+    Block,
+    BlockBody,
+    BlockHash,
+    BlockHeader,
+    BlockHeaderWithoutHash,
+    BlockNumber,
+    StarknetVersion,
+};
+use starknet_api::block_hash::block_hash_calculator::{
+    calculate_block_commitments,
+    calculate_block_hash,
+    calculate_legacy_block_commitments,
+    calculate_legacy_block_hash,
+    calculate_pre_v0_7_block_hash,
+};
+use starknet_api::core::{ChainId, EventCommitment, Nonce, TransactionCommitment};
+use starknet_api::data_availability::L1DataAvailabilityMode;
+use starknet_api::state::ThinStateDiff;
+use starknet_api::transaction::fields::{Fee, TransactionSignature};
+use starknet_api::transaction::{
+    Event,
+    InvokeTransaction,
+    InvokeTransactionOutput,
+    InvokeTransactionV1,
+    Transaction,
+    TransactionHash,
+    TransactionOutput,
+};
+use starknet_api::{contract_address, felt};
+
+use super::{transactions_hashing_data, verify_block, verify_state_diff, BlockVerificationError};
+
+const BLOCK_NUMBER: BlockNumber = BlockNumber(1);
+
+// Returns a block whose header contains the correct commitments and hash, and its state diff.
+fn block_and_state_diff(starknet_version: StarknetVersion) -> (Block, ThinStateDiff) {
+    let transaction = Transaction::Invoke(InvokeTransaction::V1(InvokeTransactionV1 {
+        signature: TransactionSignature(vec![felt!("0x1")].into()),
+        ..Default::default()
+    }));
+    let transaction_output = TransactionOutput::Invoke(InvokeTransactionOutput {
+        actual_fee: Fee(2),
+        events: vec![Event::default(), Event::default()],
+        ..Default::default()
+    });
+    let body = BlockBody {
+        transactions: vec![transaction],
+        transaction_outputs: vec![transaction_output],
+        transaction_hashes: vec![TransactionHash(felt!("0x3"))],
+    };
+    let state_diff = ThinStateDiff {
+        nonces: indexmap! { contract_address!("0x4") => Nonce(felt!("0x5")) },
+        ..Default::default()
+    };
+    let block_header_without_hash = BlockHeaderWithoutHash {
+        block_number: BLOCK_NUMBER,
+        parent_hash: BlockHash(felt!("0x6")),
+        l1_da_mode: L1DataAvailabilityMode::Blob,
+        starknet_version,
+        ..Default::default()
+    };
+
+    let mut block = Block { header: BlockHeader::default(), body };
+    let commitments = calculate_block_commitments(
+        &transactions_hashing_data(&block),
+        &state_diff,
+        block_header_without_hash.l1_da_mode,
+        &starknet_version,
+    );
+    block.header = BlockHeader {
+        block_hash: calculate_block_hash(block_header_without_hash.clone(), commitments.clone())
+            .unwrap_or_default(),
+        block_header_without_hash,
+        state_diff_commitment: Some(commitments.state_diff_commitment),
+        state_diff_length: Some(state_diff.len()),
+        transaction_commitment: Some(commitments.transaction_commitment),
+        event_commitment: Some(commitments.event_commitment),
+        n_transactions: 1,
+        n_events: 2,
+        receipt_commitment: Some(commitments.receipt_commitment),
+    };
+    (block, state_diff)
+}
+
+#[test]
+fn valid_block_and_state_diff() {
+    for starknet_version in [StarknetVersion::V0_13_2, StarknetVersion::V0_13_4] {
+        let (block, state_diff) = block_and_state_diff(starknet_version);
+        verify_block(&block, &ChainId::Mainnet).unwrap();
+        verify_state_diff(&block.header, &state_diff).unwrap();
+    }
+}
+
+// Returns a block from before Starknet version 0.13.2 whose header contains the correct
+// commitments and hash, or the hash of a block from before Starknet version 0.7.0 if a chain is
+// given.
+fn legacy_block(starknet_version: StarknetVersion, pre_v0_7_chain_id: Option<&ChainId>) -> Block {
+    let (mut block, _) = block_and_state_diff(starknet_version);
+    let commitments = calculate_legacy_block_commitments(&transactions_hashing_data(&block));
+    let block_header_without_hash = &block.header.block_header_without_hash;
+    block.header = BlockHeader {
+        block_hash: match pre_v0_7_chain_id {
+            Some(chain_id) => {
+                calculate_pre_v0_7_block_hash(block_header_without_hash, &commitments, chain_id)
+                    .unwrap()
+            }
+            None => calculate_legacy_block_hash(block_header_without_hash, &commitments),
+        },
+        block_header_without_hash: block_header_without_hash.clone(),
+        transaction_commitment: Some(commitments.transaction_commitment),
+        event_commitment: Some(commitments.event_commitment),
+        n_transactions: commitments.n_transactions,
+        n_events: commitments.n_events,
+        ..Default::default()
+    };
+    block
+}
+
+#[test]
+fn valid_legacy_block() {
+    let (_, state_diff) = block_and_state_diff(StarknetVersion::V0_13_1);
+    let block = legacy_block(StarknetVersion::V0_13_1, None);
+    verify_block(&block, &ChainId::Mainnet).unwrap();
+    // Blocks from before Starknet version 0.13.2 don't commit to their state diff.
+    verify_state_diff(&block.header, &state_diff).unwrap();
+}
+
+#[test]
+fn valid_legacy_block_without_starknet_version() {
+    // Old blocks that don't report their Starknet version are stored with the latest one.
+    let block = legacy_block(StarknetVersion::LATEST, None);
+    verify_block(&block, &ChainId::Mainnet).unwrap();
+}
+
+#[test]
+fn valid_pre_v0_7_block() {
+    let mut block = legacy_block(StarknetVersion::LATEST, Some(&ChainId::Mainnet));
+    // Blocks from before Starknet version 0.7.0 are reported with zero commitments.
+    block.header.transaction_commitment = Some(TransactionCommitment::default());
+    block.header.event_commitment = Some(EventCommitment::default());
+    verify_block(&block, &ChainId::Mainnet).unwrap();
+    assert_matches!(
+        verify_block(&block, &ChainId::Sepolia),
+        Err(BlockVerificationError::BlockHashMismatch { block_number: BLOCK_NUMBER, .. })
+    );
+}
+
+#[test]
+fn legacy_block_with_altered_body() {
+    let mut block = legacy_block(StarknetVersion::V0_13_1, None);
+    block.body.transaction_hashes[0] = TransactionHash(felt!("0x7"));
+    assert_matches!(
+        verify_block(&block, &ChainId::Mainnet),
+        Err(BlockVerificationError::BlockHashMismatch { block_number: BLOCK_NUMBER, .. })
+    );
+}
+
+#[test]
+fn legacy_block_with_wrong_commitment() {
+    let mut block = legacy_block(StarknetVersion::V0_13_1, None);
+    block.header.event_commitment = Some(EventCommitment(felt!("0x7")));
+    assert_matches!(
+        verify_block(&block, &ChainId::Mainnet),
+        Err(BlockVerificationError::CommitmentMismatch { commitment_name: "event commitment", .. })
+    );
+}
+
+#[test]
+fn block_with_wrong_commitment() {
+    let (mut block, _) = block_and_state_diff(StarknetVersion::LATEST);
+    block.header.event_commitment = Some(EventCommitment(felt!("0x7")));
+    assert_matches!(
+        verify_block(&block, &ChainId::Mainnet),
+        Err(BlockVerificationError::CommitmentMismatch {
+            block_number: BLOCK_NUMBER,
+            commitment_name: "event commitment",
+            ..
+        })
+    );
+}
+
+#[test]
+fn block_with_altered_body() {
+    let (mut block, _) = block_and_state_diff(StarknetVersion::LATEST);
+    block.body.transaction_hashes[0] = TransactionHash(felt!("0x7"));
+    assert_matches!(
+        verify_block(&block, &ChainId::Mainnet),
+        Err(BlockVerificationError::CommitmentMismatch {
+            commitment_name: "transaction commitment",
+            ..
+        })
+    );
+}
+
+#[test]
+fn block_with_wrong_hash() {
+    let (mut block, _) = block_and_state_diff(StarknetVersion::LATEST);
+    block.header.block_header_without_hash.timestamp.0 += 1;
+    assert_matches!(
+        verify_block(&block, &ChainId::Mainnet),
+        Err(BlockVerificationError::BlockHashMismatch { block_number: BLOCK_NUMBER, .. })
+    );
+}
+
+#[test]
+fn block_without_commitments() {
+    let (mut block, _) = block_and_state_diff(StarknetVersion::LATEST);
+    block.header.state_diff_commitment = None;
+    assert_matches!(
+        verify_block(&block, &ChainId::Mainnet),
+        Err(BlockVerificationError::MissingHeaderField { field: "state diff commitment", .. })
+    );
+}
+
+#[test]
+fn state_diff_with_wrong_commitment() {
+    let (block, mut state_diff) = block_and_state_diff(StarknetVersion::LATEST);
+    state_diff.nonces = indexmap! { contract_address!("0x4") => Nonce(felt!("0x7")) };
+    assert_matches!(
+        verify_state_diff(&block.header, &state_diff),
+        Err(BlockVerificationError::CommitmentMismatch {
+            commitment_name: "state diff commitment",
+            ..
+        })
+    );
+}
@@ -2,6 +2,7 @@
 // within this crate
 #![cfg_attr(coverage_nightly, feature(coverage_attribute))]
 
+mod block_verification;
 mod pending_sync;
 pub mod sources;
 #[cfg(test)]
@@ -57,13 +58,14 @@ use starknet_api::block::{
     StarknetVersion,
 };
 use starknet_api::contract_class::{ContractClass, SierraVersion};
-use starknet_api::core::{ClassHash, CompiledClassHash, SequencerPublicKey};
+use starknet_api::core::{ChainId, ClassHash, CompiledClassHash, SequencerPublicKey};
 use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
 use starknet_api::state::{StateDiff, ThinStateDiff};
 use tokio::sync::{Mutex, RwLock};
 use tokio::task::{spawn_blocking, JoinError};
 use tracing::{debug, error, info, instrument, trace, warn};
 
+use crate::block_verification::{verify_block, verify_state_diff, BlockVerificationError};
 use crate::pending_sync::sync_pending_data;
 use crate::sources::base_layer::{BaseLayerSourceTrait, EthereumBaseLayerSource};
 use crate::sources::central::{CentralError, CentralSource, CentralSourceTrait};
@@ -190,6 +192,7 @@ pub struct GenericStateSync<
     writer: Arc<Mutex<StorageWriter>>,
     sequencer_pub_key: Option<SequencerPublicKey>,
     class_manager_client: Option<SharedClassManagerClient>,
+    chain_id: ChainId,
 }
 
 pub type StateSyncResult = Result<(), StateSyncError>;
@@ -217,6 +220,8 @@ pub enum StateSyncError {
     },
     #[error("Header for block {block_number} wasn't found when trying to store base layer block.")]
     BaseLayerBlockWithoutMatchingHeader { block_number: BlockNumber },
+    #[error("Header for block {block_number} wasn't found when trying to verify its state diff.")]
+    StateDiffWithoutMatchingHeader { block_number: BlockNumber },
     #[error(transparent)]
     BaseLayerSourceError(#[from] BaseLayerSourceError),
     #[error(
@@ -234,6 +239,8 @@ pub enum StateSyncError {
     ClassManagerClientError(#[from] ClassManagerClientError),
     #[error(transparent)]
     JoinError(#[from] JoinError),
+    #[error(transparent)]
+    BlockVerificationError(#[from] BlockVerificationError),
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -302,15 +309,19 @@ impl<
                 StateSyncError::StorageError(error) => {
                     matches!(error, StorageError::InnerError(_))
                 }
+                // The feeder gateways disagree on the chain, so retrying won't help.
+                StateSyncError::CentralSourceError(error) => {
+                    !matches!(error, CentralError::CrossCheckBlockHashMismatch { .. })
+                }
                 StateSyncError::NoProgress
-                | StateSyncError::CentralSourceError(_)
                 | StateSyncError::PendingSourceError(_)
                 | StateSyncError::BaseLayerSourceError(_)
                 | StateSyncError::ParentBlockHashMismatch { .. }
                 | StateSyncError::BaseLayerHashMismatch { .. }
                 | StateSyncError::ClassManagerClientError(_)
                 | StateSyncError::BaseLayerBlockWithoutMatchingHeader { .. }
-                | StateSyncError::JoinError(_) => true,
+                | StateSyncError::JoinError(_)
+                | StateSyncError::BlockVerificationError(_) => true,
                 StateSyncError::SequencerPubKeyChanged { .. } => false,
             }
         }
@@ -476,6 +487,9 @@ impl<
         // Assuming the central source is trusted, detect reverts by comparing the incoming block's
         // parent hash to the current hash.
         self.verify_parent_block_hash(block_number, &block)?;
+        if self.config.verify_blocks {
+            verify_block(&block, &self.chain_id)?;
+        }
 
         debug!("Storing block number: {block_number}, block header: {:?}", block.header);
         trace!("Block data: {block:#?}, signature: {signature:?}");
@@ -525,7 +539,6 @@ impl<
         state_diff: StateDiff,
         deployed_contract_class_definitions: IndexMap<ClassHash, DeprecatedContractClass>,
     ) -> StateSyncResult {
-        // TODO(dan): verifications - verify state diff against stored header.
         debug!("Storing state diff.");
         trace!("StateDiff data: {state_diff:#?}");
 
@@ -534,6 +547,16 @@ impl<
         let (thin_state_diff, classes, deprecated_classes) =
             ThinStateDiff::from_state_diff(state_diff);
 
+        if self.config.verify_blocks {
+            // The state diff stream is behind the block stream, so the header should be stored.
+            let header = self
+                .reader
+                .begin_ro_txn()?
+                .get_block_header(block_number)?
+                .ok_or(StateSyncError::StateDiffWithoutMatchingHeader { block_number })?;
+            verify_state_diff(&header, &thin_state_diff)?;
+        }
+
         // Sending to class manager before updating the storage so that if the class manager send
         // fails we retry the same block.
         if let Some(class_manager_client) = &self.class_manager_client {
@@ -952,6 +975,7 @@ impl StateSync {
         reader: StorageReader,
         writer: StorageWriter,
         class_manager_client: Option<SharedClassManagerClient>,
+        chain_id: ChainId,
     ) -> Self {
         let base_layer_source = base_layer_source.map(Arc::new);
         Self {
@@ -966,6 +990,7 @@ impl StateSync {
             writer: Arc::new(Mutex::new(writer)),
             sequencer_pub_key: None,
             class_manager_client,
+            chain_id,
         }
     }
 }
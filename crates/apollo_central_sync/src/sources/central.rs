@@ -1,14 +1,27 @@
 #[cfg(test)]
 #[path = "central_test.rs"]
 mod central_test;
+mod failover_client;
 mod state_update_stream;
 
 use std::collections::{BTreeMap, HashMap};
 use std::num::NonZeroUsize;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use apollo_config::converters::{deserialize_optional_map, serialize_optional_map};
-use apollo_config::dumping::{prepend_sub_config_name, ser_param, SerializeConfig};
+use apollo_config::converters::{
+    deserialize_optional_map,
+    deserialize_seconds_to_duration,
+    deserialize_vec,
+    serialize_optional_map,
+    serialize_slice,
+};
+use apollo_config::dumping::{
+    prepend_sub_config_name,
+    ser_optional_param,
+    ser_param,
+    SerializeConfig,
+};
 use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use apollo_starknet_client::reader::{
     BlockSignatureData,
@@ -41,6 +54,7 @@ use tracing::{debug, trace};
 use url::Url;
 use validator::Validate;
 
+pub use self::failover_client::FailoverStarknetClient;
 use self::state_update_stream::{StateUpdateStream, StateUpdateStreamConfig};
 
 type CentralResult<T> = Result<T, CentralError>;
@@ -48,6 +62,11 @@ type CentralResult<T> = Result<T, CentralError>;
 pub struct CentralSourceConfig {
     pub concurrent_requests: usize,
     pub starknet_url: Url,
+    #[serde(deserialize_with = "deserialize_vec")]
+    pub fallback_starknet_urls: Vec<Url>,
+    #[serde(deserialize_with = "deserialize_seconds_to_duration")]
+    pub unhealthy_feeder_cooldown_duration: Duration,
+    pub cross_check_starknet_url: Option<Url>,
     #[serde(deserialize_with = "deserialize_optional_map")]
     pub http_headers: Option<HashMap<String, String>>,
     pub max_state_updates_to_download: usize,
@@ -64,6 +83,9 @@ impl Default for CentralSourceConfig {
             concurrent_requests: 10,
             starknet_url: Url::parse("https://alpha-mainnet.starknet.io/")
                 .expect("Unable to parse default URL, this should never happen."),
+            fallback_starknet_urls: vec![],
+            unhealthy_feeder_cooldown_duration: Duration::from_secs(60),
+            cross_check_starknet_url: None,
             http_headers: None,
             max_state_updates_to_download: 20,
             max_state_updates_to_store_in_memory: 20,
@@ -80,7 +102,7 @@ impl Default for CentralSourceConfig {
 
 impl SerializeConfig for CentralSourceConfig {
     fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
-        let self_params_dump = BTreeMap::from_iter([
+        let mut self_params_dump = BTreeMap::from_iter([
             ser_param(
                 "concurrent_requests",
                 &self.concurrent_requests,
@@ -94,6 +116,20 @@ impl SerializeConfig for CentralSourceConfig {
                 "Starknet feeder-gateway URL. It should match chain_id.",
                 ParamPrivacyInput::Public,
             ),
+            ser_param(
+                "fallback_starknet_urls",
+                &serialize_slice(&self.fallback_starknet_urls),
+                "Space-separated list of Starknet feeder-gateway URLs to fail over to, in order, \
+                 when starknet_url is unhealthy. They should match chain_id.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "unhealthy_feeder_cooldown_duration",
+                &self.unhealthy_feeder_cooldown_duration.as_secs(),
+                "Time in seconds during which a feeder-gateway that failed a request is only used \
+                 if all the other feeder-gateways are unhealthy as well.",
+                ParamPrivacyInput::Public,
+            ),
             ser_param(
                 "http_headers",
                 &serialize_optional_map(&self.http_headers),
@@ -125,6 +161,15 @@ impl SerializeConfig for CentralSourceConfig {
                 ParamPrivacyInput::Public,
             ),
         ]);
+        self_params_dump.extend(ser_optional_param(
+            &self.cross_check_starknet_url,
+            Url::parse("https://alpha-mainnet.starknet.io/")
+                .expect("Unable to parse default URL, this should never happen."),
+            "cross_check_starknet_url",
+            "Starknet feeder-gateway URL used to cross-check the hash of every downloaded block. \
+             Sync halts if the hashes disagree.",
+            ParamPrivacyInput::Public,
+        ));
         chain!(self_params_dump, prepend_sub_config_name(self.retry_config.dump(), "retry_config"))
             .collect()
     }
@@ -133,6 +178,8 @@ impl SerializeConfig for CentralSourceConfig {
 pub struct GenericCentralSource<TStarknetClient: StarknetReader + Send + Sync> {
     pub concurrent_requests: usize,
     pub apollo_starknet_client: Arc<TStarknetClient>,
+    // If set, the hash of every downloaded block is compared to the hash from this client.
+    pub cross_check_starknet_client: Option<Arc<TStarknetClient>>,
     pub storage_reader: StorageReader,
     pub state_update_stream_config: StateUpdateStreamConfig,
     pub(crate) class_cache: Arc<Mutex<LruCache<ClassHash, ApiContractClass>>>,
@@ -163,6 +210,17 @@ pub enum CentralError {
         "Block downloaded from central is in 0.13.1 format, while signature is in 0.13.2 format."
     )]
     BlockAndSignatureVersionMismatch,
+    #[error("Could not find block {block_number} in the cross-check feeder gateway.")]
+    CrossCheckBlockNotFound { block_number: BlockNumber },
+    #[error(
+        "Block {block_number} has hash {block_hash} in the feeder gateway but hash \
+         {cross_check_block_hash} in the cross-check feeder gateway."
+    )]
+    CrossCheckBlockHashMismatch {
+        block_number: BlockNumber,
+        block_hash: BlockHash,
+        cross_check_block_hash: BlockHash,
+    },
 }
 
 #[cfg_attr(test, automock)]
@@ -269,17 +327,31 @@ impl<TStarknetClient: StarknetReader + Send + Sync + 'static> CentralSourceTrait
             let mut res =
                 futures_util::stream::iter(initial_block_number.iter_up_to(up_to_block_number))
                     .map(|bn| async move {
-                        let block_and_signature = futures_util::try_join!(
-                            self.apollo_starknet_client.block(bn),
-                            self.apollo_starknet_client.block_signature(bn)
+                        let (block_and_signature, cross_check_block_hash) = futures_util::join!(
+                            async {
+                                futures_util::try_join!(
+                                    self.apollo_starknet_client.block(bn),
+                                    self.apollo_starknet_client.block_signature(bn)
+                                )
+                            },
+                            self.get_cross_check_block_hash(bn),
                         );
-                        (bn, block_and_signature)
+                        (bn, block_and_signature, cross_check_block_hash)
                     })
                     .buffered(self.concurrent_requests);
-            while let Some((current_block_number, maybe_client_block)) = res.next().await
+            while let Some((current_block_number, maybe_client_block, cross_check_block_hash)) =
+                res.next().await
             {
                 let maybe_central_block =
-                    client_to_central_block(current_block_number, maybe_client_block);
+                    client_to_central_block(current_block_number, maybe_client_block)
+                        .and_then(|(block, signature)| {
+                            verify_cross_check_block_hash(
+                                current_block_number,
+                                block.header.block_hash,
+                                cross_check_block_hash?,
+                            )?;
+                            Ok((block, signature))
+                        });
                 match maybe_central_block {
                     Ok((block, signature)) => {
                         yield Ok((current_block_number, block, signature));
@@ -403,6 +475,40 @@ impl<TStarknetClient: StarknetReader + Send + Sync + 'static> CentralSourceTrait
     }
 }
 
+impl<TStarknetClient: StarknetReader + Send + Sync> GenericCentralSource<TStarknetClient> {
+    // Returns the hash of the given block from the cross-check client, or None if cross-checking
+    // is disabled.
+    async fn get_cross_check_block_hash(
+        &self,
+        block_number: BlockNumber,
+    ) -> CentralResult<Option<BlockHash>> {
+        let Some(cross_check_starknet_client) = &self.cross_check_starknet_client else {
+            return Ok(None);
+        };
+        match cross_check_starknet_client.block(block_number).await.map_err(Arc::new)? {
+            Some(block) => Ok(Some(block.block_hash())),
+            None => Err(CentralError::CrossCheckBlockNotFound { block_number }),
+        }
+    }
+}
+
+fn verify_cross_check_block_hash(
+    block_number: BlockNumber,
+    block_hash: BlockHash,
+    cross_check_block_hash: Option<BlockHash>,
+) -> CentralResult<()> {
+    match cross_check_block_hash {
+        Some(cross_check_block_hash) if cross_check_block_hash != block_hash => {
+            Err(CentralError::CrossCheckBlockHashMismatch {
+                block_number,
+                block_hash,
+                cross_check_block_hash,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
 fn client_to_central_block(
     current_block_number: BlockNumber,
     maybe_client_block: Result<
@@ -445,7 +551,7 @@ fn client_to_central_block(
     }
 }
 
-pub type CentralSource = GenericCentralSource<StarknetFeederGatewayClient>;
+pub type CentralSource = GenericCentralSource<FailoverStarknetClient<StarknetFeederGatewayClient>>;
 
 impl CentralSource {
     pub fn new(
@@ -453,16 +559,38 @@ impl CentralSource {
         node_version: &'static str,
         storage_reader: StorageReader,
     ) -> Result<CentralSource, ClientCreationError> {
-        let apollo_starknet_client = StarknetFeederGatewayClient::new(
-            config.starknet_url.as_ref(),
-            config.http_headers,
-            node_version,
-            config.retry_config,
-        )?;
+        let create_client = |starknet_url: &Url| {
+            StarknetFeederGatewayClient::new(
+                starknet_url.as_ref(),
+                config.http_headers.clone(),
+                node_version,
+                config.retry_config,
+            )
+            .map(|client| (starknet_url.clone(), client))
+        };
+        let apollo_starknet_client = FailoverStarknetClient::new(
+            std::iter::once(&config.starknet_url)
+                .chain(&config.fallback_starknet_urls)
+                .map(create_client)
+                .collect::<Result<_, _>>()?,
+            config.unhealthy_feeder_cooldown_duration,
+        );
+        let cross_check_starknet_client = config
+            .cross_check_starknet_url
+            .as_ref()
+            .map(|cross_check_starknet_url| {
+                let client = create_client(cross_check_starknet_url)?;
+                Ok::<_, ClientCreationError>(Arc::new(FailoverStarknetClient::new(
+                    vec![client],
+                    config.unhealthy_feeder_cooldown_duration,
+                )))
+            })
+            .transpose()?;
 
         Ok(CentralSource {
             concurrent_requests: config.concurrent_requests,
             apollo_starknet_client: Arc::new(apollo_starknet_client),
+            cross_check_starknet_client,
             storage_reader,
             state_update_stream_config: StateUpdateStreamConfig {
                 max_state_updates_to_download: config.max_state_updates_to_download,
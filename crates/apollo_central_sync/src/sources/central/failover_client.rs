@@ -0,0 +1,184 @@
+#[cfg(test)]
+#[path = "failover_client_test.rs"]
+mod failover_client_test;
+
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use apollo_starknet_client::reader::{
+    Block,
+    BlockSignatureData,
+    GenericContractClass,
+    PendingData,
+    ReaderClientError,
+    ReaderClientResult,
+    StarknetReader,
+    StateUpdate,
+};
+use apollo_starknet_client::ClientError;
+use async_trait::async_trait;
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use reqwest::StatusCode;
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ClassHash, SequencerPublicKey};
+use tracing::{info, warn};
+use url::Url;
+
+/// A feeder gateway client together with the health state used for failover.
+struct FeederGatewayEndpoint<TStarknetClient> {
+    url: Url,
+    client: TStarknetClient,
+    // The time until which the endpoint is considered unhealthy, if it failed recently.
+    unhealthy_until: Mutex<Option<Instant>>,
+}
+
+/// A [`StarknetReader`] that sends each request to the first healthy feeder gateway in the
+/// configured order, and fails over to the next one if the request fails on a transport or server
+/// error. An endpoint that failed a request is skipped for `unhealthy_cooldown`, after which it's
+/// tried again in its original position. If all the endpoints are unhealthy, they're tried anyway,
+/// in the configured order. Other errors, such as Starknet errors and deserialization errors, are
+/// returned as is, since the other endpoints are expected to return them as well.
+pub struct FailoverStarknetClient<TStarknetClient: StarknetReader + Send + Sync> {
+    endpoints: Vec<FeederGatewayEndpoint<TStarknetClient>>,
+    unhealthy_cooldown: Duration,
+}
+
+impl<TStarknetClient: StarknetReader + Send + Sync> FailoverStarknetClient<TStarknetClient> {
+    pub fn new(clients: Vec<(Url, TStarknetClient)>, unhealthy_cooldown: Duration) -> Self {
+        assert!(!clients.is_empty(), "At least one feeder gateway is required.");
+        let endpoints = clients
+            .into_iter()
+            .map(|(url, client)| FeederGatewayEndpoint {
+                url,
+                client,
+                unhealthy_until: Mutex::new(None),
+            })
+            .collect();
+        Self { endpoints, unhealthy_cooldown }
+    }
+
+    fn is_healthy(&self, index: usize) -> bool {
+        let unhealthy_until =
+            self.endpoints[index].unhealthy_until.lock().expect("Failed to lock endpoint health.");
+        unhealthy_until.is_none_or(|unhealthy_until| Instant::now() >= unhealthy_until)
+    }
+
+    fn mark_healthy(&self, index: usize) {
+        let endpoint = &self.endpoints[index];
+        let mut unhealthy_until =
+            endpoint.unhealthy_until.lock().expect("Failed to lock endpoint health.");
+        if unhealthy_until.take().is_some() {
+            info!("Feeder gateway {} is healthy again.", endpoint.url);
+        }
+    }
+
+    fn mark_unhealthy(&self, index: usize) {
+        *self.endpoints[index].unhealthy_until.lock().expect("Failed to lock endpoint health.") =
+            Some(Instant::now() + self.unhealthy_cooldown);
+    }
+
+    // The healthy endpoints come first, each group in the configured order.
+    fn endpoints_order(&self) -> Vec<usize> {
+        let (healthy, unhealthy): (Vec<usize>, Vec<usize>) =
+            (0..self.endpoints.len()).partition(|index| self.is_healthy(*index));
+        healthy.into_iter().chain(unhealthy).collect()
+    }
+
+    async fn request_with_failover<'a, T, Fut>(
+        &'a self,
+        request: impl Fn(&'a TStarknetClient) -> Fut,
+    ) -> ReaderClientResult<T>
+    where
+        Fut: Future<Output = ReaderClientResult<T>> + 'a,
+    {
+        let mut last_error = None;
+        for index in self.endpoints_order() {
+            let endpoint = &self.endpoints[index];
+            match request(&endpoint.client).await {
+                Ok(response) => {
+                    self.mark_healthy(index);
+                    return Ok(response);
+                }
+                Err(err) if !is_endpoint_failure(&err) => return Err(err),
+                Err(err) => {
+                    warn!("Request to feeder gateway {} failed: {err}.", endpoint.url);
+                    self.mark_unhealthy(index);
+                    last_error = Some(err);
+                }
+            }
+        }
+        Err(last_error.expect("There's at least one feeder gateway."))
+    }
+}
+
+/// Returns whether the error means the endpoint failed to serve the request, as opposed to an error
+/// in the response that the endpoint served.
+fn is_endpoint_failure(err: &ReaderClientError) -> bool {
+    match err {
+        ReaderClientError::ClientError(ClientError::RequestError(_))
+        | ReaderClientError::ClientError(ClientError::RetryError { .. }) => true,
+        ReaderClientError::ClientError(ClientError::BadResponseStatus { code, .. }) => {
+            code.is_server_error() || *code == StatusCode::TOO_MANY_REQUESTS
+        }
+        _ => false,
+    }
+}
+
+#[async_trait]
+impl<TStarknetClient: StarknetReader + Send + Sync> StarknetReader
+    for FailoverStarknetClient<TStarknetClient>
+{
+    async fn latest_block(&self) -> ReaderClientResult<Option<Block>> {
+        self.request_with_failover(|client| client.latest_block()).await
+    }
+
+    async fn block(&self, block_number: BlockNumber) -> ReaderClientResult<Option<Block>> {
+        self.request_with_failover(|client| client.block(block_number)).await
+    }
+
+    async fn class_by_hash(
+        &self,
+        class_hash: ClassHash,
+    ) -> ReaderClientResult<Option<GenericContractClass>> {
+        self.request_with_failover(|client| client.class_by_hash(class_hash)).await
+    }
+
+    async fn compiled_class_by_hash(
+        &self,
+        class_hash: ClassHash,
+    ) -> ReaderClientResult<Option<CasmContractClass>> {
+        self.request_with_failover(|client| client.compiled_class_by_hash(class_hash)).await
+    }
+
+    async fn state_update(
+        &self,
+        block_number: BlockNumber,
+    ) -> ReaderClientResult<Option<StateUpdate>> {
+        self.request_with_failover(|client| client.state_update(block_number)).await
+    }
+
+    async fn pending_data(&self) -> ReaderClientResult<Option<PendingData>> {
+        self.request_with_failover(|client| client.pending_data()).await
+    }
+
+    async fn is_alive(&self) -> bool {
+        for endpoint in &self.endpoints {
+            if endpoint.client.is_alive().await {
+                return true;
+            }
+        }
+        false
+    }
+
+    async fn block_signature(
+        &self,
+        block_number: BlockNumber,
+    ) -> ReaderClientResult<Option<BlockSignatureData>> {
+        self.request_with_failover(|client| client.block_signature(block_number)).await
+    }
+
+    async fn sequencer_pub_key(&self) -> ReaderClientResult<SequencerPublicKey> {
+        self.request_with_failover(|client| client.sequencer_pub_key()).await
+    }
+}
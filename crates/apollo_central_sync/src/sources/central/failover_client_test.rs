@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use apollo_starknet_client::reader::objects::block::BlockPostV0_13_1;
+use apollo_starknet_client::reader::{
+    Block,
+    MockStarknetReader,
+    ReaderClientError,
+    StarknetReader,
+};
+use apollo_starknet_client::ClientError;
+use assert_matches::assert_matches;
+use reqwest::StatusCode;
+use starknet_api::block::BlockNumber;
+use url::Url;
+
+use super::FailoverStarknetClient;
+
+const LONG_COOLDOWN: Duration = Duration::from_secs(1000);
+
+fn block(block_number: BlockNumber) -> Block {
+    Block::PostV0_13_1(BlockPostV0_13_1 { block_number, ..Default::default() })
+}
+
+fn server_error() -> ReaderClientError {
+    ReaderClientError::ClientError(ClientError::BadResponseStatus {
+        code: StatusCode::INTERNAL_SERVER_ERROR,
+        message: String::from("msg"),
+    })
+}
+
+fn failover_client(
+    clients: Vec<MockStarknetReader>,
+    unhealthy_cooldown: Duration,
+) -> FailoverStarknetClient<MockStarknetReader> {
+    let clients = clients
+        .into_iter()
+        .enumerate()
+        .map(|(i, client)| (Url::parse(&format!("https://feeder-{i}.io/")).unwrap(), client))
+        .collect();
+    FailoverStarknetClient::new(clients, unhealthy_cooldown)
+}
+
+#[tokio::test]
+async fn fails_over_and_skips_unhealthy_endpoint() {
+    let mut primary = MockStarknetReader::new();
+    primary.expect_latest_block().times(1).returning(|| Err(server_error()));
+    let mut fallback = MockStarknetReader::new();
+    fallback.expect_latest_block().times(2).returning(|| Ok(Some(block(BlockNumber(1)))));
+    let client = failover_client(vec![primary, fallback], LONG_COOLDOWN);
+
+    assert_eq!(client.latest_block().await.unwrap().unwrap().block_number(), BlockNumber(1));
+    // The primary is unhealthy, so the second request goes straight to the fallback.
+    assert_eq!(client.latest_block().await.unwrap().unwrap().block_number(), BlockNumber(1));
+}
+
+#[tokio::test]
+async fn returns_to_endpoint_after_cooldown() {
+    let mut primary = MockStarknetReader::new();
+    let mut first_request = true;
+    primary.expect_latest_block().times(2).returning(move || {
+        if std::mem::take(&mut first_request) {
+            Err(server_error())
+        } else {
+            Ok(Some(block(BlockNumber(0))))
+        }
+    });
+    let mut fallback = MockStarknetReader::new();
+    fallback.expect_latest_block().times(1).returning(|| Ok(Some(block(BlockNumber(1)))));
+    let client = failover_client(vec![primary, fallback], Duration::ZERO);
+
+    assert_eq!(client.latest_block().await.unwrap().unwrap().block_number(), BlockNumber(1));
+    assert_eq!(client.latest_block().await.unwrap().unwrap().block_number(), BlockNumber(0));
+}
+
+#[tokio::test]
+async fn tries_unhealthy_endpoints_when_all_are_unhealthy() {
+    let mut primary = MockStarknetReader::new();
+    primary.expect_block().times(2).returning(|_| Err(server_error()));
+    let mut fallback = MockStarknetReader::new();
+    fallback.expect_block().times(2).returning(|_| Err(server_error()));
+    let client = failover_client(vec![primary, fallback], LONG_COOLDOWN);
+
+    for _ in 0..2 {
+        assert_matches!(
+            client.block(BlockNumber(0)).await,
+            Err(ReaderClientError::ClientError(ClientError::BadResponseStatus { .. }))
+        );
+    }
+}
+
+#[tokio::test]
+async fn does_not_fail_over_on_response_errors() {
+    let mut primary = MockStarknetReader::new();
+    primary.expect_latest_block().times(2).returning(|| {
+        Err(ReaderClientError::ClientError(ClientError::BadResponseStatus {
+            code: StatusCode::NOT_FOUND,
+            message: String::from("msg"),
+        }))
+    });
+    let mut fallback = MockStarknetReader::new();
+    fallback.expect_latest_block().never();
+    let client = failover_client(vec![primary, fallback], LONG_COOLDOWN);
+
+    // The primary isn't marked unhealthy, so both requests go to it.
+    for _ in 0..2 {
+        assert_matches!(
+            client.latest_block().await,
+            Err(ReaderClientError::ClientError(ClientError::BadResponseStatus { code, .. }))
+            if code == StatusCode::NOT_FOUND
+        );
+    }
+}
@@ -26,7 +26,7 @@ use starknet_api::block::{
     BlockNumber,
     BlockSignature,
 };
-use starknet_api::core::{ClassHash, SequencerPublicKey};
+use starknet_api::core::{ChainId, ClassHash, SequencerPublicKey};
 use starknet_api::crypto::utils::PublicKey;
 use starknet_api::felt;
 use starknet_api::state::StateDiff;
@@ -140,6 +140,7 @@ async fn run_sync(
         // up until that block we call add_class_and_executable_unsafe and from that block we call
         // add_class.
         class_manager_client,
+        chain_id: ChainId::Mainnet,
     };
 
     state_sync.run().await?;
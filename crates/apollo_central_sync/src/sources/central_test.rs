@@ -58,6 +58,7 @@ async fn last_block_number() {
     let ((reader, _), _temp_dir) = get_test_storage();
     let central_source = GenericCentralSource {
         apollo_starknet_client: Arc::new(mock),
+        cross_check_starknet_client: None,
         concurrent_requests: TEST_CONCURRENT_REQUESTS,
         storage_reader: reader,
         state_update_stream_config: state_update_stream_config_for_test(),
@@ -98,6 +99,7 @@ async fn stream_block_headers() {
     let central_source = GenericCentralSource {
         concurrent_requests: TEST_CONCURRENT_REQUESTS,
         apollo_starknet_client: Arc::new(mock),
+        cross_check_starknet_client: None,
         storage_reader: reader,
         state_update_stream_config: state_update_stream_config_for_test(),
         class_cache: get_test_class_cache(),
@@ -178,6 +180,7 @@ async fn stream_block_headers_some_are_missing() {
         let central_source = GenericCentralSource {
             concurrent_requests: TEST_CONCURRENT_REQUESTS,
             apollo_starknet_client: Arc::new(mock),
+            cross_check_starknet_client: None,
             storage_reader: reader,
             state_update_stream_config: state_update_stream_config_for_test(),
             class_cache: get_test_class_cache(),
@@ -242,6 +245,7 @@ async fn stream_block_headers_error() {
     let central_source = GenericCentralSource {
         concurrent_requests: TEST_CONCURRENT_REQUESTS,
         apollo_starknet_client: Arc::new(mock),
+        cross_check_starknet_client: None,
         storage_reader: reader,
         state_update_stream_config: state_update_stream_config_for_test(),
         class_cache: get_test_class_cache(),
@@ -272,6 +276,66 @@ async fn stream_block_headers_error() {
     assert_eq!(expected_block_num, BlockNumber(ERROR_BLOCK_NUMBER + 1));
 }
 
+#[tokio::test]
+async fn stream_block_headers_cross_check_mismatch() {
+    const START_BLOCK_NUMBER: u64 = 5;
+    const END_BLOCK_NUMBER: u64 = 8;
+    const MISMATCH_BLOCK_NUMBER: u64 = 7;
+    let mut mock = MockStarknetReader::new();
+    let mut cross_check_mock = MockStarknetReader::new();
+
+    // We need to perform all the mocks before moving the mocks into central_source.
+    for i in START_BLOCK_NUMBER..=MISMATCH_BLOCK_NUMBER {
+        mock.expect_block()
+            .with(predicate::eq(BlockNumber(i)))
+            .times(1)
+            .returning(|_| Ok(Some(Block::default())));
+        mock.expect_block_signature().with(predicate::eq(BlockNumber(i))).times(1).returning(
+            |block_number| {
+                Ok(Some(BlockSignatureData::Deprecated {
+                    block_number,
+                    signature: Default::default(),
+                    signature_input: Default::default(),
+                }))
+            },
+        );
+        let cross_check_block_hash =
+            if i == MISMATCH_BLOCK_NUMBER { BlockHash(felt!("0x1")) } else { BlockHash::default() };
+        cross_check_mock.expect_block().with(predicate::eq(BlockNumber(i))).times(1).returning(
+            move |_| {
+                Ok(Some(Block::PostV0_13_1(BlockPostV0_13_1 {
+                    block_hash: cross_check_block_hash,
+                    ..Default::default()
+                })))
+            },
+        );
+    }
+    let ((reader, _), _temp_dir) = get_test_storage();
+    let central_source = GenericCentralSource {
+        concurrent_requests: 1,
+        apollo_starknet_client: Arc::new(mock),
+        cross_check_starknet_client: Some(Arc::new(cross_check_mock)),
+        storage_reader: reader,
+        state_update_stream_config: state_update_stream_config_for_test(),
+        class_cache: get_test_class_cache(),
+        compiled_class_cache: get_test_compiled_class_cache(),
+    };
+
+    let stream = central_source
+        .stream_new_blocks(BlockNumber(START_BLOCK_NUMBER), BlockNumber(END_BLOCK_NUMBER));
+    pin_mut!(stream);
+    for i in START_BLOCK_NUMBER..MISMATCH_BLOCK_NUMBER {
+        assert_eq!(stream.next().await.unwrap().unwrap().0, BlockNumber(i));
+    }
+    assert_matches!(
+        stream.next().await.unwrap(),
+        Err(CentralError::CrossCheckBlockHashMismatch { block_number, cross_check_block_hash, .. })
+        if block_number == BlockNumber(MISMATCH_BLOCK_NUMBER)
+            && cross_check_block_hash == BlockHash(felt!("0x1"))
+    );
+    assert!(stream.next().await.is_none());
+}
+
 #[tokio::test]
 async fn stream_state_updates() {
     const START_BLOCK_NUMBER: u64 = 5;
@@ -380,6 +444,7 @@ async fn stream_state_updates() {
     let central_source = GenericCentralSource {
         concurrent_requests: TEST_CONCURRENT_REQUESTS,
         apollo_starknet_client: Arc::new(mock),
+        cross_check_starknet_client: None,
         storage_reader: reader,
         state_update_stream_config: state_update_stream_config_for_test(),
         // TODO(shahak): Check that downloaded classes appear in the cache.
@@ -534,6 +599,7 @@ async fn stream_compiled_classes() {
     let central_source = GenericCentralSource {
         concurrent_requests: TEST_CONCURRENT_REQUESTS,
         apollo_starknet_client: Arc::new(mock),
+        cross_check_starknet_client: None,
         storage_reader: reader,
         state_update_stream_config: state_update_stream_config_for_test(),
         class_cache: get_test_class_cache(),
@@ -588,6 +654,7 @@ async fn get_class() {
     let central_source = GenericCentralSource {
         concurrent_requests: TEST_CONCURRENT_REQUESTS,
         apollo_starknet_client: Arc::new(mock),
+        cross_check_starknet_client: None,
         storage_reader: reader,
         state_update_stream_config: state_update_stream_config_for_test(),
         class_cache: get_test_class_cache(),
@@ -633,6 +700,7 @@ async fn get_compiled_class() {
     let central_source = GenericCentralSource {
         concurrent_requests: TEST_CONCURRENT_REQUESTS,
         apollo_starknet_client: Arc::new(mock),
+        cross_check_starknet_client: None,
         storage_reader: reader,
         state_update_stream_config: state_update_stream_config_for_test(),
         class_cache: get_test_class_cache(),
@@ -657,6 +725,7 @@ async fn get_sequencer_pub_key() {
     let central_source = GenericCentralSource {
         concurrent_requests: TEST_CONCURRENT_REQUESTS,
         apollo_starknet_client: Arc::new(mock),
+        cross_check_starknet_client: None,
         storage_reader: reader,
         state_update_stream_config: state_update_stream_config_for_test(),
         class_cache: get_test_class_cache(),
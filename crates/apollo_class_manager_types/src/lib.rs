@@ -5,7 +5,13 @@ use std::sync::Arc;
 
 use apollo_compile_to_casm_types::SierraCompilerError;
 use apollo_infra::component_client::{ClientError, LocalComponentClient, RemoteComponentClient};
-use apollo_infra::component_definitions::{ComponentClient, ComponentRequestAndResponseSender};
+use apollo_infra::component_definitions::{
+    ComponentClient,
+    ComponentRequestAndResponseSender,
+    LabeledRequest,
+    PrioritizedRequest,
+    RequestPriority,
+};
 use apollo_infra::impl_debug_for_infra_requests_and_responses;
 use apollo_proc_macros::handle_all_response_variants;
 use async_trait::async_trait;
@@ -16,7 +22,8 @@ use starknet_api::contract_class::ContractClass;
 use starknet_api::core::{ClassHash, CompiledClassHash};
 use starknet_api::deprecated_contract_class::ContractClass as DeprecatedClass;
 use starknet_api::state::SierraContractClass;
-use strum_macros::AsRefStr;
+use strum::{EnumVariantNames, VariantNames};
+use strum_macros::{AsRefStr, EnumDiscriminants, EnumIter, IntoStaticStr};
 use thiserror::Error;
 
 pub type ClassManagerResult<T> = Result<T, ClassManagerError>;
@@ -126,7 +133,12 @@ pub enum ClassManagerClientError {
     ClassManagerError(#[from] ClassManagerError),
 }
 
-#[derive(Clone, Serialize, Deserialize, AsRefStr)]
+#[derive(Clone, Serialize, Deserialize, AsRefStr, EnumDiscriminants)]
+#[strum_discriminants(
+    name(ClassManagerRequestLabelValue),
+    derive(IntoStaticStr, EnumIter, EnumVariantNames),
+    strum(serialize_all = "snake_case")
+)]
 pub enum ClassManagerRequest {
     AddClass(Class),
     AddClassAndExecutableUnsafe(ClassId, Class, ExecutableClassHash, ExecutableClass),
@@ -136,6 +148,28 @@ pub enum ClassManagerRequest {
 }
 impl_debug_for_infra_requests_and_responses!(ClassManagerRequest);
 
+impl LabeledRequest for ClassManagerRequest {
+    const LABELS: &'static [&'static str] = ClassManagerRequestLabelValue::VARIANTS;
+
+    fn label(&self) -> &'static str {
+        ClassManagerRequestLabelValue::from(self).into()
+    }
+}
+
+impl PrioritizedRequest for ClassManagerRequest {
+    // Executables are fetched while building and validating blocks, whereas classes are added in
+    // bulk by the gateway and by state sync.
+    fn priority(&self) -> RequestPriority {
+        match self {
+            ClassManagerRequest::GetExecutable(_) => RequestPriority::High,
+            ClassManagerRequest::GetSierra(_) => RequestPriority::Normal,
+            ClassManagerRequest::AddClass(_)
+            | ClassManagerRequest::AddClassAndExecutableUnsafe(..)
+            | ClassManagerRequest::AddDeprecatedClass(..) => RequestPriority::Low,
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, AsRefStr)]
 pub enum ClassManagerResponse {
     AddClass(ClassManagerResult<ClassHashes>),
@@ -4,7 +4,12 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use apollo_infra::component_client::{ClientError, LocalComponentClient, RemoteComponentClient};
-use apollo_infra::component_definitions::{ComponentClient, ComponentRequestAndResponseSender};
+use apollo_infra::component_definitions::{
+    ComponentClient,
+    ComponentRequestAndResponseSender,
+    LabeledRequest,
+    PrioritizedRequest,
+};
 use apollo_proc_macros::handle_all_response_variants;
 use async_trait::async_trait;
 #[cfg(any(feature = "testing", test))]
@@ -13,6 +18,8 @@ use serde::{Deserialize, Serialize};
 use starknet_api::contract_class::ContractClass;
 use starknet_api::core::CompiledClassHash;
 use starknet_api::state::SierraContractClass;
+use strum::{EnumVariantNames, VariantNames};
+use strum_macros::{EnumDiscriminants, EnumIter, IntoStaticStr};
 use thiserror::Error;
 
 pub type SierraCompilerResult<T> = Result<T, SierraCompilerError>;
@@ -157,11 +164,26 @@ pub enum SierraCompilerClientError {
     SierraCompilerError(#[from] SierraCompilerError),
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, EnumDiscriminants)]
+#[strum_discriminants(
+    name(SierraCompilerRequestLabelValue),
+    derive(IntoStaticStr, EnumIter, EnumVariantNames),
+    strum(serialize_all = "snake_case")
+)]
 pub enum SierraCompilerRequest {
     Compile(RawClass),
 }
 
+impl LabeledRequest for SierraCompilerRequest {
+    const LABELS: &'static [&'static str] = SierraCompilerRequestLabelValue::VARIANTS;
+
+    fn label(&self) -> &'static str {
+        SierraCompilerRequestLabelValue::from(self).into()
+    }
+}
+
+impl PrioritizedRequest for SierraCompilerRequest {}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum SierraCompilerResponse {
     Compile(SierraCompilerResult<RawExecutableHashedClass>),
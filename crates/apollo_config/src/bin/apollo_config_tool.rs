@@ -0,0 +1,106 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use apollo_config::schema::{check_config, diff_configs, get_default_values, get_json_schema};
+use apollo_config::{ParamPath, SerializedParam};
+use clap::{value_parser, Arg, ArgMatches, Command};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+const CONFIG_SCHEMA_ARG: &str = "config_schema";
+const OUTPUT_ARG: &str = "output";
+const CONFIG_FILES_ARG: &str = "config_files";
+
+fn command() -> Command {
+    let config_schema_arg = Arg::new(CONFIG_SCHEMA_ARG)
+        .long(CONFIG_SCHEMA_ARG)
+        .required(true)
+        .value_parser(value_parser!(PathBuf))
+        .help("The config schema file, as dumped by the node.");
+    Command::new("apollo_config_tool")
+        .about("Inspects config files offline against a config schema.")
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("json_schema")
+                .about("Exports the config schema as a JSON Schema of the config files.")
+                .arg(config_schema_arg.clone())
+                .arg(
+                    Arg::new(OUTPUT_ARG)
+                        .long(OUTPUT_ARG)
+                        .value_parser(value_parser!(PathBuf))
+                        .help("The file to write the JSON Schema to, instead of the stdout."),
+                ),
+        )
+        .subcommand(
+            Command::new("diff")
+                .about(
+                    "Checks config files for unknown params and type mismatches, and diffs two \
+                     config files, or a config file against the defaults.",
+                )
+                .arg(config_schema_arg)
+                .arg(
+                    Arg::new(CONFIG_FILES_ARG)
+                        .required(true)
+                        .num_args(1..=2)
+                        .value_parser(value_parser!(PathBuf))
+                        .help("One config file to diff against the defaults, or two to diff."),
+                ),
+        )
+}
+
+/// Exports the JSON Schema of config files, and checks and diffs config files offline.
+fn main() -> ExitCode {
+    match command().get_matches().subcommand() {
+        Some(("json_schema", matches)) => {
+            let json_schema = get_json_schema(&read_config_schema(matches));
+            let json_schema = serde_json::to_string_pretty(&json_schema)
+                .expect("Should be able to serialize the JSON Schema.");
+            match matches.get_one::<PathBuf>(OUTPUT_ARG) {
+                Some(path) => std::fs::write(path, json_schema + "\n")
+                    .unwrap_or_else(|err| panic!("Failed to write {path:?}: {err}")),
+                None => println!("{json_schema}"),
+            }
+            ExitCode::SUCCESS
+        }
+        Some(("diff", matches)) => diff(&read_config_schema(matches), matches),
+        _ => unreachable!("A subcommand is required."),
+    }
+}
+
+// Prints the issues of the given config files and the differences between them, and fails if any
+// issues were found. Params that are missing from a config file take their default values.
+fn diff(config_schema: &BTreeMap<ParamPath, SerializedParam>, matches: &ArgMatches) -> ExitCode {
+    let default_values = get_default_values(config_schema);
+    let mut configs = vec![];
+    let mut found_issues = false;
+    for path in matches.get_many::<PathBuf>(CONFIG_FILES_ARG).expect("Config files are required.") {
+        let config: Map<String, Value> = read_json(path);
+        for issue in check_config(config_schema, &config) {
+            println!("{}: {issue}", path.display());
+            found_issues = true;
+        }
+        let mut config_with_defaults = default_values.clone();
+        config_with_defaults.extend(config);
+        configs.push(config_with_defaults);
+    }
+    let (left, right) = match configs.as_slice() {
+        [config] => (&default_values, config),
+        [left, right] => (left, right),
+        _ => unreachable!("One or two config files are given."),
+    };
+    for param_diff in diff_configs(config_schema, left, right) {
+        println!("{param_diff}");
+    }
+    if found_issues { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+fn read_config_schema(matches: &ArgMatches) -> BTreeMap<ParamPath, SerializedParam> {
+    read_json(matches.get_one::<PathBuf>(CONFIG_SCHEMA_ARG).expect("Config schema is required."))
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> T {
+    let file = File::open(path).unwrap_or_else(|err| panic!("Failed to open {path:?}: {err}"));
+    serde_json::from_reader(file).unwrap_or_else(|err| panic!("Failed to parse {path:?}: {err}"))
+}
@@ -2,6 +2,7 @@ use std::collections::{BTreeMap, HashSet};
 use std::env;
 use std::fs::File;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 
 use apollo_infra_utils::path::resolve_project_relative_path;
@@ -10,7 +11,7 @@ use clap::Command;
 use itertools::chain;
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
+use serde_json::{json, Value};
 use tempfile::{NamedTempFile, TempDir};
 use url::Url;
 use validator::Validate;
@@ -37,15 +38,25 @@ use crate::dumping::{
     set_pointing_param_paths,
     SerializeConfig,
 };
+use crate::dynamic_config::{watch_config_files, DynamicConfigPublisher};
 use crate::loading::{
     load,
     load_and_process_config,
+    load_and_process_config_map,
     split_pointers_map,
     split_values_and_types,
     update_config_map_by_pointers,
     update_optional_values,
 };
 use crate::presentation::get_config_presentation;
+use crate::schema::{
+    check_config,
+    diff_configs,
+    get_default_values,
+    get_json_schema,
+    ConfigIssue,
+    ParamDiff,
+};
 use crate::{
     ConfigError,
     ParamPath,
@@ -968,3 +979,354 @@ fn optional_list_nested_btreemaps() {
         r#"{"list_of_maps":[{"url":"http://a.com/","headers":{"inner1":"1","inner2":"2"}},{"url":"http://b.com/","headers":{"inner3":"3","inner4":"4"}},{"url":"http://c.com/","headers":{}},{"url":"http://d.com/","headers":{"inner5":"5"}}]}"#
     );
 }
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize, Debug, PartialEq, Validate)]
+struct PartiallyDynamicConfig {
+    dynamic_param: usize,
+    static_param: usize,
+}
+
+impl SerializeConfig for PartiallyDynamicConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from([
+            ser_param(
+                "dynamic_param",
+                &self.dynamic_param,
+                "This is dynamic_param.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "static_param",
+                &self.static_param,
+                "This is static_param.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Validate)]
+struct ConfigWithDynamicParams {
+    static_param: usize,
+    #[validate]
+    dynamic_config: InnerConfig,
+    partially_dynamic_config: PartiallyDynamicConfig,
+}
+
+impl SerializeConfig for ConfigWithDynamicParams {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        chain!(
+            [ser_param(
+                "static_param",
+                &self.static_param,
+                "This is static_param.",
+                ParamPrivacyInput::Public
+            )],
+            prepend_sub_config_name(self.dynamic_config.dump(), "dynamic_config"),
+            prepend_sub_config_name(
+                self.partially_dynamic_config.dump(),
+                "partially_dynamic_config"
+            ),
+        )
+        .collect()
+    }
+}
+
+// Dumps a default ConfigWithDynamicParams and loads it with the given custom config files.
+fn load_config_with_dynamic_params_map(
+    dir: &TempDir,
+    custom_config_paths: &[PathBuf],
+) -> BTreeMap<ParamPath, serde_json::Value> {
+    let file_path = dir.path().join("config.json");
+    ConfigWithDynamicParams {
+        static_param: 1,
+        dynamic_config: InnerConfig { o: 5 },
+        partially_dynamic_config: PartiallyDynamicConfig { dynamic_param: 2, static_param: 3 },
+    }
+    .dump_to_file(&vec![], &HashSet::new(), file_path.to_str().unwrap())
+    .unwrap();
+    let mut args = vec!["Testing".to_owned()];
+    for path in custom_config_paths {
+        args.extend([CONFIG_FILE_ARG.to_owned(), path.to_str().unwrap().to_owned()]);
+    }
+    let (config_map, _) = load_and_process_config_map(
+        File::open(file_path).unwrap(),
+        Command::new("Program"),
+        args,
+        false,
+    )
+    .unwrap();
+    config_map
+}
+
+#[test]
+fn dynamic_config_publishes_param_updates() {
+    let dir = TempDir::new().unwrap();
+    let publisher = DynamicConfigPublisher::new::<ConfigWithDynamicParams>(
+        load_config_with_dynamic_params_map(&dir, &[]),
+    )
+    .unwrap();
+    let mut receiver = publisher.subscribe::<InnerConfig>("dynamic_config").unwrap();
+    assert_eq!(*receiver.borrow_and_update(), InnerConfig { o: 5 });
+
+    let updated =
+        publisher.update_params(BTreeMap::from([("dynamic_config.o".to_owned(), json!(7))]));
+    assert_eq!(updated.unwrap(), vec!["dynamic_config.o".to_owned()]);
+    assert!(receiver.has_changed().unwrap());
+    assert_eq!(*receiver.borrow_and_update(), InnerConfig { o: 7 });
+
+    // Setting the current value again doesn't notify the subscribers.
+    let updated =
+        publisher.update_params(BTreeMap::from([("dynamic_config.o".to_owned(), json!(7))]));
+    assert!(updated.unwrap().is_empty());
+    assert!(!receiver.has_changed().unwrap());
+}
+
+#[test]
+fn dynamic_config_rejects_invalid_param_updates() {
+    let dir = TempDir::new().unwrap();
+    let publisher = DynamicConfigPublisher::new::<ConfigWithDynamicParams>(
+        load_config_with_dynamic_params_map(&dir, &[]),
+    )
+    .unwrap();
+    let receiver = publisher.subscribe::<InnerConfig>("dynamic_config").unwrap();
+
+    let update = |param_path: &str, value| {
+        publisher.update_params(BTreeMap::from([(param_path.to_owned(), value)]))
+    };
+    assert_matches!(
+        update("dynamic_config.o", json!(20)),
+        Err(ConfigError::ConfigValidationError(_))
+    );
+    assert_matches!(update("dynamic_config.o", json!("seven")), Err(ConfigError::MissingParam(_)));
+    assert_matches!(update("static_param", json!(2)), Err(ConfigError::NonDynamicParam { .. }));
+    assert_matches!(update("dynamic_config.p", json!(2)), Err(ConfigError::ParamNotFound { .. }));
+    assert!(!receiver.has_changed().unwrap());
+    assert_eq!(*receiver.borrow(), InnerConfig { o: 5 });
+
+    assert_matches!(
+        publisher.subscribe::<InnerConfig>("dynamic"),
+        Err(ConfigError::ParamNotFound { .. })
+    );
+}
+
+#[test]
+fn dynamic_config_reload_ignores_non_dynamic_params() {
+    let dir = TempDir::new().unwrap();
+    let publisher = DynamicConfigPublisher::new::<ConfigWithDynamicParams>(
+        load_config_with_dynamic_params_map(&dir, &[]),
+    )
+    .unwrap();
+    let mut receiver = publisher.subscribe::<InnerConfig>("dynamic_config").unwrap();
+
+    let custom_config_path = dir.path().join("custom_config.json");
+    std::fs::write(&custom_config_path, r#"{"static_param": 3, "dynamic_config.o": 8}"#).unwrap();
+    let reloaded = load_config_with_dynamic_params_map(&dir, &[custom_config_path]);
+
+    assert_eq!(publisher.reload(reloaded).unwrap(), vec!["dynamic_config.o".to_owned()]);
+    assert_eq!(*receiver.borrow_and_update(), InnerConfig { o: 8 });
+    assert_matches!(
+        publisher.update_params(BTreeMap::from([("static_param".to_owned(), json!(3))])),
+        Err(ConfigError::NonDynamicParam { .. })
+    );
+}
+
+#[test]
+fn dynamic_config_subscription_to_some_params_of_a_sub_config() {
+    let dir = TempDir::new().unwrap();
+    let publisher = DynamicConfigPublisher::new::<ConfigWithDynamicParams>(
+        load_config_with_dynamic_params_map(&dir, &[]),
+    )
+    .unwrap();
+    let mut receiver = publisher
+        .subscribe_to_params::<PartiallyDynamicConfig>(
+            "partially_dynamic_config",
+            &["dynamic_param"],
+        )
+        .unwrap();
+    assert_eq!(
+        *receiver.borrow_and_update(),
+        PartiallyDynamicConfig { dynamic_param: 2, static_param: 3 }
+    );
+
+    let update = |param_path: &str, value| {
+        publisher.update_params(BTreeMap::from([(param_path.to_owned(), value)]))
+    };
+    assert_eq!(
+        update("partially_dynamic_config.dynamic_param", json!(4)).unwrap(),
+        vec!["partially_dynamic_config.dynamic_param".to_owned()]
+    );
+    assert_eq!(
+        *receiver.borrow_and_update(),
+        PartiallyDynamicConfig { dynamic_param: 4, static_param: 3 }
+    );
+    assert_matches!(
+        update("partially_dynamic_config.static_param", json!(4)),
+        Err(ConfigError::NonDynamicParam { .. })
+    );
+    assert!(!receiver.has_changed().unwrap());
+
+    assert_matches!(
+        publisher.subscribe_to_params::<PartiallyDynamicConfig>(
+            "partially_dynamic_config",
+            &["missing_param"]
+        ),
+        Err(ConfigError::ParamNotFound { .. })
+    );
+}
+
+#[tokio::test]
+async fn watch_config_files_reloads_modified_files() {
+    let dir = TempDir::new().unwrap();
+    let custom_config_path = dir.path().join("custom_config.json");
+    std::fs::write(&custom_config_path, r#"{"dynamic_config.o": 5}"#).unwrap();
+    let publisher = Arc::new(
+        DynamicConfigPublisher::new::<ConfigWithDynamicParams>(
+            load_config_with_dynamic_params_map(&dir, &[custom_config_path.clone()]),
+        )
+        .unwrap(),
+    );
+    let mut receiver = publisher.subscribe::<InnerConfig>("dynamic_config").unwrap();
+
+    let watched_paths = vec![custom_config_path.clone()];
+    let reload_path = custom_config_path.clone();
+    let reload_dir = dir.path().to_owned();
+    tokio::spawn(watch_config_files(
+        publisher.clone(),
+        watched_paths,
+        Duration::from_millis(10),
+        move || {
+            let dir = TempDir::new_in(&reload_dir).unwrap();
+            Ok(load_config_with_dynamic_params_map(&dir, &[reload_path.clone()]))
+        },
+    ));
+
+    std::fs::write(&custom_config_path, r#"{"dynamic_config.o": 9}"#).unwrap();
+    // Make sure the modification is noticed, regardless of the file system's time resolution.
+    File::options()
+        .write(true)
+        .open(&custom_config_path)
+        .unwrap()
+        .set_modified(std::time::SystemTime::now() + Duration::from_secs(1))
+        .unwrap();
+
+    tokio::time::timeout(Duration::from_secs(5), receiver.changed()).await.unwrap().unwrap();
+    assert_eq!(*receiver.borrow(), InnerConfig { o: 9 });
+}
+
+// A config schema with a public param, a private param, a required param and a pointer target with
+// a pointing param.
+fn config_schema_example() -> BTreeMap<ParamPath, SerializedParam> {
+    let config_map = BTreeMap::from([
+        ser_param("public", &1, "This is public.", ParamPrivacyInput::Public),
+        ser_param("private", &"secret", "This is private.", ParamPrivacyInput::Private),
+        ser_required_param(
+            "required",
+            SerializationType::Boolean,
+            "This is required.",
+            ParamPrivacyInput::Public,
+        ),
+        ser_param("inner.pointing", &2, "This is pointing.", ParamPrivacyInput::Public),
+    ]);
+    let pointers = vec![(
+        ser_pointer_target_param("target", &3, "This is target."),
+        set_pointing_param_paths(&["inner.pointing"]),
+    )];
+    let combined = combine_config_map_and_pointers(config_map, &pointers, &HashSet::new()).unwrap();
+    serde_json::from_value(combined).unwrap()
+}
+
+#[test]
+fn json_schema_of_config_schema() {
+    let json_schema = get_json_schema(&config_schema_example());
+
+    assert_eq!(json_schema["additionalProperties"], json!(false));
+    assert_eq!(json_schema["required"], json!(["required"]));
+    let properties = json_schema["properties"].as_object().unwrap();
+    assert_eq!(
+        properties.keys().collect::<Vec<_>>(),
+        vec!["private", "public", "required", "target"]
+    );
+    assert_eq!(
+        properties["public"],
+        json!({
+            "type": "integer",
+            "minimum": 0,
+            "description": "This is public.",
+            "x-privacy": "Public",
+            "default": 1,
+        })
+    );
+    assert!(properties["private"].get("default").is_none());
+    assert_eq!(properties["required"]["type"], json!("boolean"));
+    assert_eq!(properties["target"]["x-pointing-params"], json!(["inner.pointing"]));
+}
+
+#[test]
+fn check_config_issues() {
+    let config = json!({
+        "public": "one",
+        "private": "another secret",
+        "inner.pointing": 4,
+        "unknown": 5,
+    });
+    let issues = check_config(&config_schema_example(), config.as_object().unwrap());
+    assert_eq!(
+        issues,
+        vec![
+            ConfigIssue::PointingParam {
+                param_path: "inner.pointing".to_owned(),
+                pointer_target: "target".to_owned(),
+            },
+            ConfigIssue::TypeMismatch {
+                param_path: "public".to_owned(),
+                expected: SerializationType::PositiveInteger,
+                value: json!("one"),
+            },
+            ConfigIssue::UnknownParam { param_path: "unknown".to_owned() },
+            ConfigIssue::MissingRequiredParam { param_path: "required".to_owned() },
+        ]
+    );
+
+    let config = json!({ "public": 2, "required": true, "target": 4 });
+    assert!(check_config(&config_schema_example(), config.as_object().unwrap()).is_empty());
+
+    // The values of private params are redacted.
+    let config = json!({ "private": 7, "required": true });
+    assert_eq!(
+        check_config(&config_schema_example(), config.as_object().unwrap()),
+        vec![ConfigIssue::TypeMismatch {
+            param_path: "private".to_owned(),
+            expected: SerializationType::String,
+            value: json!("<redacted>"),
+        }]
+    );
+}
+
+#[test]
+fn diff_config_against_defaults() {
+    let default_values = get_default_values(&config_schema_example());
+    assert_eq!(
+        Value::Object(default_values.clone()),
+        json!({ "public": 1, "private": "secret", "target": 3 })
+    );
+
+    // The values of private params are redacted.
+    let config = json!({ "public": 1, "target": 4, "required": true });
+    assert_eq!(
+        diff_configs(&config_schema_example(), &default_values, config.as_object().unwrap()),
+        vec![
+            ParamDiff {
+                param_path: "private".to_owned(),
+                left: Some(json!("<redacted>")),
+                right: None,
+            },
+            ParamDiff { param_path: "required".to_owned(), left: None, right: Some(json!(true)) },
+            ParamDiff {
+                param_path: "target".to_owned(),
+                left: Some(json!(3)),
+                right: Some(json!(4)),
+            },
+        ]
+    );
+}
@@ -0,0 +1,297 @@
+//! Support for config params that can be changed while the node is running.
+//!
+//! A component subscribes to one of its sub-configs by the param path of the sub-config, which
+//! marks all the params under it, or only some of them, as dynamic, and receives a typed
+//! [`watch::Receiver`] that holds the latest value of the sub-config. Updates arrive either from
+//! reloading the config files (see [`watch_config_files`]) or from explicit param updates, and are
+//! published only after the whole config passes validation and every affected sub-config loads. An
+//! update that fails is rejected as a whole, and the components keep their current values.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::sync::watch;
+use tracing::{error, info, warn};
+use validator::Validate;
+
+use crate::loading::load;
+use crate::validators::config_validate;
+use crate::{ConfigError, ParamPath, FIELD_SEPARATOR};
+
+/// A thread-safe handle to a [`DynamicConfigPublisher`].
+pub type SharedDynamicConfigPublisher = Arc<DynamicConfigPublisher>;
+
+type ConfigMap = BTreeMap<ParamPath, Value>;
+
+/// Holds the processed config map of the node, and publishes changes of its dynamic params to the
+/// subscribed components.
+pub struct DynamicConfigPublisher {
+    state: Mutex<PublisherState>,
+    validate_config_map: fn(&ConfigMap) -> Result<(), ConfigError>,
+}
+
+struct PublisherState {
+    config_map: ConfigMap,
+    subscriptions: Vec<Box<dyn Subscription>>,
+}
+
+impl DynamicConfigPublisher {
+    /// Creates a publisher for a processed config map of a config of type `T`. Every update of the
+    /// map is validated by loading it as `T` and running its validations.
+    pub fn new<T: for<'a> Deserialize<'a> + Validate>(
+        config_map: BTreeMap<ParamPath, Value>,
+    ) -> Result<Self, ConfigError> {
+        validate_config_map::<T>(&config_map)?;
+        Ok(Self {
+            state: Mutex::new(PublisherState { config_map, subscriptions: vec![] }),
+            validate_config_map: validate_config_map::<T>,
+        })
+    }
+
+    /// Marks the params of the sub-config at `sub_config_path` as dynamic, and returns a receiver
+    /// of the sub-config, which is updated whenever any of these params changes.
+    pub fn subscribe<T>(&self, sub_config_path: &str) -> Result<watch::Receiver<T>, ConfigError>
+    where
+        T: for<'a> Deserialize<'a> + Send + Sync + 'static,
+    {
+        self.subscribe_to_params(sub_config_path, &[""])
+    }
+
+    /// Same as [`Self::subscribe`], but marks only the given params of the sub-config as dynamic,
+    /// by their paths relative to it. The other params of the sub-config keep their values until
+    /// the node restarts.
+    pub fn subscribe_to_params<T>(
+        &self,
+        sub_config_path: &str,
+        dynamic_params: &[&str],
+    ) -> Result<watch::Receiver<T>, ConfigError>
+    where
+        T: for<'a> Deserialize<'a> + Send + Sync + 'static,
+    {
+        let mut state = self.lock_state();
+        let dynamic_param_paths: Vec<ParamPath> = dynamic_params
+            .iter()
+            .map(|param| match *param {
+                "" => sub_config_path.to_owned(),
+                param => format!("{sub_config_path}{FIELD_SEPARATOR}{param}"),
+            })
+            .collect();
+        for param_path in &dynamic_param_paths {
+            if get_sub_config_map(&state.config_map, param_path).is_empty() {
+                return Err(ConfigError::ParamNotFound { param_path: param_path.clone() });
+            }
+        }
+        let sub_config_map = get_sub_config_map(&state.config_map, sub_config_path);
+        let (sender, receiver) = watch::channel(load::<T>(&sub_config_map)?);
+        state.subscriptions.push(Box::new(TypedSubscription {
+            sub_config_path: sub_config_path.to_owned(),
+            dynamic_param_paths,
+            sender,
+        }));
+        Ok(receiver)
+    }
+
+    /// Updates the values of the given params, all of which must be existing dynamic params.
+    /// Returns the paths of the params whose value changed.
+    pub fn update_params(
+        &self,
+        params: BTreeMap<ParamPath, Value>,
+    ) -> Result<Vec<ParamPath>, ConfigError> {
+        let mut state = self.lock_state();
+        let mut new_config_map = state.config_map.clone();
+        for (param_path, value) in params {
+            if !state.config_map.contains_key(&param_path) {
+                return Err(ConfigError::ParamNotFound { param_path });
+            }
+            if !state.is_dynamic(&param_path) {
+                return Err(ConfigError::NonDynamicParam { param_path });
+            }
+            new_config_map.insert(param_path, value);
+        }
+        self.apply(&mut state, new_config_map)
+    }
+
+    /// Applies the dynamic params of a reloaded config map. Changes of params that are not dynamic
+    /// are ignored, and take effect only after the node restarts. Returns the paths of the dynamic
+    /// params whose value changed.
+    pub fn reload(
+        &self,
+        reloaded_config_map: BTreeMap<ParamPath, Value>,
+    ) -> Result<Vec<ParamPath>, ConfigError> {
+        let mut state = self.lock_state();
+        let mut new_config_map = state.config_map.clone();
+        for param_path in changed_params(&state.config_map, &reloaded_config_map) {
+            if !state.is_dynamic(&param_path) {
+                warn!("Ignoring a change of {param_path}, which is not a dynamic param.");
+                continue;
+            }
+            match reloaded_config_map.get(&param_path) {
+                Some(value) => new_config_map.insert(param_path, value.clone()),
+                None => new_config_map.remove(&param_path),
+            };
+        }
+        self.apply(&mut state, new_config_map)
+    }
+
+    // Validates the new config map and loads the sub-configs that it changes, and only then stores
+    // it and publishes the changed sub-configs.
+    fn apply(
+        &self,
+        state: &mut PublisherState,
+        new_config_map: ConfigMap,
+    ) -> Result<Vec<ParamPath>, ConfigError> {
+        let changed_params = changed_params(&state.config_map, &new_config_map);
+        if changed_params.is_empty() {
+            return Ok(changed_params);
+        }
+        (self.validate_config_map)(&new_config_map)?;
+
+        let mut publishes = vec![];
+        for subscription in &state.subscriptions {
+            let sub_config_path = subscription.sub_config_path();
+            let new_sub_config_map = get_sub_config_map(&new_config_map, sub_config_path);
+            if get_sub_config_map(&state.config_map, sub_config_path) != new_sub_config_map {
+                publishes.push(subscription.prepare(&new_sub_config_map)?);
+            }
+        }
+        publishes.into_iter().for_each(|publish| publish());
+        state.config_map = new_config_map;
+        info!("Updated dynamic params: {changed_params:?}.");
+        Ok(changed_params)
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, PublisherState> {
+        self.state.lock().expect("Dynamic config state lock should not be poisoned.")
+    }
+}
+
+impl PublisherState {
+    fn is_dynamic(&self, param_path: &str) -> bool {
+        self.subscriptions.iter().any(|subscription| {
+            subscription
+                .dynamic_param_paths()
+                .iter()
+                .any(|dynamic_param_path| is_in_sub_config(param_path, dynamic_param_path))
+        })
+    }
+}
+
+// A type-erased subscription, so subscriptions to sub-configs of different types can be stored
+// together.
+trait Subscription: Send {
+    fn sub_config_path(&self) -> &str;
+
+    // The params, or sub-configs, of the sub-config that may change.
+    fn dynamic_param_paths(&self) -> &[ParamPath];
+
+    // Loads and validates the sub-config, and returns a function that publishes it.
+    fn prepare(&self, sub_config_map: &ConfigMap) -> Result<Box<dyn FnOnce() + '_>, ConfigError>;
+}
+
+struct TypedSubscription<T> {
+    sub_config_path: ParamPath,
+    dynamic_param_paths: Vec<ParamPath>,
+    sender: watch::Sender<T>,
+}
+
+impl<T> Subscription for TypedSubscription<T>
+where
+    T: for<'a> Deserialize<'a> + Send + Sync,
+{
+    fn sub_config_path(&self) -> &str {
+        &self.sub_config_path
+    }
+
+    fn dynamic_param_paths(&self) -> &[ParamPath] {
+        &self.dynamic_param_paths
+    }
+
+    fn prepare(&self, sub_config_map: &ConfigMap) -> Result<Box<dyn FnOnce() + '_>, ConfigError> {
+        let sub_config = load::<T>(sub_config_map)?;
+        Ok(Box::new(move || {
+            self.sender.send_replace(sub_config);
+        }))
+    }
+}
+
+fn validate_config_map<T: for<'a> Deserialize<'a> + Validate>(
+    config_map: &ConfigMap,
+) -> Result<(), ConfigError> {
+    config_validate(&load::<T>(config_map)?)
+}
+
+fn is_in_sub_config(param_path: &str, sub_config_path: &str) -> bool {
+    param_path
+        .strip_prefix(sub_config_path)
+        .is_some_and(|suffix| suffix.is_empty() || suffix.starts_with(FIELD_SEPARATOR))
+}
+
+// Returns the params under the given sub-config, with their paths relative to it.
+fn get_sub_config_map(config_map: &ConfigMap, sub_config_path: &str) -> ConfigMap {
+    config_map
+        .iter()
+        .filter(|(param_path, _)| is_in_sub_config(param_path, sub_config_path))
+        .map(|(param_path, value)| {
+            let relative_path = param_path[sub_config_path.len()..]
+                .strip_prefix(FIELD_SEPARATOR)
+                .unwrap_or_default();
+            (relative_path.to_owned(), value.clone())
+        })
+        .collect()
+}
+
+// Returns the params that were added, removed or changed between the two config maps.
+fn changed_params(config_map: &ConfigMap, new_config_map: &ConfigMap) -> Vec<ParamPath> {
+    let mut param_paths: Vec<_> = config_map.keys().chain(new_config_map.keys()).collect();
+    param_paths.sort();
+    param_paths.dedup();
+    param_paths
+        .into_iter()
+        .filter(|param_path| config_map.get(*param_path) != new_config_map.get(*param_path))
+        .cloned()
+        .collect()
+}
+
+/// Polls the modification times of the given config files, and reloads the config into the
+/// publisher with `load_config_map` whenever any of them changes after this call. The returned
+/// future runs forever; failures to reload are logged, and the current config is kept until the
+/// next change.
+pub fn watch_config_files(
+    publisher: SharedDynamicConfigPublisher,
+    config_file_paths: Vec<PathBuf>,
+    poll_interval: Duration,
+    load_config_map: impl Fn() -> Result<BTreeMap<ParamPath, Value>, ConfigError>,
+) -> impl Future<Output = ()> {
+    let mut modification_times = get_modification_times(&config_file_paths);
+    async move {
+        let mut interval = tokio::time::interval(poll_interval);
+        loop {
+            interval.tick().await;
+            let current_modification_times = get_modification_times(&config_file_paths);
+            if current_modification_times == modification_times {
+                continue;
+            }
+            modification_times = current_modification_times;
+            info!("Config files changed, reloading the config.");
+            if let Err(error) =
+                load_config_map().and_then(|config_map| publisher.reload(config_map))
+            {
+                error!("Failed to reload the config: {error}");
+            }
+        }
+    }
+}
+
+// A file that can't be read is treated as having no modification time, so it is reloaded once it
+// becomes readable again.
+fn get_modification_times(file_paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    file_paths
+        .iter()
+        .map(|path| std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok())
+        .collect()
+}
@@ -78,8 +78,10 @@ pub mod test_utils;
 mod command;
 pub mod converters;
 pub mod dumping;
+pub mod dynamic_config;
 pub mod loading;
 pub mod presentation;
+pub mod schema;
 pub mod validators;
 
 /// The privacy level of a config parameter, that received as input from the configs.
@@ -203,6 +205,8 @@ pub enum ConfigError {
     PointerSourceNotFound { pointing_param: String },
     #[error("Changing {param_path} from required type {required} to {given} is not allowed.")]
     ChangeRequiredParamType { param_path: String, required: SerializationType, given: Value },
+    #[error("{param_path} is not a dynamic param, and can't be changed while the node is running.")]
+    NonDynamicParam { param_path: String },
     #[error(transparent)]
     ValidationError(#[from] ValidationError),
     #[error(transparent)]
@@ -9,7 +9,6 @@ use std::fs::File;
 use std::ops::IndexMut;
 use std::path::PathBuf;
 
-use clap::parser::Values;
 use clap::Command;
 use command::{get_command_matches, update_config_map_by_command_args};
 use itertools::any;
@@ -56,6 +55,20 @@ pub fn load_and_process_config<T: for<'a> Deserialize<'a>>(
     args: Vec<String>,
     ignore_default_values: bool,
 ) -> Result<T, ConfigError> {
+    let (values_map, _) =
+        load_and_process_config_map(default_config_file, command, args, ignore_default_values)?;
+    // Build and return a Config object.
+    load(&values_map)
+}
+
+/// Same as [`load_and_process_config`], but returns the processed config map instead of a config
+/// object, together with the custom config files that were applied, by their order.
+pub fn load_and_process_config_map(
+    default_config_file: File,
+    command: Command,
+    args: Vec<String>,
+    ignore_default_values: bool,
+) -> Result<(BTreeMap<ParamPath, Value>, Vec<PathBuf>), ConfigError> {
     let deserialized_default_config: Map<ParamPath, Value> =
         serde_json::from_reader(default_config_file)?;
     // Store the pointers separately from the default values. The pointers will receive a value
@@ -70,17 +83,18 @@ pub fn load_and_process_config<T: for<'a> Deserialize<'a>>(
         values_map = BTreeMap::new();
     }
     // If the config_file arg is given, updates the values map according to this files.
-    if let Some(custom_config_paths) = arg_matches.remove_many::<PathBuf>(CONFIG_FILE_ARG_NAME) {
-        update_config_map_by_custom_configs(&mut values_map, &types_map, custom_config_paths)?;
-    };
+    let custom_config_paths: Vec<PathBuf> = arg_matches
+        .remove_many::<PathBuf>(CONFIG_FILE_ARG_NAME)
+        .map(|paths| paths.collect())
+        .unwrap_or_default();
+    update_config_map_by_custom_configs(&mut values_map, &types_map, &custom_config_paths)?;
     // Updates the values map according to the args.
     update_config_map_by_command_args(&mut values_map, &types_map, &arg_matches)?;
     // Set values to the pointers.
     update_config_map_by_pointers(&mut values_map, &pointers_map)?;
     // Set values according to the is-none marks.
     update_optional_values(&mut values_map);
-    // Build and return a Config object.
-    load(&values_map)
+    Ok((values_map, custom_config_paths))
 }
 
 // Separates a json map into config map of the raw values and pointers map.
@@ -130,11 +144,11 @@ pub(crate) fn split_values_and_types(
 pub(crate) fn update_config_map_by_custom_configs(
     config_map: &mut BTreeMap<ParamPath, Value>,
     types_map: &BTreeMap<ParamPath, SerializationType>,
-    custom_config_paths: Values<PathBuf>,
+    custom_config_paths: &[PathBuf],
 ) -> Result<(), ConfigError> {
     for config_path in custom_config_paths {
         info!("Loading custom config file: {:?}", config_path);
-        validate_path_exists(&config_path)?;
+        validate_path_exists(config_path)?;
         let file = std::fs::File::open(config_path)?;
         let custom_config: Map<String, Value> = serde_json::from_reader(file)?;
         for (param_path, json_value) in custom_config {
@@ -208,14 +222,7 @@ pub(crate) fn update_config_map(
     let Some(serialization_type) = types_map.get(param_path) else {
         return Err(ConfigError::ParamNotFound { param_path: param_path.to_string() });
     };
-    let is_type_matched = match serialization_type {
-        SerializationType::Boolean => new_value.is_boolean(),
-        SerializationType::Float => new_value.is_number(),
-        SerializationType::NegativeInteger => new_value.is_number(),
-        SerializationType::PositiveInteger => new_value.is_number(),
-        SerializationType::String => new_value.is_string(),
-    };
-    if !is_type_matched {
+    if !is_type_matched(*serialization_type, &new_value) {
         return Err(ConfigError::ChangeRequiredParamType {
             param_path: param_path.to_string(),
             required: serialization_type.to_owned(),
@@ -226,3 +233,14 @@ pub(crate) fn update_config_map(
     config_map.insert(param_path.to_owned(), new_value);
     Ok(())
 }
+
+// Whether a value can be set to a param of the given type.
+pub(crate) fn is_type_matched(serialization_type: SerializationType, value: &Value) -> bool {
+    match serialization_type {
+        SerializationType::Boolean => value.is_boolean(),
+        SerializationType::Float => value.is_number(),
+        SerializationType::NegativeInteger => value.is_number(),
+        SerializationType::PositiveInteger => value.is_number(),
+        SerializationType::String => value.is_string(),
+    }
+}
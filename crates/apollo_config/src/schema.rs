@@ -0,0 +1,252 @@
+//! Offline inspection of config files against a dumped config schema: exporting the schema as a
+//! JSON Schema, checking config files for params the loader would reject, and diffing configs.
+//!
+//! The config schema is the flat map written by
+//! [`dump_to_file`](crate::dumping::SerializeConfig::dump_to_file), and the config files are flat
+//! maps from param paths to values, as given to the `--config_file` arg.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::loading::is_type_matched;
+use crate::{ParamPath, SerializationType, SerializedContent, SerializedParam};
+
+const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+// Shown instead of the values of private params.
+const REDACTED_VALUE: &str = "<redacted>";
+
+/// Returns a JSON Schema of the config files that can be loaded with the given config schema.
+///
+/// Every param that can be set in a config file is a property with its type, description and
+/// privacy, and with its default value unless the param is private. Pointing params can't be set
+/// directly, and are listed under their pointer target instead.
+pub fn get_json_schema(config_schema: &BTreeMap<ParamPath, SerializedParam>) -> Value {
+    let mut properties = Map::new();
+    let mut required = vec![];
+    for (param_path, serialized_param) in config_schema {
+        let Some(serialization_type) = serialized_param.content.get_serialization_type() else {
+            continue;
+        };
+        let mut property = get_type_schema(serialization_type);
+        property.insert("description".to_owned(), json!(serialized_param.description));
+        property.insert("x-privacy".to_owned(), json!(serialized_param.privacy));
+        match &serialized_param.content {
+            SerializedContent::DefaultValue(value) if !serialized_param.is_private() => {
+                property.insert("default".to_owned(), value.clone());
+            }
+            SerializedContent::ParamType(_) => required.push(param_path.clone()),
+            _ => {}
+        }
+        let pointing_params = get_pointing_params(config_schema, param_path);
+        if !pointing_params.is_empty() {
+            property.insert("x-pointing-params".to_owned(), json!(pointing_params));
+        }
+        properties.insert(param_path.clone(), Value::Object(property));
+    }
+    json!({
+        "$schema": JSON_SCHEMA_DIALECT,
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+/// Returns the default values of the params in the config schema, as a config file.
+pub fn get_default_values(
+    config_schema: &BTreeMap<ParamPath, SerializedParam>,
+) -> Map<String, Value> {
+    config_schema
+        .iter()
+        .filter_map(|(param_path, serialized_param)| match &serialized_param.content {
+            SerializedContent::DefaultValue(value)
+                if serialized_param.content.get_serialization_type().is_some() =>
+            {
+                Some((param_path.clone(), value.clone()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// A problem in a config file, that fails loading it with the config schema.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub enum ConfigIssue {
+    /// The param is not in the config schema.
+    UnknownParam {
+        /// The path of the param.
+        param_path: ParamPath,
+    },
+    /// The param takes its value from a pointer target, and can't be set directly.
+    PointingParam {
+        /// The path of the param.
+        param_path: ParamPath,
+        /// The path of the pointer target that should be set instead.
+        pointer_target: ParamPath,
+    },
+    /// The value of the param doesn't match its type in the config schema.
+    TypeMismatch {
+        /// The path of the param.
+        param_path: ParamPath,
+        /// The type of the param in the config schema.
+        expected: SerializationType,
+        /// The value of the param in the config file.
+        value: Value,
+    },
+    /// A required param, that has no default value, is missing.
+    MissingRequiredParam {
+        /// The path of the param.
+        param_path: ParamPath,
+    },
+}
+
+impl Display for ConfigIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigIssue::UnknownParam { param_path } => write!(f, "Unknown param {param_path}."),
+            ConfigIssue::PointingParam { param_path, pointer_target } => {
+                write!(f, "{param_path} can't be set directly, set {pointer_target} instead.")
+            }
+            ConfigIssue::TypeMismatch { param_path, expected, value } => {
+                write!(f, "{param_path} should be of type {expected}, got {value}.")
+            }
+            ConfigIssue::MissingRequiredParam { param_path } => {
+                write!(f, "Required param {param_path} is missing.")
+            }
+        }
+    }
+}
+
+/// Checks a config file against the config schema, and returns the issues found in it.
+pub fn check_config(
+    config_schema: &BTreeMap<ParamPath, SerializedParam>,
+    config: &Map<String, Value>,
+) -> Vec<ConfigIssue> {
+    let mut issues = vec![];
+    for (param_path, value) in config {
+        let Some(serialized_param) = config_schema.get(param_path) else {
+            issues.push(ConfigIssue::UnknownParam { param_path: param_path.clone() });
+            continue;
+        };
+        match (&serialized_param.content, serialized_param.content.get_serialization_type()) {
+            (SerializedContent::PointerTarget(pointer_target), _) => {
+                issues.push(ConfigIssue::PointingParam {
+                    param_path: param_path.clone(),
+                    pointer_target: pointer_target.clone(),
+                });
+            }
+            (_, None) => {
+                issues.push(ConfigIssue::UnknownParam { param_path: param_path.clone() });
+            }
+            (_, Some(expected)) if !is_type_matched(expected, value) => {
+                issues.push(ConfigIssue::TypeMismatch {
+                    param_path: param_path.clone(),
+                    expected,
+                    value: redact_private_value(serialized_param, value),
+                });
+            }
+            _ => {}
+        }
+    }
+    for (param_path, serialized_param) in config_schema {
+        if matches!(serialized_param.content, SerializedContent::ParamType(_))
+            && !config.contains_key(param_path)
+        {
+            issues.push(ConfigIssue::MissingRequiredParam { param_path: param_path.clone() });
+        }
+    }
+    issues
+}
+
+/// A param whose value differs between two configs.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ParamDiff {
+    /// The path of the param.
+    pub param_path: ParamPath,
+    /// The value of the param in the first config, if it is set there.
+    pub left: Option<Value>,
+    /// The value of the param in the second config, if it is set there.
+    pub right: Option<Value>,
+}
+
+impl Display for ParamDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let format_value =
+            |value: &Option<Value>| value.as_ref().map_or("<unset>".to_owned(), Value::to_string);
+        write!(
+            f,
+            "{}: {} -> {}",
+            self.param_path,
+            format_value(&self.left),
+            format_value(&self.right)
+        )
+    }
+}
+
+/// Returns the params whose values differ between the two configs, ordered by their paths. The
+/// values of params that are private in the config schema are redacted.
+pub fn diff_configs(
+    config_schema: &BTreeMap<ParamPath, SerializedParam>,
+    left: &Map<String, Value>,
+    right: &Map<String, Value>,
+) -> Vec<ParamDiff> {
+    let mut param_paths: Vec<_> = left.keys().chain(right.keys()).collect();
+    param_paths.sort();
+    param_paths.dedup();
+    param_paths
+        .into_iter()
+        .filter(|param_path| left.get(*param_path) != right.get(*param_path))
+        .map(|param_path| {
+            let get_value = |config: &Map<String, Value>| {
+                let value = config.get(param_path)?;
+                Some(match config_schema.get(param_path) {
+                    Some(serialized_param) => redact_private_value(serialized_param, value),
+                    None => value.clone(),
+                })
+            };
+            ParamDiff {
+                param_path: param_path.clone(),
+                left: get_value(left),
+                right: get_value(right),
+            }
+        })
+        .collect()
+}
+
+fn redact_private_value(serialized_param: &SerializedParam, value: &Value) -> Value {
+    if serialized_param.is_private() { json!(REDACTED_VALUE) } else { value.clone() }
+}
+
+fn get_type_schema(serialization_type: SerializationType) -> Map<String, Value> {
+    let type_schema = match serialization_type {
+        SerializationType::Boolean => json!({ "type": "boolean" }),
+        SerializationType::Float => json!({ "type": "number" }),
+        SerializationType::NegativeInteger => json!({ "type": "integer" }),
+        SerializationType::PositiveInteger => json!({ "type": "integer", "minimum": 0 }),
+        SerializationType::String => json!({ "type": "string" }),
+    };
+    let Value::Object(type_schema) = type_schema else {
+        unreachable!("The type schema is a JSON object.");
+    };
+    type_schema
+}
+
+fn get_pointing_params(
+    config_schema: &BTreeMap<ParamPath, SerializedParam>,
+    target_param_path: &str,
+) -> Vec<ParamPath> {
+    config_schema
+        .iter()
+        .filter(|(_, serialized_param)| {
+            matches!(
+                &serialized_param.content,
+                SerializedContent::PointerTarget(pointer_target)
+                    if pointer_target == target_param_path
+            )
+        })
+        .map(|(param_path, _)| param_path.clone())
+        .collect()
+}
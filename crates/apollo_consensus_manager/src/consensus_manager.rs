@@ -24,6 +24,7 @@ use apollo_l1_gas_price_types::L1GasPriceProviderClient;
 use apollo_network::gossipsub_impl::Topic;
 use apollo_network::network_manager::metrics::{BroadcastNetworkMetrics, NetworkMetrics};
 use apollo_network::network_manager::{BroadcastTopicChannels, NetworkManager};
+use apollo_network_types::network_types::PeerBanListClients;
 use apollo_protobuf::consensus::{HeightAndRound, ProposalPart, StreamMessage, Vote};
 use apollo_reverts::revert_blocks_and_eternal_pending;
 use apollo_signature_manager_types::SharedSignatureManagerClient;
@@ -38,11 +39,13 @@ use crate::config::ConsensusManagerConfig;
 use crate::metrics::{
     CONSENSUS_NUM_BLACKLISTED_PEERS,
     CONSENSUS_NUM_CONNECTED_PEERS,
+    CONSENSUS_NUM_DENIED_CONNECTIONS,
     CONSENSUS_PROPOSALS_NUM_RECEIVED_MESSAGES,
     CONSENSUS_PROPOSALS_NUM_SENT_MESSAGES,
     CONSENSUS_VOTES_NUM_RECEIVED_MESSAGES,
     CONSENSUS_VOTES_NUM_SENT_MESSAGES,
 };
+use crate::staker_identity::SignatureManagerStakerIdentity;
 
 #[derive(Clone)]
 pub struct ConsensusManager {
@@ -52,6 +55,7 @@ pub struct ConsensusManager {
     pub class_manager_client: SharedClassManagerClient,
     pub signature_manager_client: SharedSignatureManagerClient,
     l1_gas_price_provider: Arc<dyn L1GasPriceProviderClient>,
+    peer_ban_list_clients: PeerBanListClients,
 }
 
 impl ConsensusManager {
@@ -62,6 +66,7 @@ impl ConsensusManager {
         class_manager_client: SharedClassManagerClient,
         signature_manager_client: SharedSignatureManagerClient,
         l1_gas_price_provider: Arc<dyn L1GasPriceProviderClient>,
+        peer_ban_list_clients: PeerBanListClients,
     ) -> Self {
         Self {
             config,
@@ -70,6 +75,7 @@ impl ConsensusManager {
             class_manager_client,
             signature_manager_client,
             l1_gas_price_provider,
+            peer_ban_list_clients,
         }
     }
 
@@ -96,11 +102,18 @@ impl ConsensusManager {
         let network_manager_metrics = Some(NetworkMetrics {
             num_connected_peers: CONSENSUS_NUM_CONNECTED_PEERS,
             num_blacklisted_peers: CONSENSUS_NUM_BLACKLISTED_PEERS,
+            num_denied_connections: CONSENSUS_NUM_DENIED_CONNECTIONS,
             broadcast_metrics_by_topic: Some(broadcast_metrics_by_topic),
             sqmr_metrics: None,
         });
         let mut network_manager =
             NetworkManager::new(self.config.network_config.clone(), None, network_manager_metrics);
+        network_manager.set_staker_identity_manager(Arc::new(SignatureManagerStakerIdentity {
+            signature_manager_client: self.signature_manager_client.clone(),
+        }));
+        if let Some(peer_ban_list_client) = network_manager.peer_ban_list_client() {
+            self.peer_ban_list_clients.insert("consensus", peer_ban_list_client);
+        }
 
         let proposals_broadcast_channels = network_manager
             .register_broadcast_topic::<StreamMessage<ProposalPart, HeightAndRound>>(
@@ -247,6 +260,7 @@ pub fn create_consensus_manager(
     class_manager_client: SharedClassManagerClient,
     signature_manager_client: SharedSignatureManagerClient,
     l1_gas_price_provider: Arc<dyn L1GasPriceProviderClient>,
+    peer_ban_list_clients: PeerBanListClients,
 ) -> ConsensusManager {
     ConsensusManager::new(
         config,
@@ -255,6 +269,7 @@ pub fn create_consensus_manager(
         class_manager_client,
         signature_manager_client,
         l1_gas_price_provider,
+        peer_ban_list_clients,
     )
 }
 
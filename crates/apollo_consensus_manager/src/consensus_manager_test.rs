@@ -4,6 +4,7 @@ use apollo_batcher_types::batcher_types::{GetHeightResponse, RevertBlockInput};
 use apollo_batcher_types::communication::MockBatcherClient;
 use apollo_class_manager_types::EmptyClassManagerClient;
 use apollo_l1_gas_price_types::MockL1GasPriceProviderClient;
+use apollo_network_types::network_types::PeerBanListClients;
 use apollo_reverts::RevertConfig;
 use apollo_signature_manager_types::MockSignatureManagerClient;
 use apollo_state_sync_types::communication::MockStateSyncClient;
@@ -50,6 +51,7 @@ async fn revert_batcher_blocks() {
         Arc::new(EmptyClassManagerClient),
         Arc::new(MockSignatureManagerClient::new()),
         Arc::new(MockL1GasPriceProviderClient::new()),
+        PeerBanListClients::default(),
     );
 
     // TODO(Shahak, dvir): try to solve this better (the test will take 100 milliseconds to run).
@@ -69,6 +71,7 @@ async fn no_reverts_without_config() {
         Arc::new(EmptyClassManagerClient),
         Arc::new(MockSignatureManagerClient::new()),
         Arc::new(MockL1GasPriceProviderClient::new()),
+        PeerBanListClients::default(),
     );
 
     // TODO(Shahak, dvir): try to solve this better (the test will take 100 milliseconds to run).
@@ -2,3 +2,4 @@ pub mod communication;
 pub mod config;
 pub mod consensus_manager;
 pub mod metrics;
+pub mod staker_identity;
@@ -0,0 +1,37 @@
+use apollo_network::staker_identity::{StakerIdentityError, StakerIdentityManager};
+use apollo_network_types::network_types::PeerId;
+use apollo_signature_manager::signature_manager::verify_identity;
+use apollo_signature_manager_types::SharedSignatureManagerClient;
+use async_trait::async_trait;
+use starknet_api::core::Nonce;
+use starknet_api::crypto::utils::{PublicKey, RawSignature};
+
+/// Signs the node's staker identity with the signature manager, for the network's permissioned
+/// mode.
+pub struct SignatureManagerStakerIdentity {
+    pub signature_manager_client: SharedSignatureManagerClient,
+}
+
+#[async_trait]
+impl StakerIdentityManager for SignatureManagerStakerIdentity {
+    async fn sign_identity(
+        &self,
+        peer_id: PeerId,
+        nonce: Nonce,
+    ) -> Result<RawSignature, StakerIdentityError> {
+        self.signature_manager_client
+            .identify(peer_id, nonce)
+            .await
+            .map_err(|err| StakerIdentityError(err.to_string()))
+    }
+
+    fn verify_identity(
+        &self,
+        peer_id: PeerId,
+        nonce: Nonce,
+        signature: &RawSignature,
+        public_key: PublicKey,
+    ) -> bool {
+        verify_identity(peer_id, nonce, signature.clone(), public_key).unwrap_or(false)
+    }
+}
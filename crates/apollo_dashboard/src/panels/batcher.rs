@@ -9,6 +9,7 @@ use apollo_infra::metrics::{
     BATCHER_LOCAL_MSGS_PROCESSED,
     BATCHER_LOCAL_MSGS_RECEIVED,
     BATCHER_LOCAL_QUEUE_DEPTH,
+    BATCHER_REMOTE_CLIENT_CIRCUIT_STATE,
     BATCHER_REMOTE_CLIENT_SEND_ATTEMPTS,
     BATCHER_REMOTE_MSGS_PROCESSED,
     BATCHER_REMOTE_MSGS_RECEIVED,
@@ -57,6 +58,9 @@ fn get_panel_batcher_local_queue_depth() -> Panel {
 fn get_panel_batcher_remote_client_send_attempts() -> Panel {
     Panel::from_hist(BATCHER_REMOTE_CLIENT_SEND_ATTEMPTS, PanelType::TimeSeries)
 }
+fn get_panel_batcher_remote_client_circuit_state() -> Panel {
+    Panel::from_gauge(BATCHER_REMOTE_CLIENT_CIRCUIT_STATE, PanelType::TimeSeries)
+}
 
 pub(crate) fn get_batcher_row() -> Row {
     Row::new(
@@ -83,6 +87,7 @@ pub(crate) fn get_batcher_infra_row() -> Row {
             get_panel_batcher_remote_valid_msgs_received(),
             get_panel_batcher_remote_msgs_processed(),
             get_panel_batcher_remote_client_send_attempts(),
+            get_panel_batcher_remote_client_circuit_state(),
         ],
     )
 }
@@ -2,11 +2,17 @@ use apollo_infra::metrics::{
     CLASS_MANAGER_LOCAL_MSGS_PROCESSED,
     CLASS_MANAGER_LOCAL_MSGS_RECEIVED,
     CLASS_MANAGER_LOCAL_QUEUE_DEPTH,
+    CLASS_MANAGER_LOCAL_REQUESTS_SHED,
+    CLASS_MANAGER_LOCAL_REQUEST_QUEUE_DEPTH,
+    CLASS_MANAGER_LOCAL_REQUEST_QUEUE_TIME,
+    CLASS_MANAGER_REMOTE_CLIENT_CIRCUIT_STATE,
     CLASS_MANAGER_REMOTE_CLIENT_SEND_ATTEMPTS,
     CLASS_MANAGER_REMOTE_MSGS_PROCESSED,
     CLASS_MANAGER_REMOTE_MSGS_RECEIVED,
     CLASS_MANAGER_REMOTE_VALID_MSGS_RECEIVED,
+    LABEL_NAME_REQUEST,
 };
+use apollo_metrics::metric_label_filter;
 
 use crate::dashboard::{Panel, PanelType, Row};
 
@@ -28,9 +34,49 @@ fn get_panel_class_manager_remote_msgs_processed() -> Panel {
 fn get_panel_class_manager_local_queue_depth() -> Panel {
     Panel::from_gauge(CLASS_MANAGER_LOCAL_QUEUE_DEPTH, PanelType::TimeSeries)
 }
+fn get_panel_class_manager_local_request_queue_depth() -> Panel {
+    Panel::new(
+        CLASS_MANAGER_LOCAL_REQUEST_QUEUE_DEPTH.get_name(),
+        CLASS_MANAGER_LOCAL_REQUEST_QUEUE_DEPTH.get_description(),
+        vec![format!(
+            "sum by ({}) ({})",
+            LABEL_NAME_REQUEST,
+            CLASS_MANAGER_LOCAL_REQUEST_QUEUE_DEPTH.get_name_with_filter()
+        )],
+        PanelType::TimeSeries,
+    )
+}
+fn get_panel_class_manager_local_request_queue_time() -> Panel {
+    Panel::new(
+        CLASS_MANAGER_LOCAL_REQUEST_QUEUE_TIME.get_name(),
+        "The 95th percentile of the time (secs) requests waited to be dispatched, by request",
+        vec![format!(
+            "histogram_quantile(0.95, sum by (le, {}) (rate({}_bucket{}[5m])))",
+            LABEL_NAME_REQUEST,
+            CLASS_MANAGER_LOCAL_REQUEST_QUEUE_TIME.get_name(),
+            metric_label_filter!()
+        )],
+        PanelType::TimeSeries,
+    )
+}
+fn get_panel_class_manager_local_requests_shed() -> Panel {
+    Panel::new(
+        CLASS_MANAGER_LOCAL_REQUESTS_SHED.get_name(),
+        CLASS_MANAGER_LOCAL_REQUESTS_SHED.get_description(),
+        vec![format!(
+            "sum by ({}) (increase({}[5m]))",
+            LABEL_NAME_REQUEST,
+            CLASS_MANAGER_LOCAL_REQUESTS_SHED.get_name_with_filter()
+        )],
+        PanelType::TimeSeries,
+    )
+}
 fn get_panel_class_manager_remote_client_send_attempts() -> Panel {
     Panel::from_hist(CLASS_MANAGER_REMOTE_CLIENT_SEND_ATTEMPTS, PanelType::TimeSeries)
 }
+fn get_panel_class_manager_remote_client_circuit_state() -> Panel {
+    Panel::from_gauge(CLASS_MANAGER_REMOTE_CLIENT_CIRCUIT_STATE, PanelType::TimeSeries)
+}
 
 pub(crate) fn get_class_manager_infra_row() -> Row {
     Row::new(
@@ -39,10 +85,14 @@ pub(crate) fn get_class_manager_infra_row() -> Row {
             get_panel_class_manager_local_msgs_received(),
             get_panel_class_manager_local_msgs_processed(),
             get_panel_class_manager_local_queue_depth(),
+            get_panel_class_manager_local_request_queue_depth(),
+            get_panel_class_manager_local_request_queue_time(),
+            get_panel_class_manager_local_requests_shed(),
             get_panel_class_manager_remote_msgs_received(),
             get_panel_class_manager_remote_valid_msgs_received(),
             get_panel_class_manager_remote_msgs_processed(),
             get_panel_class_manager_remote_client_send_attempts(),
+            get_panel_class_manager_remote_client_circuit_state(),
         ],
     )
 }
@@ -11,11 +11,17 @@ use apollo_infra::metrics::{
     GATEWAY_LOCAL_MSGS_PROCESSED,
     GATEWAY_LOCAL_MSGS_RECEIVED,
     GATEWAY_LOCAL_QUEUE_DEPTH,
+    GATEWAY_LOCAL_REQUESTS_SHED,
+    GATEWAY_LOCAL_REQUEST_QUEUE_DEPTH,
+    GATEWAY_LOCAL_REQUEST_QUEUE_TIME,
+    GATEWAY_REMOTE_CLIENT_CIRCUIT_STATE,
     GATEWAY_REMOTE_CLIENT_SEND_ATTEMPTS,
     GATEWAY_REMOTE_MSGS_PROCESSED,
     GATEWAY_REMOTE_MSGS_RECEIVED,
     GATEWAY_REMOTE_VALID_MSGS_RECEIVED,
+    LABEL_NAME_REQUEST,
 };
+use apollo_metrics::metric_label_filter;
 
 use crate::dashboard::{Panel, PanelType, Row};
 
@@ -50,9 +56,49 @@ fn get_panel_gateway_remote_msgs_processed() -> Panel {
 fn get_panel_gateway_local_queue_depth() -> Panel {
     Panel::from_gauge(GATEWAY_LOCAL_QUEUE_DEPTH, PanelType::TimeSeries)
 }
+fn get_panel_gateway_local_request_queue_depth() -> Panel {
+    Panel::new(
+        GATEWAY_LOCAL_REQUEST_QUEUE_DEPTH.get_name(),
+        GATEWAY_LOCAL_REQUEST_QUEUE_DEPTH.get_description(),
+        vec![format!(
+            "sum by ({}) ({})",
+            LABEL_NAME_REQUEST,
+            GATEWAY_LOCAL_REQUEST_QUEUE_DEPTH.get_name_with_filter()
+        )],
+        PanelType::TimeSeries,
+    )
+}
+fn get_panel_gateway_local_request_queue_time() -> Panel {
+    Panel::new(
+        GATEWAY_LOCAL_REQUEST_QUEUE_TIME.get_name(),
+        "The 95th percentile of the time (secs) requests waited to be dispatched, by request",
+        vec![format!(
+            "histogram_quantile(0.95, sum by (le, {}) (rate({}_bucket{}[5m])))",
+            LABEL_NAME_REQUEST,
+            GATEWAY_LOCAL_REQUEST_QUEUE_TIME.get_name(),
+            metric_label_filter!()
+        )],
+        PanelType::TimeSeries,
+    )
+}
+fn get_panel_gateway_local_requests_shed() -> Panel {
+    Panel::new(
+        GATEWAY_LOCAL_REQUESTS_SHED.get_name(),
+        GATEWAY_LOCAL_REQUESTS_SHED.get_description(),
+        vec![format!(
+            "sum by ({}) (increase({}[5m]))",
+            LABEL_NAME_REQUEST,
+            GATEWAY_LOCAL_REQUESTS_SHED.get_name_with_filter()
+        )],
+        PanelType::TimeSeries,
+    )
+}
 fn get_panel_gateway_remote_client_send_attempts() -> Panel {
     Panel::from_hist(GATEWAY_REMOTE_CLIENT_SEND_ATTEMPTS, PanelType::TimeSeries)
 }
+fn get_panel_gateway_remote_client_circuit_state() -> Panel {
+    Panel::from_gauge(GATEWAY_REMOTE_CLIENT_CIRCUIT_STATE, PanelType::TimeSeries)
+}
 
 fn get_panel_gateway_transactions_received_by_source() -> Panel {
     Panel::new(
@@ -135,10 +181,14 @@ pub(crate) fn get_gateway_infra_row() -> Row {
             get_panel_gateway_local_msgs_received(),
             get_panel_gateway_local_msgs_processed(),
             get_panel_gateway_local_queue_depth(),
+            get_panel_gateway_local_request_queue_depth(),
+            get_panel_gateway_local_request_queue_time(),
+            get_panel_gateway_local_requests_shed(),
             get_panel_gateway_remote_msgs_received(),
             get_panel_gateway_remote_valid_msgs_received(),
             get_panel_gateway_remote_msgs_processed(),
             get_panel_gateway_remote_client_send_attempts(),
+            get_panel_gateway_remote_client_circuit_state(),
         ],
     )
 }
@@ -2,6 +2,7 @@ use apollo_infra::metrics::{
     L1_GAS_PRICE_PROVIDER_LOCAL_MSGS_PROCESSED,
     L1_GAS_PRICE_PROVIDER_LOCAL_MSGS_RECEIVED,
     L1_GAS_PRICE_PROVIDER_LOCAL_QUEUE_DEPTH,
+    L1_GAS_PRICE_PROVIDER_REMOTE_CLIENT_CIRCUIT_STATE,
     L1_GAS_PRICE_PROVIDER_REMOTE_CLIENT_SEND_ATTEMPTS,
     L1_GAS_PRICE_PROVIDER_REMOTE_MSGS_PROCESSED,
     L1_GAS_PRICE_PROVIDER_REMOTE_MSGS_RECEIVED,
@@ -9,6 +10,9 @@ use apollo_infra::metrics::{
 };
 use apollo_l1_gas_price::metrics::{
     ETH_TO_STRK_ERROR_COUNT,
+    ETH_TO_STRK_FALLBACK_COUNT,
+    ETH_TO_STRK_OUTLIER_COUNT,
+    ETH_TO_STRK_STALE_FALLBACK_COUNT,
     ETH_TO_STRK_SUCCESS_COUNT,
     L1_GAS_PRICE_PROVIDER_INSUFFICIENT_HISTORY,
     L1_GAS_PRICE_SCRAPER_BASELAYER_ERROR_COUNT,
@@ -39,6 +43,9 @@ fn get_panel_l1_gas_price_provider_local_queue_depth() -> Panel {
 fn get_panel_l1_gas_price_provider_remote_client_send_attempts() -> Panel {
     Panel::from_hist(L1_GAS_PRICE_PROVIDER_REMOTE_CLIENT_SEND_ATTEMPTS, PanelType::TimeSeries)
 }
+fn get_panel_l1_gas_price_provider_remote_client_circuit_state() -> Panel {
+    Panel::from_gauge(L1_GAS_PRICE_PROVIDER_REMOTE_CLIENT_CIRCUIT_STATE, PanelType::TimeSeries)
+}
 
 fn get_panel_l1_gas_price_provider_insufficient_history() -> Panel {
     Panel::from_counter(L1_GAS_PRICE_PROVIDER_INSUFFICIENT_HISTORY, PanelType::Stat)
@@ -58,6 +65,15 @@ fn get_panel_eth_to_strk_error_count() -> Panel {
 fn get_panel_eth_to_strk_success_count() -> Panel {
     Panel::from_counter(ETH_TO_STRK_SUCCESS_COUNT, PanelType::Stat)
 }
+fn get_panel_eth_to_strk_outlier_count() -> Panel {
+    Panel::from_counter(ETH_TO_STRK_OUTLIER_COUNT, PanelType::Stat)
+}
+fn get_panel_eth_to_strk_fallback_count() -> Panel {
+    Panel::from_counter(ETH_TO_STRK_FALLBACK_COUNT, PanelType::Stat)
+}
+fn get_panel_eth_to_strk_stale_fallback_count() -> Panel {
+    Panel::from_counter(ETH_TO_STRK_STALE_FALLBACK_COUNT, PanelType::Stat)
+}
 
 pub(crate) fn get_l1_gas_price_row() -> Row {
     Row::new(
@@ -65,6 +81,9 @@ pub(crate) fn get_l1_gas_price_row() -> Row {
         vec![
             get_panel_eth_to_strk_error_count(),
             get_panel_eth_to_strk_success_count(),
+            get_panel_eth_to_strk_outlier_count(),
+            get_panel_eth_to_strk_fallback_count(),
+            get_panel_eth_to_strk_stale_fallback_count(),
             get_panel_l1_gas_price_provider_insufficient_history(),
             get_panel_l1_gas_price_scraper_success_count(),
             get_panel_l1_gas_price_scraper_baselayer_error_count(),
@@ -84,6 +103,7 @@ pub(crate) fn get_l1_gas_price_infra_row() -> Row {
             get_panel_l1_gas_price_provider_remote_valid_msgs_received(),
             get_panel_l1_gas_price_provider_remote_msgs_processed(),
             get_panel_l1_gas_price_provider_remote_client_send_attempts(),
+            get_panel_l1_gas_price_provider_remote_client_circuit_state(),
         ],
     )
 }
@@ -2,6 +2,7 @@ use apollo_infra::metrics::{
     L1_PROVIDER_LOCAL_MSGS_PROCESSED,
     L1_PROVIDER_LOCAL_MSGS_RECEIVED,
     L1_PROVIDER_LOCAL_QUEUE_DEPTH,
+    L1_PROVIDER_REMOTE_CLIENT_CIRCUIT_STATE,
     L1_PROVIDER_REMOTE_CLIENT_SEND_ATTEMPTS,
     L1_PROVIDER_REMOTE_MSGS_PROCESSED,
     L1_PROVIDER_REMOTE_MSGS_RECEIVED,
@@ -36,6 +37,9 @@ fn get_panel_l1_provider_local_queue_depth() -> Panel {
 fn get_panel_l1_provider_remote_client_send_attempts() -> Panel {
     Panel::from_hist(L1_PROVIDER_REMOTE_CLIENT_SEND_ATTEMPTS, PanelType::TimeSeries)
 }
+fn get_panel_l1_provider_remote_client_circuit_state() -> Panel {
+    Panel::from_gauge(L1_PROVIDER_REMOTE_CLIENT_CIRCUIT_STATE, PanelType::TimeSeries)
+}
 fn get_panel_l1_message_scraper_success_count() -> Panel {
     Panel::from_counter(L1_MESSAGE_SCRAPER_SUCCESS_COUNT, PanelType::TimeSeries)
 }
@@ -68,6 +72,7 @@ pub(crate) fn get_l1_provider_infra_row() -> Row {
             get_panel_l1_provider_remote_valid_msgs_received(),
             get_panel_l1_provider_remote_msgs_processed(),
             get_panel_l1_provider_remote_client_send_attempts(),
+            get_panel_l1_provider_remote_client_circuit_state(),
         ],
     )
 }
@@ -2,6 +2,7 @@ use apollo_infra::metrics::{
     MEMPOOL_LOCAL_MSGS_PROCESSED,
     MEMPOOL_LOCAL_MSGS_RECEIVED,
     MEMPOOL_LOCAL_QUEUE_DEPTH,
+    MEMPOOL_REMOTE_CLIENT_CIRCUIT_STATE,
     MEMPOOL_REMOTE_CLIENT_SEND_ATTEMPTS,
     MEMPOOL_REMOTE_MSGS_PROCESSED,
     MEMPOOL_REMOTE_MSGS_RECEIVED,
@@ -45,6 +46,9 @@ fn get_panel_mempool_local_queue_depth() -> Panel {
 fn get_panel_mempool_remote_client_send_attempts() -> Panel {
     Panel::from_hist(MEMPOOL_REMOTE_CLIENT_SEND_ATTEMPTS, PanelType::TimeSeries)
 }
+fn get_panel_mempool_remote_client_circuit_state() -> Panel {
+    Panel::from_gauge(MEMPOOL_REMOTE_CLIENT_CIRCUIT_STATE, PanelType::TimeSeries)
+}
 fn get_panel_mempool_transactions_received() -> Panel {
     Panel::new(
         MEMPOOL_TRANSACTIONS_RECEIVED.get_name(),
@@ -168,6 +172,7 @@ pub(crate) fn get_mempool_infra_row() -> Row {
             get_panel_mempool_remote_valid_msgs_received(),
             get_panel_mempool_remote_msgs_processed(),
             get_panel_mempool_remote_client_send_attempts(),
+            get_panel_mempool_remote_client_circuit_state(),
         ],
     )
 }
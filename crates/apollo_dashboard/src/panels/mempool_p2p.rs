@@ -2,6 +2,7 @@ use apollo_infra::metrics::{
     MEMPOOL_P2P_LOCAL_MSGS_PROCESSED,
     MEMPOOL_P2P_LOCAL_MSGS_RECEIVED,
     MEMPOOL_P2P_LOCAL_QUEUE_DEPTH,
+    MEMPOOL_P2P_REMOTE_CLIENT_CIRCUIT_STATE,
     MEMPOOL_P2P_REMOTE_CLIENT_SEND_ATTEMPTS,
     MEMPOOL_P2P_REMOTE_MSGS_PROCESSED,
     MEMPOOL_P2P_REMOTE_MSGS_RECEIVED,
@@ -9,9 +10,16 @@ use apollo_infra::metrics::{
 };
 use apollo_mempool_p2p::metrics::{
     MEMPOOL_P2P_BROADCASTED_BATCH_SIZE,
+    MEMPOOL_P2P_DUPLICATE_TRANSACTION_DELAY,
     MEMPOOL_P2P_NUM_CONNECTED_PEERS,
+    MEMPOOL_P2P_NUM_DELIVERING_PEERS,
+    MEMPOOL_P2P_NUM_DUPLICATE_TRANSACTIONS,
     MEMPOOL_P2P_NUM_RECEIVED_MESSAGES,
+    MEMPOOL_P2P_NUM_RECEIVED_TRANSACTIONS,
+    MEMPOOL_P2P_NUM_RECOVERED_TRANSACTIONS,
     MEMPOOL_P2P_NUM_SENT_MESSAGES,
+    MEMPOOL_P2P_PEER_DUPLICATE_DELIVERIES,
+    MEMPOOL_P2P_PEER_FIRST_DELIVERIES,
 };
 
 use crate::dashboard::{Panel, PanelType, Row};
@@ -32,6 +40,34 @@ fn get_panel_mempool_p2p_broadcasted_batch_size() -> Panel {
     Panel::from_hist(MEMPOOL_P2P_BROADCASTED_BATCH_SIZE, PanelType::Stat)
 }
 
+fn get_panel_mempool_p2p_num_received_transactions() -> Panel {
+    Panel::from_counter(MEMPOOL_P2P_NUM_RECEIVED_TRANSACTIONS, PanelType::TimeSeries)
+}
+
+fn get_panel_mempool_p2p_num_duplicate_transactions() -> Panel {
+    Panel::from_counter(MEMPOOL_P2P_NUM_DUPLICATE_TRANSACTIONS, PanelType::TimeSeries)
+}
+
+fn get_panel_mempool_p2p_duplicate_transaction_delay() -> Panel {
+    Panel::from_hist(MEMPOOL_P2P_DUPLICATE_TRANSACTION_DELAY, PanelType::TimeSeries)
+}
+
+fn get_panel_mempool_p2p_num_delivering_peers() -> Panel {
+    Panel::from_gauge(MEMPOOL_P2P_NUM_DELIVERING_PEERS, PanelType::Stat)
+}
+
+fn get_panel_mempool_p2p_peer_first_deliveries() -> Panel {
+    Panel::from_hist(MEMPOOL_P2P_PEER_FIRST_DELIVERIES, PanelType::TimeSeries)
+}
+
+fn get_panel_mempool_p2p_peer_duplicate_deliveries() -> Panel {
+    Panel::from_hist(MEMPOOL_P2P_PEER_DUPLICATE_DELIVERIES, PanelType::TimeSeries)
+}
+
+fn get_panel_mempool_p2p_num_recovered_transactions() -> Panel {
+    Panel::from_counter(MEMPOOL_P2P_NUM_RECOVERED_TRANSACTIONS, PanelType::TimeSeries)
+}
+
 fn get_panel_mempool_p2p_local_msgs_received() -> Panel {
     Panel::from_counter(MEMPOOL_P2P_LOCAL_MSGS_RECEIVED, PanelType::TimeSeries)
 }
@@ -59,6 +95,9 @@ fn get_panel_mempool_p2p_local_queue_depth() -> Panel {
 fn get_panel_mempool_p2p_remote_client_send_attempts() -> Panel {
     Panel::from_hist(MEMPOOL_P2P_REMOTE_CLIENT_SEND_ATTEMPTS, PanelType::TimeSeries)
 }
+fn get_panel_mempool_p2p_remote_client_circuit_state() -> Panel {
+    Panel::from_gauge(MEMPOOL_P2P_REMOTE_CLIENT_CIRCUIT_STATE, PanelType::TimeSeries)
+}
 
 pub(crate) fn get_mempool_p2p_row() -> Row {
     Row::new(
@@ -68,6 +107,13 @@ pub(crate) fn get_mempool_p2p_row() -> Row {
             get_panel_mempool_p2p_num_sent_messages(),
             get_panel_mempool_p2p_num_received_messages(),
             get_panel_mempool_p2p_broadcasted_batch_size(),
+            get_panel_mempool_p2p_num_received_transactions(),
+            get_panel_mempool_p2p_num_duplicate_transactions(),
+            get_panel_mempool_p2p_duplicate_transaction_delay(),
+            get_panel_mempool_p2p_num_delivering_peers(),
+            get_panel_mempool_p2p_peer_first_deliveries(),
+            get_panel_mempool_p2p_peer_duplicate_deliveries(),
+            get_panel_mempool_p2p_num_recovered_transactions(),
         ],
     )
 }
@@ -83,6 +129,7 @@ pub(crate) fn get_mempool_p2p_infra_row() -> Row {
             get_panel_mempool_p2p_remote_valid_msgs_received(),
             get_panel_mempool_p2p_remote_msgs_processed(),
             get_panel_mempool_p2p_remote_client_send_attempts(),
+            get_panel_mempool_p2p_remote_client_circuit_state(),
         ],
     )
 }
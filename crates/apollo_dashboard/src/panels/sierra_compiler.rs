@@ -1,13 +1,19 @@
 use apollo_compile_to_casm::metrics::COMPILATION_DURATION;
 use apollo_infra::metrics::{
+    LABEL_NAME_REQUEST,
     SIERRA_COMPILER_LOCAL_MSGS_PROCESSED,
     SIERRA_COMPILER_LOCAL_MSGS_RECEIVED,
     SIERRA_COMPILER_LOCAL_QUEUE_DEPTH,
+    SIERRA_COMPILER_LOCAL_REQUESTS_SHED,
+    SIERRA_COMPILER_LOCAL_REQUEST_QUEUE_DEPTH,
+    SIERRA_COMPILER_LOCAL_REQUEST_QUEUE_TIME,
+    SIERRA_COMPILER_REMOTE_CLIENT_CIRCUIT_STATE,
     SIERRA_COMPILER_REMOTE_CLIENT_SEND_ATTEMPTS,
     SIERRA_COMPILER_REMOTE_MSGS_PROCESSED,
     SIERRA_COMPILER_REMOTE_MSGS_RECEIVED,
     SIERRA_COMPILER_REMOTE_VALID_MSGS_RECEIVED,
 };
+use apollo_metrics::metric_label_filter;
 
 use crate::dashboard::{Panel, PanelType, Row};
 
@@ -29,9 +35,49 @@ fn get_panel_sierra_compiler_remote_msgs_processed() -> Panel {
 fn get_panel_sierra_compiler_local_queue_depth() -> Panel {
     Panel::from_gauge(SIERRA_COMPILER_LOCAL_QUEUE_DEPTH, PanelType::TimeSeries)
 }
+fn get_panel_sierra_compiler_local_request_queue_depth() -> Panel {
+    Panel::new(
+        SIERRA_COMPILER_LOCAL_REQUEST_QUEUE_DEPTH.get_name(),
+        SIERRA_COMPILER_LOCAL_REQUEST_QUEUE_DEPTH.get_description(),
+        vec![format!(
+            "sum by ({}) ({})",
+            LABEL_NAME_REQUEST,
+            SIERRA_COMPILER_LOCAL_REQUEST_QUEUE_DEPTH.get_name_with_filter()
+        )],
+        PanelType::TimeSeries,
+    )
+}
+fn get_panel_sierra_compiler_local_request_queue_time() -> Panel {
+    Panel::new(
+        SIERRA_COMPILER_LOCAL_REQUEST_QUEUE_TIME.get_name(),
+        "The 95th percentile of the time (secs) requests waited to be dispatched, by request",
+        vec![format!(
+            "histogram_quantile(0.95, sum by (le, {}) (rate({}_bucket{}[5m])))",
+            LABEL_NAME_REQUEST,
+            SIERRA_COMPILER_LOCAL_REQUEST_QUEUE_TIME.get_name(),
+            metric_label_filter!()
+        )],
+        PanelType::TimeSeries,
+    )
+}
+fn get_panel_sierra_compiler_local_requests_shed() -> Panel {
+    Panel::new(
+        SIERRA_COMPILER_LOCAL_REQUESTS_SHED.get_name(),
+        SIERRA_COMPILER_LOCAL_REQUESTS_SHED.get_description(),
+        vec![format!(
+            "sum by ({}) (increase({}[5m]))",
+            LABEL_NAME_REQUEST,
+            SIERRA_COMPILER_LOCAL_REQUESTS_SHED.get_name_with_filter()
+        )],
+        PanelType::TimeSeries,
+    )
+}
 fn get_panel_sierra_compiler_remote_client_send_attempts() -> Panel {
     Panel::from_hist(SIERRA_COMPILER_REMOTE_CLIENT_SEND_ATTEMPTS, PanelType::TimeSeries)
 }
+fn get_panel_sierra_compiler_remote_client_circuit_state() -> Panel {
+    Panel::from_gauge(SIERRA_COMPILER_REMOTE_CLIENT_CIRCUIT_STATE, PanelType::TimeSeries)
+}
 
 fn get_panel_compilation_duration() -> Panel {
     Panel::from_hist(COMPILATION_DURATION, PanelType::TimeSeries)
@@ -44,10 +90,14 @@ pub(crate) fn get_sierra_compiler_infra_row() -> Row {
             get_panel_sierra_compiler_local_msgs_received(),
             get_panel_sierra_compiler_local_msgs_processed(),
             get_panel_sierra_compiler_local_queue_depth(),
+            get_panel_sierra_compiler_local_request_queue_depth(),
+            get_panel_sierra_compiler_local_request_queue_time(),
+            get_panel_sierra_compiler_local_requests_shed(),
             get_panel_sierra_compiler_remote_msgs_received(),
             get_panel_sierra_compiler_remote_valid_msgs_received(),
             get_panel_sierra_compiler_remote_msgs_processed(),
             get_panel_sierra_compiler_remote_client_send_attempts(),
+            get_panel_sierra_compiler_remote_client_circuit_state(),
         ],
     )
 }
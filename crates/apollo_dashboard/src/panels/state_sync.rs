@@ -2,12 +2,14 @@ use apollo_infra::metrics::{
     STATE_SYNC_LOCAL_MSGS_PROCESSED,
     STATE_SYNC_LOCAL_MSGS_RECEIVED,
     STATE_SYNC_LOCAL_QUEUE_DEPTH,
+    STATE_SYNC_REMOTE_CLIENT_CIRCUIT_STATE,
     STATE_SYNC_REMOTE_CLIENT_SEND_ATTEMPTS,
     STATE_SYNC_REMOTE_MSGS_PROCESSED,
     STATE_SYNC_REMOTE_MSGS_RECEIVED,
     STATE_SYNC_REMOTE_VALID_MSGS_RECEIVED,
 };
 use apollo_state_sync_metrics::metrics::{
+    CENTRAL_SYNC_BLOCK_VERIFICATION_LATENCY,
     CENTRAL_SYNC_CENTRAL_BLOCK_MARKER,
     P2P_SYNC_NUM_ACTIVE_INBOUND_SESSIONS,
     P2P_SYNC_NUM_ACTIVE_OUTBOUND_SESSIONS,
@@ -43,6 +45,9 @@ fn get_panel_state_sync_local_queue_depth() -> Panel {
 fn get_panel_state_sync_remote_client_send_attempts() -> Panel {
     Panel::from_hist(STATE_SYNC_REMOTE_CLIENT_SEND_ATTEMPTS, PanelType::TimeSeries)
 }
+fn get_panel_state_sync_remote_client_circuit_state() -> Panel {
+    Panel::from_gauge(STATE_SYNC_REMOTE_CLIENT_CIRCUIT_STATE, PanelType::TimeSeries)
+}
 
 fn get_panel_p2p_sync_num_connected_peers() -> Panel {
     Panel::from_gauge(P2P_SYNC_NUM_CONNECTED_PEERS, PanelType::Stat)
@@ -62,6 +67,9 @@ fn get_panel_state_sync_reverted_transactions() -> Panel {
 fn get_panel_central_sync_central_block_marker() -> Panel {
     Panel::from_gauge(CENTRAL_SYNC_CENTRAL_BLOCK_MARKER, PanelType::Stat)
 }
+fn get_panel_central_sync_block_verification_latency() -> Panel {
+    Panel::from_hist(CENTRAL_SYNC_BLOCK_VERIFICATION_LATENCY, PanelType::TimeSeries)
+}
 fn get_panel_state_sync_body_marker() -> Panel {
     Panel::from_gauge(STATE_SYNC_BODY_MARKER, PanelType::Stat)
 }
@@ -86,6 +94,7 @@ pub(crate) fn get_state_sync_row() -> Row {
             get_panel_state_sync_class_manager_marker(),
             get_panel_state_sync_header_marker(),
             get_panel_state_sync_state_marker(),
+            get_panel_central_sync_block_verification_latency(),
         ],
     )
 }
@@ -101,6 +110,7 @@ pub(crate) fn get_state_sync_infra_row() -> Row {
             get_panel_state_sync_remote_valid_msgs_received(),
             get_panel_state_sync_remote_msgs_processed(),
             get_panel_state_sync_remote_client_send_attempts(),
+            get_panel_state_sync_remote_client_circuit_state(),
         ],
     )
 }
@@ -8,6 +8,10 @@ pub(crate) const FIX_BINARY_NAME: &str = "deployment_generator";
 pub struct SecretsConfigOverride {
     #[serde(rename = "base_layer_config.node_url")]
     base_layer_config_node_url: Url,
+    #[serde(
+        rename = "consensus_manager_config.eth_to_strk_oracle_config.additional_url_header_list"
+    )]
+    consensus_manager_config_eth_to_strk_oracle_config_additional_url_header_list: String,
     #[serde(rename = "consensus_manager_config.eth_to_strk_oracle_config.base_url")]
     consensus_manager_config_eth_to_strk_oracle_config_base_url: Url,
     #[serde(rename = "consensus_manager_config.eth_to_strk_oracle_config.headers")]
@@ -43,6 +47,8 @@ impl Default for SecretsConfigOverride {
     fn default() -> Self {
         Self {
             base_layer_config_node_url: Url::parse("https://arbitrary.url.com").unwrap(),
+            consensus_manager_config_eth_to_strk_oracle_config_additional_url_header_list: ""
+                .to_string(),
             consensus_manager_config_eth_to_strk_oracle_config_base_url: Url::parse(
                 "https://arbitrary.eth_to_strk_oracle.url",
             )
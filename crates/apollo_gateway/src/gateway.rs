@@ -35,9 +35,10 @@ use starknet_api::rpc_transaction::{
     RpcTransaction,
 };
 use starknet_api::transaction::fields::ValidResourceBounds;
+use tokio::sync::watch;
 use tracing::{debug, error, info, instrument, warn, Span};
 
-use crate::config::GatewayConfig;
+use crate::config::{GatewayConfig, StatelessTransactionValidatorConfig};
 use crate::errors::{mempool_client_result_to_deprecated_gw_result, GatewayResult};
 use crate::metrics::{register_metrics, GatewayMetricHandle, GATEWAY_ADD_TX_LATENCY};
 use crate::state_reader::StateReaderFactory;
@@ -53,6 +54,10 @@ pub mod gateway_test;
 pub struct Gateway {
     pub config: Arc<GatewayConfig>,
     pub stateless_tx_validator: Arc<StatelessTransactionValidator>,
+    // If set, the stateless validation of each transaction uses the latest config it holds instead
+    // of `stateless_tx_validator`.
+    pub stateless_tx_validator_config_receiver:
+        Option<watch::Receiver<StatelessTransactionValidatorConfig>>,
     pub stateful_tx_validator: Arc<StatefulTransactionValidator>,
     pub state_reader_factory: Arc<dyn StateReaderFactory>,
     pub mempool_client: SharedMempoolClient,
@@ -72,6 +77,7 @@ impl Gateway {
             stateless_tx_validator: Arc::new(StatelessTransactionValidator {
                 config: config.stateless_tx_validator_config.clone(),
             }),
+            stateless_tx_validator_config_receiver: None,
             stateful_tx_validator: Arc::new(StatefulTransactionValidator {
                 config: config.stateful_tx_validator_config.clone(),
             }),
@@ -152,8 +158,14 @@ struct ProcessTxBlockingTask {
 
 impl ProcessTxBlockingTask {
     pub fn new(gateway: &Gateway, tx: RpcTransaction, runtime: tokio::runtime::Handle) -> Self {
+        let stateless_tx_validator = match &gateway.stateless_tx_validator_config_receiver {
+            Some(config_receiver) => {
+                Arc::new(StatelessTransactionValidator { config: config_receiver.borrow().clone() })
+            }
+            None => gateway.stateless_tx_validator.clone(),
+        };
         Self {
-            stateless_tx_validator: gateway.stateless_tx_validator.clone(),
+            stateless_tx_validator,
             stateful_tx_validator: gateway.stateful_tx_validator.clone(),
             state_reader_factory: gateway.state_reader_factory.clone(),
             mempool_client: gateway.mempool_client.clone(),
@@ -294,6 +306,9 @@ pub fn create_gateway(
     mempool_client: SharedMempoolClient,
     class_manager_client: SharedClassManagerClient,
     runtime: tokio::runtime::Handle,
+    stateless_tx_validator_config_receiver: Option<
+        watch::Receiver<StatelessTransactionValidatorConfig>,
+    >,
 ) -> Gateway {
     let state_reader_factory = Arc::new(SyncStateReaderFactory {
         shared_state_sync_client,
@@ -303,7 +318,10 @@ pub fn create_gateway(
     let transaction_converter =
         TransactionConverter::new(class_manager_client, config.chain_info.chain_id.clone());
 
-    Gateway::new(config, state_reader_factory, mempool_client, transaction_converter)
+    Gateway {
+        stateless_tx_validator_config_receiver,
+        ..Gateway::new(config, state_reader_factory, mempool_client, transaction_converter)
+    }
 }
 
 #[async_trait]
@@ -0,0 +1,88 @@
+//! Benchmark module for the remote component wire formats. It compares the serialization and
+//! deserialization of a large request, a declare transaction with its contract class, across the
+//! supported codecs, with and without zstd compression. The throughput is reported over the
+//! encoded size of the request, which varies by wire format.
+//!
+//! Run the benchmarks using `cargo bench --bench apollo_infra`.
+
+use apollo_infra::serde_utils::{WireCodec, WireFormat};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use starknet_api::data_availability::DataAvailabilityMode;
+use starknet_api::rpc_transaction::{
+    RpcDeclareTransaction,
+    RpcDeclareTransactionV3,
+    RpcTransaction,
+};
+use starknet_api::state::SierraContractClass;
+use starknet_types_core::felt::Felt;
+
+const SIERRA_PROGRAM_LENGTH: u64 = 50_000;
+
+const WIRE_FORMATS: [WireFormat; 4] = [
+    WireFormat { codec: WireCodec::Json, zstd: false },
+    WireFormat { codec: WireCodec::Json, zstd: true },
+    WireFormat { codec: WireCodec::Cbor, zstd: false },
+    WireFormat { codec: WireCodec::Cbor, zstd: true },
+];
+
+fn declare_tx() -> RpcTransaction {
+    let contract_class = SierraContractClass {
+        sierra_program: (0..SIERRA_PROGRAM_LENGTH).map(Felt::from).collect(),
+        ..Default::default()
+    };
+    RpcTransaction::Declare(RpcDeclareTransaction::V3(RpcDeclareTransactionV3 {
+        sender_address: Default::default(),
+        compiled_class_hash: Default::default(),
+        signature: Default::default(),
+        nonce: Default::default(),
+        contract_class,
+        resource_bounds: Default::default(),
+        tip: Default::default(),
+        paymaster_data: Default::default(),
+        account_deployment_data: Default::default(),
+        nonce_data_availability_mode: DataAvailabilityMode::L1,
+        fee_data_availability_mode: DataAvailabilityMode::L1,
+    }))
+}
+
+fn format_id(wire_format: &WireFormat) -> String {
+    let compression = if wire_format.zstd { "zstd" } else { "uncompressed" };
+    format!("{:?}/{compression}", wire_format.codec)
+}
+
+fn encoded_size(bytes: &[u8]) -> Throughput {
+    Throughput::Bytes(bytes.len().try_into().unwrap())
+}
+
+fn wire_format_benchmark(criterion: &mut Criterion) {
+    let tx = declare_tx();
+
+    let mut serialize_group = criterion.benchmark_group("serialize");
+    for wire_format in WIRE_FORMATS {
+        let bytes = wire_format.serialize(tx.clone()).unwrap();
+        serialize_group.throughput(encoded_size(&bytes));
+        serialize_group.bench_with_input(
+            BenchmarkId::from_parameter(format_id(&wire_format)),
+            &wire_format,
+            |bencher, wire_format| bencher.iter(|| wire_format.serialize(tx.clone()).unwrap()),
+        );
+    }
+    serialize_group.finish();
+
+    let mut deserialize_group = criterion.benchmark_group("deserialize");
+    for wire_format in WIRE_FORMATS {
+        let bytes = wire_format.serialize(tx.clone()).unwrap();
+        deserialize_group.throughput(encoded_size(&bytes));
+        deserialize_group.bench_with_input(
+            BenchmarkId::from_parameter(format_id(&wire_format)),
+            &bytes,
+            |bencher, bytes| {
+                bencher.iter(|| wire_format.deserialize::<RpcTransaction>(bytes).unwrap())
+            },
+        );
+    }
+    deserialize_group.finish();
+}
+
+criterion_group!(benches, wire_format_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,155 @@
+#[cfg(test)]
+#[path = "circuit_breaker_test.rs"]
+mod circuit_breaker_test;
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+use crate::metrics::RemoteClientMetrics;
+
+/// The state of a remote client's circuit breaker.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests are sent normally.
+    Closed,
+    /// A single probing request is in flight, all other requests fail fast.
+    HalfOpen,
+    /// The remote component is considered unavailable, all requests fail fast.
+    Open,
+}
+
+impl CircuitState {
+    pub fn as_metric_value(self) -> u8 {
+        match self {
+            CircuitState::Closed => 0,
+            CircuitState::HalfOpen => 1,
+            CircuitState::Open => 2,
+        }
+    }
+}
+
+struct CircuitBreakerState {
+    state: CircuitState,
+    consecutive_failures: usize,
+    opened_at: Instant,
+}
+
+/// Tracks the availability of a remote component, shared by all clones of a remote client.
+///
+/// After `failure_threshold` consecutive requests fail to reach the remote component, the circuit
+/// opens and requests fail fast. Once `reset_timeout` passes, a single probing request is let
+/// through: the circuit closes if it reaches the component, and opens again otherwise.
+#[derive(Clone)]
+pub(crate) struct CircuitBreaker {
+    /// Zero disables the circuit breaker.
+    failure_threshold: usize,
+    reset_timeout: Duration,
+    state: Arc<Mutex<CircuitBreakerState>>,
+    metrics: RemoteClientMetrics,
+}
+
+impl CircuitBreaker {
+    pub(crate) fn new(
+        failure_threshold: usize,
+        reset_timeout: Duration,
+        metrics: RemoteClientMetrics,
+    ) -> Self {
+        metrics.set_circuit_state(CircuitState::Closed);
+        Self {
+            failure_threshold,
+            reset_timeout,
+            state: Arc::new(Mutex::new(CircuitBreakerState {
+                state: CircuitState::Closed,
+                consecutive_failures: 0,
+                opened_at: Instant::now(),
+            })),
+            metrics,
+        }
+    }
+
+    /// Returns a permit to send a request, or `None` if the request should fail fast.
+    pub(crate) fn try_acquire(&self) -> Option<CircuitBreakerPermit<'_>> {
+        if self.failure_threshold == 0 {
+            return Some(CircuitBreakerPermit { breaker: self, is_probe: false, recorded: false });
+        }
+        let mut state = self.state.lock().unwrap();
+        let is_probe = match state.state {
+            CircuitState::Closed => false,
+            CircuitState::Open if state.opened_at.elapsed() >= self.reset_timeout => {
+                self.transition(&mut state, CircuitState::HalfOpen);
+                true
+            }
+            CircuitState::Open | CircuitState::HalfOpen => return None,
+        };
+        Some(CircuitBreakerPermit { breaker: self, is_probe, recorded: false })
+    }
+
+    fn record(&self, is_probe: bool, reached_component: bool) {
+        if self.failure_threshold == 0 {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        if reached_component {
+            state.consecutive_failures = 0;
+            self.transition(&mut state, CircuitState::Closed);
+            return;
+        }
+        state.consecutive_failures += 1;
+        let should_open = match state.state {
+            CircuitState::Closed => state.consecutive_failures >= self.failure_threshold,
+            CircuitState::HalfOpen => is_probe,
+            CircuitState::Open => false,
+        };
+        if should_open {
+            state.opened_at = Instant::now();
+            self.transition(&mut state, CircuitState::Open);
+        }
+    }
+
+    fn transition(&self, state: &mut CircuitBreakerState, new_state: CircuitState) {
+        if state.state == new_state {
+            return;
+        }
+        match new_state {
+            CircuitState::Open => warn!(
+                "Opening circuit after {} consecutive failed requests.",
+                state.consecutive_failures
+            ),
+            CircuitState::HalfOpen => info!("Probing the remote component, circuit is half-open."),
+            CircuitState::Closed => info!("Remote component is reachable, closing circuit."),
+        }
+        state.state = new_state;
+        self.metrics.set_circuit_state(new_state);
+    }
+}
+
+/// A permission to send a single request, whose outcome must be recorded.
+pub(crate) struct CircuitBreakerPermit<'a> {
+    breaker: &'a CircuitBreaker,
+    is_probe: bool,
+    recorded: bool,
+}
+
+impl CircuitBreakerPermit<'_> {
+    /// Probing requests are sent only once, without retries.
+    pub(crate) fn is_probe(&self) -> bool {
+        self.is_probe
+    }
+
+    pub(crate) fn record(mut self, reached_component: bool) {
+        self.recorded = true;
+        self.breaker.record(self.is_probe, reached_component);
+    }
+}
+
+impl Drop for CircuitBreakerPermit<'_> {
+    fn drop(&mut self) {
+        // A cancelled probe must not leave the circuit half-open indefinitely.
+        if !self.recorded && self.is_probe {
+            self.breaker.record(true, false);
+        }
+    }
+}
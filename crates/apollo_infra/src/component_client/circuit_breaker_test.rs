@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use super::{CircuitBreaker, CircuitState};
+use crate::tests::TEST_REMOTE_CLIENT_METRICS;
+
+const FAILURE_THRESHOLD: usize = 2;
+const RESET_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn circuit_state(breaker: &CircuitBreaker) -> CircuitState {
+    breaker.state.lock().unwrap().state
+}
+
+/// Returns a circuit breaker whose circuit was just opened.
+fn open_circuit_breaker() -> CircuitBreaker {
+    let breaker = CircuitBreaker::new(FAILURE_THRESHOLD, RESET_TIMEOUT, TEST_REMOTE_CLIENT_METRICS);
+    for _ in 0..FAILURE_THRESHOLD {
+        assert_eq!(circuit_state(&breaker), CircuitState::Closed);
+        breaker.try_acquire().unwrap().record(false);
+    }
+    assert_eq!(circuit_state(&breaker), CircuitState::Open);
+    assert!(breaker.try_acquire().is_none());
+    breaker
+}
+
+#[tokio::test(start_paused = true)]
+async fn successful_probe_closes_circuit() {
+    let breaker = open_circuit_breaker();
+
+    tokio::time::advance(RESET_TIMEOUT).await;
+    let probe = breaker.try_acquire().unwrap();
+    assert!(probe.is_probe());
+    assert_eq!(circuit_state(&breaker), CircuitState::HalfOpen);
+    // Other requests fail fast while the probe is in flight.
+    assert!(breaker.try_acquire().is_none());
+
+    probe.record(true);
+    assert_eq!(circuit_state(&breaker), CircuitState::Closed);
+    assert!(!breaker.try_acquire().unwrap().is_probe());
+}
+
+#[tokio::test(start_paused = true)]
+async fn failed_probe_reopens_circuit() {
+    let breaker = open_circuit_breaker();
+
+    tokio::time::advance(RESET_TIMEOUT).await;
+    breaker.try_acquire().unwrap().record(false);
+    assert_eq!(circuit_state(&breaker), CircuitState::Open);
+
+    // The reset timeout restarts once the circuit reopens.
+    tokio::time::advance(RESET_TIMEOUT / 2).await;
+    assert!(breaker.try_acquire().is_none());
+    tokio::time::advance(RESET_TIMEOUT / 2).await;
+    assert!(breaker.try_acquire().unwrap().is_probe());
+}
+
+#[tokio::test(start_paused = true)]
+async fn cancelled_probe_reopens_circuit() {
+    let breaker = open_circuit_breaker();
+
+    tokio::time::advance(RESET_TIMEOUT).await;
+    let probe = breaker.try_acquire().unwrap();
+    assert!(probe.is_probe());
+    drop(probe);
+    assert_eq!(circuit_state(&breaker), CircuitState::Open);
+
+    tokio::time::advance(RESET_TIMEOUT).await;
+    assert!(breaker.try_acquire().unwrap().is_probe());
+}
@@ -18,6 +18,12 @@ pub enum ClientError {
     ResponseError(StatusCode, ServerError),
     #[error("Got an unexpected response type: {0}")]
     UnexpectedResponse(String),
+    #[error("Circuit breaker is open, the remote component is considered unavailable")]
+    CircuitOpen,
+    #[error("Request deadline exceeded")]
+    DeadlineExceeded,
+    #[error("The server rejected the request as it exceeded its queue time budget")]
+    QueueTimeBudgetExceeded,
 }
 
 pub type ClientResult<T> = Result<T, ClientError>;
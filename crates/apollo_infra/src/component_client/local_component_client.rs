@@ -1,10 +1,16 @@
+use std::time::Instant;
+
 use async_trait::async_trait;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use tokio::sync::mpsc::{channel, Sender};
 
-use crate::component_client::ClientResult;
-use crate::component_definitions::{ComponentClient, ComponentRequestAndResponseSender};
+use crate::component_client::{ClientError, ClientResult};
+use crate::component_definitions::{
+    ComponentClient,
+    ComponentRequestAndResponseSender,
+    RequestRejection,
+};
 
 /// The `LocalComponentClient` struct is a generic client for sending component requests and
 /// receiving responses asynchronously.
@@ -77,6 +83,26 @@ where
     pub fn new(tx: Sender<ComponentRequestAndResponseSender<Request, Response>>) -> Self {
         Self { tx }
     }
+
+    /// Sends a request that the server rejects instead of processing if it's still queued at the
+    /// given deadline.
+    pub async fn send_with_deadline(
+        &self,
+        request: Request,
+        deadline: Option<Instant>,
+    ) -> ClientResult<Response> {
+        let (res_tx, mut res_rx) = channel::<Result<Response, RequestRejection>>(1);
+        let request_and_res_tx =
+            ComponentRequestAndResponseSender { request, tx: res_tx, deadline };
+        self.tx.send(request_and_res_tx).await.expect("Outbound connection should be open.");
+        match res_rx.recv().await.expect("Inbound connection should be open.") {
+            Ok(response) => Ok(response),
+            Err(RequestRejection::DeadlineExceeded) => Err(ClientError::DeadlineExceeded),
+            Err(RequestRejection::QueueTimeBudgetExceeded) => {
+                Err(ClientError::QueueTimeBudgetExceeded)
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -87,10 +113,7 @@ where
     Response: Send + Serialize + DeserializeOwned,
 {
     async fn send(&self, request: Request) -> ClientResult<Response> {
-        let (res_tx, mut res_rx) = channel::<Response>(1);
-        let request_and_res_tx = ComponentRequestAndResponseSender { request, tx: res_tx };
-        self.tx.send(request_and_res_tx).await.expect("Outbound connection should be open.");
-        Ok(res_rx.recv().await.expect("Inbound connection should be open."))
+        self.send_with_deadline(request, None).await
     }
 }
 
@@ -1,7 +1,9 @@
+mod circuit_breaker;
 mod definitions;
 mod local_component_client;
 mod remote_component_client;
 
+pub use circuit_breaker::*;
 pub use definitions::*;
 pub use local_component_client::*;
 pub use remote_component_client::*;
@@ -3,31 +3,37 @@ use std::fmt::Debug;
 use std::marker::PhantomData;
 use std::time::Duration;
 
-use apollo_config::dumping::{ser_param, SerializeConfig};
+use apollo_config::dumping::{ser_optional_param, ser_param, SerializeConfig};
 use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use async_trait::async_trait;
 use hyper::body::to_bytes;
-use hyper::header::CONTENT_TYPE;
+use hyper::header::{ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_TYPE};
 use hyper::{Body, Client, Request as HyperRequest, Response as HyperResponse, StatusCode, Uri};
+use rand::Rng;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
+use tokio::time::Instant;
 use tracing::{debug, trace, warn};
 use validator::Validate;
 
+use super::circuit_breaker::CircuitBreaker;
 use super::definitions::{ClientError, ClientResult};
-use crate::component_definitions::{ComponentClient, ServerError, APPLICATION_OCTET_STREAM};
+use crate::component_definitions::{ComponentClient, ServerError, REQUEST_TIMEOUT_MILLIS_HEADER};
 use crate::metrics::RemoteClientMetrics;
-use crate::serde_utils::SerdeWrapper;
+use crate::serde_utils::{WireCodec, WireFormat};
 
 // TODO(Tsabary): rename all constants to better describe their purpose.
-const DEFAULT_RETRIES: usize = 150;
+// With the default retry intervals, the retries wait 1 + 2 + 4 + 18 * 8 = 151 seconds at most
+// before a request to an unreachable component fails.
+const DEFAULT_RETRIES: usize = 21;
 const DEFAULT_IDLE_CONNECTIONS: usize = 10;
 // TODO(Tsabary): add `_SECS` suffix to the constant names and the config fields.
 const DEFAULT_IDLE_TIMEOUT: u64 = 30;
 const DEFAULT_RETRY_INTERVAL: u64 = 1;
-
-// TODO(Tsabary): consider retry delay mechanisms, e.g., exponential backoff, jitter, etc.
+const DEFAULT_MAX_RETRY_INTERVAL: u64 = 8;
+const DEFAULT_CIRCUIT_BREAKER_FAILURE_THRESHOLD: usize = 3;
+const DEFAULT_CIRCUIT_BREAKER_RESET_TIMEOUT: u64 = 10;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Validate, PartialEq)]
 pub struct RemoteClientConfig {
@@ -35,6 +41,12 @@ pub struct RemoteClientConfig {
     pub idle_connections: usize,
     pub idle_timeout: u64,
     pub retry_interval: u64,
+    pub max_retry_interval: u64,
+    pub request_deadline_millis: Option<u64>,
+    pub circuit_breaker_failure_threshold: usize,
+    pub circuit_breaker_reset_timeout: u64,
+    pub wire_codec: WireCodec,
+    pub zstd_compression: bool,
 }
 
 impl Default for RemoteClientConfig {
@@ -44,17 +56,25 @@ impl Default for RemoteClientConfig {
             idle_connections: DEFAULT_IDLE_CONNECTIONS,
             idle_timeout: DEFAULT_IDLE_TIMEOUT,
             retry_interval: DEFAULT_RETRY_INTERVAL,
+            max_retry_interval: DEFAULT_MAX_RETRY_INTERVAL,
+            request_deadline_millis: None,
+            circuit_breaker_failure_threshold: DEFAULT_CIRCUIT_BREAKER_FAILURE_THRESHOLD,
+            circuit_breaker_reset_timeout: DEFAULT_CIRCUIT_BREAKER_RESET_TIMEOUT,
+            wire_codec: WireCodec::default(),
+            zstd_compression: false,
         }
     }
 }
 
 impl SerializeConfig for RemoteClientConfig {
     fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
-        BTreeMap::from_iter([
+        let mut dump = BTreeMap::from_iter([
             ser_param(
                 "retries",
                 &self.retries,
-                "The max number of retries for sending a message.",
+                "The max number of retries for sending a message. The worst case time to fail is \
+                 the sum of the waits between the retries (see retry_interval), which is about \
+                 2.5 minutes with the default values.",
                 ParamPrivacyInput::Public,
             ),
             ser_param(
@@ -72,10 +92,56 @@ impl SerializeConfig for RemoteClientConfig {
             ser_param(
                 "retry_interval",
                 &self.retry_interval,
-                "The duration in seconds to wait between remote connection retries.",
+                "The duration in seconds to wait before the first remote connection retry. The \
+                 wait doubles after every failed attempt, up to max_retry_interval, and is \
+                 randomly shortened by up to half to spread out retries.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_retry_interval",
+                &self.max_retry_interval,
+                "The maximum duration in seconds to wait between remote connection retries.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "circuit_breaker_failure_threshold",
+                &self.circuit_breaker_failure_threshold,
+                "The number of consecutive requests failing to reach the remote component after \
+                 which requests fail fast, until a probing request reaches it. 0 disables the \
+                 circuit breaker.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "circuit_breaker_reset_timeout",
+                &self.circuit_breaker_reset_timeout,
+                "The duration in seconds requests fail fast for, before probing the remote \
+                 component again.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "wire_codec",
+                &self.wire_codec,
+                "The encoding of requests and responses, Json or Cbor. Cbor requires the remote \
+                 server to support codec negotiation.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "zstd_compression",
+                &self.zstd_compression,
+                "Whether to compress requests and responses with zstd. Requires the remote server \
+                 to support codec negotiation.",
                 ParamPrivacyInput::Public,
             ),
-        ])
+        ]);
+        dump.extend(ser_optional_param(
+            &self.request_deadline_millis,
+            0,
+            "request_deadline_millis",
+            "The time in milliseconds to wait for a response, including retries. The remaining \
+             time is sent to the server, which abandons the request once it passes.",
+            ParamPrivacyInput::Public,
+        ));
+        dump
     }
 }
 
@@ -91,13 +157,14 @@ impl SerializeConfig for RemoteClientConfig {
 /// - `uri`: URI address of the server.
 /// - `client`: The inner HTTP client that initiates the connection to the server and manages it.
 /// - `config`: Client configuration.
+/// - `circuit_breaker`: Fails requests fast while the server is unreachable.
 ///
 /// # Example
 /// ```rust
 /// // Example usage of the RemoteComponentClient
 ///
 /// use apollo_infra::metrics::RemoteClientMetrics;
-/// use apollo_metrics::metrics::{MetricHistogram, MetricScope};
+/// use apollo_metrics::metrics::{MetricGauge, MetricHistogram, MetricScope};
 /// use serde::{Deserialize, Serialize};
 ///
 /// use crate::apollo_infra::component_client::{RemoteClientConfig, RemoteComponentClient};
@@ -125,6 +192,7 @@ impl SerializeConfig for RemoteClientConfig {
 ///         idle_connections: usize::MAX,
 ///         idle_timeout: 90,
 ///         retry_interval: 3,
+///         ..Default::default()
 ///     };
 ///
 ///     const EXAMPLE_HISTOGRAM_METRIC: MetricHistogram = MetricHistogram::new(
@@ -135,7 +203,13 @@ impl SerializeConfig for RemoteClientConfig {
 ///         "example_histogram_metric_count_filter",
 ///         "Example histogram metrics",
 ///     );
-///     let metrics = RemoteClientMetrics::new(&EXAMPLE_HISTOGRAM_METRIC);
+///     const EXAMPLE_GAUGE_METRIC: MetricGauge = MetricGauge::new(
+///         MetricScope::Infra,
+///         "example_gauge_metric",
+///         "example_gauge_metric_filter",
+///         "Example gauge metrics",
+///     );
+///     let metrics = RemoteClientMetrics::new(&EXAMPLE_HISTOGRAM_METRIC, &EXAMPLE_GAUGE_METRIC);
 ///     let client =
 ///         RemoteComponentClient::<MyRequest, MyResponse>::new(config, &url, port, metrics);
 ///
@@ -160,6 +234,7 @@ where
     client: Client<hyper::client::HttpConnector>,
     config: RemoteClientConfig,
     metrics: RemoteClientMetrics,
+    circuit_breaker: CircuitBreaker,
     // [`RemoteComponentClient<Request,Response>`] should be [`Send + Sync`] while [`Request`] and
     // [`Response`] are only [`Send`]. [`Phantom<T>`] is [`Send + Sync`] only if [`T`] is, despite
     // this bound making no sense as the phantom data field is unused. As such, we wrap it as
@@ -187,16 +262,101 @@ where
             .pool_max_idle_per_host(config.idle_connections)
             .pool_idle_timeout(Duration::from_secs(config.idle_timeout))
             .build_http();
+        let circuit_breaker = CircuitBreaker::new(
+            config.circuit_breaker_failure_threshold,
+            Duration::from_secs(config.circuit_breaker_reset_timeout),
+            metrics.clone(),
+        );
         debug!("RemoteComponentClient created with URI: {:?}", uri);
-        Self { uri, client, config, metrics, _req: PhantomData, _res: PhantomData }
+        Self { uri, client, config, metrics, circuit_breaker, _req: PhantomData, _res: PhantomData }
+    }
+
+    fn wire_format(&self) -> WireFormat {
+        WireFormat::new(self.config.wire_codec, self.config.zstd_compression)
     }
 
-    fn construct_http_request(&self, serialized_request: Vec<u8>) -> HyperRequest<Body> {
+    fn construct_http_request(
+        &self,
+        serialized_request: Vec<u8>,
+        timeout: Option<Duration>,
+    ) -> HyperRequest<Body> {
         trace!("Constructing remote request");
-        HyperRequest::post(self.uri.clone())
-            .header(CONTENT_TYPE, APPLICATION_OCTET_STREAM)
-            .body(Body::from(serialized_request))
-            .expect("Request building should succeed")
+        let wire_format = self.wire_format();
+        let mut builder =
+            HyperRequest::post(self.uri.clone()).header(CONTENT_TYPE, wire_format.content_type());
+        if let Some(content_encoding) = wire_format.content_encoding() {
+            builder = builder
+                .header(CONTENT_ENCODING, content_encoding)
+                .header(ACCEPT_ENCODING, content_encoding);
+        }
+        if let Some(timeout) = timeout {
+            builder =
+                builder.header(REQUEST_TIMEOUT_MILLIS_HEADER, timeout.as_millis().to_string());
+        }
+        builder.body(Body::from(serialized_request)).expect("Request building should succeed")
+    }
+
+    /// The wait before the given retry: exponential in the number of failed attempts, capped, and
+    /// randomly shortened by up to half.
+    fn retry_delay(&self, failed_attempts: usize) -> Duration {
+        let exponent = u32::try_from(failed_attempts.saturating_sub(1)).unwrap_or(u32::MAX);
+        let delay = Duration::from_secs(
+            self.config
+                .retry_interval
+                .saturating_mul(2_u64.saturating_pow(exponent))
+                .min(self.config.max_retry_interval.max(self.config.retry_interval)),
+        );
+        delay.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+    }
+
+    async fn send_with_retries(
+        &self,
+        serialized_request: Vec<u8>,
+        max_attempts: usize,
+    ) -> ClientResult<Response> {
+        let deadline = self
+            .config
+            .request_deadline_millis
+            .map(|deadline_millis| Instant::now() + Duration::from_millis(deadline_millis));
+
+        trace!("Starting retry loop: max_attempts = {:?}", max_attempts);
+        for attempt in 1..max_attempts + 1 {
+            trace!("Attempt {} of {:?}", attempt, max_attempts);
+            let timeout =
+                deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+            let http_request = self.construct_http_request(serialized_request.clone(), timeout);
+            let res = match timeout {
+                Some(timeout) => tokio::time::timeout(timeout, self.try_send(http_request))
+                    .await
+                    .unwrap_or(Err(ClientError::DeadlineExceeded)),
+                None => self.try_send(http_request).await,
+            };
+            if res.is_ok() {
+                trace!("Request successful on attempt {}/{}", attempt, max_attempts);
+                self.metrics.record_attempt(attempt);
+                return res;
+            }
+            warn!("Request failed on attempt {}/{}: {:?}", attempt, max_attempts, res);
+            // Retrying a shed request would add load to an already saturated server.
+            if attempt == max_attempts
+                || matches!(
+                    res,
+                    Err(ClientError::DeadlineExceeded | ClientError::QueueTimeBudgetExceeded)
+                )
+            {
+                self.metrics.record_attempt(attempt);
+                return res;
+            }
+            let delay = self.retry_delay(attempt);
+            match deadline {
+                Some(deadline) if Instant::now() + delay >= deadline => {
+                    self.metrics.record_attempt(attempt);
+                    return Err(ClientError::DeadlineExceeded);
+                }
+                _ => tokio::time::sleep(delay).await,
+            }
+        }
+        unreachable!("Guaranteed to return a response before reaching this point.");
     }
 
     async fn try_send(&self, http_request: HyperRequest<Body>) -> ClientResult<Response> {
@@ -212,6 +372,14 @@ where
                 trace!("Successfully deserialized response");
                 response_body
             }
+            StatusCode::REQUEST_TIMEOUT => {
+                warn!("Server abandoned the request since its deadline passed.");
+                Err(ClientError::DeadlineExceeded)
+            }
+            StatusCode::SERVICE_UNAVAILABLE => {
+                warn!("Server shed the request since it exceeded its queue time budget.");
+                Err(ClientError::QueueTimeBudgetExceeded)
+            }
             status_code => {
                 warn!(
                     "Unexpected response status: {:?}. Unable to deserialize response.",
@@ -237,31 +405,26 @@ where
 {
     async fn send(&self, component_request: Request) -> ClientResult<Response> {
         // Serialize the request.
-        let serialized_request = SerdeWrapper::new(component_request)
-            .wrapper_serialize()
+        let serialized_request = self
+            .wire_format()
+            .serialize(component_request)
             .expect("Request serialization should succeed");
 
-        // Construct the request, and send it up to 'max_retries + 1' times. Return if received a
-        // successful response, or the last response if all attempts failed.
-        let max_attempts = self.config.retries + 1;
-        trace!("Starting retry loop: max_attempts = {:?}", max_attempts);
-        for attempt in 1..max_attempts + 1 {
-            trace!("Attempt {} of {:?}", attempt, max_attempts);
-            let http_request = self.construct_http_request(serialized_request.clone());
-            let res = self.try_send(http_request).await;
-            if res.is_ok() {
-                trace!("Request successful on attempt {}/{}", attempt, max_attempts);
-                self.metrics.record_attempt(attempt);
-                return res;
-            }
-            warn!("Request failed on attempt {}/{}: {:?}", attempt, max_attempts, res);
-            if attempt == max_attempts {
-                self.metrics.record_attempt(attempt);
-                return res;
-            }
-            tokio::time::sleep(Duration::from_secs(self.config.retry_interval)).await;
-        }
-        unreachable!("Guaranteed to return a response before reaching this point.");
+        let Some(permit) = self.circuit_breaker.try_acquire() else {
+            trace!("Circuit is open, failing fast");
+            return Err(ClientError::CircuitOpen);
+        };
+
+        // Construct the request, and send it up to 'max_retries + 1' times, within the deadline.
+        // Return if received a successful response, or the last response if all attempts failed.
+        let max_attempts = if permit.is_probe() { 1 } else { self.config.retries + 1 };
+        let res = self.send_with_retries(serialized_request, max_attempts).await;
+        // Only failing to communicate with the server indicates it is unavailable.
+        permit.record(!matches!(
+            res,
+            Err(ClientError::CommunicationFailure(_) | ClientError::DeadlineExceeded)
+        ));
+        res
     }
 }
 
@@ -269,11 +432,14 @@ async fn get_response_body<Response>(response: HyperResponse<Body>) -> Result<Re
 where
     Response: Serialize + DeserializeOwned + Debug,
 {
+    let wire_format = WireFormat::from_headers(response.headers())
+        .map_err(|err| ClientError::ResponseParsingFailure(err.to_string()))?;
     let body_bytes = to_bytes(response.into_body())
         .await
         .map_err(|err| ClientError::ResponseParsingFailure(err.to_string()))?;
 
-    SerdeWrapper::<Response>::wrapper_deserialize(&body_bytes)
+    wire_format
+        .deserialize(&body_bytes)
         .map_err(|err| ClientError::ResponseDeserializationFailure(err.to_string()))
 }
 
@@ -290,6 +456,7 @@ where
             client: self.client.clone(),
             config: self.config.clone(),
             metrics: self.metrics.clone(),
+            circuit_breaker: self.circuit_breaker.clone(),
             _req: PhantomData,
             _res: PhantomData,
         }
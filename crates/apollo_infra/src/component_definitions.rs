@@ -1,4 +1,5 @@
 use std::fmt::{Debug, Formatter, Result};
+use std::time::Instant;
 
 use apollo_infra_utils::type_name::short_type_name;
 use async_trait::async_trait;
@@ -11,12 +12,43 @@ use tracing::{error, info};
 use crate::component_client::ClientResult;
 
 pub(crate) const APPLICATION_OCTET_STREAM: &str = "application/octet-stream";
+pub(crate) const APPLICATION_CBOR: &str = "application/cbor";
+pub(crate) const ZSTD_ENCODING: &str = "zstd";
+/// The time, in milliseconds, the client is willing to wait for the response to a remote request.
+pub(crate) const REQUEST_TIMEOUT_MILLIS_HEADER: &str = "x-request-timeout-millis";
 
 #[async_trait]
 pub trait ComponentRequestHandler<Request, Response> {
     async fn handle_request(&mut self, request: Request) -> Response;
 }
 
+/// The scheduling class of a request. Requests queued by a busy local server are dispatched in
+/// descending priority, and in arrival order within a class.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RequestPriority {
+    /// Bulk requests, dispatched only when no other requests are queued.
+    Low,
+    #[default]
+    Normal,
+    /// Latency critical requests, e.g., on the consensus path. These are never shed.
+    High,
+}
+
+/// Requests handled by a local server, which schedules them by their priority.
+pub trait PrioritizedRequest {
+    fn priority(&self) -> RequestPriority {
+        RequestPriority::Normal
+    }
+}
+
+/// Requests handled by a concurrent local server, which measures them by their label.
+pub trait LabeledRequest {
+    /// The labels of all the request types, used to register the per-request metrics.
+    const LABELS: &'static [&'static str];
+
+    fn label(&self) -> &'static str;
+}
+
 #[async_trait]
 pub trait ComponentClient<Request, Response>
 where
@@ -83,11 +115,38 @@ where
     Response: Send,
 {
     pub request: Request,
-    pub tx: Sender<Response>,
+    pub tx: Sender<std::result::Result<Response, RequestRejection>>,
+    /// The time after which the client no longer waits for the response. Servers reject requests
+    /// that are still queued past it instead of processing them.
+    pub deadline: Option<Instant>,
+}
+
+impl<Request, Response> ComponentRequestAndResponseSender<Request, Response>
+where
+    Request: Send,
+    Response: Send,
+{
+    /// Responds to the request with a rejection instead of processing it.
+    pub(crate) fn reject(self, rejection: RequestRejection) {
+        // Nothing else is sent on the response channel, so it has room for the rejection, unless
+        // the client already stopped waiting for it.
+        let _ = self.tx.try_send(Err(rejection));
+    }
+}
+
+/// The reason a local server rejected a request without processing it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RequestRejection {
+    DeadlineExceeded,
+    QueueTimeBudgetExceeded,
 }
 
 #[derive(Debug, Error, Deserialize, Serialize, Clone, PartialEq, Eq)]
 pub enum ServerError {
     #[error("Could not deserialize client request: {0}")]
     RequestDeserializationFailure(String),
+    #[error("Request deadline exceeded")]
+    RequestDeadlineExceeded,
+    #[error("Request queue time budget exceeded")]
+    QueueTimeBudgetExceeded,
 }
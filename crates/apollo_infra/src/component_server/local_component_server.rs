@@ -1,12 +1,14 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, VecDeque};
 use std::fmt::Debug;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use apollo_config::dumping::{ser_param, SerializeConfig};
+use apollo_config::dumping::{ser_optional_param, ser_param, SerializeConfig};
 use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use apollo_infra_utils::type_name::short_type_name;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::error::TryRecvError;
 use tokio::sync::mpsc::{Receiver, Sender};
 use tokio::sync::Semaphore;
 use tracing::{error, info, trace, warn};
@@ -16,9 +18,13 @@ use crate::component_definitions::{
     ComponentRequestAndResponseSender,
     ComponentRequestHandler,
     ComponentStarter,
+    LabeledRequest,
+    PrioritizedRequest,
+    RequestPriority,
+    RequestRejection,
 };
 use crate::component_server::ComponentServerStarter;
-use crate::metrics::LocalServerMetrics;
+use crate::metrics::{LocalServerMetrics, LocalServerQueueMetrics};
 
 const DEFAULT_CHANNEL_CAPACITY: usize = 128;
 
@@ -26,22 +32,58 @@ const DEFAULT_CHANNEL_CAPACITY: usize = 128;
 #[derive(Clone, Debug, Serialize, Deserialize, Validate, PartialEq)]
 pub struct LocalServerConfig {
     pub channel_capacity: usize,
+    pub normal_priority_queue_time_budget_millis: Option<u64>,
+    pub low_priority_queue_time_budget_millis: Option<u64>,
+}
+
+impl LocalServerConfig {
+    /// The time a request of the given priority may wait to be dispatched before it is shed. High
+    /// priority requests are never shed.
+    fn queue_time_budget(&self, priority: RequestPriority) -> Option<Duration> {
+        let budget_millis = match priority {
+            RequestPriority::High => None,
+            RequestPriority::Normal => self.normal_priority_queue_time_budget_millis,
+            RequestPriority::Low => self.low_priority_queue_time_budget_millis,
+        };
+        budget_millis.map(Duration::from_millis)
+    }
 }
 
 impl SerializeConfig for LocalServerConfig {
     fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
-        BTreeMap::from_iter([ser_param(
+        let mut dump = BTreeMap::from_iter([ser_param(
             "channel_capacity",
             &self.channel_capacity,
             "The communication channel buffer size.",
             ParamPrivacyInput::Public,
-        )])
+        )]);
+        dump.extend(ser_optional_param(
+            &self.normal_priority_queue_time_budget_millis,
+            0,
+            "normal_priority_queue_time_budget_millis",
+            "The time in milliseconds a normal priority request may wait to be dispatched before \
+             it is rejected. Only applies to concurrent local servers.",
+            ParamPrivacyInput::Public,
+        ));
+        dump.extend(ser_optional_param(
+            &self.low_priority_queue_time_budget_millis,
+            0,
+            "low_priority_queue_time_budget_millis",
+            "The time in milliseconds a low priority request may wait to be dispatched before it \
+             is rejected. Only applies to concurrent local servers.",
+            ParamPrivacyInput::Public,
+        ));
+        dump
     }
 }
 
 impl Default for LocalServerConfig {
     fn default() -> Self {
-        Self { channel_capacity: DEFAULT_CHANNEL_CAPACITY }
+        Self {
+            channel_capacity: DEFAULT_CHANNEL_CAPACITY,
+            normal_priority_queue_time_budget_millis: None,
+            low_priority_queue_time_budget_millis: None,
+        }
     }
 }
 
@@ -50,13 +92,18 @@ impl Default for LocalServerConfig {
 /// sends back responses. The server needs to be started using the `start` function, which runs
 /// indefinitely.
 ///
+/// Requests are processed one at a time. Waiting requests are processed by their priority, see
+/// [`RequestPriority`], and in arrival order within a priority. Queue time budgets apply only to
+/// [`ConcurrentLocalComponentServer`], but requests still waiting once their client's deadline
+/// passed are rejected.
+///
 /// # Type Parameters
 ///
 /// - `Component`: The type of the component that will handle the requests. This type must implement
 ///   the `ComponentRequestHandler` trait, which defines how the component processes requests and
 ///   generates responses.
 /// - `Request`: The type of requests that the component will handle. This type must implement the
-///   `Send` trait to ensure safe concurrency.
+///   `Send` trait to ensure safe concurrency, and the `PrioritizedRequest` trait to be scheduled.
 /// - `Response`: The type of responses that the component will generate. This type must implement
 ///   the `Send` trait to ensure safe concurrency.
 ///
@@ -110,7 +157,7 @@ impl<Component, Request, Response> ComponentServerStarter
     for LocalComponentServer<Component, Request, Response>
 where
     Component: ComponentRequestHandler<Request, Response> + Send + ComponentStarter,
-    Request: Send + Debug,
+    Request: Send + Debug + PrioritizedRequest,
     Response: Send + Debug,
 {
     async fn start(&mut self) {
@@ -127,19 +174,64 @@ async fn request_response_loop<Request, Response, Component>(
     metrics: &LocalServerMetrics,
 ) where
     Component: ComponentRequestHandler<Request, Response> + Send,
-    Request: Send + Debug,
+    Request: Send + Debug + PrioritizedRequest,
     Response: Send + Debug,
 {
     info!("Starting server for component {}", short_type_name::<Component>());
 
-    while let Some(request_and_res_tx) = rx.recv().await {
-        let request = request_and_res_tx.request;
-        let tx = request_and_res_tx.tx;
-        trace!("Component {} received request {:?}", short_type_name::<Component>(), request);
+    // The received requests waiting to be processed, by priority.
+    let mut queues: BTreeMap<RequestPriority, VecDeque<_>> = BTreeMap::new();
+    let mut queue_len = 0;
+    let mut receiving = true;
+
+    while receiving || queue_len > 0 {
+        // Collect the arrived requests, so that each request is picked among all the waiting ones.
+        // Stop collecting once the queue is full, to keep the backpressure on the clients.
+        while receiving && queue_len < rx.max_capacity() {
+            let received = if queue_len == 0 {
+                rx.recv().await
+            } else {
+                match rx.try_recv() {
+                    Ok(request_and_res_tx) => Some(request_and_res_tx),
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => None,
+                }
+            };
+            let Some(request_and_res_tx) = received else {
+                receiving = false;
+                break;
+            };
+            trace!(
+                "Component {} received request {:?}",
+                short_type_name::<Component>(),
+                request_and_res_tx.request
+            );
 
+            queues
+                .entry(request_and_res_tx.request.priority())
+                .or_default()
+                .push_back(request_and_res_tx);
+            queue_len += 1;
+        }
+
+        let Some(request_and_res_tx) = queues.values_mut().rev().find_map(VecDeque::pop_front)
+        else {
+            continue;
+        };
+        queue_len -= 1;
         metrics.increment_received();
-        metrics.set_queue_depth(rx.len());
+        metrics.set_queue_depth(queue_len + rx.len());
+
+        if request_and_res_tx.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            warn!(
+                "Rejecting request {:?}, as its client's deadline passed.",
+                request_and_res_tx.request
+            );
+            request_and_res_tx.reject(RequestRejection::DeadlineExceeded);
+            continue;
+        }
 
+        let ComponentRequestAndResponseSender { request, tx, .. } = request_and_res_tx;
         process_request(component, request, tx).await;
 
         metrics.increment_processed();
@@ -153,6 +245,10 @@ async fn request_response_loop<Request, Response, Component>(
 /// running the provided component in a task, with returning response back form the task. The server
 /// needs to be started using the `start` function, which runs indefinitely.
 ///
+/// Once all the tasks are busy, requests are queued and dispatched by their priority, see
+/// [`RequestPriority`]. Queued requests exceeding their queue time budget are rejected instead of
+/// being processed, as their clients are likely to have given up on them.
+///
 /// # Type Parameters
 ///
 /// - `Component`: The type of the component that will handle the requests. This type must implement
@@ -160,7 +256,8 @@ async fn request_response_loop<Request, Response, Component>(
 ///   generates responses. In order to handle concurrent requests, the component must also implement
 ///   the `Clone` trait and the `Send`.
 /// - `Request`: The type of requests that the component will handle. This type must implement the
-///   `Send` trait to ensure safe concurrency.
+///   `Send` trait to ensure safe concurrency, the `PrioritizedRequest` trait to be scheduled, and
+///   the `LabeledRequest` trait to be measured.
 /// - `Response`: The type of responses that the component will generate. This type must implement
 ///   the `Send` trait to ensure safe concurrency.
 ///
@@ -170,8 +267,10 @@ async fn request_response_loop<Request, Response, Component>(
 /// - `rx`: A receiver that receives incoming requests along with a sender to send back the
 ///   responses. This receiver is of type ` Receiver<ComponentRequestAndResponseSender<Request,
 ///   Response>>`.
+/// - `config`: The configuration of the server, including the queue time budgets.
 /// - `max_concurrency`: The maximum number of concurrent requests that the server can handle.
 /// - `metrics`: The metrics for the server wrapped in Arc so it could be used concurrently.
+/// - `queue_metrics`: The per-request metrics of the server's scheduling queue.
 pub struct ConcurrentLocalComponentServer<Component, Request, Response>
 where
     Component: ComponentRequestHandler<Request, Response>,
@@ -180,24 +279,29 @@ where
 {
     component: Component,
     rx: Receiver<ComponentRequestAndResponseSender<Request, Response>>,
+    config: LocalServerConfig,
     max_concurrency: usize,
     metrics: Arc<LocalServerMetrics>,
+    queue_metrics: LocalServerQueueMetrics,
 }
 
 impl<Component, Request, Response> ConcurrentLocalComponentServer<Component, Request, Response>
 where
     Component: ComponentRequestHandler<Request, Response>,
-    Request: Send,
+    Request: Send + PrioritizedRequest + LabeledRequest,
     Response: Send,
 {
     pub fn new(
         component: Component,
         rx: Receiver<ComponentRequestAndResponseSender<Request, Response>>,
+        config: LocalServerConfig,
         max_concurrency: usize,
         metrics: LocalServerMetrics,
+        queue_metrics: LocalServerQueueMetrics,
     ) -> Self {
         metrics.register();
-        Self { component, rx, max_concurrency, metrics: Arc::new(metrics) }
+        queue_metrics.register::<Request>();
+        Self { component, rx, config, max_concurrency, metrics: Arc::new(metrics), queue_metrics }
     }
 }
 
@@ -220,7 +324,7 @@ impl<Component, Request, Response> ComponentServerStarter
 where
     Component:
         ComponentRequestHandler<Request, Response> + ComponentStarter + Clone + Send + 'static,
-    Request: Send + Debug + 'static,
+    Request: Send + Debug + PrioritizedRequest + LabeledRequest + 'static,
     Response: Send + Debug + 'static,
 {
     async fn start(&mut self) {
@@ -229,50 +333,167 @@ where
         concurrent_request_response_loop(
             &mut self.rx,
             &mut self.component,
+            &self.config,
             self.max_concurrency,
             self.metrics.clone(),
+            &self.queue_metrics,
         )
         .await;
         panic!("Finished ConcurrentLocalComponentServer for {}.", short_type_name::<Component>());
     }
 }
 
+/// A request waiting to be dispatched by a concurrent local server.
+struct QueuedRequest<Request, Response>
+where
+    Request: Send,
+    Response: Send,
+{
+    request_and_res_tx: ComponentRequestAndResponseSender<Request, Response>,
+    label: &'static str,
+    enqueued_at: Instant,
+    queue_time_budget: Option<Duration>,
+}
+
+/// The requests waiting to be dispatched by a concurrent local server, by priority.
+struct RequestQueue<Request, Response>
+where
+    Request: Send,
+    Response: Send,
+{
+    queues: BTreeMap<RequestPriority, VecDeque<QueuedRequest<Request, Response>>>,
+    len: usize,
+}
+
+impl<Request, Response> RequestQueue<Request, Response>
+where
+    Request: Send + PrioritizedRequest + LabeledRequest,
+    Response: Send,
+{
+    fn new() -> Self {
+        Self { queues: BTreeMap::new(), len: 0 }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn push(
+        &mut self,
+        request_and_res_tx: ComponentRequestAndResponseSender<Request, Response>,
+        config: &LocalServerConfig,
+        queue_metrics: &LocalServerQueueMetrics,
+    ) {
+        let label = request_and_res_tx.request.label();
+        let priority = request_and_res_tx.request.priority();
+        queue_metrics.increment_queue_depth(label);
+        self.queues.entry(priority).or_default().push_back(QueuedRequest {
+            request_and_res_tx,
+            label,
+            enqueued_at: Instant::now(),
+            queue_time_budget: config.queue_time_budget(priority),
+        });
+        self.len += 1;
+    }
+
+    /// Pops the earliest request of the highest priority, rejecting the requests that exceeded
+    /// their queue time budget or their client's deadline on the way.
+    fn pop(
+        &mut self,
+        queue_metrics: &LocalServerQueueMetrics,
+    ) -> Option<ComponentRequestAndResponseSender<Request, Response>> {
+        for queue in self.queues.values_mut().rev() {
+            while let Some(queued_request) = queue.pop_front() {
+                self.len -= 1;
+                let QueuedRequest { request_and_res_tx, label, enqueued_at, queue_time_budget } =
+                    queued_request;
+                queue_metrics.decrement_queue_depth(label);
+                let queue_time = enqueued_at.elapsed();
+                if queue_time_budget.is_some_and(|budget| queue_time > budget) {
+                    warn!("Rejecting request {label} after waiting {queue_time:?} in queue.");
+                    queue_metrics.increment_shed(label);
+                    request_and_res_tx.reject(RequestRejection::QueueTimeBudgetExceeded);
+                    continue;
+                }
+                if request_and_res_tx.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                    warn!("Rejecting request {label}, as its client's deadline passed.");
+                    queue_metrics.increment_shed(label);
+                    request_and_res_tx.reject(RequestRejection::DeadlineExceeded);
+                    continue;
+                }
+                queue_metrics.record_queue_time(label, queue_time.as_secs_f64());
+                return Some(request_and_res_tx);
+            }
+        }
+        None
+    }
+}
+
 // TODO(Itay): clean some code duplications here.
 async fn concurrent_request_response_loop<Request, Response, Component>(
     rx: &mut Receiver<ComponentRequestAndResponseSender<Request, Response>>,
     component: &mut Component,
+    config: &LocalServerConfig,
     max_concurrency: usize,
     metrics: Arc<LocalServerMetrics>,
+    queue_metrics: &LocalServerQueueMetrics,
 ) where
     Component: ComponentRequestHandler<Request, Response> + Clone + Send + 'static,
-    Request: Send + Debug + 'static,
+    Request: Send + Debug + PrioritizedRequest + LabeledRequest + 'static,
     Response: Send + Debug + 'static,
 {
     info!("Starting concurrent server for component {}", short_type_name::<Component>());
 
     let task_limiter = Arc::new(Semaphore::new(max_concurrency));
+    let mut queue = RequestQueue::new();
+    let mut receiving = true;
 
-    while let Some(request_and_res_tx) = rx.recv().await {
-        let request = request_and_res_tx.request;
-        let tx = request_and_res_tx.tx;
-        trace!("Component {} received request {:?}", short_type_name::<Component>(), request);
+    while receiving || !queue.is_empty() {
+        tokio::select! {
+            // Prefer collecting the arrived requests, so that each dispatch considers all the
+            // waiting requests. Stop collecting once the queue is full, to keep the backpressure on
+            // the clients.
+            biased;
+            request_and_res_tx = rx.recv(), if receiving && queue.len() < config.channel_capacity => {
+                let Some(request_and_res_tx) = request_and_res_tx else {
+                    receiving = false;
+                    continue;
+                };
+                trace!(
+                    "Component {} received request {:?}",
+                    short_type_name::<Component>(),
+                    request_and_res_tx.request
+                );
 
-        metrics.increment_received();
-        metrics.set_queue_depth(rx.len());
+                metrics.increment_received();
+                queue.push(request_and_res_tx, config, queue_metrics);
+                metrics.set_queue_depth(queue.len() + rx.len());
+            }
+            // Acquire a permit to run the task.
+            permit = task_limiter.clone().acquire_owned(), if !queue.is_empty() => {
+                let permit = permit.unwrap();
+                let popped = queue.pop(queue_metrics);
+                metrics.set_queue_depth(queue.len() + rx.len());
+                let Some(ComponentRequestAndResponseSender { request, tx, .. }) = popped else {
+                    continue;
+                };
 
-        // Acquire a permit to run the task.
-        let permit = task_limiter.clone().acquire_owned().await.unwrap();
+                let mut cloned_component = component.clone();
+                let cloned_metrics = metrics.clone();
+                tokio::spawn(async move {
+                    process_request(&mut cloned_component, request, tx).await;
 
-        let mut cloned_component = component.clone();
-        let cloned_metrics = metrics.clone();
-        tokio::spawn(async move {
-            process_request(&mut cloned_component, request, tx).await;
+                    cloned_metrics.increment_processed();
 
-            cloned_metrics.increment_processed();
-
-            // Drop the permit to allow more tasks to be created.
-            drop(permit);
-        });
+                    // Drop the permit to allow more tasks to be created.
+                    drop(permit);
+                });
+            }
+        }
     }
 
     error!("Stopping concurrent server for component {}", short_type_name::<Component>());
@@ -281,7 +502,7 @@ async fn concurrent_request_response_loop<Request, Response, Component>(
 async fn process_request<Request, Response, Component>(
     component: &mut Component,
     request: Request,
-    tx: Sender<Response>,
+    tx: Sender<Result<Response, RequestRejection>>,
 ) where
     Component: ComponentRequestHandler<Request, Response> + Send,
     Request: Send + Debug,
@@ -292,5 +513,5 @@ async fn process_request<Request, Response, Component>(
 
     // Send the response to the client. This might result in a panic if the client has closed
     // the response channel, which is considered a bug.
-    tx.send(response).await.expect("Response connection should be open.");
+    tx.send(Ok(response)).await.expect("Response connection should be open.");
 }
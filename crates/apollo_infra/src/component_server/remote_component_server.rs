@@ -1,11 +1,12 @@
 use std::fmt::Debug;
 use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use apollo_infra_utils::type_name::short_type_name;
 use async_trait::async_trait;
 use hyper::body::to_bytes;
-use hyper::header::CONTENT_TYPE;
+use hyper::header::{CONTENT_ENCODING, CONTENT_TYPE};
 use hyper::service::{make_service_fn, service_fn};
 use hyper::{Body, Request as HyperRequest, Response as HyperResponse, Server, StatusCode};
 use serde::de::DeserializeOwned;
@@ -15,10 +16,10 @@ use tower::ServiceBuilder;
 use tracing::{debug, error, trace, warn};
 
 use crate::component_client::{ClientError, LocalComponentClient};
-use crate::component_definitions::{ComponentClient, ServerError, APPLICATION_OCTET_STREAM};
+use crate::component_definitions::{ServerError, REQUEST_TIMEOUT_MILLIS_HEADER};
 use crate::component_server::ComponentServerStarter;
 use crate::metrics::RemoteServerMetrics;
-use crate::serde_utils::SerdeWrapper;
+use crate::serde_utils::{SerdeWrapper, WireFormat};
 
 /// The `RemoteComponentServer` struct is a generic server that handles requests and responses for a
 /// specified component. It receives requests, processes them using the provided component, and
@@ -167,39 +168,88 @@ where
         metrics: Arc<RemoteServerMetrics>,
     ) -> Result<HyperResponse<Body>, hyper::Error> {
         trace!("Received HTTP request: {:?}", http_request);
-        let body_bytes = to_bytes(http_request.into_body()).await?;
+        let (parts, body) = http_request.into_parts();
+        let timeout = parts
+            .headers
+            .get(REQUEST_TIMEOUT_MILLIS_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse().ok())
+            .map(Duration::from_millis);
+        let body_bytes = to_bytes(body).await?;
         trace!("Extracted {} bytes from HTTP request body", body_bytes.len());
 
         metrics.increment_total_received();
 
-        let http_response = match SerdeWrapper::<Request>::wrapper_deserialize(&body_bytes)
+        // Respond in the format the request was sent in, which clients predating codec negotiation
+        // send and expect by default.
+        let http_response = match WireFormat::from_headers(&parts.headers)
+            .and_then(|request_format| {
+                let request = request_format.deserialize::<Request>(&body_bytes)?;
+                Ok((request, request_format.response_format(&parts.headers)))
+            })
             .map_err(|err| ClientError::ResponseDeserializationFailure(err.to_string()))
         {
-            Ok(request) => {
+            Ok((request, response_format)) => {
                 debug!("Successfully deserialized request: {:?}", request);
                 metrics.increment_valid_received();
 
+                // The local server rejects the request if it's still queued once the client's
+                // deadline passes, as the client has already given up on it.
+                let deadline = timeout.map(|timeout| Instant::now() + timeout);
                 // Wrap the send operation in a tokio::spawn as it is NOT a cancel-safe operation.
                 // Even if the current task is cancelled, the inner task will continue to run.
-                let response = tokio::spawn(async move { local_client.send(request).await })
-                    .await
-                    .expect("Should be able to extract value from the task");
-
-                metrics.increment_processed();
+                let handle = tokio::spawn(async move {
+                    local_client.send_with_deadline(request, deadline).await
+                });
+                // Stop waiting for the response once the deadline passes. A request that is
+                // already being processed by then is completed by the spawned task.
+                let response = match timeout {
+                    Some(timeout) => tokio::time::timeout(timeout, handle).await.ok(),
+                    None => Some(handle.await),
+                }
+                .map(|response| response.expect("Should be able to extract value from the task"));
 
                 match response {
-                    Ok(response) => {
+                    None | Some(Err(ClientError::DeadlineExceeded)) => {
+                        warn!("Request deadline exceeded, abandoning the request.");
+                        let server_error = ServerError::RequestDeadlineExceeded;
+                        HyperResponse::builder().status(StatusCode::REQUEST_TIMEOUT).body(
+                            Body::from(
+                                SerdeWrapper::new(server_error)
+                                    .wrapper_serialize()
+                                    .expect("Server error serialization should succeed"),
+                            ),
+                        )
+                    }
+                    Some(Ok(response)) => {
                         debug!("Local client processed request successfully: {:?}", response);
-                        HyperResponse::builder()
+                        metrics.increment_processed();
+                        let mut builder = HyperResponse::builder()
                             .status(StatusCode::OK)
-                            .header(CONTENT_TYPE, APPLICATION_OCTET_STREAM)
-                            .body(Body::from(
-                                SerdeWrapper::new(response)
+                            .header(CONTENT_TYPE, response_format.content_type());
+                        if let Some(content_encoding) = response_format.content_encoding() {
+                            builder = builder.header(CONTENT_ENCODING, content_encoding);
+                        }
+                        builder.body(Body::from(
+                            response_format
+                                .serialize(response)
+                                .expect("Response serialization should succeed"),
+                        ))
+                    }
+                    Some(Err(ClientError::QueueTimeBudgetExceeded)) => {
+                        warn!(
+                            "Local server shed the request, as it exceeded its queue time budget."
+                        );
+                        let server_error = ServerError::QueueTimeBudgetExceeded;
+                        HyperResponse::builder().status(StatusCode::SERVICE_UNAVAILABLE).body(
+                            Body::from(
+                                SerdeWrapper::new(server_error)
                                     .wrapper_serialize()
-                                    .expect("Response serialization should succeed"),
-                            ))
+                                    .expect("Server error serialization should succeed"),
+                            ),
+                        )
                     }
-                    Err(error) => {
+                    Some(Err(error)) => {
                         panic!(
                             "Remote server failed sending with its local client. Error: {error:?}"
                         );
@@ -1,5 +1,21 @@
 use apollo_metrics::define_metrics;
-use apollo_metrics::metrics::{MetricCounter, MetricGauge, MetricHistogram};
+use apollo_metrics::metrics::{
+    LabeledMetricCounter,
+    LabeledMetricGauge,
+    LabeledMetricHistogram,
+    MetricCounter,
+    MetricGauge,
+    MetricHistogram,
+};
+
+use crate::component_client::CircuitState;
+use crate::component_definitions::LabeledRequest;
+
+pub const LABEL_NAME_REQUEST: &str = "request";
+
+// The request labels are defined by the component crates, which depend on this one, so the
+// per-request metrics are registered by their servers rather than through static permutations.
+const SERVER_REGISTERED_LABELS: &[&[(&str, &str)]] = &[];
 
 define_metrics!(
     Infra => {
@@ -72,6 +88,19 @@ define_metrics!(
         MetricGauge { SIERRA_COMPILER_LOCAL_QUEUE_DEPTH, "sierra_compiler_local_queue_depth", "The depth of the sierra compiler's local message queue" },
         MetricGauge { SIGNATURE_MANAGER_LOCAL_QUEUE_DEPTH, "signature_manager_local_queue_depth", "The depth of the signature manager's local message queue" },
         MetricGauge { STATE_SYNC_LOCAL_QUEUE_DEPTH, "state_sync_local_queue_depth", "The depth of the state sync's local message queue" },
+        // Concurrent local server request queues
+        LabeledMetricGauge { CLASS_MANAGER_LOCAL_REQUEST_QUEUE_DEPTH, "class_manager_local_request_queue_depth", "The number of requests waiting to be dispatched by the class manager local server, by request", labels = SERVER_REGISTERED_LABELS },
+        LabeledMetricHistogram { CLASS_MANAGER_LOCAL_REQUEST_QUEUE_TIME, "class_manager_local_request_queue_time", "The time (secs) requests waited to be dispatched by the class manager local server, by request", labels = SERVER_REGISTERED_LABELS },
+        LabeledMetricCounter { CLASS_MANAGER_LOCAL_REQUESTS_SHED, "class_manager_local_requests_shed", "Counter of requests rejected by the class manager local server for exceeding their queue time budget, by request", init = 0, labels = SERVER_REGISTERED_LABELS },
+        LabeledMetricGauge { GATEWAY_LOCAL_REQUEST_QUEUE_DEPTH, "gateway_local_request_queue_depth", "The number of requests waiting to be dispatched by the gateway local server, by request", labels = SERVER_REGISTERED_LABELS },
+        LabeledMetricHistogram { GATEWAY_LOCAL_REQUEST_QUEUE_TIME, "gateway_local_request_queue_time", "The time (secs) requests waited to be dispatched by the gateway local server, by request", labels = SERVER_REGISTERED_LABELS },
+        LabeledMetricCounter { GATEWAY_LOCAL_REQUESTS_SHED, "gateway_local_requests_shed", "Counter of requests rejected by the gateway local server for exceeding their queue time budget, by request", init = 0, labels = SERVER_REGISTERED_LABELS },
+        LabeledMetricGauge { SIERRA_COMPILER_LOCAL_REQUEST_QUEUE_DEPTH, "sierra_compiler_local_request_queue_depth", "The number of requests waiting to be dispatched by the sierra compiler local server, by request", labels = SERVER_REGISTERED_LABELS },
+        LabeledMetricHistogram { SIERRA_COMPILER_LOCAL_REQUEST_QUEUE_TIME, "sierra_compiler_local_request_queue_time", "The time (secs) requests waited to be dispatched by the sierra compiler local server, by request", labels = SERVER_REGISTERED_LABELS },
+        LabeledMetricCounter { SIERRA_COMPILER_LOCAL_REQUESTS_SHED, "sierra_compiler_local_requests_shed", "Counter of requests rejected by the sierra compiler local server for exceeding their queue time budget, by request", init = 0, labels = SERVER_REGISTERED_LABELS },
+        LabeledMetricGauge { SIGNATURE_MANAGER_LOCAL_REQUEST_QUEUE_DEPTH, "signature_manager_local_request_queue_depth", "The number of requests waiting to be dispatched by the signature manager local server, by request", labels = SERVER_REGISTERED_LABELS },
+        LabeledMetricHistogram { SIGNATURE_MANAGER_LOCAL_REQUEST_QUEUE_TIME, "signature_manager_local_request_queue_time", "The time (secs) requests waited to be dispatched by the signature manager local server, by request", labels = SERVER_REGISTERED_LABELS },
+        LabeledMetricCounter { SIGNATURE_MANAGER_LOCAL_REQUESTS_SHED, "signature_manager_local_requests_shed", "Counter of requests rejected by the signature manager local server for exceeding their queue time budget, by request", init = 0, labels = SERVER_REGISTERED_LABELS },
         // Remote client metrics
         MetricHistogram { BATCHER_REMOTE_CLIENT_SEND_ATTEMPTS, "batcher_remote_client_send_attempts", "Required number of remote connection attempts made by a batcher remote client"},
         MetricHistogram { CLASS_MANAGER_REMOTE_CLIENT_SEND_ATTEMPTS, "class_manager_remote_client_send_attempts", "Required number of remote connection attempts made by a class manager remote client"},
@@ -84,6 +113,17 @@ define_metrics!(
         MetricHistogram { SIERRA_COMPILER_REMOTE_CLIENT_SEND_ATTEMPTS, "sierra_compiler_remote_client_send_attempts", "Required number of remote connection attempts made by a sierra compiler remote client"},
         MetricHistogram { SIGNATURE_MANAGER_REMOTE_CLIENT_SEND_ATTEMPTS, "signature_manager_remote_client_send_attempts", "Required number of remote connection attempts made by a signature manager remote client"},
         MetricHistogram { STATE_SYNC_REMOTE_CLIENT_SEND_ATTEMPTS, "state_sync_remote_client_send_attempts", "Required number of remote connection attempts made by a state sync remote client"},
+        MetricGauge { BATCHER_REMOTE_CLIENT_CIRCUIT_STATE, "batcher_remote_client_circuit_state", "The circuit breaker state of a batcher remote client: 0 closed, 1 half-open, 2 open" },
+        MetricGauge { CLASS_MANAGER_REMOTE_CLIENT_CIRCUIT_STATE, "class_manager_remote_client_circuit_state", "The circuit breaker state of a class manager remote client: 0 closed, 1 half-open, 2 open" },
+        MetricGauge { GATEWAY_REMOTE_CLIENT_CIRCUIT_STATE, "gateway_remote_client_circuit_state", "The circuit breaker state of a gateway remote client: 0 closed, 1 half-open, 2 open" },
+        MetricGauge { L1_ENDPOINT_MONITOR_REMOTE_CLIENT_CIRCUIT_STATE, "l1_endpoint_monitor_remote_client_circuit_state", "The circuit breaker state of a L1 endpoint monitor remote client: 0 closed, 1 half-open, 2 open" },
+        MetricGauge { L1_PROVIDER_REMOTE_CLIENT_CIRCUIT_STATE, "l1_provider_remote_client_circuit_state", "The circuit breaker state of a L1 provider remote client: 0 closed, 1 half-open, 2 open" },
+        MetricGauge { L1_GAS_PRICE_PROVIDER_REMOTE_CLIENT_CIRCUIT_STATE, "l1_gas_price_provider_remote_client_circuit_state", "The circuit breaker state of a L1 gas price provider remote client: 0 closed, 1 half-open, 2 open" },
+        MetricGauge { MEMPOOL_REMOTE_CLIENT_CIRCUIT_STATE, "mempool_remote_client_circuit_state", "The circuit breaker state of a mempool remote client: 0 closed, 1 half-open, 2 open" },
+        MetricGauge { MEMPOOL_P2P_REMOTE_CLIENT_CIRCUIT_STATE, "mempool_p2p_propagator_remote_client_circuit_state", "The circuit breaker state of a mempool p2p remote client: 0 closed, 1 half-open, 2 open" },
+        MetricGauge { SIERRA_COMPILER_REMOTE_CLIENT_CIRCUIT_STATE, "sierra_compiler_remote_client_circuit_state", "The circuit breaker state of a sierra compiler remote client: 0 closed, 1 half-open, 2 open" },
+        MetricGauge { SIGNATURE_MANAGER_REMOTE_CLIENT_CIRCUIT_STATE, "signature_manager_remote_client_circuit_state", "The circuit breaker state of a signature manager remote client: 0 closed, 1 half-open, 2 open" },
+        MetricGauge { STATE_SYNC_REMOTE_CLIENT_CIRCUIT_STATE, "state_sync_remote_client_circuit_state", "The circuit breaker state of a state sync remote client: 0 closed, 1 half-open, 2 open" },
     },
 );
 
@@ -92,20 +132,37 @@ define_metrics!(
 pub struct RemoteClientMetrics {
     /// Histogram to track the number of send attempts to a remote server.
     attempts: &'static MetricHistogram,
+    /// Gauge to track the state of the client's circuit breaker.
+    circuit_state: &'static MetricGauge,
 }
 
 impl RemoteClientMetrics {
-    pub const fn new(attempts: &'static MetricHistogram) -> Self {
-        Self { attempts }
+    pub const fn new(
+        attempts: &'static MetricHistogram,
+        circuit_state: &'static MetricGauge,
+    ) -> Self {
+        Self { attempts, circuit_state }
     }
 
     pub fn register(&self) {
         self.attempts.register();
+        self.circuit_state.register();
     }
 
     pub fn record_attempt(&self, value: usize) {
         self.attempts.record_lossy(value);
     }
+
+    pub fn set_circuit_state(&self, state: CircuitState) {
+        self.circuit_state.set(state.as_metric_value());
+    }
+
+    #[cfg(any(feature = "testing", test))]
+    pub fn get_circuit_state_value(&self, metrics_as_string: &str) -> u8 {
+        self.circuit_state
+            .parse_numeric_metric::<u8>(metrics_as_string)
+            .expect("circuit_state metrics should be available")
+    }
 }
 
 /// A struct to contain all metrics for a local server.
@@ -164,6 +221,64 @@ impl LocalServerMetrics {
     }
 }
 
+/// Per-request metrics of the scheduling queue of a concurrent local server.
+pub struct LocalServerQueueMetrics {
+    queue_depth: &'static LabeledMetricGauge,
+    queue_time: &'static LabeledMetricHistogram,
+    shed_requests: &'static LabeledMetricCounter,
+}
+
+impl LocalServerQueueMetrics {
+    pub const fn new(
+        queue_depth: &'static LabeledMetricGauge,
+        queue_time: &'static LabeledMetricHistogram,
+        shed_requests: &'static LabeledMetricCounter,
+    ) -> Self {
+        Self { queue_depth, queue_time, shed_requests }
+    }
+
+    pub fn register<Request: LabeledRequest>(&self) {
+        self.queue_depth.register();
+        self.queue_time.register();
+        self.shed_requests.register();
+        for label in Request::LABELS {
+            let labels = [(LABEL_NAME_REQUEST, *label)];
+            self.queue_depth.set(0, &labels);
+            self.shed_requests.increment(0, &labels);
+        }
+    }
+
+    pub fn increment_queue_depth(&self, label: &'static str) {
+        self.queue_depth.increment(1, &[(LABEL_NAME_REQUEST, label)]);
+    }
+
+    pub fn decrement_queue_depth(&self, label: &'static str) {
+        self.queue_depth.decrement(1, &[(LABEL_NAME_REQUEST, label)]);
+    }
+
+    #[cfg(any(feature = "testing", test))]
+    pub fn get_queue_depth_value(&self, metrics_as_string: &str, label: &'static str) -> usize {
+        self.queue_depth
+            .parse_numeric_metric::<usize>(metrics_as_string, &[(LABEL_NAME_REQUEST, label)])
+            .expect("queue_depth metrics should be available")
+    }
+
+    pub fn record_queue_time(&self, label: &'static str, secs: f64) {
+        self.queue_time.record(secs, &[(LABEL_NAME_REQUEST, label)]);
+    }
+
+    pub fn increment_shed(&self, label: &'static str) {
+        self.shed_requests.increment(1, &[(LABEL_NAME_REQUEST, label)]);
+    }
+
+    #[cfg(any(feature = "testing", test))]
+    pub fn get_shed_value(&self, metrics_as_string: &str, label: &'static str) -> u64 {
+        self.shed_requests
+            .parse_numeric_metric::<u64>(metrics_as_string, &[(LABEL_NAME_REQUEST, label)])
+            .expect("shed_requests metrics should be available")
+    }
+}
+
 /// A struct to contain all metrics for a remote server.
 pub struct RemoteServerMetrics {
     total_received_msgs: &'static MetricCounter,
@@ -1,7 +1,13 @@
 use std::fmt::Debug;
+use std::io::Read;
 
+use hyper::header::{ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_TYPE};
+use hyper::HeaderMap;
 use serde::{Deserialize, Serialize};
 use serde_json::{from_slice, to_vec};
+use thiserror::Error;
+
+use crate::component_definitions::{APPLICATION_CBOR, APPLICATION_OCTET_STREAM, ZSTD_ENCODING};
 
 #[cfg(test)]
 #[path = "serde_utils_test.rs"]
@@ -30,3 +36,139 @@ where
         from_slice(bytes).map(|serde_wrapper: Self| serde_wrapper.data)
     }
 }
+
+/// The encoding of remote component messages, indicated by the `Content-Type` header.
+// Component messages rely on serde features, e.g., internally tagged enums, that require a
+// self-describing format, ruling out formats such as bincode.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum WireCodec {
+    /// The original encoding, understood by all servers.
+    #[default]
+    Json,
+    /// A compact binary encoding, understood only by servers supporting codec negotiation.
+    Cbor,
+}
+
+/// The maximal size of a decompressed message body, bounding the memory a compressed body can
+/// expand into.
+const MAX_DECOMPRESSED_BODY_SIZE: u64 = 1 << 27;
+
+#[derive(Debug, Error)]
+pub enum WireCodecError {
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("CBOR serialization failed: {0}")]
+    CborSerialization(String),
+    #[error("CBOR deserialization failed: {0}")]
+    CborDeserialization(String),
+    #[error("Zstd compression failed: {0}")]
+    Compression(#[from] std::io::Error),
+    #[error("Decompressed body exceeds {max_size} bytes.")]
+    DecompressedSizeExceeded { max_size: u64 },
+    #[error("Unsupported content encoding: {0}")]
+    UnsupportedContentEncoding(String),
+}
+
+/// How the body of a remote component message is encoded and compressed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WireFormat {
+    pub codec: WireCodec,
+    pub zstd: bool,
+}
+
+impl WireFormat {
+    pub fn new(codec: WireCodec, zstd: bool) -> Self {
+        Self { codec, zstd }
+    }
+
+    /// Reads the format of a message body from its headers. Messages predating codec negotiation
+    /// carry no recognized content type, and are treated as uncompressed JSON.
+    pub fn from_headers(headers: &HeaderMap) -> Result<Self, WireCodecError> {
+        let codec = match headers.get(CONTENT_TYPE) {
+            Some(content_type) if content_type == APPLICATION_CBOR => WireCodec::Cbor,
+            _ => WireCodec::Json,
+        };
+        let zstd = match headers.get(CONTENT_ENCODING) {
+            None => false,
+            Some(content_encoding) if content_encoding == ZSTD_ENCODING => true,
+            Some(content_encoding) => {
+                return Err(WireCodecError::UnsupportedContentEncoding(format!(
+                    "{content_encoding:?}"
+                )));
+            }
+        };
+        Ok(Self { codec, zstd })
+    }
+
+    /// The format of the response to a request of this format: encoded like the request, and
+    /// compressed if the client accepts compressed responses.
+    pub fn response_format(&self, request_headers: &HeaderMap) -> Self {
+        let zstd = request_headers
+            .get_all(ACCEPT_ENCODING)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .any(|encoding| encoding.trim() == ZSTD_ENCODING);
+        Self { codec: self.codec, zstd }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self.codec {
+            WireCodec::Json => APPLICATION_OCTET_STREAM,
+            WireCodec::Cbor => APPLICATION_CBOR,
+        }
+    }
+
+    pub fn content_encoding(&self) -> Option<&'static str> {
+        self.zstd.then_some(ZSTD_ENCODING)
+    }
+
+    pub fn serialize<T>(&self, data: T) -> Result<Vec<u8>, WireCodecError>
+    where
+        T: Serialize + for<'de> Deserialize<'de> + Debug,
+    {
+        let wrapper = SerdeWrapper::new(data);
+        let bytes = match self.codec {
+            WireCodec::Json => wrapper.wrapper_serialize()?,
+            WireCodec::Cbor => {
+                let mut bytes = Vec::new();
+                ciborium::into_writer(&wrapper, &mut bytes)
+                    .map_err(|err| WireCodecError::CborSerialization(err.to_string()))?;
+                bytes
+            }
+        };
+        if !self.zstd {
+            return Ok(bytes);
+        }
+        Ok(zstd::encode_all(bytes.as_slice(), zstd::DEFAULT_COMPRESSION_LEVEL)?)
+    }
+
+    pub fn deserialize<T>(&self, bytes: &[u8]) -> Result<T, WireCodecError>
+    where
+        T: Serialize + for<'de> Deserialize<'de> + Debug,
+    {
+        let decompressed;
+        let bytes = if self.zstd {
+            decompressed = zstd_decode_bounded(bytes, MAX_DECOMPRESSED_BODY_SIZE)?;
+            decompressed.as_slice()
+        } else {
+            bytes
+        };
+        match self.codec {
+            WireCodec::Json => Ok(SerdeWrapper::wrapper_deserialize(bytes)?),
+            WireCodec::Cbor => ciborium::from_reader(bytes)
+                .map(|wrapper: SerdeWrapper<T>| wrapper.data)
+                .map_err(|err| WireCodecError::CborDeserialization(err.to_string())),
+        }
+    }
+}
+
+/// Decompresses a zstd body, failing once it expands beyond `max_size` bytes.
+fn zstd_decode_bounded(bytes: &[u8], max_size: u64) -> Result<Vec<u8>, WireCodecError> {
+    let mut decompressed = Vec::new();
+    zstd::Decoder::new(bytes)?.take(max_size + 1).read_to_end(&mut decompressed)?;
+    if u64::try_from(decompressed.len()).expect("Vector length should fit in u64.") > max_size {
+        return Err(WireCodecError::DecompressedSizeExceeded { max_size });
+    }
+    Ok(decompressed)
+}
@@ -1,5 +1,6 @@
 use std::fmt::Debug;
 
+use assert_matches::assert_matches;
 use serde::{Deserialize, Serialize};
 use starknet_api::data_availability::DataAvailabilityMode;
 use starknet_api::rpc_transaction::{
@@ -13,7 +14,20 @@ use starknet_api::rpc_transaction::{
 };
 use starknet_types_core::felt::Felt;
 
-use crate::serde_utils::SerdeWrapper;
+use crate::serde_utils::{
+    zstd_decode_bounded,
+    SerdeWrapper,
+    WireCodec,
+    WireCodecError,
+    WireFormat,
+};
+
+const WIRE_FORMATS: [WireFormat; 4] = [
+    WireFormat { codec: WireCodec::Json, zstd: false },
+    WireFormat { codec: WireCodec::Json, zstd: true },
+    WireFormat { codec: WireCodec::Cbor, zstd: false },
+    WireFormat { codec: WireCodec::Cbor, zstd: true },
+];
 
 fn test_generic_data_serde<T>(data: T)
 where
@@ -25,6 +39,15 @@ where
 
     // Assert that the data is the same after serialization and deserialization.
     assert_eq!(data, decoded);
+
+    // The default wire format is compatible with the original encoding.
+    assert_eq!(WireFormat::default().serialize(data.clone()).unwrap(), encoded);
+
+    for wire_format in WIRE_FORMATS {
+        let encoded = wire_format.serialize(data.clone()).unwrap();
+        let decoded = wire_format.deserialize::<T>(&encoded).unwrap();
+        assert_eq!(data, decoded, "Wire format: {wire_format:?}");
+    }
 }
 
 #[test]
@@ -108,3 +131,14 @@ fn serde_rpc_declare_tx() {
 
     test_generic_data_serde(RpcTransaction::Declare(rpc_declare_tx));
 }
+
+#[test]
+fn zstd_decode_is_bounded() {
+    let body = [0_u8; 1024];
+    let compressed = zstd::encode_all(body.as_slice(), 0).unwrap();
+    assert_eq!(zstd_decode_bounded(&compressed, 1024).unwrap(), body);
+    assert_matches!(
+        zstd_decode_bounded(&compressed, 1023),
+        Err(WireCodecError::DecompressedSizeExceeded { max_size: 1023 })
+    );
+}
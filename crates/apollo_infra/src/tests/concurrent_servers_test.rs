@@ -3,13 +3,16 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
+use metrics::set_default_local_recorder;
+use metrics_exporter_prometheus::PrometheusBuilder;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::channel;
-use tokio::sync::Semaphore;
+use tokio::sync::{Mutex, Semaphore};
 use tokio::task;
 use tokio::time::timeout;
 
 use crate::component_client::{
+    ClientError,
     ClientResult,
     LocalComponentClient,
     RemoteClientConfig,
@@ -20,15 +23,21 @@ use crate::component_definitions::{
     ComponentRequestAndResponseSender,
     ComponentRequestHandler,
     ComponentStarter,
+    LabeledRequest,
+    PrioritizedRequest,
+    RequestPriority,
 };
 use crate::component_server::{
     ComponentServerStarter,
     ConcurrentLocalComponentServer,
+    LocalComponentServer,
+    LocalServerConfig,
     RemoteComponentServer,
 };
 use crate::tests::{
     AVAILABLE_PORTS,
     TEST_LOCAL_SERVER_METRICS,
+    TEST_LOCAL_SERVER_QUEUE_METRICS,
     TEST_REMOTE_CLIENT_METRICS,
     TEST_REMOTE_SERVER_METRICS,
 };
@@ -40,6 +49,16 @@ enum ConcurrentComponentRequest {
     PerformAction(TestSemaphore),
 }
 
+impl LabeledRequest for ConcurrentComponentRequest {
+    const LABELS: &'static [&'static str] = &["perform_action"];
+
+    fn label(&self) -> &'static str {
+        "perform_action"
+    }
+}
+
+impl PrioritizedRequest for ConcurrentComponentRequest {}
+
 #[derive(Serialize, Deserialize, Debug)]
 enum ConcurrentComponentResponse {
     PerformAction,
@@ -130,8 +149,10 @@ async fn setup_concurrent_local_test() -> LocalConcurrentComponentClient {
     let mut concurrent_local_server = ConcurrentLocalComponentServer::new(
         component,
         rx_a,
+        LocalServerConfig::default(),
         max_concurrency,
         TEST_LOCAL_SERVER_METRICS,
+        TEST_LOCAL_SERVER_QUEUE_METRICS,
     );
     task::spawn(async move {
         let _ = concurrent_local_server.start().await;
@@ -213,3 +234,193 @@ async fn remote_server_concurrency() {
 
     perform_concurrency_test(Box::new(client.clone()), Box::new(client)).await;
 }
+
+/// A request tagged with an id, handled in the order of its priority.
+#[derive(Serialize, Deserialize, Debug)]
+enum PrioritizedComponentRequest {
+    Low(usize),
+    Normal(usize),
+    High(usize),
+}
+
+impl LabeledRequest for PrioritizedComponentRequest {
+    const LABELS: &'static [&'static str] = &["low", "normal", "high"];
+
+    fn label(&self) -> &'static str {
+        match self {
+            PrioritizedComponentRequest::Low(_) => "low",
+            PrioritizedComponentRequest::Normal(_) => "normal",
+            PrioritizedComponentRequest::High(_) => "high",
+        }
+    }
+}
+
+impl PrioritizedRequest for PrioritizedComponentRequest {
+    fn priority(&self) -> RequestPriority {
+        match self {
+            PrioritizedComponentRequest::Low(_) => RequestPriority::Low,
+            PrioritizedComponentRequest::Normal(_) => RequestPriority::Normal,
+            PrioritizedComponentRequest::High(_) => RequestPriority::High,
+        }
+    }
+}
+
+/// Records the ids of the requests it handles, and then waits for a permit to respond.
+#[derive(Clone)]
+struct PrioritizedComponent {
+    handled_ids: Arc<Mutex<Vec<usize>>>,
+    permits: Arc<Semaphore>,
+}
+
+impl ComponentStarter for PrioritizedComponent {}
+
+#[async_trait]
+impl ComponentRequestHandler<PrioritizedComponentRequest, usize> for PrioritizedComponent {
+    async fn handle_request(&mut self, request: PrioritizedComponentRequest) -> usize {
+        let id = match request {
+            PrioritizedComponentRequest::Low(id)
+            | PrioritizedComponentRequest::Normal(id)
+            | PrioritizedComponentRequest::High(id) => id,
+        };
+        self.handled_ids.lock().await.push(id);
+        self.permits.acquire().await.unwrap().forget();
+        id
+    }
+}
+
+type PrioritizedComponentClient = LocalComponentClient<PrioritizedComponentRequest, usize>;
+
+/// Starts a server handling a single request at a time, returning its client, the ids of the
+/// requests it handled and the permits releasing them.
+fn setup_prioritized_local_test(
+    config: LocalServerConfig,
+) -> (PrioritizedComponentClient, Arc<Mutex<Vec<usize>>>, Arc<Semaphore>) {
+    let (component, handled_ids, permits) = prioritized_component();
+    let (tx, rx) =
+        channel::<ComponentRequestAndResponseSender<PrioritizedComponentRequest, usize>>(32);
+
+    let max_concurrency = 1;
+    let mut concurrent_local_server = ConcurrentLocalComponentServer::new(
+        component,
+        rx,
+        config,
+        max_concurrency,
+        TEST_LOCAL_SERVER_METRICS,
+        TEST_LOCAL_SERVER_QUEUE_METRICS,
+    );
+    task::spawn(async move {
+        let _ = concurrent_local_server.start().await;
+    });
+
+    (PrioritizedComponentClient::new(tx), handled_ids, permits)
+}
+
+/// Same as `setup_prioritized_local_test`, with a sequential local server.
+fn setup_prioritized_sequential_local_test()
+-> (PrioritizedComponentClient, Arc<Mutex<Vec<usize>>>, Arc<Semaphore>) {
+    let (component, handled_ids, permits) = prioritized_component();
+    let (tx, rx) =
+        channel::<ComponentRequestAndResponseSender<PrioritizedComponentRequest, usize>>(32);
+
+    let mut local_server = LocalComponentServer::new(component, rx, TEST_LOCAL_SERVER_METRICS);
+    task::spawn(async move {
+        let _ = local_server.start().await;
+    });
+
+    (PrioritizedComponentClient::new(tx), handled_ids, permits)
+}
+
+fn prioritized_component() -> (PrioritizedComponent, Arc<Mutex<Vec<usize>>>, Arc<Semaphore>) {
+    let handled_ids = Arc::new(Mutex::new(Vec::new()));
+    let permits = Arc::new(Semaphore::new(0));
+    let component =
+        PrioritizedComponent { handled_ids: handled_ids.clone(), permits: permits.clone() };
+    (component, handled_ids, permits)
+}
+
+fn spawn_request(
+    client: &PrioritizedComponentClient,
+    request: PrioritizedComponentRequest,
+) -> task::JoinHandle<ClientResult<usize>> {
+    let client = client.clone();
+    task::spawn(async move { client.send(request).await })
+}
+
+#[tokio::test]
+async fn concurrent_server_dispatches_by_priority() {
+    let (client, handled_ids, permits) = setup_prioritized_local_test(LocalServerConfig::default());
+
+    // Occupy the server, and queue requests of all the priorities behind it.
+    let mut handles = vec![spawn_request(&client, PrioritizedComponentRequest::Low(0))];
+    task::yield_now().await;
+    handles.push(spawn_request(&client, PrioritizedComponentRequest::Low(1)));
+    handles.push(spawn_request(&client, PrioritizedComponentRequest::Normal(2)));
+    handles.push(spawn_request(&client, PrioritizedComponentRequest::High(3)));
+    task::yield_now().await;
+
+    permits.add_permits(handles.len());
+    for handle in handles {
+        handle.await.unwrap().unwrap();
+    }
+
+    assert_eq!(*handled_ids.lock().await, vec![0, 3, 2, 1]);
+}
+
+#[tokio::test]
+async fn sequential_server_processes_by_priority() {
+    let (client, handled_ids, permits) = setup_prioritized_sequential_local_test();
+
+    // Occupy the server, and queue requests of all the priorities behind it.
+    let mut handles = vec![spawn_request(&client, PrioritizedComponentRequest::Low(0))];
+    task::yield_now().await;
+    handles.push(spawn_request(&client, PrioritizedComponentRequest::Low(1)));
+    handles.push(spawn_request(&client, PrioritizedComponentRequest::Normal(2)));
+    handles.push(spawn_request(&client, PrioritizedComponentRequest::High(3)));
+    handles.push(spawn_request(&client, PrioritizedComponentRequest::Normal(4)));
+    task::yield_now().await;
+
+    permits.add_permits(handles.len());
+    for handle in handles {
+        handle.await.unwrap().unwrap();
+    }
+
+    // Within a priority, requests are processed in arrival order.
+    assert_eq!(*handled_ids.lock().await, vec![0, 3, 2, 4, 1]);
+}
+
+#[tokio::test]
+async fn concurrent_server_sheds_stale_requests() {
+    let recorder = PrometheusBuilder::new().build_recorder();
+    let _recorder_guard = set_default_local_recorder(&recorder);
+
+    let queue_time_budget = Duration::from_millis(10);
+    let config = LocalServerConfig {
+        normal_priority_queue_time_budget_millis: Some(
+            queue_time_budget.as_millis().try_into().unwrap(),
+        ),
+        ..Default::default()
+    };
+    let (client, handled_ids, permits) = setup_prioritized_local_test(config);
+
+    // Occupy the server until the queued requests exceed the budget.
+    let blocking_handle = spawn_request(&client, PrioritizedComponentRequest::Normal(0));
+    task::yield_now().await;
+    let stale_handle = spawn_request(&client, PrioritizedComponentRequest::Normal(1));
+    let high_priority_handle = spawn_request(&client, PrioritizedComponentRequest::High(2));
+    tokio::time::sleep(queue_time_budget * 2).await;
+
+    permits.add_permits(2);
+    assert_eq!(blocking_handle.await.unwrap().unwrap(), 0);
+    // High priority requests are never shed.
+    assert_eq!(high_priority_handle.await.unwrap().unwrap(), 2);
+    assert!(matches!(stale_handle.await.unwrap(), Err(ClientError::QueueTimeBudgetExceeded)));
+
+    assert_eq!(*handled_ids.lock().await, vec![0, 2]);
+    let metrics_as_string = recorder.handle().render();
+    assert_eq!(TEST_LOCAL_SERVER_QUEUE_METRICS.get_shed_value(&metrics_as_string, "normal"), 1);
+    assert_eq!(TEST_LOCAL_SERVER_QUEUE_METRICS.get_shed_value(&metrics_as_string, "high"), 0);
+    assert_eq!(
+        TEST_LOCAL_SERVER_QUEUE_METRICS.get_queue_depth_value(&metrics_as_string, "normal"),
+        0
+    );
+}
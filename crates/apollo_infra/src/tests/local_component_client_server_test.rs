@@ -1,3 +1,6 @@
+use std::time::Instant;
+
+use assert_matches::assert_matches;
 use async_trait::async_trait;
 use starknet_types_core::felt::Felt;
 use tokio::sync::mpsc::channel;
@@ -90,3 +93,31 @@ async fn local_client_server() {
 
     test_a_b_functionality(a_client, b_client, expected_value).await;
 }
+
+#[tokio::test]
+async fn local_server_rejects_request_past_deadline() {
+    let setup_value: ValueB = Felt::from(30);
+
+    let (tx_a, _rx_a) =
+        channel::<ComponentRequestAndResponseSender<ComponentARequest, ComponentAResponse>>(32);
+    let (tx_b, rx_b) =
+        channel::<ComponentRequestAndResponseSender<ComponentBRequest, ComponentBResponse>>(32);
+
+    let a_client = ComponentAClient::new(tx_a);
+    let b_client = ComponentBClient::new(tx_b);
+
+    let component_b = ComponentB::new(setup_value, Box::new(a_client));
+    let mut component_b_server =
+        LocalComponentServer::new(component_b, rx_b, TEST_LOCAL_SERVER_METRICS);
+
+    task::spawn(async move {
+        let _ = component_b_server.start().await;
+    });
+
+    let result = b_client
+        .send_with_deadline(ComponentBRequest::BSetValue(setup_value + 1), Some(Instant::now()))
+        .await;
+    assert_matches!(result, Err(ClientError::DeadlineExceeded));
+    // The rejected request was not processed.
+    assert_eq!(b_client.b_get_value().await.unwrap(), setup_value);
+}
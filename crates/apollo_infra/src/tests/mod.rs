@@ -6,7 +6,15 @@ mod server_metrics_test;
 use std::sync::Arc;
 
 use apollo_infra_utils::test_utils::{AvailablePorts, TestIdentifier};
-use apollo_metrics::metrics::{MetricCounter, MetricGauge, MetricHistogram, MetricScope};
+use apollo_metrics::metrics::{
+    LabeledMetricCounter,
+    LabeledMetricGauge,
+    LabeledMetricHistogram,
+    MetricCounter,
+    MetricGauge,
+    MetricHistogram,
+    MetricScope,
+};
 use async_trait::async_trait;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
@@ -14,8 +22,13 @@ use starknet_types_core::felt::Felt;
 use tokio::sync::Mutex;
 
 use crate::component_client::ClientResult;
-use crate::component_definitions::{ComponentRequestHandler, ComponentStarter};
-use crate::metrics::{LocalServerMetrics, RemoteClientMetrics, RemoteServerMetrics};
+use crate::component_definitions::{ComponentRequestHandler, ComponentStarter, PrioritizedRequest};
+use crate::metrics::{
+    LocalServerMetrics,
+    LocalServerQueueMetrics,
+    RemoteClientMetrics,
+    RemoteServerMetrics,
+};
 
 pub(crate) type ValueA = Felt;
 pub(crate) type ValueB = Felt;
@@ -49,6 +62,38 @@ const TEST_QUEUE_DEPTH: MetricGauge = MetricGauge::new(
 pub(crate) const TEST_LOCAL_SERVER_METRICS: LocalServerMetrics =
     LocalServerMetrics::new(&TEST_MSGS_RECEIVED, &TEST_MSGS_PROCESSED, &TEST_QUEUE_DEPTH);
 
+const TEST_REQUEST_QUEUE_DEPTH: LabeledMetricGauge = LabeledMetricGauge::new(
+    MetricScope::Infra,
+    "test_request_queue_depth",
+    "test_request_queue_depth_filter",
+    "Test request queue depth gauge",
+    &[],
+);
+
+const TEST_REQUEST_QUEUE_TIME: LabeledMetricHistogram = LabeledMetricHistogram::new(
+    MetricScope::Infra,
+    "test_request_queue_time",
+    "test_request_queue_time_filter",
+    "Test request queue time histogram",
+    &[],
+);
+
+const TEST_REQUESTS_SHED: LabeledMetricCounter = LabeledMetricCounter::new(
+    MetricScope::Infra,
+    "test_requests_shed",
+    "test_requests_shed_filter",
+    "Test requests shed counter",
+    0,
+    &[],
+);
+
+pub(crate) const TEST_LOCAL_SERVER_QUEUE_METRICS: LocalServerQueueMetrics =
+    LocalServerQueueMetrics::new(
+        &TEST_REQUEST_QUEUE_DEPTH,
+        &TEST_REQUEST_QUEUE_TIME,
+        &TEST_REQUESTS_SHED,
+    );
+
 const REMOTE_TEST_MSGS_RECEIVED: MetricCounter = MetricCounter::new(
     MetricScope::Infra,
     "remote_test_msgs_received",
@@ -88,8 +133,15 @@ pub(crate) const TEST_REMOTE_SERVER_METRICS: RemoteServerMetrics = RemoteServerM
     &REMOTE_TEST_MSGS_PROCESSED,
 );
 
+const TEST_CIRCUIT_STATE: MetricGauge = MetricGauge::new(
+    MetricScope::Infra,
+    "test_circuit_state",
+    "test_circuit_state_filter",
+    "Test remote client circuit state gauge",
+);
+
 pub(crate) const TEST_REMOTE_CLIENT_METRICS: RemoteClientMetrics =
-    RemoteClientMetrics::new(&EXAMPLE_HISTOGRAM_METRIC);
+    RemoteClientMetrics::new(&EXAMPLE_HISTOGRAM_METRIC, &TEST_CIRCUIT_STATE);
 
 // Define the shared fixture
 pub static AVAILABLE_PORTS: Lazy<Arc<Mutex<AvailablePorts>>> = Lazy::new(|| {
@@ -102,6 +154,8 @@ pub enum ComponentARequest {
     AGetValue,
 }
 
+impl PrioritizedRequest for ComponentARequest {}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub enum ComponentAResponse {
     AGetValue(ValueA),
@@ -113,6 +167,8 @@ pub enum ComponentBRequest {
     BSetValue(ValueB),
 }
 
+impl PrioritizedRequest for ComponentBRequest {}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub enum ComponentBResponse {
     BGetValue(ValueB),
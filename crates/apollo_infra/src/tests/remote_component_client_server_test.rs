@@ -1,10 +1,12 @@
 use std::fmt::Debug;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
+use assert_matches::assert_matches;
 use async_trait::async_trait;
 use hyper::body::to_bytes;
-use hyper::header::CONTENT_TYPE;
+use hyper::header::{ACCEPT_ENCODING, CONTENT_TYPE};
 use hyper::service::{make_service_fn, service_fn};
 use hyper::{Body, Client, Request, Response, Server, StatusCode, Uri};
 use rstest::rstest;
@@ -27,13 +29,14 @@ use crate::component_definitions::{
     ComponentRequestAndResponseSender,
     ServerError,
     APPLICATION_OCTET_STREAM,
+    REQUEST_TIMEOUT_MILLIS_HEADER,
 };
 use crate::component_server::{
     ComponentServerStarter,
     LocalComponentServer,
     RemoteComponentServer,
 };
-use crate::serde_utils::SerdeWrapper;
+use crate::serde_utils::{SerdeWrapper, WireCodec, WireFormat};
 use crate::tests::{
     test_a_b_functionality,
     ComponentA,
@@ -97,6 +100,11 @@ impl ComponentBClientTrait for RemoteComponentClient<ComponentBRequest, Componen
     }
 }
 
+/// A client config whose retries don't back off, to keep the tests of failing requests short.
+fn no_backoff_client_config() -> RemoteClientConfig {
+    RemoteClientConfig { max_retry_interval: 1, ..Default::default() }
+}
+
 async fn verify_error(
     a_remote_client: impl ComponentAClientTrait,
     expected_error_contained_keywords: &[&str],
@@ -145,7 +153,7 @@ where
     // Ensure the server starts running.
     task::yield_now().await;
 
-    let config = RemoteClientConfig::default();
+    let config = no_backoff_client_config();
     ComponentAClient::new(
         config,
         &socket.ip().to_string(),
@@ -222,15 +230,20 @@ async fn setup_for_tests(setup_value: ValueB, a_socket: SocketAddr, b_socket: So
     task::yield_now().await;
 }
 
+#[rstest]
+#[case::json(WireCodec::Json, false)]
+#[case::json_zstd(WireCodec::Json, true)]
+#[case::cbor(WireCodec::Cbor, false)]
+#[case::cbor_zstd(WireCodec::Cbor, true)]
 #[tokio::test]
-async fn proper_setup() {
+async fn proper_setup(#[case] wire_codec: WireCodec, #[case] zstd_compression: bool) {
     let setup_value: ValueB = Felt::from(90);
     let a_socket = AVAILABLE_PORTS.lock().await.get_next_local_host_socket();
     let b_socket = AVAILABLE_PORTS.lock().await.get_next_local_host_socket();
 
     setup_for_tests(setup_value, a_socket, b_socket).await;
-    let a_client_config = RemoteClientConfig::default();
-    let b_client_config = RemoteClientConfig::default();
+    let a_client_config = RemoteClientConfig { wire_codec, zstd_compression, ..Default::default() };
+    let b_client_config = a_client_config.clone();
 
     let a_remote_client = ComponentAClient::new(
         a_client_config,
@@ -286,7 +299,7 @@ async fn faulty_client_setup() {
 #[tokio::test]
 async fn unconnected_server() {
     let socket = AVAILABLE_PORTS.lock().await.get_next_local_host_socket();
-    let client_config = RemoteClientConfig::default();
+    let client_config = no_backoff_client_config();
     let client = ComponentAClient::new(
         client_config,
         &socket.ip().to_string(),
@@ -391,3 +404,115 @@ async fn retry_request() {
     let expected_error_contained_keywords = [StatusCode::IM_A_TEAPOT.as_str()];
     verify_error(a_client_no_retry.clone(), &expected_error_contained_keywords).await;
 }
+
+#[tokio::test]
+async fn circuit_breaker_fails_fast_on_unreachable_server() {
+    let socket = AVAILABLE_PORTS.lock().await.get_next_local_host_socket();
+    let client_config = RemoteClientConfig {
+        retries: 0,
+        circuit_breaker_failure_threshold: 1,
+        circuit_breaker_reset_timeout: 3600,
+        ..Default::default()
+    };
+    let client = ComponentAClient::new(
+        client_config,
+        &socket.ip().to_string(),
+        socket.port(),
+        TEST_REMOTE_CLIENT_METRICS,
+    );
+
+    // The first request fails to reach the server and opens the circuit, failing the next one fast.
+    verify_error(client.clone(), &["Connection refused"]).await;
+    assert_eq!(client.a_get_value().await, Err(ClientError::CircuitOpen));
+}
+
+#[tokio::test]
+async fn client_request_deadline_exceeded() {
+    let socket = AVAILABLE_PORTS.lock().await.get_next_local_host_socket();
+    // Spawn a server that never responds in time.
+    task::spawn(async move {
+        let make_svc = make_service_fn(|_conn| async {
+            Ok::<_, hyper::Error>(service_fn(|_req| async {
+                tokio::time::sleep(Duration::from_secs(3600)).await;
+                Ok::<_, hyper::Error>(Response::new(Body::empty()))
+            }))
+        });
+        Server::bind(&socket).serve(make_svc).await.unwrap();
+    });
+    // Todo(uriel): Get rid of this
+    // Ensure the server starts running.
+    task::yield_now().await;
+
+    let client_config = RemoteClientConfig {
+        request_deadline_millis: Some(100),
+        circuit_breaker_failure_threshold: 0,
+        ..Default::default()
+    };
+    let client = ComponentAClient::new(
+        client_config,
+        &socket.ip().to_string(),
+        socket.port(),
+        TEST_REMOTE_CLIENT_METRICS,
+    );
+    assert_eq!(client.a_get_value().await, Err(ClientError::DeadlineExceeded));
+}
+
+#[tokio::test]
+async fn server_abandons_request_past_deadline() {
+    let socket = AVAILABLE_PORTS.lock().await.get_next_local_host_socket();
+    // The local server is never started, so the request is never processed.
+    let (tx, _rx) =
+        channel::<ComponentRequestAndResponseSender<ComponentARequest, ComponentAResponse>>(32);
+    let local_client = LocalComponentClient::<ComponentARequest, ComponentAResponse>::new(tx);
+    let max_concurrency = 10;
+    let mut remote_server = RemoteComponentServer::new(
+        local_client,
+        socket.ip(),
+        socket.port(),
+        max_concurrency,
+        TEST_REMOTE_SERVER_METRICS,
+    );
+    task::spawn(async move {
+        let _ = remote_server.start().await;
+    });
+    // Todo(uriel): Get rid of this
+    task::yield_now().await;
+
+    let uri: Uri = format!("http://[{}]:{}/", socket.ip(), socket.port()).parse().unwrap();
+    let http_request = Request::post(uri)
+        .header(CONTENT_TYPE, APPLICATION_OCTET_STREAM)
+        .header(REQUEST_TIMEOUT_MILLIS_HEADER, "100")
+        .body(Body::from(
+            SerdeWrapper::new(ComponentARequest::AGetValue).wrapper_serialize().unwrap(),
+        ))
+        .unwrap();
+    let http_response = Client::new().request(http_request).await.unwrap();
+    assert_eq!(http_response.status(), StatusCode::REQUEST_TIMEOUT);
+    let body_bytes = to_bytes(http_response.into_body()).await.unwrap();
+    let server_error = SerdeWrapper::<ServerError>::wrapper_deserialize(&body_bytes).unwrap();
+    assert_eq!(server_error, ServerError::RequestDeadlineExceeded);
+}
+
+#[tokio::test]
+async fn server_responds_in_request_wire_format() {
+    let setup_value: ValueB = Felt::from(90);
+    let a_socket = AVAILABLE_PORTS.lock().await.get_next_local_host_socket();
+    let b_socket = AVAILABLE_PORTS.lock().await.get_next_local_host_socket();
+    setup_for_tests(setup_value, a_socket, b_socket).await;
+
+    let request_format = WireFormat::new(WireCodec::Cbor, false);
+    let uri: Uri = format!("http://[{}]:{}/", b_socket.ip(), b_socket.port()).parse().unwrap();
+    let http_request = Request::post(uri)
+        .header(CONTENT_TYPE, request_format.content_type())
+        .header(ACCEPT_ENCODING, "gzip, zstd")
+        .body(Body::from(request_format.serialize(ComponentBRequest::BGetValue).unwrap()))
+        .unwrap();
+    let http_response = Client::new().request(http_request).await.unwrap();
+    assert_eq!(http_response.status(), StatusCode::OK);
+
+    let response_format = WireFormat::from_headers(http_response.headers()).unwrap();
+    assert_eq!(response_format, WireFormat::new(WireCodec::Cbor, true));
+    let body_bytes = to_bytes(http_response.into_body()).await.unwrap();
+    let response = response_format.deserialize::<ComponentBResponse>(&body_bytes).unwrap();
+    assert_matches!(response, ComponentBResponse::BGetValue(value) if value == setup_value);
+}
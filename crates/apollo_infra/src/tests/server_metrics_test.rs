@@ -22,16 +22,20 @@ use crate::component_definitions::{
     ComponentRequestAndResponseSender,
     ComponentRequestHandler,
     ComponentStarter,
+    LabeledRequest,
+    PrioritizedRequest,
 };
 use crate::component_server::{
     ComponentServerStarter,
     ConcurrentLocalComponentServer,
     LocalComponentServer,
+    LocalServerConfig,
     RemoteComponentServer,
 };
 use crate::tests::{
     AVAILABLE_PORTS,
     TEST_LOCAL_SERVER_METRICS,
+    TEST_LOCAL_SERVER_QUEUE_METRICS,
     TEST_REMOTE_CLIENT_METRICS,
     TEST_REMOTE_SERVER_METRICS,
 };
@@ -45,6 +49,16 @@ enum TestComponentRequest {
     PerformTest,
 }
 
+impl LabeledRequest for TestComponentRequest {
+    const LABELS: &'static [&'static str] = &["perform_test"];
+
+    fn label(&self) -> &'static str {
+        "perform_test"
+    }
+}
+
+impl PrioritizedRequest for TestComponentRequest {}
+
 #[derive(Serialize, Deserialize, Debug)]
 enum TestComponentResponse {
     PerformTest,
@@ -141,8 +155,10 @@ async fn setup_concurrent_local_server_test(
     let mut concurrent_local_server = ConcurrentLocalComponentServer::new(
         component,
         rx,
+        LocalServerConfig::default(),
         max_concurrency,
         TEST_LOCAL_SERVER_METRICS,
+        TEST_LOCAL_SERVER_QUEUE_METRICS,
     );
     task::spawn(async move {
         let _ = concurrent_local_server.start().await;
@@ -363,17 +379,15 @@ async fn all_metrics_for_concurrent_server() {
     task::yield_now().await;
 
     for i in 0..NUMBER_OF_ITERATIONS {
-        // After sending i permits, we should have 'max_concurrency + i + 1' received messages,
-        // up to a maximum of NUMBER_OF_ITERATIONS.
-        let expected_received_msgs = min(max_concurrency + 1 + i, NUMBER_OF_ITERATIONS);
-
-        // The queue depth should be: 'NUMBER_OF_ITERATIONS - number of received messages'.
-        let expected_queue_depth = NUMBER_OF_ITERATIONS - expected_received_msgs;
+        // All the messages are received into the server's scheduling queue, while only
+        // 'max_concurrency + i' of them are dispatched after sending i permits.
+        let expected_queue_depth =
+            NUMBER_OF_ITERATIONS - min(max_concurrency + i, NUMBER_OF_ITERATIONS);
 
         let metrics_as_string = recorder.handle().render();
         assert_server_metrics(
             metrics_as_string.as_str(),
-            expected_received_msgs,
+            NUMBER_OF_ITERATIONS,
             i,
             expected_queue_depth,
         );
@@ -272,7 +272,7 @@ impl FlowSequencerSetup {
         node_config.l1_gas_price_provider_config.number_of_blocks_for_mean = num_l1_txs;
 
         debug!("Sequencer config: {:#?}", node_config);
-        let (clients, servers) = create_node_modules(&node_config).await;
+        let (clients, servers) = create_node_modules(&node_config, None).await;
 
         let MonitoringEndpointConfig { ip, port, .. } = node_config.monitoring_endpoint_config;
         let monitoring_client = MonitoringClient::new(SocketAddr::from((ip, port)));
@@ -0,0 +1,104 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use apollo_infra_utils::test_utils::{AvailablePorts, TestIdentifier};
+use apollo_l1_provider::event_identifiers_to_track;
+use apollo_l1_provider::l1_scraper::{fetch_start_block, L1Scraper, L1ScraperConfig};
+use apollo_l1_provider::test_utils::FakeL1ProviderClient;
+use apollo_l1_provider_types::Event;
+use papyrus_base_layer::fake_base_layer::FakeBaseLayerContract;
+use papyrus_base_layer::fake_base_layer_admin::{
+    run_admin_api,
+    MineBlockRequest,
+    ReorgRequest,
+    BLOCKS,
+    EVENTS,
+    REORG,
+};
+use papyrus_base_layer::L1Event;
+use serde::Serialize;
+use starknet_api::block::BlockTimestamp;
+use starknet_api::nonce;
+use starknet_api::transaction::fields::Fee;
+use starknet_api::transaction::L1HandlerTransaction;
+
+/// Scripts the fake L1 from outside the process, the way devnet tooling does.
+struct FakeBaseLayerAdminClient {
+    socket: SocketAddr,
+    client: reqwest::Client,
+}
+
+impl FakeBaseLayerAdminClient {
+    async fn post(&self, path: &str, body: &impl Serialize) {
+        let response = self
+            .client
+            .post(format!("http://{}{path}", self.socket))
+            .json(body)
+            .send()
+            .await
+            .unwrap();
+        assert!(response.status().is_success(), "Admin request to {path} failed: {response:?}");
+    }
+
+    async fn mine_block(&self) {
+        self.post(BLOCKS, &MineBlockRequest { timestamp: Some(BlockTimestamp(0)) }).await;
+    }
+}
+
+fn log_message_to_l2(nonce: u64) -> L1Event {
+    L1Event::LogMessageToL2 {
+        tx: L1HandlerTransaction { nonce: nonce!(nonce), ..Default::default() },
+        fee: Fee(1),
+        l1_tx_hash: None,
+        timestamp: BlockTimestamp(0),
+    }
+}
+
+fn to_event(l1_event: L1Event) -> Event {
+    Event::from_l1_event(&L1ScraperConfig::default().chain_id, l1_event).unwrap()
+}
+
+/// Runs the L1 scraper against a fake L1 that is scripted only through its admin API, covering
+/// messages to L2 and L1 reorgs without an Ethereum node.
+#[tokio::test]
+async fn l1_flow_with_fake_base_layer() {
+    // Setup.
+    let fake_base_layer = FakeBaseLayerContract::default();
+    let socket = AvailablePorts::new(TestIdentifier::FakeBaseLayerL1FlowTest.into(), 0)
+        .get_next_local_host_socket();
+    tokio::spawn(run_admin_api(fake_base_layer.clone(), socket));
+    // Lets the admin API bind its socket before it's called.
+    tokio::task::yield_now().await;
+    let admin_client = FakeBaseLayerAdminClient { socket, client: reqwest::Client::new() };
+
+    let l1_provider_client = Arc::new(FakeL1ProviderClient::default());
+    let config = L1ScraperConfig::default();
+    let l1_start_block = fetch_start_block(&fake_base_layer, &config).await.unwrap();
+    let mut scraper = L1Scraper::new(
+        config,
+        l1_provider_client.clone(),
+        fake_base_layer,
+        event_identifiers_to_track(),
+        l1_start_block,
+    )
+    .await
+    .unwrap();
+
+    // Test.
+    admin_client.post(EVENTS, &log_message_to_l2(1)).await;
+    admin_client.mine_block().await;
+    scraper.send_events_to_l1_provider().await.unwrap();
+    l1_provider_client.assert_add_events_received_with(&[to_event(log_message_to_l2(1))]);
+
+    // An L1 reorg orphans the message, which is then included in the new fork along with another.
+    admin_client.post(REORG, &ReorgRequest { depth: 1 }).await;
+    admin_client.post(EVENTS, &log_message_to_l2(2)).await;
+    admin_client.post(EVENTS, &log_message_to_l2(1)).await;
+    admin_client.mine_block().await;
+    scraper.send_events_to_l1_provider().await.unwrap();
+    l1_provider_client.assert_retract_events_received_with(&[to_event(log_message_to_l2(1))]);
+    l1_provider_client.assert_add_events_received_with(&[
+        to_event(log_message_to_l2(2)),
+        to_event(log_message_to_l2(1)),
+    ]);
+}
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use apollo_infra::component_client::ClientError;
-use apollo_infra::component_definitions::ComponentClient;
+use apollo_infra::component_definitions::{ComponentClient, PrioritizedRequest};
 use apollo_infra::impl_debug_for_infra_requests_and_responses;
 use apollo_proc_macros::handle_all_response_variants;
 use async_trait::async_trait;
@@ -38,6 +38,8 @@ pub enum L1EndpointMonitorRequest {
 }
 impl_debug_for_infra_requests_and_responses!(L1EndpointMonitorRequest);
 
+impl PrioritizedRequest for L1EndpointMonitorRequest {}
+
 #[derive(Clone, Serialize, Deserialize, AsRefStr)]
 pub enum L1EndpointMonitorResponse {
     GetActiveL1Endpoint(L1EndpointMonitorResult<Url>),
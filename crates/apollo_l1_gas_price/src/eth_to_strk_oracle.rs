@@ -3,12 +3,19 @@ use std::num::NonZeroUsize;
 use std::sync::Mutex;
 use std::time::Duration;
 
-use apollo_config::converters::{deserialize_optional_map, serialize_optional_map};
+use apollo_config::converters::{
+    deserialize_optional_list_with_url_and_headers,
+    deserialize_optional_map,
+    serialize_optional_list_with_url_and_headers,
+    serialize_optional_map,
+    UrlAndHeaders,
+};
 use apollo_config::dumping::{ser_param, SerializeConfig};
 use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
 use apollo_l1_gas_price_types::errors::EthToStrkOracleClientError;
 use apollo_l1_gas_price_types::EthToStrkOracleClientTrait;
 use async_trait::async_trait;
+use futures::future::join_all;
 use futures::FutureExt;
 use lru::LruCache;
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
@@ -21,6 +28,9 @@ use url::Url;
 use crate::metrics::{
     register_eth_to_strk_metrics,
     ETH_TO_STRK_ERROR_COUNT,
+    ETH_TO_STRK_FALLBACK_COUNT,
+    ETH_TO_STRK_OUTLIER_COUNT,
+    ETH_TO_STRK_STALE_FALLBACK_COUNT,
     ETH_TO_STRK_SUCCESS_COUNT,
 };
 
@@ -45,17 +55,30 @@ fn hashmap_to_headermap(hash_map: Option<HashMap<String, String>>) -> HeaderMap
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct EthToStrkOracleConfig {
+    #[serde(deserialize_with = "deserialize_optional_list_with_url_and_headers")]
+    pub additional_url_header_list: Option<Vec<UrlAndHeaders>>,
     pub base_url: Url,
     #[serde(deserialize_with = "deserialize_optional_map")]
     pub headers: Option<HashMap<String, String>>,
     pub lag_interval_seconds: u64,
     pub max_cache_size: usize,
+    pub max_deviation_percent: u64,
+    pub max_fallback_rate_age_seconds: u64,
+    pub max_rate_age_seconds: u64,
     pub query_timeout_sec: u64,
 }
 
 impl SerializeConfig for EthToStrkOracleConfig {
     fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
         BTreeMap::from_iter([
+            ser_param(
+                "additional_url_header_list",
+                &serialize_optional_list_with_url_and_headers(&self.additional_url_header_list),
+                "Additional oracles to query alongside `base_url`, formatted as space-separated \
+                 JSON objects of the form '{\"url\":...,\"headers\":{...}}'. The conversion rate \
+                 is the median of the rates returned by all oracles.",
+                ParamPrivacyInput::Private,
+            ),
             ser_param(
                 "base_url",
                 &self.base_url,
@@ -85,10 +108,35 @@ impl SerializeConfig for EthToStrkOracleConfig {
                 "The maximum number of cached conversion rates.",
                 ParamPrivacyInput::Public,
             ),
+            ser_param(
+                "max_deviation_percent",
+                &self.max_deviation_percent,
+                "Oracle rates deviating from the median of all oracle rates by more than this \
+                 percentage are discarded as outliers. With fewer than 3 rates, outliers can't be \
+                 told apart, so the rates are all discarded unless they are within this \
+                 percentage of each other.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_fallback_rate_age_seconds",
+                &self.max_fallback_rate_age_seconds,
+                "When all oracles fail, the most recent good conversion rate is used instead, as \
+                 long as it was taken at most this many seconds away from the queried timestamp.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "max_rate_age_seconds",
+                &self.max_rate_age_seconds,
+                "Oracle rates whose reported `timestamp` is more than this many seconds away from \
+                 the queried timestamp are discarded as stale.",
+                ParamPrivacyInput::Public,
+            ),
             ser_param(
                 "query_timeout_sec",
                 &self.query_timeout_sec,
-                "The timeout (seconds) for the query to the eth to strk oracle.",
+                "The timeout (seconds) for the query to each eth to strk oracle. A query that \
+                 times out isn't retried right away; it counts as a failed oracle, and is sent \
+                 again on the next request for the rate if all the oracles failed.",
                 ParamPrivacyInput::Public,
             ),
         ])
@@ -98,41 +146,66 @@ impl SerializeConfig for EthToStrkOracleConfig {
 impl Default for EthToStrkOracleConfig {
     fn default() -> Self {
         Self {
+            additional_url_header_list: None,
             base_url: Url::parse("https://example.com/api").unwrap(),
             headers: None,
             lag_interval_seconds: 1,
             max_cache_size: 100,
+            max_deviation_percent: 10,
+            max_fallback_rate_age_seconds: 1800,
+            max_rate_age_seconds: 1800,
             query_timeout_sec: 3,
         }
     }
 }
 
-/// Client for interacting with the eth to strk Oracle API.
-pub struct EthToStrkOracleClient {
-    config: EthToStrkOracleConfig,
-    /// The base URL of the eth to strk Oracle API.
+/// An eth to strk oracle endpoint.
+#[derive(Clone)]
+struct OracleSource {
     /// The `timestamp` parameter is appended dynamically when making requests,
     /// in order to have a stable mapping from block timestamp to conversion rate.
-    base_url: Url,
+    url: Url,
     /// HTTP headers required for requests.
     headers: HeaderMap,
+}
+
+/// Client for interacting with the eth to strk Oracle API.
+/// Every query is sent to all configured oracles, and resolves to the median of their rates.
+pub struct EthToStrkOracleClient {
+    config: EthToStrkOracleConfig,
+    sources: Vec<OracleSource>,
     client: reqwest::Client,
     cached_prices: Mutex<LruCache<u64, u128>>,
     queries: Mutex<LruCache<u64, AbortOnDropHandle<Result<u128, EthToStrkOracleClientError>>>>,
+    /// The rate of the latest quantized timestamp resolved so far, used as a fallback when the
+    /// oracles fail.
+    last_good_rate: Mutex<Option<(u64, u128)>>,
 }
 
 impl EthToStrkOracleClient {
     pub fn new(config: EthToStrkOracleConfig) -> Self {
+        let additional_sources = config.additional_url_header_list.clone().unwrap_or_default();
         info!(
-            "Creating EthToStrkOracleClient with: base_url={:} headers={:?} \
+            "Creating EthToStrkOracleClient with: base_url={:} headers={:?} additional_oracles={} \
              lag_interval_seconds={}",
-            config.base_url, config.headers, config.lag_interval_seconds
+            config.base_url,
+            config.headers,
+            additional_sources.len(),
+            config.lag_interval_seconds
         );
         register_eth_to_strk_metrics();
+        let sources = std::iter::once(OracleSource {
+            url: config.base_url.clone(),
+            headers: hashmap_to_headermap(config.headers.clone()),
+        })
+        .chain(additional_sources.into_iter().map(|UrlAndHeaders { url, headers }| OracleSource {
+            url,
+            headers: hashmap_to_headermap(Some(headers.into_iter().collect())),
+        }))
+        .collect();
         Self {
             config: config.clone(),
-            base_url: config.base_url,
-            headers: hashmap_to_headermap(config.headers),
+            sources,
             client: reqwest::Client::new(),
             cached_prices: Mutex::new(LruCache::new(
                 NonZeroUsize::new(config.max_cache_size).expect("Invalid cache size"),
@@ -140,6 +213,7 @@ impl EthToStrkOracleClient {
             queries: Mutex::new(LruCache::new(
                 NonZeroUsize::new(config.max_cache_size).expect("Invalid cache size"),
             )),
+            last_good_rate: Mutex::new(None),
         }
     }
 
@@ -149,41 +223,132 @@ impl EthToStrkOracleClient {
     ) -> AbortOnDropHandle<Result<u128, EthToStrkOracleClientError>> {
         let adjusted_timestamp = quantized_timestamp * self.config.lag_interval_seconds;
         let client = self.client.clone();
-        let base_url = self.base_url.clone();
-        let headers = self.headers.clone();
-        let query_timeout_sec = self.config.query_timeout_sec;
+        let sources = self.sources.clone();
+        let query_timeout = Duration::from_secs(self.config.query_timeout_sec);
+        let max_deviation_percent = self.config.max_deviation_percent;
+        let max_rate_age_seconds = self.config.max_rate_age_seconds;
 
         let future = async move {
-            let response_body = loop {
-                let mut url = base_url.clone();
-                url.query_pairs_mut().append_pair("timestamp", &adjusted_timestamp.to_string());
-
-                let result = tokio::time::timeout(Duration::from_secs(query_timeout_sec), async {
-                    let response = client.get(url).headers(headers.clone()).send().await?;
-                    let body = response.text().await?;
-                    Ok::<_, EthToStrkOracleClientError>(body)
-                })
-                .await;
+            let results = join_all(sources.into_iter().map(|source| {
+                query_source(
+                    client.clone(),
+                    source,
+                    adjusted_timestamp,
+                    query_timeout,
+                    max_rate_age_seconds,
+                )
+            }))
+            .await;
 
+            let mut rates = Vec::with_capacity(results.len());
+            let mut first_error = None;
+            for result in results {
                 match result {
-                    Ok(inner_result) => {
-                        break inner_result?;
-                    }
-                    Err(_) => {
+                    Ok(rate) => rates.push(rate),
+                    Err(e) => {
                         ETH_TO_STRK_ERROR_COUNT.increment(1);
-                        warn!("Timeout when resolving query for timestamp {adjusted_timestamp}");
-                        continue;
+                        warn!("Oracle query failed for timestamp {adjusted_timestamp}: {e:?}");
+                        first_error.get_or_insert(e);
                     }
                 }
-            };
-            resolve_query(response_body)
+            }
+            if rates.is_empty() {
+                return Err(first_error.expect("There is at least one oracle source"));
+            }
+            aggregate_rates(rates, max_deviation_percent)
         };
 
         AbortOnDropHandle::new(tokio::spawn(future))
     }
+
+    /// Returns the last good rate in place of a failed query, if it is recent enough.
+    fn fallback_rate(
+        &self,
+        quantized_timestamp: u64,
+        error: EthToStrkOracleClientError,
+    ) -> Result<u128, EthToStrkOracleClientError> {
+        let Some((good_quantized_timestamp, rate)) = *self.last_good_rate.lock().unwrap() else {
+            return Err(error);
+        };
+        let age = quantized_timestamp.abs_diff(good_quantized_timestamp)
+            * self.config.lag_interval_seconds;
+        if age > self.config.max_fallback_rate_age_seconds {
+            ETH_TO_STRK_STALE_FALLBACK_COUNT.increment(1);
+            warn!("Last good conversion rate {rate} is too old ({age} seconds) to fall back to.");
+            return Err(error);
+        }
+        ETH_TO_STRK_FALLBACK_COUNT.increment(1);
+        warn!("Falling back to the last good conversion rate {rate} ({age} seconds old): {error}");
+        Ok(rate)
+    }
+}
+
+/// Queries a single oracle, without retrying; failed queries are retried by the next call to
+/// `eth_to_fri_rate`.
+async fn query_source(
+    client: reqwest::Client,
+    source: OracleSource,
+    adjusted_timestamp: u64,
+    query_timeout: Duration,
+    max_rate_age_seconds: u64,
+) -> Result<u128, EthToStrkOracleClientError> {
+    let mut url = source.url;
+    url.query_pairs_mut().append_pair("timestamp", &adjusted_timestamp.to_string());
+
+    let response_body = tokio::time::timeout(query_timeout, async {
+        let response = client.get(url).headers(source.headers).send().await?;
+        Ok::<_, EthToStrkOracleClientError>(response.error_for_status()?.text().await?)
+    })
+    .await
+    .map_err(|_| EthToStrkOracleClientError::QueryTimeoutError(adjusted_timestamp))??;
+    resolve_query(response_body, adjusted_timestamp, max_rate_age_seconds)
+}
+
+/// Discards the rates that deviate from the median of all rates by more than
+/// `max_deviation_percent`, and returns the median of the remaining ones. Fewer than 3 rates have
+/// no majority to tell an outlier by, so they are accepted only if they all agree.
+pub(crate) fn aggregate_rates(
+    mut rates: Vec<u128>,
+    max_deviation_percent: u64,
+) -> Result<u128, EthToStrkOracleClientError> {
+    rates.sort_unstable();
+    let median = sorted_median(&rates);
+    if rates.len() < 3 {
+        let (lowest, highest) = (rates[0], rates[rates.len() - 1]);
+        if highest - lowest > lowest.saturating_mul(max_deviation_percent.into()) / 100 {
+            ETH_TO_STRK_OUTLIER_COUNT.increment(rates.len().try_into().unwrap());
+            warn!("Discarding oracle rates {rates:?} that disagree with each other.");
+            return Err(EthToStrkOracleClientError::RateDeviationError { median, rates });
+        }
+        return Ok(median);
+    }
+    let max_deviation = median.saturating_mul(max_deviation_percent.into()) / 100;
+    let (accepted, outliers): (Vec<u128>, Vec<u128>) =
+        rates.into_iter().partition(|rate| rate.abs_diff(median) <= max_deviation);
+    if !outliers.is_empty() {
+        ETH_TO_STRK_OUTLIER_COUNT.increment(outliers.len().try_into().unwrap());
+        warn!("Discarding oracle rates {outliers:?} deviating too far from the median {median}.");
+    }
+    if accepted.is_empty() {
+        return Err(EthToStrkOracleClientError::RateDeviationError { median, rates: outliers });
+    }
+    Ok(sorted_median(&accepted))
+}
+
+fn sorted_median(sorted_rates: &[u128]) -> u128 {
+    let mid = sorted_rates.len() / 2;
+    if sorted_rates.len() % 2 == 0 {
+        sorted_rates[mid - 1] + (sorted_rates[mid] - sorted_rates[mid - 1]) / 2
+    } else {
+        sorted_rates[mid]
+    }
 }
 
-fn resolve_query(body: String) -> Result<u128, EthToStrkOracleClientError> {
+fn resolve_query(
+    body: String,
+    adjusted_timestamp: u64,
+    max_rate_age_seconds: u64,
+) -> Result<u128, EthToStrkOracleClientError> {
     let json: serde_json::Value = serde_json::from_str(&body)?;
     let price = json
         .get("price")
@@ -191,7 +356,7 @@ fn resolve_query(body: String) -> Result<u128, EthToStrkOracleClientError> {
         .ok_or(EthToStrkOracleClientError::MissingFieldError("price"))?;
     // Convert hex to u128
     let rate = u128::from_str_radix(price.trim_start_matches("0x"), 16)
-        .expect("Failed to parse price as u128");
+        .map_err(|_| EthToStrkOracleClientError::InvalidPriceError(price.to_string()))?;
     // Extract decimals from API response
     let decimals = json
         .get("decimals")
@@ -203,6 +368,18 @@ fn resolve_query(body: String) -> Result<u128, EthToStrkOracleClientError> {
             decimals,
         ));
     }
+    // Oracles that report when the price was taken are checked for staleness.
+    if let Some(rate_timestamp) = json.get("timestamp") {
+        let rate_timestamp = rate_timestamp
+            .as_u64()
+            .ok_or(EthToStrkOracleClientError::MissingFieldError("timestamp"))?;
+        if rate_timestamp.abs_diff(adjusted_timestamp) > max_rate_age_seconds {
+            return Err(EthToStrkOracleClientError::StaleRateError {
+                rate_timestamp,
+                queried_timestamp: adjusted_timestamp,
+            });
+        }
+    }
     ETH_TO_STRK_SUCCESS_COUNT.increment(1);
     Ok(rate)
 }
@@ -212,6 +389,9 @@ impl EthToStrkOracleClientTrait for EthToStrkOracleClient {
     /// The HTTP response must include the following fields:
     /// - `price`: a hexadecimal string representing the price.
     /// - `decimals`: a `u64` value, must be equal to `ETH_TO_STRK_QUANTIZATION`.
+    ///
+    /// It may also include `timestamp`, the `u64` time the price was taken at, which must be
+    /// within `max_rate_age_seconds` of the queried timestamp.
     #[instrument(skip(self))]
     async fn eth_to_fri_rate(&self, timestamp: u64) -> Result<u128, EthToStrkOracleClientError> {
         let quantized_timestamp = (timestamp - self.config.lag_interval_seconds)
@@ -243,21 +423,26 @@ impl EthToStrkOracleClientTrait for EthToStrkOracleClient {
                 queries.pop(&quantized_timestamp);
                 warn!("Query failed to join handle for timestamp {timestamp}: {e:?}");
                 ETH_TO_STRK_ERROR_COUNT.increment(1);
-                return Err(EthToStrkOracleClientError::JoinError(e));
+                return self.fallback_rate(quantized_timestamp, e.into());
             }
         };
         let rate = match query_result {
             Ok(rate) => rate,
             Err(e) => {
                 queries.pop(&quantized_timestamp);
-                warn!("Query failed to reach oracle for timestamp {timestamp}: {e:?}");
-                ETH_TO_STRK_ERROR_COUNT.increment(1);
-                return Err(e);
+                warn!("Query failed to reach the oracles for timestamp {timestamp}: {e:?}");
+                return self.fallback_rate(quantized_timestamp, e);
             }
         };
 
         // Make sure to cache the result.
         cache.put(quantized_timestamp, rate);
+        let mut last_good_rate = self.last_good_rate.lock().unwrap();
+        if last_good_rate.is_none_or(|(good_quantized_timestamp, _)| {
+            good_quantized_timestamp <= quantized_timestamp
+        }) {
+            *last_good_rate = Some((quantized_timestamp, rate));
+        }
         debug!("Conversion rate for timestamp {timestamp} is {rate}");
         Ok(rate)
     }
@@ -1,9 +1,53 @@
+use std::collections::BTreeMap;
+
+use apollo_config::converters::UrlAndHeaders;
+use apollo_l1_gas_price_types::errors::EthToStrkOracleClientError;
 use apollo_l1_gas_price_types::EthToStrkOracleClientTrait;
+use assert_matches::assert_matches;
+use rstest::rstest;
 use serde_json::json;
 use tokio::{self};
 use url::Url;
 
-use crate::eth_to_strk_oracle::{EthToStrkOracleClient, EthToStrkOracleConfig};
+use crate::eth_to_strk_oracle::{
+    aggregate_rates,
+    resolve_query,
+    EthToStrkOracleClient,
+    EthToStrkOracleConfig,
+};
+
+const LAG_INTERVAL_SECONDS: u64 = 60;
+
+async fn mock_oracle(server: &mut mockito::ServerGuard, timestamp: u64, rate: u128) -> Url {
+    let adjusted_timestamp =
+        (timestamp - LAG_INTERVAL_SECONDS) / LAG_INTERVAL_SECONDS * LAG_INTERVAL_SECONDS;
+    server
+        .mock("GET", "/")
+        .match_query(mockito::Matcher::UrlEncoded(
+            "timestamp".into(),
+            adjusted_timestamp.to_string(),
+        ))
+        .with_header("Content-Type", "application/json")
+        .with_body(json!({"price": format!("0x{rate:x}"), "decimals": 18}).to_string())
+        .create_async()
+        .await;
+    Url::parse(&server.url()).unwrap()
+}
+
+/// Polls the client until the query for the given timestamp resolves.
+async fn resolved_rate(
+    client: &EthToStrkOracleClient,
+    timestamp: u64,
+) -> Result<u128, EthToStrkOracleClientError> {
+    loop {
+        match client.eth_to_fri_rate(timestamp).await {
+            Err(EthToStrkOracleClientError::QueryNotReadyError(_)) => {
+                tokio::task::yield_now().await; // Don't block the executor.
+            }
+            result => return result,
+        }
+    }
+}
 
 #[tokio::test]
 async fn eth_to_fri_rate_uses_cache_on_quantized_hit() {
@@ -48,3 +92,94 @@ async fn eth_to_fri_rate_uses_cache_on_quantized_hit() {
         .expect("Should resolve immediately due to the cache");
     assert_eq!(rate1, rate2);
 }
+
+#[rstest]
+#[case::single_rate(vec![100], Some(100))]
+#[case::odd_count(vec![103, 100, 101], Some(101))]
+#[case::even_count(vec![100, 104], Some(102))]
+#[case::outlier_discarded(vec![1000, 100, 102], Some(101))]
+#[case::no_agreement(vec![100, 200], None)]
+#[case::two_rates_disagree(vec![100, 115], None)]
+#[case::outlier_among_four(vec![100, 102, 104, 1000], Some(102))]
+fn aggregate_rates_takes_median_without_outliers(
+    #[case] rates: Vec<u128>,
+    #[case] expected_rate: Option<u128>,
+) {
+    let max_deviation_percent = 10;
+    assert_eq!(aggregate_rates(rates, max_deviation_percent).ok(), expected_rate);
+}
+
+#[tokio::test]
+async fn eth_to_fri_rate_is_median_of_all_oracles() {
+    let timestamp = 1234567890;
+    let mut servers = Vec::new();
+    let mut urls = Vec::new();
+    for rate in [100, 102, 1000] {
+        let mut server = mockito::Server::new_async().await;
+        urls.push(mock_oracle(&mut server, timestamp, rate).await);
+        servers.push(server);
+    }
+    let config = EthToStrkOracleConfig {
+        base_url: urls[0].clone(),
+        additional_url_header_list: Some(
+            urls[1..]
+                .iter()
+                .map(|url| UrlAndHeaders { url: url.clone(), headers: BTreeMap::new() })
+                .collect(),
+        ),
+        lag_interval_seconds: LAG_INTERVAL_SECONDS,
+        max_deviation_percent: 10,
+        ..Default::default()
+    };
+    let client = EthToStrkOracleClient::new(config);
+
+    assert_eq!(resolved_rate(&client, timestamp).await.unwrap(), 101);
+}
+
+#[tokio::test]
+async fn eth_to_fri_rate_falls_back_to_recent_good_rate() {
+    let expected_rate = 123456;
+    let timestamp = 1234567890;
+    let mut server = mockito::Server::new_async().await;
+    // Only the first timestamp is served, queries for any other timestamp fail.
+    let base_url = mock_oracle(&mut server, timestamp, expected_rate).await;
+    let config = EthToStrkOracleConfig {
+        base_url,
+        lag_interval_seconds: LAG_INTERVAL_SECONDS,
+        max_fallback_rate_age_seconds: 2 * LAG_INTERVAL_SECONDS,
+        ..Default::default()
+    };
+    let client = EthToStrkOracleClient::new(config);
+    assert_eq!(resolved_rate(&client, timestamp).await.unwrap(), expected_rate);
+
+    let recent_timestamp = timestamp + 2 * LAG_INTERVAL_SECONDS;
+    assert_eq!(resolved_rate(&client, recent_timestamp).await.unwrap(), expected_rate);
+
+    let stale_timestamp = timestamp + 3 * LAG_INTERVAL_SECONDS;
+    assert!(resolved_rate(&client, stale_timestamp).await.is_err());
+}
+
+#[test]
+fn resolve_query_rejects_invalid_price() {
+    let body = json!({"price": "0xnot_hex", "decimals": 18}).to_string();
+    assert_matches!(
+        resolve_query(body, 0, 0),
+        Err(EthToStrkOracleClientError::InvalidPriceError(price)) if price == "0xnot_hex"
+    );
+}
+
+#[rstest]
+#[case::no_timestamp(json!({"price": "0x64", "decimals": 18}), Some(100))]
+#[case::recent_timestamp(json!({"price": "0x64", "decimals": 18, "timestamp": 1000}), Some(100))]
+#[case::stale_timestamp(json!({"price": "0x64", "decimals": 18, "timestamp": 999}), None)]
+fn resolve_query_rejects_stale_rate(
+    #[case] body: serde_json::Value,
+    #[case] expected: Option<u128>,
+) {
+    let adjusted_timestamp = 1200;
+    let max_rate_age_seconds = 200;
+    assert_eq!(
+        resolve_query(body.to_string(), adjusted_timestamp, max_rate_age_seconds).ok(),
+        expected
+    );
+}
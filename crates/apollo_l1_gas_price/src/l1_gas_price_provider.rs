@@ -9,7 +9,7 @@ use apollo_l1_gas_price_types::errors::L1GasPriceProviderError;
 use apollo_l1_gas_price_types::{GasPriceData, L1GasPriceProviderResult, PriceInfo};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use starknet_api::block::BlockTimestamp;
+use starknet_api::block::{BlockTimestamp, GasPrice};
 use tracing::{info, trace, warn};
 use validator::Validate;
 
@@ -19,6 +19,18 @@ use crate::metrics::{register_provider_metrics, L1_GAS_PRICE_PROVIDER_INSUFFICIE
 #[path = "l1_gas_price_provider_test.rs"]
 pub mod l1_gas_price_provider_test;
 
+/// How the prices of the L1 blocks in the window are aggregated into a single price.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum GasPriceEstimator {
+    /// The mean of the prices.
+    #[default]
+    Mean,
+    /// An exponential moving average of the prices, weighing recent blocks more.
+    Ema,
+    /// A percentile of the prices.
+    Percentile,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Validate, PartialEq)]
 pub struct L1GasPriceProviderConfig {
     // TODO(guyn): these two fields need to go into VersionedConstants.
@@ -29,6 +41,16 @@ pub struct L1GasPriceProviderConfig {
     pub storage_limit: usize,
     // Maximum valid time gap between the requested timestamp and the last price sample in seconds.
     pub max_time_gap_seconds: u64,
+    pub estimator: GasPriceEstimator,
+    // The weight, in percent, of each new block in the EMA.
+    #[validate(range(min = 1, max = 100))]
+    pub ema_smoothing_factor_percent: u64,
+    #[validate(range(max = 100))]
+    pub estimator_percentile: u64,
+    // Whether the blob fee may not go below the projected blob fee of the L1 block after the
+    // window.
+    pub project_blob_fee: bool,
+    pub blob_fee_safety_margin_percent: u64,
 }
 
 impl Default for L1GasPriceProviderConfig {
@@ -39,6 +61,11 @@ impl Default for L1GasPriceProviderConfig {
             lag_margin_seconds: 60,
             storage_limit: usize::try_from(10 * MEAN_NUMBER_OF_BLOCKS).unwrap(),
             max_time_gap_seconds: 900, // 15 minutes
+            estimator: GasPriceEstimator::Mean,
+            ema_smoothing_factor_percent: 10,
+            estimator_percentile: 50,
+            project_blob_fee: false,
+            blob_fee_safety_margin_percent: 0,
         }
     }
 }
@@ -72,6 +99,39 @@ impl SerializeConfig for L1GasPriceProviderConfig {
                  in seconds",
                 ParamPrivacyInput::Public,
             ),
+            ser_param(
+                "estimator",
+                &self.estimator,
+                "How the prices of the blocks used for the gas price calculation are aggregated: \
+                 Mean, Ema (exponential moving average) or Percentile",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "ema_smoothing_factor_percent",
+                &self.ema_smoothing_factor_percent,
+                "The weight, in percent, of each new block in the exponential moving average",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "estimator_percentile",
+                &self.estimator_percentile,
+                "The percentile of the block prices used by the Percentile estimator",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "project_blob_fee",
+                &self.project_blob_fee,
+                "If true, the data gas price is at least the blob fee projected, from the excess \
+                 blob gas, for the L1 block following the blocks used for the calculation",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "blob_fee_safety_margin_percent",
+                &self.blob_fee_safety_margin_percent,
+                "Percentage added on top of the calculated data gas price, to cover blob fee \
+                 increases until the data is posted to L1",
+                ParamPrivacyInput::Public,
+            ),
         ])
     }
 }
@@ -195,21 +255,54 @@ impl L1GasPriceProvider {
             0
         };
         debug_assert!(first_index < last_index, "error calculating indices");
-        let actual_number_of_blocks = last_index - first_index;
 
         // Go over all elements between `first_index` and `last_index` (non-inclusive).
-        let price_info_summed: PriceInfo = samples
-            .iter()
-            .skip(first_index)
-            .take(actual_number_of_blocks)
-            .map(|data| &data.price_info)
-            .sum();
-        let actual_number_of_blocks =
-            u128::try_from(actual_number_of_blocks).expect("Cannot convert to u128");
-        let price_info_out = price_info_summed
-            .checked_div(actual_number_of_blocks)
-            .expect("Actual number of blocks should be non-zero");
-        Ok(price_info_out)
+        let window = samples.range(first_index..last_index);
+        let base_fee_per_gas =
+            self.estimate(window.clone().map(|data| data.price_info.base_fee_per_gas));
+        let mut blob_fee = self.estimate(window.clone().map(|data| data.price_info.blob_fee));
+        if self.config.project_blob_fee {
+            let last_sample = window.last().expect("The window should be non-empty");
+            blob_fee = blob_fee.max(last_sample.next_blob_fee);
+        }
+        let blob_fee = blob_fee.saturating_add(GasPrice(
+            blob_fee.0.saturating_mul(self.config.blob_fee_safety_margin_percent.into()) / 100,
+        ));
+        Ok(PriceInfo { base_fee_per_gas, blob_fee })
+    }
+
+    /// Aggregates the given prices, ordered from oldest to newest, using the configured estimator.
+    fn estimate(&self, prices: impl ExactSizeIterator<Item = GasPrice>) -> GasPrice {
+        let number_of_prices = u128::try_from(prices.len()).expect("Cannot convert to u128");
+        match self.config.estimator {
+            GasPriceEstimator::Mean => prices
+                .fold(GasPrice(0), GasPrice::saturating_add)
+                .checked_div(number_of_prices)
+                .expect("Actual number of blocks should be non-zero"),
+            GasPriceEstimator::Ema => {
+                let smoothing_factor = u128::from(self.config.ema_smoothing_factor_percent);
+                prices
+                    .reduce(|ema, price| {
+                        GasPrice(
+                            price
+                                .0
+                                .saturating_mul(smoothing_factor)
+                                .saturating_add(ema.0.saturating_mul(100 - smoothing_factor))
+                                / 100,
+                        )
+                    })
+                    .expect("Actual number of blocks should be non-zero")
+            }
+            GasPriceEstimator::Percentile => {
+                let mut prices: Vec<_> = prices.collect();
+                prices.sort_unstable();
+                let index = (prices.len() - 1)
+                    * usize::try_from(self.config.estimator_percentile)
+                        .expect("estimator_percentile is too large to fit into a usize")
+                    / 100;
+                prices[index]
+            }
+        }
     }
 }
 
@@ -1,7 +1,9 @@
 use apollo_l1_gas_price_types::{GasPriceData, PriceInfo};
+use rstest::rstest;
 use starknet_api::block::{BlockTimestamp, GasPrice};
 
 use crate::l1_gas_price_provider::{
+    GasPriceEstimator,
     L1GasPriceProvider,
     L1GasPriceProviderConfig,
     L1GasPriceProviderError,
@@ -11,10 +13,17 @@ use crate::l1_gas_price_provider::{
 // To get the prices for the middle three blocks use the timestamp for block[3].
 // Returns the provider, a vector of block prices to compare with, and the timestamp of block[3].
 fn make_provider() -> (L1GasPriceProvider, Vec<PriceInfo>, u64) {
-    let mut provider = L1GasPriceProvider::new(L1GasPriceProviderConfig {
+    make_provider_with_config(L1GasPriceProviderConfig {
         number_of_blocks_for_mean: 3,
         ..Default::default()
-    });
+    })
+}
+
+// Like `make_provider`, where the projected blob fee of each block is twice its blob fee.
+fn make_provider_with_config(
+    config: L1GasPriceProviderConfig,
+) -> (L1GasPriceProvider, Vec<PriceInfo>, u64) {
+    let mut provider = L1GasPriceProvider::new(config);
     provider.initialize().unwrap();
     let mut prices = Vec::new();
     let mut timestamp3 = 0;
@@ -33,6 +42,7 @@ fn make_provider() -> (L1GasPriceProvider, Vec<PriceInfo>, u64) {
                 block_number,
                 timestamp: BlockTimestamp(time),
                 price_info,
+                next_blob_fee: GasPrice(2 * (price + 1)),
             })
             .unwrap();
     }
@@ -67,6 +77,51 @@ fn gas_price_provider_mean_prices() {
     assert_eq!(data_gas_price, data_price_calculation);
 }
 
+// Blocks 1 to 3 have base fees 1, 4, 9 and blob fees 2, 5, 10.
+#[rstest]
+#[case::mean(GasPriceEstimator::Mean, 4, 5)]
+#[case::ema(GasPriceEstimator::Ema, 5, 6)]
+#[case::percentile(GasPriceEstimator::Percentile, 4, 5)]
+fn gas_price_provider_estimators(
+    #[case] estimator: GasPriceEstimator,
+    #[case] expected_gas_price: u128,
+    #[case] expected_data_gas_price: u128,
+) {
+    let (provider, _block_prices, timestamp3) =
+        make_provider_with_config(L1GasPriceProviderConfig {
+            number_of_blocks_for_mean: 3,
+            estimator,
+            ema_smoothing_factor_percent: 50,
+            estimator_percentile: 50,
+            ..Default::default()
+        });
+    let lag = provider.config.lag_margin_seconds;
+
+    let PriceInfo { base_fee_per_gas: gas_price, blob_fee: data_gas_price } =
+        provider.get_price_info(BlockTimestamp(timestamp3 + lag)).unwrap();
+    assert_eq!(gas_price, GasPrice(expected_gas_price));
+    assert_eq!(data_gas_price, GasPrice(expected_data_gas_price));
+}
+
+#[test]
+fn gas_price_provider_projected_blob_fee_with_safety_margin() {
+    let (provider, _block_prices, timestamp3) =
+        make_provider_with_config(L1GasPriceProviderConfig {
+            number_of_blocks_for_mean: 3,
+            project_blob_fee: true,
+            blob_fee_safety_margin_percent: 10,
+            ..Default::default()
+        });
+    let lag = provider.config.lag_margin_seconds;
+
+    let PriceInfo { base_fee_per_gas: gas_price, blob_fee: data_gas_price } =
+        provider.get_price_info(BlockTimestamp(timestamp3 + lag)).unwrap();
+    // The base fee is unaffected.
+    assert_eq!(gas_price, GasPrice(4));
+    // The projected blob fee after block 3 is 20, which is above the mean, plus a 10% margin.
+    assert_eq!(data_gas_price, GasPrice(22));
+}
+
 #[test]
 fn gas_price_provider_adding_blocks() {
     let (mut provider, _block_prices, timestamp3) = make_provider();
@@ -79,7 +134,10 @@ fn gas_price_provider_adding_blocks() {
     // Add a block to the provider.
     let price_info = PriceInfo { base_fee_per_gas: GasPrice(10), blob_fee: GasPrice(11) };
     let timestamp = BlockTimestamp(10);
-    provider.add_price_info(GasPriceData { block_number: 5, timestamp, price_info }).unwrap();
+    let next_blob_fee = GasPrice(0);
+    provider
+        .add_price_info(GasPriceData { block_number: 5, timestamp, price_info, next_blob_fee })
+        .unwrap();
 
     // This should not change the results if we ask for the same timestamp.
     let PriceInfo { base_fee_per_gas: gas_price_new, blob_fee: data_gas_price_new } =
@@ -91,7 +149,9 @@ fn gas_price_provider_adding_blocks() {
     // Add another block to the provider.
     let price_info = PriceInfo { base_fee_per_gas: GasPrice(12), blob_fee: GasPrice(13) };
     let timestamp = BlockTimestamp(12);
-    provider.add_price_info(GasPriceData { block_number: 6, timestamp, price_info }).unwrap();
+    provider
+        .add_price_info(GasPriceData { block_number: 6, timestamp, price_info, next_blob_fee })
+        .unwrap();
 
     // Should fail because the memory of the provider is full, and we added another block.
     let ret = provider.get_price_info(BlockTimestamp(timestamp3 + lag));
@@ -123,7 +183,10 @@ fn gas_price_provider_can_start_at_nonzero_height() {
     provider.initialize().unwrap();
     let price_info = PriceInfo { base_fee_per_gas: GasPrice(0), blob_fee: GasPrice(0) };
     let timestamp = BlockTimestamp(0);
-    provider.add_price_info(GasPriceData { block_number: 42, timestamp, price_info }).unwrap();
+    let next_blob_fee = GasPrice(0);
+    provider
+        .add_price_info(GasPriceData { block_number: 42, timestamp, price_info, next_blob_fee })
+        .unwrap();
 }
 
 #[test]
@@ -134,6 +197,12 @@ fn gas_price_provider_uninitialized_error() {
     });
     let price_info = PriceInfo { base_fee_per_gas: GasPrice(0), blob_fee: GasPrice(0) };
     let timestamp = BlockTimestamp(0);
-    let result = provider.add_price_info(GasPriceData { block_number: 42, timestamp, price_info });
+    let next_blob_fee = GasPrice(0);
+    let result = provider.add_price_info(GasPriceData {
+        block_number: 42,
+        timestamp,
+        price_info,
+        next_blob_fee,
+    });
     assert!(matches!(result, Err(L1GasPriceProviderError::NotInitializedError)));
 }
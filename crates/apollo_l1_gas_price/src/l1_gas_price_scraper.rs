@@ -189,13 +189,14 @@ impl<B: BaseLayerContract + Send + Sync> L1GasPriceScraper<B> {
                 base_fee_per_gas: GasPrice(header.base_fee_per_gas),
                 blob_fee: GasPrice(header.blob_fee),
             };
+            let next_blob_fee = GasPrice(header.next_blob_fee);
 
             self.assert_no_l1_reorgs(&header).await?;
             // Save this block header to use for next iteration.
             self.last_l1_header = Some(header);
 
             self.l1_gas_price_provider
-                .add_price_info(GasPriceData { block_number, timestamp, price_info })
+                .add_price_info(GasPriceData { block_number, timestamp, price_info, next_blob_fee })
                 .await
                 .map_err(L1GasPriceScraperError::GasPriceClientError)?;
             L1_GAS_PRICE_SCRAPER_SUCCESS_COUNT.increment(1);
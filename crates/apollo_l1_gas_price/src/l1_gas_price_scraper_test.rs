@@ -29,6 +29,7 @@ fn create_l1_block_header(block_number: u64) -> L1BlockHeader {
         timestamp: (block_number * BLOCK_TIME).into(),
         base_fee_per_gas: u128::from(block_number) * GAS_PRICE,
         blob_fee: u128::from(block_number) * DATA_PRICE,
+        next_blob_fee: u128::from(block_number + 1) * DATA_PRICE,
         hash: u64_to_block_hash(block_number),
         parent_hash: u64_to_block_hash(block_number.saturating_sub(1)),
         // If needed, add ..Default::default() here.
@@ -40,6 +41,7 @@ fn check_gas_prices(data: &GasPriceData) -> bool {
     data.timestamp.0 == data.block_number * BLOCK_TIME
         && data.price_info.base_fee_per_gas == GasPrice(u128::from(data.block_number) * GAS_PRICE)
         && data.price_info.blob_fee == GasPrice(u128::from(data.block_number) * DATA_PRICE)
+        && data.next_blob_fee == GasPrice(u128::from(data.block_number + 1) * DATA_PRICE)
 }
 
 fn setup_scraper(
@@ -8,6 +8,9 @@ define_metrics!(
         MetricCounter { L1_GAS_PRICE_SCRAPER_REORG_DETECTED, "l1_gas_price_scraper_reorg_detected", "Number of times the L1 gas price scraper detected a reorganization in the base layer", init=0 },
         MetricCounter { ETH_TO_STRK_ERROR_COUNT, "eth_to_strk_error_count", "Number of times the query to the Eth to Strk oracle failed due to an error or timeout", init=0 },
         MetricCounter { ETH_TO_STRK_SUCCESS_COUNT, "eth_to_strk_success_count", "Number of times the query to the Eth to Strk oracle succeeded", init=0 },
+        MetricCounter { ETH_TO_STRK_OUTLIER_COUNT, "eth_to_strk_outlier_count", "Number of Eth to Strk oracle rates rejected for deviating too far from the median of all oracle rates", init=0 },
+        MetricCounter { ETH_TO_STRK_FALLBACK_COUNT, "eth_to_strk_fallback_count", "Number of times the oracles failed and the last good Eth to Strk rate was used instead", init=0 },
+        MetricCounter { ETH_TO_STRK_STALE_FALLBACK_COUNT, "eth_to_strk_stale_fallback_count", "Number of times the oracles failed and the last good Eth to Strk rate was too old to fall back to", init=0 },
     }
 );
 
@@ -24,4 +27,7 @@ pub(crate) fn register_scraper_metrics() {
 pub(crate) fn register_eth_to_strk_metrics() {
     ETH_TO_STRK_ERROR_COUNT.register();
     ETH_TO_STRK_SUCCESS_COUNT.register();
+    ETH_TO_STRK_OUTLIER_COUNT.register();
+    ETH_TO_STRK_FALLBACK_COUNT.register();
+    ETH_TO_STRK_STALE_FALLBACK_COUNT.register();
 }
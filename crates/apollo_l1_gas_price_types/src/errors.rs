@@ -37,8 +37,16 @@ pub enum EthToStrkOracleClientError {
     ParseError(#[from] serde_json::Error),
     #[error("Missing or invalid field: {0}")]
     MissingFieldError(&'static str),
+    #[error("Invalid price value: {0}")]
+    InvalidPriceError(String),
     #[error("Invalid decimals value: expected {0}, got {1}")]
     InvalidDecimalsError(u64, u64),
     #[error("Query not yet resolved: timestamp={0}")]
     QueryNotReadyError(u64),
+    #[error("Query timed out: timestamp={0}")]
+    QueryTimeoutError(u64),
+    #[error("All oracle rates {rates:?} deviate too far from their median {median}")]
+    RateDeviationError { median: u128, rates: Vec<u128> },
+    #[error("Oracle rate taken at {rate_timestamp} is stale for timestamp {queried_timestamp}")]
+    StaleRateError { rate_timestamp: u64, queried_timestamp: u64 },
 }
@@ -4,7 +4,7 @@ use std::iter::Sum;
 use std::sync::Arc;
 
 use apollo_infra::component_client::ClientError;
-use apollo_infra::component_definitions::ComponentClient;
+use apollo_infra::component_definitions::{ComponentClient, PrioritizedRequest};
 use apollo_infra::impl_debug_for_infra_requests_and_responses;
 use apollo_proc_macros::handle_all_response_variants;
 use async_trait::async_trait;
@@ -28,6 +28,8 @@ pub struct GasPriceData {
     pub block_number: L1BlockNumber,
     pub timestamp: BlockTimestamp,
     pub price_info: PriceInfo,
+    /// The blob fee of the next L1 block, projected from this block's excess blob gas.
+    pub next_blob_fee: GasPrice,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -64,6 +66,8 @@ pub enum L1GasPriceRequest {
 }
 impl_debug_for_infra_requests_and_responses!(L1GasPriceRequest);
 
+impl PrioritizedRequest for L1GasPriceRequest {}
+
 #[derive(Clone, Serialize, Deserialize, AsRefStr)]
 pub enum L1GasPriceResponse {
     Initialize(L1GasPriceProviderResult<()>),
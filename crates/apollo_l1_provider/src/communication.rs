@@ -49,6 +49,14 @@ impl ComponentRequestHandler<L1ProviderRequest, L1ProviderResponse> for L1Provid
             L1ProviderRequest::Initialize(events) => {
                 L1ProviderResponse::Initialize(self.initialize(events).await)
             }
+            L1ProviderRequest::RetractEvents(events) => {
+                L1ProviderResponse::RetractEvents(self.retract_events(events))
+            }
+            L1ProviderRequest::UpdateRecentScrapedRounds(update) => {
+                L1ProviderResponse::UpdateRecentScrapedRounds(
+                    self.update_recent_scraped_rounds(update),
+                )
+            }
             L1ProviderRequest::GetL1ProviderSnapshot => {
                 L1ProviderResponse::GetL1ProviderSnapshot(self.get_l1_provider_snapshot())
             }
@@ -9,6 +9,8 @@ use apollo_l1_provider_types::{
     Event,
     L1ProviderResult,
     L1ProviderSnapshot,
+    ScrapedRound,
+    ScrapedRoundsUpdate,
     SessionState,
     SharedL1ProviderClient,
     ValidationStatus,
@@ -22,7 +24,9 @@ use starknet_api::transaction::TransactionHash;
 use tracing::{debug, error, info, instrument, trace, warn};
 
 use crate::bootstrapper::Bootstrapper;
+use crate::persistence::{PersistedL1ProviderState, StatePersister};
 use crate::transaction_manager::TransactionManager;
+use crate::transaction_record::TransactionState;
 use crate::{L1ProviderConfig, ProviderState};
 
 #[cfg(test)]
@@ -36,7 +40,11 @@ pub struct L1Provider {
     pub config: L1ProviderConfig,
     /// Represents the L2 block height being built.
     pub current_height: BlockNumber,
+    /// The scraper's recent rounds, oldest first, as last reported by it.
+    pub recent_scraped_rounds: Vec<ScrapedRound>,
     pub tx_manager: TransactionManager,
+    /// Set iff the provider is configured to persist its state.
+    pub state_persister: Option<Arc<StatePersister>>,
     // TODO(Gilad): consider transitioning to a generic phantom state once the infra is stabilized
     // and we see how well it handles consuming the L1Provider when moving between states.
     pub state: ProviderState,
@@ -131,13 +139,14 @@ impl L1Provider {
     ) -> L1ProviderResult<()> {
         if self.state.is_bootstrapping() {
             // Once bootstrap completes it will transition to Pending state by itself.
-            return self.bootstrap(committed_txs, height);
+            self.bootstrap(committed_txs, height)?;
+        } else {
+            self.validate_height(height)?;
+            self.apply_commit_block(committed_txs, rejected_txs);
+            self.state = self.state.transition_to_pending();
         }
 
-        self.validate_height(height)?;
-        self.apply_commit_block(committed_txs, rejected_txs);
-
-        self.state = self.state.transition_to_pending();
+        self.persist_state();
         Ok(())
     }
 
@@ -195,6 +204,71 @@ impl L1Provider {
         Ok(())
     }
 
+    /// Undoes events whose L1 blocks were orphaned by an L1 reorg. The events should be given in
+    /// the reverse order of their addition.
+    #[instrument(skip_all, err)]
+    pub fn retract_events(&mut self, events: Vec<Event>) -> L1ProviderResult<()> {
+        if self.state.uninitialized() {
+            return Err(L1ProviderError::Uninitialized);
+        }
+
+        info!("Retracting {} l1 events orphaned by an L1 reorg", events.len());
+        trace!("Retracting events: {events:?}");
+
+        for event in events {
+            match event {
+                Event::L1HandlerTransaction { l1_handler_tx, .. } => {
+                    let tx_hash = l1_handler_tx.tx_hash;
+                    match self.tx_manager.remove_tx(tx_hash) {
+                        Some(TransactionState::Committed) => error!(
+                            "L1 handler transaction {tx_hash} was orphaned by an L1 reorg after \
+                             being committed on L2."
+                        ),
+                        Some(_) => {}
+                        None => debug!(
+                            "Cannot retract unknown L1 handler transaction with hash: {tx_hash}."
+                        ),
+                    }
+                }
+                Event::TransactionCancellationStarted { tx_hash, .. } => {
+                    if !self.tx_manager.retract_cancellation_request(tx_hash) {
+                        debug!(
+                            "Cannot retract cancellation request of unknown L1 handler \
+                             transaction with hash: {tx_hash}."
+                        );
+                    }
+                }
+                _ => return Err(L1ProviderError::unsupported_l1_event(event)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies the changes to the scraper's recent rounds, which are persisted along with the
+    /// records. The transactions of rounds that left the scraper's window are deeper than the max
+    /// L1 reorg depth, so they are marked final on L1, which lets their records be pruned once
+    /// committed.
+    pub fn update_recent_scraped_rounds(
+        &mut self,
+        update: ScrapedRoundsUpdate,
+    ) -> L1ProviderResult<()> {
+        let final_rounds = update.apply(&mut self.recent_scraped_rounds);
+        trace!(
+            "Recent scraped rounds updated to end at {:?}",
+            self.recent_scraped_rounds.last().map(|round| round.last_l1_block)
+        );
+        let final_tx_hashes = final_rounds
+            .iter()
+            .flat_map(|round| &round.events)
+            .filter_map(|event| match event {
+                Event::L1HandlerTransaction { l1_handler_tx, .. } => Some(l1_handler_tx.tx_hash),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        self.tx_manager.mark_final_on_l1(final_tx_hashes);
+        Ok(())
+    }
+
     pub fn get_l1_provider_snapshot(&self) -> L1ProviderResult<L1ProviderSnapshot> {
         let txs_snapshot = self.tx_manager.snapshot();
         Ok(L1ProviderSnapshot {
@@ -208,6 +282,23 @@ impl L1Provider {
         })
     }
 
+    /// Persists the records in the background, if configured to, so that a restarted provider can
+    /// resume from them. Nothing is persisted before the scraper reports its first round.
+    fn persist_state(&self) {
+        let Some(state_persister) = &self.state_persister else {
+            return;
+        };
+        if self.recent_scraped_rounds.is_empty() {
+            return;
+        }
+
+        state_persister.persist(PersistedL1ProviderState {
+            current_height: self.current_height,
+            recent_scraped_rounds: self.recent_scraped_rounds.clone(),
+            records: self.tx_manager.records.clone(),
+        });
+    }
+
     fn validate_height(&mut self, height: BlockNumber) -> L1ProviderResult<()> {
         if height != self.current_height {
             return Err(L1ProviderError::UnexpectedHeight {
@@ -328,6 +419,7 @@ pub struct L1ProviderBuilder {
     pub state_sync_client: SharedStateSyncClient,
     startup_height: Option<BlockNumber>,
     catchup_height: Option<BlockNumber>,
+    persisted_state: Option<PersistedL1ProviderState>,
     clock: Option<Arc<dyn Clock>>,
 }
 
@@ -345,6 +437,7 @@ impl L1ProviderBuilder {
             state_sync_client,
             startup_height: None,
             catchup_height: None,
+            persisted_state: None,
             clock: None,
         }
     }
@@ -359,6 +452,13 @@ impl L1ProviderBuilder {
         self
     }
 
+    /// Resumes from a persisted state instead of an empty one, starting at its height.
+    pub fn persisted_state(mut self, persisted_state: PersistedL1ProviderState) -> Self {
+        self.startup_height = Some(persisted_state.current_height);
+        self.persisted_state = Some(persisted_state);
+        self
+    }
+
     pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
         self.clock = Some(clock);
         self
@@ -407,12 +507,28 @@ impl L1ProviderBuilder {
             catchup_height,
         );
 
+        let mut tx_manager = TransactionManager::new(
+            self.config.new_l1_handler_cooldown_seconds,
+            self.config.l1_handler_cancellation_timelock_seconds,
+        );
+        let recent_scraped_rounds = self
+            .persisted_state
+            .map(|persisted_state| {
+                tx_manager.restore_records(persisted_state.records);
+                persisted_state.recent_scraped_rounds
+            })
+            .unwrap_or_default();
+        let state_persister = self
+            .config
+            .persisted_state_path
+            .clone()
+            .map(|path| Arc::new(StatePersister::new(path)));
+
         L1Provider {
             current_height: l1_provider_startup_height,
-            tx_manager: TransactionManager::new(
-                self.config.new_l1_handler_cooldown_seconds,
-                self.config.l1_handler_cancellation_timelock_seconds,
-            ),
+            recent_scraped_rounds,
+            tx_manager,
+            state_persister,
             state: ProviderState::Bootstrap(bootstrapper),
             config: self.config,
             clock: self.clock.unwrap_or_else(|| Arc::new(DefaultClock)),
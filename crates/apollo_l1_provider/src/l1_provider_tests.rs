@@ -1,3 +1,4 @@
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -8,11 +9,18 @@ use apollo_l1_provider_types::SessionState::{
     Propose as ProposeSession,
     Validate as ValidateSession,
 };
-use apollo_l1_provider_types::{Event, InvalidValidationStatus, ValidationStatus};
+use apollo_l1_provider_types::{
+    Event,
+    InvalidValidationStatus,
+    ScrapedRound,
+    ScrapedRoundsUpdate,
+    ValidationStatus,
+};
 use apollo_state_sync_types::communication::MockStateSyncClient;
 use apollo_time::test_utils::FakeClock;
 use assert_matches::assert_matches;
 use itertools::Itertools;
+use papyrus_base_layer::{L1BlockReference, MockBaseLayerContract};
 use pretty_assertions::assert_eq;
 use rstest::rstest;
 use starknet_api::block::{BlockNumber, BlockTimestamp};
@@ -21,7 +29,8 @@ use starknet_api::transaction::TransactionHash;
 use starknet_api::tx_hash;
 
 use crate::bootstrapper::{Bootstrapper, CommitBlockBacklog, SyncTaskHandle};
-use crate::l1_provider::L1Provider;
+use crate::l1_provider::{L1Provider, L1ProviderBuilder};
+use crate::persistence::{load_persisted_state, PersistedL1ProviderState};
 use crate::test_utils::{l1_handler, FakeL1ProviderClient, L1ProviderContentBuilder};
 use crate::{L1ProviderConfig, ProviderState};
 
@@ -33,6 +42,20 @@ fn commit_block_no_rejected(
     l1_provider.commit_block(txs.iter().copied().collect(), [].into(), block_number).unwrap();
 }
 
+/// Waits for the provider's background persister to write a state at the given height.
+async fn wait_for_persisted_height(path: &Path, height: BlockNumber) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            match PersistedL1ProviderState::read_from_file(path) {
+                Ok(Some(state)) if state.current_height == height => return,
+                _ => tokio::time::sleep(Duration::from_millis(10)).await,
+            }
+        }
+    })
+    .await
+    .expect("The L1 provider state was not persisted in time.");
+}
+
 fn setup_rejected_transactions() -> L1Provider {
     let tx_id1 = 1;
     let tx_id2 = 2;
@@ -947,6 +970,156 @@ fn add_events_double_cancellation_only_first_counted() {
     expected.assert_eq(&l1_provider);
 }
 
+#[test]
+fn retract_events_undoes_orphaned_events() {
+    // Setup.
+    let tx_pending = l1_handler(1);
+    let tx_cancel_requested = l1_handler(2);
+    let tx_committed = l1_handler(3);
+    let mut l1_provider = L1ProviderContentBuilder::new()
+        .with_txs([tx_pending.clone()])
+        .with_cancel_requested_txs([tx_cancel_requested.clone()])
+        .with_committed([tx_committed.clone()])
+        .with_state(ProviderState::Propose)
+        .build_into_l1_provider();
+
+    // Test.
+    let orphaned_events = vec![
+        cancellation_event(tx_cancel_requested.tx_hash, 0.into()),
+        l1_handler_event(tx_pending.tx_hash),
+        l1_handler_event(tx_committed.tx_hash),
+    ];
+    l1_provider.retract_events(orphaned_events).unwrap();
+
+    // The pending transaction is removed and the cancelled one is proposable again, but the
+    // committed transaction is kept, since its commitment can't be undone.
+    assert_eq!(
+        l1_provider.get_txs(2, l1_provider.current_height).unwrap(),
+        [tx_cancel_requested.clone()]
+    );
+    let expected = L1ProviderContentBuilder::new()
+        .with_txs([tx_cancel_requested])
+        .with_cancel_requested_txs([])
+        .with_committed([tx_committed])
+        .build();
+    expected.assert_eq(&l1_provider);
+}
+
+#[tokio::test]
+async fn persisted_state_restores_records() {
+    // Setup.
+    let persistence_dir = tempfile::tempdir().unwrap();
+    let persisted_state_path = persistence_dir.path().join("l1_provider_state.json");
+    let config = L1ProviderConfig {
+        persisted_state_path: Some(persisted_state_path.clone()),
+        ..Default::default()
+    };
+    let tx_committed = l1_handler(1);
+    let tx_pending = l1_handler(2);
+    let mut l1_provider = L1ProviderContentBuilder::new()
+        .with_config(config.clone())
+        .with_txs([tx_committed.clone(), tx_pending.clone()])
+        .with_height(BlockNumber(1))
+        .with_state(ProviderState::Pending)
+        .build_into_l1_provider();
+    let last_l1_block_processed = L1BlockReference { number: 10, hash: [1; 32] };
+    let recent_scraped_rounds = vec![
+        ScrapedRound {
+            last_l1_block: L1BlockReference { number: 9, hash: [3; 32] },
+            events: vec![l1_handler_event(tx_committed.tx_hash)],
+        },
+        ScrapedRound {
+            last_l1_block: last_l1_block_processed,
+            events: vec![l1_handler_event(tx_pending.tx_hash)],
+        },
+    ];
+    l1_provider
+        .update_recent_scraped_rounds(ScrapedRoundsUpdate {
+            last_kept_l1_block: None,
+            new_rounds: recent_scraped_rounds.clone(),
+            oldest_l1_block_number: 9,
+        })
+        .unwrap();
+
+    // Test.
+    commit_block_no_rejected(&mut l1_provider, &[tx_committed.tx_hash], BlockNumber(1));
+    wait_for_persisted_height(&persisted_state_path, BlockNumber(2)).await;
+
+    let mut base_layer = MockBaseLayerContract::new();
+    base_layer.expect_l1_block_at().returning(move |_| Ok(Some(last_l1_block_processed)));
+    let persisted_state = load_persisted_state(&persisted_state_path, &base_layer).await.unwrap();
+    let mut restored_l1_provider = L1ProviderBuilder::new(
+        config,
+        Arc::new(FakeL1ProviderClient::default()),
+        Arc::new(MockBatcherClient::default()),
+        Arc::new(MockStateSyncClient::default()),
+    )
+    .persisted_state(persisted_state)
+    .build();
+
+    assert_eq!(restored_l1_provider.current_height, BlockNumber(2));
+    assert_eq!(restored_l1_provider.recent_scraped_rounds, recent_scraped_rounds);
+    assert_eq!(restored_l1_provider.tx_manager.records, l1_provider.tx_manager.records);
+    let now = restored_l1_provider.clock.unix_now();
+    assert_eq!(restored_l1_provider.tx_manager.get_txs(2, now), [tx_pending]);
+
+    // A state whose last processed L1 block was orphaned by an L1 reorg is discarded.
+    let mut reorged_base_layer = MockBaseLayerContract::new();
+    reorged_base_layer
+        .expect_l1_block_at()
+        .returning(|number| Ok(Some(L1BlockReference { number, hash: [2; 32] })));
+    assert_eq!(load_persisted_state(&persisted_state_path, &reorged_base_layer).await, None);
+}
+
+#[test]
+fn committed_records_are_pruned_once_final_on_l1() {
+    // Setup.
+    let tx_committed_before_final = l1_handler(1);
+    let tx_committed_after_final = l1_handler(2);
+    let tx_committed_in_reorg_window = l1_handler(3);
+    let mut l1_provider = L1ProviderContentBuilder::new()
+        .with_txs([tx_committed_after_final.clone()])
+        .with_committed([tx_committed_before_final.clone(), tx_committed_in_reorg_window.clone()])
+        .with_state(ProviderState::Pending)
+        .build_into_l1_provider();
+    let round = |number, tx_hashes: &[TransactionHash]| ScrapedRound {
+        last_l1_block: L1BlockReference { number, hash: [1; 32] },
+        events: tx_hashes.iter().map(|&tx_hash| l1_handler_event(tx_hash)).collect(),
+    };
+    let oldest_round =
+        round(1, &[tx_committed_before_final.tx_hash, tx_committed_after_final.tx_hash]);
+    let recent_round = round(2, &[tx_committed_in_reorg_window.tx_hash]);
+    l1_provider
+        .update_recent_scraped_rounds(ScrapedRoundsUpdate {
+            last_kept_l1_block: None,
+            new_rounds: vec![oldest_round, recent_round.clone()],
+            oldest_l1_block_number: 1,
+        })
+        .unwrap();
+
+    // Test.
+    // The oldest round leaves the scraper's reorg window.
+    l1_provider
+        .update_recent_scraped_rounds(ScrapedRoundsUpdate {
+            last_kept_l1_block: Some(recent_round.last_l1_block),
+            new_rounds: vec![round(3, &[])],
+            oldest_l1_block_number: 2,
+        })
+        .unwrap();
+    assert_eq!(l1_provider.recent_scraped_rounds, [recent_round, round(3, &[])]);
+    assert!(!l1_provider.tx_manager.exists(tx_committed_before_final.tx_hash));
+    assert!(l1_provider.tx_manager.exists(tx_committed_after_final.tx_hash));
+
+    commit_block_no_rejected(
+        &mut l1_provider,
+        &[tx_committed_after_final.tx_hash],
+        l1_provider.current_height,
+    );
+    assert!(!l1_provider.tx_manager.exists(tx_committed_after_final.tx_hash));
+    // Its event can still be retracted by an L1 reorg, and scraped again from the new chain.
+    assert!(l1_provider.tx_manager.is_committed(tx_committed_in_reorg_window.tx_hash));
+}
+
 #[test]
 fn validate_tx_unknown_returns_invalid_consumed_or_unknown() {
     let mut l1_provider = L1ProviderContentBuilder::new()
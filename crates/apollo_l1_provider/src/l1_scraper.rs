@@ -1,5 +1,5 @@
 use std::any::type_name;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, VecDeque};
 use std::time::Duration;
 
 use apollo_config::converters::deserialize_float_seconds_to_duration;
@@ -10,7 +10,7 @@ use apollo_infra::component_client::ClientError;
 use apollo_infra::component_definitions::ComponentStarter;
 use apollo_infra_utils::info_every_n;
 use apollo_l1_provider_types::errors::{L1ProviderClientError, L1ProviderError};
-use apollo_l1_provider_types::{Event, SharedL1ProviderClient};
+use apollo_l1_provider_types::{Event, ScrapedRound, ScrapedRoundsUpdate, SharedL1ProviderClient};
 use async_trait::async_trait;
 use itertools::zip_eq;
 use papyrus_base_layer::constants::EventIdentifier;
@@ -45,6 +45,13 @@ pub struct L1Scraper<B: BaseLayerContract> {
     pub last_l1_block_processed: L1BlockReference,
     pub l1_provider_client: SharedL1ProviderClient,
     tracked_event_identifiers: Vec<EventIdentifier>,
+    /// The recent scraping rounds, oldest first, used to rewind to a common ancestor on L1 reorgs.
+    /// There is one round per L1 block, and the last round always ends at
+    /// `last_l1_block_processed`.
+    recent_rounds: VecDeque<ScrapedRound>,
+    /// The number of oldest rounds in `recent_rounds` that were reported to the provider and
+    /// didn't change since.
+    num_reported_rounds: usize,
 }
 
 impl<B: BaseLayerContract + Send + Sync> L1Scraper<B> {
@@ -55,12 +62,38 @@ impl<B: BaseLayerContract + Send + Sync> L1Scraper<B> {
         events_identifiers_to_track: &[EventIdentifier],
         l1_start_block: L1BlockReference,
     ) -> L1ScraperResult<Self, B> {
+        Self::resume(
+            config,
+            l1_provider_client,
+            base_layer,
+            events_identifiers_to_track,
+            vec![ScrapedRound { last_l1_block: l1_start_block, events: vec![] }],
+        )
+        .await
+    }
+
+    /// Creates a scraper that continues from the recent rounds of a previous run, oldest first, so
+    /// that L1 reorgs can still rewind into them and retract their events.
+    pub async fn resume(
+        config: L1ScraperConfig,
+        l1_provider_client: SharedL1ProviderClient,
+        base_layer: B,
+        events_identifiers_to_track: &[EventIdentifier],
+        recent_rounds: Vec<ScrapedRound>,
+    ) -> L1ScraperResult<Self, B> {
+        let last_l1_block_processed = recent_rounds
+            .last()
+            .expect("The scraper must resume from at least one round.")
+            .last_l1_block;
         Ok(Self {
             l1_provider_client,
             base_layer,
-            last_l1_block_processed: l1_start_block,
+            last_l1_block_processed,
             config,
             tracked_event_identifiers: events_identifiers_to_track.to_vec(),
+            recent_rounds: recent_rounds.into(),
+            // The first report replaces the provider's rounds.
+            num_reported_rounds: 0,
         })
     }
 
@@ -69,16 +102,17 @@ impl<B: BaseLayerContract + Send + Sync> L1Scraper<B> {
         let (latest_l1_block, events) = self.fetch_events().await?;
 
         // If this gets too high, send in batches.
-        let initialize_result = self.l1_provider_client.initialize(events).await;
+        let initialize_result = self.l1_provider_client.initialize(events.clone()).await;
         handle_client_error(initialize_result)?;
 
-        self.last_l1_block_processed = latest_l1_block;
+        self.record_round(latest_l1_block, events);
+        self.report_recent_rounds().await?;
 
         Ok(())
     }
 
     pub async fn send_events_to_l1_provider(&mut self) -> L1ScraperResult<(), B> {
-        self.assert_no_l1_reorgs().await?;
+        self.handle_l1_reorgs().await?;
 
         let (latest_l1_block, events) = self.fetch_events().await?;
         trace!("scraped up to {latest_l1_block:?}");
@@ -88,14 +122,69 @@ impl<B: BaseLayerContract + Send + Sync> L1Scraper<B> {
         // Sending even if there are no events, to keep the flow as simple/debuggable as possible.
         // Perf hit is minimal, since the scraper is on the same machine as the provider (no net).
         // If this gets spammy, short-circuit on events.empty().
-        let add_events_result = self.l1_provider_client.add_events(events).await;
+        let add_events_result = self.l1_provider_client.add_events(events.clone()).await;
         handle_client_error(add_events_result)?;
 
-        self.last_l1_block_processed = latest_l1_block;
+        self.record_round(latest_l1_block, events);
+        self.report_recent_rounds().await?;
 
         Ok(())
     }
 
+    fn record_round(&mut self, last_l1_block: L1BlockReference, events: Vec<Event>) {
+        self.last_l1_block_processed = last_l1_block;
+        match self.recent_rounds.back_mut() {
+            // No new L1 blocks since the previous round.
+            Some(last_round) if last_round.last_l1_block == last_l1_block => {
+                if !events.is_empty() {
+                    last_round.events.extend(events);
+                    self.num_reported_rounds =
+                        self.num_reported_rounds.min(self.recent_rounds.len() - 1);
+                }
+            }
+            _ => self.recent_rounds.push_back(ScrapedRound { last_l1_block, events }),
+        }
+
+        // Keep the newest round that is at least `max_l1_reorg_depth` blocks deep, as the
+        // fallback common ancestor for the deepest supported reorg.
+        while self.recent_rounds.len() > 1
+            && self.recent_rounds[1].last_l1_block.number + self.config.max_l1_reorg_depth
+                <= last_l1_block.number
+        {
+            self.recent_rounds.pop_front();
+            self.num_reported_rounds = self.num_reported_rounds.saturating_sub(1);
+        }
+    }
+
+    /// Lets the provider persist the recent rounds along with its records, so that a restarted
+    /// scraper resumes from the L1 block it is synced to and can still rewind past it on L1
+    /// reorgs. Only the rounds that changed since the previous report are sent.
+    async fn report_recent_rounds(&mut self) -> L1ScraperResult<(), B> {
+        // The oldest rounds are only dropped along with adding a new round, which is unreported,
+        // so the provider's rounds are up to date.
+        if self.num_reported_rounds == self.recent_rounds.len() {
+            return Ok(());
+        }
+        let update = ScrapedRoundsUpdate {
+            last_kept_l1_block: self
+                .num_reported_rounds
+                .checked_sub(1)
+                .map(|index| self.recent_rounds[index].last_l1_block),
+            new_rounds: self.recent_rounds.range(self.num_reported_rounds..).cloned().collect(),
+            oldest_l1_block_number: self
+                .recent_rounds
+                .front()
+                .expect("The scraper always has at least one round.")
+                .last_l1_block
+                .number,
+        };
+        let update_recent_scraped_rounds_result =
+            self.l1_provider_client.update_recent_scraped_rounds(update).await;
+        handle_client_error(update_recent_scraped_rounds_result)?;
+        self.num_reported_rounds = self.recent_rounds.len();
+        Ok(())
+    }
+
     async fn fetch_events(&self) -> L1ScraperResult<(L1BlockReference, Vec<Event>), B> {
         let latest_l1_block = self
             .base_layer
@@ -179,39 +268,69 @@ impl<B: BaseLayerContract + Send + Sync> L1Scraper<B> {
         }
     }
 
-    async fn assert_no_l1_reorgs(&self) -> L1ScraperResult<(), B> {
-        let last_processed_l1_block_number = self.last_l1_block_processed.number;
-        let last_block_processed_fresh = self
-            .base_layer
-            .l1_block_at(last_processed_l1_block_number)
-            .await
-            .map_err(L1ScraperError::BaseLayerError)?;
+    /// If the last processed L1 block was orphaned, rewinds to the newest recently processed block
+    /// that is still in the canonical chain, retracting the events of all the orphaned rounds from
+    /// the provider, so that they are re-scraped from the new chain.
+    async fn handle_l1_reorgs(&mut self) -> L1ScraperResult<(), B> {
+        if self.is_canonical(self.last_l1_block_processed).await? {
+            return Ok(());
+        }
+        L1_MESSAGE_SCRAPER_REORG_DETECTED.increment(1);
 
-        let Some(last_block_processed_fresh) = last_block_processed_fresh else {
-            L1_MESSAGE_SCRAPER_REORG_DETECTED.increment(1);
+        let mut common_ancestor_index = None;
+        for (index, round) in self.recent_rounds.iter().enumerate().rev() {
+            if self.is_canonical(round.last_l1_block).await? {
+                common_ancestor_index = Some(index);
+                break;
+            }
+        }
+        let Some(common_ancestor_index) = common_ancestor_index else {
             return Err(L1ScraperError::L1ReorgDetected {
                 reason: format!(
-                    "Last processed L1 block with number {last_processed_l1_block_number} no \
-                     longer exists."
+                    "None of the L1 blocks processed since block {} are in the canonical chain, \
+                     the reorg is deeper than the max L1 reorg depth {}",
+                    self.recent_rounds[0].last_l1_block.number, self.config.max_l1_reorg_depth
                 ),
             });
         };
 
-        if last_block_processed_fresh.hash != self.last_l1_block_processed.hash {
-            L1_MESSAGE_SCRAPER_REORG_DETECTED.increment(1);
-            return Err(L1ScraperError::L1ReorgDetected {
-                reason: format!(
-                    "Last processed L1 block hash, {}, for block number {}, is different from the \
-                     hash stored, {}",
-                    hex::encode(last_block_processed_fresh.hash),
-                    last_processed_l1_block_number,
-                    hex::encode(self.last_l1_block_processed.hash),
-                ),
-            });
-        }
+        let common_ancestor = self.recent_rounds[common_ancestor_index].last_l1_block;
+        // Retract in the reverse order of addition.
+        let orphaned_events: Vec<Event> = self
+            .recent_rounds
+            .range(common_ancestor_index + 1..)
+            .rev()
+            .flat_map(|round| round.events.iter().rev().cloned())
+            .collect();
+        warn!(
+            "L1 reorg detected: last processed L1 block {} with hash {} was orphaned. Rewinding \
+             to L1 block {} and retracting {} events.",
+            self.last_l1_block_processed.number,
+            hex::encode(self.last_l1_block_processed.hash),
+            common_ancestor.number,
+            orphaned_events.len()
+        );
+
+        let retract_events_result = self.l1_provider_client.retract_events(orphaned_events).await;
+        handle_client_error(retract_events_result)?;
+
+        self.recent_rounds.truncate(common_ancestor_index + 1);
+        // The common ancestor's round is reported again, so that the provider drops the orphaned
+        // rounds after it even if no newer L1 block is scraped.
+        self.num_reported_rounds = self.num_reported_rounds.min(common_ancestor_index);
+        self.last_l1_block_processed = common_ancestor;
 
         Ok(())
     }
+
+    async fn is_canonical(&self, l1_block: L1BlockReference) -> L1ScraperResult<bool, B> {
+        let canonical_l1_block = self
+            .base_layer
+            .l1_block_at(l1_block.number)
+            .await
+            .map_err(L1ScraperError::BaseLayerError)?;
+        Ok(canonical_l1_block.is_some_and(|canonical_l1_block| canonical_l1_block == l1_block))
+    }
 }
 
 pub async fn fetch_start_block<B: BaseLayerContract + Send + Sync>(
@@ -265,6 +384,7 @@ pub struct L1ScraperConfig {
     pub finality: u64,
     #[serde(deserialize_with = "deserialize_float_seconds_to_duration")]
     pub polling_interval_seconds: Duration,
+    pub max_l1_reorg_depth: u64,
 }
 
 impl Default for L1ScraperConfig {
@@ -274,6 +394,7 @@ impl Default for L1ScraperConfig {
             chain_id: ChainId::Mainnet,
             finality: 0,
             polling_interval_seconds: Duration::from_secs(1),
+            max_l1_reorg_depth: 64,
         }
     }
 }
@@ -299,6 +420,13 @@ impl SerializeConfig for L1ScraperConfig {
                 "Interval in Seconds between each scraping attempt of L1.",
                 ParamPrivacyInput::Public,
             ),
+            ser_param(
+                "max_l1_reorg_depth",
+                &self.max_l1_reorg_depth,
+                "The depth, in L1 blocks, of the deepest L1 reorg the scraper recovers from by \
+                 rewinding. Deeper reorgs require restarting the L1 provider and the scraper.",
+                ParamPrivacyInput::Public,
+            ),
             ser_param(
                 "chain_id",
                 &self.chain_id,
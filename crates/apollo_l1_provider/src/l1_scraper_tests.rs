@@ -6,20 +6,23 @@ use apollo_batcher_types::batcher_types::GetHeightResponse;
 use apollo_batcher_types::communication::MockBatcherClient;
 use apollo_infra::trace_util::configure_tracing;
 use apollo_l1_provider_types::errors::L1ProviderError;
-use apollo_l1_provider_types::{L1ProviderClient, MockL1ProviderClient};
+use apollo_l1_provider_types::{Event, L1ProviderClient, MockL1ProviderClient};
 use apollo_state_sync_types::communication::MockStateSyncClient;
 use apollo_state_sync_types::state_sync_types::SyncBlock;
 use assert_matches::assert_matches;
 use indexmap::IndexSet;
 use itertools::Itertools;
-use papyrus_base_layer::{L1BlockReference, MockBaseLayerContract};
+use papyrus_base_layer::fake_base_layer::FakeBaseLayerContract;
+use papyrus_base_layer::{L1BlockReference, L1Event, MockBaseLayerContract};
 use rstest::{fixture, rstest};
-use starknet_api::block::BlockNumber;
-use starknet_api::transaction::TransactionHash;
+use starknet_api::block::{BlockNumber, BlockTimestamp};
+use starknet_api::nonce;
+use starknet_api::transaction::fields::Fee;
+use starknet_api::transaction::{L1HandlerTransaction, TransactionHash};
 
 use crate::bootstrapper::Bootstrapper;
 use crate::l1_provider::{L1Provider, L1ProviderBuilder};
-use crate::l1_scraper::{L1Scraper, L1ScraperConfig, L1ScraperError};
+use crate::l1_scraper::{fetch_start_block, L1Scraper, L1ScraperConfig, L1ScraperError};
 use crate::test_utils::FakeL1ProviderClient;
 use crate::{event_identifiers_to_track, L1ProviderConfig};
 
@@ -89,7 +92,7 @@ async fn bootstrap_e2e() {
         ..Default::default()
     };
     let mut l1_provider = L1ProviderBuilder::new(
-        config,
+        config.clone(),
         l1_provider_client.clone(),
         Arc::new(batcher_client),
         Arc::new(sync_client),
@@ -202,7 +205,7 @@ async fn bootstrap_delayed_batcher_and_sync_state_with_trivial_catch_up() {
         ..Default::default()
     };
     let mut l1_provider = L1ProviderBuilder::new(
-        config,
+        config.clone(),
         l1_provider_client.clone(),
         Arc::new(batcher_client),
         Arc::new(sync_client),
@@ -279,7 +282,7 @@ async fn bootstrap_delayed_sync_state_with_sync_behind_batcher() {
         ..Default::default()
     };
     let mut l1_provider = L1ProviderBuilder::new(
-        config,
+        config.clone(),
         l1_provider_client.clone(),
         Arc::new(batcher_client),
         Arc::new(sync_client),
@@ -345,9 +348,9 @@ async fn test_stuck_sync() {
 
     let sync_client = MockStateSyncClient::default();
     let l1_provider_client = Arc::new(FakeL1ProviderClient::default());
-    let config = Default::default();
+    let config = L1ProviderConfig::default();
     let mut l1_provider = L1ProviderBuilder::new(
-        config,
+        config.clone(),
         l1_provider_client.clone(),
         Arc::new(batcher_client),
         Arc::new(sync_client),
@@ -416,6 +419,7 @@ async fn l1_reorg_block_hash(mut dummy_base_layer: MockBaseLayerContract) {
         .returning(move |_| Ok(*l1_block_at_response_clone.lock().unwrap()));
 
     l1_provider_client.expect_add_events().times(1).returning(|_| Ok(()));
+    l1_provider_client.expect_update_recent_scraped_rounds().returning(|_| Ok(()));
     let mut scraper = L1Scraper::new(
         L1ScraperConfig::default(),
         Arc::new(l1_provider_client),
@@ -447,6 +451,7 @@ async fn l1_reorg_block_number(mut dummy_base_layer: MockBaseLayerContract) {
     // Setup.
     let mut l1_provider_client = MockL1ProviderClient::default();
     l1_provider_client.expect_add_events().returning(|_| Ok(()));
+    l1_provider_client.expect_update_recent_scraped_rounds().returning(|_| Ok(()));
 
     let l1_block_at_response = Arc::new(Mutex::new(Some(Default::default())));
     let l1_block_at_response_clone = l1_block_at_response.clone();
@@ -477,6 +482,168 @@ async fn l1_reorg_block_number(mut dummy_base_layer: MockBaseLayerContract) {
     );
 }
 
+fn log_message_to_l2(nonce: u64) -> L1Event {
+    L1Event::LogMessageToL2 {
+        tx: L1HandlerTransaction { nonce: nonce!(nonce), ..Default::default() },
+        fee: Fee(1),
+        l1_tx_hash: None,
+        timestamp: BlockTimestamp(0),
+    }
+}
+
+/// Mines a block containing the given events, whose timestamps match the block's.
+fn add_block(base_layer: &FakeBaseLayerContract, events: Vec<L1Event>) -> L1BlockReference {
+    for event in events {
+        base_layer.add_event(event);
+    }
+    base_layer.mine_block(Some(BlockTimestamp(0)))
+}
+
+fn to_event(l1_event: L1Event) -> Event {
+    Event::from_l1_event(&L1ScraperConfig::default().chain_id, l1_event).unwrap()
+}
+
+async fn scraper_with_fake_base_layer(
+    config: L1ScraperConfig,
+    l1_provider_client: Arc<FakeL1ProviderClient>,
+) -> L1Scraper<FakeBaseLayerContract> {
+    let base_layer = FakeBaseLayerContract::default();
+    let l1_start_block = fetch_start_block(&base_layer, &config).await.unwrap();
+    L1Scraper::new(
+        config,
+        l1_provider_client,
+        base_layer,
+        event_identifiers_to_track(),
+        l1_start_block,
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn l1_reorg_rewinds_to_common_ancestor() {
+    // Setup.
+    let l1_provider_client = Arc::new(FakeL1ProviderClient::default());
+    let mut scraper =
+        scraper_with_fake_base_layer(L1ScraperConfig::default(), l1_provider_client.clone()).await;
+
+    add_block(&scraper.base_layer, vec![log_message_to_l2(1)]);
+    scraper.send_events_to_l1_provider().await.unwrap();
+    add_block(&scraper.base_layer, vec![log_message_to_l2(2)]);
+    scraper.send_events_to_l1_provider().await.unwrap();
+    l1_provider_client.assert_add_events_received_with(&[
+        to_event(log_message_to_l2(1)),
+        to_event(log_message_to_l2(2)),
+    ]);
+
+    // Test.
+
+    // Orphan the last scraped block; its message is included again later in the new fork.
+    scraper.base_layer.reorg(1).unwrap();
+    add_block(&scraper.base_layer, vec![log_message_to_l2(3)]);
+    let new_tip = add_block(&scraper.base_layer, vec![log_message_to_l2(2)]);
+
+    scraper.send_events_to_l1_provider().await.unwrap();
+
+    // Only the events of the orphaned block are retracted, then the new fork is scraped from the
+    // common ancestor.
+    l1_provider_client.assert_retract_events_received_with(&[to_event(log_message_to_l2(2))]);
+    l1_provider_client.assert_add_events_received_with(&[
+        to_event(log_message_to_l2(3)),
+        to_event(log_message_to_l2(2)),
+    ]);
+    assert_eq!(scraper.last_l1_block_processed, new_tip);
+}
+
+#[tokio::test]
+async fn resumed_scraper_rewinds_into_rounds_of_previous_run() {
+    // Setup.
+    let l1_provider_client = Arc::new(FakeL1ProviderClient::default());
+    let mut scraper =
+        scraper_with_fake_base_layer(L1ScraperConfig::default(), l1_provider_client.clone()).await;
+    add_block(&scraper.base_layer, vec![log_message_to_l2(1)]);
+    scraper.send_events_to_l1_provider().await.unwrap();
+    add_block(&scraper.base_layer, vec![log_message_to_l2(2)]);
+    scraper.send_events_to_l1_provider().await.unwrap();
+    l1_provider_client.assert_add_events_received_with(&[
+        to_event(log_message_to_l2(1)),
+        to_event(log_message_to_l2(2)),
+    ]);
+    let recent_rounds = l1_provider_client.recent_scraped_rounds.lock().unwrap().clone();
+
+    // Test.
+
+    // Restart from the reported rounds, then orphan the last block scraped before the restart.
+    let mut resumed_scraper = L1Scraper::resume(
+        L1ScraperConfig::default(),
+        l1_provider_client.clone(),
+        scraper.base_layer.clone(),
+        event_identifiers_to_track(),
+        recent_rounds,
+    )
+    .await
+    .unwrap();
+    resumed_scraper.base_layer.reorg(1).unwrap();
+    let new_tip = add_block(&resumed_scraper.base_layer, vec![log_message_to_l2(3)]);
+
+    resumed_scraper.send_events_to_l1_provider().await.unwrap();
+
+    l1_provider_client.assert_retract_events_received_with(&[to_event(log_message_to_l2(2))]);
+    l1_provider_client.assert_add_events_received_with(&[to_event(log_message_to_l2(3))]);
+    assert_eq!(resumed_scraper.last_l1_block_processed, new_tip);
+}
+
+#[tokio::test]
+async fn reported_rounds_keep_one_round_per_l1_block() {
+    // Setup.
+    let l1_provider_client = Arc::new(FakeL1ProviderClient::default());
+    let mut scraper =
+        scraper_with_fake_base_layer(L1ScraperConfig::default(), l1_provider_client.clone()).await;
+    let reported_rounds = || l1_provider_client.recent_scraped_rounds.lock().unwrap().clone();
+
+    // Test.
+
+    // Scraping again without new L1 blocks doesn't add rounds.
+    add_block(&scraper.base_layer, vec![log_message_to_l2(1)]);
+    scraper.send_events_to_l1_provider().await.unwrap();
+    scraper.send_events_to_l1_provider().await.unwrap();
+    add_block(&scraper.base_layer, vec![log_message_to_l2(2)]);
+    scraper.send_events_to_l1_provider().await.unwrap();
+    scraper.send_events_to_l1_provider().await.unwrap();
+    assert_eq!(scraper.recent_rounds.len(), 3);
+    assert_eq!(reported_rounds(), Vec::from(scraper.recent_rounds.clone()));
+
+    // Rewinding to the common ancestor without scraping newer blocks drops the orphaned round
+    // from the provider too.
+    scraper.base_layer.reorg(1).unwrap();
+    scraper.send_events_to_l1_provider().await.unwrap();
+    assert_eq!(scraper.recent_rounds.len(), 2);
+    assert_eq!(reported_rounds(), Vec::from(scraper.recent_rounds.clone()));
+}
+
+#[tokio::test]
+async fn l1_reorg_deeper_than_max_depth() {
+    // Setup.
+    let l1_provider_client = Arc::new(FakeL1ProviderClient::default());
+    let config = L1ScraperConfig { max_l1_reorg_depth: 1, ..Default::default() };
+    let mut scraper = scraper_with_fake_base_layer(config, l1_provider_client.clone()).await;
+    for nonce in 1..=3 {
+        add_block(&scraper.base_layer, vec![log_message_to_l2(nonce)]);
+        scraper.send_events_to_l1_provider().await.unwrap();
+    }
+
+    // Test.
+    scraper.base_layer.reorg(2).unwrap();
+    add_block(&scraper.base_layer, vec![]);
+    add_block(&scraper.base_layer, vec![]);
+
+    assert_matches!(
+        scraper.send_events_to_l1_provider().await,
+        Err(L1ScraperError::L1ReorgDetected { .. })
+    );
+    l1_provider_client.assert_retract_events_received_with(&[]);
+}
+
 #[test]
 #[ignore = "similar to backlog_happy_flow, only shorter, and sprinkle some start_block/get_txs \
             attempts while its bootstrapping (and assert failure on height), then assert that they \
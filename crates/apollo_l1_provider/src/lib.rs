@@ -5,6 +5,7 @@ pub mod communication;
 pub mod l1_provider;
 pub mod l1_scraper;
 pub mod metrics;
+pub mod persistence;
 
 pub(crate) mod transaction_manager;
 pub(crate) mod transaction_record;
@@ -13,6 +14,7 @@ pub(crate) mod transaction_record;
 pub mod test_utils;
 
 use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::time::Duration;
 
 use apollo_config::dumping::{ser_optional_param, ser_param, SerializeConfig};
@@ -96,7 +98,7 @@ impl std::fmt::Display for ProviderState {
     }
 }
 
-#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, Validate, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Validate, PartialEq, Eq)]
 pub struct L1ProviderConfig {
     /// In most cases this can remain None: the provider defaults to using the
     /// LastStateUpdate height at the L1 Height that the L1Scraper is initialized on.
@@ -112,6 +114,9 @@ pub struct L1ProviderConfig {
     pub l1_handler_cancellation_timelock_seconds: Duration,
     #[serde(deserialize_with = "deserialize_float_seconds_to_duration")]
     pub new_l1_handler_cooldown_seconds: Duration,
+    /// When set, the provider persists its state to this file after every committed block, and
+    /// restarts from it instead of re-scraping L1 from scratch.
+    pub persisted_state_path: Option<PathBuf>,
 }
 
 impl From<L1ProviderConfig> for TransactionManagerConfig {
@@ -163,6 +168,15 @@ impl SerializeConfig for L1ProviderConfig {
             "Override height at which the provider should catch up to the bootstrapper.",
             ParamPrivacyInput::Public,
         ));
+        dump.extend(ser_optional_param(
+            &self.persisted_state_path,
+            "".into(),
+            "persisted_state_path",
+            "The path of the JSON file in which the provider persists its transaction records and \
+             the scraper's recent L1 rounds, which it restarts from if still valid on L1. If not \
+             set, the provider re-scrapes L1 from scratch on every start.",
+            ParamPrivacyInput::Public,
+        ));
         dump
     }
 }
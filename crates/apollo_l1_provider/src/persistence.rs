@@ -0,0 +1,137 @@
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use apollo_l1_provider_types::ScrapedRound;
+use papyrus_base_layer::{BaseLayerContract, L1BlockReference};
+use serde::{Deserialize, Serialize};
+use starknet_api::block::BlockNumber;
+use tokio::sync::watch;
+use tracing::{error, info, warn};
+
+use crate::transaction_record::Records;
+
+/// A snapshot of the provider's transaction records, taken after committing an L2 block, which
+/// lets a restarted provider skip re-scraping L1 history from scratch.
+/// The records reflect exactly the L2 blocks below `current_height` and the L1 events up to and
+/// including the last L1 block processed, at which the scraper's recent rounds end.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersistedL1ProviderState {
+    pub current_height: BlockNumber,
+    /// The scraper's recent rounds, oldest first, which a restarted scraper resumes from so that
+    /// it can still retract their events on L1 reorgs.
+    pub recent_scraped_rounds: Vec<ScrapedRound>,
+    pub(crate) records: Records,
+}
+
+impl PersistedL1ProviderState {
+    /// The last L1 block whose events are all reflected in the records.
+    pub fn last_l1_block_processed(&self) -> Option<L1BlockReference> {
+        self.recent_scraped_rounds.last().map(|round| round.last_l1_block)
+    }
+
+    /// Reads a persisted state from the given file. A missing file yields `None`.
+    pub fn read_from_file(path: &Path) -> io::Result<Option<Self>> {
+        match fs::read(path) {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Writes the state to the given file. The state is first written to a temporary file that
+    /// then replaces the given file, so that a crash mid-write never leaves a corrupt state behind.
+    pub fn write_to_file(&self, path: &Path) -> io::Result<()> {
+        let mut tmp_path = path.as_os_str().to_owned();
+        tmp_path.push(".tmp");
+        fs::write(&tmp_path, serde_json::to_vec(self)?)?;
+        fs::rename(&tmp_path, path)
+    }
+}
+
+/// Loads the persisted provider state, if it exists and is still valid: its last processed L1 block
+/// must still be part of the canonical L1 chain, otherwise the records may contain events from
+/// orphaned L1 blocks. Any failure is logged and results in `None`, in which case the provider
+/// should bootstrap from scratch.
+pub async fn load_persisted_state<B: BaseLayerContract + Send + Sync>(
+    path: &Path,
+    base_layer: &B,
+) -> Option<PersistedL1ProviderState> {
+    let state = match PersistedL1ProviderState::read_from_file(path) {
+        Ok(Some(state)) => state,
+        Ok(None) => {
+            info!("No persisted L1 provider state found at {path:?}.");
+            return None;
+        }
+        Err(err) => {
+            warn!("Failed to read the persisted L1 provider state from {path:?}: {err}.");
+            return None;
+        }
+    };
+
+    let Some(last_l1_block_processed) = state.last_l1_block_processed() else {
+        warn!("Discarding persisted L1 provider state: it has no recent scraped rounds.");
+        return None;
+    };
+    match base_layer.l1_block_at(last_l1_block_processed.number).await {
+        Ok(Some(l1_block)) if l1_block == last_l1_block_processed => {
+            info!(
+                "Loaded persisted L1 provider state at height {}, last processed L1 block {}.",
+                state.current_height, last_l1_block_processed.number
+            );
+            Some(state)
+        }
+        Ok(_) => {
+            warn!(
+                "Discarding persisted L1 provider state: its last processed L1 block {} with hash \
+                 {} is no longer in the canonical L1 chain.",
+                last_l1_block_processed.number,
+                hex::encode(last_l1_block_processed.hash)
+            );
+            None
+        }
+        Err(err) => {
+            warn!(
+                "Discarding persisted L1 provider state: failed to validate it against L1: {err}."
+            );
+            None
+        }
+    }
+}
+
+/// Writes the provider's state to a file in the background, so that persisting never blocks the
+/// provider's requests. Only the latest state is written: states that are sent while a write is in
+/// progress replace each other, and only the last of them is written once it's done.
+#[derive(Debug)]
+pub struct StatePersister {
+    sender: watch::Sender<Option<PersistedL1ProviderState>>,
+}
+
+impl StatePersister {
+    /// Spawns the writing task, which ends when the persister is dropped. Must be called from
+    /// within a tokio runtime.
+    pub fn new(path: PathBuf) -> Self {
+        let (sender, mut receiver) = watch::channel(None);
+        tokio::spawn(async move {
+            // A state sent right before the persister is dropped is still written.
+            while receiver.changed().await.is_ok() {
+                let Some(state) = receiver.borrow_and_update().clone() else {
+                    continue;
+                };
+                let write_path = path.clone();
+                let write_result =
+                    tokio::task::spawn_blocking(move || state.write_to_file(&write_path))
+                        .await
+                        .expect("Writing the L1 provider state panicked.");
+                if let Err(err) = write_result {
+                    error!("Failed to persist the L1 provider state to {path:?}: {err}.");
+                }
+            }
+        });
+        Self { sender }
+    }
+
+    /// Schedules the given state to be written, replacing any state that wasn't written yet.
+    pub fn persist(&self, state: PersistedL1ProviderState) {
+        self.sender.send_replace(Some(state));
+    }
+}
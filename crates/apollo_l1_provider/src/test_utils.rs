@@ -8,6 +8,8 @@ use apollo_l1_provider_types::{
     L1ProviderClient,
     L1ProviderClientResult,
     L1ProviderSnapshot,
+    ScrapedRound,
+    ScrapedRoundsUpdate,
     SessionState,
     ValidationStatus,
 };
@@ -28,6 +30,7 @@ use starknet_api::transaction::TransactionHash;
 
 use crate::bootstrapper::CommitBlockBacklog;
 use crate::l1_provider::L1Provider;
+use crate::persistence::StatePersister;
 use crate::transaction_manager::{StagingEpoch, TransactionManager, TransactionManagerConfig};
 use crate::transaction_record::{TransactionPayload, TransactionRecord};
 use crate::{L1ProviderConfig, ProviderState};
@@ -67,13 +70,18 @@ impl L1ProviderContent {
 
 impl From<L1ProviderContent> for L1Provider {
     fn from(content: L1ProviderContent) -> L1Provider {
+        let config = content.config.unwrap_or_default();
+        let state_persister =
+            config.persisted_state_path.clone().map(|path| Arc::new(StatePersister::new(path)));
         L1Provider {
-            config: content.config.unwrap_or_default(),
+            config,
             tx_manager: content.tx_manager_content.map(Into::into).unwrap_or_default(),
             // Defaulting to Pending state, since a provider with a "default" Bootstrapper
             // is functionally equivalent to Pending for testing purposes.
             state: content.state.unwrap_or(ProviderState::Pending),
             current_height: content.current_height.unwrap_or_default(),
+            recent_scraped_rounds: vec![],
+            state_persister,
             clock: content.clock.unwrap_or_else(|| Arc::new(DefaultClock)),
         }
     }
@@ -190,7 +198,7 @@ impl L1ProviderContentBuilder {
 
         let now = self.clock.as_ref().unwrap().unix_now();
         let cancellation_timelock =
-            self.config.unwrap().l1_handler_cancellation_timelock_seconds.as_secs();
+            self.config.as_ref().unwrap().l1_handler_cancellation_timelock_seconds.as_secs();
         // If a tx's timestamp is OLDER than the timelock, then it's timeout is expired and it's
         // considered fully cancelled on L2.
         let cancellation_expired = now - (cancellation_timelock + 1);
@@ -222,9 +230,9 @@ impl L1ProviderContentBuilder {
     }
 
     pub fn build(mut self) -> L1ProviderContent {
-        if let Some(config) = self.config {
+        if let Some(config) = &self.config {
             self.tx_manager_content_builder =
-                self.tx_manager_content_builder.with_config(config.into());
+                self.tx_manager_content_builder.with_config(config.clone().into());
         }
 
         L1ProviderContent {
@@ -245,7 +253,7 @@ impl L1ProviderContentBuilder {
         self.clock = self.clock.take().or_else(|| Some(Arc::new(FakeClock::new(base_timestamp))));
 
         let nonzero_timelock = Duration::from_secs(1);
-        let config = self.config.unwrap_or_default();
+        let config = self.config.clone().unwrap_or_default();
         self.with_config(L1ProviderConfig {
             new_l1_handler_cooldown_seconds: nonzero_timelock,
             l1_handler_cancellation_timelock_seconds: nonzero_timelock,
@@ -463,6 +471,8 @@ pub struct FakeL1ProviderClient {
     // Interior mutability needed since this is modifying during client API calls, which are all
     // immutable.
     pub events_received: Mutex<Vec<Event>>,
+    pub events_retracted: Mutex<Vec<Event>>,
+    pub recent_scraped_rounds: Mutex<Vec<ScrapedRound>>,
     pub commit_blocks_received: Mutex<Vec<CommitBlockBacklog>>,
 }
 
@@ -482,6 +492,12 @@ impl FakeL1ProviderClient {
         let events_received = mem::take(&mut *self.events_received.lock().unwrap());
         assert_eq!(events_received, expected);
     }
+
+    #[track_caller]
+    pub fn assert_retract_events_received_with(&self, expected: &[Event]) {
+        let events_retracted = mem::take(&mut *self.events_retracted.lock().unwrap());
+        assert_eq!(events_retracted, expected);
+    }
 }
 
 #[async_trait]
@@ -507,6 +523,19 @@ impl L1ProviderClient for FakeL1ProviderClient {
         Ok(())
     }
 
+    async fn retract_events(&self, events: Vec<Event>) -> L1ProviderClientResult<()> {
+        self.events_retracted.lock().unwrap().extend(events);
+        Ok(())
+    }
+
+    async fn update_recent_scraped_rounds(
+        &self,
+        update: ScrapedRoundsUpdate,
+    ) -> L1ProviderClientResult<()> {
+        update.apply(&mut self.recent_scraped_rounds.lock().unwrap());
+        Ok(())
+    }
+
     async fn commit_block(
         &self,
         l1_handler_tx_hashes: IndexSet<TransactionHash>,
@@ -7,6 +7,7 @@ use apollo_l1_provider_types::{InvalidValidationStatus, ValidationStatus};
 use starknet_api::block::BlockTimestamp;
 use starknet_api::executable_transaction::L1HandlerTransaction;
 use starknet_api::transaction::TransactionHash;
+use tracing::warn;
 
 use crate::transaction_record::{
     Records,
@@ -54,6 +55,17 @@ impl TransactionManager {
         }
     }
 
+    /// Replaces all records with previously persisted ones, and rebuilds the proposable index from
+    /// them. Records are restored in their persisted order, which preserves arrival order.
+    pub fn restore_records(&mut self, records: Records) {
+        self.records = records;
+        self.proposable_index.clear();
+        let tx_hashes: Vec<_> = self.records.keys().copied().collect();
+        for tx_hash in tx_hashes {
+            self.maintain_index(tx_hash);
+        }
+    }
+
     pub fn start_block(&mut self) {
         self.rollback_staging();
     }
@@ -132,12 +144,16 @@ impl TransactionManager {
         for &tx_hash in committed_txs {
             self.create_record_if_not_exist(tx_hash);
             self.with_record(tx_hash, |r| r.mark_committed()).unwrap();
+            self.prune_if_possible(tx_hash);
         }
         for &tx_hash in rejected_txs {
-            self.with_record(tx_hash, |r| r.mark_rejected()).expect(
-                "Storage inconsistency: a transaction sent to the batcher was removed \
-                 unexpectedly.",
-            );
+            // Transactions are only removed if their L1 event was orphaned by an L1 reorg.
+            if self.with_record(tx_hash, |r| r.mark_rejected()).is_none() {
+                warn!(
+                    "Rejected L1 handler transaction {tx_hash} was removed during the block, \
+                     likely due to an L1 reorg."
+                );
+            }
         }
     }
 
@@ -166,6 +182,38 @@ impl TransactionManager {
         )
     }
 
+    /// Removes a transaction whose L1 event was orphaned by an L1 reorg. Returns the removed
+    /// record's state, or `None` if the transaction is unknown. Transactions already committed on
+    /// L2 are kept, since their commitment can't be undone.
+    pub fn remove_tx(&mut self, tx_hash: TransactionHash) -> Option<TransactionState> {
+        let record = self.records.get(&tx_hash)?;
+        let state = record.state.clone();
+        if record.is_committed() {
+            return Some(state);
+        }
+
+        if let TransactionPayload::Full { created_at_block_timestamp, .. } = record.tx {
+            self.remove_from_index(tx_hash, created_at_block_timestamp);
+        }
+        self.records.remove(tx_hash);
+        Some(state)
+    }
+
+    /// Undoes a cancellation request whose L1 event was orphaned by an L1 reorg. Returns false if
+    /// the transaction is unknown.
+    pub fn retract_cancellation_request(&mut self, tx_hash: TransactionHash) -> bool {
+        self.with_record(tx_hash, |record| record.retract_cancellation_request()).is_some()
+    }
+
+    /// Marks transactions whose L1 events left the scraper's L1 reorg window as final on L1.
+    /// Committed ones are pruned right away, and the rest once they are committed.
+    pub fn mark_final_on_l1(&mut self, tx_hashes: impl IntoIterator<Item = TransactionHash>) {
+        for tx_hash in tx_hashes {
+            self.with_record(tx_hash, |record| record.mark_final_on_l1());
+            self.prune_if_possible(tx_hash);
+        }
+    }
+
     pub fn is_committed(&self, tx_hash: TransactionHash) -> bool {
         self.records.get(&tx_hash).is_some_and(|record| record.is_committed())
     }
@@ -216,6 +264,13 @@ impl TransactionManager {
         Some(result)
     }
 
+    fn prune_if_possible(&mut self, tx_hash: TransactionHash) {
+        // Committed records aren't indexed, so only the record itself is removed.
+        if self.records.get(&tx_hash).is_some_and(|record| record.is_prunable()) {
+            self.records.remove(tx_hash);
+        }
+    }
+
     fn create_record_if_not_exist(&mut self, hash: TransactionHash) -> bool {
         self.records.insert(hash, TransactionRecord::new(hash.into()))
     }
@@ -247,20 +302,24 @@ impl TransactionManager {
                     tx_hashes.push(tx_hash);
                 }
             } else {
-                // Remove from the vec for this timestamp, and drop the entry if it becomes empty.
-                match self.proposable_index.entry(created_at) {
-                    Entry::Occupied(mut entry) => {
-                        let tx_hashes = entry.get_mut();
-                        if let Some(index_in_vec) = tx_hashes.iter().position(|&h| h == tx_hash) {
-                            tx_hashes.remove(index_in_vec);
-                            if tx_hashes.is_empty() {
-                                entry.remove();
-                            }
-                        }
+                self.remove_from_index(tx_hash, created_at);
+            }
+        }
+    }
+
+    // Remove from the vec for this timestamp, and drop the entry if it becomes empty.
+    fn remove_from_index(&mut self, tx_hash: TransactionHash, created_at: BlockTimestamp) {
+        match self.proposable_index.entry(created_at) {
+            Entry::Occupied(mut entry) => {
+                let tx_hashes = entry.get_mut();
+                if let Some(index_in_vec) = tx_hashes.iter().position(|&h| h == tx_hash) {
+                    tx_hashes.remove(index_in_vec);
+                    if tx_hashes.is_empty() {
+                        entry.remove();
                     }
-                    Entry::Vacant(_) => {}
                 }
             }
+            Entry::Vacant(_) => {}
         }
     }
 
@@ -3,6 +3,7 @@ use std::time::Duration;
 
 use indexmap::map::Entry;
 use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
 use starknet_api::block::BlockTimestamp;
 use starknet_api::executable_transaction::L1HandlerTransaction;
 use starknet_api::transaction::TransactionHash;
@@ -12,7 +13,7 @@ use crate::transaction_manager::StagingEpoch;
 
 /// An entity that wraps a committed L1 handler transaction and all information and decisions made
 /// on it ("Domain Entity"). Uses lifecycle metadata to maintain the state of the transaction.
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TransactionRecord {
     pub tx: TransactionPayload,
 
@@ -26,7 +27,12 @@ pub struct TransactionRecord {
     committed: bool,
     rejected: bool,
     cancellation_requested_at: Option<BlockTimestamp>,
+    /// Set once the transaction's L1 event is deeper than the max L1 reorg depth, after which it
+    /// can never be retracted or scraped again.
+    final_on_l1: bool,
     /// A record is staged iff its epoch equals the record owner's (tx manager) epoch counter.
+    /// Not persisted, since staging never outlives the provider's process.
+    #[serde(skip)]
     staged_epoch: StagingEpoch,
 }
 
@@ -89,6 +95,18 @@ impl TransactionRecord {
         Some(*self.cancellation_requested_at.get_or_insert(timestamp))
     }
 
+    /// Undoes a cancellation request whose L1 event was orphaned by an L1 reorg, returning the
+    /// transaction to the state it was in before the request.
+    pub fn retract_cancellation_request(&mut self) {
+        if self.cancellation_requested_at.take().is_none() || self.is_committed() {
+            return;
+        }
+
+        info!("Retracting cancellation request of L1 handler transaction {}.", self.tx.tx_hash());
+        self.state =
+            if self.rejected { TransactionState::Rejected } else { TransactionState::Pending };
+    }
+
     /// Try to stage an l1 handler transaction, which means that we allow to include it in the
     /// current proposed or validated block. If already included in a block, this test will return
     /// false, thus preventing double-inclusion in the block. Staging is reset at the start of every
@@ -101,6 +119,16 @@ impl TransactionRecord {
         was_unstaged
     }
 
+    pub fn mark_final_on_l1(&mut self) {
+        self.final_on_l1 = true;
+    }
+
+    /// Answers whether the record is no longer needed: the transaction was committed on L2 and its
+    /// L1 event will never be scraped again, so nothing can ask about it anymore.
+    pub fn is_prunable(&self) -> bool {
+        self.is_committed() && self.final_on_l1
+    }
+
     pub fn is_proposable(&self) -> bool {
         matches!(self.state, TransactionState::Pending)
     }
@@ -157,7 +185,7 @@ impl From<TransactionPayload> for TransactionRecord {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TransactionPayload {
     HashOnly(TransactionHash),
     Full { tx: L1HandlerTransaction, created_at_block_timestamp: BlockTimestamp },
@@ -188,7 +216,7 @@ impl From<TransactionHash> for TransactionPayload {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TransactionState {
     CancellationStartedOnL2,
     CancelledOnL2,
@@ -198,7 +226,7 @@ pub enum TransactionState {
     Rejected,
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Records(IndexMap<TransactionHash, TransactionRecord>);
 
 impl Records {
@@ -208,6 +236,10 @@ impl Records {
         self.0.get_mut(&hash)
     }
 
+    pub fn remove(&mut self, hash: TransactionHash) -> Option<TransactionRecord> {
+        self.0.shift_remove(&hash)
+    }
+
     pub fn insert(&mut self, hash: TransactionHash, record: TransactionRecord) -> bool {
         match self.0.entry(hash) {
             Entry::Occupied(_) => false,
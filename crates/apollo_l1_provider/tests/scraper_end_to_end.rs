@@ -144,6 +144,7 @@ async fn scraper_end_to_end() {
 
     // Expect second call to return nothing, no events left to scrape.
     l1_provider_client.expect_add_events().once().in_sequence(&mut sequence).returning(|_| Ok(()));
+    l1_provider_client.expect_update_recent_scraped_rounds().returning(|_| Ok(()));
 
     let l1_scraper_config = L1ScraperConfig {
         // Start scraping far enough back to capture all of the events created before.
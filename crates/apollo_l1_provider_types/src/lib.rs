@@ -4,14 +4,14 @@ use std::fmt::Display;
 use std::sync::Arc;
 
 use apollo_infra::component_client::ClientError;
-use apollo_infra::component_definitions::ComponentClient;
+use apollo_infra::component_definitions::{ComponentClient, PrioritizedRequest};
 use apollo_infra::impl_debug_for_infra_requests_and_responses;
 use apollo_proc_macros::handle_all_response_variants;
 use async_trait::async_trait;
 use indexmap::IndexSet;
 #[cfg(any(feature = "testing", test))]
 use mockall::automock;
-use papyrus_base_layer::{EventData, L1Event};
+use papyrus_base_layer::{EventData, L1BlockNumber, L1BlockReference, L1Event};
 use serde::{Deserialize, Serialize};
 use starknet_api::block::{BlockNumber, BlockTimestamp};
 use starknet_api::core::ChainId;
@@ -63,6 +63,8 @@ pub enum L1ProviderRequest {
         height: BlockNumber,
     },
     Initialize(Vec<Event>),
+    RetractEvents(Vec<Event>),
+    UpdateRecentScrapedRounds(ScrapedRoundsUpdate),
     StartBlock {
         state: SessionState,
         height: BlockNumber,
@@ -75,12 +77,16 @@ pub enum L1ProviderRequest {
 }
 impl_debug_for_infra_requests_and_responses!(L1ProviderRequest);
 
+impl PrioritizedRequest for L1ProviderRequest {}
+
 #[derive(Clone, Serialize, Deserialize, AsRefStr)]
 pub enum L1ProviderResponse {
     AddEvents(L1ProviderResult<()>),
     CommitBlock(L1ProviderResult<()>),
     GetTransactions(L1ProviderResult<Vec<L1HandlerTransaction>>),
     Initialize(L1ProviderResult<()>),
+    RetractEvents(L1ProviderResult<()>),
+    UpdateRecentScrapedRounds(L1ProviderResult<()>),
     StartBlock(L1ProviderResult<()>),
     Validate(L1ProviderResult<ValidationStatus>),
     GetL1ProviderSnapshot(L1ProviderResult<L1ProviderSnapshot>),
@@ -118,6 +124,14 @@ pub trait L1ProviderClient: Send + Sync {
     ) -> L1ProviderClientResult<()>;
 
     async fn add_events(&self, events: Vec<Event>) -> L1ProviderClientResult<()>;
+    /// Undoes events that were added from L1 blocks which were orphaned by an L1 reorg.
+    async fn retract_events(&self, events: Vec<Event>) -> L1ProviderClientResult<()>;
+    /// Reports the changes to the scraper's recent rounds since its previous report. The rounds end
+    /// at the last L1 block whose events were all added.
+    async fn update_recent_scraped_rounds(
+        &self,
+        update: ScrapedRoundsUpdate,
+    ) -> L1ProviderClientResult<()>;
     async fn initialize(&self, events: Vec<Event>) -> L1ProviderClientResult<()>;
     async fn get_l1_provider_snapshot(&self) -> L1ProviderClientResult<L1ProviderSnapshot>;
 }
@@ -203,6 +217,33 @@ where
         )
     }
 
+    #[instrument(skip(self))]
+    async fn retract_events(&self, events: Vec<Event>) -> L1ProviderClientResult<()> {
+        let request = L1ProviderRequest::RetractEvents(events);
+        handle_all_response_variants!(
+            L1ProviderResponse,
+            RetractEvents,
+            L1ProviderClientError,
+            L1ProviderError,
+            Direct
+        )
+    }
+
+    #[instrument(skip(self))]
+    async fn update_recent_scraped_rounds(
+        &self,
+        update: ScrapedRoundsUpdate,
+    ) -> L1ProviderClientResult<()> {
+        let request = L1ProviderRequest::UpdateRecentScrapedRounds(update);
+        handle_all_response_variants!(
+            L1ProviderResponse,
+            UpdateRecentScrapedRounds,
+            L1ProviderClientError,
+            L1ProviderError,
+            Direct
+        )
+    }
+
     async fn initialize(&self, events: Vec<Event>) -> L1ProviderClientResult<()> {
         let request = L1ProviderRequest::Initialize(events);
         handle_all_response_variants!(
@@ -268,6 +309,48 @@ impl Event {
     }
 }
 
+/// The events sent to the provider in a single scraping round, which ended at `last_l1_block`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ScrapedRound {
+    pub last_l1_block: L1BlockReference,
+    pub events: Vec<Event>,
+}
+
+/// The changes to the scraper's recent rounds since its previous report.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ScrapedRoundsUpdate {
+    /// The L1 block of the newest previously reported round that didn't change. The rounds after
+    /// it were orphaned by an L1 reorg, or merged with newer events of the same L1 block. If it's
+    /// `None`, or unknown to the receiver, only the new rounds are kept.
+    pub last_kept_l1_block: Option<L1BlockReference>,
+    /// The rounds that follow the kept ones, oldest first.
+    pub new_rounds: Vec<ScrapedRound>,
+    /// The L1 block number of the oldest round the scraper keeps. Older rounds are deeper than the
+    /// max L1 reorg depth, so their events are final on L1.
+    pub oldest_l1_block_number: L1BlockNumber,
+}
+
+impl ScrapedRoundsUpdate {
+    /// Applies the update to the given rounds, oldest first, and returns the rounds that became
+    /// final.
+    pub fn apply(self, rounds: &mut Vec<ScrapedRound>) -> Vec<ScrapedRound> {
+        let num_final_rounds = rounds
+            .iter()
+            .take_while(|round| round.last_l1_block.number < self.oldest_l1_block_number)
+            .count();
+        let final_rounds = rounds.drain(..num_final_rounds).collect();
+        let num_kept_rounds = self
+            .last_kept_l1_block
+            .and_then(|last_kept_l1_block| {
+                rounds.iter().position(|round| round.last_l1_block == last_kept_l1_block)
+            })
+            .map_or(0, |index| index + 1);
+        rounds.truncate(num_kept_rounds);
+        rounds.extend(self.new_rounds);
+        final_rounds
+    }
+}
+
 impl Display for Event {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
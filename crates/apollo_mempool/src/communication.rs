@@ -16,6 +16,8 @@ use async_trait::async_trait;
 use starknet_api::block::GasPrice;
 use starknet_api::core::ContractAddress;
 use starknet_api::rpc_transaction::InternalRpcTransaction;
+use starknet_api::transaction::TransactionHash;
+use tokio::sync::watch;
 use tracing::warn;
 
 use crate::config::MempoolConfig;
@@ -26,20 +28,26 @@ pub type LocalMempoolServer =
     LocalComponentServer<MempoolCommunicationWrapper, MempoolRequest, MempoolResponse>;
 pub type RemoteMempoolServer = RemoteComponentServer<MempoolRequest, MempoolResponse>;
 
+/// Creates the mempool component. If a config receiver is given, the mempool applies the latest
+/// config it holds before handling each request.
 pub fn create_mempool(
     config: MempoolConfig,
     mempool_p2p_propagator_client: SharedMempoolP2pPropagatorClient,
+    config_receiver: Option<watch::Receiver<MempoolConfig>>,
 ) -> MempoolCommunicationWrapper {
-    MempoolCommunicationWrapper::new(
+    let mut mempool_wrapper = MempoolCommunicationWrapper::new(
         Mempool::new(config, Arc::new(DefaultClock)),
         mempool_p2p_propagator_client,
-    )
+    );
+    mempool_wrapper.config_receiver = config_receiver;
+    mempool_wrapper
 }
 
 /// Wraps the mempool to enable inbound async communication from other components.
 pub struct MempoolCommunicationWrapper {
     mempool: Mempool,
     mempool_p2p_propagator_client: SharedMempoolP2pPropagatorClient,
+    config_receiver: Option<watch::Receiver<MempoolConfig>>,
 }
 
 impl MempoolCommunicationWrapper {
@@ -47,7 +55,21 @@ impl MempoolCommunicationWrapper {
         mempool: Mempool,
         mempool_p2p_propagator_client: SharedMempoolP2pPropagatorClient,
     ) -> Self {
-        MempoolCommunicationWrapper { mempool, mempool_p2p_propagator_client }
+        MempoolCommunicationWrapper {
+            mempool,
+            mempool_p2p_propagator_client,
+            config_receiver: None,
+        }
+    }
+
+    fn apply_config_update(&mut self) {
+        let Some(config_receiver) = &mut self.config_receiver else {
+            return;
+        };
+        // An error means the publisher is gone, so the config can't change anymore.
+        if config_receiver.has_changed().unwrap_or(false) {
+            self.mempool.update_config(config_receiver.borrow_and_update().clone());
+        }
     }
 
     async fn send_tx_to_p2p(
@@ -110,11 +132,23 @@ impl MempoolCommunicationWrapper {
     fn mempool_snapshot(&self) -> MempoolResult<MempoolSnapshot> {
         self.mempool.mempool_snapshot()
     }
+
+    fn tx_hashes_by_account(&self) -> MempoolResult<Vec<(ContractAddress, Vec<TransactionHash>)>> {
+        Ok(self.mempool.tx_hashes_by_account())
+    }
+
+    fn get_txs_by_hash(
+        &self,
+        tx_hashes: Vec<TransactionHash>,
+    ) -> MempoolResult<Vec<InternalRpcTransaction>> {
+        Ok(self.mempool.get_txs_by_hash(&tx_hashes))
+    }
 }
 
 #[async_trait]
 impl ComponentRequestHandler<MempoolRequest, MempoolResponse> for MempoolCommunicationWrapper {
     async fn handle_request(&mut self, request: MempoolRequest) -> MempoolResponse {
+        self.apply_config_update();
         match request {
             MempoolRequest::AddTransaction(args) => {
                 MempoolResponse::AddTransaction(self.add_tx(args).await)
@@ -136,6 +170,12 @@ impl ComponentRequestHandler<MempoolRequest, MempoolResponse> for MempoolCommuni
             MempoolRequest::GetMempoolSnapshot() => {
                 MempoolResponse::GetMempoolSnapshot(self.mempool_snapshot())
             }
+            MempoolRequest::GetTransactionHashesByAccount() => {
+                MempoolResponse::GetTransactionHashesByAccount(self.tx_hashes_by_account())
+            }
+            MempoolRequest::GetTransactionsByHash(tx_hashes) => {
+                MempoolResponse::GetTransactionsByHash(self.get_txs_by_hash(tx_hashes))
+            }
         }
     }
 }
@@ -261,6 +261,12 @@ impl Mempool {
         }
     }
 
+    /// Replaces the config of the mempool. The retention of committed nonces is set on creation and
+    /// isn't affected.
+    pub fn update_config(&mut self, config: MempoolConfig) {
+        self.config = config;
+    }
+
     /// Returns an iterator of the current eligible transactions for sequencing, ordered by their
     /// priority.
     pub fn iter(&self) -> impl Iterator<Item = &TransactionReference> {
@@ -676,6 +682,21 @@ impl Mempool {
         })
     }
 
+    /// Returns the hashes of the transactions in the pool of each account, sorted by nonce.
+    pub fn tx_hashes_by_account(&self) -> Vec<(ContractAddress, Vec<TransactionHash>)> {
+        self.tx_pool.txs_hashes_by_account()
+    }
+
+    /// Returns the transactions in the pool with the given hashes. Hashes of transactions that
+    /// aren't in the pool are skipped.
+    pub fn get_txs_by_hash(&self, tx_hashes: &[TransactionHash]) -> Vec<InternalRpcTransaction> {
+        tx_hashes
+            .iter()
+            .filter_map(|tx_hash| self.tx_pool.get_by_tx_hash(*tx_hash).ok())
+            .cloned()
+            .collect()
+    }
+
     fn size_in_bytes(&self) -> u64 {
         self.tx_pool.size_in_bytes() + self.delayed_declares.size_in_bytes()
     }
@@ -3,17 +3,23 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use apollo_infra::component_client::ClientError;
+use apollo_infra::component_definitions::ComponentRequestHandler;
 use apollo_mempool_p2p_types::communication::{
     MempoolP2pPropagatorClientError,
     MockMempoolP2pPropagatorClient,
 };
-use apollo_mempool_types::communication::AddTransactionArgsWrapper;
+use apollo_mempool_types::communication::{
+    AddTransactionArgsWrapper,
+    MempoolRequest,
+    MempoolResponse,
+};
 use apollo_mempool_types::errors::MempoolError;
-use apollo_mempool_types::mempool_types::{AccountState, AddTransactionArgs};
+use apollo_mempool_types::mempool_types::{AccountState, AddTransactionArgs, MempoolResult};
 use apollo_metrics::metrics::HistogramValue;
 use apollo_network_types::network_types::BroadcastedMessageMetadata;
 use apollo_test_utils::{get_rng, GetTestInstance};
 use apollo_time::test_utils::FakeClock;
+use assert_matches::assert_matches;
 use mempool_test_utils::starknet_api_test_utils::test_valid_resource_bounds;
 use metrics_exporter_prometheus::PrometheusBuilder;
 use mockall::predicate::eq;
@@ -26,9 +32,10 @@ use starknet_api::test_utils::invoke::internal_invoke_tx;
 use starknet_api::transaction::fields::TransactionSignature;
 use starknet_api::transaction::TransactionHash;
 use starknet_api::{contract_address, declare_tx_args, felt, invoke_tx_args, nonce, tx_hash};
+use tokio::sync::watch;
 
 use super::AddTransactionQueue;
-use crate::communication::MempoolCommunicationWrapper;
+use crate::communication::{create_mempool, MempoolCommunicationWrapper};
 use crate::mempool::{
     AccountsWithGap,
     Mempool,
@@ -958,6 +965,49 @@ async fn test_propagated_tx_sent_to_p2p(mempool: Mempool) {
     mempool_wrapper.add_tx(propagated_args).await.unwrap();
 }
 
+#[tokio::test]
+async fn mempool_applies_config_updates_before_handling_requests() {
+    let config = MempoolConfig {
+        enable_fee_escalation: true,
+        fee_escalation_percentage: 10,
+        ..Default::default()
+    };
+    let (config_sender, config_receiver) = watch::channel(config.clone());
+    let mut mock_mempool_p2p_propagator_client = MockMempoolP2pPropagatorClient::new();
+    mock_mempool_p2p_propagator_client.expect_add_transaction().returning(|_| Ok(()));
+    let mut mempool_wrapper = create_mempool(
+        config.clone(),
+        Arc::new(mock_mempool_p2p_propagator_client),
+        Some(config_receiver),
+    );
+    async fn add_tx(
+        mempool_wrapper: &mut MempoolCommunicationWrapper,
+        args: AddTransactionArgs,
+    ) -> MempoolResult<()> {
+        let request = MempoolRequest::AddTransaction(AddTransactionArgsWrapper {
+            args,
+            p2p_message_metadata: None,
+        });
+        match mempool_wrapper.handle_request(request).await {
+            MempoolResponse::AddTransaction(result) => result,
+            response => panic!("Unexpected response: {response:?}"),
+        }
+    }
+
+    let tx_input = add_tx_input!(tx_hash: 1, tip: 100, max_l2_gas_price: 100);
+    add_tx(&mut mempool_wrapper, tx_input).await.unwrap();
+    let replacement_input = add_tx_input!(tx_hash: 2, tip: 200, max_l2_gas_price: 200);
+    add_tx(&mut mempool_wrapper, replacement_input).await.unwrap();
+
+    // Once fee escalation is disabled, the same replacement is rejected.
+    config_sender.send(MempoolConfig { enable_fee_escalation: false, ..config }).unwrap();
+    let replacement_input = add_tx_input!(tx_hash: 3, tip: 400, max_l2_gas_price: 400);
+    assert_matches!(
+        add_tx(&mut mempool_wrapper, replacement_input).await,
+        Err(MempoolError::DuplicateNonce { .. })
+    );
+}
+
 #[rstest]
 fn test_rejected_tx_deleted_from_mempool(mut mempool: Mempool) {
     // Setup. The tip is used here to control the order of transactions in the mempool.
@@ -1396,6 +1446,33 @@ fn test_get_mempool_snapshot() {
     assert_eq!(mempool_snapshot.transactions, expected_chronological_hashes);
 }
 
+#[rstest]
+fn test_tx_hashes_by_account_and_get_txs_by_hash(mut mempool: Mempool) {
+    let input_address_1_nonce_1 =
+        add_tx_input!(tx_hash: 1, address: "0x1", tx_nonce: 1, account_nonce: 0);
+    let input_address_1_nonce_0 =
+        add_tx_input!(tx_hash: 2, address: "0x1", tx_nonce: 0, account_nonce: 0);
+    let input_address_0 = add_tx_input!(tx_hash: 3, address: "0x0", tx_nonce: 0, account_nonce: 0);
+    for input in [&input_address_1_nonce_1, &input_address_1_nonce_0, &input_address_0] {
+        add_tx(&mut mempool, input);
+    }
+
+    // Accounts are sorted by address and transactions by nonce.
+    assert_eq!(
+        mempool.tx_hashes_by_account(),
+        vec![
+            (contract_address!("0x0"), vec![tx_hash!(3)]),
+            (contract_address!("0x1"), vec![tx_hash!(2), tx_hash!(1)]),
+        ]
+    );
+
+    // Unknown hashes are skipped.
+    assert_eq!(
+        mempool.get_txs_by_hash(&[tx_hash!(3), tx_hash!(4), tx_hash!(1)]),
+        vec![input_address_0.tx, input_address_1_nonce_1.tx]
+    );
+}
+
 #[rstest]
 #[tokio::test]
 async fn add_tx_tolerates_p2p_propagation_error(mempool: Mempool) {
@@ -209,6 +209,12 @@ impl TransactionPool {
         }
     }
 
+    /// Returns the hashes of the transactions of each account, sorted by ascending nonce values.
+    /// The accounts are sorted by address.
+    pub fn txs_hashes_by_account(&self) -> Vec<(ContractAddress, Vec<TransactionHash>)> {
+        self.txs_by_account.txs_hashes_by_account()
+    }
+
     pub fn chronological_txs_hashes(&self) -> Vec<TransactionHash> {
         self.txs_by_submission_time
             .txs_by_submission_time
@@ -280,6 +286,18 @@ impl AccountTransactionIndex {
     fn contains(&self, address: ContractAddress) -> bool {
         self.0.contains_key(&address)
     }
+
+    fn txs_hashes_by_account(&self) -> Vec<(ContractAddress, Vec<TransactionHash>)> {
+        let mut txs_hashes_by_account: Vec<_> = self
+            .0
+            .iter()
+            .map(|(address, account_txs)| {
+                (*address, account_txs.values().map(|tx_ref| tx_ref.tx_hash).collect())
+            })
+            .collect();
+        txs_hashes_by_account.sort_unstable_by_key(|(address, _)| *address);
+        txs_hashes_by_account
+    }
 }
 
 #[derive(Debug, Default, Eq, PartialEq, Clone)]
@@ -18,6 +18,11 @@ pub struct MempoolP2pConfig {
     pub max_transaction_batch_size: usize,
     #[serde(deserialize_with = "deserialize_milliseconds_to_duration")]
     pub transaction_batch_rate_millis: Duration,
+    pub seen_transactions_cache_size: usize,
+    #[serde(deserialize_with = "deserialize_milliseconds_to_duration")]
+    pub recovery_interval_millis: Duration,
+    pub max_transactions_per_recovery_query: usize,
+    pub max_transactions_per_recovery_summary: usize,
 }
 
 impl Default for MempoolP2pConfig {
@@ -28,6 +33,10 @@ impl Default for MempoolP2pConfig {
             // TODO(Eitan): Change to appropriate values.
             max_transaction_batch_size: 1,
             transaction_batch_rate_millis: Duration::from_secs(1),
+            seen_transactions_cache_size: 100000,
+            recovery_interval_millis: Duration::from_secs(60),
+            max_transactions_per_recovery_query: 100,
+            max_transactions_per_recovery_summary: 10000,
         }
     }
 }
@@ -55,6 +64,34 @@ impl SerializeConfig for MempoolP2pConfig {
                      milliseconds.",
                     ParamPrivacyInput::Public,
                 ),
+                ser_param(
+                    "seen_transactions_cache_size",
+                    &self.seen_transactions_cache_size,
+                    "The number of recently seen transactions to remember in order to drop \
+                     re-gossiped transactions.",
+                    ParamPrivacyInput::Public,
+                ),
+                ser_param(
+                    "recovery_interval_millis",
+                    &self.recovery_interval_millis.as_millis(),
+                    "Time between requests for the transactions of another mempool that are \
+                     missing from this mempool, in milliseconds.",
+                    ParamPrivacyInput::Public,
+                ),
+                ser_param(
+                    "max_transactions_per_recovery_query",
+                    &self.max_transactions_per_recovery_query,
+                    "Maximum number of transactions requested from or served to another mempool \
+                     in a single recovery query.",
+                    ParamPrivacyInput::Public,
+                ),
+                ser_param(
+                    "max_transactions_per_recovery_summary",
+                    &self.max_transactions_per_recovery_summary,
+                    "Maximum number of transaction hashes requested from or served to another \
+                     mempool in the summary of a mempool.",
+                    ParamPrivacyInput::Public,
+                ),
             ]),
             prepend_sub_config_name(self.network_config.dump(), "network_config"),
         ]
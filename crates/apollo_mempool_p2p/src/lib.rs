@@ -1,17 +1,22 @@
 pub mod config;
 pub mod metrics;
 pub mod propagator;
+pub mod recovery;
 pub mod runner;
+pub mod seen_transactions;
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use apollo_class_manager_types::transaction_converter::TransactionConverter;
 use apollo_class_manager_types::SharedClassManagerClient;
 use apollo_gateway_types::communication::SharedGatewayClient;
 use apollo_mempool_p2p_types::communication::SharedMempoolP2pPropagatorClient;
+use apollo_mempool_types::communication::SharedMempoolClient;
 use apollo_network::gossipsub_impl::Topic;
 use apollo_network::network_manager::metrics::{BroadcastNetworkMetrics, NetworkMetrics};
 use apollo_network::network_manager::{BroadcastTopicChannels, NetworkManager};
+use apollo_network_types::network_types::PeerBanListClients;
 use futures::FutureExt;
 use metrics::MEMPOOL_P2P_NUM_BLACKLISTED_PEERS;
 use tracing::{info_span, Instrument};
@@ -19,24 +24,35 @@ use tracing::{info_span, Instrument};
 use crate::config::MempoolP2pConfig;
 use crate::metrics::{
     MEMPOOL_P2P_NUM_CONNECTED_PEERS,
+    MEMPOOL_P2P_NUM_DENIED_CONNECTIONS,
     MEMPOOL_P2P_NUM_RECEIVED_MESSAGES,
     MEMPOOL_P2P_NUM_SENT_MESSAGES,
 };
 use crate::propagator::MempoolP2pPropagator;
+use crate::recovery::{
+    MempoolRecoveryClient,
+    MempoolRecoveryServer,
+    MEMPOOL_SUMMARY_PROTOCOL,
+    MEMPOOL_TRANSACTIONS_PROTOCOL,
+};
 use crate::runner::MempoolP2pRunner;
+use crate::seen_transactions::SeenTransactions;
 
 pub const MEMPOOL_TOPIC: &str = "apollo_mempool_transaction_propagation/0.1.0";
+/// The maximal number of peers whose gossip statistics are collected in each statistics interval.
+pub const MAX_TRACKED_PEERS: usize = 1000;
 
 pub fn create_p2p_propagator_and_runner(
     mempool_p2p_config: MempoolP2pConfig,
     gateway_client: SharedGatewayClient,
     class_manager_client: SharedClassManagerClient,
     mempool_p2p_propagator_client: SharedMempoolP2pPropagatorClient,
+    mempool_client: SharedMempoolClient,
+    peer_ban_list_clients: PeerBanListClients,
 ) -> (MempoolP2pPropagator, MempoolP2pRunner) {
-    let transaction_converter = TransactionConverter::new(
-        class_manager_client.clone(),
-        mempool_p2p_config.network_config.chain_id.clone(),
-    );
+    let chain_id = mempool_p2p_config.network_config.chain_id.clone();
+    let transaction_converter =
+        TransactionConverter::new(class_manager_client.clone(), chain_id.clone());
     let mut broadcast_metrics_by_topic = HashMap::new();
     broadcast_metrics_by_topic.insert(
         Topic::new(MEMPOOL_TOPIC).hash(),
@@ -48,6 +64,7 @@ pub fn create_p2p_propagator_and_runner(
     let network_manager_metrics = Some(NetworkMetrics {
         num_connected_peers: MEMPOOL_P2P_NUM_CONNECTED_PEERS,
         num_blacklisted_peers: MEMPOOL_P2P_NUM_BLACKLISTED_PEERS,
+        num_denied_connections: MEMPOOL_P2P_NUM_DENIED_CONNECTIONS,
         broadcast_metrics_by_topic: Some(broadcast_metrics_by_topic),
         sqmr_metrics: None,
     });
@@ -57,6 +74,9 @@ pub fn create_p2p_propagator_and_runner(
         None,
         network_manager_metrics,
     );
+    if let Some(peer_ban_list_client) = network_manager.peer_ban_list_client() {
+        peer_ban_list_clients.insert("mempool_p2p", peer_ban_list_client);
+    }
     let BroadcastTopicChannels { broadcasted_messages_receiver, broadcast_topic_client } =
         network_manager
             .register_broadcast_topic(
@@ -64,12 +84,44 @@ pub fn create_p2p_propagator_and_runner(
                 mempool_p2p_config.network_buffer_size,
             )
             .expect("Failed to register broadcast topic");
+    let summary_server = network_manager.register_sqmr_protocol_server(
+        MEMPOOL_SUMMARY_PROTOCOL.to_string(),
+        mempool_p2p_config.network_buffer_size,
+    );
+    let transactions_server = network_manager.register_sqmr_protocol_server(
+        MEMPOOL_TRANSACTIONS_PROTOCOL.to_string(),
+        mempool_p2p_config.network_buffer_size,
+    );
+    let summary_client = network_manager.register_sqmr_protocol_client(
+        MEMPOOL_SUMMARY_PROTOCOL.to_string(),
+        mempool_p2p_config.network_buffer_size,
+    );
+    let transactions_client = network_manager.register_sqmr_protocol_client(
+        MEMPOOL_TRANSACTIONS_PROTOCOL.to_string(),
+        mempool_p2p_config.network_buffer_size,
+    );
     let network_future = network_manager.run().instrument(info_span!("[Mempool network]"));
     let mempool_p2p_propagator = MempoolP2pPropagator::new(
         broadcast_topic_client.clone(),
-        Box::new(transaction_converter),
+        Box::new(transaction_converter.clone()),
         mempool_p2p_config.max_transaction_batch_size,
     );
+    let recovery_client = MempoolRecoveryClient::new(
+        summary_client,
+        transactions_client,
+        mempool_client.clone(),
+        chain_id.clone(),
+        mempool_p2p_config.max_transactions_per_recovery_query,
+        mempool_p2p_config.max_transactions_per_recovery_summary,
+    );
+    let recovery_server = MempoolRecoveryServer::new(
+        summary_server,
+        transactions_server,
+        mempool_client,
+        Arc::new(transaction_converter),
+        mempool_p2p_config.max_transactions_per_recovery_query,
+        mempool_p2p_config.max_transactions_per_recovery_summary,
+    );
     let mempool_p2p_runner = MempoolP2pRunner::new(
         network_future.boxed(),
         broadcasted_messages_receiver,
@@ -77,6 +129,11 @@ pub fn create_p2p_propagator_and_runner(
         gateway_client,
         mempool_p2p_propagator_client,
         mempool_p2p_config.transaction_batch_rate_millis,
+        SeenTransactions::new(mempool_p2p_config.seen_transactions_cache_size, MAX_TRACKED_PEERS),
+        chain_id,
+        recovery_client,
+        recovery_server,
+        mempool_p2p_config.recovery_interval_millis,
     );
     (mempool_p2p_propagator, mempool_p2p_runner)
 }
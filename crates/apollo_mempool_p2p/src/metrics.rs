@@ -5,10 +5,18 @@ define_metrics!(
         // Gauges
         MetricGauge { MEMPOOL_P2P_NUM_CONNECTED_PEERS, "apollo_mempool_p2p_num_connected_peers", "The number of connected peers to the mempool p2p component" },
         MetricGauge { MEMPOOL_P2P_NUM_BLACKLISTED_PEERS, "apollo_mempool_p2p_num_blacklisted_peers", "The number of currently blacklisted peers by the mempool p2p component" },
+        MetricGauge { MEMPOOL_P2P_NUM_DELIVERING_PEERS, "apollo_mempool_p2p_num_delivering_peers", "The number of peers that were the first to deliver at least one transaction to the mempool p2p component in the last statistics interval" },
         // Counters
+        MetricCounter { MEMPOOL_P2P_NUM_DENIED_CONNECTIONS, "apollo_mempool_p2p_num_denied_connections", "The number of connections with peers that were denied by the mempool p2p component", init = 0 },
         MetricCounter { MEMPOOL_P2P_NUM_SENT_MESSAGES, "apollo_mempool_p2p_num_sent_messages", "The number of messages sent by the mempool p2p component", init = 0 },
         MetricCounter { MEMPOOL_P2P_NUM_RECEIVED_MESSAGES, "apollo_mempool_p2p_num_received_messages", "The number of messages received by the mempool p2p component", init = 0 },
+        MetricCounter { MEMPOOL_P2P_NUM_RECEIVED_TRANSACTIONS, "apollo_mempool_p2p_num_received_transactions", "The number of new transactions received from the network by the mempool p2p component", init = 0 },
+        MetricCounter { MEMPOOL_P2P_NUM_DUPLICATE_TRANSACTIONS, "apollo_mempool_p2p_num_duplicate_transactions", "The number of re-gossiped transactions dropped by the mempool p2p component", init = 0 },
+        MetricCounter { MEMPOOL_P2P_NUM_RECOVERED_TRANSACTIONS, "apollo_mempool_p2p_num_recovered_transactions", "The number of transactions missing from the mempool that were recovered from other mempools", init = 0 },
         // Histogram
-        MetricHistogram { MEMPOOL_P2P_BROADCASTED_BATCH_SIZE, "apollo_mempool_p2p_broadcasted_transaction_batch_size", "The number of transactions in batches broadcast by the mempool p2p component" }
+        MetricHistogram { MEMPOOL_P2P_BROADCASTED_BATCH_SIZE, "apollo_mempool_p2p_broadcasted_transaction_batch_size", "The number of transactions in batches broadcast by the mempool p2p component" },
+        MetricHistogram { MEMPOOL_P2P_DUPLICATE_TRANSACTION_DELAY, "apollo_mempool_p2p_duplicate_transaction_delay_seconds", "The time between first receiving a transaction and receiving it again from the network" },
+        MetricHistogram { MEMPOOL_P2P_PEER_FIRST_DELIVERIES, "apollo_mempool_p2p_peer_first_deliveries", "The number of accepted transactions each peer was the first to deliver in a statistics interval" },
+        MetricHistogram { MEMPOOL_P2P_PEER_DUPLICATE_DELIVERIES, "apollo_mempool_p2p_peer_duplicate_deliveries", "The number of transactions each peer delivered after they were already seen in a statistics interval" }
     },
 );
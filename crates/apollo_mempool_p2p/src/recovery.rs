@@ -0,0 +1,331 @@
+#[cfg(test)]
+#[path = "recovery_test.rs"]
+mod recovery_test;
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use apollo_class_manager_types::transaction_converter::TransactionConverterTrait;
+use apollo_mempool_types::communication::{MempoolClientError, SharedMempoolClient};
+use apollo_network::network_manager::{
+    ClientResponsesManager,
+    ServerQueryManager,
+    SqmrClientSender,
+    SqmrServerReceiver,
+};
+use apollo_protobuf::converters::ProtobufConversionError;
+use apollo_protobuf::mempool::{
+    AccountTransactionHashes,
+    MempoolSummaryQuery,
+    MempoolTransactionsQuery,
+};
+use apollo_protobuf::sync::DataOrFin;
+use futures::channel::mpsc::SendError;
+use futures::future::BoxFuture;
+use futures::{FutureExt, StreamExt};
+use starknet_api::core::ChainId;
+use starknet_api::rpc_transaction::RpcTransaction;
+use starknet_api::transaction::{Transaction, TransactionHash};
+use tracing::{debug, info, warn};
+
+pub const MEMPOOL_SUMMARY_PROTOCOL: &str = "/starknet/mempool_summary/0.1.0";
+pub const MEMPOOL_TRANSACTIONS_PROTOCOL: &str = "/starknet/mempool_transactions/0.1.0";
+
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(15);
+
+pub type MempoolSummaryClientSender =
+    SqmrClientSender<MempoolSummaryQuery, DataOrFin<AccountTransactionHashes>>;
+pub type MempoolTransactionsClientSender =
+    SqmrClientSender<MempoolTransactionsQuery, DataOrFin<RpcTransaction>>;
+pub type MempoolSummaryServerReceiver =
+    SqmrServerReceiver<MempoolSummaryQuery, DataOrFin<AccountTransactionHashes>>;
+pub type MempoolTransactionsServerReceiver =
+    SqmrServerReceiver<MempoolTransactionsQuery, DataOrFin<RpcTransaction>>;
+
+type MempoolSummaryServerQueryManager =
+    ServerQueryManager<MempoolSummaryQuery, DataOrFin<AccountTransactionHashes>>;
+type MempoolTransactionsServerQueryManager =
+    ServerQueryManager<MempoolTransactionsQuery, DataOrFin<RpcTransaction>>;
+
+#[derive(thiserror::Error, Debug)]
+pub enum MempoolRecoveryError {
+    #[error(transparent)]
+    MempoolClientError(#[from] MempoolClientError),
+    #[error(transparent)]
+    NetworkSendError(#[from] SendError),
+    #[error(transparent)]
+    ProtobufConversionError(#[from] ProtobufConversionError),
+    #[error("Peer didn't respond in time to a {type_description} query.")]
+    ResponseTimeout { type_description: &'static str },
+    #[error("The {type_description} session ended without a Fin.")]
+    SessionEndedWithoutFin { type_description: &'static str },
+    #[error("Peer sent transaction {tx_hash} which wasn't requested.")]
+    UnrequestedTransaction { tx_hash: TransactionHash },
+    #[error("Peer sent a mempool summary with more than {max_transactions} transactions.")]
+    SummaryTooLarge { max_transactions: usize },
+}
+
+/// Pulls the transactions that other mempools have and the local mempool doesn't. Used to make
+/// the mempools converge after restarts and network splits, since gossiped transactions are only
+/// delivered to the peers that were connected when they were gossiped.
+pub struct MempoolRecoveryClient {
+    summary_client: MempoolSummaryClientSender,
+    transactions_client: MempoolTransactionsClientSender,
+    mempool_client: SharedMempoolClient,
+    chain_id: ChainId,
+    max_transactions_per_query: usize,
+    max_transactions_per_summary: usize,
+}
+
+impl MempoolRecoveryClient {
+    pub fn new(
+        summary_client: MempoolSummaryClientSender,
+        transactions_client: MempoolTransactionsClientSender,
+        mempool_client: SharedMempoolClient,
+        chain_id: ChainId,
+        max_transactions_per_query: usize,
+        max_transactions_per_summary: usize,
+    ) -> Self {
+        assert!(max_transactions_per_query > 0, "Recovery queries must request transactions.");
+        Self {
+            summary_client,
+            transactions_client,
+            mempool_client,
+            chain_id,
+            max_transactions_per_query,
+            max_transactions_per_summary,
+        }
+    }
+
+    /// Requests the summary of a peer's mempool and fetches the transactions that are missing from
+    /// the local mempool. The transactions are returned in the order of the summary, i.e. the
+    /// transactions of each account are sorted by nonce.
+    pub async fn recover(&mut self) -> Result<Vec<RpcTransaction>, MempoolRecoveryError> {
+        let remote_summary = self.fetch_summary().await?;
+        let local_tx_hashes: HashSet<TransactionHash> = self
+            .mempool_client
+            .get_tx_hashes_by_account()
+            .await?
+            .into_iter()
+            .flat_map(|(_address, tx_hashes)| tx_hashes)
+            .collect();
+        let missing_tx_hashes: Vec<TransactionHash> = remote_summary
+            .into_iter()
+            .flat_map(|account_transaction_hashes| account_transaction_hashes.transaction_hashes)
+            .filter(|tx_hash| !local_tx_hashes.contains(tx_hash))
+            .collect();
+        if missing_tx_hashes.is_empty() {
+            debug!("The mempool isn't missing any transaction of the queried peer.");
+            return Ok(vec![]);
+        }
+        info!("Fetching {} transactions missing from the mempool.", missing_tx_hashes.len());
+
+        let mut transactions = Vec::with_capacity(missing_tx_hashes.len());
+        for tx_hashes in missing_tx_hashes.chunks(self.max_transactions_per_query) {
+            transactions.extend(self.fetch_transactions(tx_hashes.to_vec()).await?);
+        }
+        Ok(transactions)
+    }
+
+    async fn fetch_summary(
+        &mut self,
+    ) -> Result<Vec<AccountTransactionHashes>, MempoolRecoveryError> {
+        let responses_manager = self.summary_client.send_new_query(MempoolSummaryQuery).await?;
+        let max_transactions = self.max_transactions_per_summary;
+        let mut num_transactions = 0;
+        collect_responses(responses_manager, "mempool summary", |account_transaction_hashes| {
+            num_transactions += account_transaction_hashes.transaction_hashes.len();
+            if num_transactions > max_transactions {
+                return Err(MempoolRecoveryError::SummaryTooLarge { max_transactions });
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn fetch_transactions(
+        &mut self,
+        tx_hashes: Vec<TransactionHash>,
+    ) -> Result<Vec<RpcTransaction>, MempoolRecoveryError> {
+        let mut requested_tx_hashes: HashSet<TransactionHash> = tx_hashes.iter().copied().collect();
+        let responses_manager = self
+            .transactions_client
+            .send_new_query(MempoolTransactionsQuery { transaction_hashes: tx_hashes })
+            .await?;
+        let chain_id = &self.chain_id;
+        collect_responses(responses_manager, "mempool transactions", |transaction| {
+            // A transaction whose hash can't be calculated can't be one of the requested ones.
+            let tx_hash = Transaction::from(transaction.clone())
+                .calculate_transaction_hash(chain_id)
+                .unwrap_or_default();
+            // Removing the hash also rejects transactions that were sent twice.
+            if requested_tx_hashes.remove(&tx_hash) {
+                Ok(())
+            } else {
+                Err(MempoolRecoveryError::UnrequestedTransaction { tx_hash })
+            }
+        })
+        .await
+    }
+}
+
+/// Collects the data sent in a session until the peer sends a Fin. The peer is reported if it
+/// sent a response that couldn't be parsed or that failed the given validation.
+async fn collect_responses<T>(
+    mut responses_manager: ClientResponsesManager<DataOrFin<T>>,
+    type_description: &'static str,
+    mut validate: impl FnMut(&T) -> Result<(), MempoolRecoveryError>,
+) -> Result<Vec<T>, MempoolRecoveryError>
+where
+    DataOrFin<T>: TryFrom<Vec<u8>, Error = ProtobufConversionError>,
+{
+    let mut data = vec![];
+    loop {
+        let response = tokio::time::timeout(RESPONSE_TIMEOUT, responses_manager.next())
+            .await
+            .map_err(|_| MempoolRecoveryError::ResponseTimeout { type_description })?
+            .ok_or(MempoolRecoveryError::SessionEndedWithoutFin { type_description })?;
+        let result =
+            response.map_err(MempoolRecoveryError::from).and_then(|DataOrFin(maybe_data)| {
+                maybe_data.as_ref().map(&mut validate).transpose()?;
+                Ok(maybe_data)
+            });
+        match result {
+            Ok(Some(new_data)) => data.push(new_data),
+            Ok(None) => return Ok(data),
+            Err(err) => {
+                responses_manager.report_peer();
+                return Err(err);
+            }
+        }
+    }
+}
+
+/// Serves the mempool recovery queries of other peers from the content of the local mempool.
+pub struct MempoolRecoveryServer {
+    summary_server: MempoolSummaryServerReceiver,
+    transactions_server: MempoolTransactionsServerReceiver,
+    mempool_client: SharedMempoolClient,
+    transaction_converter: Arc<dyn TransactionConverterTrait>,
+    max_transactions_per_query: usize,
+    max_transactions_per_summary: usize,
+}
+
+impl MempoolRecoveryServer {
+    pub fn new(
+        summary_server: MempoolSummaryServerReceiver,
+        transactions_server: MempoolTransactionsServerReceiver,
+        mempool_client: SharedMempoolClient,
+        transaction_converter: Arc<dyn TransactionConverterTrait>,
+        max_transactions_per_query: usize,
+        max_transactions_per_summary: usize,
+    ) -> Self {
+        Self {
+            summary_server,
+            transactions_server,
+            mempool_client,
+            transaction_converter,
+            max_transactions_per_query,
+            max_transactions_per_summary,
+        }
+    }
+
+    /// Waits for the next query of a peer and returns a future that responds to it.
+    pub async fn next_query_response(&mut self) -> BoxFuture<'static, ()> {
+        tokio::select! {
+            Some(query_manager) = self.summary_server.next() => {
+                respond_to_summary_query(
+                    self.mempool_client.clone(),
+                    self.max_transactions_per_summary,
+                    query_manager,
+                )
+                .boxed()
+            }
+            Some(query_manager) = self.transactions_server.next() => {
+                respond_to_transactions_query(
+                    self.mempool_client.clone(),
+                    self.transaction_converter.clone(),
+                    self.max_transactions_per_query,
+                    query_manager,
+                )
+                .boxed()
+            }
+            else => futures::future::pending().await,
+        }
+    }
+}
+
+/// Responds with the transaction hashes of each account in the mempool. Only the first
+/// `max_transactions_per_summary` hashes are served, and the transactions of an account are
+/// truncated by nonce. The session always ends with a Fin, also if the mempool failed.
+async fn respond_to_summary_query(
+    mempool_client: SharedMempoolClient,
+    max_transactions_per_summary: usize,
+    mut query_manager: MempoolSummaryServerQueryManager,
+) {
+    if let Err(err) = query_manager.query() {
+        warn!("Received an invalid mempool summary query: {err:?}. Reporting peer.");
+        query_manager.report_peer();
+        return;
+    }
+    let tx_hashes_by_account = match mempool_client.get_tx_hashes_by_account().await {
+        Ok(tx_hashes_by_account) => tx_hashes_by_account,
+        Err(err) => {
+            warn!("Failed getting the mempool summary from the mempool: {err:?}");
+            vec![]
+        }
+    };
+    let mut num_remaining_transactions = max_transactions_per_summary;
+    for (address, mut transaction_hashes) in tx_hashes_by_account {
+        if num_remaining_transactions == 0 {
+            break;
+        }
+        transaction_hashes.truncate(num_remaining_transactions);
+        num_remaining_transactions -= transaction_hashes.len();
+        let account_transaction_hashes = AccountTransactionHashes { address, transaction_hashes };
+        if query_manager.send_response(DataOrFin(Some(account_transaction_hashes))).await.is_err() {
+            return;
+        }
+    }
+    let _ = query_manager.send_response(DataOrFin(None)).await;
+}
+
+/// Responds with the requested transactions that are in the mempool. Only the first
+/// `max_transactions_per_query` hashes of the query are served. The session always ends with a
+/// Fin, also if the mempool failed.
+async fn respond_to_transactions_query(
+    mempool_client: SharedMempoolClient,
+    transaction_converter: Arc<dyn TransactionConverterTrait>,
+    max_transactions_per_query: usize,
+    mut query_manager: MempoolTransactionsServerQueryManager,
+) {
+    let Ok(query) = query_manager.query().clone() else {
+        warn!("Received an invalid mempool transactions query. Reporting peer.");
+        query_manager.report_peer();
+        return;
+    };
+    let tx_hashes = query.transaction_hashes.into_iter().take(max_transactions_per_query).collect();
+    let transactions = match mempool_client.get_txs_by_hash(tx_hashes).await {
+        Ok(transactions) => transactions,
+        Err(err) => {
+            warn!("Failed getting transactions from the mempool: {err:?}");
+            vec![]
+        }
+    };
+    for transaction in transactions {
+        let tx_hash = transaction.tx_hash;
+        let transaction =
+            match transaction_converter.convert_internal_rpc_tx_to_rpc_tx(transaction).await {
+                Ok(transaction) => transaction,
+                Err(err) => {
+                    warn!("Failed converting transaction {tx_hash} from the mempool: {err:?}");
+                    continue;
+                }
+            };
+        if query_manager.send_response(DataOrFin(Some(transaction))).await.is_err() {
+            return;
+        }
+    }
+    let _ = query_manager.send_response(DataOrFin(None)).await;
+}
@@ -0,0 +1,367 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use apollo_class_manager_types::transaction_converter::MockTransactionConverterTrait;
+use apollo_infra::component_client::ClientError;
+use apollo_mempool_types::communication::{MempoolClientError, MockMempoolClient};
+use apollo_network::network_manager::test_utils::{
+    create_test_server_query_manager,
+    mock_register_sqmr_protocol_client,
+};
+use apollo_protobuf::mempool::{
+    AccountTransactionHashes,
+    MempoolSummaryQuery,
+    MempoolTransactionsQuery,
+};
+use apollo_protobuf::sync::DataOrFin;
+use futures::StreamExt;
+use mockall::predicate::eq;
+use starknet_api::core::{ChainId, Nonce};
+use starknet_api::rpc_transaction::RpcTransaction;
+use starknet_api::test_utils::invoke::{internal_invoke_tx, rpc_invoke_tx};
+use starknet_api::transaction::{Transaction, TransactionHash};
+use starknet_api::{contract_address, invoke_tx_args};
+
+use super::{
+    respond_to_summary_query,
+    respond_to_transactions_query,
+    MempoolRecoveryClient,
+    MempoolRecoveryError,
+};
+
+const BUFFER_SIZE: usize = 10;
+const MAX_TRANSACTIONS_PER_QUERY: usize = 2;
+const MAX_TRANSACTIONS_PER_SUMMARY: usize = 4;
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn transaction_with_nonce(nonce: u8) -> (RpcTransaction, TransactionHash) {
+    let transaction = rpc_invoke_tx(invoke_tx_args!(
+        sender_address: contract_address!("0x1"),
+        nonce: Nonce(nonce.into()),
+    ));
+    let tx_hash = Transaction::from(transaction.clone())
+        .calculate_transaction_hash(&ChainId::Mainnet)
+        .unwrap();
+    (transaction, tx_hash)
+}
+
+#[tokio::test]
+async fn recover_fetches_only_missing_transactions() {
+    let transactions: Vec<_> = (0..4).map(transaction_with_nonce).collect();
+    let tx_hashes: Vec<_> = transactions.iter().map(|(_, tx_hash)| *tx_hash).collect();
+    let address = contract_address!("0x1");
+
+    // The local mempool only has the second transaction.
+    let mut mempool_client = MockMempoolClient::new();
+    let local_tx_hashes = vec![(address, vec![tx_hashes[1]])];
+    mempool_client.expect_get_tx_hashes_by_account().return_once(move || Ok(local_tx_hashes));
+
+    let (summary_client, mut summary_queries) = mock_register_sqmr_protocol_client(BUFFER_SIZE);
+    let (transactions_client, mut transactions_queries) =
+        mock_register_sqmr_protocol_client(BUFFER_SIZE);
+    let mut recovery_client = MempoolRecoveryClient::new(
+        summary_client,
+        transactions_client,
+        Arc::new(mempool_client),
+        ChainId::Mainnet,
+        MAX_TRANSACTIONS_PER_QUERY,
+        MAX_TRANSACTIONS_PER_SUMMARY,
+    );
+
+    let peer = async move {
+        let mut summary_query = summary_queries.next().await.unwrap();
+        assert_eq!(*summary_query.query(), Ok(MempoolSummaryQuery));
+        summary_query
+            .send_response(DataOrFin(Some(AccountTransactionHashes {
+                address,
+                transaction_hashes: tx_hashes.clone(),
+            })))
+            .await
+            .unwrap();
+        summary_query.send_response(DataOrFin(None)).await.unwrap();
+
+        // The missing transactions are requested in queries of at most
+        // MAX_TRANSACTIONS_PER_QUERY transactions.
+        for expected_indices in [vec![0, 2], vec![3]] {
+            let mut transactions_query = transactions_queries.next().await.unwrap();
+            let expected_tx_hashes = expected_indices.iter().map(|i| tx_hashes[*i]).collect();
+            assert_eq!(
+                *transactions_query.query(),
+                Ok(MempoolTransactionsQuery { transaction_hashes: expected_tx_hashes })
+            );
+            for i in expected_indices {
+                transactions_query
+                    .send_response(DataOrFin(Some(transactions[i].0.clone())))
+                    .await
+                    .unwrap();
+            }
+            transactions_query.send_response(DataOrFin(None)).await.unwrap();
+        }
+    };
+
+    let (recovered_transactions, ()) =
+        tokio::time::timeout(TIMEOUT, futures::future::join(recovery_client.recover(), peer))
+            .await
+            .unwrap();
+    let expected_transactions: Vec<_> =
+        [0, 2, 3].into_iter().map(|i| transaction_with_nonce(i).0).collect();
+    assert_eq!(recovered_transactions.unwrap(), expected_transactions);
+}
+
+#[tokio::test]
+async fn recover_reports_peer_that_sends_unrequested_transaction() {
+    let (_, requested_tx_hash) = transaction_with_nonce(0);
+    let (unrequested_transaction, unrequested_tx_hash) = transaction_with_nonce(1);
+    let address = contract_address!("0x1");
+
+    let mut mempool_client = MockMempoolClient::new();
+    mempool_client.expect_get_tx_hashes_by_account().return_once(|| Ok(vec![]));
+
+    let (summary_client, mut summary_queries) = mock_register_sqmr_protocol_client(BUFFER_SIZE);
+    let (transactions_client, mut transactions_queries) =
+        mock_register_sqmr_protocol_client(BUFFER_SIZE);
+    let mut recovery_client = MempoolRecoveryClient::new(
+        summary_client,
+        transactions_client,
+        Arc::new(mempool_client),
+        ChainId::Mainnet,
+        MAX_TRANSACTIONS_PER_QUERY,
+        MAX_TRANSACTIONS_PER_SUMMARY,
+    );
+
+    let peer = async move {
+        let mut summary_query = summary_queries.next().await.unwrap();
+        summary_query
+            .send_response(DataOrFin(Some(AccountTransactionHashes {
+                address,
+                transaction_hashes: vec![requested_tx_hash],
+            })))
+            .await
+            .unwrap();
+        summary_query.send_response(DataOrFin(None)).await.unwrap();
+
+        let mut transactions_query = transactions_queries.next().await.unwrap();
+        transactions_query.send_response(DataOrFin(Some(unrequested_transaction))).await.unwrap();
+        transactions_query.assert_reported(TIMEOUT).await;
+    };
+
+    let (result, ()) =
+        tokio::time::timeout(TIMEOUT, futures::future::join(recovery_client.recover(), peer))
+            .await
+            .unwrap();
+    assert_matches::assert_matches!(
+        result,
+        Err(MempoolRecoveryError::UnrequestedTransaction { tx_hash })
+        if tx_hash == unrequested_tx_hash
+    );
+}
+
+#[tokio::test]
+async fn recover_reports_peer_that_sends_too_large_summary() {
+    let tx_hashes: Vec<_> =
+        (0..=MAX_TRANSACTIONS_PER_SUMMARY).map(|i| TransactionHash(i.into())).collect();
+
+    let (summary_client, mut summary_queries) = mock_register_sqmr_protocol_client(BUFFER_SIZE);
+    let (transactions_client, _transactions_queries) =
+        mock_register_sqmr_protocol_client(BUFFER_SIZE);
+    let mut recovery_client = MempoolRecoveryClient::new(
+        summary_client,
+        transactions_client,
+        Arc::new(MockMempoolClient::new()),
+        ChainId::Mainnet,
+        MAX_TRANSACTIONS_PER_QUERY,
+        MAX_TRANSACTIONS_PER_SUMMARY,
+    );
+
+    let peer = async move {
+        let mut summary_query = summary_queries.next().await.unwrap();
+        // The summary is split between messages, and only their total size exceeds the maximum.
+        for transaction_hashes in tx_hashes.chunks(2) {
+            summary_query
+                .send_response(DataOrFin(Some(AccountTransactionHashes {
+                    address: contract_address!("0x1"),
+                    transaction_hashes: transaction_hashes.to_vec(),
+                })))
+                .await
+                .unwrap();
+        }
+        summary_query.assert_reported(TIMEOUT).await;
+    };
+
+    let (result, ()) =
+        tokio::time::timeout(TIMEOUT, futures::future::join(recovery_client.recover(), peer))
+            .await
+            .unwrap();
+    assert_matches::assert_matches!(
+        result,
+        Err(MempoolRecoveryError::SummaryTooLarge { max_transactions })
+        if max_transactions == MAX_TRANSACTIONS_PER_SUMMARY
+    );
+}
+
+#[tokio::test]
+async fn server_truncates_summary() {
+    let first_address = contract_address!("0x1");
+    let second_address = contract_address!("0x2");
+    let tx_hashes: Vec<_> =
+        (0..MAX_TRANSACTIONS_PER_SUMMARY + 2).map(|i| TransactionHash(i.into())).collect();
+    let (first_account_tx_hashes, second_account_tx_hashes) =
+        tx_hashes.split_at(MAX_TRANSACTIONS_PER_SUMMARY - 1);
+
+    let mut mempool_client = MockMempoolClient::new();
+    let tx_hashes_by_account = vec![
+        (first_address, first_account_tx_hashes.to_vec()),
+        (second_address, second_account_tx_hashes.to_vec()),
+        (contract_address!("0x3"), vec![TransactionHash(1234_u16.into())]),
+    ];
+    mempool_client.expect_get_tx_hashes_by_account().return_once(move || Ok(tx_hashes_by_account));
+
+    let (summary_query_manager, _summary_report_receiver, summary_responses) =
+        create_test_server_query_manager(MempoolSummaryQuery);
+    let summary_responses = futures::future::join(
+        respond_to_summary_query(
+            Arc::new(mempool_client),
+            MAX_TRANSACTIONS_PER_SUMMARY,
+            summary_query_manager,
+        ),
+        summary_responses.collect::<Vec<_>>(),
+    );
+    let ((), summary_responses) = tokio::time::timeout(TIMEOUT, summary_responses).await.unwrap();
+    assert_eq!(
+        summary_responses,
+        vec![
+            DataOrFin(Some(AccountTransactionHashes {
+                address: first_address,
+                transaction_hashes: first_account_tx_hashes.to_vec(),
+            })),
+            DataOrFin(Some(AccountTransactionHashes {
+                address: second_address,
+                transaction_hashes: second_account_tx_hashes[..1].to_vec(),
+            })),
+            DataOrFin(None),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn server_sends_fin_when_mempool_fails() {
+    let mempool_error =
+        || MempoolClientError::ClientError(ClientError::CommunicationFailure("".to_string()));
+    let mut mempool_client = MockMempoolClient::new();
+    mempool_client.expect_get_tx_hashes_by_account().return_once(move || Err(mempool_error()));
+    mempool_client.expect_get_txs_by_hash().return_once(move |_| Err(mempool_error()));
+    let mempool_client = Arc::new(mempool_client);
+
+    let (summary_query_manager, _summary_report_receiver, summary_responses) =
+        create_test_server_query_manager(MempoolSummaryQuery);
+    let summary_responses = futures::future::join(
+        respond_to_summary_query(
+            mempool_client.clone(),
+            MAX_TRANSACTIONS_PER_SUMMARY,
+            summary_query_manager,
+        ),
+        summary_responses.collect::<Vec<_>>(),
+    );
+    let ((), summary_responses) = tokio::time::timeout(TIMEOUT, summary_responses).await.unwrap();
+    assert_eq!(summary_responses, vec![DataOrFin(None)]);
+
+    let query = MempoolTransactionsQuery { transaction_hashes: vec![TransactionHash(1_u8.into())] };
+    let (transactions_query_manager, _transactions_report_receiver, transactions_responses) =
+        create_test_server_query_manager(query);
+    let transactions_responses = futures::future::join(
+        respond_to_transactions_query(
+            mempool_client,
+            Arc::new(MockTransactionConverterTrait::new()),
+            MAX_TRANSACTIONS_PER_QUERY,
+            transactions_query_manager,
+        ),
+        transactions_responses.collect::<Vec<_>>(),
+    );
+    let ((), transactions_responses) =
+        tokio::time::timeout(TIMEOUT, transactions_responses).await.unwrap();
+    assert_eq!(transactions_responses, vec![DataOrFin(None)]);
+}
+
+#[tokio::test]
+async fn server_responds_with_mempool_content() {
+    let address = contract_address!("0x1");
+    let (transaction, tx_hash) = transaction_with_nonce(0);
+    let unknown_tx_hash = TransactionHash(1_u8.into());
+    let internal_transaction =
+        internal_invoke_tx(invoke_tx_args!(sender_address: address, tx_hash: tx_hash));
+
+    let mut mempool_client = MockMempoolClient::new();
+    mempool_client
+        .expect_get_tx_hashes_by_account()
+        .return_once(move || Ok(vec![(address, vec![tx_hash])]));
+    let returned_internal_transaction = internal_transaction.clone();
+    mempool_client
+        .expect_get_txs_by_hash()
+        .with(eq(vec![unknown_tx_hash, tx_hash]))
+        .return_once(move |_| Ok(vec![returned_internal_transaction]));
+    let mempool_client = Arc::new(mempool_client);
+
+    let mut transaction_converter = MockTransactionConverterTrait::new();
+    let converted_transaction = transaction.clone();
+    transaction_converter
+        .expect_convert_internal_rpc_tx_to_rpc_tx()
+        .with(eq(internal_transaction))
+        .return_once(move |_| Ok(converted_transaction));
+
+    let (summary_query_manager, _summary_report_receiver, summary_responses) =
+        create_test_server_query_manager(MempoolSummaryQuery);
+    let summary_responses = futures::future::join(
+        respond_to_summary_query(
+            mempool_client.clone(),
+            MAX_TRANSACTIONS_PER_SUMMARY,
+            summary_query_manager,
+        ),
+        summary_responses.collect::<Vec<_>>(),
+    );
+    let ((), summary_responses) = tokio::time::timeout(TIMEOUT, summary_responses).await.unwrap();
+    assert_eq!(
+        summary_responses,
+        vec![
+            DataOrFin(Some(AccountTransactionHashes {
+                address,
+                transaction_hashes: vec![tx_hash]
+            })),
+            DataOrFin(None),
+        ]
+    );
+
+    // The last hash is dropped since it exceeds the maximum number of transactions per query.
+    let query = MempoolTransactionsQuery {
+        transaction_hashes: vec![unknown_tx_hash, tx_hash, TransactionHash(2_u8.into())],
+    };
+    let (transactions_query_manager, _transactions_report_receiver, transactions_responses) =
+        create_test_server_query_manager(query);
+    let transactions_responses = futures::future::join(
+        respond_to_transactions_query(
+            mempool_client,
+            Arc::new(transaction_converter),
+            MAX_TRANSACTIONS_PER_QUERY,
+            transactions_query_manager,
+        ),
+        transactions_responses.collect::<Vec<_>>(),
+    );
+    let ((), transactions_responses) =
+        tokio::time::timeout(TIMEOUT, transactions_responses).await.unwrap();
+    assert_eq!(transactions_responses, vec![DataOrFin(Some(transaction)), DataOrFin(None)]);
+}
+
+#[test]
+#[should_panic(expected = "Recovery queries must request transactions.")]
+fn recovery_client_panics_on_zero_transactions_per_query() {
+    let (summary_client, _summary_queries) = mock_register_sqmr_protocol_client(BUFFER_SIZE);
+    let (transactions_client, _transactions_queries) =
+        mock_register_sqmr_protocol_client(BUFFER_SIZE);
+    MempoolRecoveryClient::new(
+        summary_client,
+        transactions_client,
+        Arc::new(MockMempoolClient::new()),
+        ChainId::Mainnet,
+        0,
+        MAX_TRANSACTIONS_PER_SUMMARY,
+    );
+}
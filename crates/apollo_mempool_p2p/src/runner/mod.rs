@@ -1,11 +1,15 @@
 #[cfg(test)]
 mod test;
 
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use apollo_gateway_types::communication::{GatewayClientError, SharedGatewayClient};
+use apollo_gateway_types::communication::{
+    GatewayClientError,
+    GatewayClientResult,
+    SharedGatewayClient,
+};
 use apollo_gateway_types::errors::GatewayError;
-use apollo_gateway_types::gateway_types::GatewayInput;
+use apollo_gateway_types::gateway_types::{GatewayInput, GatewayOutput};
 use apollo_infra::component_definitions::ComponentStarter;
 use apollo_infra::component_server::WrapperServer;
 use apollo_mempool_p2p_types::communication::SharedMempoolP2pPropagatorClient;
@@ -15,14 +19,33 @@ use apollo_network::network_manager::{
     BroadcastTopicServer,
     NetworkError,
 };
+use apollo_network_types::network_types::OpaquePeerId;
 use apollo_protobuf::mempool::RpcTransactionBatch;
 use async_trait::async_trait;
 use futures::future::BoxFuture;
 use futures::stream::FuturesUnordered;
-use futures::StreamExt;
+use futures::{FutureExt, StreamExt};
+use starknet_api::core::ChainId;
+use starknet_api::rpc_transaction::RpcTransaction;
+use starknet_api::transaction::{Transaction, TransactionHash};
 use tokio::time::MissedTickBehavior::Delay;
 use tracing::{debug, info, warn};
 
+use crate::metrics::{
+    MEMPOOL_P2P_DUPLICATE_TRANSACTION_DELAY,
+    MEMPOOL_P2P_NUM_DELIVERING_PEERS,
+    MEMPOOL_P2P_NUM_DUPLICATE_TRANSACTIONS,
+    MEMPOOL_P2P_NUM_RECEIVED_TRANSACTIONS,
+    MEMPOOL_P2P_NUM_RECOVERED_TRANSACTIONS,
+    MEMPOOL_P2P_PEER_DUPLICATE_DELIVERIES,
+    MEMPOOL_P2P_PEER_FIRST_DELIVERIES,
+};
+use crate::recovery::{MempoolRecoveryClient, MempoolRecoveryServer};
+use crate::seen_transactions::{Observation, SeenTransactions};
+
+/// The interval in which the per-peer gossip statistics are collected before they're exported.
+const PEER_STATS_INTERVAL: Duration = Duration::from_secs(60);
+
 pub struct MempoolP2pRunner {
     network_future: BoxFuture<'static, Result<(), NetworkError>>,
     broadcasted_topic_server: BroadcastTopicServer<RpcTransactionBatch>,
@@ -30,9 +53,16 @@ pub struct MempoolP2pRunner {
     gateway_client: SharedGatewayClient,
     _mempool_p2p_propagator_client: SharedMempoolP2pPropagatorClient,
     transaction_batch_rate_millis: Duration,
+    seen_transactions: SeenTransactions,
+    chain_id: ChainId,
+    // None while a recovery is in progress.
+    recovery_client: Option<MempoolRecoveryClient>,
+    recovery_server: MempoolRecoveryServer,
+    recovery_interval_millis: Duration,
 }
 
 impl MempoolP2pRunner {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         network_future: BoxFuture<'static, Result<(), NetworkError>>,
         broadcasted_topic_server: BroadcastTopicServer<RpcTransactionBatch>,
@@ -40,7 +70,19 @@ impl MempoolP2pRunner {
         gateway_client: SharedGatewayClient,
         mempool_p2p_propagator_client: SharedMempoolP2pPropagatorClient,
         transaction_batch_rate_millis: Duration,
+        seen_transactions: SeenTransactions,
+        chain_id: ChainId,
+        recovery_client: MempoolRecoveryClient,
+        recovery_server: MempoolRecoveryServer,
+        recovery_interval_millis: Duration,
     ) -> Self {
+        MEMPOOL_P2P_NUM_DELIVERING_PEERS.register();
+        MEMPOOL_P2P_NUM_RECEIVED_TRANSACTIONS.register();
+        MEMPOOL_P2P_NUM_DUPLICATE_TRANSACTIONS.register();
+        MEMPOOL_P2P_DUPLICATE_TRANSACTION_DELAY.register();
+        MEMPOOL_P2P_NUM_RECOVERED_TRANSACTIONS.register();
+        MEMPOOL_P2P_PEER_FIRST_DELIVERIES.register();
+        MEMPOOL_P2P_PEER_DUPLICATE_DELIVERIES.register();
         Self {
             network_future,
             broadcasted_topic_server,
@@ -48,7 +90,71 @@ impl MempoolP2pRunner {
             gateway_client,
             _mempool_p2p_propagator_client: mempool_p2p_propagator_client,
             transaction_batch_rate_millis,
+            seen_transactions,
+            chain_id,
+            recovery_client: Some(recovery_client),
+            recovery_server,
+            recovery_interval_millis,
+        }
+    }
+
+    /// Returns whether the transaction should be forwarded to the gateway, i.e. it wasn't already
+    /// received from the network recently, along with its hash if it could be calculated. `peer`
+    /// is None for transactions that weren't gossiped by a specific peer.
+    fn observe_transaction(
+        &mut self,
+        rpc_tx: &RpcTransaction,
+        peer: Option<&OpaquePeerId>,
+    ) -> (bool, Option<TransactionHash>) {
+        let tx_hash =
+            match Transaction::from(rpc_tx.clone()).calculate_transaction_hash(&self.chain_id) {
+                Ok(tx_hash) => tx_hash,
+                Err(err) => {
+                    // The gateway will reject the transaction, so there's no point in deduplicating
+                    // it.
+                    debug!("Failed calculating the hash of a transaction from the network: {err}");
+                    return (true, None);
+                }
+            };
+        let observation = self.seen_transactions.observe(tx_hash, peer, Instant::now());
+        match observation {
+            Observation::New => {
+                MEMPOOL_P2P_NUM_RECEIVED_TRANSACTIONS.increment(1);
+                (true, Some(tx_hash))
+            }
+            Observation::Duplicate { since_first_seen } => {
+                debug!(
+                    "Dropping transaction {tx_hash} that was first seen {since_first_seen:?} ago."
+                );
+                MEMPOOL_P2P_NUM_DUPLICATE_TRANSACTIONS.increment(1);
+                MEMPOOL_P2P_DUPLICATE_TRANSACTION_DELAY.record(since_first_seen.as_secs_f64());
+                (false, Some(tx_hash))
+            }
+        }
+    }
+
+    /// Sends the transaction to the gateway. The returned future resolves to the transaction's
+    /// hash, if it's known, and the gateway's response.
+    fn add_tx_to_gateway(
+        &self,
+        tx_hash: Option<TransactionHash>,
+        gateway_input: GatewayInput,
+    ) -> BoxFuture<'static, (Option<TransactionHash>, GatewayClientResult<GatewayOutput>)> {
+        let gateway_client = self.gateway_client.clone();
+        async move { (tx_hash, gateway_client.add_tx(gateway_input).await) }.boxed()
+    }
+
+    /// Exports the gossip statistics collected for each peer since the previous export.
+    fn export_peer_stats(&mut self) {
+        let peer_stats = self.seen_transactions.take_peer_stats();
+        debug!("Mempool gossip statistics per peer: {:?}", peer_stats);
+        for stats in peer_stats.values() {
+            MEMPOOL_P2P_PEER_FIRST_DELIVERIES.record_lossy(stats.first_deliveries);
+            MEMPOOL_P2P_PEER_DUPLICATE_DELIVERIES.record_lossy(stats.duplicate_deliveries);
         }
+        let num_delivering_peers =
+            peer_stats.values().filter(|stats| stats.first_deliveries > 0).count();
+        MEMPOOL_P2P_NUM_DELIVERING_PEERS.set_lossy(num_delivering_peers);
     }
 }
 
@@ -60,6 +166,18 @@ impl ComponentStarter for MempoolP2pRunner {
             tokio::time::interval(self.transaction_batch_rate_millis);
         transaction_batch_broadcast_interval.set_missed_tick_behavior(Delay);
         transaction_batch_broadcast_interval.tick().await; // The first tick is ready immediately so we consume it.
+
+        // The first recovery runs immediately in order to recover the transactions gossiped while
+        // the node was down.
+        let mut recovery_interval = tokio::time::interval(self.recovery_interval_millis);
+        recovery_interval.set_missed_tick_behavior(Delay);
+        let mut peer_stats_interval = tokio::time::interval_at(
+            tokio::time::Instant::now() + PEER_STATS_INTERVAL,
+            PEER_STATS_INTERVAL,
+        );
+        peer_stats_interval.set_missed_tick_behavior(Delay);
+        let mut recovery_futures = FuturesUnordered::new();
+        let mut recovery_response_futures = FuturesUnordered::new();
         loop {
             tokio::select! {
                 _ = &mut self.network_future => {
@@ -70,10 +188,54 @@ impl ComponentStarter for MempoolP2pRunner {
                         warn!("MempoolP2pPropagatorClient denied BroadcastQueuedTransactions request");
                     };
                 }
-                Some(result) = gateway_futures.next() => {
+                _ = peer_stats_interval.tick() => {
+                    self.export_peer_stats();
+                }
+                _ = recovery_interval.tick() => {
+                    match self.recovery_client.take() {
+                        Some(mut recovery_client) => recovery_futures.push(async move {
+                            let result = recovery_client.recover().await;
+                            (recovery_client, result)
+                        }.boxed()),
+                        None => debug!("The previous mempool recovery is still running. Skipping recovery."),
+                    }
+                }
+                Some((recovery_client, result)) = recovery_futures.next() => {
+                    self.recovery_client = Some(recovery_client);
                     match result {
-                        Ok(_) => {}
+                        Ok(transactions) => {
+                            // The recovered transactions are added to the mempool like local
+                            // transactions, so they're also propagated to peers that missed them.
+                            for rpc_tx in transactions {
+                                let (is_new, tx_hash) = self.observe_transaction(&rpc_tx, None);
+                                if !is_new {
+                                    continue;
+                                }
+                                MEMPOOL_P2P_NUM_RECOVERED_TRANSACTIONS.increment(1);
+                                gateway_futures.push(self.add_tx_to_gateway(
+                                    tx_hash,
+                                    GatewayInput { rpc_tx, message_metadata: None },
+                                ));
+                            }
+                        }
+                        Err(err) => warn!("Failed recovering transactions from other mempools: {err}"),
+                    }
+                }
+                recovery_response_future = self.recovery_server.next_query_response() => {
+                    recovery_response_futures.push(recovery_response_future);
+                }
+                Some(()) = recovery_response_futures.next() => {}
+                Some((tx_hash, result)) = gateway_futures.next() => {
+                    match result {
+                        Ok(_) => {
+                            if let Some(tx_hash) = tx_hash {
+                                self.seen_transactions.mark_accepted(tx_hash);
+                            }
+                        }
                         Err(gateway_client_error) => {
+                            if let Some(tx_hash) = tx_hash {
+                                self.seen_transactions.mark_rejected(tx_hash);
+                            }
                             // TODO(shahak): Analyze the error to see if it's the tx's fault or an
                             // internal error. Widen GatewayError's variants if necessary.
                             if let GatewayClientError::GatewayError(
@@ -101,8 +263,13 @@ impl ComponentStarter for MempoolP2pRunner {
                             info!("Received transaction from network, forwarding to gateway");
                             debug!("received transaction: {:?}", message.0);
                             for rpc_tx in message.0 {
-                                gateway_futures.push(self.gateway_client.add_tx(
-                                    GatewayInput { rpc_tx, message_metadata: Some(broadcasted_message_metadata.clone()) }
+                                let (is_new, tx_hash) = self.observe_transaction(&rpc_tx, Some(&broadcasted_message_metadata.originator_id));
+                                if !is_new {
+                                    continue;
+                                }
+                                gateway_futures.push(self.add_tx_to_gateway(
+                                    tx_hash,
+                                    GatewayInput { rpc_tx, message_metadata: Some(broadcasted_message_metadata.clone()) },
                                 ));
                             }
                         }
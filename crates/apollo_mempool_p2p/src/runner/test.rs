@@ -1,6 +1,7 @@
 use std::sync::Arc;
 use std::time::Duration;
 
+use apollo_class_manager_types::transaction_converter::MockTransactionConverterTrait;
 use apollo_gateway_types::communication::{GatewayClient, GatewayClientError, MockGatewayClient};
 use apollo_gateway_types::deprecated_gateway_error::{
     KnownStarknetErrorCode,
@@ -14,8 +15,11 @@ use apollo_mempool_p2p_types::communication::{
     MempoolP2pPropagatorClient,
     MockMempoolP2pPropagatorClient,
 };
+use apollo_mempool_types::communication::MockMempoolClient;
 use apollo_network::network_manager::test_utils::{
     mock_register_broadcast_topic,
+    mock_register_sqmr_protocol_client,
+    mock_register_sqmr_protocol_server,
     BroadcastNetworkMock,
     TestSubscriberChannels,
 };
@@ -26,13 +30,21 @@ use apollo_test_utils::{get_rng, GetTestInstance};
 use futures::future::{pending, ready, BoxFuture};
 use futures::stream::StreamExt;
 use futures::{FutureExt, SinkExt};
-use starknet_api::core::Nonce;
-use starknet_api::rpc_transaction::RpcTransaction;
+use starknet_api::core::{ChainId, Nonce};
+use starknet_api::rpc_transaction::{RpcInvokeTransaction, RpcInvokeTransactionV3, RpcTransaction};
 use starknet_api::transaction::TransactionHash;
 
 use super::MempoolP2pRunner;
+use crate::recovery::{MempoolRecoveryClient, MempoolRecoveryServer};
+use crate::seen_transactions::SeenTransactions;
+use crate::MAX_TRACKED_PEERS;
 
 const MAX_TRANSACTION_BATCH_RATE: Duration = Duration::MAX;
+const SEEN_TRANSACTIONS_CACHE_SIZE: usize = 100;
+const BUFFER_SIZE: usize = 10;
+const MAX_TRANSACTIONS_PER_RECOVERY_QUERY: usize = 100;
+const MAX_TRANSACTIONS_PER_RECOVERY_SUMMARY: usize = 1000;
+const RECOVERY_INTERVAL: Duration = Duration::MAX;
 
 fn setup(
     network_future: BoxFuture<'static, Result<(), NetworkError>>,
@@ -44,6 +56,27 @@ fn setup(
         mock_register_broadcast_topic().expect("Failed to create mock network");
     let BroadcastTopicChannels { broadcasted_messages_receiver, broadcast_topic_client } =
         subscriber_channels;
+    // Recovery is tested separately, so its peers never respond.
+    let (summary_client, _) = mock_register_sqmr_protocol_client(BUFFER_SIZE);
+    let (transactions_client, _) = mock_register_sqmr_protocol_client(BUFFER_SIZE);
+    let (summary_server, _) = mock_register_sqmr_protocol_server(BUFFER_SIZE);
+    let (transactions_server, _) = mock_register_sqmr_protocol_server(BUFFER_SIZE);
+    let recovery_client = MempoolRecoveryClient::new(
+        summary_client,
+        transactions_client,
+        Arc::new(MockMempoolClient::new()),
+        ChainId::Mainnet,
+        MAX_TRANSACTIONS_PER_RECOVERY_QUERY,
+        MAX_TRANSACTIONS_PER_RECOVERY_SUMMARY,
+    );
+    let recovery_server = MempoolRecoveryServer::new(
+        summary_server,
+        transactions_server,
+        Arc::new(MockMempoolClient::new()),
+        Arc::new(MockTransactionConverterTrait::new()),
+        MAX_TRANSACTIONS_PER_RECOVERY_QUERY,
+        MAX_TRANSACTIONS_PER_RECOVERY_SUMMARY,
+    );
     let mempool_p2p_runner = MempoolP2pRunner::new(
         network_future,
         broadcasted_messages_receiver,
@@ -51,6 +84,11 @@ fn setup(
         gateway_client,
         mempool_p2p_propagator_client,
         transaction_batch_rate_millis,
+        SeenTransactions::new(SEEN_TRANSACTIONS_CACHE_SIZE, MAX_TRACKED_PEERS),
+        ChainId::Mainnet,
+        recovery_client,
+        recovery_server,
+        RECOVERY_INTERVAL,
     );
     (mempool_p2p_runner, mock_network)
 }
@@ -136,6 +174,122 @@ async fn incoming_p2p_tx_reaches_gateway_client() {
     }
 }
 
+fn invoke_transaction_with_nonce(nonce: u64) -> RpcTransaction {
+    RpcTransaction::Invoke(RpcInvokeTransaction::V3(RpcInvokeTransactionV3 {
+        nonce: Nonce(nonce.into()),
+        ..RpcInvokeTransactionV3::get_test_instance(&mut get_rng())
+    }))
+}
+
+// A transaction that was re-gossiped by another peer isn't forwarded to the gateway again.
+#[tokio::test]
+async fn duplicate_p2p_tx_does_not_reach_gateway_client() {
+    let network_future = pending().boxed();
+    let (added_txs_sender, mut added_txs_receiver) = futures::channel::mpsc::unbounded();
+
+    let first_transaction = invoke_transaction_with_nonce(1);
+    let second_transaction = invoke_transaction_with_nonce(2);
+
+    let mut mock_gateway_client = MockGatewayClient::new();
+    mock_gateway_client.expect_add_tx().times(2).returning(move |gateway_input| {
+        added_txs_sender.unbounded_send(gateway_input.rpc_tx).unwrap();
+        Ok(GatewayOutput::Invoke(InvokeGatewayOutput::new(TransactionHash::default())))
+    });
+    let (mut mempool_p2p_runner, mock_network) = setup(
+        network_future,
+        Arc::new(mock_gateway_client),
+        Arc::new(MockMempoolP2pPropagatorClient::new()),
+        MAX_TRANSACTION_BATCH_RATE,
+    );
+
+    let BroadcastNetworkMock {
+        broadcasted_messages_sender: mut mock_broadcasted_messages_sender,
+        ..
+    } = mock_network;
+    let mut rng = get_rng();
+    for batch in [
+        vec![first_transaction.clone()],
+        vec![first_transaction.clone(), second_transaction.clone()],
+    ] {
+        mock_broadcasted_messages_sender
+            .send((
+                RpcTransactionBatch(batch),
+                BroadcastedMessageMetadata::get_test_instance(&mut rng),
+            ))
+            .await
+            .expect("Failed to send message");
+    }
+
+    tokio::select! {
+        res = tokio::time::timeout(Duration::from_secs(5), mempool_p2p_runner.start()) => {
+            res.expect("Test timed out");
+            panic!("MempoolP2pRunner terminated");
+        },
+        added_txs = added_txs_receiver.by_ref().take(2).collect::<Vec<_>>() => {
+            assert_eq!(added_txs, vec![first_transaction, second_transaction]);
+        }
+    }
+}
+
+// A transaction that the gateway rejected is forwarded to the gateway again when it's re-gossiped.
+#[tokio::test]
+async fn rejected_p2p_tx_reaches_gateway_client_when_gossiped_again() {
+    let network_future = pending().boxed();
+    let (added_txs_sender, mut added_txs_receiver) = futures::channel::mpsc::unbounded();
+
+    let transaction = invoke_transaction_with_nonce(1);
+
+    let mut mock_gateway_client = MockGatewayClient::new();
+    let mut num_add_tx_calls = 0;
+    mock_gateway_client.expect_add_tx().times(2).returning(move |gateway_input| {
+        added_txs_sender.unbounded_send(gateway_input.rpc_tx).unwrap();
+        num_add_tx_calls += 1;
+        if num_add_tx_calls == 1 {
+            return Err(GatewayClientError::GatewayError(GatewayError::DeprecatedGatewayError {
+                source: StarknetError {
+                    code: StarknetErrorCode::KnownErrorCode(
+                        KnownStarknetErrorCode::TransactionLimitExceeded,
+                    ),
+                    message: "The mempool is full.".to_string(),
+                },
+                p2p_message_metadata: None,
+            }));
+        }
+        Ok(GatewayOutput::Invoke(InvokeGatewayOutput::new(TransactionHash::default())))
+    });
+    let (mut mempool_p2p_runner, mock_network) = setup(
+        network_future,
+        Arc::new(mock_gateway_client),
+        Arc::new(MockMempoolP2pPropagatorClient::new()),
+        MAX_TRANSACTION_BATCH_RATE,
+    );
+
+    let BroadcastNetworkMock {
+        broadcasted_messages_sender: mut mock_broadcasted_messages_sender,
+        ..
+    } = mock_network;
+    let handle = tokio::spawn(async move {
+        mempool_p2p_runner.start().await;
+    });
+
+    let mut rng = get_rng();
+    for _ in 0..2 {
+        mock_broadcasted_messages_sender
+            .send((
+                RpcTransactionBatch(vec![transaction.clone()]),
+                BroadcastedMessageMetadata::get_test_instance(&mut rng),
+            ))
+            .await
+            .expect("Failed to send message");
+        let added_tx = tokio::time::timeout(Duration::from_secs(5), added_txs_receiver.next())
+            .await
+            .expect("Test timed out");
+        assert_eq!(added_tx, Some(transaction.clone()));
+    }
+
+    handle.abort();
+}
+
 // The p2p runner receives a tx from network, and the gateway declines it, triggering report_peer.
 #[tokio::test]
 async fn incoming_p2p_tx_fails_on_gateway_client() {
@@ -0,0 +1,141 @@
+#[cfg(test)]
+#[path = "seen_transactions_test.rs"]
+mod seen_transactions_test;
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use apollo_network_types::network_types::OpaquePeerId;
+use starknet_api::transaction::TransactionHash;
+
+/// The first time a transaction was seen, and the peer that delivered it first. The peer is `None`
+/// for transactions that were added locally.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FirstSeen {
+    pub timestamp: Instant,
+    pub peer: Option<OpaquePeerId>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Observation {
+    /// The transaction wasn't seen before.
+    New,
+    /// The transaction was already seen. Contains the time since it was first seen.
+    Duplicate { since_first_seen: Duration },
+}
+
+/// Gossip statistics of a single peer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PeerGossipStats {
+    /// The number of transactions this peer was the first to deliver.
+    pub first_deliveries: u64,
+    /// The number of transactions this peer delivered after they were already seen.
+    pub duplicate_deliveries: u64,
+}
+
+/// A bounded cache of the transactions seen on the mempool gossip. Used to drop re-gossiped
+/// transactions before they reach the gateway and to collect propagation statistics. When the
+/// cache is full, the transaction that was seen first is evicted.
+///
+/// A new transaction is pending until the gateway decides on it. Pending transactions are treated
+/// as seen, but only the ones the gateway accepted are added to the cache, so a transaction that
+/// was rejected once isn't dropped when it's gossiped again.
+pub struct SeenTransactions {
+    capacity: usize,
+    first_seen: HashMap<TransactionHash, FirstSeen>,
+    insertion_order: VecDeque<TransactionHash>,
+    pending: HashMap<TransactionHash, FirstSeen>,
+    max_tracked_peers: usize,
+    peer_stats: HashMap<OpaquePeerId, PeerGossipStats>,
+}
+
+impl SeenTransactions {
+    /// Creates a cache of `capacity` transactions that collects the statistics of at most
+    /// `max_tracked_peers` peers between calls to [`Self::take_peer_stats`].
+    pub fn new(capacity: usize, max_tracked_peers: usize) -> Self {
+        assert!(capacity > 0, "The seen transactions cache capacity must be positive.");
+        Self {
+            capacity,
+            first_seen: HashMap::new(),
+            insertion_order: VecDeque::new(),
+            pending: HashMap::new(),
+            max_tracked_peers,
+            peer_stats: HashMap::new(),
+        }
+    }
+
+    /// Records that the given transaction was delivered by `peer` (or added locally if `peer` is
+    /// `None`) at `now`, and returns whether it was seen before. A new transaction is pending
+    /// until [`Self::mark_accepted`] or [`Self::mark_rejected`] is called with its hash.
+    pub fn observe(
+        &mut self,
+        tx_hash: TransactionHash,
+        peer: Option<&OpaquePeerId>,
+        now: Instant,
+    ) -> Observation {
+        if let Some(first_seen) =
+            self.first_seen.get(&tx_hash).or_else(|| self.pending.get(&tx_hash))
+        {
+            let since_first_seen = now.saturating_duration_since(first_seen.timestamp);
+            if let Some(peer) = peer {
+                if let Some(peer_stats) = self.tracked_peer_stats(peer) {
+                    peer_stats.duplicate_deliveries += 1;
+                }
+            }
+            return Observation::Duplicate { since_first_seen };
+        }
+
+        self.pending.insert(tx_hash, FirstSeen { timestamp: now, peer: peer.cloned() });
+        Observation::New
+    }
+
+    /// Adds a pending transaction that the gateway accepted to the cache and attributes its first
+    /// delivery to the peer that delivered it.
+    pub fn mark_accepted(&mut self, tx_hash: TransactionHash) {
+        let Some(first_seen) = self.pending.remove(&tx_hash) else {
+            return;
+        };
+        if let Some(peer) = &first_seen.peer {
+            if let Some(peer_stats) = self.tracked_peer_stats(peer) {
+                peer_stats.first_deliveries += 1;
+            }
+        }
+        if self.insertion_order.len() == self.capacity {
+            let evicted = self.insertion_order.pop_front().expect("The cache is full.");
+            self.first_seen.remove(&evicted);
+        }
+        self.insertion_order.push_back(tx_hash);
+        self.first_seen.insert(tx_hash, first_seen);
+    }
+
+    /// Forgets a pending transaction that the gateway rejected, so it's treated as new the next
+    /// time it's observed.
+    pub fn mark_rejected(&mut self, tx_hash: TransactionHash) {
+        self.pending.remove(&tx_hash);
+    }
+
+    pub fn first_seen(&self, tx_hash: &TransactionHash) -> Option<&FirstSeen> {
+        self.first_seen.get(tx_hash)
+    }
+
+    /// Returns the statistics collected since the previous call, and starts collecting them anew.
+    pub fn take_peer_stats(&mut self) -> HashMap<OpaquePeerId, PeerGossipStats> {
+        std::mem::take(&mut self.peer_stats)
+    }
+
+    pub fn len(&self) -> usize {
+        self.first_seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.first_seen.is_empty()
+    }
+
+    /// Returns the statistics of the given peer, or None if too many other peers are tracked.
+    fn tracked_peer_stats(&mut self, peer: &OpaquePeerId) -> Option<&mut PeerGossipStats> {
+        if !self.peer_stats.contains_key(peer) && self.peer_stats.len() >= self.max_tracked_peers {
+            return None;
+        }
+        Some(self.peer_stats.entry(peer.clone()).or_default())
+    }
+}
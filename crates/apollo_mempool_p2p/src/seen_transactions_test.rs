@@ -0,0 +1,117 @@
+use std::time::{Duration, Instant};
+
+use apollo_network_types::network_types::OpaquePeerId;
+use apollo_test_utils::{get_rng, GetTestInstance};
+use starknet_api::tx_hash;
+
+use super::{FirstSeen, Observation, PeerGossipStats, SeenTransactions};
+
+const MAX_TRACKED_PEERS: usize = 10;
+
+#[test]
+fn duplicates_are_detected_and_attributed_to_peers() {
+    let mut rng = get_rng();
+    let first_peer = OpaquePeerId::get_test_instance(&mut rng);
+    let second_peer = OpaquePeerId::get_test_instance(&mut rng);
+    let mut seen_transactions = SeenTransactions::new(10, MAX_TRACKED_PEERS);
+    let start = Instant::now();
+
+    assert_eq!(seen_transactions.observe(tx_hash!(1), Some(&first_peer), start), Observation::New);
+    seen_transactions.mark_accepted(tx_hash!(1));
+    let later = start + Duration::from_millis(300);
+    assert_eq!(
+        seen_transactions.observe(tx_hash!(1), Some(&second_peer), later),
+        Observation::Duplicate { since_first_seen: Duration::from_millis(300) }
+    );
+    assert_eq!(seen_transactions.observe(tx_hash!(2), Some(&second_peer), later), Observation::New);
+    seen_transactions.mark_accepted(tx_hash!(2));
+
+    assert_eq!(
+        seen_transactions.first_seen(&tx_hash!(1)),
+        Some(&FirstSeen { timestamp: start, peer: Some(first_peer.clone()) })
+    );
+    let peer_stats = seen_transactions.take_peer_stats();
+    assert_eq!(
+        peer_stats[&first_peer],
+        PeerGossipStats { first_deliveries: 1, duplicate_deliveries: 0 }
+    );
+    assert_eq!(
+        peer_stats[&second_peer],
+        PeerGossipStats { first_deliveries: 1, duplicate_deliveries: 1 }
+    );
+    assert!(seen_transactions.take_peer_stats().is_empty());
+}
+
+#[test]
+fn local_transactions_are_not_attributed_to_peers() {
+    let mut seen_transactions = SeenTransactions::new(10, MAX_TRACKED_PEERS);
+    let now = Instant::now();
+
+    assert_eq!(seen_transactions.observe(tx_hash!(1), None, now), Observation::New);
+    seen_transactions.mark_accepted(tx_hash!(1));
+    assert_eq!(
+        seen_transactions.observe(tx_hash!(1), None, now),
+        Observation::Duplicate { since_first_seen: Duration::ZERO }
+    );
+    assert!(seen_transactions.take_peer_stats().is_empty());
+}
+
+#[test]
+fn pending_transactions_are_seen_until_rejected() {
+    let peer = OpaquePeerId::get_test_instance(&mut get_rng());
+    let mut seen_transactions = SeenTransactions::new(10, MAX_TRACKED_PEERS);
+    let now = Instant::now();
+
+    assert_eq!(seen_transactions.observe(tx_hash!(1), Some(&peer), now), Observation::New);
+    assert_eq!(
+        seen_transactions.observe(tx_hash!(1), Some(&peer), now),
+        Observation::Duplicate { since_first_seen: Duration::ZERO }
+    );
+    assert!(seen_transactions.first_seen(&tx_hash!(1)).is_none());
+
+    seen_transactions.mark_rejected(tx_hash!(1));
+    assert!(seen_transactions.is_empty());
+    assert_eq!(seen_transactions.observe(tx_hash!(1), Some(&peer), now), Observation::New);
+    assert_eq!(
+        seen_transactions.take_peer_stats()[&peer],
+        PeerGossipStats { first_deliveries: 0, duplicate_deliveries: 1 }
+    );
+}
+
+#[test]
+fn peers_beyond_the_limit_are_not_tracked() {
+    let mut rng = get_rng();
+    let tracked_peer = OpaquePeerId::get_test_instance(&mut rng);
+    let untracked_peer = OpaquePeerId::get_test_instance(&mut rng);
+    let mut seen_transactions = SeenTransactions::new(10, 1);
+    let now = Instant::now();
+
+    for (tx_hash, peer) in [(tx_hash!(1), &tracked_peer), (tx_hash!(2), &untracked_peer)] {
+        seen_transactions.observe(tx_hash, Some(peer), now);
+        seen_transactions.mark_accepted(tx_hash);
+    }
+
+    let peer_stats = seen_transactions.take_peer_stats();
+    assert_eq!(peer_stats.len(), 1);
+    assert_eq!(
+        peer_stats[&tracked_peer],
+        PeerGossipStats { first_deliveries: 1, duplicate_deliveries: 0 }
+    );
+}
+
+#[test]
+fn oldest_transaction_is_evicted_when_full() {
+    let mut seen_transactions = SeenTransactions::new(2, MAX_TRACKED_PEERS);
+    let now = Instant::now();
+
+    for tx_hash in [tx_hash!(1), tx_hash!(2), tx_hash!(3)] {
+        seen_transactions.observe(tx_hash, None, now);
+        seen_transactions.mark_accepted(tx_hash);
+    }
+
+    assert_eq!(seen_transactions.len(), 2);
+    assert!(seen_transactions.first_seen(&tx_hash!(1)).is_none());
+    assert_eq!(seen_transactions.observe(tx_hash!(1), None, now), Observation::New);
+    seen_transactions.mark_accepted(tx_hash!(1));
+    assert!(seen_transactions.first_seen(&tx_hash!(2)).is_none());
+}
@@ -1,7 +1,11 @@
 use std::sync::Arc;
 
 use apollo_infra::component_client::{ClientError, LocalComponentClient, RemoteComponentClient};
-use apollo_infra::component_definitions::{ComponentClient, ComponentRequestAndResponseSender};
+use apollo_infra::component_definitions::{
+    ComponentClient,
+    ComponentRequestAndResponseSender,
+    PrioritizedRequest,
+};
 use apollo_infra::impl_debug_for_infra_requests_and_responses;
 use apollo_network_types::network_types::BroadcastedMessageMetadata;
 use apollo_proc_macros::handle_all_response_variants;
@@ -52,6 +56,8 @@ pub enum MempoolP2pPropagatorRequest {
 }
 impl_debug_for_infra_requests_and_responses!(MempoolP2pPropagatorRequest);
 
+impl PrioritizedRequest for MempoolP2pPropagatorRequest {}
+
 #[derive(Clone, Serialize, Deserialize, AsRefStr)]
 pub enum MempoolP2pPropagatorResponse {
     AddTransaction(MempoolP2pPropagatorResult<()>),
@@ -1,7 +1,11 @@
 use std::sync::Arc;
 
 use apollo_infra::component_client::{ClientError, LocalComponentClient, RemoteComponentClient};
-use apollo_infra::component_definitions::{ComponentClient, ComponentRequestAndResponseSender};
+use apollo_infra::component_definitions::{
+    ComponentClient,
+    ComponentRequestAndResponseSender,
+    PrioritizedRequest,
+};
 use apollo_infra::impl_debug_for_infra_requests_and_responses;
 use apollo_network_types::network_types::BroadcastedMessageMetadata;
 use apollo_proc_macros::handle_all_response_variants;
@@ -12,6 +16,7 @@ use serde::{Deserialize, Serialize};
 use starknet_api::block::GasPrice;
 use starknet_api::core::ContractAddress;
 use starknet_api::rpc_transaction::InternalRpcTransaction;
+use starknet_api::transaction::TransactionHash;
 use strum_macros::AsRefStr;
 use thiserror::Error;
 
@@ -48,6 +53,16 @@ pub trait MempoolClient: Send + Sync {
     ) -> MempoolClientResult<bool>;
     async fn update_gas_price(&self, gas_price: GasPrice) -> MempoolClientResult<()>;
     async fn get_mempool_snapshot(&self) -> MempoolClientResult<MempoolSnapshot>;
+    /// Returns the hashes of the transactions of each account in the mempool, sorted by nonce.
+    async fn get_tx_hashes_by_account(
+        &self,
+    ) -> MempoolClientResult<Vec<(ContractAddress, Vec<TransactionHash>)>>;
+    /// Returns the transactions in the mempool with the given hashes. Hashes of transactions that
+    /// aren't in the mempool are skipped.
+    async fn get_txs_by_hash(
+        &self,
+        tx_hashes: Vec<TransactionHash>,
+    ) -> MempoolClientResult<Vec<InternalRpcTransaction>>;
 }
 
 #[derive(Clone, Serialize, Deserialize, AsRefStr)]
@@ -59,9 +74,13 @@ pub enum MempoolRequest {
     // TODO(yair): Rename to `StartBlock` and add cleanup of staged txs.
     UpdateGasPrice(GasPrice),
     GetMempoolSnapshot(),
+    GetTransactionHashesByAccount(),
+    GetTransactionsByHash(Vec<TransactionHash>),
 }
 impl_debug_for_infra_requests_and_responses!(MempoolRequest);
 
+impl PrioritizedRequest for MempoolRequest {}
+
 #[derive(Clone, Serialize, Deserialize, AsRefStr)]
 pub enum MempoolResponse {
     AddTransaction(MempoolResult<()>),
@@ -70,6 +89,8 @@ pub enum MempoolResponse {
     AccountTxInPoolOrRecentBlock(MempoolResult<bool>),
     UpdateGasPrice(MempoolResult<()>),
     GetMempoolSnapshot(MempoolResult<MempoolSnapshot>),
+    GetTransactionHashesByAccount(MempoolResult<Vec<(ContractAddress, Vec<TransactionHash>)>>),
+    GetTransactionsByHash(MempoolResult<Vec<InternalRpcTransaction>>),
 }
 impl_debug_for_infra_requests_and_responses!(MempoolResponse);
 
@@ -154,4 +175,31 @@ where
             Direct
         )
     }
+
+    async fn get_tx_hashes_by_account(
+        &self,
+    ) -> MempoolClientResult<Vec<(ContractAddress, Vec<TransactionHash>)>> {
+        let request = MempoolRequest::GetTransactionHashesByAccount();
+        handle_all_response_variants!(
+            MempoolResponse,
+            GetTransactionHashesByAccount,
+            MempoolClientError,
+            MempoolError,
+            Direct
+        )
+    }
+
+    async fn get_txs_by_hash(
+        &self,
+        tx_hashes: Vec<TransactionHash>,
+    ) -> MempoolClientResult<Vec<InternalRpcTransaction>> {
+        let request = MempoolRequest::GetTransactionsByHash(tx_hashes);
+        handle_all_response_variants!(
+            MempoolResponse,
+            GetTransactionsByHash,
+            MempoolClientError,
+            MempoolError,
+            Direct
+        )
+    }
 }
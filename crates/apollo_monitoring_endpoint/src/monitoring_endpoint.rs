@@ -1,17 +1,29 @@
+use std::collections::BTreeMap;
 use std::net::SocketAddr;
+use std::str::FromStr;
 
+use apollo_config::dynamic_config::SharedDynamicConfigPublisher;
+use apollo_config::{ConfigError, ParamPath};
 use apollo_infra::component_definitions::ComponentStarter;
 use apollo_infra_utils::type_name::short_type_name;
 use apollo_l1_provider_types::{L1ProviderSnapshot, SharedL1ProviderClient};
 use apollo_mempool_types::communication::SharedMempoolClient;
 use apollo_mempool_types::mempool_types::MempoolSnapshot;
 use apollo_metrics::metrics::COLLECT_SEQUENCER_PROFILING_METRICS;
+use apollo_network_types::network_types::{
+    PeerBanList,
+    PeerBanListClients,
+    PeerBanListError,
+    PeerId,
+};
+use axum::extract::Path;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
-use axum::routing::get;
+use axum::routing::{get, post};
 use axum::{async_trait, Json, Router, Server};
 use hyper::Error;
 use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use serde_json::Value;
 use tracing::{error, info, instrument};
 
 use crate::config::MonitoringEndpointConfig;
@@ -27,6 +39,8 @@ pub(crate) const VERSION: &str = "nodeVersion";
 pub(crate) const METRICS: &str = "metrics";
 pub(crate) const MEMPOOL_SNAPSHOT: &str = "mempoolSnapshot";
 pub(crate) const L1_PROVIDER_SNAPSHOT: &str = "l1ProviderSnapshot";
+pub(crate) const PEER_BAN_LISTS: &str = "peerBanLists";
+pub(crate) const DYNAMIC_CONFIG: &str = "dynamicConfig";
 
 const HISTOGRAM_BUCKETS: &[f64] =
     &[0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 25.0, 50.0];
@@ -37,6 +51,8 @@ pub struct MonitoringEndpoint {
     prometheus_handle: Option<PrometheusHandle>,
     mempool_client: Option<SharedMempoolClient>,
     l1_provider_client: Option<SharedL1ProviderClient>,
+    peer_ban_list_clients: PeerBanListClients,
+    dynamic_config: Option<SharedDynamicConfigPublisher>,
 }
 
 impl MonitoringEndpoint {
@@ -45,6 +61,8 @@ impl MonitoringEndpoint {
         version: &'static str,
         mempool_client: Option<SharedMempoolClient>,
         l1_provider_client: Option<SharedL1ProviderClient>,
+        peer_ban_list_clients: PeerBanListClients,
+        dynamic_config: Option<SharedDynamicConfigPublisher>,
     ) -> Self {
         // TODO(Tsabary): consider error handling
         let prometheus_handle = if config.collect_metrics {
@@ -70,6 +88,8 @@ impl MonitoringEndpoint {
             prometheus_handle,
             mempool_client,
             l1_provider_client,
+            peer_ban_list_clients,
+            dynamic_config,
         }
     }
 
@@ -95,6 +115,10 @@ impl MonitoringEndpoint {
         let prometheus_handle = self.prometheus_handle.clone();
         let mempool_client = self.mempool_client.clone();
         let l1_provider_client = self.l1_provider_client.clone();
+        let peer_ban_list_clients = self.peer_ban_list_clients.clone();
+        let ban_peer_ban_list_clients = self.peer_ban_list_clients.clone();
+        let unban_peer_ban_list_clients = self.peer_ban_list_clients.clone();
+        let dynamic_config = self.dynamic_config.clone();
 
         Router::new()
             .route(
@@ -121,6 +145,23 @@ impl MonitoringEndpoint {
                 format!("/{MONITORING_PREFIX}/{L1_PROVIDER_SNAPSHOT}").as_str(),
                 get(move || get_l1_provider_snapshot(l1_provider_client)),
             )
+            .route(
+                format!("/{MONITORING_PREFIX}/{PEER_BAN_LISTS}").as_str(),
+                get(move || peer_ban_lists(peer_ban_list_clients)),
+            )
+            .route(
+                format!("/{MONITORING_PREFIX}/{PEER_BAN_LISTS}/:network/:peer_id").as_str(),
+                post(move |Path((network, peer_id))| {
+                    update_peer_ban_list(ban_peer_ban_list_clients, network, peer_id, true)
+                })
+                .delete(move |Path((network, peer_id))| {
+                    update_peer_ban_list(unban_peer_ban_list_clients, network, peer_id, false)
+                }),
+            )
+            .route(
+                format!("/{MONITORING_PREFIX}/{DYNAMIC_CONFIG}").as_str(),
+                post(move |Json(params)| update_dynamic_params(dynamic_config, params)),
+            )
     }
 }
 
@@ -129,8 +170,17 @@ pub fn create_monitoring_endpoint(
     version: &'static str,
     mempool_client: Option<SharedMempoolClient>,
     l1_provider_client: Option<SharedL1ProviderClient>,
+    peer_ban_list_clients: PeerBanListClients,
+    dynamic_config: Option<SharedDynamicConfigPublisher>,
 ) -> MonitoringEndpoint {
-    MonitoringEndpoint::new(config, version, mempool_client, l1_provider_client)
+    MonitoringEndpoint::new(
+        config,
+        version,
+        mempool_client,
+        l1_provider_client,
+        peer_ban_list_clients,
+        dynamic_config,
+    )
 }
 
 #[async_trait]
@@ -186,3 +236,70 @@ async fn get_l1_provider_snapshot(
         None => Err(StatusCode::METHOD_NOT_ALLOWED),
     }
 }
+
+// Returns the peer ban lists of the networks that run in this process.
+#[instrument(level = "debug", skip(peer_ban_list_clients))]
+async fn peer_ban_lists(
+    peer_ban_list_clients: PeerBanListClients,
+) -> Result<Json<BTreeMap<String, PeerBanList>>, StatusCode> {
+    let mut peer_ban_lists = BTreeMap::new();
+    for (network, client) in peer_ban_list_clients.all() {
+        let peer_ban_list = match client.get().await {
+            Ok(peer_ban_list) => peer_ban_list,
+            // The network has stopped, so it has no ban list to report.
+            Err(PeerBanListError::OwnerStopped) => continue,
+        };
+        peer_ban_lists.insert(network, peer_ban_list);
+    }
+    Ok(Json(peer_ban_lists))
+}
+
+// Bans or unbans a peer of the given network. The change is made by the network, which is the only
+// writer of its ban list.
+#[instrument(level = "debug", skip(peer_ban_list_clients))]
+async fn update_peer_ban_list(
+    peer_ban_list_clients: PeerBanListClients,
+    network: String,
+    peer_id: String,
+    ban: bool,
+) -> StatusCode {
+    let Some(client) = peer_ban_list_clients.get(&network) else {
+        return StatusCode::NOT_FOUND;
+    };
+    let Ok(peer_id) = PeerId::from_str(&peer_id) else {
+        return StatusCode::BAD_REQUEST;
+    };
+    let result = if ban {
+        client.ban(peer_id, "Banned through the monitoring endpoint.".to_string()).await
+    } else {
+        client.unban(peer_id).await
+    };
+    match result {
+        Ok(()) => StatusCode::OK,
+        Err(err) => {
+            error!("Failed to update the peer ban list of {}: {:?}", network, err);
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+    }
+}
+
+// Updates dynamic config params, given as a map from param paths to their new values, and returns
+// the paths of the params that changed. The update is applied only if all the params are dynamic
+// and the resulting config is valid.
+#[instrument(level = "debug", skip(dynamic_config))]
+async fn update_dynamic_params(
+    dynamic_config: Option<SharedDynamicConfigPublisher>,
+    params: BTreeMap<ParamPath, Value>,
+) -> Result<Json<Vec<ParamPath>>, StatusCode> {
+    let Some(dynamic_config) = dynamic_config else {
+        return Err(StatusCode::METHOD_NOT_ALLOWED);
+    };
+    dynamic_config.update_params(params).map(Json).map_err(|err| {
+        error!("Failed to update dynamic config params: {}", err);
+        match err {
+            ConfigError::ParamNotFound { .. } => StatusCode::NOT_FOUND,
+            ConfigError::NonDynamicParam { .. } => StatusCode::FORBIDDEN,
+            _ => StatusCode::BAD_REQUEST,
+        }
+    })
+}
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::net::IpAddr;
 use std::sync::Arc;
 
+use apollo_config::dynamic_config::DynamicConfigPublisher;
 use apollo_l1_provider_types::{L1ProviderSnapshot, MockL1ProviderClient};
 use apollo_mempool_types::communication::MockMempoolClient;
 use apollo_mempool_types::mempool_types::{
@@ -9,20 +10,32 @@ use apollo_mempool_types::mempool_types::{
     MempoolStateSnapshot,
     TransactionQueueSnapshot,
 };
-use axum::http::StatusCode;
+use apollo_network_types::network_types::{
+    PeerBan,
+    PeerBanList,
+    PeerBanListClient,
+    PeerBanListClients,
+    PeerBanListRequest,
+    PeerId,
+};
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
 use axum::response::Response;
 use axum::Router;
+use futures::StreamExt;
 use hyper::body::to_bytes;
 use hyper::Client;
 use metrics::{counter, describe_counter};
 use pretty_assertions::assert_eq;
-use serde_json::{from_slice, to_value, Value};
+use serde::Deserialize;
+use serde_json::{from_slice, json, to_value, Value};
 use starknet_api::block::{BlockNumber, GasPrice};
 use starknet_api::core::{ContractAddress, Nonce};
 use starknet_api::{nonce, tx_hash};
 use tokio::spawn;
 use tokio::task::yield_now;
 use tower::ServiceExt;
+use validator::Validate;
 
 use super::MonitoringEndpointConfig;
 use crate::config::{MONITORING_ENDPOINT_DEFAULT_IP, MONITORING_ENDPOINT_DEFAULT_PORT};
@@ -30,9 +43,12 @@ use crate::monitoring_endpoint::{
     create_monitoring_endpoint,
     MonitoringEndpoint,
     ALIVE,
+    DYNAMIC_CONFIG,
     L1_PROVIDER_SNAPSHOT,
     MEMPOOL_SNAPSHOT,
     METRICS,
+    MONITORING_PREFIX,
+    PEER_BAN_LISTS,
     READY,
     VERSION,
 };
@@ -51,7 +67,14 @@ const CONFIG_WITHOUT_METRICS: MonitoringEndpointConfig = MonitoringEndpointConfi
 
 fn setup_monitoring_endpoint(config: Option<MonitoringEndpointConfig>) -> MonitoringEndpoint {
     let config = config.unwrap_or(CONFIG_WITHOUT_METRICS);
-    create_monitoring_endpoint(config, TEST_VERSION, None, None)
+    create_monitoring_endpoint(
+        config,
+        TEST_VERSION,
+        None,
+        None,
+        PeerBanListClients::default(),
+        None,
+    )
 }
 
 async fn request_app(app: Router, method: &str) -> Response {
@@ -134,6 +157,8 @@ fn setup_monitoring_endpoint_with_mempool_client() -> MonitoringEndpoint {
         TEST_VERSION,
         Some(shared_mock_mempool_client),
         None,
+        PeerBanListClients::default(),
+        None,
     )
 }
 
@@ -196,6 +221,8 @@ fn setup_monitoring_endpoint_with_l1_provider_client() -> MonitoringEndpoint {
         TEST_VERSION,
         None,
         Some(shared_mock_l1_provider_client),
+        PeerBanListClients::default(),
+        None,
     )
 }
 
@@ -244,3 +271,169 @@ async fn l1_provider_not_present() {
     let response = request_app(app, L1_PROVIDER_SNAPSHOT).await;
     assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
 }
+
+/// Handles the requests to the owner of a peer ban list the way a network does, without persisting
+/// the list, and returns a client of the list.
+fn spawn_peer_ban_list_owner() -> PeerBanListClient {
+    let (client, mut requests) = PeerBanListClient::new();
+    tokio::spawn(async move {
+        let mut peer_ban_list = PeerBanList::default();
+        while let Some(request) = requests.next().await {
+            match request {
+                PeerBanListRequest::Get { response_sender } => {
+                    response_sender.send(peer_ban_list.clone()).unwrap();
+                }
+                PeerBanListRequest::Ban { peer_id, reason, response_sender } => {
+                    peer_ban_list
+                        .banned_peers
+                        .insert(peer_id, PeerBan { reason, banned_at_unix_seconds: 0 });
+                    response_sender.send(()).unwrap();
+                }
+                PeerBanListRequest::Unban { peer_id, response_sender } => {
+                    peer_ban_list.banned_peers.remove(&peer_id);
+                    response_sender.send(()).unwrap();
+                }
+            }
+        }
+    });
+    client
+}
+
+#[tokio::test]
+async fn ban_and_unban_peer() {
+    const NETWORK: &str = "consensus";
+    let peer_ban_list_clients = PeerBanListClients::default();
+    let app = create_monitoring_endpoint(
+        CONFIG_WITHOUT_METRICS,
+        TEST_VERSION,
+        None,
+        None,
+        peer_ban_list_clients.clone(),
+        None,
+    )
+    .app();
+    let peer_id = PeerId::random();
+    let update_request = |method: &str, network: &str, peer_id: &str| {
+        Request::builder()
+            .method(method)
+            .uri(format!("/{MONITORING_PREFIX}/{PEER_BAN_LISTS}/{network}/{peer_id}"))
+            .body(Body::empty())
+            .unwrap()
+    };
+    let get_ban_lists = |app: Router| async {
+        let response = request_app(app, PEER_BAN_LISTS).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body_bytes = to_bytes(response.into_body()).await.unwrap();
+        from_slice::<HashMap<String, PeerBanList>>(&body_bytes).unwrap()
+    };
+
+    // The network hasn't added its ban list client yet.
+    let response =
+        app.clone().oneshot(update_request("POST", NETWORK, &peer_id.to_string())).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // The network that owns the ban list has stopped.
+    let (stopped_network_client, _) = PeerBanListClient::new();
+    peer_ban_list_clients.insert(NETWORK, stopped_network_client);
+    let response =
+        app.clone().oneshot(update_request("POST", NETWORK, &peer_id.to_string())).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert!(get_ban_lists(app.clone()).await.is_empty());
+
+    peer_ban_list_clients.insert(NETWORK, spawn_peer_ban_list_owner());
+    let response =
+        app.clone().oneshot(update_request("POST", NETWORK, &peer_id.to_string())).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(get_ban_lists(app.clone()).await[NETWORK].is_banned(&peer_id));
+
+    let response =
+        app.clone().oneshot(update_request("DELETE", NETWORK, &peer_id.to_string())).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(!get_ban_lists(app.clone()).await[NETWORK].is_banned(&peer_id));
+
+    let response = app
+        .clone()
+        .oneshot(update_request("POST", "unknown_network", &peer_id.to_string()))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = app.oneshot(update_request("POST", NETWORK, "invalid_peer_id")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[derive(Deserialize, Validate)]
+struct ConfigWithDynamicParams {
+    #[validate]
+    dynamic_config: MonitoringEndpointConfig,
+    #[validate]
+    static_config: MonitoringEndpointConfig,
+}
+
+#[tokio::test]
+async fn update_dynamic_config() {
+    let config_map = ["dynamic_config", "static_config"]
+        .into_iter()
+        .flat_map(|sub_config| {
+            [
+                (format!("{sub_config}.ip"), json!("0.0.0.0")),
+                (format!("{sub_config}.port"), json!(MONITORING_ENDPOINT_DEFAULT_PORT)),
+                (format!("{sub_config}.collect_metrics"), json!(false)),
+                (format!("{sub_config}.collect_profiling_metrics"), json!(false)),
+            ]
+        })
+        .collect();
+    let dynamic_config =
+        Arc::new(DynamicConfigPublisher::new::<ConfigWithDynamicParams>(config_map).unwrap());
+    let receiver = dynamic_config.subscribe::<MonitoringEndpointConfig>("dynamic_config").unwrap();
+    let app = create_monitoring_endpoint(
+        CONFIG_WITHOUT_METRICS,
+        TEST_VERSION,
+        None,
+        None,
+        PeerBanListClients::default(),
+        Some(dynamic_config),
+    )
+    .app();
+    let update_request = |param_path: &str, value: Value| {
+        Request::builder()
+            .method("POST")
+            .uri(format!("/{MONITORING_PREFIX}/{DYNAMIC_CONFIG}"))
+            .header("content-type", "application/json")
+            .body(Body::from(json!({ param_path: value }).to_string()))
+            .unwrap()
+    };
+
+    let response = app
+        .clone()
+        .oneshot(update_request("dynamic_config.collect_metrics", json!(true)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body_bytes = to_bytes(response.into_body()).await.unwrap();
+    let updated_params: Vec<String> = from_slice(&body_bytes).unwrap();
+    assert_eq!(updated_params, vec!["dynamic_config.collect_metrics".to_string()]);
+    assert!(receiver.borrow().collect_metrics);
+
+    let failed_updates = [
+        ("dynamic_config.port", json!("not a port"), StatusCode::BAD_REQUEST),
+        ("dynamic_config.unknown", json!(1), StatusCode::NOT_FOUND),
+        ("static_config.collect_metrics", json!(true), StatusCode::FORBIDDEN),
+    ];
+    for (param_path, value, expected_status) in failed_updates {
+        let response = app.clone().oneshot(update_request(param_path, value)).await.unwrap();
+        assert_eq!(response.status(), expected_status);
+    }
+    assert_eq!(receiver.borrow().port, MONITORING_ENDPOINT_DEFAULT_PORT);
+}
+
+#[tokio::test]
+async fn dynamic_config_not_present() {
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/{MONITORING_PREFIX}/{DYNAMIC_CONFIG}"))
+        .header("content-type", "application/json")
+        .body(Body::from("{}"))
+        .unwrap();
+    let response = setup_monitoring_endpoint(None).app().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+}
@@ -37,7 +37,8 @@ impl<TStore: kad::store::RecordStore + Send + 'static> BridgedBehaviour for kad:
                 }
             }
             mixed_behaviour::ToOtherBehaviourEvent::PeerManager(
-                peer_manager::ToOtherBehaviourEvent::PeerBlacklisted { peer_id },
+                peer_manager::ToOtherBehaviourEvent::PeerBlacklisted { peer_id }
+                | peer_manager::ToOtherBehaviourEvent::PeerNotPermitted { peer_id },
             ) => {
                 self.remove_peer(peer_id);
             }
@@ -1,9 +1,17 @@
-use libp2p::gossipsub::TopicHash;
+#[cfg(test)]
+#[path = "gossipsub_impl_test.rs"]
+mod gossipsub_impl_test;
+
+use libp2p::gossipsub::{PeerScoreParams, PeerScoreThresholds, TopicHash};
 use libp2p::{gossipsub, PeerId};
 use tracing::error;
 
 use crate::mixed_behaviour::BridgedBehaviour;
-use crate::{mixed_behaviour, Bytes};
+use crate::{mixed_behaviour, peer_manager, staker_identity, Bytes};
+
+// Below the graylist threshold, so that gossipsub ignores everything an unverified peer sends, and
+// doesn't add it to the mesh or send messages to it.
+const UNVERIFIED_PEER_SCORE: f64 = -1000.0;
 
 #[cfg(test)]
 pub type Topic = gossipsub::IdentTopic;
@@ -47,6 +55,36 @@ impl From<gossipsub::Event> for mixed_behaviour::Event {
     }
 }
 
+/// Returns the peer scoring that keeps the peers whose staker identity isn't verified yet out of
+/// gossipsub. Only the application score, which is lowered for unverified peers, affects the
+/// score, so the other peers are treated as if there's no scoring.
+pub(crate) fn unverified_peers_score_params() -> (PeerScoreParams, PeerScoreThresholds) {
+    let params = PeerScoreParams {
+        app_specific_weight: 1.0,
+        ip_colocation_factor_weight: 0.0,
+        behaviour_penalty_weight: 0.0,
+        ..Default::default()
+    };
+    (params, PeerScoreThresholds::default())
+}
+
 impl BridgedBehaviour for gossipsub::Behaviour {
-    fn on_other_behaviour_event(&mut self, _event: &mixed_behaviour::ToOtherBehaviourEvent) {}
+    fn on_other_behaviour_event(&mut self, event: &mixed_behaviour::ToOtherBehaviourEvent) {
+        match event {
+            // Blacklisting also drops the messages the peer originated that other peers relay.
+            mixed_behaviour::ToOtherBehaviourEvent::PeerManager(
+                peer_manager::ToOtherBehaviourEvent::PeerUnverified { peer_id },
+            ) => {
+                self.blacklist_peer(peer_id);
+                self.set_application_score(peer_id, UNVERIFIED_PEER_SCORE);
+            }
+            mixed_behaviour::ToOtherBehaviourEvent::StakerIdentity(
+                staker_identity::ToOtherBehaviourEvent::PeerVerified { peer_id },
+            ) => {
+                self.remove_blacklisted_peer(peer_id);
+                self.set_application_score(peer_id, 0.0);
+            }
+            _ => {}
+        }
+    }
 }
@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use futures::StreamExt;
+use libp2p::gossipsub::{self, MessageAuthenticity};
+use libp2p::swarm::SwarmEvent;
+use libp2p::{PeerId, Swarm};
+use libp2p_swarm_test::SwarmExt;
+
+use super::{unverified_peers_score_params, Topic};
+use crate::mixed_behaviour::{self, BridgedBehaviour};
+use crate::{peer_manager, staker_identity, Bytes};
+
+const TOPIC: &str = "TOPIC";
+// Messages are delivered over the memory transport well within this time.
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(3);
+
+async fn create_swarm() -> Swarm<gossipsub::Behaviour> {
+    let mut swarm = Swarm::new_ephemeral(|keypair| {
+        let mut behaviour = gossipsub::Behaviour::new(
+            MessageAuthenticity::Signed(keypair),
+            gossipsub::Config::default(),
+        )
+        .unwrap();
+        let (params, thresholds) = unverified_peers_score_params();
+        behaviour.with_peer_score(params, thresholds).unwrap();
+        behaviour
+    });
+    swarm.behaviour_mut().subscribe(&Topic::new(TOPIC)).unwrap();
+    swarm.listen().with_memory_addr_external().await;
+    swarm
+}
+
+// Publishes the message from the sender once it knows the receiver is subscribed, and returns the
+// message the receiver got, or None if it got nothing in time.
+async fn publish_and_receive(
+    sender: &mut Swarm<gossipsub::Behaviour>,
+    receiver: &mut Swarm<gossipsub::Behaviour>,
+    message: Bytes,
+) -> Option<Bytes> {
+    let mut is_published = false;
+    let deadline = tokio::time::sleep(DELIVERY_TIMEOUT);
+    tokio::pin!(deadline);
+    loop {
+        if !is_published {
+            is_published =
+                sender.behaviour_mut().publish(Topic::new(TOPIC), message.clone()).is_ok();
+        }
+        tokio::select! {
+            _ = sender.select_next_some() => {}
+            event = receiver.select_next_some() => {
+                if let SwarmEvent::Behaviour(gossipsub::Event::Message { message, .. }) = event {
+                    return Some(message.data);
+                }
+            }
+            _ = &mut deadline => return None,
+        }
+    }
+}
+
+fn unverified_event(peer_id: PeerId) -> mixed_behaviour::ToOtherBehaviourEvent {
+    mixed_behaviour::ToOtherBehaviourEvent::PeerManager(
+        peer_manager::ToOtherBehaviourEvent::PeerUnverified { peer_id },
+    )
+}
+
+fn verified_event(peer_id: PeerId) -> mixed_behaviour::ToOtherBehaviourEvent {
+    mixed_behaviour::ToOtherBehaviourEvent::StakerIdentity(
+        staker_identity::ToOtherBehaviourEvent::PeerVerified { peer_id },
+    )
+}
+
+#[tokio::test]
+async fn gossip_of_unverified_peer_is_not_delivered() {
+    let mut sender = create_swarm().await;
+    let mut receiver = create_swarm().await;
+    receiver.connect(&mut sender).await;
+    let sender_peer_id = *sender.local_peer_id();
+
+    receiver.behaviour_mut().on_other_behaviour_event(&unverified_event(sender_peer_id));
+    assert_eq!(publish_and_receive(&mut sender, &mut receiver, vec![1]).await, None);
+
+    receiver.behaviour_mut().on_other_behaviour_event(&verified_event(sender_peer_id));
+    assert_eq!(publish_and_receive(&mut sender, &mut receiver, vec![2]).await, Some(vec![2]));
+}
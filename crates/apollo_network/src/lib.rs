@@ -13,6 +13,7 @@ mod mixed_behaviour;
 pub mod network_manager;
 mod peer_manager;
 mod sqmr;
+pub mod staker_identity;
 #[cfg(test)]
 mod test_utils;
 pub mod utils;
@@ -102,6 +103,7 @@ pub struct NetworkConfig {
     pub advertised_multiaddr: Option<Multiaddr>,
     pub chain_id: ChainId,
     pub discovery_config: DiscoveryConfig,
+    #[validate]
     pub peer_manager_config: PeerManagerConfig,
     pub broadcasted_message_metadata_buffer_size: usize,
     pub reported_peer_ids_buffer_size: usize,
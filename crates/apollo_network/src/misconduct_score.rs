@@ -18,6 +18,10 @@ impl MisconductScore {
         Self { score }
     }
 
+    pub fn score(&self) -> f64 {
+        self.score
+    }
+
     pub fn is_malicious(&self) -> bool {
         &Self::MALICIOUS <= self
     }
@@ -12,7 +12,7 @@ use crate::discovery::identify_impl::{IdentifyToOtherBehaviourEvent, IDENTIFY_PR
 use crate::discovery::kad_impl::KadToOtherBehaviourEvent;
 use crate::discovery::DiscoveryConfig;
 use crate::peer_manager::PeerManagerConfig;
-use crate::{discovery, gossipsub_impl, peer_manager, sqmr};
+use crate::{discovery, gossipsub_impl, peer_manager, sqmr, staker_identity};
 
 const ONE_MEGA: usize = 1 << 20;
 
@@ -27,6 +27,7 @@ pub struct MixedBehaviour {
     pub kademlia: kad::Behaviour<MemoryStore>,
     pub sqmr: sqmr::Behaviour,
     pub gossipsub: gossipsub::Behaviour,
+    pub staker_identity: staker_identity::Behaviour,
 }
 
 #[derive(Debug)]
@@ -49,6 +50,7 @@ pub enum ToOtherBehaviourEvent {
     Discovery(discovery::ToOtherBehaviourEvent),
     PeerManager(peer_manager::ToOtherBehaviourEvent),
     Sqmr(sqmr::ToOtherBehaviourEvent),
+    StakerIdentity(staker_identity::ToOtherBehaviourEvent),
 }
 
 pub trait BridgedBehaviour {
@@ -75,8 +77,36 @@ impl MixedBehaviour {
             StreamProtocol::try_from_owned(format!("/starknet/kad/{chain_id}/1.0.0"))
                 .expect("Failed to create StreamProtocol from a string that starts with /"),
         ]);
+        let peer_manager = peer_manager::PeerManager::new(peer_manager_config);
+        let permissioned_config = peer_manager.permissioned_config();
+        // In permissioned mode, peers are added to the routing table only once they're identified,
+        // and are removed if they aren't permitted.
+        if permissioned_config.enabled {
+            kademlia_config.set_kbucket_inserts(kad::BucketInserts::Manual);
+        }
+        let staker_public_keys = match permissioned_config.enabled {
+            true => permissioned_config.staker_public_keys.clone(),
+            false => vec![],
+        };
+        let mut gossipsub = gossipsub::Behaviour::new(
+            gossipsub::MessageAuthenticity::Signed(keypair),
+            gossipsub::ConfigBuilder::default()
+                .max_transmit_size(ONE_MEGA)
+                .build()
+                .expect("Failed to build gossipsub config"),
+        )
+        .unwrap_or_else(|err_string| {
+            panic!("Failed creating gossipsub behaviour due to the following error: {err_string}")
+        });
+        // Peer scoring is how gossipsub ignores the peers whose staker identity isn't verified.
+        if peer_manager.verifies_staker_identities() {
+            let (params, thresholds) = gossipsub_impl::unverified_peers_score_params();
+            gossipsub
+                .with_peer_score(params, thresholds)
+                .expect("Failed to set the gossipsub peer scoring");
+        }
         Self {
-            peer_manager: peer_manager::PeerManager::new(peer_manager_config),
+            peer_manager,
             discovery: bootstrap_peers_multiaddrs
                 .map(|bootstrap_peer_multiaddr| {
                     discovery::Behaviour::new(
@@ -113,18 +143,8 @@ impl MixedBehaviour {
                 kademlia_config,
             ),
             sqmr: sqmr::Behaviour::new(streamed_bytes_config),
-            gossipsub: gossipsub::Behaviour::new(
-                gossipsub::MessageAuthenticity::Signed(keypair),
-                gossipsub::ConfigBuilder::default()
-                    .max_transmit_size(ONE_MEGA)
-                    .build()
-                    .expect("Failed to build gossipsub config"),
-            )
-            .unwrap_or_else(|err_string| {
-                panic!(
-                    "Failed creating gossipsub behaviour due to the following error: {err_string}"
-                )
-            }),
+            gossipsub,
+            staker_identity: staker_identity::Behaviour::new(local_peer_id, staker_public_keys),
         }
     }
 }
@@ -8,9 +8,14 @@ pub mod test_utils;
 use std::collections::{BTreeMap, HashMap};
 use std::pin::Pin;
 use std::str::FromStr;
+use std::sync::Arc;
 use std::task::{Context, Poll};
 
-use apollo_network_types::network_types::{BroadcastedMessageMetadata, OpaquePeerId};
+use apollo_network_types::network_types::{
+    BroadcastedMessageMetadata,
+    OpaquePeerId,
+    PeerBanListClient,
+};
 use async_trait::async_trait;
 use futures::channel::mpsc::{Receiver, SendError, Sender};
 use futures::channel::oneshot;
@@ -20,7 +25,7 @@ use futures::stream::{FuturesUnordered, Map, Stream};
 use futures::{pin_mut, FutureExt, Sink, SinkExt, StreamExt};
 use libp2p::gossipsub::{SubscriptionError, TopicHash};
 use libp2p::identity::Keypair;
-use libp2p::swarm::SwarmEvent;
+use libp2p::swarm::{DialError, ListenError, SwarmEvent};
 use libp2p::{Multiaddr, PeerId, StreamProtocol, Swarm, SwarmBuilder};
 use metrics::NetworkMetrics;
 use tracing::{debug, error, trace, warn};
@@ -31,8 +36,9 @@ use crate::misconduct_score::MisconductScore;
 use crate::mixed_behaviour::{self, BridgedBehaviour};
 use crate::sqmr::behaviour::SessionError;
 use crate::sqmr::{self, InboundSessionId, OutboundSessionId, SessionId};
+use crate::staker_identity::StakerIdentityManager;
 use crate::utils::{is_localhost, StreamMap};
-use crate::{gossipsub_impl, Bytes, NetworkConfig};
+use crate::{gossipsub_impl, peer_manager, Bytes, NetworkConfig};
 
 #[derive(thiserror::Error, Debug)]
 pub enum NetworkError {
@@ -47,7 +53,9 @@ pub struct GenericNetworkManager<SwarmT: SwarmTrait> {
     sqmr_inbound_response_receivers: StreamMap<InboundSessionId, ResponsesReceiver>,
     sqmr_inbound_payload_senders: HashMap<StreamProtocol, SqmrServerSender>,
     sqmr_outbound_payload_receivers: StreamMap<String, SqmrClientReceiver>,
-    sqmr_outbound_response_senders: HashMap<OutboundSessionId, ResponsesSender>,
+    // The protocols that were offered in each outbound session, with the sender of its responses.
+    sqmr_outbound_response_senders:
+        HashMap<OutboundSessionId, (Vec<StreamProtocol>, ClientResponsesSender)>,
     sqmr_outbound_report_receivers_awaiting_assignment: HashMap<OutboundSessionId, ReportReceiver>,
     // Splitting the broadcast receivers from the broadcasted senders in order to poll all
     // receivers simultaneously.
@@ -72,9 +80,8 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
             tokio::select! {
                 Some(event) = self.swarm.next() => self.handle_swarm_event(event)?,
                 Some(res) = self.sqmr_inbound_response_receivers.next() => self.handle_response_for_inbound_query(res),
-                Some((protocol, client_payload)) = self.sqmr_outbound_payload_receivers.next() => {
-                    let protocol = StreamProtocol::try_from_owned(protocol).expect("Invalid protocol should not appear");
-                    self.handle_local_sqmr_payload(protocol, client_payload.expect("An SQMR client channel should not be terminated."))
+                Some((_protocol, client_payload)) = self.sqmr_outbound_payload_receivers.next() => {
+                    self.handle_local_sqmr_payload(client_payload.expect("An SQMR client channel should not be terminated."))
                 }
                 Some((topic_hash, message)) = self.messages_to_broadcast_receivers.next() => {
                     self.broadcast_message(
@@ -131,8 +138,6 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
         }
     }
 
-    // TODO(Shahak): Support multiple protocols where they're all different versions of the same
-    // protocol
     pub fn register_sqmr_protocol_server<Query, Response>(
         &mut self,
         protocol: String,
@@ -140,36 +145,62 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
     ) -> SqmrServerReceiver<Query, Response>
     where
         Bytes: From<Response>,
-        Query: TryFrom<Bytes> + Clone,
+        Query: TryFrom<Bytes> + Clone + 'static,
         <Query as TryFrom<Bytes>>::Error: Clone,
         Response: 'static,
     {
-        let protocol = StreamProtocol::try_from_owned(protocol)
-            .expect("Could not parse protocol into StreamProtocol.");
-        self.swarm.add_new_supported_inbound_protocol(protocol.clone());
-        if let Some(_old_buffer_size) =
-            self.inbound_protocol_to_buffer_size.insert(protocol.clone(), buffer_size)
-        {
-            panic!("Protocol '{protocol}' has already been registered as a server.");
-        }
+        self.register_sqmr_protocol_server_with_versions(
+            vec![SqmrServerProtocolVersion::new(protocol)],
+            buffer_size,
+        )
+    }
+
+    /// Register a new server for several versions of the same protocol. Queries of all the
+    /// versions are received on the same receiver, decoded according to the version the remote
+    /// peer chose, and responses are encoded in that version.
+    /// Panics if one of the protocols is already registered as a server.
+    pub fn register_sqmr_protocol_server_with_versions<Query, Response>(
+        &mut self,
+        versions: Vec<SqmrServerProtocolVersion<Query, Response>>,
+        buffer_size: usize,
+    ) -> SqmrServerReceiver<Query, Response>
+    where
+        Query: TryFrom<Bytes> + 'static,
+        Response: 'static,
+    {
         let (inbound_payload_sender, inbound_payload_receiver) =
             futures::channel::mpsc::channel(buffer_size);
-        let insert_result = self
-            .sqmr_inbound_payload_senders
-            .insert(protocol.clone(), Box::new(inbound_payload_sender));
-        if insert_result.is_some() {
-            panic!("Protocol '{protocol}' has already been registered as a server.");
+        let mut versions_by_protocol = HashMap::new();
+        for version in versions {
+            let protocol = StreamProtocol::try_from_owned(version.protocol.clone())
+                .expect("Could not parse protocol into StreamProtocol.");
+            self.swarm.add_new_supported_inbound_protocol(protocol.clone());
+            if let Some(_old_buffer_size) =
+                self.inbound_protocol_to_buffer_size.insert(protocol.clone(), buffer_size)
+            {
+                panic!("Protocol '{protocol}' has already been registered as a server.");
+            }
+            let insert_result = self
+                .sqmr_inbound_payload_senders
+                .insert(protocol.clone(), Box::new(inbound_payload_sender.clone()));
+            if insert_result.is_some() {
+                panic!("Protocol '{protocol}' has already been registered as a server.");
+            }
+            versions_by_protocol.insert(protocol, version);
         }
 
-        let inbound_payload_receiver = inbound_payload_receiver
-            .map(|payload: SqmrServerPayload| ServerQueryManager::from(payload));
+        let inbound_payload_receiver =
+            inbound_payload_receiver.map(move |payload: SqmrServerPayload| {
+                let version = versions_by_protocol
+                    .get(&payload.protocol_name)
+                    .expect("Received a query on a protocol that wasn't registered.");
+                ServerQueryManager::new(payload, version)
+            });
         SqmrServerReceiver { receiver: Box::new(inbound_payload_receiver) }
     }
 
     /// Register a new subscriber for sending a single query and receiving multiple responses.
     /// Panics if the given protocol is already subscribed.
-    // TODO(Shahak): Support multiple protocols where they're all different versions of the same
-    // protocol.
     // TODO(Shahak): Seperate query and response buffer sizes.
     pub fn register_sqmr_protocol_client<Query, Response>(
         &mut self,
@@ -182,19 +213,53 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
         <Response as TryFrom<Bytes>>::Error: 'static + Send,
         Query: 'static,
     {
-        let protocol = StreamProtocol::try_from_owned(protocol)
-            .expect("Could not parse protocol into StreamProtocol.");
-        self.swarm.add_new_supported_inbound_protocol(protocol.clone());
+        self.register_sqmr_protocol_client_with_versions(
+            vec![SqmrClientProtocolVersion::new(protocol)],
+            buffer_size,
+        )
+    }
+
+    /// Register a new subscriber for several versions of the same protocol, ordered from the most
+    /// preferred to the least preferred. Each query is offered to the remote peer in all the
+    /// versions, and the responses are decoded according to the version the remote peer chose.
+    /// Panics if `versions` is empty or if the most preferred protocol is already subscribed.
+    pub fn register_sqmr_protocol_client_with_versions<Query, Response>(
+        &mut self,
+        versions: Vec<SqmrClientProtocolVersion<Query, Response>>,
+        buffer_size: usize,
+    ) -> SqmrClientSender<Query, Response>
+    where
+        Response: TryFrom<Bytes> + 'static + Send,
+        <Response as TryFrom<Bytes>>::Error: 'static + Send,
+        Query: 'static,
+    {
+        let preferred_protocol = versions
+            .first()
+            .expect("An SQMR client must support at least one protocol version.")
+            .protocol
+            .clone();
+        for version in &versions {
+            let protocol = StreamProtocol::try_from_owned(version.protocol.clone())
+                .expect("Could not parse protocol into StreamProtocol.");
+            self.swarm.add_new_supported_inbound_protocol(protocol);
+        }
         let (payload_sender, payload_receiver) = futures::channel::mpsc::channel(buffer_size);
 
         let insert_result = self
             .sqmr_outbound_payload_receivers
-            .insert(protocol.clone().as_ref().to_string(), Box::new(payload_receiver));
+            .insert(preferred_protocol.clone(), Box::new(payload_receiver));
         if insert_result.is_some() {
-            panic!("Protocol '{protocol}' has already been registered as a client.");
+            panic!("Protocol '{preferred_protocol}' has already been registered as a client.");
         };
 
-        SqmrClientSender::new(Box::new(payload_sender), buffer_size)
+        SqmrClientSender::new(Box::new(payload_sender), buffer_size, versions)
+    }
+
+    /// Sets the manager that signs this node's staker identity and verifies the identities of
+    /// other peers. Peers are permitted by their staker identity only in permissioned mode with
+    /// configured staker public keys.
+    pub fn set_staker_identity_manager(&mut self, manager: Arc<dyn StakerIdentityManager>) {
+        self.swarm.behaviour_mut().staker_identity.set_manager(manager);
     }
 
     /// Register a new subscriber for broadcasting and receiving broadcasts for a given topic.
@@ -302,6 +367,9 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
                 self.handle_behaviour_event(event)?;
             }
             SwarmEvent::OutgoingConnectionError { connection_id, peer_id, error } => {
+                if let DialError::Denied { .. } = error {
+                    self.report_denied_connection_to_metrics();
+                }
                 warn!(
                     "Outgoing connection error. connection id: {connection_id:?}, requested peer \
                      id: {peer_id:?}, error: {error:?}"
@@ -315,6 +383,9 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
             } => {
                 // No need to panic here since this is a result of another peer trying to dial to us
                 // and failing. Other peers are welcome to retry.
+                if let ListenError::Denied { .. } = error {
+                    self.report_denied_connection_to_metrics();
+                }
                 warn!(
                     "Incoming connection error. connection id: {connection_id:?}, local addr: \
                      {local_addr:?}, send back addr: {send_back_addr:?}, error: {error:?}"
@@ -372,6 +443,12 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
         if let mixed_behaviour::ToOtherBehaviourEvent::NoOp = event {
             return;
         }
+        if let mixed_behaviour::ToOtherBehaviourEvent::PeerManager(
+            peer_manager::ToOtherBehaviourEvent::PeerNotPermitted { .. },
+        ) = event
+        {
+            self.report_denied_connection_to_metrics();
+        }
         self.swarm.behaviour_mut().identify.on_other_behaviour_event(&event);
         self.swarm.behaviour_mut().kademlia.on_other_behaviour_event(&event);
         if let Some(discovery) = self.swarm.behaviour_mut().discovery.as_mut() {
@@ -399,7 +476,13 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
                 outbound_session_id,
                 response,
                 peer_id,
-            } => self.handle_sqmr_event_received_response(outbound_session_id, peer_id, response),
+                protocol_name,
+            } => self.handle_sqmr_event_received_response(
+                outbound_session_id,
+                peer_id,
+                protocol_name,
+                response,
+            ),
             sqmr::behaviour::ExternalEvent::SessionFailed { session_id, error } => {
                 self.handle_sqmr_event_session_failed(session_id, error)
             }
@@ -451,7 +534,7 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
         // TODO(shahak): Close the inbound session if the buffer is full.
         send_now(
             query_sender,
-            SqmrServerPayload { query, report_sender, responses_sender },
+            SqmrServerPayload { query, report_sender, responses_sender, protocol_name },
             format!(
                 "Received an inbound query while the buffer is full. Dropping query for session \
                  {inbound_session_id:?}"
@@ -464,6 +547,7 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
         &mut self,
         outbound_session_id: OutboundSessionId,
         peer_id: PeerId,
+        protocol_name: StreamProtocol,
         response: Vec<u8>,
     ) {
         trace!(
@@ -475,14 +559,22 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
         {
             self.handle_new_report_receiver(peer_id, report_receiver)
         }
-        if let Some(response_sender) =
+        if let Some((offered_protocols, response_sender)) =
             self.sqmr_outbound_response_senders.get_mut(&outbound_session_id)
         {
+            if !offered_protocols.contains(&protocol_name) {
+                warn!(
+                    "Peer {peer_id:?} responded on protocol {protocol_name} which wasn't offered \
+                     in {outbound_session_id:?}. Reporting the peer and dropping the response."
+                );
+                self.swarm.report_peer_as_malicious(peer_id, MisconductScore::MALICIOUS);
+                return;
+            }
             // TODO(shahak): Close the channel if the buffer is full.
             // TODO(Eitan): Close the channel if query was dropped by user.
             send_now(
                 response_sender,
-                response,
+                (protocol_name, response),
                 format!(
                     "Received response for an outbound query while the buffer is full. Dropping \
                      it. {outbound_session_id:?}"
@@ -597,19 +689,17 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
         };
     }
 
-    fn handle_local_sqmr_payload(
-        &mut self,
-        protocol: StreamProtocol,
-        client_payload: SqmrClientPayload,
-    ) {
+    fn handle_local_sqmr_payload(&mut self, client_payload: SqmrClientPayload) {
         let SqmrClientPayload { query, report_receiver, responses_sender } = client_payload;
-        let outbound_session_id = self.swarm.send_query(query, protocol.clone());
+        let offered_protocols = query.protocols.clone();
+        let outbound_session_id = self.swarm.send_query(query);
         if let Some(sqmr_metrics) =
             self.metrics.as_ref().and_then(|metrics| metrics.sqmr_metrics.as_ref())
         {
             sqmr_metrics.num_active_outbound_sessions.increment(1);
         }
-        self.sqmr_outbound_response_senders.insert(outbound_session_id, responses_sender);
+        self.sqmr_outbound_response_senders
+            .insert(outbound_session_id, (offered_protocols, responses_sender));
         self.sqmr_outbound_report_receivers_awaiting_assignment
             .insert(outbound_session_id, report_receiver);
     }
@@ -629,6 +719,12 @@ impl<SwarmT: SwarmTrait> GenericNetworkManager<SwarmT> {
         self.swarm.broadcast_message(message, topic_hash);
     }
 
+    fn report_denied_connection_to_metrics(&self) {
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.num_denied_connections.increment(1);
+        }
+    }
+
     fn report_session_removed_to_metrics(&mut self, session_id: SessionId) {
         match session_id {
             SessionId::InboundSessionId(_) => {
@@ -753,6 +849,12 @@ impl NetworkManager {
     pub fn get_local_peer_id(&self) -> String {
         self.swarm.local_peer_id().to_string()
     }
+
+    /// Returns a client of this network's peer ban list, or None if the ban list isn't persisted.
+    /// The client's requests are handled while the network is running.
+    pub fn peer_ban_list_client(&self) -> Option<PeerBanListClient> {
+        self.swarm.behaviour().peer_manager.ban_list_client()
+    }
 }
 
 pub type ReportSender = oneshot::Sender<()>;
@@ -764,6 +866,8 @@ pub type GenericReceiver<T> = Box<dyn Stream<Item = T> + Unpin + Send>;
 
 type ResponsesSender = GenericSender<Bytes>;
 type ResponsesReceiver = GenericReceiver<Bytes>;
+// Responses to an outbound query, along with the protocol that was negotiated for the query.
+type ClientResponsesSender = GenericSender<(StreamProtocol, Bytes)>;
 
 type ClientResponsesReceiver<Response> =
     GenericReceiver<Result<Response, <Response as TryFrom<Bytes>>::Error>>;
@@ -787,43 +891,133 @@ impl<Response> Drop for ServerResponsesSender<Response> {
     }
 }
 
-pub struct SqmrClientSender<Query, Response>
+pub type QueryEncoder<Query> = Arc<dyn Fn(Query) -> Bytes + Send + Sync>;
+pub type QueryDecoder<Query> =
+    Arc<dyn Fn(Bytes) -> Result<Query, <Query as TryFrom<Bytes>>::Error> + Send + Sync>;
+pub type ResponseEncoder<Response> = Arc<dyn Fn(Response) -> Bytes + Send + Sync>;
+pub type ResponseDecoder<Response> =
+    Arc<dyn Fn(Bytes) -> Result<Response, <Response as TryFrom<Bytes>>::Error> + Send + Sync>;
+
+/// A version of an SQMR protocol that a client supports, with the encoding of the queries and the
+/// decoding of the responses in that version.
+pub struct SqmrClientProtocolVersion<Query, Response: TryFrom<Bytes>> {
+    pub protocol: String,
+    pub encode_query: QueryEncoder<Query>,
+    pub decode_response: ResponseDecoder<Response>,
+}
+
+impl<Query, Response> SqmrClientProtocolVersion<Query, Response>
 where
     Bytes: From<Query>,
+    Query: 'static,
+    Response: TryFrom<Bytes> + 'static,
+{
+    /// A version whose messages are encoded with the `Bytes` conversions of the message types.
+    pub fn new(protocol: String) -> Self {
+        Self {
+            protocol,
+            encode_query: Arc::new(Bytes::from),
+            decode_response: Arc::new(Response::try_from),
+        }
+    }
+}
+
+/// A version of an SQMR protocol that a server supports, with the decoding of the queries and the
+/// encoding of the responses in that version.
+pub struct SqmrServerProtocolVersion<Query: TryFrom<Bytes>, Response> {
+    pub protocol: String,
+    pub decode_query: QueryDecoder<Query>,
+    pub encode_response: ResponseEncoder<Response>,
+}
+
+impl<Query, Response> SqmrServerProtocolVersion<Query, Response>
+where
+    Bytes: From<Response>,
+    Query: TryFrom<Bytes> + 'static,
+    Response: 'static,
+{
+    /// A version whose messages are encoded with the `Bytes` conversions of the message types.
+    pub fn new(protocol: String) -> Self {
+        Self {
+            protocol,
+            decode_query: Arc::new(Query::try_from),
+            encode_response: Arc::new(Bytes::from),
+        }
+    }
+}
+
+pub struct SqmrClientSender<Query, Response>
+where
     Response: TryFrom<Bytes> + 'static + Send,
     <Response as TryFrom<Bytes>>::Error: 'static + Send,
 {
     sender: GenericSender<SqmrClientPayload>,
     buffer_size: usize,
-    _query_type: std::marker::PhantomData<Query>,
-    _response_type: std::marker::PhantomData<Response>,
+    // Ordered from the most preferred version to the least preferred.
+    versions: Arc<Vec<(StreamProtocol, QueryEncoder<Query>)>>,
+    response_decoders: Arc<HashMap<StreamProtocol, ResponseDecoder<Response>>>,
 }
 
 impl<Query, Response> SqmrClientSender<Query, Response>
 where
-    Bytes: From<Query>,
     Response: TryFrom<Bytes> + 'static + Send,
     <Response as TryFrom<Bytes>>::Error: 'static + Send,
 {
-    fn new(sender: GenericSender<SqmrClientPayload>, buffer_size: usize) -> Self {
+    fn new(
+        sender: GenericSender<SqmrClientPayload>,
+        buffer_size: usize,
+        versions: Vec<SqmrClientProtocolVersion<Query, Response>>,
+    ) -> Self {
+        let mut response_decoders = HashMap::new();
+        let versions = versions
+            .into_iter()
+            .map(|SqmrClientProtocolVersion { protocol, encode_query, decode_response }| {
+                let protocol = StreamProtocol::try_from_owned(protocol)
+                    .expect("Could not parse protocol into StreamProtocol.");
+                response_decoders.insert(protocol.clone(), decode_response);
+                (protocol, encode_query)
+            })
+            .collect();
         Self {
             sender,
             buffer_size,
-            _query_type: std::marker::PhantomData,
-            _response_type: std::marker::PhantomData,
+            versions: Arc::new(versions),
+            response_decoders: Arc::new(response_decoders),
         }
     }
+
     pub async fn send_new_query(
         &mut self,
         query: Query,
-    ) -> Result<ClientResponsesManager<Response>, SendError> {
+    ) -> Result<ClientResponsesManager<Response>, SendError>
+    where
+        Query: Clone + Send + Sync + 'static,
+    {
         let (report_sender, report_receiver) = oneshot::channel::<()>();
         let (responses_sender, responses_receiver) =
             futures::channel::mpsc::channel(self.buffer_size);
         let responses_receiver = Box::new(responses_receiver);
-        let query = Bytes::from(query);
-        let responses_sender =
-            Box::new(responses_sender.with(|response| ready(Ok(Response::try_from(response)))));
+        let protocols =
+            self.versions.iter().map(|(protocol, _encode_query)| protocol.clone()).collect();
+        let versions = self.versions.clone();
+        // The query is encoded only for the version that the peer chooses.
+        let query = sqmr::OutboundQuery::new(protocols, move |protocol| {
+            versions
+                .iter()
+                .find(|(offered_protocol, _encode_query)| offered_protocol == protocol)
+                .map(|(_protocol, encode_query)| encode_query(query.clone()))
+        });
+        let response_decoders = self.response_decoders.clone();
+        let responses_sender = Box::new(responses_sender.with_flat_map(
+            move |(protocol, response): (StreamProtocol, Bytes)| {
+                // Responses on protocols that weren't offered are dropped by the network manager
+                // before reaching here, so a missing decoder drops the response as well.
+                let decoded_response = response_decoders
+                    .get(&protocol)
+                    .map(|decode_response| Ok(decode_response(response)));
+                futures::stream::iter(decoded_response)
+            },
+        ));
         let payload = SqmrClientPayload { query, report_receiver, responses_sender };
         self.sender.send(payload).await?;
         Ok(ClientResponsesManager { report_sender, responses_receiver })
@@ -856,9 +1050,9 @@ impl<Response: TryFrom<Bytes>> Stream for ClientResponsesManager<Response> {
 type SqmrClientReceiver = GenericReceiver<SqmrClientPayload>;
 
 pub struct SqmrClientPayload {
-    query: Bytes,
+    query: sqmr::OutboundQuery,
     report_receiver: ReportReceiver,
-    responses_sender: ResponsesSender,
+    responses_sender: ClientResponsesSender,
 }
 
 pub struct SqmrServerReceiver<Query, Response>
@@ -918,17 +1112,21 @@ where
     }
 }
 
-impl<Query, Response> From<SqmrServerPayload> for ServerQueryManager<Query, Response>
+impl<Query, Response> ServerQueryManager<Query, Response>
 where
-    Bytes: From<Response>,
-    Response: 'static,
     Query: TryFrom<Bytes>,
+    Response: 'static,
 {
-    fn from(payload: SqmrServerPayload) -> Self {
-        let SqmrServerPayload { query, report_sender, responses_sender } = payload;
-        let query = Query::try_from(query);
+    fn new(
+        payload: SqmrServerPayload,
+        version: &SqmrServerProtocolVersion<Query, Response>,
+    ) -> Self {
+        let SqmrServerPayload { query, report_sender, responses_sender, protocol_name: _ } =
+            payload;
+        let query = (version.decode_query)(query);
+        let encode_response = version.encode_response.clone();
         let responses_sender =
-            Box::new(responses_sender.with(|response| ready(Ok(Bytes::from(response)))));
+            Box::new(responses_sender.with(move |response| ready(Ok(encode_response(response)))));
         let responses_sender = ServerResponsesSender { sender: responses_sender };
 
         Self { query, report_sender, responses_sender }
@@ -941,6 +1139,8 @@ struct SqmrServerPayload {
     query: Bytes,
     report_sender: ReportSender,
     responses_sender: ResponsesSender,
+    // The protocol the remote peer chose for the query.
+    protocol_name: StreamProtocol,
 }
 
 #[async_trait]
@@ -10,7 +10,7 @@ use crate::gossipsub_impl::Topic;
 use crate::misconduct_score::MisconductScore;
 use crate::peer_manager::ReputationModifier;
 use crate::sqmr::behaviour::SessionIdNotFoundError;
-use crate::sqmr::{InboundSessionId, OutboundSessionId, SessionId};
+use crate::sqmr::{InboundSessionId, OutboundQuery, OutboundSessionId, SessionId};
 use crate::{mixed_behaviour, Bytes};
 pub type Event = SwarmEvent<<mixed_behaviour::MixedBehaviour as NetworkBehaviour>::ToSwarm>;
 
@@ -21,7 +21,7 @@ pub trait SwarmTrait: Stream<Item = Event> + Unpin {
         inbound_session_id: InboundSessionId,
     ) -> Result<(), SessionIdNotFoundError>;
 
-    fn send_query(&mut self, query: Vec<u8>, protocol: StreamProtocol) -> OutboundSessionId;
+    fn send_query(&mut self, query: OutboundQuery) -> OutboundSessionId;
 
     fn dial(&mut self, peer_multiaddr: Multiaddr) -> Result<(), DialError>;
 
@@ -59,8 +59,8 @@ impl SwarmTrait for Swarm<mixed_behaviour::MixedBehaviour> {
         self.behaviour_mut().sqmr.send_response(response, inbound_session_id)
     }
 
-    fn send_query(&mut self, query: Vec<u8>, protocol: StreamProtocol) -> OutboundSessionId {
-        self.behaviour_mut().sqmr.start_query(query, protocol)
+    fn send_query(&mut self, query: OutboundQuery) -> OutboundSessionId {
+        self.behaviour_mut().sqmr.start_query(query)
     }
 
     fn dial(&mut self, peer_multiaddr: Multiaddr) -> Result<(), DialError> {
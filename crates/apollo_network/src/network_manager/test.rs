@@ -26,7 +26,7 @@ use crate::gossipsub_impl::{self, Topic};
 use crate::misconduct_score::MisconductScore;
 use crate::network_manager::{BroadcastTopicClientTrait, ServerQueryManager};
 use crate::sqmr::behaviour::SessionIdNotFoundError;
-use crate::sqmr::{GenericEvent, InboundSessionId, OutboundSessionId};
+use crate::sqmr::{GenericEvent, InboundSessionId, OutboundQuery, OutboundSessionId};
 use crate::{mixed_behaviour, Bytes};
 
 const TIMEOUT: Duration = Duration::from_secs(1);
@@ -47,6 +47,8 @@ struct MockSwarm {
     inbound_session_id_to_response_sender: HashMap<InboundSessionId, UnboundedSender<Bytes>>,
     next_outbound_session_id: usize,
     first_polled_event_notifier: Option<oneshot::Sender<()>>,
+    // The protocol that peers respond on. If None, they respond on the most preferred protocol.
+    response_protocol: Option<StreamProtocol>,
 }
 
 impl Stream for MockSwarm {
@@ -107,6 +109,7 @@ impl MockSwarm {
         query: Vec<u8>,
         outbound_session_id: OutboundSessionId,
         peer_id: PeerId,
+        protocol_name: StreamProtocol,
     ) {
         for response in query {
             self.pending_events.push(Event::Behaviour(mixed_behaviour::Event::ExternalEvent(
@@ -114,6 +117,7 @@ impl MockSwarm {
                     response: vec![response],
                     outbound_session_id,
                     peer_id,
+                    protocol_name: protocol_name.clone(),
                 }),
             )));
         }
@@ -134,12 +138,16 @@ impl SwarmTrait for MockSwarm {
         Ok(())
     }
 
-    fn send_query(&mut self, query: Vec<u8>, _protocol: StreamProtocol) -> OutboundSessionId {
+    fn send_query(&mut self, query: OutboundQuery) -> OutboundSessionId {
         let outbound_session_id = OutboundSessionId { value: self.next_outbound_session_id };
+        // The peer supports the most preferred protocol.
+        let protocol_name = query.protocols.first().unwrap().clone();
+        let query = query.encode(&protocol_name).unwrap();
         self.create_response_events_for_query_each_num_becomes_response(
             query,
             outbound_session_id,
             PeerId::random(),
+            self.response_protocol.clone().unwrap_or(protocol_name),
         );
         self.next_outbound_session_id += 1;
         outbound_session_id
@@ -260,6 +268,40 @@ async fn register_sqmr_protocol_client_and_use_channels() {
     assert_eq!(*response_receiver_length.lock().await, VEC1.len());
 }
 
+#[tokio::test]
+async fn response_on_protocol_that_was_not_offered_is_dropped_and_peer_is_reported() {
+    let mut mock_swarm = MockSwarm {
+        response_protocol: Some(StreamProtocol::new("/starknet/headers/2")),
+        ..Default::default()
+    };
+    let mut reported_peer_receiver = mock_swarm.get_reported_peers_stream();
+
+    let mut network_manager = GenericNetworkManager::generic_new(
+        mock_swarm,
+        None,
+        None,
+        MESSAGE_METADATA_BUFFER_SIZE,
+        MESSAGE_METADATA_BUFFER_SIZE,
+    );
+    let mut payload_sender = network_manager.register_sqmr_protocol_client::<Vec<u8>, Vec<u8>>(
+        SIGNED_BLOCK_HEADER_PROTOCOL.to_string(),
+        BUFFER_SIZE,
+    );
+
+    tokio::select! {
+        _ = network_manager.run() => panic!("network manager ended"),
+        result = tokio::time::timeout(TIMEOUT, async {
+            let client_response_manager =
+                payload_sender.send_new_query(VEC1.clone()).await.unwrap();
+            reported_peer_receiver.next().await.unwrap();
+            client_response_manager
+        }) => {
+            // The channel closes once the network manager is dropped.
+            assert!(result.unwrap().collect::<Vec<_>>().await.is_empty());
+        }
+    }
+}
+
 // TODO(shahak): Add multiple protocols and multiple queries in the test.
 #[tokio::test]
 async fn process_incoming_query() {
@@ -23,6 +23,7 @@ use super::{
     ServerQueryManager,
     ServerResponsesSender,
     SqmrClientPayload,
+    SqmrClientProtocolVersion,
     SqmrClientSender,
     SqmrServerReceiver,
     Topic,
@@ -30,6 +31,9 @@ use super::{
 use crate::utils::make_quic_multiaddr;
 use crate::{Bytes, NetworkConfig};
 
+// The protocol of the mocked SQMR clients. The mocked clients don't negotiate it with any peer.
+const MOCK_SQMR_PROTOCOL: &str = "/mock_sqmr_protocol";
+
 pub fn mock_register_sqmr_protocol_client<Query, Response>(
     buffer_size: usize,
     // TODO(eitan): wrap second type with a struct to make it more readable
@@ -46,7 +50,8 @@ where
         MockClientResponsesManager::<Query, Response>::from(payload)
     });
     let receiver = Box::new(receiver);
-    (SqmrClientSender::new(sender, buffer_size), receiver)
+    let versions = vec![SqmrClientProtocolVersion::new(MOCK_SQMR_PROTOCOL.to_string())];
+    (SqmrClientSender::new(sender, buffer_size, versions), receiver)
 }
 
 pub fn mock_register_sqmr_protocol_server<Query, Response>(
@@ -248,9 +253,16 @@ where
 {
     fn from(payload: SqmrClientPayload) -> Self {
         let SqmrClientPayload { query, report_receiver, responses_sender } = payload;
-        let query = Query::try_from(query);
+        // The peer supports the most preferred protocol.
+        let protocol =
+            query.protocols.first().expect("A query is sent in at least one protocol.").clone();
+        let query = Query::try_from(
+            query.encode(&protocol).expect("A query is encoded for each offered protocol."),
+        );
         let responses_sender =
-            Box::new(responses_sender.with(|response: Response| ready(Ok(Bytes::from(response)))));
+            Box::new(responses_sender.with(move |response: Response| {
+                ready(Ok((protocol.clone(), Bytes::from(response))))
+            }));
         Self {
             query,
             report_receiver,
@@ -0,0 +1,48 @@
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread::{self, JoinHandle};
+
+use apollo_network_types::network_types::PeerBanList;
+use tracing::error;
+
+/// Persists the ban list of a peer manager on a dedicated thread, so that the peer manager never
+/// blocks on file I/O. Only the latest ban list is written if several are pending. Pending writes
+/// are completed when the writer is dropped.
+pub(crate) struct BanListWriter {
+    sender: Option<mpsc::Sender<PeerBanList>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl BanListWriter {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        let (sender, receiver) = mpsc::channel::<PeerBanList>();
+        let thread = thread::Builder::new()
+            .name("peer_ban_list_writer".to_string())
+            .spawn(move || {
+                while let Ok(mut ban_list) = receiver.recv() {
+                    ban_list = receiver.try_iter().last().unwrap_or(ban_list);
+                    if let Err(err) = ban_list.write_to_file(&path) {
+                        error!("Failed to write the peer ban list to {path:?}. Error: {err}");
+                    }
+                }
+            })
+            .expect("Failed to spawn the peer ban list writer thread.");
+        Self { sender: Some(sender), thread: Some(thread) }
+    }
+
+    pub(crate) fn write(&self, ban_list: PeerBanList) {
+        // The thread exits only after the sender is dropped.
+        let _ = self.sender.as_ref().expect("The sender is dropped only on drop.").send(ban_list);
+    }
+}
+
+impl Drop for BanListWriter {
+    fn drop(&mut self) {
+        self.sender = None;
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                error!("The peer ban list writer thread panicked.");
+            }
+        }
+    }
+}
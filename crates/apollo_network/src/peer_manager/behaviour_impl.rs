@@ -1,5 +1,6 @@
 use std::task::{ready, Poll};
 
+use futures::StreamExt;
 use libp2p::swarm::behaviour::ConnectionEstablished;
 use libp2p::swarm::{
     dummy,
@@ -26,6 +27,15 @@ pub enum ToOtherBehaviourEvent {
     PeerBlacklisted {
         peer_id: PeerId,
     },
+    // The peer connected in permissioned mode without being permitted, and was disconnected.
+    PeerNotPermitted {
+        peer_id: PeerId,
+    },
+    // The peer connected in permissioned mode before its staker identity was verified. No
+    // messages are exchanged with it other than its identity until it's verified.
+    PeerUnverified {
+        peer_id: PeerId,
+    },
 }
 
 impl NetworkBehaviour for PeerManager {
@@ -39,6 +49,13 @@ impl NetworkBehaviour for PeerManager {
         _local_addr: &libp2p::Multiaddr,
         _remote_addr: &libp2p::Multiaddr,
     ) -> Result<libp2p::swarm::THandler<Self>, libp2p::swarm::ConnectionDenied> {
+        if self.banned_peers.is_banned(&inbound_peer_id) {
+            return Err(libp2p::swarm::ConnectionDenied::new(PeerManagerError::PeerIsBanned(
+                inbound_peer_id,
+            )));
+        }
+        self.check_connection_permitted(inbound_peer_id)
+            .map_err(libp2p::swarm::ConnectionDenied::new)?;
         // TODO(Shahak): consider implementing a better lookup mechanism in case there's a lot of
         // peers this will be slow
         match self
@@ -65,13 +82,37 @@ impl NetworkBehaviour for PeerManager {
         Ok(())
     }
 
+    // Denying the dial of a peer that isn't permitted before connecting, so that discovery
+    // doesn't reach out to peers outside of the permissioned network. Peers may be dialed if they
+    // can still be permitted by a staker identity.
+    fn handle_pending_outbound_connection(
+        &mut self,
+        _connection_id: libp2p::swarm::ConnectionId,
+        maybe_peer: Option<PeerId>,
+        _addresses: &[Multiaddr],
+        _effective_role: libp2p::core::Endpoint,
+    ) -> Result<Vec<Multiaddr>, libp2p::swarm::ConnectionDenied> {
+        if let Some(peer_id) = maybe_peer {
+            if !self.is_permitted(&peer_id) && !self.verifies_staker_identities() {
+                return Err(libp2p::swarm::ConnectionDenied::new(
+                    PeerManagerError::PeerIsNotPermitted(peer_id),
+                ));
+            }
+        }
+        Ok(vec![])
+    }
+
     fn handle_established_outbound_connection(
         &mut self,
         _connection_id: libp2p::swarm::ConnectionId,
-        _peer: libp2p::PeerId,
+        peer: libp2p::PeerId,
         _addr: &libp2p::Multiaddr,
         _role_override: libp2p::core::Endpoint,
     ) -> Result<libp2p::swarm::THandler<Self>, libp2p::swarm::ConnectionDenied> {
+        if self.banned_peers.is_banned(&peer) {
+            return Err(libp2p::swarm::ConnectionDenied::new(PeerManagerError::PeerIsBanned(peer)));
+        }
+        self.check_connection_permitted(peer).map_err(libp2p::swarm::ConnectionDenied::new)?;
         Ok(dummy::ConnectionHandler)
     }
 
@@ -183,6 +224,14 @@ impl NetworkBehaviour for PeerManager {
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<libp2p::swarm::ToSwarm<Self::ToSwarm, libp2p::swarm::THandlerInEvent<Self>>>
     {
+        while let Some(Poll::Ready(Some(request))) =
+            self.ban_list_requests.as_mut().map(|requests| requests.poll_next_unpin(cx))
+        {
+            self.handle_ban_list_request(request);
+        }
+        while let Poll::Ready(Some(peer_id)) = self.unverified_peer_timeouts.poll_next_unpin(cx) {
+            self.on_unverified_peer_timeout(peer_id);
+        }
         if let Some(event) = self.pending_events.pop() {
             return Poll::Ready(event);
         }
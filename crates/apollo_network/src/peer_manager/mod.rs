@@ -1,33 +1,61 @@
-use std::collections::{BTreeMap, HashMap};
-use std::time::Duration;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use apollo_config::converters::{
     deserialize_milliseconds_to_duration,
     deserialize_seconds_to_duration,
 };
-use apollo_config::dumping::{ser_param, SerializeConfig};
+use apollo_config::dumping::{
+    prepend_sub_config_name,
+    ser_optional_param,
+    ser_param,
+    SerializeConfig,
+};
 use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use apollo_network_types::network_types::{
+    PeerBan,
+    PeerBanList,
+    PeerBanListClient,
+    PeerBanListRequest,
+    PeerBanListRequestReceiver,
+};
 use futures::future::BoxFuture;
+use futures::stream::FuturesUnordered;
 use futures::FutureExt;
 use libp2p::swarm::dial_opts::DialOpts;
-use libp2p::swarm::{ConnectionId, ToSwarm};
-use libp2p::PeerId;
+use libp2p::swarm::{CloseConnection, ConnectionId, ToSwarm};
+use libp2p::{PeerId, StreamProtocol};
 use peer::Peer;
+use permissioned::PermissionedConfig;
+use rand::Rng;
+use reputation::Offence;
 use serde::{Deserialize, Serialize};
-use tracing::info;
+use tracing::{error, info, warn};
+use validator::{Validate, ValidationError};
 
+use self::ban_list_writer::BanListWriter;
 pub use self::behaviour_impl::ToOtherBehaviourEvent;
 use crate::discovery::identify_impl::IdentifyToOtherBehaviourEvent;
 use crate::misconduct_score::MisconductScore;
 use crate::mixed_behaviour::BridgedBehaviour;
+use crate::sqmr::behaviour::OutboundSessionReport;
 use crate::sqmr::OutboundSessionId;
-use crate::{discovery, mixed_behaviour, sqmr};
+use crate::{discovery, mixed_behaviour, sqmr, staker_identity};
 
+mod ban_list_writer;
 pub(crate) mod behaviour_impl;
 pub(crate) mod peer;
+pub(crate) mod performance;
+pub(crate) mod permissioned;
+pub(crate) mod reputation;
 #[cfg(test)]
 mod test;
 
+// Longer than the staker identity handshake timeout, to leave time for the local identity to be
+// signed.
+const UNVERIFIED_PEER_TIMEOUT: Duration = Duration::from_secs(30);
+
 #[cfg_attr(test, derive(Debug, PartialEq))]
 #[derive(Clone, Copy)]
 pub enum ReputationModifier {
@@ -39,6 +67,7 @@ pub struct PeerManager {
     peers: HashMap<PeerId, Peer>,
     // TODO(Shahak): consider implementing a cleanup mechanism to not store all queries forever
     session_to_peer_map: HashMap<OutboundSessionId, PeerId>,
+    session_to_protocol_map: HashMap<OutboundSessionId, StreamProtocol>,
     config: PeerManagerConfig,
     last_peer_index: usize,
     // TODO(shahak): Change to VecDeque and awake when item is added.
@@ -48,24 +77,60 @@ pub struct PeerManager {
     sleep_waiting_for_unblocked_peer: Option<BoxFuture<'static, ()>>,
     // A peer is known only after we get the identify message.
     connections_for_unknown_peers: HashMap<PeerId, Vec<ConnectionId>>,
+    banned_peers: PeerBanList,
+    // All are None if the ban list isn't persisted.
+    ban_list_client: Option<PeerBanListClient>,
+    ban_list_requests: Option<PeerBanListRequestReceiver>,
+    ban_list_writer: Option<BanListWriter>,
+    // None if the network isn't permissioned. Includes the peers whose staker identity was
+    // verified.
+    permitted_peers: Option<HashSet<PeerId>>,
+    // Resolve to peers that connected without being permitted, once their time to send a staker
+    // identity is up.
+    unverified_peer_timeouts: FuturesUnordered<BoxFuture<'static, PeerId>>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Validate)]
 pub struct PeerManagerConfig {
     #[serde(deserialize_with = "deserialize_seconds_to_duration")]
     malicious_timeout_seconds: Duration,
     #[serde(deserialize_with = "deserialize_milliseconds_to_duration")]
     unstable_timeout_millis: Duration,
+    #[serde(deserialize_with = "deserialize_seconds_to_duration")]
+    reputation_half_life_seconds: Duration,
+    unstable_score: f64,
+    deprioritization_score: f64,
+    ban_score: f64,
+    ban_list_path: Option<PathBuf>,
+    #[validate(custom = "validate_performance_smoothing_factor")]
+    performance_smoothing_factor: f64,
+    exploration_probability: f64,
+    permissioned_config: PermissionedConfig,
+}
+
+/// Validates that the smoothing factor is in (0, 1], so that new reports affect the performance.
+fn validate_performance_smoothing_factor(
+    performance_smoothing_factor: f64,
+) -> Result<(), ValidationError> {
+    if performance_smoothing_factor > 0.0 && performance_smoothing_factor <= 1.0 {
+        Ok(())
+    } else {
+        Err(ValidationError::new("The performance smoothing factor must be in (0, 1]."))
+    }
 }
 
 #[derive(thiserror::Error, Debug)]
-pub(crate) enum PeerManagerError {
+pub enum PeerManagerError {
     #[error("No such peer: {0}")]
     NoSuchPeer(PeerId),
     #[error("No such session: {0}")]
     NoSuchSession(OutboundSessionId),
     #[error("Peer is blocked: {0}")]
     PeerIsBlocked(PeerId),
+    #[error("Peer is banned: {0}")]
+    PeerIsBanned(PeerId),
+    #[error("Peer is not permitted: {0}")]
+    PeerIsNotPermitted(PeerId),
 }
 
 impl Default for PeerManagerConfig {
@@ -74,13 +139,21 @@ impl Default for PeerManagerConfig {
             // TODO(shahak): Increase this once we're in a non-trusted setup.
             malicious_timeout_seconds: Duration::from_secs(1),
             unstable_timeout_millis: Duration::from_millis(1000),
+            reputation_half_life_seconds: Duration::from_secs(3600),
+            unstable_score: 0.1,
+            deprioritization_score: 0.5,
+            ban_score: 5.0,
+            ban_list_path: None,
+            performance_smoothing_factor: 0.2,
+            exploration_probability: 0.1,
+            permissioned_config: PermissionedConfig::default(),
         }
     }
 }
 
 impl SerializeConfig for PeerManagerConfig {
     fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
-        BTreeMap::from([
+        let mut dump = BTreeMap::from([
             ser_param(
                 "malicious_timeout_seconds",
                 &self.malicious_timeout_seconds.as_secs(),
@@ -93,7 +166,63 @@ impl SerializeConfig for PeerManagerConfig {
                 "The duration in milliseconds a peer blacklisted after being reported as unstable.",
                 ParamPrivacyInput::Public,
             ),
-        ])
+            ser_param(
+                "reputation_half_life_seconds",
+                &self.reputation_half_life_seconds.as_secs(),
+                "The duration in seconds after which the reputation scores of a peer decay to \
+                 half.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "unstable_score",
+                &self.unstable_score,
+                "The reputation score a peer accumulates each time it is reported as unstable.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "deprioritization_score",
+                &self.deprioritization_score,
+                "Peers with a reputation score of at least this value are assigned to sessions \
+                 only if no other peer is available.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "ban_score",
+                &self.ban_score,
+                "Peers whose accumulated misconduct score reaches this value are banned until \
+                 they are removed from the ban list.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "performance_smoothing_factor",
+                &self.performance_smoothing_factor,
+                "The weight of the latest session in the moving averages of the latency, \
+                 throughput and failure rate of a peer.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "exploration_probability",
+                &self.exploration_probability,
+                "The probability of assigning a session to the next peer in round-robin order \
+                 instead of the best performing peer, so that the performance of all peers keeps \
+                 being measured.",
+                ParamPrivacyInput::Public,
+            ),
+        ]);
+        dump.extend(ser_optional_param(
+            &self.ban_list_path,
+            "".into(),
+            "ban_list_path",
+            "The path of the JSON file in which banned peers are persisted. If not set, bans do \
+             not survive restarts. The file is read on startup and written only by the node while \
+             it is running.",
+            ParamPrivacyInput::Public,
+        ));
+        dump.extend(prepend_sub_config_name(
+            self.permissioned_config.dump(),
+            "permissioned_config",
+        ));
+        dump
     }
 }
 
@@ -101,9 +230,31 @@ impl SerializeConfig for PeerManagerConfig {
 impl PeerManager {
     pub(crate) fn new(config: PeerManagerConfig) -> Self {
         let peers = HashMap::new();
+        let (banned_peers, ban_list_client, ban_list_requests, ban_list_writer) =
+            match &config.ban_list_path {
+                Some(path) => {
+                    let banned_peers = PeerBanList::read_from_file(path).unwrap_or_else(|err| {
+                        error!(
+                            "Failed to read the peer ban list from {path:?}. Starting with an \
+                             empty ban list. Error: {err}"
+                        );
+                        PeerBanList::default()
+                    });
+                    let (ban_list_client, ban_list_requests) = PeerBanListClient::new();
+                    (
+                        banned_peers,
+                        Some(ban_list_client),
+                        Some(ban_list_requests),
+                        Some(BanListWriter::new(path.clone())),
+                    )
+                }
+                None => (PeerBanList::default(), None, None, None),
+            };
+        let permitted_peers = permissioned::permitted_peers(&config.permissioned_config);
         Self {
             peers,
             session_to_peer_map: HashMap::new(),
+            session_to_protocol_map: HashMap::new(),
             config,
             last_peer_index: 0,
             pending_events: Vec::new(),
@@ -111,7 +262,70 @@ impl PeerManager {
             sessions_received_when_no_peers: Vec::new(),
             sleep_waiting_for_unblocked_peer: None,
             connections_for_unknown_peers: HashMap::default(),
+            banned_peers,
+            ban_list_client,
+            ban_list_requests,
+            ban_list_writer,
+            permitted_peers,
+            unverified_peer_timeouts: FuturesUnordered::new(),
+        }
+    }
+
+    /// Returns a client of the persisted ban list, or None if the ban list isn't persisted.
+    pub(crate) fn ban_list_client(&self) -> Option<PeerBanListClient> {
+        self.ban_list_client.clone()
+    }
+
+    /// Returns whether the peer may connect to us. All peers are permitted unless the network is
+    /// permissioned.
+    fn is_permitted(&self, peer_id: &PeerId) -> bool {
+        self.permitted_peers
+            .as_ref()
+            .is_none_or(|permitted_peers| permitted_peers.contains(peer_id))
+    }
+
+    /// Returns whether peers that aren't allowed by their peer id may be permitted by a staker
+    /// identity.
+    pub(crate) fn verifies_staker_identities(&self) -> bool {
+        self.permitted_peers.is_some()
+            && !self.config.permissioned_config.staker_public_keys.is_empty()
+    }
+
+    /// Denies a connection with a peer that isn't permitted, unless the peer may still send its
+    /// staker identity, in which case the other behaviours are told to ignore it until it's
+    /// verified, and it's disconnected if it isn't verified in time.
+    fn check_connection_permitted(&mut self, peer_id: PeerId) -> Result<(), PeerManagerError> {
+        if self.is_permitted(&peer_id) {
+            return Ok(());
+        }
+        if !self.verifies_staker_identities() {
+            return Err(PeerManagerError::PeerIsNotPermitted(peer_id));
+        }
+        self.pending_events
+            .push(ToSwarm::GenerateEvent(ToOtherBehaviourEvent::PeerUnverified { peer_id }));
+        self.unverified_peer_timeouts.push(
+            async move {
+                tokio::time::sleep(UNVERIFIED_PEER_TIMEOUT).await;
+                peer_id
+            }
+            .boxed(),
+        );
+        Ok(())
+    }
+
+    fn on_unverified_peer_timeout(&mut self, peer_id: PeerId) {
+        if self.is_permitted(&peer_id) {
+            return;
         }
+        warn!("Disconnecting from peer {peer_id:?} since it didn't send a valid staker identity.");
+        self.pending_events
+            .push(ToSwarm::GenerateEvent(ToOtherBehaviourEvent::PeerNotPermitted { peer_id }));
+        self.pending_events
+            .push(ToSwarm::CloseConnection { peer_id, connection: CloseConnection::All });
+    }
+
+    pub(crate) fn permissioned_config(&self) -> &PermissionedConfig {
+        &self.config.permissioned_config
     }
 
     fn add_peer(&mut self, peer: Peer) {
@@ -139,14 +353,51 @@ impl PeerManager {
             self.sessions_received_when_no_peers.push(outbound_session_id);
             return None;
         }
-        let peer = self
-            .peers
-            .iter()
-            .skip(self.last_peer_index)
-            .find(|(_, peer)| peer.is_available())
-            .or_else(|| {
-                self.peers.iter().take(self.last_peer_index).find(|(_, peer)| peer.is_available())
-            });
+        let half_life = self.config.reputation_half_life_seconds;
+        let deprioritization_score = self.config.deprioritization_score;
+        let banned_peers = &self.banned_peers;
+        let permitted_peers = &self.permitted_peers;
+        let is_assignable = |peer: &Peer| {
+            peer.is_available()
+                && !banned_peers.is_banned(&peer.peer_id())
+                && permitted_peers
+                    .as_ref()
+                    .is_none_or(|permitted_peers| permitted_peers.contains(&peer.peer_id()))
+        };
+        let is_preferred = |peer: &Peer| {
+            is_assignable(peer) && peer.reputation().total_score(half_life) < deprioritization_score
+        };
+        // Unless exploring, the best performing peer for the session's protocol is assigned. Peers
+        // without any ended session of the protocol are tried first. Ties are broken in
+        // round-robin order.
+        let protocol_name = self.session_to_protocol_map.get(&outbound_session_id).filter(|_| {
+            !rand::thread_rng().gen_bool(self.config.exploration_probability.clamp(0.0, 1.0))
+        });
+        let performance_score = |peer: &Peer| {
+            protocol_name
+                .and_then(|protocol_name| peer.performance(protocol_name))
+                .map_or(f64::INFINITY, |performance| performance.score())
+        };
+        let find_peer = |predicate: &dyn Fn(&Peer) -> bool| {
+            let mut candidates = self
+                .peers
+                .iter()
+                .skip(self.last_peer_index)
+                .chain(self.peers.iter().take(self.last_peer_index))
+                .filter(|(_, peer)| predicate(peer));
+            match protocol_name {
+                Some(_) => candidates.reduce(|best, candidate| {
+                    if performance_score(candidate.1) > performance_score(best.1) {
+                        candidate
+                    } else {
+                        best
+                    }
+                }),
+                None => candidates.next(),
+            }
+        };
+        // Peers with a bad reputation are assigned only if there is no other available peer.
+        let peer = find_peer(&is_preferred).or_else(|| find_peer(&is_assignable));
         // TODO(shahak): advance to selected peer's index plus one.
         self.last_peer_index = (self.last_peer_index + 1) % self.peers.len();
         if peer.is_none() {
@@ -156,15 +407,16 @@ impl PeerManager {
                  {outbound_session_id:?}"
             );
             self.sessions_received_when_no_peers.push(outbound_session_id);
-            // Find the peer closest to becoming unblocked.
+            // Find the peer closest to becoming unblocked. Banned peers are unbanned only when the
+            // ban list is refreshed.
             let sleep_deadline = self
                 .peers
                 .values()
+                .filter(|peer| !self.banned_peers.is_banned(&peer.peer_id()))
                 .map(|peer| peer.blocked_until())
-                .min()
-                .expect("min should not return None on a non-empty iterator");
-            self.sleep_waiting_for_unblocked_peer =
-                Some(tokio::time::sleep_until(sleep_deadline.into()).boxed());
+                .min();
+            self.sleep_waiting_for_unblocked_peer = sleep_deadline
+                .map(|sleep_deadline| tokio::time::sleep_until(sleep_deadline.into()).boxed());
             return None;
         }
         peer.map(|(peer_id, peer)| {
@@ -211,10 +463,16 @@ impl PeerManager {
         peer_id: PeerId,
         reason: ReputationModifier,
     ) -> Result<(), PeerManagerError> {
+        let half_life = self.config.reputation_half_life_seconds;
         if let Some(peer) = self.peers.get_mut(&peer_id) {
             match reason {
                 ReputationModifier::Misconduct { misconduct_score } => {
                     peer.report(misconduct_score);
+                    peer.reputation_mut().add(
+                        Offence::Misconduct,
+                        misconduct_score.score(),
+                        half_life,
+                    );
                     if peer.is_malicious() {
                         self.pending_events.push(ToSwarm::GenerateEvent(
                             ToOtherBehaviourEvent::PeerBlacklisted { peer_id },
@@ -226,18 +484,102 @@ impl PeerManager {
                     }
                 }
                 ReputationModifier::Unstable => {
+                    peer.reputation_mut().add(
+                        Offence::Unstable,
+                        self.config.unstable_score,
+                        half_life,
+                    );
                     self.pending_events.push(ToSwarm::GenerateEvent(
                         ToOtherBehaviourEvent::PeerBlacklisted { peer_id },
                     ));
                     peer.blacklist_peer(self.config.unstable_timeout_millis);
                 }
             }
+            let misconduct_score = peer.reputation().score(Offence::Misconduct, half_life);
+            if misconduct_score >= self.config.ban_score && !self.banned_peers.is_banned(&peer_id) {
+                self.ban_peer(
+                    peer_id,
+                    format!("Accumulated a misconduct score of {misconduct_score:.2}."),
+                );
+            }
             Ok(())
         } else {
             Err(PeerManagerError::NoSuchPeer(peer_id))
         }
     }
 
+    fn ban_peer(&mut self, peer_id: PeerId, reason: String) {
+        warn!("Banning peer {peer_id:?}. Reason: {reason}");
+        let ban = PeerBan {
+            reason,
+            banned_at_unix_seconds: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("Current time should be after the unix epoch.")
+                .as_secs(),
+        };
+        self.banned_peers.banned_peers.insert(peer_id, ban);
+        self.persist_ban_list();
+        self.pending_events
+            .push(ToSwarm::GenerateEvent(ToOtherBehaviourEvent::PeerBlacklisted { peer_id }));
+        self.pending_events
+            .push(ToSwarm::CloseConnection { peer_id, connection: CloseConnection::All });
+    }
+
+    fn unban_peer(&mut self, peer_id: PeerId) {
+        if self.banned_peers.banned_peers.remove(&peer_id).is_none() {
+            return;
+        }
+        info!("Unbanning peer {peer_id:?}.");
+        self.persist_ban_list();
+        // An unbanned peer gets a fresh start, otherwise its next offence would ban it again.
+        if let Some(peer) = self.peers.get_mut(&peer_id) {
+            peer.reputation_mut().reset();
+        }
+        for outbound_session_id in std::mem::take(&mut self.sessions_received_when_no_peers) {
+            self.assign_peer_to_session(outbound_session_id);
+        }
+    }
+
+    fn persist_ban_list(&self) {
+        if let Some(ban_list_writer) = &self.ban_list_writer {
+            ban_list_writer.write(self.banned_peers.clone());
+        }
+    }
+
+    fn handle_ban_list_request(&mut self, request: PeerBanListRequest) {
+        // The requester may have stopped waiting for the response.
+        match request {
+            PeerBanListRequest::Get { response_sender } => {
+                let _ = response_sender.send(self.banned_peers.clone());
+            }
+            PeerBanListRequest::Ban { peer_id, reason, response_sender } => {
+                if !self.banned_peers.is_banned(&peer_id) {
+                    self.ban_peer(peer_id, reason);
+                }
+                let _ = response_sender.send(());
+            }
+            PeerBanListRequest::Unban { peer_id, response_sender } => {
+                self.unban_peer(peer_id);
+                let _ = response_sender.send(());
+            }
+        }
+    }
+
+    fn on_outbound_session_ended(
+        &mut self,
+        outbound_session_id: OutboundSessionId,
+        report: Option<&OutboundSessionReport>,
+    ) {
+        self.session_to_protocol_map.remove(&outbound_session_id);
+        let Some(report) = report else {
+            return;
+        };
+        if let Some(peer) = self.peers.get_mut(&report.peer_id) {
+            peer.performance_mut(report.protocol_name.clone())
+                .update(report, self.config.performance_smoothing_factor);
+        }
+    }
+
     fn report_session(
         &mut self,
         outbound_session_id: OutboundSessionId,
@@ -261,10 +603,19 @@ impl BridgedBehaviour for PeerManager {
     fn on_other_behaviour_event(&mut self, event: &mixed_behaviour::ToOtherBehaviourEvent) {
         match event {
             mixed_behaviour::ToOtherBehaviourEvent::Sqmr(
-                sqmr::ToOtherBehaviourEvent::RequestPeerAssignment { outbound_session_id },
+                sqmr::ToOtherBehaviourEvent::RequestPeerAssignment {
+                    outbound_session_id,
+                    protocol_name,
+                },
             ) => {
+                self.session_to_protocol_map.insert(*outbound_session_id, protocol_name.clone());
                 self.assign_peer_to_session(*outbound_session_id);
             }
+            mixed_behaviour::ToOtherBehaviourEvent::Sqmr(
+                sqmr::ToOtherBehaviourEvent::OutboundSessionEnded { outbound_session_id, report },
+            ) => {
+                self.on_outbound_session_ended(*outbound_session_id, report.as_ref());
+            }
             mixed_behaviour::ToOtherBehaviourEvent::Identify(
                 IdentifyToOtherBehaviourEvent::FoundListenAddresses { peer_id, listen_addresses },
             )
@@ -289,6 +640,14 @@ impl BridgedBehaviour for PeerManager {
                 }
                 self.add_peer(peer);
             }
+            mixed_behaviour::ToOtherBehaviourEvent::StakerIdentity(
+                staker_identity::ToOtherBehaviourEvent::PeerVerified { peer_id },
+            ) => {
+                if let Some(permitted_peers) = self.permitted_peers.as_mut() {
+                    info!("Permitting peer {peer_id:?} by its staker identity.");
+                    permitted_peers.insert(*peer_id);
+                }
+            }
             _ => {}
         }
     }
@@ -1,9 +1,12 @@
+use std::collections::HashMap;
 use std::time::{Duration, Instant};
 
 use libp2p::swarm::ConnectionId;
-use libp2p::{Multiaddr, PeerId};
+use libp2p::{Multiaddr, PeerId, StreamProtocol};
 use tracing::info;
 
+use super::performance::ProtocolPerformance;
+use super::reputation::Reputation;
 use crate::misconduct_score::MisconductScore;
 
 #[derive(Clone)]
@@ -13,6 +16,8 @@ pub struct Peer {
     timed_out_until: Instant,
     connection_ids: Vec<ConnectionId>,
     misconduct_score: MisconductScore,
+    reputation: Reputation,
+    performance: HashMap<StreamProtocol, ProtocolPerformance>,
 }
 
 impl Peer {
@@ -23,6 +28,8 @@ impl Peer {
             timed_out_until: get_instant_now(),
             connection_ids: Vec::new(),
             misconduct_score: MisconductScore::NEUTRAL,
+            reputation: Reputation::default(),
+            performance: HashMap::new(),
         }
     }
 
@@ -82,15 +89,32 @@ impl Peer {
     pub fn is_malicious(&self) -> bool {
         self.misconduct_score.is_malicious()
     }
+
+    pub fn reputation(&self) -> &Reputation {
+        &self.reputation
+    }
+
+    pub fn reputation_mut(&mut self) -> &mut Reputation {
+        &mut self.reputation
+    }
+
+    /// Returns the performance of the peer in sessions of the given protocol, if any of them ended.
+    pub fn performance(&self, protocol_name: &StreamProtocol) -> Option<&ProtocolPerformance> {
+        self.performance.get(protocol_name)
+    }
+
+    pub fn performance_mut(&mut self, protocol_name: StreamProtocol) -> &mut ProtocolPerformance {
+        self.performance.entry(protocol_name).or_default()
+    }
 }
 
 #[cfg(not(test))]
-fn get_instant_now() -> Instant {
+pub(super) fn get_instant_now() -> Instant {
     Instant::now()
 }
 
 // In tests we simulate time passing using tokio, so we need to use tokio's Instant instead of std.
 #[cfg(test)]
-fn get_instant_now() -> Instant {
+pub(super) fn get_instant_now() -> Instant {
     tokio::time::Instant::now().into_std()
 }
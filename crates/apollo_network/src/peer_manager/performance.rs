@@ -0,0 +1,61 @@
+use crate::sqmr::behaviour::OutboundSessionReport;
+
+/// The performance of a peer in the outbound sessions of a single protocol, as exponential moving
+/// averages over the sessions that ended.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProtocolPerformance {
+    n_sessions: usize,
+    latency_seconds: Option<f64>,
+    throughput: Option<f64>,
+    failure_rate: f64,
+}
+
+impl ProtocolPerformance {
+    /// Adds the given session to the moving averages. `smoothing_factor` is the weight of the
+    /// session in the averages.
+    pub fn update(&mut self, report: &OutboundSessionReport, smoothing_factor: f64) {
+        let moving_average = |average: Option<f64>, value: f64| match average {
+            Some(average) => average + smoothing_factor * (value - average),
+            None => value,
+        };
+        let failure = if report.succeeded { 0.0 } else { 1.0 };
+        self.failure_rate =
+            moving_average((self.n_sessions > 0).then_some(self.failure_rate), failure);
+        if let Some(time_to_first_response) = report.time_to_first_response {
+            self.latency_seconds =
+                Some(moving_average(self.latency_seconds, time_to_first_response.as_secs_f64()));
+        }
+        let duration_seconds = report.duration.as_secs_f64();
+        if report.succeeded && duration_seconds > 0.0 {
+            #[allow(clippy::as_conversions)]
+            let throughput = report.received_bytes as f64 / duration_seconds;
+            self.throughput = Some(moving_average(self.throughput, throughput));
+        }
+        self.n_sessions += 1;
+    }
+
+    /// A heuristic estimation of how useful it is to assign a session to the peer: the throughput
+    /// in bytes per second, discounted by the failure rate and the latency in seconds. A peer
+    /// that never finished a session successfully has a score of zero.
+    pub fn score(&self) -> f64 {
+        let Some(throughput) = self.throughput else {
+            return 0.0;
+        };
+        (1.0 - self.failure_rate) * throughput / (1.0 + self.latency_seconds.unwrap_or_default())
+    }
+
+    #[cfg(test)]
+    pub fn failure_rate(&self) -> f64 {
+        self.failure_rate
+    }
+
+    #[cfg(test)]
+    pub fn latency_seconds(&self) -> Option<f64> {
+        self.latency_seconds
+    }
+
+    #[cfg(test)]
+    pub fn throughput(&self) -> Option<f64> {
+        self.throughput
+    }
+}
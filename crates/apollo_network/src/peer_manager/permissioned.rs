@@ -0,0 +1,82 @@
+use std::collections::{BTreeMap, HashSet};
+
+use apollo_config::converters::{deserialize_vec, serialize_slice};
+use apollo_config::dumping::{ser_param, SerializeConfig};
+use apollo_config::{ParamPath, ParamPrivacyInput, SerializedParam};
+use libp2p::PeerId;
+use serde::{Deserialize, Deserializer, Serialize};
+use starknet_api::crypto::utils::PublicKey;
+use starknet_api::hash::StarkHash;
+use tracing::info;
+
+/// Configuration of the permissioned mode, in which only known peers may connect to the node.
+///
+/// A peer is permitted if its peer id is in `allowed_peer_ids`, or if it sends an identity signed
+/// by one of the stakers in `staker_public_keys` after connecting. Since libp2p connections are
+/// mutually authenticated, both sides of a connection are guaranteed to own the peer id they
+/// present, and each side checks that the other is permitted.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct PermissionedConfig {
+    pub enabled: bool,
+    #[serde(deserialize_with = "deserialize_vec")]
+    pub allowed_peer_ids: Vec<PeerId>,
+    #[serde(deserialize_with = "deserialize_public_keys")]
+    pub staker_public_keys: Vec<PublicKey>,
+}
+
+fn deserialize_public_keys<'de, D>(de: D) -> Result<Vec<PublicKey>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(deserialize_vec::<D, StarkHash>(de)?.into_iter().map(PublicKey).collect())
+}
+
+impl SerializeConfig for PermissionedConfig {
+    fn dump(&self) -> BTreeMap<ParamPath, SerializedParam> {
+        BTreeMap::from_iter([
+            ser_param(
+                "enabled",
+                &self.enabled,
+                "Whether only permitted peers may connect to the node. Connections with other \
+                 peers are denied and they are not dialed during discovery.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "allowed_peer_ids",
+                &serialize_slice(
+                    &self.allowed_peer_ids.iter().map(PeerId::to_string).collect::<Vec<_>>(),
+                ),
+                "A space separated list of the peer ids that are permitted in permissioned mode.",
+                ParamPrivacyInput::Public,
+            ),
+            ser_param(
+                "staker_public_keys",
+                &serialize_slice(
+                    &self
+                        .staker_public_keys
+                        .iter()
+                        .map(|public_key| public_key.0.to_hex_string())
+                        .collect::<Vec<_>>(),
+                ),
+                "A space separated list of the public keys of the stakers whose signed identities \
+                 permit a peer in permissioned mode. Peers that aren't allowed by their peer id \
+                 are disconnected unless they send such an identity shortly after connecting.",
+                ParamPrivacyInput::Public,
+            ),
+        ])
+    }
+}
+
+/// Returns the peers that are permitted by the given config before any staker identity is
+/// verified, or None if permissioned mode is disabled.
+pub(crate) fn permitted_peers(config: &PermissionedConfig) -> Option<HashSet<PeerId>> {
+    if !config.enabled {
+        return None;
+    }
+    info!(
+        "Running in permissioned mode with {} allowed peers and {} stakers.",
+        config.allowed_peer_ids.len(),
+        config.staker_public_keys.len()
+    );
+    Some(config.allowed_peer_ids.iter().copied().collect())
+}
@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use super::peer::get_instant_now;
+
+/// The types of offences a peer can be reported for. Each type accumulates a separate score.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Offence {
+    Misconduct,
+    Unstable,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct DecayingScore {
+    score: f64,
+    last_update: Instant,
+}
+
+impl DecayingScore {
+    /// Returns the score at the given time, after it decayed exponentially since its last update.
+    fn value_at(&self, now: Instant, half_life: Duration) -> f64 {
+        if half_life.is_zero() {
+            return 0.0;
+        }
+        let elapsed = now.saturating_duration_since(self.last_update);
+        self.score * 0.5_f64.powf(elapsed.as_secs_f64() / half_life.as_secs_f64())
+    }
+}
+
+/// The reputation of a peer. Offence scores accumulate per offence type and decay exponentially
+/// over time, so that a peer that stops misbehaving eventually regains a neutral reputation.
+#[derive(Clone, Debug, Default)]
+pub struct Reputation {
+    scores: HashMap<Offence, DecayingScore>,
+}
+
+impl Reputation {
+    pub fn add(&mut self, offence: Offence, score: f64, half_life: Duration) {
+        let now = get_instant_now();
+        let decayed_score = self.score(offence, half_life);
+        self.scores
+            .insert(offence, DecayingScore { score: decayed_score + score, last_update: now });
+    }
+
+    pub fn score(&self, offence: Offence, half_life: Duration) -> f64 {
+        let now = get_instant_now();
+        self.scores.get(&offence).map_or(0.0, |score| score.value_at(now, half_life))
+    }
+
+    /// The sum of the scores of all offence types. A higher score means a worse reputation.
+    pub fn total_score(&self, half_life: Duration) -> f64 {
+        let now = get_instant_now();
+        self.scores.values().map(|score| score.value_at(now, half_life)).sum()
+    }
+
+    pub fn reset(&mut self) {
+        self.scores.clear();
+    }
+}
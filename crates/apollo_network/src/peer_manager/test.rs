@@ -1,27 +1,37 @@
 // TODO(shahak): Add tests for multiple connection ids
 
 use core::{panic, time};
-use std::pin::Pin;
+use std::collections::HashSet;
+use std::future::Future;
+use std::pin::{pin, Pin};
 use std::task::{Context, Poll};
 use std::time::Duration;
 
+use apollo_network_types::network_types::{PeerBanList, PeerBanListError};
 use assert_matches::assert_matches;
 use futures::future::poll_fn;
 use futures::{FutureExt, Stream, StreamExt};
+use libp2p::core::Endpoint;
 use libp2p::swarm::behaviour::ConnectionEstablished;
 use libp2p::swarm::{ConnectionId, NetworkBehaviour, ToSwarm};
-use libp2p::{Multiaddr, PeerId};
+use libp2p::{Multiaddr, PeerId, StreamProtocol};
+use starknet_api::crypto::utils::PublicKey;
+use starknet_api::hash::StarkHash;
 use tokio::time::sleep;
+use validator::Validate;
 use void::Void;
 
 use super::behaviour_impl::ToOtherBehaviourEvent;
 use crate::discovery::identify_impl::IdentifyToOtherBehaviourEvent;
 use crate::misconduct_score::MisconductScore;
-use crate::mixed_behaviour;
 use crate::mixed_behaviour::BridgedBehaviour;
 use crate::peer_manager::peer::Peer;
+use crate::peer_manager::permissioned::PermissionedConfig;
+use crate::peer_manager::reputation::Offence;
 use crate::peer_manager::{PeerManager, PeerManagerConfig, ReputationModifier};
+use crate::sqmr::behaviour::OutboundSessionReport;
 use crate::sqmr::OutboundSessionId;
+use crate::{mixed_behaviour, sqmr, staker_identity};
 
 impl Unpin for PeerManager {}
 
@@ -182,8 +192,11 @@ async fn peer_assignment_no_unblocked_peers() {
     const BLOCKED_UNTIL: Duration = Duration::from_secs(5);
     const TIMEOUT: Duration = Duration::from_secs(1);
     // Create a new peer manager
-    let config =
-        PeerManagerConfig { malicious_timeout_seconds: TIMEOUT, unstable_timeout_millis: TIMEOUT };
+    let config = PeerManagerConfig {
+        malicious_timeout_seconds: TIMEOUT,
+        unstable_timeout_millis: TIMEOUT,
+        ..Default::default()
+    };
     let mut peer_manager: PeerManager = PeerManager::new(config.clone());
 
     // Create a session
@@ -484,3 +497,461 @@ fn identify_on_unknown_peer_is_added_to_peer_manager() {
     assert!(res_peer_id.peer_id() == peer_id);
     assert!(res_peer_id.multiaddr() == address);
 }
+
+fn add_connected_peer(peer_manager: &mut PeerManager) -> PeerId {
+    let peer_id = PeerId::random();
+    let mut peer = Peer::new(peer_id, Multiaddr::empty());
+    peer.add_connection_id(ConnectionId::new_unchecked(0));
+    peer_manager.add_peer(peer);
+    peer_id
+}
+
+fn is_inbound_connection_allowed(peer_manager: &mut PeerManager, peer_id: PeerId) -> bool {
+    peer_manager
+        .handle_established_inbound_connection(
+            ConnectionId::new_unchecked(0),
+            peer_id,
+            &Multiaddr::empty(),
+            &Multiaddr::empty(),
+        )
+        .is_ok()
+}
+
+#[tokio::test(start_paused = true)]
+async fn reputation_scores_accumulate_per_offence_and_decay() {
+    let config = PeerManagerConfig::default();
+    let half_life = config.reputation_half_life_seconds;
+    let mut peer_manager: PeerManager = PeerManager::new(config.clone());
+    let peer_id = add_connected_peer(&mut peer_manager);
+
+    let misconduct = ReputationModifier::Misconduct { misconduct_score: MisconductScore::new(0.2) };
+    peer_manager.report_peer(peer_id, misconduct).unwrap();
+    peer_manager.report_peer(peer_id, misconduct).unwrap();
+    peer_manager.report_peer(peer_id, ReputationModifier::Unstable).unwrap();
+
+    let reputation = peer_manager.get_mut_peer(peer_id).unwrap().reputation().clone();
+    assert_eq!(reputation.score(Offence::Misconduct, half_life), 0.4);
+    assert_eq!(reputation.score(Offence::Unstable, half_life), config.unstable_score);
+
+    tokio::time::advance(half_life).await;
+    assert_eq!(reputation.score(Offence::Misconduct, half_life), 0.2);
+    let expected_total_score = (0.4 + config.unstable_score) / 2.0;
+    assert!((reputation.total_score(half_life) - expected_total_score).abs() < 1e-9);
+}
+
+#[test]
+fn peer_with_bad_reputation_is_assigned_only_if_no_other_peer_is_available() {
+    let config = PeerManagerConfig::default();
+    let mut peer_manager: PeerManager = PeerManager::new(config.clone());
+    let bad_peer_id = add_connected_peer(&mut peer_manager);
+    let good_peer_id = add_connected_peer(&mut peer_manager);
+
+    // The report is not severe enough to blacklist the peer.
+    peer_manager
+        .report_peer(
+            bad_peer_id,
+            ReputationModifier::Misconduct { misconduct_score: MisconductScore::new(0.9) },
+        )
+        .unwrap();
+
+    let outbound_session_id = OutboundSessionId { value: 1 };
+    for _ in 0..2 {
+        assert_eq!(peer_manager.assign_peer_to_session(outbound_session_id), Some(good_peer_id));
+    }
+
+    peer_manager.get_mut_peer(good_peer_id).unwrap().connection_ids_mut().clear();
+    assert_eq!(peer_manager.assign_peer_to_session(outbound_session_id), Some(bad_peer_id));
+}
+
+#[tokio::test(start_paused = true)]
+async fn severe_offender_is_banned_and_ban_survives_restart() {
+    let ban_list_dir = tempfile::tempdir().unwrap();
+    let ban_list_path = ban_list_dir.path().join("ban_list.json");
+    let config = PeerManagerConfig {
+        ban_score: 1.5,
+        ban_list_path: Some(ban_list_path.clone()),
+        ..Default::default()
+    };
+    let mut peer_manager: PeerManager = PeerManager::new(config.clone());
+    let peer_id = add_connected_peer(&mut peer_manager);
+
+    let malicious = ReputationModifier::Misconduct { misconduct_score: MisconductScore::MALICIOUS };
+    peer_manager.report_peer(peer_id, malicious).unwrap();
+    assert!(!peer_manager.banned_peers.is_banned(&peer_id));
+
+    // Wait until the peer is no longer blacklisted.
+    tokio::time::advance(config.malicious_timeout_seconds).await;
+    peer_manager.report_peer(peer_id, malicious).unwrap();
+    assert!(peer_manager.banned_peers.is_banned(&peer_id));
+    assert!(peer_manager.pending_events.iter().any(|event| matches!(
+        event,
+        ToSwarm::CloseConnection { peer_id: event_peer_id, .. } if *event_peer_id == peer_id
+    )));
+
+    // Unlike blacklisting, the ban doesn't expire.
+    tokio::time::advance(config.malicious_timeout_seconds).await;
+    assert!(!is_inbound_connection_allowed(&mut peer_manager, peer_id));
+    assert_eq!(peer_manager.assign_peer_to_session(OutboundSessionId { value: 1 }), None);
+
+    // Dropping the peer manager completes the pending writes of the ban list.
+    drop(peer_manager);
+    assert!(PeerBanList::read_from_file(&ban_list_path).unwrap().is_banned(&peer_id));
+    let mut restarted_peer_manager: PeerManager = PeerManager::new(config);
+    assert!(!is_inbound_connection_allowed(&mut restarted_peer_manager, peer_id));
+}
+
+#[tokio::test(start_paused = true)]
+async fn peer_is_unbanned_through_ban_list_client() {
+    let ban_list_dir = tempfile::tempdir().unwrap();
+    let ban_list_path = ban_list_dir.path().join("ban_list.json");
+    let config = PeerManagerConfig {
+        ban_score: 1.0,
+        ban_list_path: Some(ban_list_path.clone()),
+        ..Default::default()
+    };
+    let mut peer_manager: PeerManager = PeerManager::new(config.clone());
+    let peer_id = add_connected_peer(&mut peer_manager);
+
+    peer_manager
+        .report_peer(
+            peer_id,
+            ReputationModifier::Misconduct { misconduct_score: MisconductScore::MALICIOUS },
+        )
+        .unwrap();
+    tokio::time::advance(config.malicious_timeout_seconds).await;
+    let outbound_session_id = OutboundSessionId { value: 1 };
+    assert_eq!(peer_manager.assign_peer_to_session(outbound_session_id), None);
+
+    let ban_list_client = peer_manager.ban_list_client().unwrap();
+    assert!(ban_list_client_request(&mut peer_manager, ban_list_client.get()).is_banned(&peer_id));
+    peer_manager.pending_events.clear();
+    ban_list_client_request(&mut peer_manager, ban_list_client.unban(peer_id));
+
+    // The session that waited for a peer is assigned to the unbanned peer.
+    assert_matches!(
+        peer_manager.pending_events.as_slice(),
+        [ToSwarm::GenerateEvent(ToOtherBehaviourEvent::SessionAssigned {
+            peer_id: event_peer_id, ..
+        })] if *event_peer_id == peer_id
+    );
+    assert!(is_inbound_connection_allowed(&mut peer_manager, peer_id));
+    assert_eq!(
+        peer_manager
+            .get_mut_peer(peer_id)
+            .unwrap()
+            .reputation()
+            .total_score(config.reputation_half_life_seconds),
+        0.0
+    );
+
+    drop(peer_manager);
+    assert!(!PeerBanList::read_from_file(&ban_list_path).unwrap().is_banned(&peer_id));
+    assert_matches!(ban_list_client.unban(peer_id).await, Err(PeerBanListError::OwnerStopped));
+}
+
+#[test]
+fn unreadable_ban_list_is_replaced_by_an_empty_one() {
+    let ban_list_dir = tempfile::tempdir().unwrap();
+    let ban_list_path = ban_list_dir.path().join("ban_list.json");
+    std::fs::write(&ban_list_path, "not a ban list").unwrap();
+    let config = PeerManagerConfig { ban_list_path: Some(ban_list_path), ..Default::default() };
+
+    let peer_manager: PeerManager = PeerManager::new(config);
+    assert_eq!(peer_manager.banned_peers, PeerBanList::default());
+    assert!(peer_manager.ban_list_client().is_some());
+}
+
+/// Sends a request through the ban list client while letting the peer manager handle it, without
+/// consuming the events the peer manager emits.
+fn ban_list_client_request<Response>(
+    peer_manager: &mut PeerManager,
+    request: impl Future<Output = Result<Response, PeerBanListError>>,
+) -> Response {
+    let mut request = pin!(request);
+    let waker = futures::task::noop_waker();
+    let mut cx = Context::from_waker(&waker);
+    // Sends the request.
+    assert!(request.as_mut().poll(&mut cx).is_pending());
+    // Polling handles the request before emitting a pending event, which is put back in place.
+    if let Poll::Ready(event) = peer_manager.poll(&mut cx) {
+        peer_manager.pending_events.push(event);
+    }
+    let Poll::Ready(response) = request.poll(&mut cx) else {
+        panic!("The peer manager did not handle the ban list request.");
+    };
+    response.unwrap()
+}
+
+const PROTOCOL_NAME: StreamProtocol = StreamProtocol::new("/protocol");
+
+fn simulate_outbound_session_ended(
+    peer_manager: &mut PeerManager,
+    peer_id: PeerId,
+    outbound_session_id: OutboundSessionId,
+    succeeded: bool,
+    duration: Duration,
+    received_bytes: usize,
+) {
+    peer_manager.on_other_behaviour_event(&mixed_behaviour::ToOtherBehaviourEvent::Sqmr(
+        sqmr::ToOtherBehaviourEvent::OutboundSessionEnded {
+            outbound_session_id,
+            report: Some(OutboundSessionReport {
+                peer_id,
+                protocol_name: PROTOCOL_NAME,
+                succeeded,
+                time_to_first_response: Some(duration / 2),
+                duration,
+                received_bytes,
+            }),
+        },
+    ));
+}
+
+fn request_peer_assignment(
+    peer_manager: &mut PeerManager,
+    outbound_session_id: OutboundSessionId,
+) -> PeerId {
+    peer_manager.on_other_behaviour_event(&mixed_behaviour::ToOtherBehaviourEvent::Sqmr(
+        sqmr::ToOtherBehaviourEvent::RequestPeerAssignment {
+            outbound_session_id,
+            protocol_name: PROTOCOL_NAME,
+        },
+    ));
+    let Some(ToSwarm::GenerateEvent(ToOtherBehaviourEvent::SessionAssigned { peer_id, .. })) =
+        peer_manager.pending_events.pop()
+    else {
+        panic!("Expected the session to be assigned to a peer.");
+    };
+    peer_id
+}
+
+#[test]
+fn outbound_session_end_updates_peer_performance() {
+    let config = PeerManagerConfig::default();
+    let mut peer_manager: PeerManager = PeerManager::new(config.clone());
+    let peer_id = add_connected_peer(&mut peer_manager);
+    let outbound_session_id = OutboundSessionId { value: 1 };
+
+    simulate_outbound_session_ended(
+        &mut peer_manager,
+        peer_id,
+        outbound_session_id,
+        true,
+        Duration::from_secs(2),
+        1000,
+    );
+    let performance =
+        peer_manager.get_mut_peer(peer_id).unwrap().performance(&PROTOCOL_NAME).unwrap().clone();
+    assert_eq!(performance.latency_seconds(), Some(1.0));
+    assert_eq!(performance.throughput(), Some(500.0));
+    assert_eq!(performance.failure_rate(), 0.0);
+    assert_eq!(performance.score(), 250.0);
+
+    simulate_outbound_session_ended(
+        &mut peer_manager,
+        peer_id,
+        outbound_session_id,
+        false,
+        Duration::from_secs(2),
+        0,
+    );
+    let performance =
+        peer_manager.get_mut_peer(peer_id).unwrap().performance(&PROTOCOL_NAME).unwrap().clone();
+    // A failed session doesn't affect the throughput.
+    assert_eq!(performance.throughput(), Some(500.0));
+    assert_eq!(performance.failure_rate(), config.performance_smoothing_factor);
+}
+
+#[test]
+fn outbound_session_end_removes_session_even_without_report() {
+    let mut peer_manager: PeerManager = PeerManager::new(PeerManagerConfig::default());
+    let peer_id = add_connected_peer(&mut peer_manager);
+    let outbound_session_id = OutboundSessionId { value: 1 };
+    peer_manager.on_other_behaviour_event(&mixed_behaviour::ToOtherBehaviourEvent::Sqmr(
+        sqmr::ToOtherBehaviourEvent::RequestPeerAssignment {
+            outbound_session_id,
+            protocol_name: PROTOCOL_NAME,
+        },
+    ));
+    assert_eq!(peer_manager.session_to_protocol_map.len(), 1);
+
+    peer_manager.on_other_behaviour_event(&mixed_behaviour::ToOtherBehaviourEvent::Sqmr(
+        sqmr::ToOtherBehaviourEvent::OutboundSessionEnded { outbound_session_id, report: None },
+    ));
+    assert!(peer_manager.session_to_protocol_map.is_empty());
+    assert!(peer_manager.get_mut_peer(peer_id).unwrap().performance(&PROTOCOL_NAME).is_none());
+}
+
+#[test]
+fn performance_smoothing_factor_must_be_in_range() {
+    for performance_smoothing_factor in [0.2, 1.0] {
+        PeerManagerConfig { performance_smoothing_factor, ..Default::default() }
+            .validate()
+            .unwrap();
+    }
+    for performance_smoothing_factor in [0.0, -0.1, 1.1, f64::NAN] {
+        PeerManagerConfig { performance_smoothing_factor, ..Default::default() }
+            .validate()
+            .unwrap_err();
+    }
+}
+
+#[test]
+fn best_performing_peer_is_assigned_after_unmeasured_peers() {
+    let config = PeerManagerConfig { exploration_probability: 0.0, ..Default::default() };
+    let mut peer_manager: PeerManager = PeerManager::new(config);
+    let fast_peer_id = add_connected_peer(&mut peer_manager);
+    let slow_peer_id = add_connected_peer(&mut peer_manager);
+
+    // Both peers are unmeasured, so they're assigned in round-robin order.
+    let first_peer_id = request_peer_assignment(&mut peer_manager, OutboundSessionId { value: 1 });
+    let second_peer_id = request_peer_assignment(&mut peer_manager, OutboundSessionId { value: 2 });
+    assert_ne!(first_peer_id, second_peer_id);
+
+    let duration = Duration::from_secs(1);
+    simulate_outbound_session_ended(
+        &mut peer_manager,
+        fast_peer_id,
+        OutboundSessionId { value: 1 },
+        true,
+        duration,
+        1000,
+    );
+    simulate_outbound_session_ended(
+        &mut peer_manager,
+        slow_peer_id,
+        OutboundSessionId { value: 2 },
+        true,
+        duration,
+        10,
+    );
+    for value in 3..6 {
+        assert_eq!(
+            request_peer_assignment(&mut peer_manager, OutboundSessionId { value }),
+            fast_peer_id
+        );
+    }
+
+    let new_peer_id = add_connected_peer(&mut peer_manager);
+    assert_eq!(
+        request_peer_assignment(&mut peer_manager, OutboundSessionId { value: 6 }),
+        new_peer_id
+    );
+}
+
+#[test]
+fn exploration_ignores_peer_performance() {
+    let config = PeerManagerConfig { exploration_probability: 1.0, ..Default::default() };
+    let mut peer_manager: PeerManager = PeerManager::new(config);
+    let fast_peer_id = add_connected_peer(&mut peer_manager);
+    let slow_peer_id = add_connected_peer(&mut peer_manager);
+    for (peer_id, received_bytes) in [(fast_peer_id, 1000), (slow_peer_id, 10)] {
+        simulate_outbound_session_ended(
+            &mut peer_manager,
+            peer_id,
+            OutboundSessionId::default(),
+            true,
+            Duration::from_secs(1),
+            received_bytes,
+        );
+    }
+
+    let assigned_peer_ids = (1..3)
+        .map(|value| request_peer_assignment(&mut peer_manager, OutboundSessionId { value }))
+        .collect::<HashSet<_>>();
+    assert_eq!(assigned_peer_ids, HashSet::from([fast_peer_id, slow_peer_id]));
+}
+
+fn is_outbound_dial_allowed(peer_manager: &mut PeerManager, peer_id: PeerId) -> bool {
+    peer_manager
+        .handle_pending_outbound_connection(
+            ConnectionId::new_unchecked(0),
+            Some(peer_id),
+            &[],
+            Endpoint::Dialer,
+        )
+        .is_ok()
+}
+
+#[test]
+fn permissioned_mode_denies_peers_that_are_not_allowed() {
+    let allowed_peer_id = PeerId::random();
+    let config = PeerManagerConfig {
+        permissioned_config: PermissionedConfig {
+            enabled: true,
+            allowed_peer_ids: vec![allowed_peer_id],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut peer_manager: PeerManager = PeerManager::new(config);
+    let other_peer_id = PeerId::random();
+
+    assert!(is_inbound_connection_allowed(&mut peer_manager, allowed_peer_id));
+    assert!(is_outbound_dial_allowed(&mut peer_manager, allowed_peer_id));
+    assert!(!is_inbound_connection_allowed(&mut peer_manager, other_peer_id));
+    assert!(!is_outbound_dial_allowed(&mut peer_manager, other_peer_id));
+}
+
+#[test]
+fn all_peers_are_permitted_when_permissioned_mode_is_disabled() {
+    let config = PeerManagerConfig {
+        permissioned_config: PermissionedConfig {
+            enabled: false,
+            allowed_peer_ids: vec![PeerId::random()],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut peer_manager: PeerManager = PeerManager::new(config);
+    let peer_id = PeerId::random();
+
+    assert!(is_inbound_connection_allowed(&mut peer_manager, peer_id));
+    assert!(is_outbound_dial_allowed(&mut peer_manager, peer_id));
+}
+
+#[tokio::test(start_paused = true)]
+async fn permissioned_mode_permits_peers_with_a_verified_staker_identity() {
+    let config = PeerManagerConfig {
+        permissioned_config: PermissionedConfig {
+            enabled: true,
+            staker_public_keys: vec![PublicKey(StarkHash::ONE)],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut peer_manager: PeerManager = PeerManager::new(config);
+    let verified_peer_id = PeerId::random();
+    let unverified_peer_id = PeerId::random();
+
+    // Peers may connect until they're due to send their staker identity.
+    assert!(is_outbound_dial_allowed(&mut peer_manager, unverified_peer_id));
+    assert!(is_inbound_connection_allowed(&mut peer_manager, verified_peer_id));
+    assert!(is_inbound_connection_allowed(&mut peer_manager, unverified_peer_id));
+    peer_manager.on_other_behaviour_event(&mixed_behaviour::ToOtherBehaviourEvent::StakerIdentity(
+        staker_identity::ToOtherBehaviourEvent::PeerVerified { peer_id: verified_peer_id },
+    ));
+
+    // The other behaviours ignore the peers that connected until they're verified.
+    for expected_peer_id in [unverified_peer_id, verified_peer_id] {
+        assert_matches!(
+            peer_manager.next().await.unwrap(),
+            ToSwarm::GenerateEvent(ToOtherBehaviourEvent::PeerUnverified { peer_id })
+            if peer_id == expected_peer_id
+        );
+    }
+
+    // Only the peer that didn't send its identity is disconnected and removed from discovery.
+    assert_matches!(
+        peer_manager.next().await.unwrap(),
+        ToSwarm::CloseConnection { peer_id, .. } if peer_id == unverified_peer_id
+    );
+    assert_matches!(
+        peer_manager.next().await.unwrap(),
+        ToSwarm::GenerateEvent(ToOtherBehaviourEvent::PeerNotPermitted { peer_id })
+        if peer_id == unverified_peer_id
+    );
+    assert!(peer_manager.next().now_or_never().is_none());
+    assert!(peer_manager.is_permitted(&verified_peer_id));
+    assert!(!peer_manager.is_permitted(&unverified_peer_id));
+}
@@ -21,6 +21,7 @@ use libp2p::swarm::{
     ToSwarm,
 };
 use libp2p::{Multiaddr, PeerId, StreamProtocol};
+use tokio::time::Instant;
 use tracing::{debug, error};
 
 use super::handler::{
@@ -29,9 +30,9 @@ use super::handler::{
     RequestToBehaviourEvent,
     SessionError as HandlerSessionError,
 };
-use super::{Config, GenericEvent, InboundSessionId, OutboundSessionId, SessionId};
+use super::{Config, GenericEvent, InboundSessionId, OutboundQuery, OutboundSessionId, SessionId};
 use crate::mixed_behaviour::{self, BridgedBehaviour};
-use crate::{peer_manager, Bytes};
+use crate::{peer_manager, staker_identity, Bytes};
 
 #[derive(thiserror::Error, Debug)]
 pub enum SessionError {
@@ -58,9 +59,12 @@ impl From<GenericEvent<HandlerSessionError>> for GenericEvent<SessionError> {
                 peer_id,
                 protocol_name,
             } => Self::NewInboundSession { query, inbound_session_id, peer_id, protocol_name },
-            GenericEvent::ReceivedResponse { outbound_session_id, response, peer_id } => {
-                Self::ReceivedResponse { outbound_session_id, response, peer_id }
-            }
+            GenericEvent::ReceivedResponse {
+                outbound_session_id,
+                response,
+                peer_id,
+                protocol_name,
+            } => Self::ReceivedResponse { outbound_session_id, response, peer_id, protocol_name },
             GenericEvent::SessionFailed {
                 session_id,
                 error: HandlerSessionError::Timeout { session_timeout },
@@ -88,7 +92,52 @@ pub type ExternalEvent = GenericEvent<SessionError>;
 
 #[derive(Debug)]
 pub enum ToOtherBehaviourEvent {
-    RequestPeerAssignment { outbound_session_id: OutboundSessionId },
+    RequestPeerAssignment {
+        outbound_session_id: OutboundSessionId,
+        protocol_name: StreamProtocol,
+    },
+    OutboundSessionEnded {
+        outbound_session_id: OutboundSessionId,
+        // None if the session was dropped by us.
+        report: Option<OutboundSessionReport>,
+    },
+}
+
+/// How an outbound session performed, reported when the session ends so that the peer manager can
+/// prefer the best performing peers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutboundSessionReport {
+    pub peer_id: PeerId,
+    pub protocol_name: StreamProtocol,
+    pub succeeded: bool,
+    /// The duration from the peer assignment until the first response, if one was received.
+    pub time_to_first_response: Option<Duration>,
+    /// The duration from the peer assignment until the session ended.
+    pub duration: Duration,
+    pub received_bytes: usize,
+}
+
+struct OutboundSessionStats {
+    peer_id: PeerId,
+    protocol_name: StreamProtocol,
+    assigned_at: Instant,
+    first_response_at: Option<Instant>,
+    received_bytes: usize,
+}
+
+impl OutboundSessionStats {
+    fn into_report(self, succeeded: bool) -> OutboundSessionReport {
+        OutboundSessionReport {
+            peer_id: self.peer_id,
+            protocol_name: self.protocol_name,
+            succeeded,
+            time_to_first_response: self
+                .first_response_at
+                .map(|first_response_at| first_response_at - self.assigned_at),
+            duration: self.assigned_at.elapsed(),
+            received_bytes: self.received_bytes,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -113,8 +162,12 @@ pub struct Behaviour {
     next_inbound_session_id: Arc<AtomicUsize>,
     dropped_sessions: HashSet<SessionId>,
     wakers_waiting_for_event: Vec<Waker>,
-    outbound_sessions_pending_peer_assignment: HashMap<OutboundSessionId, (Bytes, StreamProtocol)>,
+    outbound_sessions_pending_peer_assignment: HashMap<OutboundSessionId, OutboundQuery>,
     supported_inbound_protocols: HashSet<StreamProtocol>,
+    outbound_session_stats: HashMap<OutboundSessionId, OutboundSessionStats>,
+    // Peers whose staker identity isn't verified yet. Their inbound sessions are dropped, and the
+    // peer manager doesn't assign them outbound sessions.
+    unverified_peers: HashSet<PeerId>,
 }
 
 impl Behaviour {
@@ -129,26 +182,32 @@ impl Behaviour {
             wakers_waiting_for_event: Default::default(),
             outbound_sessions_pending_peer_assignment: Default::default(),
             supported_inbound_protocols: Default::default(),
+            outbound_session_stats: Default::default(),
+            unverified_peers: Default::default(),
         }
     }
 
     /// Assign some peer and start a query. Return the id of the new session.
-    pub fn start_query(
-        &mut self,
-        query: Bytes,
-        protocol_name: StreamProtocol,
-    ) -> OutboundSessionId {
+    ///
+    /// The peer is assigned according to the most preferred protocol of the query.
+    ///
+    /// Panics if the query doesn't offer any protocol.
+    pub fn start_query(&mut self, query: OutboundQuery) -> OutboundSessionId {
         let outbound_session_id = self.next_outbound_session_id;
         self.next_outbound_session_id.value += 1;
 
-        self.outbound_sessions_pending_peer_assignment
-            .insert(outbound_session_id, (query, protocol_name));
+        let protocol_name = query
+            .protocols
+            .first()
+            .expect("An outbound query must offer at least one protocol.")
+            .clone();
+        self.outbound_sessions_pending_peer_assignment.insert(outbound_session_id, query);
         debug!(
             "Network received new outbound query. Requesting peer assignment for {:?}.",
             outbound_session_id
         );
         self.add_event_to_queue(ToSwarm::GenerateEvent(Event::ToOtherBehaviourEvent(
-            ToOtherBehaviourEvent::RequestPeerAssignment { outbound_session_id },
+            ToOtherBehaviourEvent::RequestPeerAssignment { outbound_session_id, protocol_name },
         )));
 
         outbound_session_id
@@ -217,6 +276,22 @@ impl Behaviour {
             waker.wake();
         }
     }
+    /// Reports an outbound session that ended to the other behaviours, along with its performance.
+    /// The performance of dropped sessions isn't reported, since they were ended by us.
+    fn report_outbound_session_ended(&mut self, session_id: SessionId, succeeded: bool) {
+        let SessionId::OutboundSessionId(outbound_session_id) = session_id else {
+            return;
+        };
+        let report = self
+            .outbound_session_stats
+            .remove(&outbound_session_id)
+            .filter(|_| !self.dropped_sessions.contains(&session_id))
+            .map(|stats| stats.into_report(succeeded));
+        self.add_event_to_queue(ToSwarm::GenerateEvent(Event::ToOtherBehaviourEvent(
+            ToOtherBehaviourEvent::OutboundSessionEnded { outbound_session_id, report },
+        )));
+    }
+
     pub fn add_new_supported_inbound_protocol(&mut self, protocol: StreamProtocol) {
         if !self.supported_inbound_protocols.contains(&protocol) {
             self.supported_inbound_protocols.insert(protocol);
@@ -259,10 +334,18 @@ impl NetworkBehaviour for Behaviour {
     }
 
     fn on_swarm_event(&mut self, event: FromSwarm<'_>) {
-        let FromSwarm::ConnectionClosed(ConnectionClosed { peer_id, connection_id, .. }) = event
+        let FromSwarm::ConnectionClosed(ConnectionClosed {
+            peer_id,
+            connection_id,
+            remaining_established,
+            ..
+        }) = event
         else {
             return;
         };
+        if remaining_established == 0 {
+            self.unverified_peers.remove(&peer_id);
+        }
         let mut session_ids = Vec::new();
         self.session_id_to_peer_id_and_connection_id.retain(
             |session_id, (session_peer_id, session_connection_id)| {
@@ -278,6 +361,7 @@ impl NetworkBehaviour for Behaviour {
             self.add_event_to_queue(ToSwarm::GenerateEvent(Event::External(
                 ExternalEvent::SessionFailed { session_id, error: SessionError::ConnectionClosed },
             )));
+            self.report_outbound_session_ended(session_id, false);
         }
     }
 
@@ -291,23 +375,44 @@ impl NetworkBehaviour for Behaviour {
             RequestToBehaviourEvent::GenerateEvent(event) => {
                 let converted_event = event.into();
                 let mut is_event_muted = false;
+                let mut ended_session = None;
                 match converted_event {
                     ExternalEvent::NewInboundSession { inbound_session_id, .. } => {
                         self.session_id_to_peer_id_and_connection_id
                             .insert(inbound_session_id.into(), (peer_id, connection_id));
+                        if self.unverified_peers.contains(&peer_id) {
+                            debug!(
+                                "Dropping inbound session {inbound_session_id:?} of peer                                  {peer_id:?} since its staker identity isn't verified."
+                            );
+                            is_event_muted = true;
+                            self.drop_session(inbound_session_id.into())
+                                .expect("The inbound session was just inserted.");
+                        }
                     }
                     ExternalEvent::SessionFailed { session_id, .. }
                     | ExternalEvent::SessionFinishedSuccessfully { session_id, .. } => {
+                        let succeeded = matches!(
+                            converted_event,
+                            ExternalEvent::SessionFinishedSuccessfully { .. }
+                        );
+                        ended_session = Some((session_id, succeeded));
                         self.session_id_to_peer_id_and_connection_id.remove(&session_id);
-                        let is_dropped = self.dropped_sessions.remove(&session_id);
-                        if is_dropped {
+                        if self.dropped_sessions.contains(&session_id) {
                             is_event_muted = true;
                         }
                     }
-                    ExternalEvent::ReceivedResponse { outbound_session_id, .. } => {
+                    ExternalEvent::ReceivedResponse {
+                        outbound_session_id, ref response, ..
+                    } => {
                         if self.dropped_sessions.contains(&outbound_session_id.into()) {
                             is_event_muted = true;
                         }
+                        if let Some(stats) =
+                            self.outbound_session_stats.get_mut(&outbound_session_id)
+                        {
+                            stats.first_response_at.get_or_insert_with(Instant::now);
+                            stats.received_bytes += response.len();
+                        }
                     }
                 }
                 if !is_event_muted {
@@ -315,6 +420,10 @@ impl NetworkBehaviour for Behaviour {
                         converted_event,
                     )));
                 }
+                if let Some((session_id, succeeded)) = ended_session {
+                    self.report_outbound_session_ended(session_id, succeeded);
+                    self.dropped_sessions.remove(&session_id);
+                }
             }
             RequestToBehaviourEvent::NotifySessionDropped { session_id } => {
                 self.dropped_sessions.remove(&session_id);
@@ -337,15 +446,27 @@ impl NetworkBehaviour for Behaviour {
 
 impl BridgedBehaviour for Behaviour {
     fn on_other_behaviour_event(&mut self, event: &mixed_behaviour::ToOtherBehaviourEvent) {
-        let mixed_behaviour::ToOtherBehaviourEvent::PeerManager(
-            peer_manager::ToOtherBehaviourEvent::SessionAssigned {
-                outbound_session_id,
-                peer_id,
-                connection_id,
-            },
-        ) = event
-        else {
-            return;
+        let (outbound_session_id, peer_id, connection_id) = match event {
+            mixed_behaviour::ToOtherBehaviourEvent::PeerManager(
+                peer_manager::ToOtherBehaviourEvent::SessionAssigned {
+                    outbound_session_id,
+                    peer_id,
+                    connection_id,
+                },
+            ) => (outbound_session_id, peer_id, connection_id),
+            mixed_behaviour::ToOtherBehaviourEvent::PeerManager(
+                peer_manager::ToOtherBehaviourEvent::PeerUnverified { peer_id },
+            ) => {
+                self.unverified_peers.insert(*peer_id);
+                return;
+            }
+            mixed_behaviour::ToOtherBehaviourEvent::StakerIdentity(
+                staker_identity::ToOtherBehaviourEvent::PeerVerified { peer_id },
+            ) => {
+                self.unverified_peers.remove(peer_id);
+                return;
+            }
+            _ => return,
         };
         debug!(
             "Assigned peer {:?} to session {:?} with connection id: {:?}",
@@ -354,7 +475,7 @@ impl BridgedBehaviour for Behaviour {
         self.session_id_to_peer_id_and_connection_id
             .insert((*outbound_session_id).into(), (*peer_id, *connection_id));
 
-        let Some((query, protocol_name)) =
+        let Some(query) =
             self.outbound_sessions_pending_peer_assignment.remove(outbound_session_id)
         else {
             error!(
@@ -364,13 +485,26 @@ impl BridgedBehaviour for Behaviour {
             return;
         };
 
+        // Performance is tracked by the most preferred protocol, which is the one the peer was
+        // assigned by.
+        let protocol_name =
+            query.protocols.first().expect("An outbound query must offer at least one protocol.");
+        self.outbound_session_stats.insert(
+            *outbound_session_id,
+            OutboundSessionStats {
+                peer_id: *peer_id,
+                protocol_name: protocol_name.clone(),
+                assigned_at: Instant::now(),
+                first_response_at: None,
+                received_bytes: 0,
+            },
+        );
         self.add_event_to_queue(ToSwarm::NotifyHandler {
             peer_id: *peer_id,
             handler: NotifyHandler::One(*connection_id),
             event: RequestFromBehaviourEvent::CreateOutboundSession {
                 query,
                 outbound_session_id: *outbound_session_id,
-                protocol_name,
             },
         });
     }
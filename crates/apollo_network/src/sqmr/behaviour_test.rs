@@ -9,11 +9,18 @@ use libp2p::swarm::{ConnectionClosed, ConnectionId, FromSwarm, NetworkBehaviour,
 use libp2p::{Multiaddr, PeerId, StreamProtocol};
 
 use super::super::handler::{RequestFromBehaviourEvent, RequestToBehaviourEvent};
-use super::super::{Config, GenericEvent, InboundSessionId, OutboundSessionId, SessionId};
+use super::super::{
+    Config,
+    GenericEvent,
+    InboundSessionId,
+    OutboundQuery,
+    OutboundSessionId,
+    SessionId,
+};
 use super::{Behaviour, Event, ExternalEvent, SessionError, ToOtherBehaviourEvent};
 use crate::mixed_behaviour::BridgedBehaviour;
 use crate::test_utils::dummy_data;
-use crate::{mixed_behaviour, peer_manager, Bytes};
+use crate::{mixed_behaviour, peer_manager, staker_identity, Bytes};
 
 impl Unpin for Behaviour {}
 
@@ -81,6 +88,7 @@ fn simulate_received_response(
             response,
             outbound_session_id,
             peer_id,
+            protocol_name: PROTOCOL_NAME.clone(),
         }),
     );
 }
@@ -132,9 +140,34 @@ async fn validate_request_peer_assignment_event(
     assert_matches!(
         event,
         ToSwarm::GenerateEvent(Event::ToOtherBehaviourEvent(ToOtherBehaviourEvent::RequestPeerAssignment {
-                outbound_session_id: event_outbound_session_id
+                outbound_session_id: event_outbound_session_id,
+                protocol_name,
+            },
+        )) if outbound_session_id == event_outbound_session_id
+            && protocol_name == PROTOCOL_NAME.clone()
+    );
+}
+
+async fn validate_outbound_session_ended_event(
+    behaviour: &mut Behaviour,
+    outbound_session_id: OutboundSessionId,
+    peer_id: PeerId,
+    succeeded: bool,
+    received_bytes: usize,
+) {
+    let event = behaviour.next().await.unwrap();
+    assert_matches!(
+        event,
+        ToSwarm::GenerateEvent(Event::ToOtherBehaviourEvent(ToOtherBehaviourEvent::OutboundSessionEnded {
+                outbound_session_id: event_outbound_session_id,
+                report: Some(report),
             },
         )) if outbound_session_id == event_outbound_session_id
+            && report.peer_id == peer_id
+            && report.protocol_name == PROTOCOL_NAME.clone()
+            && report.succeeded == succeeded
+            && report.received_bytes == received_bytes
+            && report.time_to_first_response.is_some() == (received_bytes > 0)
     );
 }
 
@@ -149,12 +182,12 @@ async fn validate_create_outbound_session_event(
         event,
         ToSwarm::NotifyHandler {
             peer_id: event_peer_id,
-            event: RequestFromBehaviourEvent::CreateOutboundSession { query: event_query, outbound_session_id: event_outbound_session_id, protocol_name },
+            event: RequestFromBehaviourEvent::CreateOutboundSession { query: event_query, outbound_session_id: event_outbound_session_id },
             ..
         } if *peer_id == event_peer_id
             && *outbound_session_id == event_outbound_session_id
-            && *query == event_query
-            && protocol_name == PROTOCOL_NAME.clone()
+            && event_query.protocols == vec![PROTOCOL_NAME.clone()]
+            && event_query.encode(&PROTOCOL_NAME).as_ref() == Some(query)
     );
 }
 
@@ -190,8 +223,9 @@ async fn validate_received_response_event(
         event,
         ToSwarm::GenerateEvent(Event::External(ExternalEvent::ReceivedResponse {
             response: event_response, outbound_session_id: event_outbound_session_id,
-            peer_id: event_peer_id,
+            peer_id: event_peer_id, protocol_name,
         })) if event_response == *response && event_outbound_session_id == outbound_session_id && peer_id == event_peer_id
+            && protocol_name == PROTOCOL_NAME.clone()
     );
 }
 
@@ -315,7 +349,8 @@ async fn create_and_process_outbound_session() {
 
     let peer_id = PeerId::random();
 
-    let outbound_session_id = behaviour.start_query(QUERY.clone(), PROTOCOL_NAME.clone());
+    let outbound_session_id = behaviour
+        .start_query(OutboundQuery::from_encoded(vec![(PROTOCOL_NAME.clone(), QUERY.clone())]));
 
     validate_request_peer_assignment_event(&mut behaviour, outbound_session_id).await;
     validate_no_events(&mut behaviour);
@@ -339,6 +374,15 @@ async fn create_and_process_outbound_session() {
     let session_id = outbound_session_id.into();
     simulate_session_finished_successfully(&mut behaviour, peer_id, session_id);
     validate_session_finished_successfully_event(&mut behaviour, session_id).await;
+    let received_bytes = dummy_data_vec.iter().map(|response| response.len()).sum();
+    validate_outbound_session_ended_event(
+        &mut behaviour,
+        outbound_session_id,
+        peer_id,
+        true,
+        received_bytes,
+    )
+    .await;
     validate_no_events(&mut behaviour);
 }
 
@@ -350,7 +394,8 @@ async fn connection_closed() {
     let peer_id = PeerId::random();
 
     // Add an outbound session on the connection.
-    let outbound_session_id = behaviour.start_query(QUERY.clone(), PROTOCOL_NAME.clone());
+    let outbound_session_id = behaviour
+        .start_query(OutboundQuery::from_encoded(vec![(PROTOCOL_NAME.clone(), QUERY.clone())]));
     // Consume the event to request peer assignment.
     behaviour.next().await.unwrap();
     simulate_peer_assigned(&mut behaviour, peer_id, outbound_session_id);
@@ -365,9 +410,24 @@ async fn connection_closed() {
 
     simulate_connection_closed(&mut behaviour, peer_id);
 
-    let event1 = behaviour.next().await.unwrap();
-    let event2 = behaviour.next().await.unwrap();
-    let failed_session_ids = [event1, event2]
+    let mut events = Vec::new();
+    for _ in 0..3 {
+        let event = behaviour.next().await.unwrap();
+        // The failure of the outbound session is also reported to the peer manager.
+        if let ToSwarm::GenerateEvent(Event::ToOtherBehaviourEvent(
+            ToOtherBehaviourEvent::OutboundSessionEnded {
+                outbound_session_id: event_outbound_session_id,
+                report: Some(report),
+            },
+        )) = &event
+        {
+            assert_eq!(*event_outbound_session_id, outbound_session_id);
+            assert!(!report.succeeded);
+            continue;
+        }
+        events.push(event);
+    }
+    let failed_session_ids = events
         .iter()
         .map(|event| {
             let ToSwarm::GenerateEvent(Event::External(ExternalEvent::SessionFailed {
@@ -396,7 +456,8 @@ async fn drop_outbound_session() {
 
     let peer_id = PeerId::random();
 
-    let outbound_session_id = behaviour.start_query(QUERY.clone(), PROTOCOL_NAME.clone());
+    let outbound_session_id = behaviour
+        .start_query(OutboundQuery::from_encoded(vec![(PROTOCOL_NAME.clone(), QUERY.clone())]));
     // Consume the event to request peer assignment.
     behaviour.next().await.unwrap();
     simulate_peer_assigned(&mut behaviour, peer_id, outbound_session_id);
@@ -414,6 +475,17 @@ async fn drop_outbound_session() {
 
     simulate_session_finished_successfully(&mut behaviour, peer_id, outbound_session_id.into());
 
+    // The other behaviours are told that the session ended, but not how it performed.
+    let event = behaviour.next().await.unwrap();
+    assert_matches!(
+        event,
+        ToSwarm::GenerateEvent(Event::ToOtherBehaviourEvent(
+            ToOtherBehaviourEvent::OutboundSessionEnded {
+                outbound_session_id: event_outbound_session_id,
+                report: None,
+            },
+        )) if event_outbound_session_id == outbound_session_id
+    );
     validate_no_events(&mut behaviour);
 
     simulate_session_dropped(&mut behaviour, peer_id, outbound_session_id.into());
@@ -460,3 +532,27 @@ fn send_response_non_existing_session_fails() {
         behaviour.send_response(response, InboundSessionId::default()).unwrap_err();
     }
 }
+
+#[tokio::test]
+async fn inbound_session_of_unverified_peer_is_dropped() {
+    let mut behaviour = Behaviour::new(Config::get_test_config());
+
+    let peer_id = PeerId::random();
+    behaviour.on_other_behaviour_event(&mixed_behaviour::ToOtherBehaviourEvent::PeerManager(
+        peer_manager::ToOtherBehaviourEvent::PeerUnverified { peer_id },
+    ));
+
+    let inbound_session_id = InboundSessionId { value: 0 };
+    simulate_new_inbound_session(&mut behaviour, peer_id, inbound_session_id, QUERY.clone());
+    validate_request_drop_session_event(&mut behaviour, &peer_id, inbound_session_id.into()).await;
+    validate_no_events(&mut behaviour);
+
+    behaviour.on_other_behaviour_event(&mixed_behaviour::ToOtherBehaviourEvent::StakerIdentity(
+        staker_identity::ToOtherBehaviourEvent::PeerVerified { peer_id },
+    ));
+
+    let inbound_session_id = InboundSessionId { value: 1 };
+    simulate_new_inbound_session(&mut behaviour, peer_id, inbound_session_id, QUERY.clone());
+    validate_new_inbound_session_event(&mut behaviour, &peer_id, inbound_session_id, &QUERY).await;
+    validate_no_events(&mut behaviour);
+}
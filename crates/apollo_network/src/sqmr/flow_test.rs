@@ -9,7 +9,7 @@ use libp2p::swarm::{ConnectionId, NetworkBehaviour, SwarmEvent};
 use libp2p::{PeerId, StreamProtocol, Swarm};
 
 use super::behaviour::{Behaviour, Event, ExternalEvent, ToOtherBehaviourEvent};
-use super::{Config, InboundSessionId, OutboundSessionId, SessionId};
+use super::{Config, InboundSessionId, OutboundQuery, OutboundSessionId, SessionId};
 use crate::mixed_behaviour::BridgedBehaviour;
 use crate::test_utils::create_fully_connected_swarms_stream;
 use crate::utils::StreamMap;
@@ -20,6 +20,9 @@ const NUM_MESSAGES_PER_SESSION: usize = 5;
 
 pub const PROTOCOL_NAME: StreamProtocol = StreamProtocol::new("/example");
 pub const OTHER_PROTOCOL_NAME: StreamProtocol = StreamProtocol::new("/other");
+// A newer version of PROTOCOL_NAME that the peers prefer but don't support as servers, so they
+// should fall back to PROTOCOL_NAME.
+pub const UNSUPPORTED_PROTOCOL_NAME: StreamProtocol = StreamProtocol::new("/example/2");
 
 type SwarmEventAlias<BehaviourTrait> = SwarmEvent<<BehaviourTrait as NetworkBehaviour>::ToSwarm>;
 
@@ -67,10 +70,13 @@ fn start_query_and_update_map(
     outbound_session_id_to_peer_id: &mut HashMap<(PeerId, OutboundSessionId), PeerId>,
 ) {
     let outbound_peer_id = *outbound_swarm.local_peer_id();
-    let outbound_session_id = outbound_swarm.behaviour_mut().start_query(
-        get_bytes_from_query_indices(outbound_peer_id, inbound_peer_id),
-        PROTOCOL_NAME,
-    );
+    let query = get_bytes_from_query_indices(outbound_peer_id, inbound_peer_id);
+    let unsupported_query = query.iter().rev().copied().collect();
+    let outbound_session_id =
+        outbound_swarm.behaviour_mut().start_query(OutboundQuery::from_encoded(vec![
+            (UNSUPPORTED_PROTOCOL_NAME, unsupported_query),
+            (PROTOCOL_NAME, query),
+        ]));
     outbound_session_id_to_peer_id.insert((outbound_peer_id, outbound_session_id), inbound_peer_id);
 }
 
@@ -130,6 +136,7 @@ fn check_request_peer_assignment_event_and_return_session_id(
     };
     let Event::ToOtherBehaviourEvent(ToOtherBehaviourEvent::RequestPeerAssignment {
         outbound_session_id,
+        ..
     }) = event
     else {
         panic!("Got unexpected event {event:?} when expecting RequestPeerAssignment");
@@ -173,10 +180,12 @@ fn check_received_response_event(
         outbound_session_id: _outbound_session_id,
         response,
         peer_id: inbound_peer_id,
+        protocol_name,
     }) = event
     else {
         panic!("Got unexpected event {event:?} when expecting ReceivedResponse");
     };
+    assert_eq!(protocol_name, PROTOCOL_NAME);
     assert_eq!(
         outbound_session_id_to_peer_id[&(outbound_peer_id, _outbound_session_id)],
         inbound_peer_id
@@ -31,26 +31,15 @@ use tracing::debug;
 use self::inbound_session::InboundSession;
 use super::messages::read_message;
 use super::protocol::{InboundProtocol, OutboundProtocol};
-use super::{Config, GenericEvent, InboundSessionId, OutboundSessionId, SessionId};
+use super::{Config, GenericEvent, InboundSessionId, OutboundQuery, OutboundSessionId, SessionId};
 use crate::Bytes;
 
 #[derive(Debug)]
 pub enum RequestFromBehaviourEvent {
-    CreateOutboundSession {
-        query: Bytes,
-        outbound_session_id: OutboundSessionId,
-        protocol_name: StreamProtocol,
-    },
-    SendResponse {
-        response: Bytes,
-        inbound_session_id: InboundSessionId,
-    },
-    CloseInboundSession {
-        inbound_session_id: InboundSessionId,
-    },
-    DropSession {
-        session_id: SessionId,
-    },
+    CreateOutboundSession { query: OutboundQuery, outbound_session_id: OutboundSessionId },
+    SendResponse { response: Bytes, inbound_session_id: InboundSessionId },
+    CloseInboundSession { inbound_session_id: InboundSessionId },
+    DropSession { session_id: SessionId },
 }
 
 #[derive(Debug)]
@@ -81,8 +70,9 @@ pub struct Handler {
     next_inbound_session_id: Arc<AtomicUsize>,
     peer_id: PeerId,
     id_to_inbound_session: HashMap<InboundSessionId, InboundSession>,
+    // Each outbound session is stored with the protocol that was negotiated for it.
     id_to_outbound_session:
-        HashMap<OutboundSessionId, BoxStream<'static, Result<Bytes, io::Error>>>,
+        HashMap<OutboundSessionId, (StreamProtocol, BoxStream<'static, Result<Bytes, io::Error>>)>,
     // TODO(shahak): Use deadqueue if using a VecDeque is a bug (libp2p uses VecDeque, so we opened
     // an issue on it https://github.com/libp2p/rust-libp2p/issues/5147)
     pending_events: VecDeque<HandlerEvent<Self>>,
@@ -202,14 +192,17 @@ impl ConnectionHandler for Handler {
         });
 
         // Handle outbound sessions.
-        self.id_to_outbound_session.retain(|outbound_session_id, outbound_session| {
-            match outbound_session.poll_next_unpin(cx) {
+        self.id_to_outbound_session.retain(
+            |outbound_session_id, (protocol_name, outbound_session)| match outbound_session
+                .poll_next_unpin(cx)
+            {
                 Poll::Ready(Some(Ok(response))) => {
                     self.pending_events.push_back(ConnectionHandlerEvent::NotifyBehaviour(
                         RequestToBehaviourEvent::GenerateEvent(GenericEvent::ReceivedResponse {
                             outbound_session_id: *outbound_session_id,
                             response,
                             peer_id: self.peer_id,
+                            protocol_name: protocol_name.clone(),
                         }),
                     ));
                     true
@@ -234,8 +227,8 @@ impl ConnectionHandler for Handler {
                     false
                 }
                 Poll::Pending => true,
-            }
-        });
+            },
+        );
 
         // Handling pending_events at the end of the function to avoid starvation and to make sure
         // we don't return Pending if the code above created an event.
@@ -247,11 +240,7 @@ impl ConnectionHandler for Handler {
 
     fn on_behaviour_event(&mut self, event: Self::FromBehaviour) {
         match event {
-            RequestFromBehaviourEvent::CreateOutboundSession {
-                query,
-                outbound_session_id,
-                protocol_name,
-            } => {
+            RequestFromBehaviourEvent::CreateOutboundSession { query, outbound_session_id } => {
                 // TODO(shahak): Consider extracting to a utility function to prevent forgetfulness
                 // of the timeout.
 
@@ -259,7 +248,7 @@ impl ConnectionHandler for Handler {
                 // on_behaviour_event. See https://github.com/libp2p/rust-libp2p/issues/5147
                 self.pending_events.push_back(ConnectionHandlerEvent::OutboundSubstreamRequest {
                     protocol: SubstreamProtocol::new(
-                        OutboundProtocol { query, protocol_name },
+                        OutboundProtocol { query },
                         outbound_session_id,
                     )
                     .with_timeout(self.config.session_timeout),
@@ -332,7 +321,7 @@ impl ConnectionHandler for Handler {
     ) {
         match event {
             ConnectionEvent::FullyNegotiatedOutbound(FullyNegotiatedOutbound {
-                protocol: mut read_stream,
+                protocol: (mut read_stream, protocol_name),
                 info: outbound_session_id,
             }) => {
                 if self.dropped_outbound_sessions_non_negotiated.remove(&outbound_session_id) {
@@ -340,22 +329,25 @@ impl ConnectionHandler for Handler {
                 }
                 self.id_to_outbound_session.insert(
                     outbound_session_id,
-                    stream! {
-                        loop {
-                            let result_opt = read_message(&mut read_stream).await;
-                            let result = match result_opt {
-                                Ok(Some(response)) => Ok(response),
-                                Ok(None) => break,
-                                Err(error) => Err(error),
-                            };
-                            let is_err = result.is_err();
-                            yield result;
-                            if is_err {
-                                break;
+                    (
+                        protocol_name,
+                        stream! {
+                            loop {
+                                let result_opt = read_message(&mut read_stream).await;
+                                let result = match result_opt {
+                                    Ok(Some(response)) => Ok(response),
+                                    Ok(None) => break,
+                                    Err(error) => Err(error),
+                                };
+                                let is_err = result.is_err();
+                                yield result;
+                                if is_err {
+                                    break;
+                                }
                             }
                         }
-                    }
-                    .boxed(),
+                        .boxed(),
+                    ),
                 );
             }
             ConnectionEvent::FullyNegotiatedInbound(FullyNegotiatedInbound {
@@ -18,7 +18,14 @@ use libp2p::swarm::{ConnectionHandler, ConnectionHandlerEvent, Stream, StreamUpg
 use libp2p::{PeerId, StreamProtocol};
 
 use super::super::messages::{read_message, write_message};
-use super::super::{Config, GenericEvent, InboundSessionId, OutboundSessionId, SessionId};
+use super::super::{
+    Config,
+    GenericEvent,
+    InboundSessionId,
+    OutboundQuery,
+    OutboundSessionId,
+    SessionId,
+};
 use super::{
     Handler,
     HandlerEvent,
@@ -66,9 +73,8 @@ fn simulate_request_to_send_query_from_swarm(
     outbound_session_id: OutboundSessionId,
 ) {
     handler.on_behaviour_event(RequestFromBehaviourEvent::CreateOutboundSession {
-        query,
+        query: OutboundQuery::from_encoded(vec![(PROTOCOL_NAME.clone(), query)]),
         outbound_session_id,
-        protocol_name: PROTOCOL_NAME.clone(),
     });
 }
 
@@ -102,7 +108,10 @@ fn simulate_negotiated_outbound_session_from_swarm(
     outbound_session_id: OutboundSessionId,
 ) {
     handler.on_connection_event(ConnectionEvent::FullyNegotiatedOutbound(
-        FullyNegotiatedOutbound { protocol: outbound_stream.split().0, info: outbound_session_id },
+        FullyNegotiatedOutbound {
+            protocol: (outbound_stream.split().0, PROTOCOL_NAME.clone()),
+            info: outbound_session_id,
+        },
     ));
 }
 
@@ -152,11 +161,12 @@ async fn validate_received_response_event(
         ConnectionHandlerEvent::NotifyBehaviour(
             RequestToBehaviourEvent::GenerateEvent(
                 GenericEvent::ReceivedResponse {
-                    response: event_response, outbound_session_id: event_outbound_session_id, peer_id : event_peer_id
-
+                    response: event_response, outbound_session_id: event_outbound_session_id, peer_id : event_peer_id,
+                    protocol_name,
                 }
             )
         ) if event_response == *response &&  event_outbound_session_id == outbound_session_id && event_peer_id == handler.peer_id
+            && protocol_name == PROTOCOL_NAME.clone()
     );
 }
 
@@ -215,7 +225,9 @@ async fn validate_request_to_swarm_new_outbound_session_to_swarm_event(
     assert_matches!(
         event,
         ConnectionHandlerEvent::OutboundSubstreamRequest{ protocol }
-        if protocol.upgrade().query == *query && *protocol.info() == outbound_session_id
+        if protocol.upgrade().query.protocols == vec![PROTOCOL_NAME.clone()]
+            && protocol.upgrade().query.encode(&PROTOCOL_NAME).as_ref() == Some(query)
+            && *protocol.info() == outbound_session_id
     );
 }
 
@@ -1,11 +1,13 @@
 pub mod behaviour;
 pub mod handler;
-mod messages;
+pub(crate) mod messages;
 pub mod protocol;
 
 #[cfg(test)]
 mod flow_test;
 
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
 use std::time::Duration;
 
 pub use behaviour::{Behaviour, ToOtherBehaviourEvent};
@@ -43,6 +45,49 @@ impl From<InboundSessionId> for SessionId {
     }
 }
 
+type QueryEncoder = Arc<dyn Fn(&StreamProtocol) -> Option<Bytes> + Send + Sync>;
+
+/// A query offered to a peer in several versions of a protocol. The query is encoded only for the
+/// version that the peer chooses.
+#[derive(Clone)]
+pub struct OutboundQuery {
+    /// The offered protocols, from the most preferred to the least preferred.
+    pub protocols: Vec<StreamProtocol>,
+    encode: QueryEncoder,
+}
+
+impl OutboundQuery {
+    /// `encode` returns the query encoded for the given protocol, or None if the protocol isn't
+    /// offered.
+    pub fn new(
+        protocols: Vec<StreamProtocol>,
+        encode: impl Fn(&StreamProtocol) -> Option<Bytes> + Send + Sync + 'static,
+    ) -> Self {
+        Self { protocols, encode: Arc::new(encode) }
+    }
+
+    /// A query that was already encoded for each of the offered protocols.
+    pub fn from_encoded(queries: Vec<(StreamProtocol, Bytes)>) -> Self {
+        let protocols = queries.iter().map(|(protocol, _query)| protocol.clone()).collect();
+        Self::new(protocols, move |protocol| {
+            queries
+                .iter()
+                .find(|(offered_protocol, _query)| offered_protocol == protocol)
+                .map(|(_protocol, query)| query.clone())
+        })
+    }
+
+    pub fn encode(&self, protocol: &StreamProtocol) -> Option<Bytes> {
+        (self.encode)(protocol)
+    }
+}
+
+impl Debug for OutboundQuery {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OutboundQuery").field("protocols", &self.protocols).finish_non_exhaustive()
+    }
+}
+
 #[derive(Debug)]
 pub enum GenericEvent<SessionError> {
     NewInboundSession {
@@ -55,6 +100,8 @@ pub enum GenericEvent<SessionError> {
         outbound_session_id: OutboundSessionId,
         response: Bytes,
         peer_id: PeerId,
+        /// The protocol that was negotiated for the session.
+        protocol_name: StreamProtocol,
     },
     SessionFailed {
         session_id: SessionId,
@@ -2,7 +2,7 @@
 #[path = "protocol_test.rs"]
 mod protocol_test;
 
-use std::{io, iter};
+use std::io;
 
 use futures::future::BoxFuture;
 use futures::io::{ReadHalf, WriteHalf};
@@ -11,6 +11,7 @@ use libp2p::core::upgrade::{InboundUpgrade, OutboundUpgrade, UpgradeInfo};
 use libp2p::StreamProtocol;
 
 use super::messages::{read_message_without_length_prefix, write_message_without_length_prefix};
+use super::OutboundQuery;
 use crate::Bytes;
 
 pub struct InboundProtocol {
@@ -52,16 +53,15 @@ where
 
 #[derive(Debug)]
 pub struct OutboundProtocol {
-    pub query: Bytes,
-    pub protocol_name: StreamProtocol,
+    pub query: OutboundQuery,
 }
 
 impl UpgradeInfo for OutboundProtocol {
     type Info = StreamProtocol;
-    type InfoIter = iter::Once<Self::Info>;
+    type InfoIter = Vec<Self::Info>;
 
     fn protocol_info(&self) -> Self::InfoIter {
-        iter::once(self.protocol_name.clone())
+        self.query.protocols.clone()
     }
 }
 
@@ -69,15 +69,21 @@ impl<Stream> OutboundUpgrade<Stream> for OutboundProtocol
 where
     Stream: AsyncRead + AsyncWrite + Unpin + Send + 'static,
 {
-    type Output = ReadHalf<Stream>;
+    type Output = (ReadHalf<Stream>, StreamProtocol);
     type Error = io::Error;
     type Future = BoxFuture<'static, Result<Self::Output, Self::Error>>;
 
-    fn upgrade_outbound(self, stream: Stream, _: Self::Info) -> Self::Future {
+    fn upgrade_outbound(self, stream: Stream, protocol_name: Self::Info) -> Self::Future {
         async move {
+            let query = self.query.encode(&protocol_name).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Negotiated protocol {protocol_name} wasn't offered."),
+                )
+            })?;
             let (read_half, write_half) = stream.split();
-            write_message_without_length_prefix(&self.query, write_half).await?;
-            Ok(read_half)
+            write_message_without_length_prefix(&query, write_half).await?;
+            Ok((read_half, protocol_name))
         }
         .boxed()
     }
@@ -1,24 +1,32 @@
+use std::sync::{Arc, Mutex};
+
 use libp2p::core::upgrade::{InboundUpgrade, OutboundUpgrade};
 use libp2p::core::UpgradeInfo;
 use libp2p::StreamProtocol;
 use pretty_assertions::assert_eq;
 
 use super::super::messages::{read_message, write_message};
+use super::super::OutboundQuery;
 use super::{InboundProtocol, OutboundProtocol};
 use crate::test_utils::{dummy_data, get_connected_streams};
 
 pub const PROTOCOL_NAME: StreamProtocol = StreamProtocol::new("/example/1.0.0");
+pub const OTHER_VERSION_PROTOCOL_NAME: StreamProtocol = StreamProtocol::new("/example/2.0.0");
 
 #[test]
 fn outbound_protocol_info() {
-    let outbound_protocol =
-        OutboundProtocol { query: Default::default(), protocol_name: PROTOCOL_NAME };
-    assert_eq!(outbound_protocol.protocol_info().collect::<Vec<_>>(), vec![PROTOCOL_NAME]);
+    let outbound_protocol = OutboundProtocol {
+        query: OutboundQuery::from_encoded(vec![
+            (OTHER_VERSION_PROTOCOL_NAME, Default::default()),
+            (PROTOCOL_NAME, Default::default()),
+        ]),
+    };
+    assert_eq!(outbound_protocol.protocol_info(), vec![OTHER_VERSION_PROTOCOL_NAME, PROTOCOL_NAME]);
 }
 
 #[test]
 fn inbound_protocol_info() {
-    let protocol_names = vec![PROTOCOL_NAME, StreamProtocol::new("/example/2.0.0")];
+    let protocol_names = vec![PROTOCOL_NAME, OTHER_VERSION_PROTOCOL_NAME];
     let inbound_protocol = InboundProtocol::new(protocol_names.clone());
     assert_eq!(inbound_protocol.protocol_info(), protocol_names);
 }
@@ -28,7 +36,13 @@ async fn positive_flow() {
     let (inbound_stream, outbound_stream, _) = get_connected_streams().await;
 
     let query = vec![1u8, 2u8, 3u8];
-    let outbound_protocol = OutboundProtocol { query: query.clone(), protocol_name: PROTOCOL_NAME };
+    // The query of the negotiated protocol should be sent.
+    let outbound_protocol = OutboundProtocol {
+        query: OutboundQuery::from_encoded(vec![
+            (OTHER_VERSION_PROTOCOL_NAME, vec![4u8]),
+            (PROTOCOL_NAME, query.clone()),
+        ]),
+    };
     let inbound_protocol = InboundProtocol::new(vec![PROTOCOL_NAME]);
 
     tokio::join!(
@@ -42,8 +56,9 @@ async fn positive_flow() {
             }
         },
         async move {
-            let mut stream =
+            let (mut stream, protocol_name) =
                 outbound_protocol.upgrade_outbound(outbound_stream, PROTOCOL_NAME).await.unwrap();
+            assert_eq!(protocol_name, PROTOCOL_NAME);
             for expected_response in dummy_data() {
                 let response = read_message(&mut stream).await.unwrap().unwrap();
                 assert_eq!(response, expected_response);
@@ -52,10 +67,37 @@ async fn positive_flow() {
     );
 }
 
+#[tokio::test]
+async fn query_is_encoded_only_for_negotiated_protocol() {
+    let (inbound_stream, outbound_stream, _) = get_connected_streams().await;
+
+    let encoded_protocols = Arc::new(Mutex::new(Vec::new()));
+    let encoded_protocols_clone = encoded_protocols.clone();
+    let outbound_protocol = OutboundProtocol {
+        query: OutboundQuery::new(
+            vec![OTHER_VERSION_PROTOCOL_NAME, PROTOCOL_NAME],
+            move |protocol_name| {
+                encoded_protocols_clone.lock().unwrap().push(protocol_name.clone());
+                Some(vec![0u8])
+            },
+        ),
+    };
+    let inbound_protocol = InboundProtocol::new(vec![PROTOCOL_NAME]);
+
+    let (inbound_result, outbound_result) = tokio::join!(
+        inbound_protocol.upgrade_inbound(inbound_stream, PROTOCOL_NAME),
+        outbound_protocol.upgrade_outbound(outbound_stream, PROTOCOL_NAME),
+    );
+    inbound_result.unwrap();
+    outbound_result.unwrap();
+    assert_eq!(*encoded_protocols.lock().unwrap(), vec![PROTOCOL_NAME]);
+}
+
 #[tokio::test]
 async fn inbound_dropped() {
     let (inbound_stream, outbound_stream, _) = get_connected_streams().await;
-    let outbound_protocol = OutboundProtocol { query: vec![0u8], protocol_name: PROTOCOL_NAME };
+    let outbound_protocol =
+        OutboundProtocol { query: OutboundQuery::from_encoded(vec![(PROTOCOL_NAME, vec![0u8])]) };
 
     drop(inbound_stream);
 
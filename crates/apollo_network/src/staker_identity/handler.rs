@@ -0,0 +1,156 @@
+use std::collections::VecDeque;
+use std::io;
+use std::task::{Context, Poll};
+
+use futures::future::BoxFuture;
+use futures::{AsyncRead, AsyncWrite, AsyncWriteExt, FutureExt};
+use libp2p::core::upgrade::{InboundUpgrade, OutboundUpgrade, UpgradeInfo};
+use libp2p::swarm::handler::{
+    ConnectionEvent,
+    DialUpgradeError,
+    FullyNegotiatedInbound,
+    ListenUpgradeError,
+};
+use libp2p::swarm::{ConnectionHandler, ConnectionHandlerEvent, SubstreamProtocol};
+use libp2p::StreamProtocol;
+use tracing::debug;
+
+use super::{HANDSHAKE_TIMEOUT, PROTOCOL_NAME};
+use crate::sqmr::messages::{read_message, write_message};
+use crate::Bytes;
+
+/// Reads a handshake message that the remote peer sends.
+pub struct InboundProtocol;
+
+impl UpgradeInfo for InboundProtocol {
+    type Info = StreamProtocol;
+    type InfoIter = [Self::Info; 1];
+
+    fn protocol_info(&self) -> Self::InfoIter {
+        [PROTOCOL_NAME]
+    }
+}
+
+impl<Stream> InboundUpgrade<Stream> for InboundProtocol
+where
+    Stream: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    type Output = Bytes;
+    type Error = io::Error;
+    type Future = BoxFuture<'static, Result<Self::Output, Self::Error>>;
+
+    fn upgrade_inbound(self, mut stream: Stream, _protocol_name: Self::Info) -> Self::Future {
+        async move {
+            read_message(&mut stream).await?.ok_or_else(|| {
+                io::Error::new(io::ErrorKind::UnexpectedEof, "The peer didn't send a message.")
+            })
+        }
+        .boxed()
+    }
+}
+
+/// Sends a handshake message to the remote peer.
+#[derive(Debug)]
+pub struct OutboundProtocol {
+    pub message: Bytes,
+}
+
+impl UpgradeInfo for OutboundProtocol {
+    type Info = StreamProtocol;
+    type InfoIter = [Self::Info; 1];
+
+    fn protocol_info(&self) -> Self::InfoIter {
+        [PROTOCOL_NAME]
+    }
+}
+
+impl<Stream> OutboundUpgrade<Stream> for OutboundProtocol
+where
+    Stream: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    type Output = ();
+    type Error = io::Error;
+    type Future = BoxFuture<'static, Result<Self::Output, Self::Error>>;
+
+    fn upgrade_outbound(self, mut stream: Stream, _protocol_name: Self::Info) -> Self::Future {
+        async move {
+            write_message(&self.message, &mut stream).await?;
+            stream.close().await
+        }
+        .boxed()
+    }
+}
+
+/// Sends the handshake messages of the behaviour, each over its own substream, and passes the
+/// messages received from the remote peer to the behaviour.
+#[derive(Default)]
+pub struct Handler {
+    messages_to_send: VecDeque<Bytes>,
+    pending_events: VecDeque<
+        ConnectionHandlerEvent<OutboundProtocol, (), <Self as ConnectionHandler>::ToBehaviour>,
+    >,
+}
+
+impl ConnectionHandler for Handler {
+    // A message to send to the remote peer.
+    type FromBehaviour = Bytes;
+    // A message that the remote peer sent.
+    type ToBehaviour = Bytes;
+    type InboundProtocol = InboundProtocol;
+    type OutboundProtocol = OutboundProtocol;
+    type InboundOpenInfo = ();
+    type OutboundOpenInfo = ();
+
+    fn listen_protocol(&self) -> SubstreamProtocol<Self::InboundProtocol, Self::InboundOpenInfo> {
+        SubstreamProtocol::new(InboundProtocol, ()).with_timeout(HANDSHAKE_TIMEOUT)
+    }
+
+    fn poll(
+        &mut self,
+        _cx: &mut Context<'_>,
+    ) -> Poll<
+        ConnectionHandlerEvent<Self::OutboundProtocol, Self::OutboundOpenInfo, Self::ToBehaviour>,
+    > {
+        if let Some(message) = self.messages_to_send.pop_front() {
+            return Poll::Ready(ConnectionHandlerEvent::OutboundSubstreamRequest {
+                protocol: SubstreamProtocol::new(OutboundProtocol { message }, ())
+                    .with_timeout(HANDSHAKE_TIMEOUT),
+            });
+        }
+        match self.pending_events.pop_front() {
+            Some(event) => Poll::Ready(event),
+            None => Poll::Pending,
+        }
+    }
+
+    fn on_behaviour_event(&mut self, message: Self::FromBehaviour) {
+        self.messages_to_send.push_back(message);
+    }
+
+    fn on_connection_event(
+        &mut self,
+        event: ConnectionEvent<
+            '_,
+            Self::InboundProtocol,
+            Self::OutboundProtocol,
+            Self::InboundOpenInfo,
+            Self::OutboundOpenInfo,
+        >,
+    ) {
+        match event {
+            ConnectionEvent::FullyNegotiatedInbound(FullyNegotiatedInbound {
+                protocol: message,
+                ..
+            }) => {
+                self.pending_events.push_back(ConnectionHandlerEvent::NotifyBehaviour(message));
+            }
+            ConnectionEvent::DialUpgradeError(DialUpgradeError { error, .. }) => {
+                debug!("Failed sending a staker identity handshake message. Error: {error:?}");
+            }
+            ConnectionEvent::ListenUpgradeError(ListenUpgradeError { error, .. }) => {
+                debug!("Failed receiving a staker identity handshake message. Error: {error:?}");
+            }
+            _ => {}
+        }
+    }
+}
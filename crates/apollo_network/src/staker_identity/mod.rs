@@ -0,0 +1,313 @@
+pub(crate) mod handler;
+#[cfg(test)]
+mod staker_identity_test;
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use blake2s::blake2s_to_felt;
+use futures::future::BoxFuture;
+use futures::stream::FuturesUnordered;
+use futures::{FutureExt, StreamExt};
+use libp2p::core::Endpoint;
+use libp2p::swarm::behaviour::ConnectionEstablished;
+use libp2p::swarm::{
+    ConnectionClosed,
+    ConnectionDenied,
+    ConnectionId,
+    FromSwarm,
+    NetworkBehaviour,
+    NotifyHandler,
+    THandler,
+    THandlerInEvent,
+    THandlerOutEvent,
+    ToSwarm,
+};
+use libp2p::{Multiaddr, PeerId, StreamProtocol};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use starknet_api::core::Nonce;
+use starknet_api::crypto::utils::{PublicKey, RawSignature};
+use tracing::{debug, error, warn};
+
+use self::handler::Handler;
+use crate::{mixed_behaviour, Bytes};
+
+pub(crate) const PROTOCOL_NAME: StreamProtocol = StreamProtocol::new("/starknet/staker_identity/1");
+pub(crate) const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+const CHALLENGE_LENGTH: usize = 32;
+
+type Challenge = [u8; CHALLENGE_LENGTH];
+
+/// A peer id signed by a staker, which peers send each other in permissioned mode. The signature
+/// is over a nonce derived from the challenge of the verifying peer, so it's only valid for the
+/// connection it was sent over.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct StakerIdentity {
+    pub peer_id: PeerId,
+    pub signature: RawSignature,
+}
+
+/// The messages of the handshake. Each peer sends a random challenge over every connection, and
+/// answers the challenge of the remote peer with its identity.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+enum HandshakeMessage {
+    Challenge(Challenge),
+    Identity(StakerIdentity),
+}
+
+// Returns the nonce that a peer signs its identity with in answer to the given challenge of the
+// given verifier. The signature also covers the peer id of the signer, so it's bound to the
+// challenge and to both peer ids, and can't be replayed to another peer or over another
+// connection.
+fn identity_nonce(challenge: &[u8], verifier_peer_id: PeerId) -> Nonce {
+    let mut message = challenge.to_vec();
+    message.extend_from_slice(&verifier_peer_id.to_bytes());
+    Nonce(blake2s_to_felt(&message))
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("Failed to sign the staker identity: {0}")]
+pub struct StakerIdentityError(pub String);
+
+/// Signs and verifies the staker identities that peers exchange in permissioned mode, so that the
+/// network doesn't depend on how the stakers' keys are managed.
+#[async_trait]
+pub trait StakerIdentityManager: Send + Sync {
+    /// Signs the peer id of this node with the key of its staker.
+    async fn sign_identity(
+        &self,
+        peer_id: PeerId,
+        nonce: Nonce,
+    ) -> Result<RawSignature, StakerIdentityError>;
+
+    /// Returns whether the signature is a valid signature of the peer id by the given staker.
+    fn verify_identity(
+        &self,
+        peer_id: PeerId,
+        nonce: Nonce,
+        signature: &RawSignature,
+        public_key: PublicKey,
+    ) -> bool;
+}
+
+#[derive(Debug)]
+pub enum ToOtherBehaviourEvent {
+    PeerVerified { peer_id: PeerId },
+}
+
+struct Connection {
+    peer_id: PeerId,
+    // The challenge sent to the peer, which it must sign its identity with.
+    local_challenge: Challenge,
+    // The challenge the peer sent, which is answered once the manager is set.
+    remote_challenge: Option<Challenge>,
+}
+
+type SigningFuture = BoxFuture<'static, (ConnectionId, Result<RawSignature, StakerIdentityError>)>;
+
+/// Exchanges staker identities with every connected peer and reports the peers whose identity is
+/// signed by one of the given stakers. Identities are only signed and verified once a manager is
+/// set.
+pub struct Behaviour {
+    local_peer_id: PeerId,
+    staker_public_keys: Vec<PublicKey>,
+    manager: Option<Arc<dyn StakerIdentityManager>>,
+    signing_identities: FuturesUnordered<SigningFuture>,
+    connections: HashMap<ConnectionId, Connection>,
+    pending_events: VecDeque<ToSwarm<ToOtherBehaviourEvent, THandlerInEvent<Self>>>,
+}
+
+impl Behaviour {
+    pub fn new(local_peer_id: PeerId, staker_public_keys: Vec<PublicKey>) -> Self {
+        Self {
+            local_peer_id,
+            staker_public_keys,
+            manager: None,
+            signing_identities: FuturesUnordered::new(),
+            connections: HashMap::new(),
+            pending_events: VecDeque::new(),
+        }
+    }
+
+    /// Starts answering the challenges of peers and verifying their identities. Does nothing if
+    /// there are no stakers to verify the identities with.
+    pub fn set_manager(&mut self, manager: Arc<dyn StakerIdentityManager>) {
+        if self.staker_public_keys.is_empty() {
+            return;
+        }
+        self.manager = Some(manager);
+        let challenged_connections = self
+            .connections
+            .iter()
+            .filter_map(|(connection_id, connection)| {
+                connection.remote_challenge.map(|challenge| (*connection_id, challenge))
+            })
+            .collect::<Vec<_>>();
+        for (connection_id, challenge) in challenged_connections {
+            self.sign_identity(connection_id, challenge);
+        }
+    }
+
+    fn send_message(&mut self, connection_id: ConnectionId, message: &HandshakeMessage) {
+        let Some(connection) = self.connections.get(&connection_id) else {
+            return;
+        };
+        self.pending_events.push_back(ToSwarm::NotifyHandler {
+            peer_id: connection.peer_id,
+            handler: NotifyHandler::One(connection_id),
+            event: serde_json::to_vec(message).expect("Failed to serialize handshake message"),
+        });
+    }
+
+    fn sign_identity(&mut self, connection_id: ConnectionId, challenge: Challenge) {
+        let (Some(manager), Some(connection)) =
+            (self.manager.clone(), self.connections.get(&connection_id))
+        else {
+            return;
+        };
+        let local_peer_id = self.local_peer_id;
+        let nonce = identity_nonce(&challenge, connection.peer_id);
+        self.signing_identities.push(
+            async move { (connection_id, manager.sign_identity(local_peer_id, nonce).await) }
+                .boxed(),
+        );
+    }
+
+    fn on_message_received(
+        &mut self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        message: Bytes,
+    ) {
+        match serde_json::from_slice::<HandshakeMessage>(&message) {
+            Ok(HandshakeMessage::Challenge(challenge)) => {
+                let Some(connection) = self.connections.get_mut(&connection_id) else {
+                    return;
+                };
+                connection.remote_challenge = Some(challenge);
+                self.sign_identity(connection_id, challenge);
+            }
+            Ok(HandshakeMessage::Identity(identity)) => {
+                self.on_identity_received(peer_id, connection_id, identity)
+            }
+            Err(err) => warn!("Peer {peer_id} sent a malformed handshake message. Error: {err}"),
+        }
+    }
+
+    fn on_identity_received(
+        &mut self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        identity: StakerIdentity,
+    ) {
+        let (Some(manager), Some(connection)) =
+            (self.manager.as_ref(), self.connections.get(&connection_id))
+        else {
+            return;
+        };
+        if identity.peer_id != peer_id {
+            warn!("Peer {peer_id} sent the staker identity of peer {}.", identity.peer_id);
+            return;
+        }
+        let nonce = identity_nonce(&connection.local_challenge, self.local_peer_id);
+        let is_valid = self.staker_public_keys.iter().any(|public_key| {
+            manager.verify_identity(peer_id, nonce, &identity.signature, *public_key)
+        });
+        if !is_valid {
+            warn!("Peer {peer_id} sent a staker identity that isn't signed by a known staker.");
+            return;
+        }
+        self.pending_events
+            .push_back(ToSwarm::GenerateEvent(ToOtherBehaviourEvent::PeerVerified { peer_id }));
+    }
+}
+
+impl NetworkBehaviour for Behaviour {
+    type ConnectionHandler = Handler;
+    type ToSwarm = ToOtherBehaviourEvent;
+
+    fn handle_established_inbound_connection(
+        &mut self,
+        _connection_id: ConnectionId,
+        _peer_id: PeerId,
+        _local_addr: &Multiaddr,
+        _remote_addr: &Multiaddr,
+    ) -> Result<THandler<Self>, ConnectionDenied> {
+        Ok(Handler::default())
+    }
+
+    fn handle_established_outbound_connection(
+        &mut self,
+        _connection_id: ConnectionId,
+        _peer_id: PeerId,
+        _addr: &Multiaddr,
+        _role_override: Endpoint,
+    ) -> Result<THandler<Self>, ConnectionDenied> {
+        Ok(Handler::default())
+    }
+
+    fn on_swarm_event(&mut self, event: FromSwarm<'_>) {
+        match event {
+            FromSwarm::ConnectionEstablished(ConnectionEstablished {
+                peer_id,
+                connection_id,
+                ..
+            }) => {
+                let local_challenge = rand::thread_rng().gen::<Challenge>();
+                self.connections.insert(
+                    connection_id,
+                    Connection { peer_id, local_challenge, remote_challenge: None },
+                );
+                // Peers are only verified if there are stakers to verify them with.
+                if !self.staker_public_keys.is_empty() {
+                    self.send_message(connection_id, &HandshakeMessage::Challenge(local_challenge));
+                }
+            }
+            FromSwarm::ConnectionClosed(ConnectionClosed { connection_id, .. }) => {
+                self.connections.remove(&connection_id);
+            }
+            _ => {}
+        }
+    }
+
+    fn on_connection_handler_event(
+        &mut self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        message: THandlerOutEvent<Self>,
+    ) {
+        self.on_message_received(peer_id, connection_id, message);
+    }
+
+    fn poll(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<ToSwarm<Self::ToSwarm, THandlerInEvent<Self>>> {
+        while let Poll::Ready(Some((connection_id, result))) =
+            self.signing_identities.poll_next_unpin(cx)
+        {
+            match result {
+                Ok(signature) => {
+                    debug!("Signed the staker identity of the local peer for {connection_id}.");
+                    let identity = StakerIdentity { peer_id: self.local_peer_id, signature };
+                    self.send_message(connection_id, &HandshakeMessage::Identity(identity));
+                }
+                Err(err) => error!("{err}. The peer of {connection_id} won't verify this node."),
+            }
+        }
+        match self.pending_events.pop_front() {
+            Some(event) => Poll::Ready(event),
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl From<ToOtherBehaviourEvent> for mixed_behaviour::Event {
+    fn from(event: ToOtherBehaviourEvent) -> Self {
+        Self::ToOtherBehaviourEvent(mixed_behaviour::ToOtherBehaviourEvent::StakerIdentity(event))
+    }
+}
@@ -0,0 +1,146 @@
+use std::sync::Arc;
+
+use assert_matches::assert_matches;
+use async_trait::async_trait;
+use blake2s::blake2s_to_felt;
+use libp2p::identity::Keypair;
+use libp2p::swarm::{ConnectionId, ToSwarm};
+use libp2p::{PeerId, Swarm};
+use libp2p_swarm_test::{drive, SwarmExt};
+use starknet_api::core::Nonce;
+use starknet_api::crypto::utils::{PublicKey, RawSignature};
+use starknet_api::hash::StarkHash;
+
+use super::{
+    identity_nonce,
+    Behaviour,
+    Connection,
+    StakerIdentity,
+    StakerIdentityError,
+    StakerIdentityManager,
+    ToOtherBehaviourEvent,
+};
+
+const STAKER_A: PublicKey = PublicKey(StarkHash::ONE);
+const STAKER_B: PublicKey = PublicKey(StarkHash::TWO);
+const STAKER_C: PublicKey = PublicKey(StarkHash::THREE);
+
+/// Signs with the key of the given staker. A signature is valid if it's the public key of the
+/// staker that signed it, followed by the peer id and the nonce it was signed with.
+struct FakeStakerIdentityManager {
+    staker: PublicKey,
+}
+
+#[async_trait]
+impl StakerIdentityManager for FakeStakerIdentityManager {
+    async fn sign_identity(
+        &self,
+        peer_id: PeerId,
+        nonce: Nonce,
+    ) -> Result<RawSignature, StakerIdentityError> {
+        Ok(fake_signature(self.staker, peer_id, nonce))
+    }
+
+    fn verify_identity(
+        &self,
+        peer_id: PeerId,
+        nonce: Nonce,
+        signature: &RawSignature,
+        public_key: PublicKey,
+    ) -> bool {
+        *signature == fake_signature(public_key, peer_id, nonce)
+    }
+}
+
+fn fake_signature(staker: PublicKey, peer_id: PeerId, nonce: Nonce) -> RawSignature {
+    RawSignature(vec![staker.0, blake2s_to_felt(&peer_id.to_bytes()), nonce.0])
+}
+
+async fn create_connected_swarms(
+    (staker1, known_stakers1): (PublicKey, Vec<PublicKey>),
+    (staker2, known_stakers2): (PublicKey, Vec<PublicKey>),
+) -> (Swarm<Behaviour>, Swarm<Behaviour>) {
+    let create_swarm = |staker: PublicKey, known_stakers: Vec<PublicKey>| {
+        Swarm::new_ephemeral(move |keypair: Keypair| {
+            let mut behaviour = Behaviour::new(keypair.public().to_peer_id(), known_stakers);
+            behaviour.set_manager(Arc::new(FakeStakerIdentityManager { staker }));
+            behaviour
+        })
+    };
+    let mut swarm1 = create_swarm(staker1, known_stakers1);
+    let mut swarm2 = create_swarm(staker2, known_stakers2);
+    swarm1.listen().with_memory_addr_external().await;
+    swarm2.listen().with_memory_addr_external().await;
+    swarm1.connect(&mut swarm2).await;
+    (swarm1, swarm2)
+}
+
+#[tokio::test]
+async fn peers_signed_by_known_stakers_verify_each_other() {
+    let (mut swarm1, mut swarm2) =
+        create_connected_swarms((STAKER_A, vec![STAKER_B]), (STAKER_B, vec![STAKER_A])).await;
+    let peer_id1 = *swarm1.local_peer_id();
+    let peer_id2 = *swarm2.local_peer_id();
+
+    let (
+        [ToOtherBehaviourEvent::PeerVerified { peer_id: verified_by_1 }],
+        [ToOtherBehaviourEvent::PeerVerified { peer_id: verified_by_2 }],
+    ) = drive(&mut swarm1, &mut swarm2).await;
+    assert_eq!(verified_by_1, peer_id2);
+    assert_eq!(verified_by_2, peer_id1);
+}
+
+#[tokio::test]
+async fn peer_signed_by_unknown_staker_is_not_verified() {
+    let (mut swarm1, mut swarm2) =
+        create_connected_swarms((STAKER_B, vec![STAKER_A]), (STAKER_C, vec![STAKER_B])).await;
+    let peer_id1 = *swarm1.local_peer_id();
+
+    // Driving fails if the first swarm emits an event by the time the second swarm verifies it.
+    let ([], [ToOtherBehaviourEvent::PeerVerified { peer_id: verified_by_2 }]): (
+        [ToOtherBehaviourEvent; 0],
+        _,
+    ) = drive(&mut swarm1, &mut swarm2).await;
+    assert_eq!(verified_by_2, peer_id1);
+}
+
+#[tokio::test]
+async fn identity_signed_for_another_challenge_is_not_verified() {
+    let local_peer_id = PeerId::random();
+    let remote_peer_id = PeerId::random();
+    let manager = Arc::new(FakeStakerIdentityManager { staker: STAKER_A });
+    let mut behaviour = Behaviour::new(local_peer_id, vec![STAKER_A]);
+    behaviour.set_manager(manager.clone());
+    let connection_id = ConnectionId::new_unchecked(0);
+    behaviour.connections.insert(
+        connection_id,
+        Connection { peer_id: remote_peer_id, local_challenge: [1; 32], remote_challenge: None },
+    );
+
+    // An identity that the remote peer signed in answer to the challenge of another connection.
+    let replayed_signature = manager
+        .sign_identity(remote_peer_id, identity_nonce(&[2; 32], local_peer_id))
+        .await
+        .unwrap();
+    behaviour.on_identity_received(
+        remote_peer_id,
+        connection_id,
+        StakerIdentity { peer_id: remote_peer_id, signature: replayed_signature },
+    );
+    assert!(behaviour.pending_events.is_empty());
+
+    let signature = manager
+        .sign_identity(remote_peer_id, identity_nonce(&[1; 32], local_peer_id))
+        .await
+        .unwrap();
+    behaviour.on_identity_received(
+        remote_peer_id,
+        connection_id,
+        StakerIdentity { peer_id: remote_peer_id, signature },
+    );
+    assert_matches!(
+        behaviour.pending_events.pop_front(),
+        Some(ToSwarm::GenerateEvent(ToOtherBehaviourEvent::PeerVerified { peer_id }))
+        if peer_id == remote_peer_id
+    );
+}
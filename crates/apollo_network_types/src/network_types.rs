@@ -1,3 +1,10 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::{fs, io};
+
+use futures::channel::{mpsc, oneshot};
+use futures::SinkExt;
 use serde::{Deserialize, Serialize};
 
 pub type PeerId = libp2p::PeerId;
@@ -23,3 +30,131 @@ impl OpaquePeerId {
         self.0
     }
 }
+
+/// A ban of a peer that was reported for severe misconduct. Unlike blacklisting, a ban does not
+/// expire and is lifted only by removing the peer from the ban list.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PeerBan {
+    pub reason: String,
+    pub banned_at_unix_seconds: u64,
+}
+
+/// The banned peers of a network. The ban list is persisted as a JSON file so that bans survive
+/// restarts. While the network is running, the file is written only by the network's peer manager;
+/// changes from outside the network (e.g., through the monitoring endpoint) are made through a
+/// [PeerBanListClient].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PeerBanList {
+    pub banned_peers: BTreeMap<PeerId, PeerBan>,
+}
+
+impl PeerBanList {
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        self.banned_peers.contains_key(peer_id)
+    }
+
+    /// Reads the ban list from the given file. A missing file is considered an empty ban list.
+    pub fn read_from_file(path: &Path) -> io::Result<Self> {
+        match fs::read(path) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Writes the ban list to the given file. The list is first written to a temporary file that
+    /// then replaces the given file, so that readers never see a partially written list.
+    pub fn write_to_file(&self, path: &Path) -> io::Result<()> {
+        let mut tmp_path = path.as_os_str().to_owned();
+        tmp_path.push(".tmp");
+        fs::write(&tmp_path, serde_json::to_vec_pretty(self)?)?;
+        fs::rename(&tmp_path, path)
+    }
+}
+
+/// A request to the owner of a peer ban list.
+#[derive(Debug)]
+pub enum PeerBanListRequest {
+    Get { response_sender: oneshot::Sender<PeerBanList> },
+    Ban { peer_id: PeerId, reason: String, response_sender: oneshot::Sender<()> },
+    Unban { peer_id: PeerId, response_sender: oneshot::Sender<()> },
+}
+
+pub type PeerBanListRequestReceiver = mpsc::UnboundedReceiver<PeerBanListRequest>;
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum PeerBanListError {
+    #[error("The network that owns the peer ban list isn't running.")]
+    OwnerStopped,
+}
+
+/// A client of the owner of a peer ban list, through which the list is changed while the network
+/// that owns it is running.
+#[derive(Clone, Debug)]
+pub struct PeerBanListClient {
+    sender: mpsc::UnboundedSender<PeerBanListRequest>,
+}
+
+impl PeerBanListClient {
+    /// Returns a client and the receiver of its requests. The owner of the ban list must handle
+    /// the requests of the receiver and is the only one that writes the list's file.
+    pub fn new() -> (Self, PeerBanListRequestReceiver) {
+        let (sender, receiver) = mpsc::unbounded();
+        (Self { sender }, receiver)
+    }
+
+    pub async fn get(&self) -> Result<PeerBanList, PeerBanListError> {
+        self.send_request(|response_sender| PeerBanListRequest::Get { response_sender }).await
+    }
+
+    pub async fn ban(&self, peer_id: PeerId, reason: String) -> Result<(), PeerBanListError> {
+        self.send_request(|response_sender| PeerBanListRequest::Ban {
+            peer_id,
+            reason,
+            response_sender,
+        })
+        .await
+    }
+
+    pub async fn unban(&self, peer_id: PeerId) -> Result<(), PeerBanListError> {
+        self.send_request(|response_sender| PeerBanListRequest::Unban { peer_id, response_sender })
+            .await
+    }
+
+    async fn send_request<Response>(
+        &self,
+        request: impl FnOnce(oneshot::Sender<Response>) -> PeerBanListRequest,
+    ) -> Result<Response, PeerBanListError> {
+        let (response_sender, response_receiver) = oneshot::channel();
+        self.sender
+            .clone()
+            .send(request(response_sender))
+            .await
+            .map_err(|_| PeerBanListError::OwnerStopped)?;
+        response_receiver.await.map_err(|_| PeerBanListError::OwnerStopped)
+    }
+}
+
+/// The clients of the peer ban lists of the node's networks, keyed by the network name. Each
+/// network adds the client of its ban list once it's created, so that the ban lists can be
+/// managed from outside the networks (e.g., through the monitoring endpoint).
+#[derive(Clone, Debug, Default)]
+pub struct PeerBanListClients(Arc<Mutex<BTreeMap<String, PeerBanListClient>>>);
+
+impl PeerBanListClients {
+    pub fn insert(&self, network: &str, client: PeerBanListClient) {
+        self.lock().insert(network.to_string(), client);
+    }
+
+    pub fn get(&self, network: &str) -> Option<PeerBanListClient> {
+        self.lock().get(network).cloned()
+    }
+
+    pub fn all(&self) -> BTreeMap<String, PeerBanListClient> {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<String, PeerBanListClient>> {
+        self.0.lock().expect("Peer ban list clients lock poisoned.")
+    }
+}
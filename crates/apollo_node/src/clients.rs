@@ -31,16 +31,27 @@ use apollo_gateway_types::communication::{
 use apollo_infra::component_client::{Client, LocalComponentClient};
 use apollo_infra::metrics::{
     RemoteClientMetrics,
+    BATCHER_REMOTE_CLIENT_CIRCUIT_STATE,
     BATCHER_REMOTE_CLIENT_SEND_ATTEMPTS,
+    CLASS_MANAGER_REMOTE_CLIENT_CIRCUIT_STATE,
     CLASS_MANAGER_REMOTE_CLIENT_SEND_ATTEMPTS,
+    GATEWAY_REMOTE_CLIENT_CIRCUIT_STATE,
     GATEWAY_REMOTE_CLIENT_SEND_ATTEMPTS,
+    L1_ENDPOINT_MONITOR_REMOTE_CLIENT_CIRCUIT_STATE,
     L1_ENDPOINT_MONITOR_SEND_ATTEMPTS,
+    L1_GAS_PRICE_PROVIDER_REMOTE_CLIENT_CIRCUIT_STATE,
     L1_GAS_PRICE_PROVIDER_REMOTE_CLIENT_SEND_ATTEMPTS,
+    L1_PROVIDER_REMOTE_CLIENT_CIRCUIT_STATE,
     L1_PROVIDER_REMOTE_CLIENT_SEND_ATTEMPTS,
+    MEMPOOL_P2P_REMOTE_CLIENT_CIRCUIT_STATE,
     MEMPOOL_P2P_REMOTE_CLIENT_SEND_ATTEMPTS,
+    MEMPOOL_REMOTE_CLIENT_CIRCUIT_STATE,
     MEMPOOL_REMOTE_CLIENT_SEND_ATTEMPTS,
+    SIERRA_COMPILER_REMOTE_CLIENT_CIRCUIT_STATE,
     SIERRA_COMPILER_REMOTE_CLIENT_SEND_ATTEMPTS,
+    SIGNATURE_MANAGER_REMOTE_CLIENT_CIRCUIT_STATE,
     SIGNATURE_MANAGER_REMOTE_CLIENT_SEND_ATTEMPTS,
+    STATE_SYNC_REMOTE_CLIENT_CIRCUIT_STATE,
     STATE_SYNC_REMOTE_CLIENT_SEND_ATTEMPTS,
 };
 use apollo_l1_endpoint_monitor::communication::{
@@ -331,7 +342,10 @@ pub fn create_node_clients(
     channels: &mut SequencerNodeCommunication,
 ) -> SequencerNodeClients {
     info!("Creating node clients.");
-    let batcher_remote_metrics = RemoteClientMetrics::new(&BATCHER_REMOTE_CLIENT_SEND_ATTEMPTS);
+    let batcher_remote_metrics = RemoteClientMetrics::new(
+        &BATCHER_REMOTE_CLIENT_SEND_ATTEMPTS,
+        &BATCHER_REMOTE_CLIENT_CIRCUIT_STATE,
+    );
     let batcher_client = create_client!(
         &config.components.batcher.execution_mode,
         LocalBatcherClient,
@@ -343,8 +357,10 @@ pub fn create_node_clients(
         batcher_remote_metrics
     );
 
-    let class_manager_remote_metrics =
-        RemoteClientMetrics::new(&CLASS_MANAGER_REMOTE_CLIENT_SEND_ATTEMPTS);
+    let class_manager_remote_metrics = RemoteClientMetrics::new(
+        &CLASS_MANAGER_REMOTE_CLIENT_SEND_ATTEMPTS,
+        &CLASS_MANAGER_REMOTE_CLIENT_CIRCUIT_STATE,
+    );
     let class_manager_client = create_client!(
         &config.components.class_manager.execution_mode,
         LocalClassManagerClient,
@@ -356,7 +372,10 @@ pub fn create_node_clients(
         class_manager_remote_metrics
     );
 
-    let gateway_remote_metrics = RemoteClientMetrics::new(&GATEWAY_REMOTE_CLIENT_SEND_ATTEMPTS);
+    let gateway_remote_metrics = RemoteClientMetrics::new(
+        &GATEWAY_REMOTE_CLIENT_SEND_ATTEMPTS,
+        &GATEWAY_REMOTE_CLIENT_CIRCUIT_STATE,
+    );
     let gateway_client = create_client!(
         &config.components.gateway.execution_mode,
         LocalGatewayClient,
@@ -368,8 +387,10 @@ pub fn create_node_clients(
         gateway_remote_metrics
     );
 
-    let l1_endpoint_monitor_remote_metrics =
-        RemoteClientMetrics::new(&L1_ENDPOINT_MONITOR_SEND_ATTEMPTS);
+    let l1_endpoint_monitor_remote_metrics = RemoteClientMetrics::new(
+        &L1_ENDPOINT_MONITOR_SEND_ATTEMPTS,
+        &L1_ENDPOINT_MONITOR_REMOTE_CLIENT_CIRCUIT_STATE,
+    );
     let l1_endpoint_monitor_client = create_client!(
         &config.components.l1_endpoint_monitor.execution_mode,
         LocalL1EndpointMonitorClient,
@@ -381,8 +402,10 @@ pub fn create_node_clients(
         l1_endpoint_monitor_remote_metrics
     );
 
-    let l1_provider_remote_metrics =
-        RemoteClientMetrics::new(&L1_PROVIDER_REMOTE_CLIENT_SEND_ATTEMPTS);
+    let l1_provider_remote_metrics = RemoteClientMetrics::new(
+        &L1_PROVIDER_REMOTE_CLIENT_SEND_ATTEMPTS,
+        &L1_PROVIDER_REMOTE_CLIENT_CIRCUIT_STATE,
+    );
     let l1_provider_client = create_client!(
         &config.components.l1_provider.execution_mode,
         LocalL1ProviderClient,
@@ -394,8 +417,10 @@ pub fn create_node_clients(
         l1_provider_remote_metrics
     );
 
-    let l1_gas_price_provider_remote_metrics =
-        RemoteClientMetrics::new(&L1_GAS_PRICE_PROVIDER_REMOTE_CLIENT_SEND_ATTEMPTS);
+    let l1_gas_price_provider_remote_metrics = RemoteClientMetrics::new(
+        &L1_GAS_PRICE_PROVIDER_REMOTE_CLIENT_SEND_ATTEMPTS,
+        &L1_GAS_PRICE_PROVIDER_REMOTE_CLIENT_CIRCUIT_STATE,
+    );
     let l1_gas_price_client = create_client!(
         &config.components.l1_gas_price_provider.execution_mode,
         LocalL1GasPriceClient,
@@ -407,7 +432,10 @@ pub fn create_node_clients(
         l1_gas_price_provider_remote_metrics
     );
 
-    let mempool_remote_metrics = RemoteClientMetrics::new(&MEMPOOL_REMOTE_CLIENT_SEND_ATTEMPTS);
+    let mempool_remote_metrics = RemoteClientMetrics::new(
+        &MEMPOOL_REMOTE_CLIENT_SEND_ATTEMPTS,
+        &MEMPOOL_REMOTE_CLIENT_CIRCUIT_STATE,
+    );
     let mempool_client = create_client!(
         &config.components.mempool.execution_mode,
         LocalMempoolClient,
@@ -419,8 +447,10 @@ pub fn create_node_clients(
         mempool_remote_metrics
     );
 
-    let mempool_p2p_remote_metrics =
-        RemoteClientMetrics::new(&MEMPOOL_P2P_REMOTE_CLIENT_SEND_ATTEMPTS);
+    let mempool_p2p_remote_metrics = RemoteClientMetrics::new(
+        &MEMPOOL_P2P_REMOTE_CLIENT_SEND_ATTEMPTS,
+        &MEMPOOL_P2P_REMOTE_CLIENT_CIRCUIT_STATE,
+    );
     let mempool_p2p_propagator_client = create_client!(
         &config.components.mempool_p2p.execution_mode,
         LocalMempoolP2pPropagatorClient,
@@ -432,8 +462,10 @@ pub fn create_node_clients(
         mempool_p2p_remote_metrics
     );
 
-    let sierra_compiler_remote_metrics =
-        RemoteClientMetrics::new(&SIERRA_COMPILER_REMOTE_CLIENT_SEND_ATTEMPTS);
+    let sierra_compiler_remote_metrics = RemoteClientMetrics::new(
+        &SIERRA_COMPILER_REMOTE_CLIENT_SEND_ATTEMPTS,
+        &SIERRA_COMPILER_REMOTE_CLIENT_CIRCUIT_STATE,
+    );
     let sierra_compiler_client = create_client!(
         &config.components.sierra_compiler.execution_mode,
         LocalSierraCompilerClient,
@@ -445,8 +477,10 @@ pub fn create_node_clients(
         sierra_compiler_remote_metrics
     );
 
-    let signature_manager_remote_metrics =
-        RemoteClientMetrics::new(&SIGNATURE_MANAGER_REMOTE_CLIENT_SEND_ATTEMPTS);
+    let signature_manager_remote_metrics = RemoteClientMetrics::new(
+        &SIGNATURE_MANAGER_REMOTE_CLIENT_SEND_ATTEMPTS,
+        &SIGNATURE_MANAGER_REMOTE_CLIENT_CIRCUIT_STATE,
+    );
     let signature_manager_client = create_client!(
         &config.components.signature_manager.execution_mode,
         LocalSignatureManagerClient,
@@ -458,8 +492,10 @@ pub fn create_node_clients(
         signature_manager_remote_metrics
     );
 
-    let state_sync_remote_metrics =
-        RemoteClientMetrics::new(&STATE_SYNC_REMOTE_CLIENT_SEND_ATTEMPTS);
+    let state_sync_remote_metrics = RemoteClientMetrics::new(
+        &STATE_SYNC_REMOTE_CLIENT_SEND_ATTEMPTS,
+        &STATE_SYNC_REMOTE_CLIENT_CIRCUIT_STATE,
+    );
     let state_sync_client = create_client!(
         &config.components.state_sync.execution_mode,
         LocalStateSyncClient,
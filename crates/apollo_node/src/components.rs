@@ -3,6 +3,7 @@ use apollo_batcher::pre_confirmed_cende_client::PreconfirmedCendeClient;
 use apollo_class_manager::class_manager::create_class_manager;
 use apollo_class_manager::ClassManager;
 use apollo_compile_to_casm::{create_sierra_compiler, SierraCompiler};
+use apollo_config::dynamic_config::SharedDynamicConfigPublisher;
 use apollo_consensus_manager::consensus_manager::ConsensusManager;
 use apollo_gateway::gateway::{create_gateway, Gateway};
 use apollo_http_server::http_server::{create_http_server, HttpServer};
@@ -12,6 +13,8 @@ use apollo_l1_gas_price::l1_gas_price_scraper::L1GasPriceScraper;
 use apollo_l1_provider::event_identifiers_to_track;
 use apollo_l1_provider::l1_provider::{L1Provider, L1ProviderBuilder};
 use apollo_l1_provider::l1_scraper::{fetch_start_block, L1Scraper};
+use apollo_l1_provider::persistence::load_persisted_state;
+use apollo_l1_provider_types::ScrapedRound;
 use apollo_mempool::communication::{create_mempool, MempoolCommunicationWrapper};
 use apollo_mempool_p2p::create_p2p_propagator_and_runner;
 use apollo_mempool_p2p::propagator::MempoolP2pPropagator;
@@ -20,6 +23,7 @@ use apollo_monitoring_endpoint::monitoring_endpoint::{
     create_monitoring_endpoint,
     MonitoringEndpoint,
 };
+use apollo_network_types::network_types::PeerBanListClients;
 use apollo_signature_manager::{create_signature_manager, SignatureManager};
 use apollo_state_sync::runner::StateSyncRunner;
 use apollo_state_sync::{create_state_sync_and_runner, StateSync};
@@ -60,8 +64,11 @@ pub struct SequencerNodeComponents {
 pub async fn create_node_components(
     config: &SequencerNodeConfig,
     clients: &SequencerNodeClients,
+    dynamic_config: Option<SharedDynamicConfigPublisher>,
 ) -> SequencerNodeComponents {
     info!("Creating node components.");
+    // Each network adds the client of its peer ban list once it's created.
+    let peer_ban_list_clients = PeerBanListClients::default();
     let batcher = match config.components.batcher.execution_mode {
         ReactiveComponentExecutionMode::LocalExecutionWithRemoteDisabled
         | ReactiveComponentExecutionMode::LocalExecutionWithRemoteEnabled => {
@@ -76,12 +83,21 @@ pub async fn create_node_components(
             let pre_confirmed_cende_client = std::sync::Arc::new(PreconfirmedCendeClient::new(
                 config.batcher_config.pre_confirmed_cende_config.clone(),
             ));
+            let bouncer_config_receiver = dynamic_config.as_ref().map(|dynamic_config| {
+                dynamic_config
+                    .subscribe_to_params(
+                        "batcher_config.block_builder_config.bouncer_config",
+                        &["block_max_capacity"],
+                    )
+                    .expect("Bouncer config should be in the config map")
+            });
             Some(create_batcher(
                 config.batcher_config.clone(),
                 mempool_client,
                 l1_provider_client,
                 class_manager_client,
                 pre_confirmed_cende_client,
+                bouncer_config_receiver,
             ))
         }
         ReactiveComponentExecutionMode::Disabled | ReactiveComponentExecutionMode::Remote => None,
@@ -129,6 +145,7 @@ pub async fn create_node_components(
                 class_manager_client,
                 signature_manager_client,
                 l1_gas_price_client,
+                peer_ban_list_clients.clone(),
             ))
         }
         ActiveComponentExecutionMode::Disabled => None,
@@ -144,12 +161,19 @@ pub async fn create_node_components(
             let class_manager_client = clients
                 .get_class_manager_shared_client()
                 .expect("Class Manager Client should be available");
+            let stateless_tx_validator_config_receiver =
+                dynamic_config.as_ref().map(|dynamic_config| {
+                    dynamic_config
+                        .subscribe("gateway_config.stateless_tx_validator_config")
+                        .expect("Stateless tx validator config should be in the config map")
+                });
             Some(create_gateway(
                 config.gateway_config.clone(),
                 state_sync_client,
                 mempool_client,
                 class_manager_client,
                 tokio::runtime::Handle::current(),
+                stateless_tx_validator_config_receiver,
             ))
         }
         ReactiveComponentExecutionMode::Disabled | ReactiveComponentExecutionMode::Remote => None,
@@ -177,11 +201,16 @@ pub async fn create_node_components(
                 let mempool_p2p_propagator_client = clients
                     .get_mempool_p2p_propagator_shared_client()
                     .expect("Mempool P2p Propagator Client should be available");
+                let mempool_client = clients
+                    .get_mempool_shared_client()
+                    .expect("Mempool Client should be available");
                 let (mempool_p2p_propagator, mempool_p2p_runner) = create_p2p_propagator_and_runner(
                     config.mempool_p2p_config.clone(),
                     gateway_client,
                     class_manager_client,
                     mempool_p2p_propagator_client,
+                    mempool_client,
+                    peer_ban_list_clients.clone(),
                 );
                 (Some(mempool_p2p_propagator), Some(mempool_p2p_runner))
             }
@@ -196,8 +225,19 @@ pub async fn create_node_components(
             let mempool_p2p_propagator_client = clients
                 .get_mempool_p2p_propagator_shared_client()
                 .expect("Propagator Client should be available");
-            let mempool =
-                create_mempool(config.mempool_config.clone(), mempool_p2p_propagator_client);
+            let mempool_config_receiver = dynamic_config.as_ref().map(|dynamic_config| {
+                dynamic_config
+                    .subscribe_to_params(
+                        "mempool_config",
+                        &["enable_fee_escalation", "fee_escalation_percentage"],
+                    )
+                    .expect("Mempool config should be in the config map")
+            });
+            let mempool = create_mempool(
+                config.mempool_config.clone(),
+                mempool_p2p_propagator_client,
+                mempool_config_receiver,
+            );
             Some(mempool)
         }
         ReactiveComponentExecutionMode::Disabled | ReactiveComponentExecutionMode::Remote => None,
@@ -232,6 +272,8 @@ pub async fn create_node_components(
                 VERSION_FULL,
                 mempool_client,
                 l1_provider_client,
+                peer_ban_list_clients.clone(),
+                dynamic_config,
             ))
         }
         ActiveComponentExecutionMode::Disabled => None,
@@ -246,6 +288,7 @@ pub async fn create_node_components(
             let (state_sync, state_sync_runner) = create_state_sync_and_runner(
                 config.state_sync_config.clone(),
                 class_manager_client,
+                peer_ban_list_clients.clone(),
             );
             (Some(state_sync), Some(state_sync_runner))
         }
@@ -266,6 +309,25 @@ pub async fn create_node_components(
         ReactiveComponentExecutionMode::Disabled | ReactiveComponentExecutionMode::Remote => None,
     };
 
+    // A valid persisted provider state lets the scraper resume from the L1 block it was persisted
+    // at, instead of rewinding and re-scraping the L1 history.
+    let persisted_l1_provider_state = match (
+        &config.components.l1_scraper.execution_mode,
+        &config.components.l1_provider.execution_mode,
+        &config.l1_provider_config.persisted_state_path,
+    ) {
+        (
+            ActiveComponentExecutionMode::Enabled,
+            ReactiveComponentExecutionMode::LocalExecutionWithRemoteDisabled
+            | ReactiveComponentExecutionMode::LocalExecutionWithRemoteEnabled,
+            Some(path),
+        ) => {
+            let base_layer = EthereumBaseLayerContract::new(config.base_layer_config.clone());
+            load_persisted_state(path, &base_layer).await
+        }
+        _ => None,
+    };
+
     let l1_scraper = match config.components.l1_scraper.execution_mode {
         ActiveComponentExecutionMode::Enabled => {
             let l1_provider_client = clients.get_l1_provider_shared_client().unwrap();
@@ -273,9 +335,17 @@ pub async fn create_node_components(
                 clients.get_l1_endpoint_monitor_shared_client().unwrap();
             let l1_scraper_config = config.l1_scraper_config.clone();
             let base_layer = EthereumBaseLayerContract::new(config.base_layer_config.clone());
-            let l1_start_block = fetch_start_block(&base_layer, &l1_scraper_config)
-                .await
-                .unwrap_or_else(|err| panic!("Error while initializing the L1 scraper: {err}"));
+            // The scraper resumes from its persisted recent rounds, so it can still rewind into
+            // them on L1 reorgs.
+            let recent_scraped_rounds = match &persisted_l1_provider_state {
+                Some(persisted_state) => Ok(persisted_state.recent_scraped_rounds.clone()),
+                None => {
+                    fetch_start_block(&base_layer, &l1_scraper_config).await.map(|l1_start_block| {
+                        vec![ScrapedRound { last_l1_block: l1_start_block, events: vec![] }]
+                    })
+                }
+            }
+            .unwrap_or_else(|err| panic!("Error while initializing the L1 scraper: {err}"));
 
             let monitored_base_layer = MonitoredEthereumBaseLayer::new(
                 base_layer,
@@ -284,12 +354,12 @@ pub async fn create_node_components(
             );
 
             Some(
-                L1Scraper::new(
+                L1Scraper::resume(
                     l1_scraper_config,
                     l1_provider_client,
                     monitored_base_layer,
                     event_identifiers_to_track(),
-                    l1_start_block,
+                    recent_scraped_rounds,
                 )
                 .await
                 .unwrap(),
@@ -304,13 +374,16 @@ pub async fn create_node_components(
         ReactiveComponentExecutionMode::LocalExecutionWithRemoteDisabled
         | ReactiveComponentExecutionMode::LocalExecutionWithRemoteEnabled => {
             let mut l1_provider_builder = L1ProviderBuilder::new(
-                config.l1_provider_config,
+                config.l1_provider_config.clone(),
                 clients.get_l1_provider_shared_client().unwrap(),
                 clients.get_batcher_shared_client().unwrap(),
                 clients.get_state_sync_shared_client().unwrap(),
             );
-            match &l1_scraper {
-                Some(l1_scraper) => {
+            match (&l1_scraper, persisted_l1_provider_state) {
+                (Some(_), Some(persisted_l1_provider_state)) => {
+                    Some(l1_provider_builder.persisted_state(persisted_l1_provider_state).build())
+                }
+                (Some(l1_scraper), None) => {
                     let l1_scraper_start_l1_height = l1_scraper.last_l1_block_processed.number;
                     let base_layer =
                         EthereumBaseLayerContract::new(config.base_layer_config.clone());
@@ -335,7 +408,7 @@ pub async fn create_node_components(
 
                     Some(l1_provider_builder.build())
                 }
-                None => {
+                (None, _) => {
                     warn!("L1 Scraper is disabled, initialize L1 provider in dummy mode");
                     let batcher_height = batcher
                         .as_ref()
@@ -1,5 +1,6 @@
 use std::collections::{BTreeMap, HashSet};
 use std::fs::File;
+use std::path::PathBuf;
 use std::sync::LazyLock;
 use std::vec::Vec;
 
@@ -16,7 +17,7 @@ use apollo_config::dumping::{
     Pointers,
     SerializeConfig,
 };
-use apollo_config::loading::load_and_process_config;
+use apollo_config::loading::{load_and_process_config, load_and_process_config_map};
 use apollo_config::{ConfigError, ParamPath, SerializedParam};
 use apollo_consensus_manager::config::ConsensusManagerConfig;
 use apollo_gateway::config::GatewayConfig;
@@ -35,6 +36,7 @@ use apollo_state_sync::config::StateSyncConfig;
 use clap::Command;
 use papyrus_base_layer::ethereum_base_layer_contract::EthereumBaseLayerConfig;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use validator::Validate;
 
 use crate::config::component_config::ComponentConfig;
@@ -247,6 +249,16 @@ impl SequencerNodeConfig {
         let default_config_file = File::open(config_file_name)?;
         load_and_process_config(default_config_file, node_command(), args, true)
     }
+
+    /// Same as [`Self::load_and_process`], but returns the processed config map instead of a
+    /// config object, together with the custom config files it was loaded from.
+    pub fn load_and_process_config_map(
+        args: Vec<String>,
+    ) -> Result<(BTreeMap<ParamPath, Value>, Vec<PathBuf>), ConfigError> {
+        let config_file_name = &resolve_project_relative_path(CONFIG_SCHEMA_PATH)?;
+        let default_config_file = File::open(config_file_name)?;
+        load_and_process_config_map(default_config_file, node_command(), args, true)
+    }
 }
 
 /// The command line interface of this node.
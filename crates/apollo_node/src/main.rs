@@ -3,7 +3,7 @@ use std::env::args;
 use apollo_infra::trace_util::configure_tracing;
 use apollo_infra_utils::set_global_allocator;
 use apollo_node::servers::run_component_servers;
-use apollo_node::utils::{create_node_modules, load_and_validate_config};
+use apollo_node::utils::{create_node_modules, load_and_validate_config_with_dynamic_config};
 use tracing::info;
 
 set_global_allocator!();
@@ -23,11 +23,12 @@ async fn main() -> anyhow::Result<()> {
 
     set_exit_process_on_panic();
 
-    let config =
-        load_and_validate_config(args().collect()).expect("Failed to load and validate config");
+    let args: Vec<String> = args().collect();
+    let (config, dynamic_config) = load_and_validate_config_with_dynamic_config(args)
+        .expect("Failed to load and validate config");
 
     // Clients are currently unused, but should not be dropped.
-    let (_clients, servers) = create_node_modules(&config).await;
+    let (_clients, servers) = create_node_modules(&config, Some(dynamic_config)).await;
 
     info!("START_UP: Starting components!");
     run_component_servers(servers).await;
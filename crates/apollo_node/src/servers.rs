@@ -19,6 +19,7 @@ use apollo_infra::component_server::{
 };
 use apollo_infra::metrics::{
     LocalServerMetrics,
+    LocalServerQueueMetrics,
     RemoteServerMetrics,
     BATCHER_LOCAL_MSGS_PROCESSED,
     BATCHER_LOCAL_MSGS_RECEIVED,
@@ -29,12 +30,18 @@ use apollo_infra::metrics::{
     CLASS_MANAGER_LOCAL_MSGS_PROCESSED,
     CLASS_MANAGER_LOCAL_MSGS_RECEIVED,
     CLASS_MANAGER_LOCAL_QUEUE_DEPTH,
+    CLASS_MANAGER_LOCAL_REQUESTS_SHED,
+    CLASS_MANAGER_LOCAL_REQUEST_QUEUE_DEPTH,
+    CLASS_MANAGER_LOCAL_REQUEST_QUEUE_TIME,
     CLASS_MANAGER_REMOTE_MSGS_PROCESSED,
     CLASS_MANAGER_REMOTE_MSGS_RECEIVED,
     CLASS_MANAGER_REMOTE_VALID_MSGS_RECEIVED,
     GATEWAY_LOCAL_MSGS_PROCESSED,
     GATEWAY_LOCAL_MSGS_RECEIVED,
     GATEWAY_LOCAL_QUEUE_DEPTH,
+    GATEWAY_LOCAL_REQUESTS_SHED,
+    GATEWAY_LOCAL_REQUEST_QUEUE_DEPTH,
+    GATEWAY_LOCAL_REQUEST_QUEUE_TIME,
     GATEWAY_REMOTE_MSGS_PROCESSED,
     GATEWAY_REMOTE_MSGS_RECEIVED,
     GATEWAY_REMOTE_VALID_MSGS_RECEIVED,
@@ -71,12 +78,18 @@ use apollo_infra::metrics::{
     SIERRA_COMPILER_LOCAL_MSGS_PROCESSED,
     SIERRA_COMPILER_LOCAL_MSGS_RECEIVED,
     SIERRA_COMPILER_LOCAL_QUEUE_DEPTH,
+    SIERRA_COMPILER_LOCAL_REQUESTS_SHED,
+    SIERRA_COMPILER_LOCAL_REQUEST_QUEUE_DEPTH,
+    SIERRA_COMPILER_LOCAL_REQUEST_QUEUE_TIME,
     SIERRA_COMPILER_REMOTE_MSGS_PROCESSED,
     SIERRA_COMPILER_REMOTE_MSGS_RECEIVED,
     SIERRA_COMPILER_REMOTE_VALID_MSGS_RECEIVED,
     SIGNATURE_MANAGER_LOCAL_MSGS_PROCESSED,
     SIGNATURE_MANAGER_LOCAL_MSGS_RECEIVED,
     SIGNATURE_MANAGER_LOCAL_QUEUE_DEPTH,
+    SIGNATURE_MANAGER_LOCAL_REQUESTS_SHED,
+    SIGNATURE_MANAGER_LOCAL_REQUEST_QUEUE_DEPTH,
+    SIGNATURE_MANAGER_LOCAL_REQUEST_QUEUE_TIME,
     SIGNATURE_MANAGER_REMOTE_MSGS_PROCESSED,
     SIGNATURE_MANAGER_REMOTE_MSGS_RECEIVED,
     SIGNATURE_MANAGER_REMOTE_VALID_MSGS_RECEIVED,
@@ -255,8 +268,12 @@ macro_rules! create_remote_server {
 ///   is enabled(LocalExecutionWithRemoteDisabled / LocalExecutionWithRemoteEnabled).
 /// * $receiver - receiver side for the server.
 /// * $server_metrics - The metrics for the server.
+/// * $local_server_config - The configuration of the server, including its queue time budgets. Only
+///   required for the CONCURRENT_LOCAL_SERVER.
 /// * $max_concurrency - The maximum number of concurrent requests the server will handle. Only
 ///   required for the CONCURRENT_LOCAL_SERVER.
+/// * $queue_metrics - The per-request metrics of the server's scheduling queue. Only required for
+///   the CONCURRENT_LOCAL_SERVER.
 ///
 /// # Returns
 ///
@@ -281,7 +298,7 @@ macro_rules! create_remote_server {
 /// }
 /// ```
 macro_rules! create_local_server {
-    ($server_type:tt, $execution_mode:expr, $component:expr, $receiver:expr, $server_metrics:expr  $(, $max_concurrency:expr)? ) => {
+    ($server_type:tt, $execution_mode:expr, $component:expr, $receiver:expr, $server_metrics:expr  $(, $local_server_config:expr, $max_concurrency:expr, $queue_metrics:expr)? ) => {
         match *$execution_mode {
             ReactiveComponentExecutionMode::LocalExecutionWithRemoteDisabled
             | ReactiveComponentExecutionMode::LocalExecutionWithRemoteEnabled => {
@@ -290,8 +307,9 @@ macro_rules! create_local_server {
                         .take()
                         .expect(concat!(stringify!($component), " is not initialized.")),
                     $receiver,
-                    $( $max_concurrency,)?
+                    $( $local_server_config.clone(), $max_concurrency,)?
                     $server_metrics,
+                    $( $queue_metrics,)?
                 )))
             }
             ReactiveComponentExecutionMode::Disabled | ReactiveComponentExecutionMode::Remote => {
@@ -371,26 +389,40 @@ fn create_local_servers(
         &CLASS_MANAGER_LOCAL_MSGS_PROCESSED,
         &CLASS_MANAGER_LOCAL_QUEUE_DEPTH,
     );
+    let class_manager_queue_metrics = LocalServerQueueMetrics::new(
+        &CLASS_MANAGER_LOCAL_REQUEST_QUEUE_DEPTH,
+        &CLASS_MANAGER_LOCAL_REQUEST_QUEUE_TIME,
+        &CLASS_MANAGER_LOCAL_REQUESTS_SHED,
+    );
     let class_manager_server = create_local_server!(
         CONCURRENT_LOCAL_SERVER,
         &config.components.class_manager.execution_mode,
         &mut components.class_manager,
         communication.take_class_manager_rx(),
         class_manager_metrics,
-        config.components.class_manager.max_concurrency
+        config.components.class_manager.local_server_config,
+        config.components.class_manager.max_concurrency,
+        class_manager_queue_metrics
     );
     let gateway_metrics = LocalServerMetrics::new(
         &GATEWAY_LOCAL_MSGS_RECEIVED,
         &GATEWAY_LOCAL_MSGS_PROCESSED,
         &GATEWAY_LOCAL_QUEUE_DEPTH,
     );
+    let gateway_queue_metrics = LocalServerQueueMetrics::new(
+        &GATEWAY_LOCAL_REQUEST_QUEUE_DEPTH,
+        &GATEWAY_LOCAL_REQUEST_QUEUE_TIME,
+        &GATEWAY_LOCAL_REQUESTS_SHED,
+    );
     let gateway_server = create_local_server!(
         CONCURRENT_LOCAL_SERVER,
         &config.components.gateway.execution_mode,
         &mut components.gateway,
         communication.take_gateway_rx(),
         gateway_metrics,
-        config.components.gateway.max_concurrency
+        config.components.gateway.local_server_config,
+        config.components.gateway.max_concurrency,
+        gateway_queue_metrics
     );
 
     let l1_endpoint_monitor_metrics = LocalServerMetrics::new(
@@ -459,13 +491,20 @@ fn create_local_servers(
         &SIERRA_COMPILER_LOCAL_MSGS_PROCESSED,
         &SIERRA_COMPILER_LOCAL_QUEUE_DEPTH,
     );
+    let sierra_compiler_queue_metrics = LocalServerQueueMetrics::new(
+        &SIERRA_COMPILER_LOCAL_REQUEST_QUEUE_DEPTH,
+        &SIERRA_COMPILER_LOCAL_REQUEST_QUEUE_TIME,
+        &SIERRA_COMPILER_LOCAL_REQUESTS_SHED,
+    );
     let sierra_compiler_server = create_local_server!(
         CONCURRENT_LOCAL_SERVER,
         &config.components.sierra_compiler.execution_mode,
         &mut components.sierra_compiler,
         communication.take_sierra_compiler_rx(),
         sierra_compiler_metrics,
-        config.components.sierra_compiler.max_concurrency
+        config.components.sierra_compiler.local_server_config,
+        config.components.sierra_compiler.max_concurrency,
+        sierra_compiler_queue_metrics
     );
     let state_sync_metrics = LocalServerMetrics::new(
         &STATE_SYNC_LOCAL_MSGS_RECEIVED,
@@ -485,13 +524,20 @@ fn create_local_servers(
         &SIGNATURE_MANAGER_LOCAL_MSGS_PROCESSED,
         &SIGNATURE_MANAGER_LOCAL_QUEUE_DEPTH,
     );
+    let signature_manager_queue_metrics = LocalServerQueueMetrics::new(
+        &SIGNATURE_MANAGER_LOCAL_REQUEST_QUEUE_DEPTH,
+        &SIGNATURE_MANAGER_LOCAL_REQUEST_QUEUE_TIME,
+        &SIGNATURE_MANAGER_LOCAL_REQUESTS_SHED,
+    );
     let signature_manager_server = create_local_server!(
         CONCURRENT_LOCAL_SERVER,
         &config.components.signature_manager.execution_mode,
         &mut components.signature_manager,
         communication.take_signature_manager_rx(),
         signature_manager_metrics,
-        config.components.signature_manager.max_concurrency
+        config.components.signature_manager.local_server_config,
+        config.components.signature_manager.max_concurrency,
+        signature_manager_queue_metrics
     );
 
     LocalServers {
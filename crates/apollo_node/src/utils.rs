@@ -1,8 +1,19 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::process::exit;
+use std::sync::Arc;
+use std::time::Duration;
 
+use apollo_config::dynamic_config::{
+    watch_config_files,
+    DynamicConfigPublisher,
+    SharedDynamicConfigPublisher,
+};
+use apollo_config::loading::load;
 use apollo_config::presentation::get_config_presentation;
 use apollo_config::validators::config_validate;
-use apollo_config::ConfigError;
+use apollo_config::{ConfigError, ParamPath};
+use serde_json::Value;
 use tracing::{error, info};
 
 use crate::clients::{create_node_clients, SequencerNodeClients};
@@ -11,28 +22,66 @@ use crate::components::create_node_components;
 use crate::config::node_config::SequencerNodeConfig;
 use crate::servers::{create_node_servers, SequencerNodeServers};
 
+const CONFIG_FILES_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
 pub async fn create_node_modules(
     config: &SequencerNodeConfig,
+    dynamic_config: Option<SharedDynamicConfigPublisher>,
 ) -> (SequencerNodeClients, SequencerNodeServers) {
     info!("Creating node modules.");
 
     let mut channels = create_node_channels(config);
     let clients = create_node_clients(config, &mut channels);
-    let components = create_node_components(config, &clients).await;
+    let components = create_node_components(config, &clients, dynamic_config).await;
     let servers = create_node_servers(config, &mut channels, components, &clients);
 
     (clients, servers)
 }
 
 pub fn load_and_validate_config(args: Vec<String>) -> Result<SequencerNodeConfig, ConfigError> {
-    let config = SequencerNodeConfig::load_and_process(args);
-    if let Err(ConfigError::CommandInput(clap_err)) = &config {
+    let (config_map, _) = load_config_map(args)?;
+    validate_config_map(&config_map)
+}
+
+/// Same as [`load_and_validate_config`], but also creates the dynamic config of the node from the
+/// same config map, and spawns a task that reloads it whenever one of the custom config files given
+/// in the args changes.
+pub fn load_and_validate_config_with_dynamic_config(
+    args: Vec<String>,
+) -> Result<(SequencerNodeConfig, SharedDynamicConfigPublisher), ConfigError> {
+    let (config_map, custom_config_paths) = load_config_map(args.clone())?;
+    let config = validate_config_map(&config_map)?;
+
+    let dynamic_config = Arc::new(DynamicConfigPublisher::new::<SequencerNodeConfig>(config_map)?);
+    tokio::spawn(watch_config_files(
+        dynamic_config.clone(),
+        custom_config_paths,
+        CONFIG_FILES_POLL_INTERVAL,
+        move || {
+            SequencerNodeConfig::load_and_process_config_map(args.clone())
+                .map(|(config_map, _)| config_map)
+        },
+    ));
+    info!("Watching the config files for dynamic config changes.");
+    Ok((config, dynamic_config))
+}
+
+fn load_config_map(
+    args: Vec<String>,
+) -> Result<(BTreeMap<ParamPath, Value>, Vec<PathBuf>), ConfigError> {
+    let config_map = SequencerNodeConfig::load_and_process_config_map(args);
+    if let Err(ConfigError::CommandInput(clap_err)) = &config_map {
         error!("Failed loading configuration: {}", clap_err);
         clap_err.exit();
     }
     info!("Finished loading configuration.");
+    config_map
+}
 
-    let config = config?;
+fn validate_config_map(
+    config_map: &BTreeMap<ParamPath, Value>,
+) -> Result<SequencerNodeConfig, ConfigError> {
+    let config = load::<SequencerNodeConfig>(config_map)?;
     if let Err(error) = config_validate(&config) {
         error!("{}", error);
         exit(1);
@@ -5,8 +5,9 @@ use std::time::Duration;
 use apollo_class_manager_types::SharedClassManagerClient;
 use apollo_network::network_manager::{ClientResponsesManager, SqmrClientSender};
 use apollo_protobuf::converters::ProtobufConversionError;
-use apollo_protobuf::sync::{BlockHashOrNumber, DataOrFin, Direction, Query};
+use apollo_protobuf::sync::{BlockHashOrNumber, DataOrFin, Direction, Query, TrieNode};
 use apollo_state_sync_types::state_sync_types::SyncBlock;
+use apollo_storage::body::BodyStorageReader;
 use apollo_storage::header::HeaderStorageReader;
 use apollo_storage::state::StateStorageReader;
 use apollo_storage::{StorageError, StorageReader, StorageWriter};
@@ -17,6 +18,7 @@ use futures::stream::BoxStream;
 use futures::{FutureExt, StreamExt};
 use starknet_api::block::{BlockNumber, BlockSignature};
 use starknet_api::core::ClassHash;
+use starknet_api::transaction::TransactionHash;
 use tracing::{debug, info, trace, warn};
 
 use super::{P2pSyncClientError, STEP};
@@ -38,6 +40,7 @@ pub(crate) enum BlockNumberLimit {
     Unlimited,
     HeaderMarker,
     StateDiffMarker,
+    BodyMarker,
 }
 
 pub(crate) trait BlockDataStreamBuilder<InputFromNetwork>
@@ -79,8 +82,10 @@ where
             if let Some(block) = internal_blocks_received.remove(&current_block_number) {
                 return block;
             }
-            let internal_block_receiver =
-                internal_block_receiver.as_mut().expect("Internal block receiver not set");
+            // Streams without an internal block receiver only get their data from the network.
+            let Some(internal_block_receiver) = internal_block_receiver.as_mut() else {
+                return futures::future::pending().await;
+            };
             while let Some(sync_block) = internal_block_receiver.next().await {
                 let block_number = sync_block.block_header_without_hash.block_number;
                 if block_number >= current_block_number {
@@ -107,7 +112,7 @@ where
         num_blocks_per_query: u64,
     ) -> BoxStream<'static, BlockDataResult>
     where
-        TQuery: From<Query> + Send + 'static,
+        TQuery: From<Query> + Clone + Send + Sync + 'static,
         Vec<u8>: From<TQuery>,
     {
         stream! {
@@ -116,10 +121,13 @@ where
             'send_query_and_parse_responses: loop {
                 let limit = match Self::BLOCK_NUMBER_LIMIT {
                     BlockNumberLimit::Unlimited => num_blocks_per_query,
-                    BlockNumberLimit::HeaderMarker | BlockNumberLimit::StateDiffMarker => {
+                    BlockNumberLimit::HeaderMarker
+                    | BlockNumberLimit::StateDiffMarker
+                    | BlockNumberLimit::BodyMarker => {
                         let (last_block_number, description) = match Self::BLOCK_NUMBER_LIMIT {
                             BlockNumberLimit::HeaderMarker => (storage_reader.begin_ro_txn()?.get_header_marker()?, "header"),
                             BlockNumberLimit::StateDiffMarker => (storage_reader.begin_ro_txn()?.get_state_marker()?, "state diff"),
+                            BlockNumberLimit::BodyMarker => (storage_reader.begin_ro_txn()?.get_body_marker()?, "body"),
                             _ => unreachable!(),
                         };
                         let limit = min(last_block_number.0 - current_block_number.0, num_blocks_per_query);
@@ -271,8 +279,29 @@ pub(crate) enum BadPeerError {
     ClassNotInStateDiff { class_hash: ClassHash },
     #[error("Received two classes with the same hash: {class_hash}.")]
     DuplicateClass { class_hash: ClassHash },
+    #[error(
+        "Expected to receive {expected} receipts for {block_number} from the network. Got \
+         {actual} instead."
+    )]
+    NotEnoughReceipts { expected: usize, actual: usize, block_number: u64 },
+    #[error(
+        "Received a receipt of transaction {actual} where a receipt of transaction {expected} was \
+         expected."
+    )]
+    WrongReceiptTransactionHash { expected: TransactionHash, actual: TransactionHash },
     #[error("Response timeout while waiting for data from the network.")]
     ResponseTimeout,
+    #[error("Received Fin for a state snapshot query without any trie leaves.")]
+    MissingStateTrieLeaves,
+    #[error("Received more than one message of trie leaves for a state snapshot query.")]
+    StateTrieLeavesWithoutFin,
+    #[error("Received an invalid trie node {trie_node:?}.")]
+    InvalidTrieNode { trie_node: TrieNode },
+    #[error(
+        "Received trie leaves with a limit of {limit} for a state snapshot query with a limit of \
+         {query_limit}."
+    )]
+    InvalidStateTrieLeavesLimit { limit: u64, query_limit: u64 },
 }
 
 #[derive(thiserror::Error, Debug)]
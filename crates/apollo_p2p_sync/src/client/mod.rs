@@ -5,9 +5,15 @@ mod class_test;
 mod header;
 #[cfg(test)]
 mod header_test;
+mod receipt;
+#[cfg(test)]
+mod receipt_test;
 mod state_diff;
 #[cfg(test)]
 mod state_diff_test;
+mod state_snapshot;
+#[cfg(test)]
+mod state_snapshot_test;
 #[cfg(test)]
 mod test;
 #[cfg(test)]
@@ -28,9 +34,11 @@ use apollo_protobuf::sync::{
     ClassQuery,
     DataOrFin,
     HeaderQuery,
+    ReceiptQuery,
     SignedBlockHeader,
     StateDiffChunk,
     StateDiffQuery,
+    StateTrieRoot,
     TransactionQuery,
 };
 use apollo_state_sync_types::state_sync_types::SyncBlock;
@@ -43,11 +51,14 @@ use futures::stream::BoxStream;
 use futures::{SinkExt as _, Stream};
 use header::HeaderStreamBuilder;
 use papyrus_common::pending_classes::ApiContractClass;
+use receipt::ReceiptStreamBuilder;
 use serde::{Deserialize, Serialize};
 use starknet_api::block::BlockNumber;
 use starknet_api::core::ClassHash;
-use starknet_api::transaction::FullTransaction;
+use starknet_api::transaction::{FullTransaction, TransactionHash, TransactionOutput};
+use starknet_types_core::felt::Felt;
 use state_diff::StateDiffStreamBuilder;
+pub use state_snapshot::StateSnapshotClient;
 use tokio_stream::StreamExt;
 use tracing::{info, instrument};
 use transaction::TransactionStreamFactory;
@@ -64,6 +75,7 @@ pub struct P2pSyncClientConfig {
     pub num_block_state_diffs_per_query: u64,
     pub num_block_transactions_per_query: u64,
     pub num_block_classes_per_query: u64,
+    pub num_block_receipts_per_query: u64,
     #[serde(deserialize_with = "deserialize_milliseconds_to_duration")]
     pub wait_period_for_new_data: Duration,
     #[serde(deserialize_with = "deserialize_milliseconds_to_duration")]
@@ -99,6 +111,12 @@ impl SerializeConfig for P2pSyncClientConfig {
                 "The maximum amount of block's classes to ask from peers in each iteration.",
                 ParamPrivacyInput::Public,
             ),
+            ser_param(
+                "num_block_receipts_per_query",
+                &self.num_block_receipts_per_query,
+                "The maximum amount of blocks to ask their receipts from peers in each iteration.",
+                ParamPrivacyInput::Public,
+            ),
             ser_param(
                 "wait_period_for_new_data",
                 &self.wait_period_for_new_data.as_millis(),
@@ -132,6 +150,7 @@ impl Default for P2pSyncClientConfig {
             num_block_state_diffs_per_query: 100,
             num_block_transactions_per_query: 100,
             num_block_classes_per_query: 100,
+            num_block_receipts_per_query: 100,
             wait_period_for_new_data: Duration::from_millis(50),
             wait_period_for_other_protocol: Duration::from_millis(50),
             // TODO(eitan): split this by protocol
@@ -155,18 +174,26 @@ pub enum P2pSyncClientError {
     StorageError(#[from] StorageError),
     #[error(transparent)]
     SendError(#[from] SendError),
+    #[error(
+        "Failed downloading the range of {root:?} from {start:?} of the state snapshot after \
+         {attempts} attempts."
+    )]
+    StateSnapshotRangeUnavailable { root: StateTrieRoot, start: Felt, attempts: usize },
 }
 
 type HeaderSqmrSender = SqmrClientSender<HeaderQuery, DataOrFin<SignedBlockHeader>>;
 type StateSqmrDiffSender = SqmrClientSender<StateDiffQuery, DataOrFin<StateDiffChunk>>;
 type TransactionSqmrSender = SqmrClientSender<TransactionQuery, DataOrFin<FullTransaction>>;
 type ClassSqmrSender = SqmrClientSender<ClassQuery, DataOrFin<(ApiContractClass, ClassHash)>>;
+type ReceiptSqmrSender =
+    SqmrClientSender<ReceiptQuery, DataOrFin<(TransactionOutput, TransactionHash)>>;
 
 pub struct P2pSyncClientChannels {
     header_sender: HeaderSqmrSender,
     state_diff_sender: StateSqmrDiffSender,
     transaction_sender: TransactionSqmrSender,
     class_sender: ClassSqmrSender,
+    receipt_sender: ReceiptSqmrSender,
 }
 
 impl P2pSyncClientChannels {
@@ -175,8 +202,9 @@ impl P2pSyncClientChannels {
         state_diff_sender: StateSqmrDiffSender,
         transaction_sender: TransactionSqmrSender,
         class_sender: ClassSqmrSender,
+        receipt_sender: ReceiptSqmrSender,
     ) -> Self {
-        Self { header_sender, state_diff_sender, transaction_sender, class_sender }
+        Self { header_sender, state_diff_sender, transaction_sender, class_sender, receipt_sender }
     }
     pub(crate) fn create_stream(
         self,
@@ -220,7 +248,22 @@ impl P2pSyncClientChannels {
             config.num_block_classes_per_query,
         );
 
-        header_stream.merge(state_diff_stream).merge(transaction_stream).merge(class_stream)
+        let receipt_stream = ReceiptStreamBuilder::create_stream(
+            self.receipt_sender,
+            storage_reader.clone(),
+            // Internal blocks don't contain the transaction outputs, so receipts are always
+            // downloaded from the network.
+            None,
+            config.wait_period_for_new_data,
+            config.wait_period_for_other_protocol,
+            config.num_block_receipts_per_query,
+        );
+
+        header_stream
+            .merge(state_diff_stream)
+            .merge(transaction_stream)
+            .merge(class_stream)
+            .merge(receipt_stream)
     }
 }
 
@@ -0,0 +1,115 @@
+use apollo_class_manager_types::SharedClassManagerClient;
+use apollo_network::network_manager::ClientResponsesManager;
+use apollo_protobuf::sync::DataOrFin;
+use apollo_state_sync_types::state_sync_types::SyncBlock;
+use apollo_storage::body::receipts::{ReceiptStorageReader, ReceiptStorageWriter};
+use apollo_storage::body::BodyStorageReader;
+use apollo_storage::{StorageError, StorageReader, StorageWriter};
+use futures::future::BoxFuture;
+use futures::{FutureExt, StreamExt};
+use starknet_api::block::BlockNumber;
+use starknet_api::transaction::{TransactionHash, TransactionOutput};
+
+use super::block_data_stream_builder::{
+    BadPeerError,
+    BlockData,
+    BlockDataStreamBuilder,
+    BlockNumberLimit,
+    ParseDataError,
+};
+use super::P2pSyncClientError;
+use crate::client::RESPONSE_TIMEOUT;
+
+impl BlockData for (Vec<TransactionOutput>, BlockNumber) {
+    fn write_to_storage<'a>(
+        self: Box<Self>,
+        storage_writer: &'a mut StorageWriter,
+        _class_manager_client: &'a mut SharedClassManagerClient,
+    ) -> BoxFuture<'a, Result<(), P2pSyncClientError>> {
+        async move {
+            storage_writer
+                .begin_rw_txn()?
+                .replace_block_transaction_outputs(self.1, self.0)?
+                .commit()?;
+            Ok(())
+        }
+        .boxed()
+    }
+}
+
+/// Completes the transaction outputs of the bodies downloaded by the transactions protocol, which
+/// don't contain the events of the transactions.
+pub(crate) struct ReceiptStreamBuilder;
+
+impl BlockDataStreamBuilder<(TransactionOutput, TransactionHash)> for ReceiptStreamBuilder {
+    type Output = (Vec<TransactionOutput>, BlockNumber);
+
+    const TYPE_DESCRIPTION: &'static str = "receipts";
+    const BLOCK_NUMBER_LIMIT: BlockNumberLimit = BlockNumberLimit::BodyMarker;
+
+    fn parse_data_for_block<'a>(
+        receipts_response_manager: &'a mut ClientResponsesManager<
+            DataOrFin<(TransactionOutput, TransactionHash)>,
+        >,
+        block_number: BlockNumber,
+        storage_reader: &'a StorageReader,
+    ) -> BoxFuture<'a, Result<Option<Self::Output>, ParseDataError>> {
+        async move {
+            let transaction_hashes = storage_reader
+                .begin_ro_txn()?
+                .get_block_transaction_hashes(block_number)?
+                .ok_or(StorageError::DBInconsistency {
+                    msg: format!(
+                        "The body of block {block_number}, which is lower than the body marker, \
+                         is missing."
+                    ),
+                })?;
+            let mut transaction_outputs = Vec::with_capacity(transaction_hashes.len());
+            for expected_transaction_hash in transaction_hashes.iter() {
+                let maybe_receipt =
+                    tokio::time::timeout(RESPONSE_TIMEOUT, receipts_response_manager.next())
+                        .await
+                        .map_err(|_| ParseDataError::BadPeer(BadPeerError::ResponseTimeout))?
+                        .ok_or(ParseDataError::BadPeer(BadPeerError::SessionEndedWithoutFin {
+                            type_description: Self::TYPE_DESCRIPTION,
+                        }))?;
+                let Some((transaction_output, transaction_hash)) = maybe_receipt?.0 else {
+                    if transaction_outputs.is_empty() {
+                        return Ok(None);
+                    } else {
+                        return Err(ParseDataError::BadPeer(BadPeerError::NotEnoughReceipts {
+                            expected: transaction_hashes.len(),
+                            actual: transaction_outputs.len(),
+                            block_number: block_number.0,
+                        }));
+                    }
+                };
+                if transaction_hash != *expected_transaction_hash {
+                    return Err(ParseDataError::BadPeer(
+                        BadPeerError::WrongReceiptTransactionHash {
+                            expected: *expected_transaction_hash,
+                            actual: transaction_hash,
+                        },
+                    ));
+                }
+                transaction_outputs.push(transaction_output);
+            }
+            Ok(Some((transaction_outputs, block_number)))
+        }
+        .boxed()
+    }
+
+    fn get_start_block_number(storage_reader: &StorageReader) -> Result<BlockNumber, StorageError> {
+        storage_reader.begin_ro_txn()?.get_receipt_marker()
+    }
+
+    // The receipt stream is created without an internal block receiver, because the transaction
+    // outputs of internal blocks are placeholders, and writing them would advance the receipt
+    // marker past blocks whose receipts were never downloaded.
+    fn convert_sync_block_to_block_data(
+        _block_number: BlockNumber,
+        _sync_block: SyncBlock,
+    ) -> (Vec<TransactionOutput>, BlockNumber) {
+        unreachable!("The receipt stream doesn't receive internal blocks.")
+    }
+}
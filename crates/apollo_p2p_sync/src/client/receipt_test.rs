@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+
+use apollo_protobuf::sync::{BlockHashOrNumber, DataOrFin, Direction, Query};
+use apollo_storage::body::BodyStorageReader;
+use apollo_test_utils::{get_rng, get_test_body};
+use futures::FutureExt;
+use starknet_api::block::{BlockBody, BlockNumber};
+use starknet_api::transaction::{FullTransaction, TransactionHash, TransactionOutput};
+
+use super::test_utils::{
+    random_header,
+    run_test,
+    wait_for_marker,
+    Action,
+    DataType,
+    SLEEP_DURATION_TO_LET_SYNC_ADVANCE,
+    TIMEOUT_FOR_TEST,
+};
+
+fn without_events(transaction_output: TransactionOutput) -> TransactionOutput {
+    match transaction_output {
+        TransactionOutput::Declare(mut output) => {
+            output.events.clear();
+            TransactionOutput::Declare(output)
+        }
+        TransactionOutput::Deploy(mut output) => {
+            output.events.clear();
+            TransactionOutput::Deploy(output)
+        }
+        TransactionOutput::DeployAccount(mut output) => {
+            output.events.clear();
+            TransactionOutput::DeployAccount(output)
+        }
+        TransactionOutput::Invoke(mut output) => {
+            output.events.clear();
+            TransactionOutput::Invoke(output)
+        }
+        TransactionOutput::L1Handler(mut output) => {
+            output.events.clear();
+            TransactionOutput::L1Handler(output)
+        }
+    }
+}
+
+#[tokio::test]
+async fn receipt_basic_flow() {
+    const NUM_BLOCKS: u64 = 2;
+    const NUM_TRANSACTIONS_PER_BLOCK: u64 = 3;
+    const RECEIPT_QUERY_LENGTH: u64 = 2;
+
+    let mut rng = get_rng();
+
+    let block_bodies = (0..NUM_BLOCKS)
+        .map(|i| {
+            let mut body =
+                get_test_body(NUM_TRANSACTIONS_PER_BLOCK.try_into().unwrap(), Some(2), None, None);
+            // get_test_body returns transaction hash in the range 0..num_transactions. We want to
+            // avoid collisions in transaction hash.
+            for transaction_hash in &mut body.transaction_hashes {
+                *transaction_hash =
+                    TransactionHash(transaction_hash.0 + NUM_TRANSACTIONS_PER_BLOCK * i);
+            }
+            body
+        })
+        .collect::<Vec<_>>();
+
+    let mut actions = vec![
+        Action::RunP2pSync,
+        // We already validate the header query content in other tests.
+        Action::ReceiveQuery(Box::new(|_query| ()), DataType::Header),
+    ];
+    for (i, block_body) in block_bodies.iter().enumerate() {
+        actions.push(Action::SendHeader(DataOrFin(Some(random_header(
+            &mut rng,
+            BlockNumber(i.try_into().unwrap()),
+            None,
+            Some(block_body.transactions.len()),
+        )))));
+    }
+    actions.push(Action::SendHeader(DataOrFin(None)));
+    actions.push(Action::CheckStorage(Box::new(move |reader| {
+        async move {
+            wait_for_marker(
+                DataType::Header,
+                &reader,
+                BlockNumber(NUM_BLOCKS),
+                SLEEP_DURATION_TO_LET_SYNC_ADVANCE,
+                TIMEOUT_FOR_TEST,
+            )
+            .await;
+        }
+        .boxed()
+    })));
+    actions.push(Action::SimulateWaitPeriodForOtherProtocol);
+
+    // The transactions protocol doesn't send the events of the transactions.
+    actions.push(Action::ReceiveQuery(Box::new(|_query| ()), DataType::Transaction));
+    for BlockBody { transactions, transaction_outputs, transaction_hashes } in &block_bodies {
+        for ((transaction, transaction_output), transaction_hash) in
+            transactions.iter().zip(transaction_outputs).zip(transaction_hashes)
+        {
+            actions.push(Action::SendTransaction(DataOrFin(Some(FullTransaction {
+                transaction: transaction.clone(),
+                transaction_output: without_events(transaction_output.clone()),
+                transaction_hash: *transaction_hash,
+            }))));
+        }
+    }
+    actions.push(Action::SendTransaction(DataOrFin(None)));
+    actions.push(Action::CheckStorage(Box::new(move |reader| {
+        async move {
+            wait_for_marker(
+                DataType::Transaction,
+                &reader,
+                BlockNumber(NUM_BLOCKS),
+                SLEEP_DURATION_TO_LET_SYNC_ADVANCE,
+                TIMEOUT_FOR_TEST,
+            )
+            .await;
+        }
+        .boxed()
+    })));
+    actions.push(Action::SimulateWaitPeriodForOtherProtocol);
+
+    actions.push(Action::ReceiveQuery(
+        Box::new(|query| {
+            assert_eq!(
+                query,
+                Query {
+                    start_block: BlockHashOrNumber::Number(BlockNumber(0)),
+                    direction: Direction::Forward,
+                    limit: RECEIPT_QUERY_LENGTH,
+                    step: 1,
+                }
+            )
+        }),
+        DataType::Receipt,
+    ));
+    for (i, block_body) in block_bodies.into_iter().enumerate() {
+        let block_number = BlockNumber(i.try_into().unwrap());
+        for (transaction_output, transaction_hash) in block_body
+            .transaction_outputs
+            .iter()
+            .cloned()
+            .zip(block_body.transaction_hashes.iter().cloned())
+        {
+            actions
+                .push(Action::SendReceipt(DataOrFin(Some((transaction_output, transaction_hash)))));
+        }
+
+        // Check that the events of the block were completed by its receipts.
+        actions.push(Action::CheckStorage(Box::new(move |reader| {
+            async move {
+                wait_for_marker(
+                    DataType::Receipt,
+                    &reader,
+                    block_number.unchecked_next(),
+                    SLEEP_DURATION_TO_LET_SYNC_ADVANCE,
+                    TIMEOUT_FOR_TEST,
+                )
+                .await;
+
+                let actual_events = reader
+                    .begin_ro_txn()
+                    .unwrap()
+                    .get_block_transaction_outputs(block_number)
+                    .unwrap()
+                    .unwrap()
+                    .iter()
+                    .map(|transaction_output| transaction_output.events().to_vec())
+                    .collect::<Vec<_>>();
+                let expected_events = block_body
+                    .transaction_outputs
+                    .iter()
+                    .map(|transaction_output| transaction_output.events().to_vec())
+                    .collect::<Vec<_>>();
+                assert_eq!(actual_events, expected_events);
+            }
+            .boxed()
+        })));
+    }
+    actions.push(Action::SendReceipt(DataOrFin(None)));
+
+    run_test(
+        HashMap::from([
+            (DataType::Header, NUM_BLOCKS),
+            (DataType::Transaction, NUM_BLOCKS),
+            (DataType::Receipt, RECEIPT_QUERY_LENGTH),
+        ]),
+        None,
+        actions,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn receipt_with_wrong_transaction_hash_reports_peer() {
+    const NUM_TRANSACTIONS: usize = 2;
+
+    let mut rng = get_rng();
+    let block_body = get_test_body(NUM_TRANSACTIONS, Some(1), None, None);
+
+    let mut actions = vec![
+        Action::RunP2pSync,
+        Action::ReceiveQuery(Box::new(|_query| ()), DataType::Header),
+        Action::SendHeader(DataOrFin(Some(random_header(
+            &mut rng,
+            BlockNumber(0),
+            None,
+            Some(NUM_TRANSACTIONS),
+        )))),
+        Action::SendHeader(DataOrFin(None)),
+        Action::CheckStorage(Box::new(|reader| {
+            async move {
+                wait_for_marker(
+                    DataType::Header,
+                    &reader,
+                    BlockNumber(1),
+                    SLEEP_DURATION_TO_LET_SYNC_ADVANCE,
+                    TIMEOUT_FOR_TEST,
+                )
+                .await;
+            }
+            .boxed()
+        })),
+        Action::SimulateWaitPeriodForOtherProtocol,
+        Action::ReceiveQuery(Box::new(|_query| ()), DataType::Transaction),
+    ];
+    for ((transaction, transaction_output), transaction_hash) in block_body
+        .transactions
+        .iter()
+        .zip(&block_body.transaction_outputs)
+        .zip(&block_body.transaction_hashes)
+    {
+        actions.push(Action::SendTransaction(DataOrFin(Some(FullTransaction {
+            transaction: transaction.clone(),
+            transaction_output: without_events(transaction_output.clone()),
+            transaction_hash: *transaction_hash,
+        }))));
+    }
+    actions.push(Action::CheckStorage(Box::new(|reader| {
+        async move {
+            wait_for_marker(
+                DataType::Transaction,
+                &reader,
+                BlockNumber(1),
+                SLEEP_DURATION_TO_LET_SYNC_ADVANCE,
+                TIMEOUT_FOR_TEST,
+            )
+            .await;
+        }
+        .boxed()
+    })));
+    actions.push(Action::SimulateWaitPeriodForOtherProtocol);
+
+    actions.push(Action::ReceiveQuery(Box::new(|_query| ()), DataType::Receipt));
+    actions.push(Action::SendReceipt(DataOrFin(Some((
+        block_body.transaction_outputs[0].clone(),
+        block_body.transaction_hashes[1],
+    )))));
+    actions.push(Action::ValidateReportSent(DataType::Receipt));
+
+    run_test(HashMap::new(), None, actions).await;
+}
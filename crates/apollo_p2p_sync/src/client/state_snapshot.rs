@@ -0,0 +1,293 @@
+use apollo_network::network_manager::{ClientResponsesManager, SqmrClientSender};
+use apollo_protobuf::sync::{
+    DataOrFin,
+    StateTrieLeaves,
+    StateTrieLeavesQuery,
+    StateTrieRoot,
+    TrieNode,
+};
+use apollo_storage::state_snapshot::{
+    SnapshotContract,
+    StateSnapshotStorageReader,
+    StateSnapshotStorageWriter,
+};
+use apollo_storage::StorageWriter;
+use futures::StreamExt;
+use starknet_api::core::ContractAddress;
+use starknet_api::hash::StarkHash;
+use starknet_api::state::StorageKey;
+use starknet_committer::block_committer::input::StarknetStorageValue;
+use starknet_committer::hash_function::hash::TreeHashFunctionImpl;
+use starknet_committer::patricia_merkle_tree::leaf::leaf_impl::ContractState;
+use starknet_patricia::hash::hash_trait::HashOutput;
+use starknet_patricia::patricia_merkle_tree::node_data::inner_node::{
+    BinaryData,
+    EdgeData,
+    EdgePath,
+    EdgePathLength,
+    NodeData,
+    PathToBottom,
+};
+use starknet_patricia::patricia_merkle_tree::node_data::leaf::Leaf;
+use starknet_patricia::patricia_merkle_tree::range_proof::{verify_leaf_range, LeafRange};
+use starknet_patricia::patricia_merkle_tree::updated_skeleton_tree::hash_function::TreeHashFunction;
+use starknet_types_core::felt::Felt;
+use tracing::{debug, info, warn};
+
+use super::block_data_stream_builder::{BadPeerError, ParseDataError};
+use super::{P2pSyncClientError, RESPONSE_TIMEOUT};
+
+type StateSnapshotSqmrSender = SqmrClientSender<StateTrieLeavesQuery, DataOrFin<StateTrieLeaves>>;
+type StateSnapshotResponsesManager = ClientResponsesManager<DataOrFin<StateTrieLeaves>>;
+
+/// The amount of times a range is queried before giving up on downloading the state snapshot.
+const MAX_ATTEMPTS_PER_RANGE: usize = 10;
+
+/// Downloads the state at a given contracts trie root from peers into the storage, in ranges of
+/// trie leaves, and verifies each range against the root before writing it. Peers that send
+/// invalid ranges are reported and the range is queried again, up to [`MAX_ATTEMPTS_PER_RANGE`]
+/// times.
+///
+/// Each range is committed once it's verified, and each trie is marked complete once all its
+/// leaves are written, so a download that was stopped in the middle skips the storage tries it
+/// already completed when it's resumed.
+///
+/// The node doesn't download state snapshots yet, since no node keeps the Patricia tries that
+/// [`StateSnapshotServer`](crate::server::StateSnapshotServer) serves them from.
+pub struct StateSnapshotClient<'a> {
+    sqmr_sender: &'a mut StateSnapshotSqmrSender,
+    num_leaves_per_query: u64,
+}
+
+impl<'a> StateSnapshotClient<'a> {
+    pub fn new(sqmr_sender: &'a mut StateSnapshotSqmrSender, num_leaves_per_query: u64) -> Self {
+        assert!(num_leaves_per_query > 0, "The number of leaves per query must be positive.");
+        Self { sqmr_sender, num_leaves_per_query }
+    }
+
+    /// Downloads all the contracts of the contracts trie with the given root and all their
+    /// storage into the storage.
+    pub async fn download_state(
+        &mut self,
+        contracts_trie_root: StarkHash,
+        storage_writer: &mut StorageWriter,
+    ) -> Result<(), P2pSyncClientError> {
+        if storage_writer.begin_rw_txn()?.is_snapshot_trie_complete(&contracts_trie_root)? {
+            info!("The state snapshot at {contracts_trie_root:?} was already downloaded.");
+            return Ok(());
+        }
+        let root = StateTrieRoot::ContractsTrie(contracts_trie_root);
+        let mut num_contracts = 0;
+        let mut start = Some(Felt::ZERO);
+        while let Some(range_start) = start {
+            let (contracts, next_start) = self
+                .download_range::<ContractState>(root, range_start, get_contract_leaves)
+                .await?;
+            let contracts = contracts
+                .into_iter()
+                .map(|(address, contract_state)| {
+                    (
+                        ContractAddress::try_from(address)
+                            .expect("Verified trie leaves have valid keys."),
+                        SnapshotContract {
+                            class_hash: contract_state.class_hash,
+                            nonce: contract_state.nonce,
+                            storage_root: contract_state.storage_root_hash.0,
+                        },
+                    )
+                })
+                .collect::<Vec<_>>();
+            storage_writer.begin_rw_txn()?.append_snapshot_contracts(&contracts)?.commit()?;
+            // Contracts often share the same storage, so each storage trie is downloaded once.
+            for (_, contract) in &contracts {
+                self.download_contract_storage(contract.storage_root, storage_writer).await?;
+            }
+            num_contracts += contracts.len();
+            start = next_start;
+        }
+        storage_writer
+            .begin_rw_txn()?
+            .mark_snapshot_trie_complete(&contracts_trie_root)?
+            .commit()?;
+        info!("Downloaded {num_contracts} contracts of the state snapshot.");
+        Ok(())
+    }
+
+    /// Downloads all the leaves of the contract storage trie with the given root into the
+    /// storage, unless they were already downloaded.
+    pub async fn download_contract_storage(
+        &mut self,
+        storage_root: StarkHash,
+        storage_writer: &mut StorageWriter,
+    ) -> Result<(), P2pSyncClientError> {
+        // The root of an empty trie is zero.
+        if storage_root == Felt::ZERO
+            || storage_writer.begin_rw_txn()?.is_snapshot_trie_complete(&storage_root)?
+        {
+            return Ok(());
+        }
+        let root = StateTrieRoot::ContractStorage(storage_root);
+        let mut start = Some(Felt::ZERO);
+        while let Some(range_start) = start {
+            let (storage, next_start) = self
+                .download_range::<StarknetStorageValue>(root, range_start, get_storage_leaves)
+                .await?;
+            let storage = storage
+                .into_iter()
+                .map(|(key, value)| {
+                    (
+                        StorageKey::try_from(key).expect("Verified trie leaves have valid keys."),
+                        value.0,
+                    )
+                })
+                .collect::<Vec<_>>();
+            storage_writer
+                .begin_rw_txn()?
+                .append_snapshot_storage(&storage_root, &storage)?
+                .commit()?;
+            start = next_start;
+        }
+        storage_writer.begin_rw_txn()?.mark_snapshot_trie_complete(&storage_root)?.commit()?;
+        Ok(())
+    }
+
+    /// Downloads and verifies the range of leaves that starts at `start`. Returns the leaves and
+    /// the start of the next range, or None if this is the last range of the trie.
+    async fn download_range<L>(
+        &mut self,
+        root: StateTrieRoot,
+        start: Felt,
+        get_leaves: fn(StateTrieLeaves) -> Vec<(Felt, L)>,
+    ) -> Result<(Vec<(Felt, L)>, Option<Felt>), P2pSyncClientError>
+    where
+        L: Leaf,
+        TreeHashFunctionImpl: TreeHashFunction<L>,
+    {
+        let root_hash = match root {
+            StateTrieRoot::ContractsTrie(root_hash) | StateTrieRoot::ContractStorage(root_hash) => {
+                HashOutput(root_hash)
+            }
+        };
+        for _ in 0..MAX_ATTEMPTS_PER_RANGE {
+            let query = StateTrieLeavesQuery { root, start, limit: self.num_leaves_per_query };
+            let mut client_response_manager = self.sqmr_sender.send_new_query(query).await?;
+            let (range, limit) = match parse_range::<L>(
+                &mut client_response_manager,
+                self.num_leaves_per_query,
+                get_leaves,
+            )
+            .await
+            {
+                Ok(range_and_limit) => range_and_limit,
+                Err(ParseDataError::BadPeer(err)) => {
+                    warn!(
+                        "State snapshot query for {root:?} from {start:?} returned with bad peer \
+                         error: {err:?}. Reporting peer and retrying query."
+                    );
+                    client_response_manager.report_peer();
+                    continue;
+                }
+                Err(ParseDataError::Fatal(err)) => return Err(err),
+            };
+            if let Err(err) =
+                verify_leaf_range::<L, TreeHashFunctionImpl>(root_hash, start, limit, &range)
+            {
+                warn!(
+                    "State snapshot query for {root:?} from {start:?} returned an invalid range: \
+                     {err:?}. Reporting peer and retrying query."
+                );
+                client_response_manager.report_peer();
+                continue;
+            }
+            debug!("Downloaded {} leaves of {root:?} from {start:?}.", range.leaves.len());
+            let next_start = range.next_start(limit);
+            return Ok((range.leaves, next_start));
+        }
+        Err(P2pSyncClientError::StateSnapshotRangeUnavailable {
+            root,
+            start,
+            attempts: MAX_ATTEMPTS_PER_RANGE,
+        })
+    }
+}
+
+fn get_contract_leaves(leaves: StateTrieLeaves) -> Vec<(Felt, ContractState)> {
+    leaves
+        .contracts
+        .into_iter()
+        .map(|contract| {
+            (
+                *contract.address.0.key(),
+                ContractState {
+                    nonce: contract.nonce,
+                    storage_root_hash: HashOutput(contract.storage_root),
+                    class_hash: contract.class_hash,
+                },
+            )
+        })
+        .collect()
+}
+
+fn get_storage_leaves(leaves: StateTrieLeaves) -> Vec<(Felt, StarknetStorageValue)> {
+    leaves
+        .storage
+        .into_iter()
+        .map(|(key, value)| (*key.0.key(), StarknetStorageValue(value)))
+        .collect()
+}
+
+/// Returns the range in the response and the number of leaves it should have if it's not the last
+/// range of the trie, which may be lower than the query's limit.
+async fn parse_range<L: Leaf>(
+    client_response_manager: &mut StateSnapshotResponsesManager,
+    query_limit: u64,
+    get_leaves: fn(StateTrieLeaves) -> Vec<(Felt, L)>,
+) -> Result<(LeafRange<L>, usize), ParseDataError> {
+    let maybe_leaves = tokio::time::timeout(RESPONSE_TIMEOUT, client_response_manager.next())
+        .await
+        .map_err(|_| BadPeerError::ResponseTimeout)?;
+    let Some(leaves) = maybe_leaves
+        .ok_or(BadPeerError::SessionEndedWithoutFin { type_description: "state snapshot" })??
+        .0
+    else {
+        return Err(BadPeerError::MissingStateTrieLeaves.into());
+    };
+    // Each query is answered with a single message of leaves.
+    match tokio::time::timeout(RESPONSE_TIMEOUT, client_response_manager.next())
+        .await
+        .map_err(|_| BadPeerError::ResponseTimeout)?
+    {
+        Some(Ok(DataOrFin(None))) => {}
+        Some(_) => return Err(BadPeerError::StateTrieLeavesWithoutFin.into()),
+        None => {
+            return Err(BadPeerError::SessionEndedWithoutFin {
+                type_description: "state snapshot",
+            }
+            .into());
+        }
+    }
+    if leaves.limit > query_limit {
+        return Err(
+            BadPeerError::InvalidStateTrieLeavesLimit { limit: leaves.limit, query_limit }.into()
+        );
+    }
+    let limit = usize::try_from(leaves.limit).expect("Failed converting u64 to usize.");
+    let proof = leaves.proof.iter().map(to_node_data).collect::<Result<_, _>>()?;
+    Ok((LeafRange { leaves: get_leaves(leaves), proof }, limit))
+}
+
+fn to_node_data<L: Leaf>(trie_node: &TrieNode) -> Result<NodeData<L>, BadPeerError> {
+    match trie_node {
+        TrieNode::Binary { left, right } => Ok(NodeData::Binary(BinaryData {
+            left_hash: HashOutput(*left),
+            right_hash: HashOutput(*right),
+        })),
+        TrieNode::Edge { bottom, path, length } => {
+            let path_to_bottom = EdgePathLength::new(*length)
+                .ok()
+                .and_then(|length| PathToBottom::new(EdgePath::from(*path), length).ok())
+                .ok_or(BadPeerError::InvalidTrieNode { trie_node: trie_node.clone() })?;
+            Ok(NodeData::Edge(EdgeData { bottom_hash: HashOutput(*bottom), path_to_bottom }))
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use apollo_network::network_manager::test_utils::{
+    create_test_server_query_manager,
+    mock_register_sqmr_protocol_client,
+    mock_register_sqmr_protocol_server,
+};
+use apollo_protobuf::sync::{DataOrFin, StateTrieLeaves, StateTrieLeavesQuery, StateTrieRoot};
+use apollo_storage::state_snapshot::StateSnapshotStorageReader;
+use apollo_storage::test_utils::get_test_storage;
+use apollo_storage::StorageReader;
+use assert_matches::assert_matches;
+use futures::{SinkExt, StreamExt};
+use starknet_api::hash::StarkHash;
+use starknet_api::state::StorageKey;
+use starknet_committer::block_committer::input::StarknetStorageValue;
+use starknet_committer::hash_function::hash::TreeHashFunctionImpl;
+use starknet_committer::patricia_merkle_tree::tree::OriginalSkeletonStorageTrieConfig;
+use starknet_patricia::hash::hash_trait::HashOutput;
+use starknet_patricia::patricia_merkle_tree::external_test_utils::tree_computation_flow;
+use starknet_patricia::patricia_merkle_tree::filled_tree::tree::FilledTree;
+use starknet_patricia::patricia_merkle_tree::node_data::leaf::LeafModifications;
+use starknet_patricia::patricia_merkle_tree::types::NodeIndex;
+use starknet_patricia_storage::map_storage::MapStorage;
+use starknet_types_core::felt::Felt;
+
+use super::StateSnapshotClient;
+use crate::client::P2pSyncClientError;
+use crate::server::StateSnapshotServer;
+use crate::BUFFER_SIZE;
+
+const NUM_LEAVES_PER_QUERY: u64 = 2;
+const TIMEOUT_FOR_TEST: Duration = Duration::from_secs(5);
+
+async fn create_storage_trie(leaves: &[(StorageKey, Felt)]) -> (MapStorage, StarkHash) {
+    let leaf_modifications: LeafModifications<StarknetStorageValue> = leaves
+        .iter()
+        .map(|(key, value)| (NodeIndex::from_leaf_felt(key.0.key()), StarknetStorageValue(*value)))
+        .collect();
+    let filled_tree = tree_computation_flow::<StarknetStorageValue, TreeHashFunctionImpl>(
+        leaf_modifications,
+        &MapStorage::default(),
+        HashOutput(Felt::ZERO),
+        OriginalSkeletonStorageTrieConfig::new(false),
+    )
+    .await;
+    (MapStorage { storage: filled_tree.serialize() }, filled_tree.get_root_hash().0)
+}
+
+#[tokio::test]
+async fn download_contract_storage_from_honest_peer() {
+    let DownloadResult { result, root, storage_reader } =
+        download_contract_storage(0, NUM_LEAVES_PER_QUERY).await;
+    result.unwrap();
+    assert_contract_storage_written(&storage_reader, root);
+}
+
+#[tokio::test]
+async fn download_contract_storage_reports_peer_on_invalid_range() {
+    let DownloadResult { result, root, storage_reader } =
+        download_contract_storage(1, NUM_LEAVES_PER_QUERY).await;
+    result.unwrap();
+    assert_contract_storage_written(&storage_reader, root);
+}
+
+#[tokio::test]
+async fn download_contract_storage_from_peer_with_lower_limit() {
+    let DownloadResult { result, root, storage_reader } =
+        download_contract_storage(0, NUM_LEAVES_PER_QUERY - 1).await;
+    result.unwrap();
+    assert_contract_storage_written(&storage_reader, root);
+}
+
+#[tokio::test]
+async fn download_contract_storage_fails_after_max_attempts() {
+    let DownloadResult { result, root, storage_reader } =
+        download_contract_storage(usize::MAX, NUM_LEAVES_PER_QUERY).await;
+    assert_matches!(
+        result,
+        Err(P2pSyncClientError::StateSnapshotRangeUnavailable { start, .. }) if start == Felt::ZERO
+    );
+    assert!(!storage_reader.begin_ro_txn().unwrap().is_snapshot_trie_complete(&root).unwrap());
+}
+
+fn storage_leaves() -> Vec<(StorageKey, Felt)> {
+    [1_u8, 2, 30, 200, 201]
+        .into_iter()
+        .map(|key| (StorageKey::from(key), Felt::from(key) + Felt::ONE))
+        .collect()
+}
+
+fn assert_contract_storage_written(storage_reader: &StorageReader, root: StarkHash) {
+    let txn = storage_reader.begin_ro_txn().unwrap();
+    assert!(txn.is_snapshot_trie_complete(&root).unwrap());
+    for (key, value) in storage_leaves() {
+        assert_eq!(txn.get_snapshot_storage_at(&root, &key).unwrap(), Some(value));
+    }
+}
+
+struct DownloadResult {
+    result: Result<(), P2pSyncClientError>,
+    root: StarkHash,
+    storage_reader: StorageReader,
+}
+
+/// Downloads a storage trie from a server that serves at most `server_max_leaves_per_query` leaves
+/// per query and whose first `num_tampered_responses` responses are tampered.
+async fn download_contract_storage(
+    num_tampered_responses: usize,
+    server_max_leaves_per_query: u64,
+) -> DownloadResult {
+    let (storage, root) = create_storage_trie(&storage_leaves()).await;
+    let ((storage_reader, mut storage_writer), temp_dir) = get_test_storage();
+
+    let (server_receiver, mut server_query_sender) = mock_register_sqmr_protocol_server::<
+        StateTrieLeavesQuery,
+        DataOrFin<StateTrieLeaves>,
+    >(BUFFER_SIZE);
+    let server =
+        StateSnapshotServer::new(Arc::new(storage), server_receiver, server_max_leaves_per_query);
+    tokio::spawn(server.run());
+
+    let (mut client_sender, mut client_queries_receiver) = mock_register_sqmr_protocol_client::<
+        StateTrieLeavesQuery,
+        DataOrFin<StateTrieLeaves>,
+    >(BUFFER_SIZE);
+    let download = tokio::spawn(async move {
+        // Keeps the storage directory until the download is done.
+        let _temp_dir = temp_dir;
+        StateSnapshotClient::new(&mut client_sender, NUM_LEAVES_PER_QUERY)
+            .download_contract_storage(root, &mut storage_writer)
+            .await
+    });
+
+    // Passes the queries of the client to the server and the server's responses to the client.
+    let mut num_tampered_responses = num_tampered_responses;
+    let result = loop {
+        // The client stops sending queries once it's done, and then its sender is dropped.
+        let Some(mut client_responses_manager) = client_queries_receiver.next().await else {
+            break download.await.unwrap();
+        };
+        let query = client_responses_manager.query().clone().unwrap();
+        assert_eq!(query.root, StateTrieRoot::ContractStorage(root));
+        assert_eq!(query.limit, NUM_LEAVES_PER_QUERY);
+        let (server_query_manager, _report_receiver, mut server_responses_receiver) =
+            create_test_server_query_manager(query);
+        server_query_sender.send(server_query_manager).await.unwrap();
+        let tamper = num_tampered_responses > 0;
+        while let Some(mut response) = server_responses_receiver.next().await {
+            if let DataOrFin(Some(state_trie_leaves)) = &mut response {
+                if tamper {
+                    state_trie_leaves.storage[0].1 = Felt::from(1234_u16);
+                }
+            }
+            client_responses_manager.send_response(response).await.unwrap();
+        }
+        if tamper {
+            num_tampered_responses -= 1;
+            client_responses_manager.assert_reported(TIMEOUT_FOR_TEST).await;
+        }
+    };
+    DownloadResult { result, root, storage_reader }
+}
@@ -1,8 +1,9 @@
 use std::collections::HashMap;
 use std::vec;
 
-use apollo_protobuf::sync::DataOrFin;
+use apollo_protobuf::sync::{BlockHashOrNumber, DataOrFin};
 use apollo_state_sync_types::state_sync_types::SyncBlock;
+use apollo_storage::body::receipts::ReceiptStorageReader;
 use apollo_storage::body::BodyStorageReader;
 use apollo_storage::header::HeaderStorageReader;
 use apollo_storage::state::StateStorageReader;
@@ -100,6 +101,51 @@ async fn receive_block_internally() {
     .await;
 }
 
+#[tokio::test]
+async fn internal_block_receipts_are_downloaded_from_the_network() {
+    let sync_block = create_random_sync_block(BlockNumber(0), 1, 0, get_rng());
+
+    run_test(
+        HashMap::from([(DataType::Receipt, 1)]),
+        None,
+        vec![
+            Action::SendInternalBlock(sync_block),
+            Action::RunP2pSync,
+            Action::CheckStorage(Box::new(|reader| {
+                async move {
+                    wait_for_marker(
+                        DataType::Transaction,
+                        &reader,
+                        BlockNumber(1),
+                        SLEEP_DURATION_TO_LET_SYNC_ADVANCE,
+                        TIMEOUT_FOR_TEST,
+                    )
+                    .await;
+                }
+                .boxed()
+            })),
+            // The placeholder transaction outputs of the internal block don't complete its
+            // receipts, so they're queried from the network.
+            Action::ReceiveQuery(
+                Box::new(|query| {
+                    assert_eq!(query.start_block, BlockHashOrNumber::Number(BlockNumber(0)))
+                }),
+                DataType::Receipt,
+            ),
+            Action::CheckStorage(Box::new(|reader| {
+                async move {
+                    assert_eq!(
+                        reader.begin_ro_txn().unwrap().get_receipt_marker().unwrap(),
+                        BlockNumber(0)
+                    );
+                }
+                .boxed()
+            })),
+        ],
+    )
+    .await;
+}
+
 #[tokio::test]
 async fn receive_blocks_out_of_order() {
     let mut rng = get_rng();
@@ -15,12 +15,14 @@ use apollo_protobuf::sync::{
     DataOrFin,
     HeaderQuery,
     Query,
+    ReceiptQuery,
     SignedBlockHeader,
     StateDiffChunk,
     StateDiffQuery,
     TransactionQuery,
 };
 use apollo_state_sync_types::state_sync_types::SyncBlock;
+use apollo_storage::body::receipts::ReceiptStorageReader;
 use apollo_storage::body::BodyStorageReader;
 use apollo_storage::class_manager::ClassManagerStorageReader;
 use apollo_storage::header::HeaderStorageReader;
@@ -45,7 +47,7 @@ use starknet_api::block::{
 use starknet_api::core::ClassHash;
 use starknet_api::crypto::utils::Signature;
 use starknet_api::hash::StarkHash;
-use starknet_api::transaction::FullTransaction;
+use starknet_api::transaction::{FullTransaction, TransactionHash, TransactionOutput};
 use starknet_types_core::felt::Felt;
 use tokio::sync::oneshot;
 
@@ -57,6 +59,7 @@ pub const HEADER_QUERY_LENGTH: u64 = 5;
 pub const STATE_DIFF_QUERY_LENGTH: u64 = 3;
 pub const CLASS_DIFF_QUERY_LENGTH: u64 = 3;
 pub const TRANSACTION_QUERY_LENGTH: u64 = 3;
+pub const RECEIPT_QUERY_LENGTH: u64 = 3;
 pub const SLEEP_DURATION_TO_LET_SYNC_ADVANCE: Duration = Duration::from_millis(10);
 pub const WAIT_PERIOD_FOR_NEW_DATA: Duration = Duration::from_secs(1);
 pub const WAIT_PERIOD_FOR_OTHER_PROTOCOL: Duration = Duration::from_secs(1);
@@ -69,6 +72,7 @@ lazy_static! {
         num_block_state_diffs_per_query: STATE_DIFF_QUERY_LENGTH,
         num_block_transactions_per_query: TRANSACTION_QUERY_LENGTH,
         num_block_classes_per_query: CLASS_DIFF_QUERY_LENGTH,
+        num_block_receipts_per_query: RECEIPT_QUERY_LENGTH,
         wait_period_for_new_data: WAIT_PERIOD_FOR_NEW_DATA,
         wait_period_for_other_protocol: WAIT_PERIOD_FOR_OTHER_PROTOCOL,
         buffer_size: BUFFER_SIZE,
@@ -82,6 +86,8 @@ pub(crate) type TransactionTestPayload =
     MockClientResponsesManager<TransactionQuery, DataOrFin<FullTransaction>>;
 pub(crate) type ClassTestPayload =
     MockClientResponsesManager<ClassQuery, DataOrFin<(ApiContractClass, ClassHash)>>;
+pub(crate) type ReceiptTestPayload =
+    MockClientResponsesManager<ReceiptQuery, DataOrFin<(TransactionOutput, TransactionHash)>>;
 
 // TODO(Eitan): Use SqmrSubscriberChannels once there is a utility function for testing
 pub struct TestArgs {
@@ -94,6 +100,8 @@ pub struct TestArgs {
     pub mock_transaction_response_manager: GenericReceiver<TransactionTestPayload>,
     #[allow(dead_code)]
     pub mock_class_response_manager: GenericReceiver<ClassTestPayload>,
+    #[allow(dead_code)]
+    pub mock_receipt_response_manager: GenericReceiver<ReceiptTestPayload>,
 }
 
 pub fn setup() -> TestArgs {
@@ -108,11 +116,14 @@ pub fn setup() -> TestArgs {
         mock_register_sqmr_protocol_client(buffer_size);
     let (class_sender, mock_class_response_manager) =
         mock_register_sqmr_protocol_client(buffer_size);
+    let (receipt_sender, mock_receipt_response_manager) =
+        mock_register_sqmr_protocol_client(buffer_size);
     let p2p_sync_channels = P2pSyncClientChannels {
         header_sender,
         state_diff_sender,
         transaction_sender,
         class_sender,
+        receipt_sender,
     };
     let class_manager_client = Arc::new(MockClassManagerClient::new());
     let p2p_sync = P2pSyncClient::new(
@@ -130,6 +141,7 @@ pub fn setup() -> TestArgs {
         mock_state_diff_response_manager,
         mock_transaction_response_manager,
         mock_class_response_manager,
+        mock_receipt_response_manager,
     }
 }
 
@@ -141,6 +153,7 @@ pub enum DataType {
     StateDiff,
     #[allow(dead_code)]
     Class,
+    Receipt,
 }
 
 pub enum Action {
@@ -160,6 +173,9 @@ pub enum Action {
     /// Send a class as a response to a query we got from ReceiveQuery. Will panic if didn't
     /// call ReceiveQuery with DataType::Class before.
     SendClass(DataOrFin<(ApiContractClass, ClassHash)>),
+    /// Send a receipt as a response to a query we got from ReceiveQuery. Will panic if didn't
+    /// call ReceiveQuery with DataType::Receipt before.
+    SendReceipt(DataOrFin<(TransactionOutput, TransactionHash)>),
     /// Perform custom validations on the storage. Returns back the storage reader it received as
     /// input
     CheckStorage(Box<dyn FnOnce(StorageReader) -> BoxFuture<'static, ()>>),
@@ -191,6 +207,10 @@ pub async fn run_test(
             .cloned()
             .unwrap_or(1),
         num_block_classes_per_query: max_query_lengths.get(&DataType::Class).cloned().unwrap_or(1),
+        num_block_receipts_per_query: max_query_lengths
+            .get(&DataType::Receipt)
+            .cloned()
+            .unwrap_or(1),
         wait_period_for_new_data: WAIT_PERIOD_FOR_NEW_DATA,
         wait_period_for_other_protocol: WAIT_PERIOD_FOR_OTHER_PROTOCOL,
         buffer_size: BUFFER_SIZE,
@@ -205,11 +225,14 @@ pub async fn run_test(
     let (transaction_sender, mut mock_transaction_network) =
         mock_register_sqmr_protocol_client(buffer_size);
     let (class_sender, mut mock_class_network) = mock_register_sqmr_protocol_client(buffer_size);
+    let (receipt_sender, mut mock_receipt_network) =
+        mock_register_sqmr_protocol_client(buffer_size);
     let p2p_sync_channels = P2pSyncClientChannels {
         header_sender,
         state_diff_sender,
         transaction_sender,
         class_sender,
+        receipt_sender,
     };
     let (mut internal_block_sender, internal_block_receiver) = mpsc::channel(buffer_size);
     let p2p_sync = P2pSyncClient::new(
@@ -225,6 +248,7 @@ pub async fn run_test(
     let mut state_diff_current_query_responses_manager = None;
     let mut transaction_current_query_responses_manager = None;
     let mut class_current_query_responses_manager = None;
+    let mut receipt_current_query_responses_manager = None;
 
     let (sync_future_sender, sync_future_receiver) = oneshot::channel();
     let mut sync_future_sender = Some(sync_future_sender);
@@ -259,6 +283,12 @@ pub async fn run_test(
                                     &mut class_current_query_responses_manager,
                                 ).await.0
                             }
+                            DataType::Receipt => {
+                                get_next_query_and_update_responses_manager(
+                                    &mut mock_receipt_network,
+                                    &mut receipt_current_query_responses_manager,
+                                ).await.0
+                            }
                         };
                         validate_query_fn(query);
                     }
@@ -282,6 +312,11 @@ pub async fn run_test(
                             .expect("Called SendClass without calling ReceiveQuery");
                         responses_manager.send_response(class_or_fin).await.unwrap();
                     }
+                    Action::SendReceipt(receipt_or_fin) => {
+                        let responses_manager = receipt_current_query_responses_manager.as_mut()
+                            .expect("Called SendReceipt without calling ReceiveQuery");
+                        responses_manager.send_response(receipt_or_fin).await.unwrap();
+                    }
                     Action::CheckStorage(check_storage_fn) => {
                         // We tried avoiding the clone here but it causes lifetime issues.
                         check_storage_fn(storage_reader.clone()).await;
@@ -314,6 +349,13 @@ pub async fn run_test(
                                 data type");
                         responses_manager.assert_reported(TIMEOUT_FOR_TEST).await;
                     }
+                    Action::ValidateReportSent(DataType::Receipt) => {
+                        let responses_manager = receipt_current_query_responses_manager.take()
+                            .expect(
+                                "Called ValidateReportSent without calling ReceiveQuery on the same
+                                data type");
+                        responses_manager.assert_reported(TIMEOUT_FOR_TEST).await;
+                    }
                     Action::SendInternalBlock(sync_block) => {
                         internal_block_sender.send(sync_block).await.unwrap();
                     }
@@ -405,6 +447,7 @@ pub(crate) async fn wait_for_marker(
             DataType::Transaction => txn.get_body_marker().unwrap(),
             DataType::StateDiff => txn.get_state_marker().unwrap(),
             DataType::Class => txn.get_class_manager_block_marker().unwrap(),
+            DataType::Receipt => txn.get_receipt_marker().unwrap(),
         };
 
         if storage_marker >= expected_marker {
@@ -1,11 +1,20 @@
 pub mod client;
 pub mod server;
 
+use std::sync::Arc;
+
+use apollo_network::network_manager::{SqmrClientProtocolVersion, SqmrServerProtocolVersion};
+use apollo_protobuf::converters::sync_protocol_version::{
+    SyncProtocolVersion,
+    VersionedSyncMessage,
+};
+use apollo_protobuf::converters::ProtobufConversionError;
 use enum_iterator::Sequence;
 
 pub const BUFFER_SIZE: usize = 100000;
 
-/// The p2p sync protocol names needed for negotiation, as they appear in the p2p specs
+/// The p2p sync protocols. Each protocol is negotiated in one of the supported versions, with names
+/// as they appear in the p2p specs.
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Sequence)]
 pub enum Protocol {
     SignedBlockHeader,
@@ -13,22 +22,67 @@ pub enum Protocol {
     Transaction,
     Class,
     Event,
+    StateSnapshot,
+    Receipt,
 }
 
 impl Protocol {
-    pub fn as_str(&self) -> &'static str {
+    fn data_type(&self) -> &'static str {
         match self {
-            Protocol::SignedBlockHeader => "/starknet/headers/0.1.0-rc.0",
-            Protocol::StateDiff => "/starknet/state_diffs/0.1.0-rc.0",
-            Protocol::Transaction => "/starknet/transactions/0.1.0-rc.0",
-            Protocol::Class => "/starknet/classes/0.1.0-rc.0",
-            Protocol::Event => "/starknet/events/0.1.0-rc.0",
+            Protocol::SignedBlockHeader => "headers",
+            Protocol::StateDiff => "state_diffs",
+            Protocol::Transaction => "transactions",
+            Protocol::Class => "classes",
+            Protocol::Event => "events",
+            Protocol::StateSnapshot => "state_snapshots",
+            Protocol::Receipt => "receipts",
         }
     }
-}
 
-impl From<Protocol> for String {
-    fn from(protocol: Protocol) -> String {
-        protocol.as_str().to_string()
+    /// The name of the protocol in the given version, e.g `/starknet/headers/0.1.0-rc.0`.
+    pub fn versioned_name(&self, version: SyncProtocolVersion) -> String {
+        format!("/starknet/{}/{}", self.data_type(), version.as_str())
+    }
+
+    /// The supported versions of the protocol for an SQMR client, ordered from the most preferred
+    /// to the least preferred.
+    pub fn client_versions<Query, Response>(
+        &self,
+    ) -> Vec<SqmrClientProtocolVersion<Query, Response>>
+    where
+        Query: VersionedSyncMessage + 'static,
+        Response:
+            VersionedSyncMessage + TryFrom<Vec<u8>, Error = ProtobufConversionError> + 'static,
+    {
+        SyncProtocolVersion::SUPPORTED
+            .into_iter()
+            .map(|version| SqmrClientProtocolVersion {
+                protocol: self.versioned_name(version),
+                encode_query: Arc::new(move |query: Query| query.encode_for_version(version)),
+                decode_response: Arc::new(move |response| {
+                    Response::decode_for_version(response, version)
+                }),
+            })
+            .collect()
+    }
+
+    /// The supported versions of the protocol for an SQMR server.
+    pub fn server_versions<Query, Response>(
+        &self,
+    ) -> Vec<SqmrServerProtocolVersion<Query, Response>>
+    where
+        Query: VersionedSyncMessage + TryFrom<Vec<u8>, Error = ProtobufConversionError> + 'static,
+        Response: VersionedSyncMessage + 'static,
+    {
+        SyncProtocolVersion::SUPPORTED
+            .into_iter()
+            .map(|version| SqmrServerProtocolVersion {
+                protocol: self.versioned_name(version),
+                decode_query: Arc::new(move |query| Query::decode_for_version(query, version)),
+                encode_response: Arc::new(move |response: Response| {
+                    response.encode_for_version(version)
+                }),
+            })
+            .collect()
     }
 }
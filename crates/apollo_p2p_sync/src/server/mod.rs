@@ -13,6 +13,7 @@ use apollo_protobuf::sync::{
     EventQuery,
     HeaderQuery,
     Query,
+    ReceiptQuery,
     SignedBlockHeader,
     StateDiffChunk,
     StateDiffQuery,
@@ -31,14 +32,17 @@ use starknet_api::block::BlockNumber;
 use starknet_api::contract_class::ContractClass;
 use starknet_api::core::ClassHash;
 use starknet_api::state::ThinStateDiff;
-use starknet_api::transaction::{Event, FullTransaction, TransactionHash};
+use starknet_api::transaction::{Event, FullTransaction, TransactionHash, TransactionOutput};
 use tracing::{debug, error, info};
 
 #[cfg(test)]
 mod test;
 
+mod state_snapshot;
 mod utils;
 
+pub use state_snapshot::{StateSnapshotServer, StateSnapshotServerError};
+
 #[derive(thiserror::Error, Debug)]
 pub enum P2pSyncServerError {
     #[error(transparent)]
@@ -79,6 +83,8 @@ type StateDiffReceiver = SqmrServerReceiver<StateDiffQuery, DataOrFin<StateDiffC
 type TransactionReceiver = SqmrServerReceiver<TransactionQuery, DataOrFin<FullTransaction>>;
 type ClassReceiver = SqmrServerReceiver<ClassQuery, DataOrFin<(ApiContractClass, ClassHash)>>;
 type EventReceiver = SqmrServerReceiver<EventQuery, DataOrFin<(Event, TransactionHash)>>;
+type ReceiptReceiver =
+    SqmrServerReceiver<ReceiptQuery, DataOrFin<(TransactionOutput, TransactionHash)>>;
 
 pub struct P2pSyncServerChannels {
     header_receiver: HeaderReceiver,
@@ -86,6 +92,7 @@ pub struct P2pSyncServerChannels {
     transaction_receiver: TransactionReceiver,
     class_receiver: ClassReceiver,
     event_receiver: EventReceiver,
+    receipt_receiver: ReceiptReceiver,
 }
 
 impl P2pSyncServerChannels {
@@ -95,6 +102,7 @@ impl P2pSyncServerChannels {
         transaction_receiver: TransactionReceiver,
         class_receiver: ClassReceiver,
         event_receiver: EventReceiver,
+        receipt_receiver: ReceiptReceiver,
     ) -> Self {
         Self {
             header_receiver,
@@ -102,6 +110,7 @@ impl P2pSyncServerChannels {
             transaction_receiver,
             class_receiver,
             event_receiver,
+            receipt_receiver,
         }
     }
 }
@@ -121,6 +130,7 @@ impl P2pSyncServer {
             mut transaction_receiver,
             mut class_receiver,
             mut event_receiver,
+            mut receipt_receiver,
         } = self.p2p_sync_channels;
         loop {
             tokio::select! {
@@ -154,6 +164,12 @@ impl P2pSyncServer {
                     );
                     register_query(self.storage_reader.clone(), server_query_manager, self.class_manager_client.clone(), "event");
                 }
+                maybe_server_query_manager = receipt_receiver.next() => {
+                    let server_query_manager = maybe_server_query_manager.expect(
+                        "Receipt queries sender was unexpectedly dropped."
+                    );
+                    register_query(self.storage_reader.clone(), server_query_manager, self.class_manager_client.clone(), "receipt");
+                }
             };
         }
     }
@@ -369,6 +385,27 @@ impl FetchBlockData for (Event, TransactionHash) {
     }
 }
 
+#[async_trait]
+impl FetchBlockData for (TransactionOutput, TransactionHash) {
+    async fn fetch_block_data(
+        block_number: BlockNumber,
+        txn: &StorageTxn<'_, db::RO>,
+        _class_manager_client: &mut SharedClassManagerClient,
+    ) -> Result<Vec<Self>, P2pSyncServerError> {
+        let transaction_outputs = txn.get_block_transaction_outputs(block_number)?.ok_or(
+            P2pSyncServerError::BlockNotFound {
+                block_hash_or_number: BlockHashOrNumber::Number(block_number),
+            },
+        )?;
+        let transaction_hashes = txn.get_block_transaction_hashes(block_number)?.ok_or(
+            P2pSyncServerError::BlockNotFound {
+                block_hash_or_number: BlockHashOrNumber::Number(block_number),
+            },
+        )?;
+        Ok(transaction_outputs.into_iter().zip(transaction_hashes).collect())
+    }
+}
+
 pub fn split_thin_state_diff(thin_state_diff: ThinStateDiff) -> Vec<StateDiffChunk> {
     let mut state_diff_chunks = Vec::new();
     #[cfg(not(test))]
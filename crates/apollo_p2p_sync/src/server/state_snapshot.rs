@@ -0,0 +1,190 @@
+use std::sync::Arc;
+
+use apollo_network::network_manager::{ServerQueryManager, SqmrServerReceiver};
+use apollo_protobuf::sync::{
+    ContractLeaf,
+    DataOrFin,
+    StateTrieLeaves,
+    StateTrieLeavesQuery,
+    StateTrieRoot,
+    TrieNode,
+};
+use futures::never::Never;
+use futures::StreamExt;
+use starknet_api::core::ContractAddress;
+use starknet_api::state::StorageKey;
+use starknet_api::StarknetApiError;
+use starknet_committer::block_committer::input::StarknetStorageValue;
+use starknet_committer::patricia_merkle_tree::leaf::leaf_impl::ContractState;
+use starknet_patricia::hash::hash_trait::HashOutput;
+use starknet_patricia::patricia_merkle_tree::node_data::inner_node::{
+    BinaryData,
+    EdgeData,
+    NodeData,
+};
+use starknet_patricia::patricia_merkle_tree::node_data::leaf::Leaf;
+use starknet_patricia::patricia_merkle_tree::range_proof::{
+    fetch_leaf_range,
+    LeafRange,
+    RangeProofError,
+};
+use starknet_patricia_storage::storage_trait::Storage;
+use starknet_types_core::felt::Felt;
+use tracing::{debug, error};
+
+#[derive(thiserror::Error, Debug)]
+pub enum StateSnapshotServerError {
+    #[error(transparent)]
+    RangeProof(#[from] RangeProofError),
+    #[error(transparent)]
+    StarknetApi(#[from] StarknetApiError),
+    #[error(transparent)]
+    SendError(#[from] futures::channel::mpsc::SendError),
+}
+
+type StateSnapshotReceiver = SqmrServerReceiver<StateTrieLeavesQuery, DataOrFin<StateTrieLeaves>>;
+
+/// Serves ranges of the leaves of the contracts trie and of the contract storage tries, with their
+/// proofs, from a storage of the Patricia tries of the state.
+pub struct StateSnapshotServer<S: Storage> {
+    storage: Arc<S>,
+    receiver: StateSnapshotReceiver,
+    max_leaves_per_query: u64,
+}
+
+impl<S: Storage + Send + Sync + 'static> StateSnapshotServer<S> {
+    pub fn new(
+        storage: Arc<S>,
+        receiver: StateSnapshotReceiver,
+        max_leaves_per_query: u64,
+    ) -> Self {
+        Self { storage, receiver, max_leaves_per_query }
+    }
+
+    pub async fn run(mut self) -> Never {
+        loop {
+            let server_query_manager = self
+                .receiver
+                .next()
+                .await
+                .expect("State snapshot queries sender was unexpectedly dropped.");
+            self.register_query(server_query_manager);
+        }
+    }
+
+    fn register_query(
+        &self,
+        server_query_manager: ServerQueryManager<StateTrieLeavesQuery, DataOrFin<StateTrieLeaves>>,
+    ) {
+        let query = match server_query_manager.query() {
+            Ok(query) => query.clone(),
+            Err(error) => {
+                error!("Failed to parse inbound state snapshot query: {error:?}");
+                server_query_manager.report_peer();
+                return;
+            }
+        };
+        debug!("State snapshot server received a new inbound query {query:?}");
+        if query.limit == 0 {
+            debug!("Reporting peer that sent a state snapshot query with no leaves: {query:?}");
+            server_query_manager.report_peer();
+            return;
+        }
+        let storage = self.storage.clone();
+        let max_leaves_per_query = self.max_leaves_per_query;
+        tokio::task::spawn(async move {
+            let result = send_leaves_for_query(
+                storage,
+                server_query_manager,
+                query.clone(),
+                max_leaves_per_query,
+            )
+            .await;
+            if let Err(error) = result {
+                error!("Running inbound state snapshot query {query:?} failed on {error:?}");
+            }
+        });
+    }
+}
+
+async fn send_leaves_for_query<S: Storage + Send + Sync + 'static>(
+    storage: Arc<S>,
+    mut server_query_manager: ServerQueryManager<StateTrieLeavesQuery, DataOrFin<StateTrieLeaves>>,
+    query: StateTrieLeavesQuery,
+    max_leaves_per_query: u64,
+) -> Result<(), StateSnapshotServerError> {
+    let leaves = get_state_trie_leaves(storage.as_ref(), query, max_leaves_per_query)?;
+    server_query_manager.send_response(DataOrFin(Some(leaves))).await?;
+    server_query_manager.send_response(DataOrFin(None)).await?;
+    Ok(())
+}
+
+/// Returns the leaves requested in the query, with up to `max_leaves_per_query` leaves.
+fn get_state_trie_leaves(
+    storage: &impl Storage,
+    query: StateTrieLeavesQuery,
+    max_leaves_per_query: u64,
+) -> Result<StateTrieLeaves, StateSnapshotServerError> {
+    // The client needs the limit to tell whether the leaves reach the end of the trie.
+    let limit_u64 = query.limit.min(max_leaves_per_query);
+    let limit = usize::try_from(limit_u64).expect("Failed converting u64 to usize.");
+    match query.root {
+        StateTrieRoot::ContractsTrie(root) => {
+            let LeafRange { leaves, proof } =
+                fetch_leaf_range::<ContractState>(storage, HashOutput(root), query.start, limit)?;
+            let contracts = leaves
+                .into_iter()
+                .map(|(key, contract_state)| {
+                    Ok(ContractLeaf {
+                        address: ContractAddress::try_from(key)?,
+                        class_hash: contract_state.class_hash,
+                        nonce: contract_state.nonce,
+                        storage_root: contract_state.storage_root_hash.0,
+                    })
+                })
+                .collect::<Result<_, StarknetApiError>>()?;
+            Ok(StateTrieLeaves {
+                contracts,
+                storage: vec![],
+                proof: to_trie_nodes(proof),
+                limit: limit_u64,
+            })
+        }
+        StateTrieRoot::ContractStorage(root) => {
+            let LeafRange { leaves, proof } = fetch_leaf_range::<StarknetStorageValue>(
+                storage,
+                HashOutput(root),
+                query.start,
+                limit,
+            )?;
+            let storage = leaves
+                .into_iter()
+                .map(|(key, value)| Ok((StorageKey::try_from(key)?, value.0)))
+                .collect::<Result<_, StarknetApiError>>()?;
+            Ok(StateTrieLeaves {
+                contracts: vec![],
+                storage,
+                proof: to_trie_nodes(proof),
+                limit: limit_u64,
+            })
+        }
+    }
+}
+
+fn to_trie_nodes<L: Leaf>(proof: Vec<NodeData<L>>) -> Vec<TrieNode> {
+    proof
+        .into_iter()
+        .filter_map(|node_data| match node_data {
+            NodeData::Binary(BinaryData { left_hash, right_hash }) => {
+                Some(TrieNode::Binary { left: left_hash.0, right: right_hash.0 })
+            }
+            NodeData::Edge(EdgeData { bottom_hash, path_to_bottom }) => Some(TrieNode::Edge {
+                bottom: bottom_hash.0,
+                path: Felt::from(&path_to_bottom.path),
+                length: path_to_bottom.length.into(),
+            }),
+            // Proofs only contain inner nodes.
+            NodeData::Leaf(_) => None,
+        })
+        .collect()
+}
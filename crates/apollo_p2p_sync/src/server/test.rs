@@ -16,6 +16,7 @@ use apollo_protobuf::sync::{
     EventQuery,
     HeaderQuery,
     Query,
+    ReceiptQuery,
     SignedBlockHeader,
     StateDiffChunk,
     StateDiffQuery,
@@ -143,6 +144,23 @@ async fn event_query_positive_flow() {
     run_test::<_, _, EventQuery>(assert_event, 0, StartBlockType::Number).await;
 }
 
+#[tokio::test]
+async fn receipt_query_positive_flow() {
+    let assert_receipt = |data: Vec<(TransactionOutput, TransactionHash)>| {
+        assert_eq!(data.len(), NUM_OF_BLOCKS * NUM_TXS_PER_BLOCK);
+        for (i, (transaction_output, transaction_hash)) in data.into_iter().enumerate() {
+            assert_eq!(
+                transaction_output,
+                TX_OUTPUTS[i / NUM_TXS_PER_BLOCK][i % NUM_TXS_PER_BLOCK]
+            );
+            assert_eq!(transaction_hash, TX_HASHES[i / NUM_TXS_PER_BLOCK][i % NUM_TXS_PER_BLOCK]);
+        }
+    };
+
+    run_test::<_, _, ReceiptQuery>(assert_receipt, 0, StartBlockType::Hash).await;
+    run_test::<_, _, ReceiptQuery>(assert_receipt, 0, StartBlockType::Number).await;
+}
+
 #[tokio::test]
 async fn class_query_positive_flow() {
     let assert_class = |data: Vec<(ApiContractClass, ClassHash)>| {
@@ -335,6 +353,7 @@ async fn run_test<T, F, TQuery>(
         transaction_sender: _transaction_sender,
         class_sender: _class_sender,
         event_sender: _event_sender,
+        receipt_sender: _receipt_sender,
     } = setup_sync_server_and_storage();
 
     let block_number = BlockNumber(start_block_number.try_into().unwrap());
@@ -395,6 +414,8 @@ pub struct TestArgs {
     pub class_sender:
         Sender<ServerQueryManager<ClassQuery, DataOrFin<(ApiContractClass, ClassHash)>>>,
     pub event_sender: Sender<ServerQueryManager<EventQuery, DataOrFin<(Event, TransactionHash)>>>,
+    pub receipt_sender:
+        Sender<ServerQueryManager<ReceiptQuery, DataOrFin<(TransactionOutput, TransactionHash)>>>,
 }
 
 #[allow(clippy::type_complexity)]
@@ -413,12 +434,14 @@ fn setup_sync_server_and_storage() -> TestArgs {
         mock_register_sqmr_protocol_server(BUFFER_SIZE);
     let (class_receiver, class_sender) = mock_register_sqmr_protocol_server(BUFFER_SIZE);
     let (event_receiver, event_sender) = mock_register_sqmr_protocol_server(BUFFER_SIZE);
+    let (receipt_receiver, receipt_sender) = mock_register_sqmr_protocol_server(BUFFER_SIZE);
     let p2p_sync_server_channels = P2pSyncServerChannels {
         header_receiver,
         state_diff_receiver,
         transaction_receiver,
         class_receiver,
         event_receiver,
+        receipt_receiver,
     };
 
     let p2p_sync_server = super::P2pSyncServer::new(
@@ -434,6 +457,7 @@ fn setup_sync_server_and_storage() -> TestArgs {
         transaction_sender,
         class_sender,
         event_sender,
+        receipt_sender,
     }
 }
 
@@ -0,0 +1,148 @@
+#[cfg(test)]
+#[path = "mempool_recovery_test.rs"]
+mod mempool_recovery_test;
+
+use prost::Message;
+use starknet_api::rpc_transaction::RpcTransaction;
+use starknet_api::transaction::TransactionHash;
+
+use super::common::missing;
+use super::ProtobufConversionError;
+use crate::mempool::{AccountTransactionHashes, MempoolSummaryQuery, MempoolTransactionsQuery};
+use crate::sync::DataOrFin;
+use crate::{auto_impl_into_and_try_from_vec_u8, protobuf};
+
+impl From<MempoolSummaryQuery> for protobuf::MempoolSummaryRequest {
+    fn from(_value: MempoolSummaryQuery) -> Self {
+        Self {}
+    }
+}
+
+impl TryFrom<protobuf::MempoolSummaryRequest> for MempoolSummaryQuery {
+    type Error = ProtobufConversionError;
+    fn try_from(_value: protobuf::MempoolSummaryRequest) -> Result<Self, Self::Error> {
+        Ok(Self)
+    }
+}
+
+auto_impl_into_and_try_from_vec_u8!(MempoolSummaryQuery, protobuf::MempoolSummaryRequest);
+
+impl TryFrom<protobuf::MempoolSummaryResponse> for DataOrFin<AccountTransactionHashes> {
+    type Error = ProtobufConversionError;
+    fn try_from(value: protobuf::MempoolSummaryResponse) -> Result<Self, Self::Error> {
+        match value.mempool_summary_message {
+            Some(
+                protobuf::mempool_summary_response::MempoolSummaryMessage::AccountTransactionHashes(
+                    account_transaction_hashes,
+                ),
+            ) => Ok(Self(Some(account_transaction_hashes.try_into()?))),
+            Some(protobuf::mempool_summary_response::MempoolSummaryMessage::Fin(_)) => {
+                Ok(Self(None))
+            }
+            None => Err(missing("MempoolSummaryResponse::mempool_summary_message")),
+        }
+    }
+}
+
+impl From<DataOrFin<AccountTransactionHashes>> for protobuf::MempoolSummaryResponse {
+    fn from(value: DataOrFin<AccountTransactionHashes>) -> Self {
+        let mempool_summary_message = match value.0 {
+            Some(account_transaction_hashes) => {
+                protobuf::mempool_summary_response::MempoolSummaryMessage::AccountTransactionHashes(
+                    account_transaction_hashes.into(),
+                )
+            }
+            None => {
+                protobuf::mempool_summary_response::MempoolSummaryMessage::Fin(protobuf::Fin {})
+            }
+        };
+        Self { mempool_summary_message: Some(mempool_summary_message) }
+    }
+}
+
+auto_impl_into_and_try_from_vec_u8!(
+    DataOrFin<AccountTransactionHashes>,
+    protobuf::MempoolSummaryResponse
+);
+
+impl TryFrom<protobuf::AccountTransactionHashes> for AccountTransactionHashes {
+    type Error = ProtobufConversionError;
+    fn try_from(value: protobuf::AccountTransactionHashes) -> Result<Self, Self::Error> {
+        let address =
+            value.address.ok_or(missing("AccountTransactionHashes::address"))?.try_into()?;
+        let transaction_hashes = try_from_protobuf_hashes(value.transaction_hashes)?;
+        Ok(Self { address, transaction_hashes })
+    }
+}
+
+impl From<AccountTransactionHashes> for protobuf::AccountTransactionHashes {
+    fn from(value: AccountTransactionHashes) -> Self {
+        Self {
+            address: Some(value.address.into()),
+            transaction_hashes: into_protobuf_hashes(value.transaction_hashes),
+        }
+    }
+}
+
+impl TryFrom<protobuf::MempoolTransactionsRequest> for MempoolTransactionsQuery {
+    type Error = ProtobufConversionError;
+    fn try_from(value: protobuf::MempoolTransactionsRequest) -> Result<Self, Self::Error> {
+        Ok(Self { transaction_hashes: try_from_protobuf_hashes(value.transaction_hashes)? })
+    }
+}
+
+impl From<MempoolTransactionsQuery> for protobuf::MempoolTransactionsRequest {
+    fn from(value: MempoolTransactionsQuery) -> Self {
+        Self { transaction_hashes: into_protobuf_hashes(value.transaction_hashes) }
+    }
+}
+
+auto_impl_into_and_try_from_vec_u8!(MempoolTransactionsQuery, protobuf::MempoolTransactionsRequest);
+
+impl TryFrom<protobuf::MempoolTransactionsResponse> for DataOrFin<RpcTransaction> {
+    type Error = ProtobufConversionError;
+    fn try_from(value: protobuf::MempoolTransactionsResponse) -> Result<Self, Self::Error> {
+        match value.mempool_transactions_message {
+            Some(
+                protobuf::mempool_transactions_response::MempoolTransactionsMessage::Transaction(
+                    transaction,
+                ),
+            ) => Ok(Self(Some(transaction.try_into()?))),
+            Some(protobuf::mempool_transactions_response::MempoolTransactionsMessage::Fin(_)) => {
+                Ok(Self(None))
+            }
+            None => Err(missing("MempoolTransactionsResponse::mempool_transactions_message")),
+        }
+    }
+}
+
+impl From<DataOrFin<RpcTransaction>> for protobuf::MempoolTransactionsResponse {
+    fn from(value: DataOrFin<RpcTransaction>) -> Self {
+        let mempool_transactions_message = match value.0 {
+            Some(transaction) => {
+                protobuf::mempool_transactions_response::MempoolTransactionsMessage::Transaction(
+                    transaction.into(),
+                )
+            }
+            None => protobuf::mempool_transactions_response::MempoolTransactionsMessage::Fin(
+                protobuf::Fin {},
+            ),
+        };
+        Self { mempool_transactions_message: Some(mempool_transactions_message) }
+    }
+}
+
+auto_impl_into_and_try_from_vec_u8!(
+    DataOrFin<RpcTransaction>,
+    protobuf::MempoolTransactionsResponse
+);
+
+fn try_from_protobuf_hashes(
+    hashes: Vec<protobuf::Hash>,
+) -> Result<Vec<TransactionHash>, ProtobufConversionError> {
+    hashes.into_iter().map(|hash| Ok(TransactionHash(hash.try_into()?))).collect()
+}
+
+fn into_protobuf_hashes(hashes: Vec<TransactionHash>) -> Vec<protobuf::Hash> {
+    hashes.into_iter().map(|hash| hash.0.into()).collect()
+}
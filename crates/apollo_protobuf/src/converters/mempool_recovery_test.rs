@@ -0,0 +1,55 @@
+use apollo_test_utils::{get_rng, GetTestInstance};
+use starknet_api::block::GasPrice;
+use starknet_api::core::ContractAddress;
+use starknet_api::execution_resources::GasAmount;
+use starknet_api::rpc_transaction::{RpcInvokeTransaction, RpcInvokeTransactionV3, RpcTransaction};
+use starknet_api::transaction::fields::{AllResourceBounds, ResourceBounds};
+use starknet_api::transaction::TransactionHash;
+use starknet_types_core::felt::Felt;
+
+use crate::mempool::{AccountTransactionHashes, MempoolSummaryQuery, MempoolTransactionsQuery};
+use crate::sync::DataOrFin;
+
+#[test]
+fn mempool_summary_query_to_bytes_and_back() {
+    let bytes = Vec::<u8>::from(MempoolSummaryQuery);
+    assert_eq!(MempoolSummaryQuery::try_from(bytes).unwrap(), MempoolSummaryQuery);
+}
+
+#[test]
+fn account_transaction_hashes_to_bytes_and_back() {
+    let account_transaction_hashes = AccountTransactionHashes {
+        address: ContractAddress::from(1_u8),
+        transaction_hashes: vec![TransactionHash(Felt::TWO), TransactionHash(Felt::THREE)],
+    };
+    for data in [DataOrFin(Some(account_transaction_hashes)), DataOrFin(None)] {
+        let bytes = Vec::<u8>::from(data.clone());
+        let res_data = DataOrFin::<AccountTransactionHashes>::try_from(bytes).unwrap();
+        assert_eq!(data, res_data);
+    }
+}
+
+#[test]
+fn mempool_transactions_query_and_response_to_bytes_and_back() {
+    let query = MempoolTransactionsQuery {
+        transaction_hashes: vec![TransactionHash(Felt::ONE), TransactionHash(Felt::TWO)],
+    };
+    let bytes = Vec::<u8>::from(query.clone());
+    assert_eq!(MempoolTransactionsQuery::try_from(bytes).unwrap(), query);
+
+    let mut rng = get_rng();
+    let mut invoke_transaction = RpcInvokeTransactionV3::get_test_instance(&mut rng);
+    let resource_bounds =
+        ResourceBounds { max_amount: GasAmount(1), max_price_per_unit: GasPrice(1) };
+    invoke_transaction.resource_bounds = AllResourceBounds {
+        l1_gas: resource_bounds,
+        l2_gas: resource_bounds,
+        l1_data_gas: resource_bounds,
+    };
+    let transaction = RpcTransaction::Invoke(RpcInvokeTransaction::V3(invoke_transaction));
+    for data in [DataOrFin(Some(transaction)), DataOrFin(None)] {
+        let bytes = Vec::<u8>::from(data.clone());
+        let res_data = DataOrFin::<RpcTransaction>::try_from(bytes).unwrap();
+        assert_eq!(data, res_data);
+    }
+}
@@ -4,9 +4,12 @@ pub(crate) mod common;
 pub mod consensus;
 mod event;
 mod header;
+mod mempool_recovery;
 mod receipt;
 pub mod rpc_transaction;
+mod snapshot;
 mod state_diff;
+pub mod sync_protocol_version;
 #[cfg(test)]
 mod test_instances;
 mod transaction;
@@ -1,5 +1,10 @@
+#[cfg(test)]
+#[path = "receipt_test.rs"]
+mod receipt_test;
+
 use std::collections::HashMap;
 
+use prost::Message;
 use starknet_api::core::{ContractAddress, EthAddress, PatriciaKey};
 use starknet_api::execution_resources::{Builtin, ExecutionResources, GasVector};
 use starknet_api::transaction::fields::Fee;
@@ -7,19 +12,22 @@ use starknet_api::transaction::{
     DeclareTransactionOutput,
     DeployAccountTransactionOutput,
     DeployTransactionOutput,
+    Event,
     InvokeTransactionOutput,
     L1HandlerTransactionOutput,
     L2ToL1Payload,
     MessageToL1,
     RevertedTransactionExecutionStatus,
     TransactionExecutionStatus,
+    TransactionHash,
     TransactionOutput,
 };
 use starknet_types_core::felt::Felt;
 
 use super::common::{missing, try_from_starkfelt_to_u128};
 use super::ProtobufConversionError;
-use crate::protobuf;
+use crate::sync::{DataOrFin, Query, ReceiptQuery};
+use crate::{auto_impl_into_and_try_from_vec_u8, protobuf};
 
 impl TryFrom<protobuf::Receipt> for TransactionOutput {
     type Error = ProtobufConversionError;
@@ -69,6 +77,107 @@ impl From<TransactionOutput> for protobuf::Receipt {
     }
 }
 
+impl TryFrom<protobuf::ReceiptsResponse> for DataOrFin<(TransactionOutput, TransactionHash)> {
+    type Error = ProtobufConversionError;
+    fn try_from(value: protobuf::ReceiptsResponse) -> Result<Self, Self::Error> {
+        match value.receipt_message {
+            Some(protobuf::receipts_response::ReceiptMessage::Receipt(receipt)) => {
+                Ok(Self(Some(receipt.try_into()?)))
+            }
+            Some(protobuf::receipts_response::ReceiptMessage::Fin(_)) => Ok(Self(None)),
+            None => Err(missing("ReceiptsResponse::receipt_message")),
+        }
+    }
+}
+
+impl From<DataOrFin<(TransactionOutput, TransactionHash)>> for protobuf::ReceiptsResponse {
+    fn from(value: DataOrFin<(TransactionOutput, TransactionHash)>) -> Self {
+        let receipt_message = match value.0 {
+            Some(receipt) => protobuf::receipts_response::ReceiptMessage::Receipt(receipt.into()),
+            None => protobuf::receipts_response::ReceiptMessage::Fin(protobuf::Fin {}),
+        };
+        protobuf::ReceiptsResponse { receipt_message: Some(receipt_message) }
+    }
+}
+
+auto_impl_into_and_try_from_vec_u8!(
+    DataOrFin<(TransactionOutput, TransactionHash)>,
+    protobuf::ReceiptsResponse
+);
+
+impl TryFrom<protobuf::TransactionReceipt> for (TransactionOutput, TransactionHash) {
+    type Error = ProtobufConversionError;
+    fn try_from(value: protobuf::TransactionReceipt) -> Result<Self, Self::Error> {
+        let transaction_hash = TransactionHash(
+            value
+                .transaction_hash
+                .ok_or(missing("TransactionReceipt::transaction_hash"))?
+                .try_into()?,
+        );
+        let mut transaction_output = TransactionOutput::try_from(
+            value.receipt.ok_or(missing("TransactionReceipt::receipt"))?,
+        )?;
+        let events = value
+            .events
+            .into_iter()
+            .map(|event| Ok(<(Event, TransactionHash)>::try_from(event)?.0))
+            .collect::<Result<Vec<_>, ProtobufConversionError>>()?;
+        match &mut transaction_output {
+            TransactionOutput::Declare(output) => output.events = events,
+            TransactionOutput::Deploy(output) => output.events = events,
+            TransactionOutput::DeployAccount(output) => output.events = events,
+            TransactionOutput::Invoke(output) => output.events = events,
+            TransactionOutput::L1Handler(output) => output.events = events,
+        }
+        Ok((transaction_output, transaction_hash))
+    }
+}
+
+impl From<(TransactionOutput, TransactionHash)> for protobuf::TransactionReceipt {
+    fn from(value: (TransactionOutput, TransactionHash)) -> Self {
+        let (transaction_output, transaction_hash) = value;
+        let events = transaction_output
+            .events()
+            .iter()
+            .cloned()
+            .map(|event| protobuf::Event::from((event, transaction_hash)))
+            .collect();
+        protobuf::TransactionReceipt {
+            transaction_hash: Some(transaction_hash.0.into()),
+            receipt: Some(transaction_output.into()),
+            events,
+        }
+    }
+}
+
+impl TryFrom<protobuf::ReceiptsRequest> for Query {
+    type Error = ProtobufConversionError;
+    fn try_from(value: protobuf::ReceiptsRequest) -> Result<Self, Self::Error> {
+        Ok(ReceiptQuery::try_from(value)?.0)
+    }
+}
+
+impl TryFrom<protobuf::ReceiptsRequest> for ReceiptQuery {
+    type Error = ProtobufConversionError;
+    fn try_from(value: protobuf::ReceiptsRequest) -> Result<Self, Self::Error> {
+        Ok(ReceiptQuery(value.iteration.ok_or(missing("ReceiptsRequest::iteration"))?.try_into()?))
+    }
+}
+
+impl From<Query> for protobuf::ReceiptsRequest {
+    fn from(value: Query) -> Self {
+        protobuf::ReceiptsRequest { iteration: Some(value.into()) }
+    }
+}
+
+impl From<ReceiptQuery> for protobuf::ReceiptsRequest {
+    fn from(value: ReceiptQuery) -> Self {
+        protobuf::ReceiptsRequest { iteration: Some(value.0.into()) }
+    }
+}
+
+auto_impl_into_and_try_from_vec_u8!(ReceiptQuery, protobuf::ReceiptsRequest);
+
 // The output will have an empty events vec
 impl TryFrom<protobuf::receipt::DeployAccount> for DeployAccountTransactionOutput {
     type Error = ProtobufConversionError;
@@ -261,7 +370,10 @@ impl TryFrom<ProtobufBuiltinCounter> for HashMap<Builtin, u64> {
         builtin_instance_counter.insert(Builtin::Ecdsa, u64::from(value.ecdsa));
         builtin_instance_counter.insert(Builtin::Bitwise, u64::from(value.bitwise));
         builtin_instance_counter.insert(Builtin::Keccak, u64::from(value.keccak));
-        builtin_instance_counter.insert(Builtin::SegmentArena, 0);
+        builtin_instance_counter.insert(Builtin::SegmentArena, u64::from(value.segment_arena));
+        builtin_instance_counter.insert(Builtin::AddMod, u64::from(value.add_mod));
+        builtin_instance_counter.insert(Builtin::MulMod, u64::from(value.mul_mod));
+        builtin_instance_counter.insert(Builtin::RangeCheck96, u64::from(value.range_check96));
         Ok(builtin_instance_counter)
     }
 }
@@ -285,6 +397,14 @@ impl From<HashMap<Builtin, u64>> for ProtobufBuiltinCounter {
             keccak: u32::try_from(*value.get(&Builtin::Keccak).unwrap_or(&0))
                 .expect("Failed to convert u64 to u32"),
             output: 0,
+            segment_arena: u32::try_from(*value.get(&Builtin::SegmentArena).unwrap_or(&0))
+                .expect("Failed to convert u64 to u32"),
+            add_mod: u32::try_from(*value.get(&Builtin::AddMod).unwrap_or(&0))
+                .expect("Failed to convert u64 to u32"),
+            mul_mod: u32::try_from(*value.get(&Builtin::MulMod).unwrap_or(&0))
+                .expect("Failed to convert u64 to u32"),
+            range_check96: u32::try_from(*value.get(&Builtin::RangeCheck96).unwrap_or(&0))
+                .expect("Failed to convert u64 to u32"),
         };
         builtin_counter
     }
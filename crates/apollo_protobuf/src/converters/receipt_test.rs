@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+use apollo_test_utils::{get_rng, GetTestInstance};
+use starknet_api::block::BlockNumber;
+use starknet_api::execution_resources::{Builtin, ExecutionResources, GasVector};
+use starknet_api::transaction::{
+    Event,
+    InvokeTransactionOutput,
+    TransactionHash,
+    TransactionOutput,
+};
+
+use crate::sync::{BlockHashOrNumber, DataOrFin, Direction, Query, ReceiptQuery};
+
+fn execution_resources() -> ExecutionResources {
+    ExecutionResources {
+        steps: 10,
+        builtin_instance_counter: HashMap::from([
+            (Builtin::RangeCheck, 1),
+            (Builtin::Pedersen, 2),
+            (Builtin::Poseidon, 3),
+            (Builtin::EcOp, 4),
+            (Builtin::Ecdsa, 5),
+            (Builtin::Bitwise, 6),
+            (Builtin::Keccak, 7),
+            (Builtin::SegmentArena, 8),
+            (Builtin::AddMod, 9),
+            (Builtin::MulMod, 10),
+            (Builtin::RangeCheck96, 11),
+        ]),
+        memory_holes: 2,
+        da_gas_consumed: GasVector::default(),
+        gas_consumed: GasVector::default(),
+    }
+}
+
+#[test]
+fn convert_receipt_with_events_to_vec_u8_and_back() {
+    let mut rng = get_rng();
+    let mut transaction_output = InvokeTransactionOutput::get_test_instance(&mut rng);
+    transaction_output.execution_resources = execution_resources();
+    transaction_output.events =
+        (0..3).map(|_| Event::get_test_instance(&mut rng)).collect::<Vec<_>>();
+    let transaction_hash = TransactionHash::get_test_instance(&mut rng);
+
+    let data = DataOrFin(Some((TransactionOutput::Invoke(transaction_output), transaction_hash)));
+    let bytes_data = Vec::<u8>::from(data.clone());
+    let res_data = DataOrFin::try_from(bytes_data).unwrap();
+    assert_eq!(data, res_data);
+}
+
+#[test]
+fn fin_receipt_to_bytes_and_back() {
+    let bytes_data = Vec::<u8>::from(DataOrFin::<(TransactionOutput, TransactionHash)>(None));
+
+    let res_data = DataOrFin::<(TransactionOutput, TransactionHash)>::try_from(bytes_data).unwrap();
+    assert!(res_data.0.is_none());
+}
+
+#[test]
+fn convert_receipt_query_to_vec_u8_and_back() {
+    let query = ReceiptQuery(Query {
+        start_block: BlockHashOrNumber::Number(BlockNumber(7)),
+        direction: Direction::Forward,
+        limit: 5,
+        step: 1,
+    });
+    let bytes_data = Vec::<u8>::from(query.clone());
+    let res_query = ReceiptQuery::try_from(bytes_data).unwrap();
+    assert_eq!(query, res_query);
+}
@@ -0,0 +1,192 @@
+#[cfg(test)]
+#[path = "snapshot_test.rs"]
+mod snapshot_test;
+
+use prost::Message;
+use starknet_api::core::{ClassHash, Nonce};
+use starknet_api::hash::StarkHash;
+use starknet_api::state::StorageKey;
+use starknet_types_core::felt::Felt;
+
+use super::common::missing;
+use super::ProtobufConversionError;
+use crate::sync::{
+    ContractLeaf,
+    DataOrFin,
+    StateTrieLeaves,
+    StateTrieLeavesQuery,
+    StateTrieRoot,
+    TrieNode,
+};
+use crate::{auto_impl_into_and_try_from_vec_u8, protobuf};
+
+impl TryFrom<protobuf::StateTrieLeavesRequest> for StateTrieLeavesQuery {
+    type Error = ProtobufConversionError;
+    fn try_from(value: protobuf::StateTrieLeavesRequest) -> Result<Self, Self::Error> {
+        let root = match value.root.ok_or(missing("StateTrieLeavesRequest::root"))? {
+            protobuf::state_trie_leaves_request::Root::ContractsTrieRoot(root) => {
+                StateTrieRoot::ContractsTrie(root.try_into()?)
+            }
+            protobuf::state_trie_leaves_request::Root::ContractStorageRoot(root) => {
+                StateTrieRoot::ContractStorage(root.try_into()?)
+            }
+        };
+        let start = value.start.ok_or(missing("StateTrieLeavesRequest::start"))?.try_into()?;
+        Ok(Self { root, start, limit: value.limit })
+    }
+}
+
+impl From<StateTrieLeavesQuery> for protobuf::StateTrieLeavesRequest {
+    fn from(value: StateTrieLeavesQuery) -> Self {
+        let root = match value.root {
+            StateTrieRoot::ContractsTrie(root) => {
+                protobuf::state_trie_leaves_request::Root::ContractsTrieRoot(root.into())
+            }
+            StateTrieRoot::ContractStorage(root) => {
+                protobuf::state_trie_leaves_request::Root::ContractStorageRoot(root.into())
+            }
+        };
+        Self { root: Some(root), start: Some(value.start.into()), limit: value.limit }
+    }
+}
+
+auto_impl_into_and_try_from_vec_u8!(StateTrieLeavesQuery, protobuf::StateTrieLeavesRequest);
+
+impl TryFrom<protobuf::StateTrieLeavesResponse> for DataOrFin<StateTrieLeaves> {
+    type Error = ProtobufConversionError;
+    fn try_from(value: protobuf::StateTrieLeavesResponse) -> Result<Self, Self::Error> {
+        match value.state_trie_leaves_message {
+            Some(protobuf::state_trie_leaves_response::StateTrieLeavesMessage::Leaves(leaves)) => {
+                Ok(Self(Some(leaves.try_into()?)))
+            }
+            Some(protobuf::state_trie_leaves_response::StateTrieLeavesMessage::Fin(_)) => {
+                Ok(Self(None))
+            }
+            None => Err(missing("StateTrieLeavesResponse::state_trie_leaves_message")),
+        }
+    }
+}
+
+impl From<DataOrFin<StateTrieLeaves>> for protobuf::StateTrieLeavesResponse {
+    fn from(value: DataOrFin<StateTrieLeaves>) -> Self {
+        let state_trie_leaves_message = match value.0 {
+            Some(leaves) => {
+                protobuf::state_trie_leaves_response::StateTrieLeavesMessage::Leaves(leaves.into())
+            }
+            None => {
+                protobuf::state_trie_leaves_response::StateTrieLeavesMessage::Fin(protobuf::Fin {})
+            }
+        };
+        Self { state_trie_leaves_message: Some(state_trie_leaves_message) }
+    }
+}
+
+auto_impl_into_and_try_from_vec_u8!(DataOrFin<StateTrieLeaves>, protobuf::StateTrieLeavesResponse);
+
+impl TryFrom<protobuf::StateTrieLeaves> for StateTrieLeaves {
+    type Error = ProtobufConversionError;
+    fn try_from(value: protobuf::StateTrieLeaves) -> Result<Self, Self::Error> {
+        let contracts = value
+            .contracts
+            .into_iter()
+            .map(ContractLeaf::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        let storage = value
+            .storage
+            .into_iter()
+            .map(<(StorageKey, Felt)>::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        let proof =
+            value.proof.into_iter().map(TrieNode::try_from).collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { contracts, storage, proof, limit: value.limit })
+    }
+}
+
+impl From<StateTrieLeaves> for protobuf::StateTrieLeaves {
+    fn from(value: StateTrieLeaves) -> Self {
+        Self {
+            contracts: value.contracts.into_iter().map(Into::into).collect(),
+            storage: value
+                .storage
+                .into_iter()
+                .map(|(key, value)| protobuf::ContractStoredValue {
+                    key: Some((*key.0.key()).into()),
+                    value: Some(value.into()),
+                })
+                .collect(),
+            proof: value.proof.into_iter().map(Into::into).collect(),
+            limit: value.limit,
+        }
+    }
+}
+
+impl TryFrom<protobuf::ContractLeaf> for ContractLeaf {
+    type Error = ProtobufConversionError;
+    fn try_from(value: protobuf::ContractLeaf) -> Result<Self, Self::Error> {
+        let address = value.address.ok_or(missing("ContractLeaf::address"))?.try_into()?;
+        let class_hash =
+            ClassHash(value.class_hash.ok_or(missing("ContractLeaf::class_hash"))?.try_into()?);
+        let nonce = Nonce(value.nonce.ok_or(missing("ContractLeaf::nonce"))?.try_into()?);
+        let storage_root =
+            StarkHash::try_from(value.storage_root.ok_or(missing("ContractLeaf::storage_root"))?)?;
+        Ok(Self { address, class_hash, nonce, storage_root })
+    }
+}
+
+impl From<ContractLeaf> for protobuf::ContractLeaf {
+    fn from(value: ContractLeaf) -> Self {
+        Self {
+            address: Some(value.address.into()),
+            class_hash: Some(value.class_hash.0.into()),
+            nonce: Some(value.nonce.0.into()),
+            storage_root: Some(value.storage_root.into()),
+        }
+    }
+}
+
+impl TryFrom<protobuf::TrieNode> for TrieNode {
+    type Error = ProtobufConversionError;
+    fn try_from(value: protobuf::TrieNode) -> Result<Self, Self::Error> {
+        match value.node.ok_or(missing("TrieNode::node"))? {
+            protobuf::trie_node::Node::Binary(protobuf::trie_node::Binary { left, right }) => {
+                Ok(Self::Binary {
+                    left: left.ok_or(missing("TrieNode::Binary::left"))?.try_into()?,
+                    right: right.ok_or(missing("TrieNode::Binary::right"))?.try_into()?,
+                })
+            }
+            protobuf::trie_node::Node::Edge(protobuf::trie_node::Edge { bottom, path, length }) => {
+                Ok(Self::Edge {
+                    bottom: bottom.ok_or(missing("TrieNode::Edge::bottom"))?.try_into()?,
+                    path: path.ok_or(missing("TrieNode::Edge::path"))?.try_into()?,
+                    length: length.try_into().map_err(|_| {
+                        ProtobufConversionError::OutOfRangeValue {
+                            type_description: "u8",
+                            value_as_str: length.to_string(),
+                        }
+                    })?,
+                })
+            }
+        }
+    }
+}
+
+impl From<TrieNode> for protobuf::TrieNode {
+    fn from(value: TrieNode) -> Self {
+        let node = match value {
+            TrieNode::Binary { left, right } => {
+                protobuf::trie_node::Node::Binary(protobuf::trie_node::Binary {
+                    left: Some(left.into()),
+                    right: Some(right.into()),
+                })
+            }
+            TrieNode::Edge { bottom, path, length } => {
+                protobuf::trie_node::Node::Edge(protobuf::trie_node::Edge {
+                    bottom: Some(bottom.into()),
+                    path: Some(path.into()),
+                    length: length.into(),
+                })
+            }
+        };
+        Self { node: Some(node) }
+    }
+}
@@ -0,0 +1,51 @@
+use apollo_test_utils::{get_rng, GetTestInstance};
+use starknet_api::core::{ClassHash, ContractAddress, Nonce};
+use starknet_api::hash::StarkHash;
+use starknet_api::state::StorageKey;
+use starknet_types_core::felt::Felt;
+
+use crate::sync::{ContractLeaf, DataOrFin, StateTrieLeaves, StateTrieLeavesQuery, TrieNode};
+
+#[test]
+fn state_trie_leaves_query_to_bytes_and_back() {
+    let mut rng = get_rng();
+    let query = StateTrieLeavesQuery::get_test_instance(&mut rng);
+
+    let bytes = Vec::<u8>::from(query.clone());
+    let res_query = StateTrieLeavesQuery::try_from(bytes).unwrap();
+    assert_eq!(query, res_query);
+}
+
+#[test]
+fn state_trie_leaves_to_bytes_and_back() {
+    let proof = vec![
+        TrieNode::Binary { left: StarkHash::ONE, right: StarkHash::TWO },
+        TrieNode::Edge { bottom: StarkHash::THREE, path: Felt::from(5_u8), length: 3 },
+    ];
+    let contracts_trie_leaves = StateTrieLeaves {
+        contracts: vec![ContractLeaf {
+            address: ContractAddress::from(1_u8),
+            class_hash: ClassHash(Felt::TWO),
+            nonce: Nonce(Felt::THREE),
+            storage_root: StarkHash::from(4_u8),
+        }],
+        storage: vec![],
+        proof: proof.clone(),
+        limit: 2,
+    };
+    let contract_storage_trie_leaves = StateTrieLeaves {
+        contracts: vec![],
+        storage: vec![(StorageKey::from(1_u8), Felt::TWO), (StorageKey::from(3_u8), Felt::ONE)],
+        proof,
+        limit: 2,
+    };
+    for data in [
+        DataOrFin(Some(contracts_trie_leaves)),
+        DataOrFin(Some(contract_storage_trie_leaves)),
+        DataOrFin(None),
+    ] {
+        let bytes = Vec::<u8>::from(data.clone());
+        let res_data = DataOrFin::<StateTrieLeaves>::try_from(bytes).unwrap();
+        assert_eq!(data, res_data);
+    }
+}
@@ -0,0 +1,91 @@
+#[cfg(test)]
+#[path = "sync_protocol_version_test.rs"]
+mod sync_protocol_version_test;
+
+use papyrus_common::pending_classes::ApiContractClass;
+use starknet_api::core::ClassHash;
+use starknet_api::transaction::{Event, FullTransaction, TransactionHash, TransactionOutput};
+
+use super::ProtobufConversionError;
+use crate::sync::{
+    ClassQuery,
+    DataOrFin,
+    EventQuery,
+    HeaderQuery,
+    ReceiptQuery,
+    SignedBlockHeader,
+    StateDiffChunk,
+    StateDiffQuery,
+    StateTrieLeaves,
+    StateTrieLeavesQuery,
+    TransactionQuery,
+};
+
+/// A version of the wire format of the p2p sync protocols.
+///
+/// Nodes advertise all the versions they support, so during a rolling upgrade nodes that support
+/// a new version can still sync with nodes that only support the old one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SyncProtocolVersion {
+    V0_1_0Rc0,
+}
+
+impl SyncProtocolVersion {
+    /// All the supported versions, ordered from the most preferred to the least preferred.
+    pub const SUPPORTED: [SyncProtocolVersion; 1] = [SyncProtocolVersion::V0_1_0Rc0];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyncProtocolVersion::V0_1_0Rc0 => "0.1.0-rc.0",
+        }
+    }
+}
+
+/// A message of the p2p sync protocols that can be converted to and from each supported version
+/// of the wire format.
+pub trait VersionedSyncMessage: Sized {
+    fn encode_for_version(self, version: SyncProtocolVersion) -> Vec<u8>;
+
+    fn decode_for_version(
+        bytes: Vec<u8>,
+        version: SyncProtocolVersion,
+    ) -> Result<Self, ProtobufConversionError>;
+}
+
+// All the messages currently have the same format in all the supported versions. When a version
+// changes the format of a message, the message should implement VersionedSyncMessage manually.
+macro_rules! auto_impl_versioned_sync_message {
+    ($T:ty) => {
+        impl VersionedSyncMessage for $T {
+            fn encode_for_version(self, version: SyncProtocolVersion) -> Vec<u8> {
+                match version {
+                    SyncProtocolVersion::V0_1_0Rc0 => Vec::<u8>::from(self),
+                }
+            }
+
+            fn decode_for_version(
+                bytes: Vec<u8>,
+                version: SyncProtocolVersion,
+            ) -> Result<Self, ProtobufConversionError> {
+                match version {
+                    SyncProtocolVersion::V0_1_0Rc0 => <$T>::try_from(bytes),
+                }
+            }
+        }
+    };
+}
+
+auto_impl_versioned_sync_message!(HeaderQuery);
+auto_impl_versioned_sync_message!(StateDiffQuery);
+auto_impl_versioned_sync_message!(TransactionQuery);
+auto_impl_versioned_sync_message!(ClassQuery);
+auto_impl_versioned_sync_message!(EventQuery);
+auto_impl_versioned_sync_message!(ReceiptQuery);
+auto_impl_versioned_sync_message!(StateTrieLeavesQuery);
+auto_impl_versioned_sync_message!(DataOrFin<SignedBlockHeader>);
+auto_impl_versioned_sync_message!(DataOrFin<StateDiffChunk>);
+auto_impl_versioned_sync_message!(DataOrFin<FullTransaction>);
+auto_impl_versioned_sync_message!(DataOrFin<(ApiContractClass, ClassHash)>);
+auto_impl_versioned_sync_message!(DataOrFin<(Event, TransactionHash)>);
+auto_impl_versioned_sync_message!(DataOrFin<(TransactionOutput, TransactionHash)>);
+auto_impl_versioned_sync_message!(DataOrFin<StateTrieLeaves>);
@@ -0,0 +1,21 @@
+use apollo_test_utils::{get_rng, GetTestInstance};
+
+use super::{SyncProtocolVersion, VersionedSyncMessage};
+use crate::sync::{DataOrFin, HeaderQuery, SignedBlockHeader};
+
+#[test]
+fn versioned_messages_to_bytes_and_back_in_all_versions() {
+    let mut rng = get_rng();
+    let query = HeaderQuery::get_test_instance(&mut rng);
+    let response = DataOrFin(Some(SignedBlockHeader::get_test_instance(&mut rng)));
+    for version in SyncProtocolVersion::SUPPORTED {
+        let query_bytes = query.clone().encode_for_version(version);
+        assert_eq!(HeaderQuery::decode_for_version(query_bytes, version).unwrap(), query);
+
+        let response_bytes = response.clone().encode_for_version(version);
+        assert_eq!(
+            DataOrFin::<SignedBlockHeader>::decode_for_version(response_bytes, version).unwrap(),
+            response
+        );
+    }
+}
@@ -1,4 +1,23 @@
+use starknet_api::core::ContractAddress;
 use starknet_api::rpc_transaction::RpcTransaction;
+use starknet_api::transaction::TransactionHash;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RpcTransactionBatch(pub Vec<RpcTransaction>);
+
+/// A query for the hashes of all the transactions in the mempool of a peer.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MempoolSummaryQuery;
+
+/// The hashes of the transactions of a single account in the mempool, ordered by nonce.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AccountTransactionHashes {
+    pub address: ContractAddress,
+    pub transaction_hashes: Vec<TransactionHash>,
+}
+
+/// A query for the mempool transactions with the given hashes.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MempoolTransactionsQuery {
+    pub transaction_hashes: Vec<TransactionHash>,
+}
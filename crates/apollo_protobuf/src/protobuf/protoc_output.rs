@@ -635,9 +635,16 @@ pub mod receipt {
             pub poseidon: u32,
             #[prost(uint32, tag = "7")]
             pub keccak: u32,
-            /// TODO(alonl): add the missing builtins
             #[prost(uint32, tag = "8")]
             pub output: u32,
+            #[prost(uint32, tag = "9")]
+            pub segment_arena: u32,
+            #[prost(uint32, tag = "10")]
+            pub add_mod: u32,
+            #[prost(uint32, tag = "11")]
+            pub mul_mod: u32,
+            #[prost(uint32, tag = "12")]
+            pub range_check96: u32,
         }
         /// TODO(alonl): remove GasVector and unsplit gas_consumed and da_gas_consumed
         #[allow(clippy::derive_partial_eq_without_eq)]
@@ -716,6 +723,43 @@ pub mod receipt {
         DeployAccount(DeployAccount),
     }
 }
+/// A receipt with the full output of a transaction, including its events.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TransactionReceipt {
+    #[prost(message, optional, tag = "1")]
+    pub transaction_hash: ::core::option::Option<Hash>,
+    #[prost(message, optional, tag = "2")]
+    pub receipt: ::core::option::Option<Receipt>,
+    #[prost(message, repeated, tag = "3")]
+    pub events: ::prost::alloc::vec::Vec<Event>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReceiptsRequest {
+    #[prost(message, optional, tag = "1")]
+    pub iteration: ::core::option::Option<Iteration>,
+}
+/// Responses are sent ordered by the order given in the request. The order inside each block is
+/// according to the execution order.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReceiptsResponse {
+    #[prost(oneof = "receipts_response::ReceiptMessage", tags = "1, 2")]
+    pub receipt_message: ::core::option::Option<receipts_response::ReceiptMessage>,
+}
+/// Nested message and enum types in `ReceiptsResponse`.
+pub mod receipts_response {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum ReceiptMessage {
+        #[prost(message, tag = "1")]
+        Receipt(super::TransactionReceipt),
+        /// Fin is sent after the peer sent all the data or when it encountered a block that it doesn't have its receipts.
+        #[prost(message, tag = "2")]
+        Fin(super::Fin),
+    }
+}
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
 pub enum PriceUnit {
@@ -955,6 +999,69 @@ pub struct MempoolTransactionBatch {
     #[prost(message, repeated, tag = "1")]
     pub transactions: ::prost::alloc::vec::Vec<MempoolTransaction>,
 }
+/// A request for the hashes of all the transactions in the mempool of the peer.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MempoolSummaryRequest {}
+/// The hashes of the transactions of a single account in the mempool, ordered by nonce.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AccountTransactionHashes {
+    #[prost(message, optional, tag = "1")]
+    pub address: ::core::option::Option<Address>,
+    #[prost(message, repeated, tag = "2")]
+    pub transaction_hashes: ::prost::alloc::vec::Vec<Hash>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MempoolSummaryResponse {
+    #[prost(oneof = "mempool_summary_response::MempoolSummaryMessage", tags = "1, 2")]
+    pub mempool_summary_message: ::core::option::Option<
+        mempool_summary_response::MempoolSummaryMessage,
+    >,
+}
+/// Nested message and enum types in `MempoolSummaryResponse`.
+pub mod mempool_summary_response {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum MempoolSummaryMessage {
+        #[prost(message, tag = "1")]
+        AccountTransactionHashes(super::AccountTransactionHashes),
+        /// Fin is sent after the hashes of all the accounts were sent.
+        #[prost(message, tag = "2")]
+        Fin(super::Fin),
+    }
+}
+/// A request for the transactions with the given hashes.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MempoolTransactionsRequest {
+    #[prost(message, repeated, tag = "1")]
+    pub transaction_hashes: ::prost::alloc::vec::Vec<Hash>,
+}
+/// Transactions are sent in the order of the request. Transactions the peer doesn't have are skipped.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MempoolTransactionsResponse {
+    #[prost(
+        oneof = "mempool_transactions_response::MempoolTransactionsMessage",
+        tags = "1, 2"
+    )]
+    pub mempool_transactions_message: ::core::option::Option<
+        mempool_transactions_response::MempoolTransactionsMessage,
+    >,
+}
+/// Nested message and enum types in `MempoolTransactionsResponse`.
+pub mod mempool_transactions_response {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum MempoolTransactionsMessage {
+        #[prost(message, tag = "1")]
+        Transaction(super::MempoolTransaction),
+        #[prost(message, tag = "2")]
+        Fin(super::Fin),
+    }
+}
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ClassesRequest {
@@ -1190,3 +1297,117 @@ pub mod state_diffs_response {
         Fin(super::Fin),
     }
 }
+/// A leaf of the contracts trie.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ContractLeaf {
+    #[prost(message, optional, tag = "1")]
+    pub address: ::core::option::Option<Address>,
+    #[prost(message, optional, tag = "2")]
+    pub class_hash: ::core::option::Option<Hash>,
+    #[prost(message, optional, tag = "3")]
+    pub nonce: ::core::option::Option<Felt252>,
+    #[prost(message, optional, tag = "4")]
+    pub storage_root: ::core::option::Option<Hash>,
+}
+/// The preimage of an inner node of a trie.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TrieNode {
+    #[prost(oneof = "trie_node::Node", tags = "1, 2")]
+    pub node: ::core::option::Option<trie_node::Node>,
+}
+/// Nested message and enum types in `TrieNode`.
+pub mod trie_node {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Binary {
+        #[prost(message, optional, tag = "1")]
+        pub left: ::core::option::Option<super::Hash>,
+        #[prost(message, optional, tag = "2")]
+        pub right: ::core::option::Option<super::Hash>,
+    }
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Edge {
+        #[prost(message, optional, tag = "1")]
+        pub bottom: ::core::option::Option<super::Hash>,
+        #[prost(message, optional, tag = "2")]
+        pub path: ::core::option::Option<super::Felt252>,
+        #[prost(uint32, tag = "3")]
+        pub length: u32,
+    }
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Node {
+        #[prost(message, tag = "1")]
+        Binary(Binary),
+        #[prost(message, tag = "2")]
+        Edge(Edge),
+    }
+}
+/// A request for the leaves of a trie of the state, starting from a given key.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StateTrieLeavesRequest {
+    #[prost(message, optional, tag = "3")]
+    pub start: ::core::option::Option<Felt252>,
+    #[prost(uint64, tag = "4")]
+    pub limit: u64,
+    /// The trie is identified by its root.
+    #[prost(oneof = "state_trie_leaves_request::Root", tags = "1, 2")]
+    pub root: ::core::option::Option<state_trie_leaves_request::Root>,
+}
+/// Nested message and enum types in `StateTrieLeavesRequest`.
+pub mod state_trie_leaves_request {
+    /// The trie is identified by its root.
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Root {
+        #[prost(message, tag = "1")]
+        ContractsTrieRoot(super::Hash),
+        #[prost(message, tag = "2")]
+        ContractStorageRoot(super::Hash),
+    }
+}
+/// The first non-empty leaves of the requested trie from the requested start. If there are limit leaves, they
+/// are all the leaves up to the last one. Otherwise, they are all the leaves up to the end of the trie.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StateTrieLeaves {
+    /// Present only if the contracts trie was requested.
+    #[prost(message, repeated, tag = "1")]
+    pub contracts: ::prost::alloc::vec::Vec<ContractLeaf>,
+    /// Present only if a contract storage trie was requested.
+    #[prost(message, repeated, tag = "2")]
+    pub storage: ::prost::alloc::vec::Vec<ContractStoredValue>,
+    /// The preimages of the inner nodes on the boundaries of the leaves.
+    #[prost(message, repeated, tag = "3")]
+    pub proof: ::prost::alloc::vec::Vec<TrieNode>,
+    /// The number of leaves the peer answers with at most, which may be lower than the requested limit.
+    #[prost(uint64, tag = "4")]
+    pub limit: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StateTrieLeavesResponse {
+    #[prost(
+        oneof = "state_trie_leaves_response::StateTrieLeavesMessage",
+        tags = "1, 2"
+    )]
+    pub state_trie_leaves_message: ::core::option::Option<
+        state_trie_leaves_response::StateTrieLeavesMessage,
+    >,
+}
+/// Nested message and enum types in `StateTrieLeavesResponse`.
+pub mod state_trie_leaves_response {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum StateTrieLeavesMessage {
+        #[prost(message, tag = "1")]
+        Leaves(super::StateTrieLeaves),
+        /// Fin is sent after the leaves, or if the peer doesn't have the requested trie.
+        #[prost(message, tag = "2")]
+        Fin(super::Fin),
+    }
+}
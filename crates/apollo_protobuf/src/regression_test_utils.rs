@@ -8,12 +8,14 @@ pub const PROTO_DIR: &str = "src/protobuf";
 pub const PROTO_FILES: &[&str] = &[
     "src/proto/p2p/proto/class.proto",
     "src/proto/p2p/proto/consensus/consensus.proto",
+    "src/proto/p2p/proto/mempool/recovery.proto",
     "src/proto/p2p/proto/mempool/transaction.proto",
     "src/proto/p2p/proto/sync/class.proto",
     "src/proto/p2p/proto/sync/event.proto",
     "src/proto/p2p/proto/sync/header.proto",
     "src/proto/p2p/proto/sync/state.proto",
     "src/proto/p2p/proto/sync/receipt.proto",
+    "src/proto/p2p/proto/sync/snapshot.proto",
     "src/proto/p2p/proto/sync/transaction.proto",
     "src/proto/p2p/proto/transaction.proto",
 ];
@@ -5,6 +5,7 @@ use apollo_test_utils::{auto_impl_get_test_instance, get_number_of_variants, Get
 use indexmap::IndexMap;
 use starknet_api::block::{BlockHash, BlockHeader, BlockNumber, BlockSignature};
 use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::hash::StarkHash;
 use starknet_api::state::StorageKey;
 use starknet_types_core::felt::Felt;
 
@@ -48,6 +49,11 @@ impl From<EventQuery> for Query {
         event_query.0
     }
 }
+impl From<ReceiptQuery> for Query {
+    fn from(receipt_query: ReceiptQuery) -> Self {
+        receipt_query.0
+    }
+}
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum BlockHashOrNumber {
@@ -109,6 +115,67 @@ impl From<Query> for EventQuery {
     }
 }
 
+#[derive(Default, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ReceiptQuery(pub Query);
+
+impl From<Query> for ReceiptQuery {
+    fn from(query: Query) -> Self {
+        Self(query)
+    }
+}
+
+/// A trie of the state, identified by its root.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StateTrieRoot {
+    ContractsTrie(StarkHash),
+    ContractStorage(StarkHash),
+}
+
+impl Default for StateTrieRoot {
+    fn default() -> Self {
+        Self::ContractsTrie(StarkHash::default())
+    }
+}
+
+/// A query for the first `limit` non-empty leaves of a trie of the state, whose keys are at least
+/// `start`.
+#[derive(Default, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StateTrieLeavesQuery {
+    pub root: StateTrieRoot,
+    pub start: Felt,
+    pub limit: u64,
+}
+
+/// A leaf of the contracts trie.
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+pub struct ContractLeaf {
+    pub address: ContractAddress,
+    pub class_hash: ClassHash,
+    pub nonce: Nonce,
+    pub storage_root: StarkHash,
+}
+
+/// The preimage of an inner node of a trie.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TrieNode {
+    Binary { left: StarkHash, right: StarkHash },
+    Edge { bottom: StarkHash, path: Felt, length: u8 },
+}
+
+/// The leaves of a trie of the state that were requested in a [`StateTrieLeavesQuery`], with the
+/// preimages of the inner nodes needed to prove them against the root of the trie. Only the
+/// leaves of the requested trie's type are present.
+///
+/// Peers may answer with less leaves than requested, so `limit` is the number of leaves the peer
+/// answered with at most. Less than `limit` leaves means the leaves reach the end of the trie.
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+pub struct StateTrieLeaves {
+    pub contracts: Vec<ContractLeaf>,
+    pub storage: Vec<(StorageKey, Felt)>,
+    pub proof: Vec<TrieNode>,
+    pub limit: u64,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SignedBlockHeader {
     pub block_header: BlockHeader,
@@ -207,6 +274,15 @@ auto_impl_get_test_instance! {
         Backward=1,
     }
     pub struct HeaderQuery(pub Query);
+    pub struct StateTrieLeavesQuery {
+        pub root: StateTrieRoot,
+        pub start: Felt,
+        pub limit: u64,
+    }
+    pub enum StateTrieRoot {
+        ContractsTrie(StarkHash) = 0,
+        ContractStorage(StarkHash) = 1,
+    }
     pub struct SignedBlockHeader {
         pub block_header: BlockHeader,
         pub signatures: Vec<BlockSignature>,
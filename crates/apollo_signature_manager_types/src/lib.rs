@@ -1,7 +1,13 @@
 use std::sync::Arc;
 
 use apollo_infra::component_client::{ClientError, LocalComponentClient, RemoteComponentClient};
-use apollo_infra::component_definitions::{ComponentClient, ComponentRequestAndResponseSender};
+use apollo_infra::component_definitions::{
+    ComponentClient,
+    ComponentRequestAndResponseSender,
+    LabeledRequest,
+    PrioritizedRequest,
+    RequestPriority,
+};
 use apollo_infra::impl_debug_for_infra_requests_and_responses;
 use apollo_network_types::network_types::PeerId;
 use apollo_proc_macros::handle_all_response_variants;
@@ -12,7 +18,8 @@ use serde::{Deserialize, Serialize};
 use starknet_api::block::BlockHash;
 use starknet_api::core::Nonce;
 use starknet_api::crypto::utils::{PrivateKey, RawSignature, SignatureConversionError};
-use strum_macros::AsRefStr;
+use strum::{EnumVariantNames, VariantNames};
+use strum_macros::{AsRefStr, EnumDiscriminants, EnumIter, IntoStaticStr};
 use thiserror::Error;
 
 pub type KeyStoreResult<T> = Result<T, KeyStoreError>;
@@ -79,13 +86,36 @@ pub enum SignatureManagerClientError {
     SignatureManagerError(#[from] SignatureManagerError),
 }
 
-#[derive(Clone, Serialize, Deserialize, AsRefStr)]
+#[derive(Clone, Serialize, Deserialize, AsRefStr, EnumDiscriminants)]
+#[strum_discriminants(
+    name(SignatureManagerRequestLabelValue),
+    derive(IntoStaticStr, EnumIter, EnumVariantNames),
+    strum(serialize_all = "snake_case")
+)]
 pub enum SignatureManagerRequest {
     Identify(PeerId, Nonce),
     SignPrecommitVote(BlockHash),
 }
 impl_debug_for_infra_requests_and_responses!(SignatureManagerRequest);
 
+impl LabeledRequest for SignatureManagerRequest {
+    const LABELS: &'static [&'static str] = SignatureManagerRequestLabelValue::VARIANTS;
+
+    fn label(&self) -> &'static str {
+        SignatureManagerRequestLabelValue::from(self).into()
+    }
+}
+
+impl PrioritizedRequest for SignatureManagerRequest {
+    // Precommit votes are on the consensus path.
+    fn priority(&self) -> RequestPriority {
+        match self {
+            SignatureManagerRequest::SignPrecommitVote(_) => RequestPriority::High,
+            SignatureManagerRequest::Identify(..) => RequestPriority::Normal,
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, AsRefStr)]
 pub enum SignatureManagerResponse {
     Identify(SignatureManagerResult<RawSignature>),
@@ -8,6 +8,7 @@ use std::cmp::min;
 use apollo_class_manager_types::SharedClassManagerClient;
 use apollo_infra::component_definitions::{ComponentRequestHandler, ComponentStarter};
 use apollo_infra::component_server::{LocalComponentServer, RemoteComponentServer};
+use apollo_network_types::network_types::PeerBanListClients;
 use apollo_state_sync_types::communication::{StateSyncRequest, StateSyncResponse};
 use apollo_state_sync_types::errors::StateSyncError;
 use apollo_state_sync_types::state_sync_types::{StateSyncResult, SyncBlock};
@@ -33,10 +34,15 @@ const BUFFER_SIZE: usize = 100000;
 pub fn create_state_sync_and_runner(
     config: StateSyncConfig,
     class_manager_client: SharedClassManagerClient,
+    peer_ban_list_clients: PeerBanListClients,
 ) -> (StateSync, StateSyncRunner) {
     let (new_block_sender, new_block_receiver) = channel(BUFFER_SIZE);
-    let (state_sync_runner, storage_reader) =
-        StateSyncRunner::new(config, new_block_receiver, class_manager_client);
+    let (state_sync_runner, storage_reader) = StateSyncRunner::new(
+        config,
+        new_block_receiver,
+        class_manager_client,
+        peer_ban_list_clients,
+    );
     (StateSync { storage_reader, new_block_sender }, state_sync_runner)
 }
 
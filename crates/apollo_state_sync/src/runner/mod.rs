@@ -15,6 +15,7 @@ use apollo_infra::component_definitions::ComponentStarter;
 use apollo_infra::component_server::WrapperServer;
 use apollo_network::network_manager::metrics::{NetworkMetrics, SqmrNetworkMetrics};
 use apollo_network::network_manager::{NetworkError, NetworkManager};
+use apollo_network_types::network_types::PeerBanListClients;
 use apollo_p2p_sync::client::{
     P2pSyncClient,
     P2pSyncClientChannels,
@@ -37,6 +38,7 @@ use apollo_state_sync_metrics::metrics::{
     P2P_SYNC_NUM_ACTIVE_OUTBOUND_SESSIONS,
     P2P_SYNC_NUM_BLACKLISTED_PEERS,
     P2P_SYNC_NUM_CONNECTED_PEERS,
+    P2P_SYNC_NUM_DENIED_CONNECTIONS,
     STATE_SYNC_REVERTED_TRANSACTIONS,
 };
 use apollo_state_sync_types::state_sync_types::SyncBlock;
@@ -50,6 +52,7 @@ use futures::never::Never;
 use futures::{FutureExt, StreamExt};
 use papyrus_common::pending_classes::PendingClasses;
 use starknet_api::block::{BlockHash, BlockHashAndNumber};
+use starknet_api::core::ChainId;
 use starknet_api::felt;
 use tokio::sync::RwLock;
 use tracing::info_span;
@@ -127,6 +130,7 @@ impl StateSyncRunner {
         config: StateSyncConfig,
         new_block_receiver: Receiver<SyncBlock>,
         class_manager_client: SharedClassManagerClient,
+        peer_ban_list_clients: PeerBanListClients,
     ) -> (Self, StorageReader) {
         let StateSyncConfig {
             storage_config,
@@ -196,17 +200,22 @@ impl StateSyncRunner {
             let network_manager_metrics = Some(NetworkMetrics {
                 num_connected_peers: P2P_SYNC_NUM_CONNECTED_PEERS,
                 num_blacklisted_peers: P2P_SYNC_NUM_BLACKLISTED_PEERS,
+                num_denied_connections: P2P_SYNC_NUM_DENIED_CONNECTIONS,
                 broadcast_metrics_by_topic: None,
                 sqmr_metrics: Some(SqmrNetworkMetrics {
                     num_active_inbound_sessions: P2P_SYNC_NUM_ACTIVE_INBOUND_SESSIONS,
                     num_active_outbound_sessions: P2P_SYNC_NUM_ACTIVE_OUTBOUND_SESSIONS,
                 }),
             });
-            NetworkManager::new(
+            let network_manager = NetworkManager::new(
                 network_config.clone(),
                 Some(VERSION_FULL.to_string()),
                 network_manager_metrics,
-            )
+            );
+            if let Some(peer_ban_list_client) = network_manager.peer_ban_list_client() {
+                peer_ban_list_clients.insert("state_sync", peer_ban_list_client);
+            }
+            network_manager
         });
 
         // Creating the sync clients futures
@@ -242,6 +251,7 @@ impl StateSyncRunner {
                         pending_classes.clone(),
                         central_sync_client_config,
                         class_manager_client.clone(),
+                        storage_config.db_config.chain_id.clone(),
                     );
 
                     let p2p_sync_client_future = future::pending().boxed();
@@ -315,19 +325,33 @@ impl StateSyncRunner {
         new_block_receiver: Receiver<SyncBlock>,
         class_manager_client: SharedClassManagerClient,
     ) -> P2pSyncClient {
-        let header_client_sender = network_manager
-            .register_sqmr_protocol_client(Protocol::SignedBlockHeader.into(), BUFFER_SIZE);
-        let state_diff_client_sender =
-            network_manager.register_sqmr_protocol_client(Protocol::StateDiff.into(), BUFFER_SIZE);
+        let header_client_sender = network_manager.register_sqmr_protocol_client_with_versions(
+            Protocol::SignedBlockHeader.client_versions(),
+            BUFFER_SIZE,
+        );
+        let state_diff_client_sender = network_manager.register_sqmr_protocol_client_with_versions(
+            Protocol::StateDiff.client_versions(),
+            BUFFER_SIZE,
+        );
         let transaction_client_sender = network_manager
-            .register_sqmr_protocol_client(Protocol::Transaction.into(), BUFFER_SIZE);
-        let class_client_sender =
-            network_manager.register_sqmr_protocol_client(Protocol::Class.into(), BUFFER_SIZE);
+            .register_sqmr_protocol_client_with_versions(
+                Protocol::Transaction.client_versions(),
+                BUFFER_SIZE,
+            );
+        let class_client_sender = network_manager.register_sqmr_protocol_client_with_versions(
+            Protocol::Class.client_versions(),
+            BUFFER_SIZE,
+        );
+        let receipt_client_sender = network_manager.register_sqmr_protocol_client_with_versions(
+            Protocol::Receipt.client_versions(),
+            BUFFER_SIZE,
+        );
         let p2p_sync_client_channels = P2pSyncClientChannels::new(
             header_client_sender,
             state_diff_client_sender,
             transaction_client_sender,
             class_client_sender,
+            receipt_client_sender,
         );
         P2pSyncClient::new(
             p2p_sync_client_config,
@@ -344,22 +368,39 @@ impl StateSyncRunner {
         network_manager: &mut NetworkManager,
         class_manager_client: SharedClassManagerClient,
     ) -> P2pSyncServer {
-        let header_server_receiver = network_manager
-            .register_sqmr_protocol_server(Protocol::SignedBlockHeader.into(), BUFFER_SIZE);
-        let state_diff_server_receiver =
-            network_manager.register_sqmr_protocol_server(Protocol::StateDiff.into(), BUFFER_SIZE);
+        let header_server_receiver = network_manager.register_sqmr_protocol_server_with_versions(
+            Protocol::SignedBlockHeader.server_versions(),
+            BUFFER_SIZE,
+        );
+        let state_diff_server_receiver = network_manager
+            .register_sqmr_protocol_server_with_versions(
+                Protocol::StateDiff.server_versions(),
+                BUFFER_SIZE,
+            );
         let transaction_server_receiver = network_manager
-            .register_sqmr_protocol_server(Protocol::Transaction.into(), BUFFER_SIZE);
-        let class_server_receiver =
-            network_manager.register_sqmr_protocol_server(Protocol::Class.into(), BUFFER_SIZE);
-        let event_server_receiver =
-            network_manager.register_sqmr_protocol_server(Protocol::Event.into(), BUFFER_SIZE);
+            .register_sqmr_protocol_server_with_versions(
+                Protocol::Transaction.server_versions(),
+                BUFFER_SIZE,
+            );
+        let class_server_receiver = network_manager.register_sqmr_protocol_server_with_versions(
+            Protocol::Class.server_versions(),
+            BUFFER_SIZE,
+        );
+        let event_server_receiver = network_manager.register_sqmr_protocol_server_with_versions(
+            Protocol::Event.server_versions(),
+            BUFFER_SIZE,
+        );
+        let receipt_server_receiver = network_manager.register_sqmr_protocol_server_with_versions(
+            Protocol::Receipt.server_versions(),
+            BUFFER_SIZE,
+        );
         let p2p_sync_server_channels = P2pSyncServerChannels::new(
             header_server_receiver,
             state_diff_server_receiver,
             transaction_server_receiver,
             class_server_receiver,
             event_server_receiver,
+            receipt_server_receiver,
         );
         P2pSyncServer::new(storage_reader, p2p_sync_server_channels, class_manager_client)
     }
@@ -372,6 +413,7 @@ impl StateSyncRunner {
         pending_classes: Arc<RwLock<PendingClasses>>,
         central_sync_client_config: CentralSyncClientConfig,
         class_manager_client: SharedClassManagerClient,
+        chain_id: ChainId,
     ) -> CentralStateSync {
         let CentralSyncClientConfig { sync_config, central_source_config } =
             central_sync_client_config;
@@ -394,6 +436,7 @@ impl StateSyncRunner {
             storage_reader.clone(),
             storage_writer,
             Some(class_manager_client),
+            chain_id,
         )
     }
 
@@ -24,8 +24,11 @@ define_metrics!(
         MetricGauge { STATE_SYNC_CLASS_MANAGER_MARKER, "apollo_state_sync_class_manager_marker", "The first block number for which the state sync component does not guarantee all of the corresponding classes are stored in the class manager component" },
         MetricGauge { STATE_SYNC_HEADER_LATENCY_SEC, "apollo_state_sync_header_latency", "The latency, in seconds, between a block timestamp (as state in its header) and the time the state sync component stores the header" },
         // Counters
+        MetricCounter { P2P_SYNC_NUM_DENIED_CONNECTIONS, "apollo_p2p_sync_num_denied_connections", "The number of connections with peers that were denied by the p2p sync component", init = 0 },
         MetricCounter { STATE_SYNC_PROCESSED_TRANSACTIONS, "apollo_state_sync_processed_transactions", "The number of transactions processed by the state sync component", init = 0 },
         MetricCounter { STATE_SYNC_REVERTED_TRANSACTIONS, "apollo_state_sync_reverted_transactions", "The number of transactions reverted by the state sync component", init = 0 },
+        // Histograms
+        MetricHistogram { CENTRAL_SYNC_BLOCK_VERIFICATION_LATENCY, "apollo_central_sync_block_verification_latency_seconds", "The time, in seconds, it takes the central sync component to recalculate and verify the commitments and hash of a block" },
     },
 );
 
@@ -38,6 +41,7 @@ pub fn register_metrics<Mode: TransactionKind>(txn: &StorageTxn<'_, Mode>) {
     STATE_SYNC_PROCESSED_TRANSACTIONS.register();
     STATE_SYNC_REVERTED_TRANSACTIONS.register();
     CENTRAL_SYNC_CENTRAL_BLOCK_MARKER.register();
+    CENTRAL_SYNC_BLOCK_VERIFICATION_LATENCY.register();
     update_marker_metrics(txn);
     reconstruct_processed_transactions_metric(txn);
 }
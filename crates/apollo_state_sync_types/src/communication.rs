@@ -1,7 +1,12 @@
 use std::sync::Arc;
 
 use apollo_infra::component_client::{ClientError, LocalComponentClient, RemoteComponentClient};
-use apollo_infra::component_definitions::{ComponentClient, ComponentRequestAndResponseSender};
+use apollo_infra::component_definitions::{
+    ComponentClient,
+    ComponentRequestAndResponseSender,
+    PrioritizedRequest,
+    RequestPriority,
+};
 use apollo_infra::impl_debug_for_infra_requests_and_responses;
 use apollo_proc_macros::handle_all_response_variants;
 use async_trait::async_trait;
@@ -107,6 +112,23 @@ pub enum StateSyncRequest {
 }
 impl_debug_for_infra_requests_and_responses!(StateSyncRequest);
 
+impl PrioritizedRequest for StateSyncRequest {
+    // Consensus adds the decided blocks, and gets the blocks it fell behind on, whereas the state
+    // reads are made by the gateway.
+    fn priority(&self) -> RequestPriority {
+        match self {
+            StateSyncRequest::AddNewBlock(_) | StateSyncRequest::GetBlock(_) => {
+                RequestPriority::High
+            }
+            StateSyncRequest::GetStorageAt(..)
+            | StateSyncRequest::GetNonceAt(..)
+            | StateSyncRequest::GetClassHashAt(..)
+            | StateSyncRequest::GetLatestBlockNumber()
+            | StateSyncRequest::IsClassDeclaredAt(..) => RequestPriority::Normal,
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, AsRefStr)]
 pub enum StateSyncResponse {
     GetBlock(StateSyncResult<Box<Option<SyncBlock>>>),
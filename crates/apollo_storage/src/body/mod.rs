@@ -41,6 +41,7 @@
 #[cfg(test)]
 mod body_test;
 pub mod events;
+pub mod receipts;
 
 use std::collections::HashSet;
 use std::fmt::Debug;
@@ -456,6 +457,11 @@ impl BodyStorageWriter for StorageTxn<'_, RW> {
 
         markers_table.upsert(&self.txn, &MarkerKind::Body, &block_number)?;
         markers_table.upsert(&self.txn, &MarkerKind::Event, &block_number)?;
+        let receipt_marker =
+            markers_table.get(&self.txn, &MarkerKind::Receipt)?.unwrap_or_default();
+        if receipt_marker > block_number {
+            markers_table.upsert(&self.txn, &MarkerKind::Receipt, &block_number)?;
+        }
         Ok((self, reverted_block_body))
     }
 }
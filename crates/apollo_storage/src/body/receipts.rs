@@ -0,0 +1,176 @@
+//! Interface for completing the transaction outputs of blocks with receipts.
+//!
+//! Block bodies synced from sources that don't provide the full transaction outputs (e.g. the
+//! transactions protocol of p2p sync, which omits the events) can later have their transaction
+//! outputs replaced by the full outputs, block by block.
+//! Import [`ReceiptStorageReader`] and [`ReceiptStorageWriter`] to read and write data related to
+//! the receipts using a [`StorageTxn`].
+//!
+//! # Example
+//! ```
+//! use apollo_storage::body::receipts::{ReceiptStorageReader, ReceiptStorageWriter};
+//! use apollo_storage::body::BodyStorageWriter;
+//! use apollo_storage::open_storage;
+//! # use apollo_storage::{db::DbConfig, StorageConfig};
+//! # use starknet_api::core::ChainId;
+//! use starknet_api::block::{Block, BlockNumber};
+//!
+//! # let dir_handle = tempfile::tempdir().unwrap();
+//! # let dir = dir_handle.path().to_path_buf();
+//! # let db_config = DbConfig {
+//! #     path_prefix: dir,
+//! #     chain_id: ChainId::Mainnet,
+//! #     enforce_file_exists: false,
+//! #     min_size: 1 << 20,    // 1MB
+//! #     max_size: 1 << 35,    // 32GB
+//! #     growth_step: 1 << 26, // 64MB
+//! # };
+//! # let storage_config = StorageConfig{db_config, ..Default::default()};
+//! let (reader, mut writer) = open_storage(storage_config)?;
+//! writer
+//!     .begin_rw_txn()?
+//!     .append_body(BlockNumber(0), Block::default().body)?
+//!     .replace_block_transaction_outputs(BlockNumber(0), vec![])? // Advances the receipt marker.
+//!     .commit()?;
+//! let receipt_marker = reader.begin_ro_txn()?.get_receipt_marker()?;
+//! assert_eq!(receipt_marker, BlockNumber(1));
+//! # Ok::<(), apollo_storage::StorageError>(())
+//! ```
+
+#[cfg(test)]
+#[path = "receipts_test.rs"]
+mod receipts_test;
+
+use std::collections::HashSet;
+
+use starknet_api::block::BlockNumber;
+use starknet_api::transaction::{TransactionOffsetInBlock, TransactionOutput};
+
+use super::{BodyStorageReader, TransactionIndex};
+use crate::db::table_types::{NoValue, Table};
+use crate::db::{TransactionKind, RW};
+use crate::{MarkerKind, OffsetKind, StorageError, StorageResult, StorageTxn, TransactionMetadata};
+
+/// Interface for reading data related to the receipts.
+pub trait ReceiptStorageReader {
+    /// The receipt marker is the first block whose transaction outputs weren't replaced yet.
+    fn get_receipt_marker(&self) -> StorageResult<BlockNumber>;
+}
+
+/// Interface for writing data related to the receipts.
+pub trait ReceiptStorageWriter
+where
+    Self: Sized,
+{
+    /// Replaces the transaction outputs of the block at the receipt marker and advances the
+    /// marker. Outputs that are equal to the stored ones aren't rewritten. The block's body must
+    /// already be in the storage.
+    // To enforce that no commit happen after a failure, we consume and return Self on success.
+    fn replace_block_transaction_outputs(
+        self,
+        block_number: BlockNumber,
+        transaction_outputs: Vec<TransactionOutput>,
+    ) -> StorageResult<Self>;
+}
+
+impl<Mode: TransactionKind> ReceiptStorageReader for StorageTxn<'_, Mode> {
+    fn get_receipt_marker(&self) -> StorageResult<BlockNumber> {
+        let markers_table = self.open_table(&self.tables.markers)?;
+        Ok(markers_table.get(&self.txn, &MarkerKind::Receipt)?.unwrap_or_default())
+    }
+}
+
+impl ReceiptStorageWriter for StorageTxn<'_, RW> {
+    fn replace_block_transaction_outputs(
+        self,
+        block_number: BlockNumber,
+        transaction_outputs: Vec<TransactionOutput>,
+    ) -> StorageResult<Self> {
+        let receipt_marker = self.get_receipt_marker()?;
+        if receipt_marker != block_number {
+            return Err(StorageError::MarkerMismatch {
+                expected: receipt_marker,
+                found: block_number,
+            });
+        }
+        let body_marker = self.get_body_marker()?;
+        if block_number >= body_marker {
+            return Err(StorageError::MarkerMismatch {
+                expected: body_marker,
+                found: block_number,
+            });
+        }
+
+        let markers_table = self.open_table(&self.tables.markers)?;
+        let transaction_metadata_table = self.open_table(&self.tables.transaction_metadata)?;
+        let events_table = self.open_table(&self.tables.events)?;
+        let file_offset_table = self.txn.open_table(&self.tables.file_offsets)?;
+
+        let num_transactions = self.get_block_transactions_count(block_number)?.unwrap_or_default();
+        if num_transactions != transaction_outputs.len() {
+            return Err(StorageError::DBInconsistency {
+                msg: format!(
+                    "Block {block_number} has {num_transactions} transactions, got {} transaction \
+                     outputs.",
+                    transaction_outputs.len()
+                ),
+            });
+        }
+
+        for (offset, transaction_output) in transaction_outputs.iter().enumerate() {
+            let transaction_index =
+                TransactionIndex(block_number, TransactionOffsetInBlock(offset));
+            let TransactionMetadata { tx_hash, tx_location, tx_output_location } =
+                transaction_metadata_table.get(&self.txn, &transaction_index)?.ok_or(
+                    StorageError::DBInconsistency {
+                        msg: format!("Missing transaction metadata at {transaction_index:?}."),
+                    },
+                )?;
+
+            // Outputs that are already complete (e.g. synced from central) are kept in place
+            // instead of appending a copy of them and leaving the old one unreferenced.
+            let old_transaction_output =
+                self.file_handlers.get_transaction_output_unchecked(tx_output_location)?;
+            if old_transaction_output == *transaction_output {
+                continue;
+            }
+
+            // Replace the index of the events of the old output with the index of the new one.
+            for event in old_transaction_output.events() {
+                events_table.delete(&self.txn, &(event.from_address, transaction_index))?;
+            }
+            let contract_addresses: HashSet<_> =
+                transaction_output.events().iter().map(|event| event.from_address).collect();
+            for contract_address in contract_addresses {
+                events_table.upsert(&self.txn, &(contract_address, transaction_index), &NoValue)?;
+            }
+
+            let tx_output_location =
+                self.file_handlers.append_transaction_output(transaction_output);
+            transaction_metadata_table.upsert(
+                &self.txn,
+                &transaction_index,
+                &TransactionMetadata { tx_hash, tx_location, tx_output_location },
+            )?;
+            file_offset_table.upsert(
+                &self.txn,
+                &OffsetKind::TransactionOutput,
+                &tx_output_location.next_offset(),
+            )?;
+        }
+
+        markers_table.upsert(&self.txn, &MarkerKind::Receipt, &block_number.unchecked_next())?;
+        Ok(self)
+    }
+}
+
+impl StorageTxn<'_, RW> {
+    // Starts the receipt marker at the body marker. Used when migrating a storage that was written
+    // before the receipt marker existed.
+    pub(crate) fn start_receipt_marker_at_body_marker(self) -> StorageResult<Self> {
+        let body_marker = self.get_body_marker()?;
+        let markers_table = self.open_table(&self.tables.markers)?;
+        markers_table.upsert(&self.txn, &MarkerKind::Receipt, &body_marker)?;
+        Ok(self)
+    }
+}
@@ -0,0 +1,159 @@
+use apollo_test_utils::get_test_body;
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use starknet_api::block::{BlockBody, BlockNumber};
+use starknet_api::transaction::{
+    EventIndexInTransactionOutput,
+    TransactionHash,
+    TransactionOffsetInBlock,
+    TransactionOutput,
+};
+
+use crate::body::events::{EventIndex, EventsReader};
+use crate::body::receipts::{ReceiptStorageReader, ReceiptStorageWriter};
+use crate::body::{BodyStorageReader, BodyStorageWriter, TransactionIndex};
+use crate::test_utils::get_test_storage;
+use crate::StorageError;
+
+fn without_events(transaction_output: TransactionOutput) -> TransactionOutput {
+    match transaction_output {
+        TransactionOutput::Declare(mut output) => {
+            output.events.clear();
+            TransactionOutput::Declare(output)
+        }
+        TransactionOutput::Deploy(mut output) => {
+            output.events.clear();
+            TransactionOutput::Deploy(output)
+        }
+        TransactionOutput::DeployAccount(mut output) => {
+            output.events.clear();
+            TransactionOutput::DeployAccount(output)
+        }
+        TransactionOutput::Invoke(mut output) => {
+            output.events.clear();
+            TransactionOutput::Invoke(output)
+        }
+        TransactionOutput::L1Handler(mut output) => {
+            output.events.clear();
+            TransactionOutput::L1Handler(output)
+        }
+    }
+}
+
+#[test]
+fn replace_block_transaction_outputs() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    let body = get_test_body(3, Some(2), None, None);
+    let partial_body = BlockBody {
+        transaction_outputs: body.transaction_outputs.iter().cloned().map(without_events).collect(),
+        ..body.clone()
+    };
+    // Transaction hashes are unique across blocks.
+    let next_partial_body = BlockBody {
+        transaction_hashes: partial_body
+            .transaction_hashes
+            .iter()
+            .map(|transaction_hash| TransactionHash(transaction_hash.0 + 3_u64))
+            .collect(),
+        ..partial_body.clone()
+    };
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_body(BlockNumber(0), partial_body)
+        .unwrap()
+        .append_body(BlockNumber(1), next_partial_body)
+        .unwrap()
+        .commit()
+        .unwrap();
+    assert_eq!(reader.begin_ro_txn().unwrap().get_receipt_marker().unwrap(), BlockNumber(0));
+
+    // Blocks must be completed in order.
+    assert_matches!(
+        writer
+            .begin_rw_txn()
+            .unwrap()
+            .replace_block_transaction_outputs(BlockNumber(1), body.transaction_outputs.clone()),
+        Err(StorageError::MarkerMismatch { expected: BlockNumber(0), found: BlockNumber(1) })
+    );
+
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .replace_block_transaction_outputs(BlockNumber(0), body.transaction_outputs.clone())
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_receipt_marker().unwrap(), BlockNumber(1));
+    assert_eq!(
+        txn.get_block_transaction_outputs(BlockNumber(0)).unwrap(),
+        Some(body.transaction_outputs.clone())
+    );
+    // The events of the new outputs are indexed.
+    let event_index = EventIndex(
+        TransactionIndex(BlockNumber(0), TransactionOffsetInBlock(0)),
+        EventIndexInTransactionOutput(0),
+    );
+    let num_events = txn.iter_events(None, event_index, BlockNumber(0)).unwrap().count();
+    assert_eq!(num_events, body.transaction_outputs.iter().map(|o| o.events().len()).sum());
+}
+
+#[test]
+fn replace_block_transaction_outputs_keeps_equal_outputs() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    let body = get_test_body(2, Some(1), None, None);
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_body(BlockNumber(0), body.clone())
+        .unwrap()
+        .commit()
+        .unwrap();
+    let transaction_output_stats = reader.mmap_files_stats()["transaction_output"];
+
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .replace_block_transaction_outputs(BlockNumber(0), body.transaction_outputs.clone())
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    assert_eq!(reader.begin_ro_txn().unwrap().get_receipt_marker().unwrap(), BlockNumber(1));
+    // Nothing was appended to the transaction outputs file.
+    assert_eq!(reader.mmap_files_stats()["transaction_output"], transaction_output_stats);
+    assert_eq!(
+        reader.begin_ro_txn().unwrap().get_block_transaction_outputs(BlockNumber(0)).unwrap(),
+        Some(body.transaction_outputs)
+    );
+}
+
+#[test]
+fn replace_block_transaction_outputs_of_a_missing_block() {
+    let ((_reader, mut writer), _temp_dir) = get_test_storage();
+    assert_matches!(
+        writer.begin_rw_txn().unwrap().replace_block_transaction_outputs(BlockNumber(0), vec![]),
+        Err(StorageError::MarkerMismatch { expected: BlockNumber(0), found: BlockNumber(0) })
+    );
+}
+
+#[test]
+fn revert_body_reverts_receipt_marker() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    let body = get_test_body(2, Some(1), None, None);
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_body(BlockNumber(0), body.clone())
+        .unwrap()
+        .replace_block_transaction_outputs(BlockNumber(0), body.transaction_outputs)
+        .unwrap()
+        .commit()
+        .unwrap();
+    assert_eq!(reader.begin_ro_txn().unwrap().get_receipt_marker().unwrap(), BlockNumber(1));
+
+    writer.begin_rw_txn().unwrap().revert_body(BlockNumber(0)).unwrap().0.commit().unwrap();
+    assert_eq!(reader.begin_ro_txn().unwrap().get_receipt_marker().unwrap(), BlockNumber(0));
+}
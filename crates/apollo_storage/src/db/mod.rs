@@ -39,11 +39,11 @@ use starknet_api::core::ChainId;
 use validator::Validate;
 
 use self::serialization::{Key, ValueSerde};
-use self::table_types::{DbCursor, DbCursorTrait};
+use self::table_types::{CommonPrefix, DbCursor, DbCursorTrait, SimpleTable};
 use crate::db::table_types::TableType;
 
 // Maximum number of Sub-Databases.
-const MAX_DBS: usize = 21;
+const MAX_DBS: usize = 24;
 
 // Note that NO_TLS mode is used by default.
 type EnvironmentKind = WriteMap;
@@ -165,6 +165,10 @@ pub enum DbError {
     /// An error that occurred when trying to append a key when it is not the last.
     #[error("Append error. The key is not the last in the table.")]
     Append,
+    /// An error that occurred when trying to write to a table that is missing from a storage that
+    /// was opened for reading only.
+    #[error("The table '{0}' is missing from the storage.")]
+    MissingTable(&'static str),
 }
 
 type DbResult<V> = result::Result<V, DbError>;
@@ -196,8 +200,22 @@ pub(crate) fn open_env(config: &DbConfig) -> DbResult<(DbReader, DbWriter)> {
     if config.enforce_file_exists && !db_file_path.exists() {
         return Err(DbError::FileDoesNotExist(db_file_path));
     }
+    let env = open_environment(config, libmdbx::Mode::default())?;
+    Ok((DbReader { env: env.clone() }, DbWriter { env }))
+}
+
+/// Opens an existing MDBX environment for reading only, and returns a reader to it.
+pub(crate) fn open_env_read_only(config: &DbConfig) -> DbResult<DbReader> {
+    let db_file_path = config.path().join("mdbx.dat");
+    if !db_file_path.exists() {
+        return Err(DbError::FileDoesNotExist(db_file_path));
+    }
+    Ok(DbReader { env: open_environment(config, libmdbx::Mode::ReadOnly)? })
+}
+
+fn open_environment(config: &DbConfig, mode: libmdbx::Mode) -> DbResult<Arc<Environment>> {
     const MAX_READERS: u32 = 1 << 13; // 8K readers
-    let env = Arc::new(
+    Ok(Arc::new(
         Environment::new()
             .set_geometry(Geometry {
                 size: Some(config.min_size..config.max_size),
@@ -213,11 +231,11 @@ pub(crate) fn open_env(config: &DbConfig) -> DbResult<(DbReader, DbWriter)> {
                 no_rdahead: true,
                 // LIFO policy for recycling a Garbage Collection items should be faster.
                 liforeclaim: true,
+                mode,
                 ..Default::default()
             })
             .open(&config.path())?,
-    );
-    Ok((DbReader { env: env.clone() }, DbWriter { env }))
+    ))
 }
 
 // Size in bytes.
@@ -253,6 +271,83 @@ impl DbReader {
 
 type DbReadTransaction<'env> = DbTransaction<'env, RO>;
 
+/// Provides the identifiers of the tables of the storage. A [`DbWriter`] creates the tables that
+/// don't exist yet, while a [`DbReader`] can't create them, and treats them as empty. This lets a
+/// storage that was written by an older version of the code, before some tables were added, be
+/// read.
+pub(crate) trait TableProvider {
+    fn simple_table<K: Key + Debug, V: ValueSerde + Debug>(
+        &mut self,
+        name: &'static str,
+    ) -> DbResult<TableIdentifier<K, V, SimpleTable>>;
+
+    fn common_prefix_table<MainKey: Key + Debug, SubKey: Key + Debug, V: ValueSerde + Debug>(
+        &mut self,
+        name: &'static str,
+    ) -> DbResult<TableIdentifier<(MainKey, SubKey), V, CommonPrefix>>
+    where
+        (MainKey, SubKey): Key + Debug;
+}
+
+impl TableProvider for DbWriter {
+    fn simple_table<K: Key + Debug, V: ValueSerde + Debug>(
+        &mut self,
+        name: &'static str,
+    ) -> DbResult<TableIdentifier<K, V, SimpleTable>> {
+        self.create_simple_table(name)
+    }
+
+    fn common_prefix_table<MainKey: Key + Debug, SubKey: Key + Debug, V: ValueSerde + Debug>(
+        &mut self,
+        name: &'static str,
+    ) -> DbResult<TableIdentifier<(MainKey, SubKey), V, CommonPrefix>>
+    where
+        (MainKey, SubKey): Key + Debug,
+    {
+        self.create_common_prefix_table(name)
+    }
+}
+
+impl TableProvider for DbReader {
+    fn simple_table<K: Key + Debug, V: ValueSerde + Debug>(
+        &mut self,
+        name: &'static str,
+    ) -> DbResult<TableIdentifier<K, V, SimpleTable>> {
+        self.open_table_if_exists(name)
+    }
+
+    fn common_prefix_table<MainKey: Key + Debug, SubKey: Key + Debug, V: ValueSerde + Debug>(
+        &mut self,
+        name: &'static str,
+    ) -> DbResult<TableIdentifier<(MainKey, SubKey), V, CommonPrefix>>
+    where
+        (MainKey, SubKey): Key + Debug,
+    {
+        self.open_table_if_exists(name)
+    }
+}
+
+impl DbReader {
+    // Returns the identifier of a table, which is marked as missing if the table doesn't exist.
+    fn open_table_if_exists<K: Key + Debug, V: ValueSerde + Debug, T: TableType>(
+        &self,
+        name: &'static str,
+    ) -> DbResult<TableIdentifier<K, V, T>> {
+        let missing = match self.env.begin_ro_txn()?.open_table(Some(name)) {
+            Ok(_) => false,
+            Err(libmdbx::Error::NotFound) => true,
+            Err(err) => return Err(err.into()),
+        };
+        Ok(TableIdentifier {
+            name,
+            missing,
+            _key_type: PhantomData {},
+            _value_type: PhantomData {},
+            _table_type: PhantomData {},
+        })
+    }
+}
+
 impl DbWriter {
     pub(crate) fn begin_rw_txn(&mut self) -> DbResult<DbWriteTransaction<'_>> {
         Ok(DbWriteTransaction { txn: self.env.begin_rw_txn()? })
@@ -267,6 +362,16 @@ impl DbWriteTransaction<'_> {
         self.txn.commit()?;
         Ok(())
     }
+
+    // Removes the table from the database, to simulate a storage that was written before the table
+    // was added.
+    #[cfg(test)]
+    pub(crate) fn drop_table(&self, name: &'static str) -> DbResult<()> {
+        let table = self.txn.open_table(Some(name))?;
+        // Safety: no handle of the table is used after it's dropped.
+        unsafe { self.txn.drop_table(table)? };
+        Ok(())
+    }
 }
 
 #[doc(hidden)]
@@ -284,7 +389,8 @@ impl<Mode: TransactionKind> DbTransaction<'_, Mode> {
         &'env self,
         table_id: &TableIdentifier<K, V, T>,
     ) -> DbResult<TableHandle<'env, K, V, T>> {
-        let database = self.txn.open_table(Some(table_id.name))?;
+        let database =
+            if table_id.missing { None } else { Some(self.txn.open_table(Some(table_id.name))?) };
         Ok(TableHandle {
             database,
             name: table_id.name,
@@ -296,19 +402,29 @@ impl<Mode: TransactionKind> DbTransaction<'_, Mode> {
 }
 pub(crate) struct TableIdentifier<K: Key + Debug, V: ValueSerde + Debug, T: TableType> {
     pub(crate) name: &'static str,
+    // Whether the table is missing from a storage that was opened for reading only.
+    missing: bool,
     _key_type: PhantomData<K>,
     _value_type: PhantomData<V>,
     _table_type: PhantomData<T>,
 }
 
 pub(crate) struct TableHandle<'env, K: Key + Debug, V: ValueSerde + Debug, T: TableType> {
-    database: libmdbx::Table<'env>,
+    // None if the table is missing, in which case it's read as empty.
+    database: Option<libmdbx::Table<'env>>,
     name: &'static str,
     _key_type: PhantomData<K>,
     _value_type: PhantomData<V>,
     _table_type: PhantomData<T>,
 }
 
+impl<'env, K: Key + Debug, V: ValueSerde + Debug, T: TableType> TableHandle<'env, K, V, T> {
+    // Returns the table to write to, which must exist.
+    fn database(&self) -> DbResult<&libmdbx::Table<'env>> {
+        self.database.as_ref().ok_or(DbError::MissingTable(self.name))
+    }
+}
+
 /// Iterator for iterating over a DB table
 pub(crate) struct DbIter<'cursor, 'txn, Mode: TransactionKind, K: Key, V: ValueSerde, T: TableType>
 {
@@ -147,6 +147,7 @@ impl DbWriter {
         txn.commit()?;
         Ok(TableIdentifier {
             name,
+            missing: false,
             _key_type: PhantomData {},
             _value_type: PhantomData {},
             _table_type: PhantomData {},
@@ -165,7 +166,8 @@ impl<'env, K: KeyTrait + Debug, V: ValueSerde + Debug, T: DupSortTableType + Dup
         &'env self,
         txn: &'txn DbTransaction<'env, Mode>,
     ) -> DbResult<DbCursor<'txn, Mode, Self::Key, Self::Value, T>> {
-        let cursor = txn.txn.cursor(&self.database)?;
+        // A missing table is empty, so its cursor has nothing to walk over.
+        let cursor = self.database.as_ref().map(|database| txn.txn.cursor(database)).transpose()?;
         Ok(DbCursor {
             cursor,
             _key_type: PhantomData {},
@@ -179,10 +181,13 @@ impl<'env, K: KeyTrait + Debug, V: ValueSerde + Debug, T: DupSortTableType + Dup
         txn: &'env DbTransaction<'env, Mode>,
         key: &Self::Key,
     ) -> DbResult<Option<<Self::Value as ValueSerde>::Value>> {
+        let Some(database) = &self.database else {
+            return Ok(None);
+        };
         let main_key = T::get_main_key(key)?;
         let first_sub_key = T::get_sub_key_lower_bound(key)?;
 
-        let mut cursor = txn.txn.cursor(&self.database)?;
+        let mut cursor = txn.txn.cursor(database)?;
         let Some(bytes) = cursor.get_both_range::<Cow<'_, [u8]>>(&main_key, &first_sub_key)? else {
             return Ok(None);
         };
@@ -208,7 +213,7 @@ impl<'env, K: KeyTrait + Debug, V: ValueSerde + Debug, T: DupSortTableType + Dup
         let main_key = T::get_main_key(key)?;
         let sub_key_value = T::get_sub_key_and_value(key, value)?;
 
-        let mut cursor = txn.txn.cursor(&self.database)?;
+        let mut cursor = txn.txn.cursor(self.database()?)?;
         cursor.put(&main_key, &sub_key_value, WriteFlags::UPSERT)?;
 
         let sub_key = T::get_sub_key(key)?;
@@ -248,7 +253,7 @@ impl<'env, K: KeyTrait + Debug, V: ValueSerde + Debug, T: DupSortTableType + Dup
         let main_key = T::get_main_key(key)?;
         let sub_key_value = T::get_sub_key_and_value(key, value)?;
 
-        let mut cursor = txn.txn.cursor(&self.database)?;
+        let mut cursor = txn.txn.cursor(self.database()?)?;
         cursor.put(&main_key, &sub_key_value, WriteFlags::NO_DUP_DATA).map_err(
             |err| match err {
                 libmdbx::Error::KeyExist => {
@@ -302,7 +307,7 @@ impl<'env, K: KeyTrait + Debug, V: ValueSerde + Debug, T: DupSortTableType + Dup
         let main_key = T::get_main_key(key)?;
         let sub_key_and_value = T::get_sub_key_and_value(key, value)?;
 
-        let mut cursor = txn.txn.cursor(&self.database)?;
+        let mut cursor = txn.txn.cursor(self.database()?)?;
         match cursor.put(&main_key, &sub_key_and_value, WriteFlags::APPEND_DUP | WriteFlags::APPEND)
         {
             Err(libmdbx::Error::KeyMismatch) => {
@@ -352,7 +357,7 @@ impl<'env, K: KeyTrait + Debug, V: ValueSerde + Debug, T: DupSortTableType + Dup
         let main_key = T::get_main_key(key)?;
         let first_sub_key = T::get_sub_key_lower_bound(key)?;
 
-        let mut cursor = txn.txn.cursor(&self.database)?;
+        let mut cursor = txn.txn.cursor(self.database()?)?;
         let Some(bytes) = cursor.get_both_range::<Cow<'_, [u8]>>(&main_key, &first_sub_key)? else {
             return Ok(());
         };
@@ -386,7 +391,7 @@ impl<'env, K: KeyTrait + Debug, V: ValueSerde + Debug, T: DupSortTableType + Dup
         let main_key = T::get_main_key(key)?;
         let sub_key_and_value = T::get_sub_key_and_value(key, value)?;
 
-        let mut cursor = txn.txn.cursor(&self.database)?;
+        let mut cursor = txn.txn.cursor(self.database()?)?;
         cursor.put(&main_key, &sub_key_and_value, WriteFlags::APPEND_DUP).map_err(
             |err| match err {
                 libmdbx::Error::KeyMismatch => DbError::Append,
@@ -419,7 +424,10 @@ impl<
     type Value = V;
 
     fn prev(&mut self) -> DbResult<Option<(Self::Key, <Self::Value as ValueSerde>::Value)>> {
-        let prev_cursor_res = self.cursor.prev::<DbKeyType<'_>, DbValueType<'_>>()?;
+        let Some(cursor) = &mut self.cursor else {
+            return Ok(None);
+        };
+        let prev_cursor_res = cursor.prev::<DbKeyType<'_>, DbValueType<'_>>()?;
         match prev_cursor_res {
             None => Ok(None),
             Some((main_key_bytes, sub_key_value_bytes)) => {
@@ -429,7 +437,10 @@ impl<
     }
 
     fn next(&mut self) -> DbResult<Option<(Self::Key, <Self::Value as ValueSerde>::Value)>> {
-        let prev_cursor_res = self.cursor.next::<DbKeyType<'_>, DbValueType<'_>>()?;
+        let Some(cursor) = &mut self.cursor else {
+            return Ok(None);
+        };
+        let prev_cursor_res = cursor.next::<DbKeyType<'_>, DbValueType<'_>>()?;
         match prev_cursor_res {
             None => Ok(None),
             Some((main_key_bytes, sub_key_value_bytes)) => {
@@ -445,12 +456,15 @@ impl<
         &mut self,
         key: &Self::Key,
     ) -> DbResult<Option<(Self::Key, <Self::Value as ValueSerde>::Value)>> {
+        let Some(cursor) = &mut self.cursor else {
+            return Ok(None);
+        };
         let mut main_key = T::get_main_key(key)?;
         let first_sub_key = T::get_sub_key_lower_bound(key)?;
 
         // First try to find a match for the main-key.
         if let Some(value_bytes) =
-            self.cursor.get_both_range::<DbValueType<'_>>(&main_key, &first_sub_key)?
+            cursor.get_both_range::<DbValueType<'_>>(&main_key, &first_sub_key)?
         {
             return Ok(T::get_key_value_pair(&main_key, &value_bytes));
         }
@@ -459,7 +473,7 @@ impl<
         T::next_main_key(&mut main_key);
 
         let Some((main_key_bytes, sub_key_value_bytes)) =
-            self.cursor.set_range::<DbKeyType<'_>, DbValueType<'_>>(&main_key)?
+            cursor.set_range::<DbKeyType<'_>, DbValueType<'_>>(&main_key)?
         else {
             return Ok(None);
         };
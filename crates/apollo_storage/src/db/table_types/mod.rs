@@ -91,7 +91,8 @@ pub(crate) trait DbCursorTrait {
 }
 
 pub(crate) struct DbCursor<'txn, Mode: TransactionKind, K: KeyTrait, V: ValueSerde, T: TableType> {
-    cursor: Cursor<'txn, Mode::Internal>,
+    // None if the table is missing from a storage that was opened for reading only.
+    cursor: Option<Cursor<'txn, Mode::Internal>>,
     _key_type: PhantomData<K>,
     _value_type: PhantomData<V>,
     _table_type: PhantomData<T>,
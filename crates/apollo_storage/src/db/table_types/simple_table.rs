@@ -40,6 +40,7 @@ impl DbWriter {
         txn.commit()?;
         Ok(TableIdentifier {
             name,
+            missing: false,
             _key_type: PhantomData {},
             _value_type: PhantomData {},
             _table_type: PhantomData {},
@@ -58,7 +59,8 @@ impl<'env, K: KeyTrait + Debug, V: ValueSerde + Debug> Table<'env>
         &'env self,
         txn: &'txn DbTransaction<'env, Mode>,
     ) -> DbResult<DbCursor<'txn, Mode, Self::Key, Self::Value, SimpleTable>> {
-        let cursor = txn.txn.cursor(&self.database)?;
+        // A missing table is empty, so its cursor has nothing to walk over.
+        let cursor = self.database.as_ref().map(|database| txn.txn.cursor(database)).transpose()?;
         Ok(DbCursor {
             cursor,
             _key_type: PhantomData {},
@@ -72,8 +74,11 @@ impl<'env, K: KeyTrait + Debug, V: ValueSerde + Debug> Table<'env>
         txn: &'env DbTransaction<'env, Mode>,
         key: &Self::Key,
     ) -> DbResult<Option<<Self::Value as ValueSerde>::Value>> {
+        let Some(database) = &self.database else {
+            return Ok(None);
+        };
         let bin_key = key.serialize()?;
-        let Some(bytes) = txn.txn.get::<Cow<'env, [u8]>>(&self.database, &bin_key)? else {
+        let Some(bytes) = txn.txn.get::<Cow<'env, [u8]>>(database, &bin_key)? else {
             return Ok(None);
         };
         let value =
@@ -89,7 +94,7 @@ impl<'env, K: KeyTrait + Debug, V: ValueSerde + Debug> Table<'env>
     ) -> DbResult<()> {
         let data = <Self::Value>::serialize(value)?;
         let bin_key = key.serialize()?;
-        txn.txn.put(&self.database, bin_key, data, WriteFlags::UPSERT)?;
+        txn.txn.put(self.database()?, bin_key, data, WriteFlags::UPSERT)?;
         Ok(())
     }
 
@@ -101,7 +106,7 @@ impl<'env, K: KeyTrait + Debug, V: ValueSerde + Debug> Table<'env>
     ) -> DbResult<()> {
         let data = <Self::Value>::serialize(value)?;
         let bin_key = key.serialize()?;
-        txn.txn.put(&self.database, bin_key, data, WriteFlags::NO_OVERWRITE).map_err(|err| {
+        txn.txn.put(self.database()?, bin_key, data, WriteFlags::NO_OVERWRITE).map_err(|err| {
             match err {
                 libmdbx::Error::KeyExist => {
                     DbError::KeyAlreadyExists(KeyAlreadyExistsError::new(self.name, key, value))
@@ -121,7 +126,7 @@ impl<'env, K: KeyTrait + Debug, V: ValueSerde + Debug> Table<'env>
     ) -> DbResult<()> {
         let data = V::serialize(value)?;
         let bin_key = key.serialize()?;
-        txn.txn.put(&self.database, bin_key, data, WriteFlags::APPEND).map_err(
+        txn.txn.put(self.database()?, bin_key, data, WriteFlags::APPEND).map_err(
             |err| match err {
                 libmdbx::Error::KeyMismatch => DbError::Append,
                 _ => err.into(),
@@ -132,7 +137,7 @@ impl<'env, K: KeyTrait + Debug, V: ValueSerde + Debug> Table<'env>
 
     fn delete(&'env self, txn: &DbTransaction<'env, RW>, key: &Self::Key) -> DbResult<()> {
         let bin_key = key.serialize()?;
-        txn.txn.del(&self.database, bin_key, None)?;
+        txn.txn.del(self.database()?, bin_key, None)?;
         Ok(())
     }
 }
@@ -144,7 +149,10 @@ impl<Mode: TransactionKind, K: KeyTrait + Debug, V: ValueSerde + Debug> DbCursor
     type Value = V;
 
     fn prev(&mut self) -> DbResult<Option<(K, <Self::Value as ValueSerde>::Value)>> {
-        let prev_cursor_res = self.cursor.prev::<DbKeyType<'_>, DbValueType<'_>>()?;
+        let Some(cursor) = &mut self.cursor else {
+            return Ok(None);
+        };
+        let prev_cursor_res = cursor.prev::<DbKeyType<'_>, DbValueType<'_>>()?;
         match prev_cursor_res {
             None => Ok(None),
             Some((key_bytes, value_bytes)) => {
@@ -158,7 +166,10 @@ impl<Mode: TransactionKind, K: KeyTrait + Debug, V: ValueSerde + Debug> DbCursor
     }
 
     fn next(&mut self) -> DbResult<Option<(K, <Self::Value as ValueSerde>::Value)>> {
-        let prev_cursor_res = self.cursor.next::<DbKeyType<'_>, DbValueType<'_>>()?;
+        let Some(cursor) = &mut self.cursor else {
+            return Ok(None);
+        };
+        let prev_cursor_res = cursor.next::<DbKeyType<'_>, DbValueType<'_>>()?;
         match prev_cursor_res {
             None => Ok(None),
             Some((key_bytes, value_bytes)) => {
@@ -175,9 +186,11 @@ impl<Mode: TransactionKind, K: KeyTrait + Debug, V: ValueSerde + Debug> DbCursor
         &mut self,
         key: &K,
     ) -> DbResult<Option<(K, <Self::Value as ValueSerde>::Value)>> {
+        let Some(cursor) = &mut self.cursor else {
+            return Ok(None);
+        };
         let key_bytes = key.serialize()?;
-        let prev_cursor_res =
-            self.cursor.set_range::<DbKeyType<'_>, DbValueType<'_>>(&key_bytes)?;
+        let prev_cursor_res = cursor.set_range::<DbKeyType<'_>, DbValueType<'_>>(&key_bytes)?;
         match prev_cursor_res {
             None => Ok(None),
             Some((key_bytes, value_bytes)) => {
@@ -92,6 +92,7 @@ pub mod header;
 pub mod mmap_file;
 mod serialization;
 pub mod state;
+pub mod state_snapshot;
 mod version;
 
 mod deprecated;
@@ -117,6 +118,7 @@ use db::serialization::{Key, NoVersionValueWrapper, ValueSerde, VersionZeroWrapp
 use db::table_types::{CommonPrefix, NoValue, Table, TableType};
 use mmap_file::{
     open_file,
+    open_file_read_only,
     FileHandler,
     LocationInFile,
     MMapFileError,
@@ -128,6 +130,7 @@ use serde::{Deserialize, Serialize};
 use starknet_api::block::{BlockHash, BlockNumber, BlockSignature, StarknetVersion};
 use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
+use starknet_api::hash::StarkHash;
 use starknet_api::state::{SierraContractClass, StateNumber, StorageKey, ThinStateDiff};
 use starknet_api::transaction::{Transaction, TransactionHash, TransactionOutput};
 use starknet_types_core::felt::Felt;
@@ -139,6 +142,7 @@ use crate::body::TransactionIndex;
 use crate::db::table_types::SimpleTable;
 use crate::db::{
     open_env,
+    open_env_read_only,
     DbConfig,
     DbError,
     DbReader,
@@ -146,6 +150,7 @@ use crate::db::{
     DbWriter,
     TableHandle,
     TableIdentifier,
+    TableProvider,
     TransactionKind,
     RO,
     RW,
@@ -153,13 +158,14 @@ use crate::db::{
 use crate::header::StorageBlockHeader;
 use crate::mmap_file::MMapFileStats;
 use crate::state::data::IndexedDeprecatedContractClass;
+use crate::state_snapshot::SnapshotContract;
 use crate::version::{VersionStorageReader, VersionStorageWriter};
 
 // For more details on the storage version, see the module documentation.
 /// The current version of the storage state code.
-pub const STORAGE_VERSION_STATE: Version = Version { major: 6, minor: 0 };
+pub const STORAGE_VERSION_STATE: Version = Version { major: 6, minor: 1 };
 /// The current version of the storage blocks code.
-pub const STORAGE_VERSION_BLOCKS: Version = Version { major: 6, minor: 0 };
+pub const STORAGE_VERSION_BLOCKS: Version = Version { major: 6, minor: 1 };
 
 /// Opens a storage and returns a [`StorageReader`] and a [`StorageWriter`].
 pub fn open_storage(
@@ -174,36 +180,7 @@ pub fn open_storage(
     }
 
     let (db_reader, mut db_writer) = open_env(&storage_config.db_config)?;
-    let tables = Arc::new(Tables {
-        block_hash_to_number: db_writer.create_simple_table("block_hash_to_number")?,
-        block_signatures: db_writer.create_simple_table("block_signatures")?,
-        casms: db_writer.create_simple_table("casms")?,
-        contract_storage: db_writer.create_common_prefix_table("contract_storage")?,
-        declared_classes: db_writer.create_simple_table("declared_classes")?,
-        declared_classes_block: db_writer.create_simple_table("declared_classes_block")?,
-        deprecated_declared_classes: db_writer
-            .create_simple_table("deprecated_declared_classes")?,
-        deprecated_declared_classes_block: db_writer
-            .create_simple_table("deprecated_declared_classes_block")?,
-        deployed_contracts: db_writer.create_simple_table("deployed_contracts")?,
-        events: db_writer.create_common_prefix_table("events")?,
-        headers: db_writer.create_simple_table("headers")?,
-        markers: db_writer.create_simple_table("markers")?,
-        nonces: db_writer.create_common_prefix_table("nonces")?,
-        file_offsets: db_writer.create_simple_table("file_offsets")?,
-        state_diffs: db_writer.create_simple_table("state_diffs")?,
-        transaction_hash_to_idx: db_writer.create_simple_table("transaction_hash_to_idx")?,
-        transaction_metadata: db_writer.create_simple_table("transaction_metadata")?,
-
-        // Version tables.
-        starknet_version: db_writer.create_simple_table("starknet_version")?,
-        storage_version: db_writer.create_simple_table("storage_version")?,
-
-        // Class hashes.
-        compiled_class_hash: db_writer.create_common_prefix_table("compiled_class_hash")?,
-        stateless_compiled_class_hash_v2: db_writer
-            .create_simple_table("stateless_compiled_class_hash_v2")?,
-    });
+    let tables = Arc::new(open_tables(&mut db_writer)?);
     let (file_writers, file_readers) = open_storage_files(
         &storage_config.db_config,
         storage_config.mmap_file_config,
@@ -224,6 +201,91 @@ pub fn open_storage(
     Ok((reader, writer))
 }
 
+/// Opens an existing storage for reading only, and returns a [`StorageReader`].
+/// Unlike [`open_storage`], nothing is ever written to the storage: the storage version must
+/// already exist, and the scope of the storage is taken from its version. A storage written by an
+/// older minor version is accepted, and the tables it doesn't have yet are read as empty.
+pub fn open_storage_read_only(
+    db_config: DbConfig,
+    mmap_file_config: MmapFileConfig,
+) -> StorageResult<StorageReader> {
+    info!("Opening storage for reading only: {}", db_config.path_prefix.display());
+    let mut db_reader = open_env_read_only(&db_config)?;
+    let tables = Arc::new(open_tables(&mut db_reader)?);
+    let file_readers = open_storage_files_read_only(
+        &db_config,
+        mmap_file_config,
+        db_reader.clone(),
+        &tables.file_offsets,
+    )?;
+
+    let mut reader =
+        StorageReader { db_reader, tables, scope: StorageScope::default(), file_readers };
+    reader.scope = match get_storage_version(reader.clone())? {
+        Some(StorageVersion::FullArchive(FullArchiveVersion { state_version, blocks_version })) => {
+            verify_readable_version(STORAGE_VERSION_STATE, state_version)?;
+            verify_readable_version(STORAGE_VERSION_BLOCKS, blocks_version)?;
+            StorageScope::FullArchive
+        }
+        Some(StorageVersion::StateOnly(StateOnlyVersion { state_version })) => {
+            verify_readable_version(STORAGE_VERSION_STATE, state_version)?;
+            StorageScope::StateOnly
+        }
+        None => {
+            return Err(StorageError::StorageVersionInconsistency(
+                StorageVersionError::MissingStorageVersion,
+            ));
+        }
+    };
+    Ok(reader)
+}
+
+// A storage can be read by a crate with the same major version and the same or a newer minor
+// version, since minor versions only add tables.
+fn verify_readable_version(crate_version: Version, storage_version: Version) -> StorageResult<()> {
+    if crate_version.major != storage_version.major || crate_version.minor < storage_version.minor {
+        return Err(StorageError::StorageVersionInconsistency(
+            StorageVersionError::InconsistentStorageVersion { crate_version, storage_version },
+        ));
+    }
+    Ok(())
+}
+
+fn open_tables(table_provider: &mut impl TableProvider) -> StorageResult<Tables> {
+    Ok(Tables {
+        block_hash_to_number: table_provider.simple_table("block_hash_to_number")?,
+        block_signatures: table_provider.simple_table("block_signatures")?,
+        casms: table_provider.simple_table("casms")?,
+        contract_storage: table_provider.common_prefix_table("contract_storage")?,
+        declared_classes: table_provider.simple_table("declared_classes")?,
+        declared_classes_block: table_provider.simple_table("declared_classes_block")?,
+        deprecated_declared_classes: table_provider.simple_table("deprecated_declared_classes")?,
+        deprecated_declared_classes_block: table_provider
+            .simple_table("deprecated_declared_classes_block")?,
+        deployed_contracts: table_provider.simple_table("deployed_contracts")?,
+        events: table_provider.common_prefix_table("events")?,
+        headers: table_provider.simple_table("headers")?,
+        markers: table_provider.simple_table("markers")?,
+        nonces: table_provider.common_prefix_table("nonces")?,
+        snapshot_contracts: table_provider.simple_table("snapshot_contracts")?,
+        snapshot_storage: table_provider.simple_table("snapshot_storage")?,
+        snapshot_tries: table_provider.simple_table("snapshot_tries")?,
+        file_offsets: table_provider.simple_table("file_offsets")?,
+        state_diffs: table_provider.simple_table("state_diffs")?,
+        transaction_hash_to_idx: table_provider.simple_table("transaction_hash_to_idx")?,
+        transaction_metadata: table_provider.simple_table("transaction_metadata")?,
+
+        // Version tables.
+        starknet_version: table_provider.simple_table("starknet_version")?,
+        storage_version: table_provider.simple_table("storage_version")?,
+
+        // Class hashes.
+        compiled_class_hash: table_provider.common_prefix_table("compiled_class_hash")?,
+        stateless_compiled_class_hash_v2: table_provider
+            .simple_table("stateless_compiled_class_hash_v2")?,
+    })
+}
+
 // In case storage version does not exist, set it to the crate version.
 // Expected to happen once - when the node is launched for the first time.
 // If the storage scope has changed, update accordingly.
@@ -291,6 +353,12 @@ fn set_version_if_needed(
                     "Updating the storage blocks version from {:?} to {:?}",
                     blocks_version, STORAGE_VERSION_BLOCKS
                 );
+                // The receipt marker was added in blocks version 6.1. The bodies written before it
+                // have no receipts to complete their transaction outputs with, so they're kept
+                // as is instead of being downloaded and rewritten.
+                if blocks_version.minor < 1 {
+                    wtxn = wtxn.start_receipt_marker_at_body_marker()?;
+                }
                 wtxn = wtxn.set_blocks_version(&STORAGE_VERSION_BLOCKS)?;
             }
         }
@@ -543,6 +611,10 @@ struct_field_names! {
         headers: TableIdentifier<BlockNumber, VersionZeroWrapper<StorageBlockHeader>, SimpleTable>,
         markers: TableIdentifier<MarkerKind, VersionZeroWrapper<BlockNumber>, SimpleTable>,
         nonces: TableIdentifier<(ContractAddress, BlockNumber), VersionZeroWrapper<Nonce>, CommonPrefix>,
+        // The state snapshot, which is the state without the blocks that led to it.
+        snapshot_contracts: TableIdentifier<ContractAddress, VersionZeroWrapper<SnapshotContract>, SimpleTable>,
+        snapshot_storage: TableIdentifier<(StarkHash, StorageKey), NoVersionValueWrapper<Felt>, SimpleTable>,
+        snapshot_tries: TableIdentifier<StarkHash, NoVersionValueWrapper<NoValue>, SimpleTable>,
         file_offsets: TableIdentifier<OffsetKind, NoVersionValueWrapper<usize>, SimpleTable>,
         state_diffs: TableIdentifier<BlockNumber, VersionZeroWrapper<LocationInFile>, SimpleTable>,
         transaction_hash_to_idx: TableIdentifier<TransactionHash, NoVersionValueWrapper<TransactionIndex>, SimpleTable>,
@@ -684,6 +756,7 @@ pub(crate) enum MarkerKind {
     /// Marks the block beyond the last block that its classes can't be compiled with the current
     /// compiler version used in the class manager. Determined by starknet version.
     CompilerBackwardCompatibility,
+    Receipt,
 }
 
 pub(crate) type MarkersTable<'env> =
@@ -888,6 +961,52 @@ fn open_storage_files(
     ))
 }
 
+fn open_storage_files_read_only(
+    db_config: &DbConfig,
+    mmap_file_config: MmapFileConfig,
+    db_reader: DbReader,
+    file_offsets_table: &TableIdentifier<OffsetKind, NoVersionValueWrapper<usize>, SimpleTable>,
+) -> StorageResult<FileHandlers<RO>> {
+    let db_transaction = db_reader.begin_ro_txn()?;
+    let table = db_transaction.open_table(file_offsets_table)?;
+    let offset = |offset_kind| -> StorageResult<usize> {
+        Ok(table.get(&db_transaction, &offset_kind)?.unwrap_or_default())
+    };
+
+    Ok(FileHandlers {
+        thin_state_diff: open_file_read_only(
+            mmap_file_config.clone(),
+            db_config.path().join("thin_state_diff.dat"),
+            offset(OffsetKind::ThinStateDiff)?,
+        )?,
+        contract_class: open_file_read_only(
+            mmap_file_config.clone(),
+            db_config.path().join("contract_class.dat"),
+            offset(OffsetKind::ContractClass)?,
+        )?,
+        casm: open_file_read_only(
+            mmap_file_config.clone(),
+            db_config.path().join("casm.dat"),
+            offset(OffsetKind::Casm)?,
+        )?,
+        deprecated_contract_class: open_file_read_only(
+            mmap_file_config.clone(),
+            db_config.path().join("deprecated_contract_class.dat"),
+            offset(OffsetKind::DeprecatedContractClass)?,
+        )?,
+        transaction_output: open_file_read_only(
+            mmap_file_config.clone(),
+            db_config.path().join("transaction_output.dat"),
+            offset(OffsetKind::TransactionOutput)?,
+        )?,
+        transaction: open_file_read_only(
+            mmap_file_config,
+            db_config.path().join("transaction.dat"),
+            offset(OffsetKind::Transaction)?,
+        )?,
+    })
+}
+
 /// Represents a kind of mmap file.
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Ord)]
 pub enum OffsetKind {
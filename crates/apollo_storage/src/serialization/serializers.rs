@@ -142,6 +142,7 @@ use crate::mmap_file::LocationInFile;
 #[cfg(test)]
 use crate::serialization::serializers_test::{create_storage_serde_test, StorageSerdeTest};
 use crate::state::data::IndexedDeprecatedContractClass;
+use crate::state_snapshot::SnapshotContract;
 use crate::version::Version;
 use crate::{MarkerKind, OffsetKind, TransactionMetadata};
 
@@ -329,6 +330,7 @@ auto_storage_serde! {
         BaseLayerBlock = 6,
         ClassManagerBlock = 7,
         CompilerBackwardCompatibility = 8,
+        Receipt = 9,
     }
     pub struct MessageToL1 {
         pub to_address: EthAddress,
@@ -377,6 +379,11 @@ auto_storage_serde! {
         pub max_price_per_unit: GasPrice,
     }
     pub struct SequencerContractAddress(pub ContractAddress);
+    pub struct SnapshotContract {
+        pub class_hash: ClassHash,
+        pub nonce: Nonce,
+        pub storage_root: StarkHash,
+    }
     pub struct Signature {
         pub r: Felt,
         pub s: Felt,
@@ -519,6 +526,7 @@ auto_storage_serde! {
     (ContractAddress, StorageKey);
     (ContractAddress, TransactionIndex);
     ((ContractAddress, StorageKey), BlockNumber);
+    (StarkHash, StorageKey);
     (usize, Vec<Hint>);
     (usize, Vec<String>);
 }
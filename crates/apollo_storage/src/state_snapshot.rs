@@ -0,0 +1,169 @@
+//! Interface for handling a snapshot of the state.
+//!
+//! A state snapshot is the state at some contracts trie root, downloaded without the blocks that
+//! led to it. The storage of each contract is stored by the root of its storage trie, so contracts
+//! with the same storage share it.
+//!
+//! Import [`StateSnapshotStorageReader`] and [`StateSnapshotStorageWriter`] to read and write
+//! the state snapshot using a [`StorageTxn`].
+//! # Example
+//! ```
+//! use apollo_storage::open_storage;
+//! use apollo_storage::state_snapshot::{
+//!     SnapshotContract,
+//!     StateSnapshotStorageReader,
+//!     StateSnapshotStorageWriter,
+//! };
+//! use starknet_api::core::ContractAddress;
+//! use starknet_api::hash::StarkHash;
+//! # use apollo_storage::{db::DbConfig, StorageConfig};
+//! # use starknet_api::core::ChainId;
+//!
+//! # let dir_handle = tempfile::tempdir().unwrap();
+//! # let dir = dir_handle.path().to_path_buf();
+//! # let db_config = DbConfig {
+//! #     path_prefix: dir,
+//! #     chain_id: ChainId::Mainnet,
+//! #     enforce_file_exists: false,
+//! #     min_size: 1 << 20,    // 1MB
+//! #     max_size: 1 << 35,    // 32GB
+//! #     growth_step: 1 << 26, // 64MB
+//! # };
+//! # let storage_config = StorageConfig{db_config, ..Default::default()};
+//! let contracts = vec![(ContractAddress::from(1_u8), SnapshotContract::default())];
+//! let (reader, mut writer) = open_storage(storage_config)?;
+//! writer
+//!     .begin_rw_txn()?
+//!     .append_snapshot_contracts(&contracts)?
+//!     .mark_snapshot_trie_complete(&StarkHash::ONE)?
+//!     .commit()?;
+//!
+//! let txn = reader.begin_ro_txn()?;
+//! assert_eq!(
+//!     txn.get_snapshot_contract(&ContractAddress::from(1_u8))?,
+//!     Some(SnapshotContract::default())
+//! );
+//! assert!(txn.is_snapshot_trie_complete(&StarkHash::ONE)?);
+//! # Ok::<(), apollo_storage::StorageError>(())
+//! ```
+
+#[cfg(test)]
+#[path = "state_snapshot_test.rs"]
+mod state_snapshot_test;
+
+use starknet_api::core::{ClassHash, ContractAddress, Nonce};
+use starknet_api::hash::StarkHash;
+use starknet_api::state::StorageKey;
+use starknet_types_core::felt::Felt;
+
+use crate::db::table_types::{NoValue, Table};
+use crate::db::{TransactionKind, RW};
+use crate::{StorageResult, StorageTxn};
+
+/// A contract in the state snapshot.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct SnapshotContract {
+    pub class_hash: ClassHash,
+    pub nonce: Nonce,
+    /// The root of the contract's storage trie, which is zero if the storage is empty.
+    pub storage_root: StarkHash,
+}
+
+/// Interface for reading the state snapshot.
+pub trait StateSnapshotStorageReader {
+    /// Returns the contract at the given address in the state snapshot.
+    fn get_snapshot_contract(
+        &self,
+        address: &ContractAddress,
+    ) -> StorageResult<Option<SnapshotContract>>;
+
+    /// Returns the value at the given key of the storage trie with the given root.
+    fn get_snapshot_storage_at(
+        &self,
+        storage_root: &StarkHash,
+        key: &StorageKey,
+    ) -> StorageResult<Option<Felt>>;
+
+    /// Returns whether all the leaves of the trie with the given root were written.
+    fn is_snapshot_trie_complete(&self, root: &StarkHash) -> StorageResult<bool>;
+}
+
+/// Interface for writing the state snapshot.
+pub trait StateSnapshotStorageWriter
+where
+    Self: Sized,
+{
+    /// Writes contracts of the state snapshot. Contracts that were already written are
+    /// overwritten.
+    // To enforce that no commit happen after a failure, we consume and return Self on success.
+    fn append_snapshot_contracts(
+        self,
+        contracts: &[(ContractAddress, SnapshotContract)],
+    ) -> StorageResult<Self>;
+
+    /// Writes leaves of the storage trie with the given root. Leaves that were already written are
+    /// overwritten.
+    fn append_snapshot_storage(
+        self,
+        storage_root: &StarkHash,
+        storage: &[(StorageKey, Felt)],
+    ) -> StorageResult<Self>;
+
+    /// Marks that all the leaves of the trie with the given root were written.
+    fn mark_snapshot_trie_complete(self, root: &StarkHash) -> StorageResult<Self>;
+}
+
+impl<Mode: TransactionKind> StateSnapshotStorageReader for StorageTxn<'_, Mode> {
+    fn get_snapshot_contract(
+        &self,
+        address: &ContractAddress,
+    ) -> StorageResult<Option<SnapshotContract>> {
+        let snapshot_contracts_table = self.open_table(&self.tables.snapshot_contracts)?;
+        Ok(snapshot_contracts_table.get(&self.txn, address)?)
+    }
+
+    fn get_snapshot_storage_at(
+        &self,
+        storage_root: &StarkHash,
+        key: &StorageKey,
+    ) -> StorageResult<Option<Felt>> {
+        let snapshot_storage_table = self.open_table(&self.tables.snapshot_storage)?;
+        Ok(snapshot_storage_table.get(&self.txn, &(*storage_root, *key))?)
+    }
+
+    fn is_snapshot_trie_complete(&self, root: &StarkHash) -> StorageResult<bool> {
+        let snapshot_tries_table = self.open_table(&self.tables.snapshot_tries)?;
+        Ok(snapshot_tries_table.get(&self.txn, root)?.is_some())
+    }
+}
+
+impl StateSnapshotStorageWriter for StorageTxn<'_, RW> {
+    fn append_snapshot_contracts(
+        self,
+        contracts: &[(ContractAddress, SnapshotContract)],
+    ) -> StorageResult<Self> {
+        let snapshot_contracts_table = self.open_table(&self.tables.snapshot_contracts)?;
+        for (address, contract) in contracts {
+            snapshot_contracts_table.upsert(&self.txn, address, contract)?;
+        }
+        Ok(self)
+    }
+
+    fn append_snapshot_storage(
+        self,
+        storage_root: &StarkHash,
+        storage: &[(StorageKey, Felt)],
+    ) -> StorageResult<Self> {
+        let snapshot_storage_table = self.open_table(&self.tables.snapshot_storage)?;
+        for (key, value) in storage {
+            snapshot_storage_table.upsert(&self.txn, &(*storage_root, *key), value)?;
+        }
+        Ok(self)
+    }
+
+    fn mark_snapshot_trie_complete(self, root: &StarkHash) -> StorageResult<Self> {
+        let snapshot_tries_table = self.open_table(&self.tables.snapshot_tries)?;
+        snapshot_tries_table.upsert(&self.txn, root, &NoValue)?;
+        Ok(self)
+    }
+}
@@ -0,0 +1,66 @@
+use starknet_api::core::{ClassHash, ContractAddress, Nonce};
+use starknet_api::hash::StarkHash;
+use starknet_api::state::StorageKey;
+use starknet_types_core::felt::Felt;
+
+use crate::state_snapshot::{
+    SnapshotContract,
+    StateSnapshotStorageReader,
+    StateSnapshotStorageWriter,
+};
+use crate::test_utils::get_test_storage;
+
+#[test]
+fn append_and_get_snapshot_contracts() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    let address = ContractAddress::from(1_u8);
+    let contract = SnapshotContract {
+        class_hash: ClassHash(Felt::TWO),
+        nonce: Nonce(Felt::THREE),
+        storage_root: StarkHash::from(4_u8),
+    };
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_snapshot_contracts(&[(address, contract.clone())])
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_snapshot_contract(&address).unwrap(), Some(contract));
+    assert_eq!(txn.get_snapshot_contract(&ContractAddress::from(2_u8)).unwrap(), None);
+}
+
+#[test]
+fn snapshot_storage_is_shared_by_root() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    let storage_root = StarkHash::ONE;
+    let other_storage_root = StarkHash::TWO;
+    let key = StorageKey::from(5_u8);
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_snapshot_storage(&storage_root, &[(key, Felt::THREE)])
+        .unwrap()
+        .append_snapshot_storage(&other_storage_root, &[(key, Felt::TWO)])
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_snapshot_storage_at(&storage_root, &key).unwrap(), Some(Felt::THREE));
+    assert_eq!(txn.get_snapshot_storage_at(&other_storage_root, &key).unwrap(), Some(Felt::TWO));
+    assert_eq!(txn.get_snapshot_storage_at(&storage_root, &StorageKey::from(6_u8)).unwrap(), None);
+}
+
+#[test]
+fn mark_snapshot_trie_complete() {
+    let ((reader, mut writer), _temp_dir) = get_test_storage();
+    let root = StarkHash::ONE;
+    assert!(!reader.begin_ro_txn().unwrap().is_snapshot_trie_complete(&root).unwrap());
+
+    writer.begin_rw_txn().unwrap().mark_snapshot_trie_complete(&root).unwrap().commit().unwrap();
+
+    assert!(reader.begin_ro_txn().unwrap().is_snapshot_trie_complete(&root).unwrap());
+}
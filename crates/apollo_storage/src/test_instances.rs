@@ -1,8 +1,10 @@
 use apollo_test_utils::{auto_impl_get_test_instance, get_number_of_variants, GetTestInstance};
 use starknet_api::block::{BlockHash, BlockNumber, BlockTimestamp, GasPrice, GasPricePerToken};
 use starknet_api::core::{
+    ClassHash,
     EventCommitment,
     GlobalRoot,
+    Nonce,
     ReceiptCommitment,
     SequencerContractAddress,
     StateDiffCommitment,
@@ -10,6 +12,7 @@ use starknet_api::core::{
 };
 use starknet_api::data_availability::L1DataAvailabilityMode;
 use starknet_api::execution_resources::GasAmount;
+use starknet_api::hash::StarkHash;
 use starknet_api::transaction::{
     EventIndexInTransactionOutput,
     TransactionHash,
@@ -21,6 +24,7 @@ use crate::compression_utils::IsCompressed;
 use crate::header::StorageBlockHeader;
 use crate::mmap_file::LocationInFile;
 use crate::state::data::IndexedDeprecatedContractClass;
+use crate::state_snapshot::SnapshotContract;
 use crate::version::Version;
 use crate::{EventIndex, MarkerKind, OffsetKind, TransactionMetadata};
 
@@ -66,6 +70,7 @@ auto_impl_get_test_instance! {
         BaseLayerBlock = 6,
         ClassManagerBlock = 7,
         CompilerBackwardCompatibility = 8,
+        Receipt = 9,
     }
     pub enum OffsetKind {
         ThinStateDiff = 0,
@@ -73,6 +78,11 @@ auto_impl_get_test_instance! {
         Casm = 2,
         DeprecatedContractClass = 3,
     }
+    pub struct SnapshotContract {
+        pub class_hash: ClassHash,
+        pub nonce: Nonce,
+        pub storage_root: StarkHash,
+    }
     pub struct TransactionMetadata{
         pub tx_hash: TransactionHash,
         pub tx_location: LocationInFile,
@@ -39,6 +39,9 @@ pub enum StorageVersionError {
          existing one {storage_version}."
     )]
     SetMajorVersion { crate_version: Version, storage_version: Version },
+
+    #[error("The storage has no version, it was never opened for writing.")]
+    MissingStorageVersion,
 }
 
 pub trait VersionStorageReader {
@@ -1,9 +1,16 @@
 use assert_matches::assert_matches;
 use pretty_assertions::assert_eq;
 use rand::Rng;
+use starknet_api::block::{BlockBody, BlockNumber};
+use starknet_api::hash::StarkHash;
 
+use crate::body::receipts::ReceiptStorageReader;
+use crate::body::BodyStorageWriter;
 use crate::db::table_types::Table;
+use crate::db::DbError;
+use crate::state_snapshot::StateSnapshotStorageReader;
 use crate::test_utils::{
+    get_test_config,
     get_test_storage,
     get_test_storage_by_scope,
     get_test_storage_with_config_by_scope,
@@ -18,6 +25,7 @@ use crate::version::{
 };
 use crate::{
     open_storage,
+    open_storage_read_only,
     set_version_if_needed,
     verify_storage_version,
     StorageError,
@@ -91,6 +99,13 @@ fn set_state_version_test() {
 fn version_migration() {
     let ((reader, mut writer), config, _temp_dir) =
         get_test_storage_with_config_by_scope(StorageScope::FullArchive);
+    writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_body(BlockNumber(0), BlockBody::default())
+        .unwrap()
+        .commit()
+        .unwrap();
 
     // Set the storage version on a lower minor version.
     change_storage_version(
@@ -113,6 +128,8 @@ fn version_migration() {
     assert_eq!(version_state.unwrap(), STORAGE_VERSION_STATE);
     let version_blocks = reader.begin_ro_txn().unwrap().get_blocks_version().unwrap();
     assert_eq!(version_blocks.unwrap(), STORAGE_VERSION_BLOCKS);
+    // The bodies from before the receipt marker existed aren't completed with receipts.
+    assert_eq!(reader.begin_ro_txn().unwrap().get_receipt_marker().unwrap(), BlockNumber(1));
 }
 
 #[test]
@@ -287,3 +304,74 @@ fn test_set_version_if_needed() {
         "Should fail, because storage scope cannot shift from state-only to full-archive."
     );
 }
+
+#[test]
+fn open_storage_read_only_takes_scope_from_storage() {
+    for scope in [StorageScope::FullArchive, StorageScope::StateOnly] {
+        let ((reader, writer), config, _temp_dir) = get_test_storage_with_config_by_scope(scope);
+        drop(reader);
+        drop(writer);
+
+        let reader = open_storage_read_only(config.db_config, config.mmap_file_config).unwrap();
+        assert_eq!(reader.get_scope(), scope);
+        let version_state = reader.begin_ro_txn().unwrap().get_state_version().unwrap();
+        assert_eq!(version_state, Some(STORAGE_VERSION_STATE));
+    }
+}
+
+#[test]
+fn open_storage_read_only_different_state_major_version() {
+    let ((reader, mut writer), config, _temp_dir) =
+        get_test_storage_with_config_by_scope(StorageScope::FullArchive);
+    let different_state_major_version = get_different_major_version(STORAGE_VERSION_STATE);
+    change_storage_version(&mut writer, VERSION_STATE_KEY, &different_state_major_version);
+    drop(reader);
+    drop(writer);
+
+    let Err(err) = open_storage_read_only(config.db_config, config.mmap_file_config) else {
+        panic!("Unexpected Ok.");
+    };
+    assert_matches!(
+        err,
+        StorageError::StorageVersionInconsistency(StorageVersionError::InconsistentStorageVersion {
+            crate_version,
+            storage_version
+        })
+        if crate_version == STORAGE_VERSION_STATE && storage_version == different_state_major_version
+    );
+}
+
+#[test]
+fn open_storage_read_only_does_not_create_storage() {
+    let (mut config, temp_dir) = get_test_config(None);
+    config.db_config.path_prefix = temp_dir.path().join("missing_storage");
+
+    let Err(err) = open_storage_read_only(config.db_config.clone(), config.mmap_file_config) else {
+        panic!("Unexpected Ok.");
+    };
+    assert_matches!(err, StorageError::InnerError(DbError::FileDoesNotExist(_)));
+    assert!(!config.db_config.path_prefix.exists());
+}
+
+#[test]
+fn open_storage_read_only_older_minor_version_without_new_tables() {
+    let ((reader, mut writer), config, _temp_dir) =
+        get_test_storage_with_config_by_scope(StorageScope::FullArchive);
+    // Turn the storage into one that was written by version 6.0, before the state snapshot
+    // tables were added.
+    let older_minor_version = Version { major: STORAGE_VERSION_STATE.major, minor: 0 };
+    change_storage_version(&mut writer, VERSION_STATE_KEY, &older_minor_version);
+    change_storage_version(&mut writer, VERSION_BLOCKS_KEY, &older_minor_version);
+    let wtxn = writer.db_writer.begin_rw_txn().unwrap();
+    for table in ["snapshot_contracts", "snapshot_storage", "snapshot_tries"] {
+        wtxn.drop_table(table).unwrap();
+    }
+    wtxn.commit().unwrap();
+    drop(reader);
+    drop(writer);
+
+    let reader = open_storage_read_only(config.db_config, config.mmap_file_config).unwrap();
+    let txn = reader.begin_ro_txn().unwrap();
+    assert_eq!(txn.get_state_version().unwrap(), Some(older_minor_version));
+    assert!(!txn.is_snapshot_trie_complete(&StarkHash::ONE).unwrap());
+}
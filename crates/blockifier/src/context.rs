@@ -145,6 +145,10 @@ impl BlockContext {
         &self.versioned_constants
     }
 
+    pub fn bouncer_config(&self) -> &BouncerConfig {
+        &self.bouncer_config
+    }
+
     pub fn to_tx_context(
         &self,
         tx_info_creator: &impl TransactionInfoCreator,
@@ -7,6 +7,7 @@ pub mod global_cache;
 #[cfg(feature = "cairo_native")]
 pub mod native_class_manager;
 pub mod state_api;
+pub mod state_diff_mismatch;
 pub mod state_reader_and_contract_manager;
 pub mod stateful_compression;
 #[cfg(any(feature = "testing", test))]
@@ -0,0 +1,92 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Debug;
+
+use serde::{Deserialize, Serialize};
+
+use crate::state::cached_state::CommitmentStateDiff;
+
+#[cfg(test)]
+#[path = "state_diff_mismatch_test.rs"]
+mod test;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StateDiffEntryKind {
+    ClassHash,
+    Nonce,
+    Storage,
+    CompiledClassHash,
+}
+
+/// A state diff entry whose value differs between the expected and the actual state diffs.
+/// A `None` value means the entry is missing from the corresponding state diff.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct StateDiffMismatch {
+    pub kind: StateDiffEntryKind,
+    pub key: String,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+}
+
+fn compare_maps<K: Ord + Debug, V: PartialEq + Debug>(
+    kind: StateDiffEntryKind,
+    expected: &BTreeMap<K, V>,
+    actual: &BTreeMap<K, V>,
+    mismatches: &mut Vec<StateDiffMismatch>,
+) {
+    let keys: BTreeSet<&K> = expected.keys().chain(actual.keys()).collect();
+    for key in keys {
+        let (expected_value, actual_value) = (expected.get(key), actual.get(key));
+        if expected_value != actual_value {
+            mismatches.push(StateDiffMismatch {
+                kind,
+                key: format!("{key:?}"),
+                expected: expected_value.map(|value| format!("{value:?}")),
+                actual: actual_value.map(|value| format!("{value:?}")),
+            });
+        }
+    }
+}
+
+/// Returns the entries that differ between the two state diffs, ignoring insertion order.
+pub fn state_diff_mismatches(
+    expected_state_diff: CommitmentStateDiff,
+    actual_state_diff: CommitmentStateDiff,
+) -> Vec<StateDiffMismatch> {
+    let flatten_storage = |state_diff: &CommitmentStateDiff| {
+        state_diff
+            .storage_updates
+            .iter()
+            .flat_map(|(address, storage)| {
+                storage.iter().map(move |(key, value)| ((*address, *key), *value))
+            })
+            .collect::<BTreeMap<_, _>>()
+    };
+
+    let mut mismatches = vec![];
+    compare_maps(
+        StateDiffEntryKind::ClassHash,
+        &expected_state_diff.address_to_class_hash.iter().collect(),
+        &actual_state_diff.address_to_class_hash.iter().collect(),
+        &mut mismatches,
+    );
+    compare_maps(
+        StateDiffEntryKind::Nonce,
+        &expected_state_diff.address_to_nonce.iter().collect(),
+        &actual_state_diff.address_to_nonce.iter().collect(),
+        &mut mismatches,
+    );
+    compare_maps(
+        StateDiffEntryKind::Storage,
+        &flatten_storage(&expected_state_diff),
+        &flatten_storage(&actual_state_diff),
+        &mut mismatches,
+    );
+    compare_maps(
+        StateDiffEntryKind::CompiledClassHash,
+        &expected_state_diff.class_hash_to_compiled_class_hash.iter().collect(),
+        &actual_state_diff.class_hash_to_compiled_class_hash.iter().collect(),
+        &mut mismatches,
+    );
+    mismatches
+}
@@ -0,0 +1,63 @@
+use indexmap::IndexMap;
+use pretty_assertions::assert_eq;
+use starknet_api::{class_hash, contract_address, felt, nonce, storage_key};
+
+use crate::state::cached_state::CommitmentStateDiff;
+use crate::state::state_diff_mismatch::{
+    state_diff_mismatches,
+    StateDiffEntryKind,
+    StateDiffMismatch,
+};
+
+#[test]
+fn test_state_diff_mismatches() {
+    let address = contract_address!("0x1");
+    let expected_state_diff = CommitmentStateDiff {
+        address_to_class_hash: IndexMap::from([(address, class_hash!("0x10"))]),
+        address_to_nonce: IndexMap::from([(address, nonce!(1_u8))]),
+        storage_updates: IndexMap::from([(
+            address,
+            IndexMap::from([
+                (storage_key!("0x2"), felt!("0x3")),
+                (storage_key!("0x4"), felt!("0x5")),
+            ]),
+        )]),
+        ..Default::default()
+    };
+    // Same entries in a different order, a different storage value and a missing nonce.
+    let actual_state_diff = CommitmentStateDiff {
+        address_to_class_hash: IndexMap::from([(address, class_hash!("0x10"))]),
+        storage_updates: IndexMap::from([(
+            address,
+            IndexMap::from([
+                (storage_key!("0x4"), felt!("0x5")),
+                (storage_key!("0x2"), felt!("0x6")),
+            ]),
+        )]),
+        ..Default::default()
+    };
+
+    let mismatches = state_diff_mismatches(expected_state_diff.clone(), actual_state_diff);
+    assert_eq!(
+        mismatches.iter().map(|mismatch| mismatch.kind).collect::<Vec<_>>(),
+        vec![StateDiffEntryKind::Nonce, StateDiffEntryKind::Storage]
+    );
+    assert_eq!(mismatches[0].actual, None);
+    assert_eq!(mismatches[1].expected, Some(format!("{:?}", felt!("0x3"))));
+    assert_eq!(mismatches[1].actual, Some(format!("{:?}", felt!("0x6"))));
+
+    assert!(state_diff_mismatches(expected_state_diff.clone(), expected_state_diff).is_empty());
+}
+
+#[test]
+fn test_state_diff_mismatch_serde() {
+    let mismatch: StateDiffMismatch = serde_json::from_value(serde_json::json!({
+        "kind": "storage",
+        "key": "0x1",
+        "expected": null,
+        "actual": "0x2",
+    }))
+    .unwrap();
+    assert_eq!(mismatch.kind, StateDiffEntryKind::Storage);
+    assert_eq!(serde_json::to_value(&mismatch).unwrap()["kind"], "storage");
+}
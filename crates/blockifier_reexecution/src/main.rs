@@ -1,9 +1,24 @@
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use apollo_gateway::config::RpcStateReaderConfig;
 use blockifier_reexecution::state_reader::offline_state_reader::OfflineConsecutiveStateReaders;
+use blockifier_reexecution::state_reader::os_input_generator::{
+    generate_os_input,
+    GeneratedOsInput,
+    PatriciaStorage,
+};
+use blockifier_reexecution::state_reader::reexecution_report::reexecute_blocks_in_parallel;
+use blockifier_reexecution::state_reader::storage_state_reader::{
+    open_storage_for_reexecution,
+    ConsecutiveStorageStateReaders,
+    StorageStateReader,
+};
 use blockifier_reexecution::state_reader::test_state_reader::ConsecutiveTestStateReaders;
+use blockifier_reexecution::state_reader::transaction_replay::{
+    replay_transaction,
+    ReplayOverrides,
+};
 use blockifier_reexecution::state_reader::utils::{
     get_block_numbers_for_reexecution,
     guess_chain_id_from_node_url,
@@ -11,6 +26,7 @@ use blockifier_reexecution::state_reader::utils::{
     write_block_reexecution_data_to_file,
     FULL_RESOURCES_DIR,
 };
+use cairo_vm::types::layout_name::LayoutName;
 use clap::{Args, Parser, Subcommand};
 use google_cloud_storage::client::{Client, ClientConfig};
 use google_cloud_storage::http::objects::download::Range;
@@ -18,6 +34,10 @@ use google_cloud_storage::http::objects::get::GetObjectRequest;
 use google_cloud_storage::http::objects::upload::{Media, UploadObjectRequest, UploadType};
 use starknet_api::block::BlockNumber;
 use starknet_api::core::ChainId;
+use starknet_api::transaction::TransactionHash;
+use starknet_os::io::os_output::StarknetOsRunnerOutput;
+use starknet_os::runner::run_os;
+use starknet_types_core::felt::Felt;
 
 const BUCKET: &str = "reexecution_artifacts";
 const RESOURCES_DIR: &str = "/resources";
@@ -84,6 +104,29 @@ enum Command {
         block_number: u64,
     },
 
+    /// Replays a single transaction at its original position in its block, optionally with
+    /// state and block context overrides, and prints its call tree and state diff.
+    ReplayTransaction {
+        #[clap(flatten)]
+        rpc_args: RpcArgs,
+
+        /// The number of the block containing the transaction.
+        #[clap(long, short = 'b')]
+        block_number: u64,
+
+        /// The hash of the transaction to replay.
+        #[clap(long, short = 't')]
+        tx_hash: String,
+
+        /// Path to a JSON file with state and block context overrides.
+        #[clap(long, short = 'o', default_value = None)]
+        overrides_path: Option<String>,
+
+        /// Path to a file to which the full execution info and state diff are written as JSON.
+        #[clap(long, default_value = None)]
+        output_path: Option<String>,
+    },
+
     /// Writes the RPC queries of all (selected) blocks to json files.
     WriteToFile {
         #[clap(flatten)]
@@ -115,6 +158,70 @@ enum Command {
         directory_path: Option<String>,
     },
 
+    /// Reexecutes a range of blocks, reading the blocks and the state from a local node storage,
+    /// and compares the resulting state diffs to the stored ones. Blocks are reexecuted in
+    /// parallel, and mismatches and failures do not stop the reexecution of the other blocks.
+    ReexecuteFromStorage {
+        /// Path prefix of the storage; the database itself is under `<storage_path>/<chain_id>`.
+        #[clap(long, short = 's')]
+        storage_path: String,
+
+        /// The chain ID of the storage.
+        #[clap(long, short = 'c')]
+        chain_id: SupportedChainId,
+
+        /// First block number to reexecute.
+        #[clap(long)]
+        start_block: u64,
+
+        /// Last block number to reexecute (inclusive).
+        #[clap(long)]
+        end_block: u64,
+
+        /// Number of blocks reexecuted in parallel.
+        #[clap(
+            long,
+            short = 'w',
+            default_value_t = 1,
+            value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+        )]
+        n_workers: usize,
+
+        /// Path to a file to which a JSON report of the reexecution is written.
+        #[clap(long, short = 'r', default_value = None)]
+        report_path: Option<String>,
+    },
+
+    /// Generates the OS input of a block from a local node storage and the Patricia storage of the
+    /// state before the block, and runs the OS on it to produce a Cairo PIE.
+    RunOsFromStorage {
+        /// Path prefix of the storage; the database itself is under `<storage_path>/<chain_id>`.
+        #[clap(long, short = 's')]
+        storage_path: String,
+
+        /// The chain ID of the storage.
+        #[clap(long, short = 'c')]
+        chain_id: SupportedChainId,
+
+        /// Block number.
+        #[clap(long, short = 'b')]
+        block_number: u64,
+
+        /// Path to the Patricia storage of the state before the block, in the committer CLI
+        /// output format.
+        #[clap(long, short = 'p')]
+        patricia_storage_path: String,
+
+        /// Path to which the Cairo PIE of the OS run is written (as a zip file).
+        #[clap(long, short = 'o')]
+        cairo_pie_zip_path: String,
+
+        /// Path to which the Patricia storage of the state after the block is written, for
+        /// running the OS on the next block.
+        #[clap(long, default_value = None)]
+        updated_patricia_storage_path: Option<String>,
+    },
+
     // Upload all (selected) blocks to the gc bucket.
     UploadFiles {
         /// Block numbers. If not specified, blocks are retrieved from
@@ -201,6 +308,51 @@ async fn main() {
             println!("RPC test passed successfully.");
         }
 
+        Command::ReplayTransaction {
+            rpc_args,
+            block_number,
+            tx_hash,
+            overrides_path,
+            output_path,
+        } => {
+            println!(
+                "Replaying transaction {tx_hash} of block number {block_number} using node url {}.",
+                rpc_args.node_url
+            );
+
+            let config = RpcStateReaderConfig::from_url(rpc_args.node_url.clone());
+            let tx_hash = TransactionHash(
+                Felt::from_hex(&tx_hash).expect("Transaction hash should be a hex string."),
+            );
+            let overrides = overrides_path
+                .map(|overrides_path| ReplayOverrides::read_from_file(&overrides_path).unwrap())
+                .unwrap_or_default();
+
+            // RPC calls are "synchronous IO" (see, e.g., https://stackoverflow.com/questions/74547541/when-should-you-use-tokios-spawn-blocking)
+            // for details), so should be executed in a blocking thread.
+            let replay_output = tokio::task::spawn_blocking(move || {
+                replay_transaction(
+                    ConsecutiveTestStateReaders::new(
+                        BlockNumber(block_number - 1),
+                        Some(config),
+                        rpc_args.parse_chain_id(),
+                        false,
+                    ),
+                    tx_hash,
+                    overrides,
+                )
+            })
+            .await
+            .unwrap()
+            .expect("Failed to replay the transaction.");
+
+            println!("{}", replay_output.pretty_print().unwrap());
+            if let Some(output_path) = output_path {
+                replay_output.write_to_file(&output_path).unwrap();
+                println!("Replay output written to {output_path}.");
+            }
+        }
+
         Command::WriteToFile { block_numbers, directory_path, rpc_args } => {
             let directory_path = directory_path.unwrap_or(FULL_RESOURCES_DIR.to_string());
 
@@ -251,6 +403,92 @@ async fn main() {
             task_set.join_all().await;
         }
 
+        Command::ReexecuteFromStorage {
+            storage_path,
+            chain_id,
+            start_block,
+            end_block,
+            n_workers,
+            report_path,
+        } => {
+            let chain_id = ChainId::from(chain_id);
+            let storage_reader =
+                open_storage_for_reexecution(PathBuf::from(storage_path), chain_id.clone())
+                    .expect("Failed to open the storage.");
+            println!(
+                "Reexecuting blocks {start_block} to {end_block} from the storage using \
+                 {n_workers} workers."
+            );
+
+            // Storage reads are synchronous IO, so the reexecution is done in blocking threads.
+            let report = tokio::task::spawn_blocking(move || {
+                let block_numbers: Vec<_> = (start_block..=end_block).map(BlockNumber).collect();
+                reexecute_blocks_in_parallel(&block_numbers, n_workers, |block_number| {
+                    Ok(ConsecutiveStorageStateReaders::new(
+                        storage_reader.clone(),
+                        block_number,
+                        chain_id.clone(),
+                    ))
+                })
+            })
+            .await
+            .unwrap();
+
+            println!(
+                "Reexecution done: {} matched, {} mismatched and {} failed blocks.",
+                report.n_matched, report.n_mismatched, report.n_failed
+            );
+            if let Some(report_path) = report_path {
+                report.write_to_file(&report_path).unwrap();
+                println!("Reexecution report written to {report_path}.");
+            }
+            if !report.all_matched() {
+                std::process::exit(1);
+            }
+        }
+
+        Command::RunOsFromStorage {
+            storage_path,
+            chain_id,
+            block_number,
+            patricia_storage_path,
+            cairo_pie_zip_path,
+            updated_patricia_storage_path,
+        } => {
+            let chain_id = ChainId::from(chain_id);
+            let block_number = BlockNumber(block_number);
+            let storage_reader =
+                open_storage_for_reexecution(PathBuf::from(storage_path), chain_id.clone())
+                    .expect("Failed to open the storage.");
+            let patricia_storage = PatriciaStorage::read_from_file(&patricia_storage_path)
+                .expect("Failed to read the Patricia storage.");
+            println!("Generating the OS input of block {block_number} from the storage.");
+
+            let GeneratedOsInput { os_hints, updated_patricia_storage } =
+                generate_os_input(storage_reader.clone(), block_number, chain_id, patricia_storage)
+                    .await
+                    .expect("Failed to generate the OS input.");
+
+            println!("Running the OS on block {block_number}.");
+            // The OS reads the state before the block.
+            let StarknetOsRunnerOutput { cairo_pie, .. } = run_os(
+                LayoutName::all_cairo,
+                os_hints,
+                vec![StorageStateReader::new(storage_reader, block_number)],
+            )
+            .unwrap_or_else(|err| panic!("OS run failed. Error: {err}"));
+            let merge_extra_segments = true;
+            cairo_pie
+                .write_zip_file(Path::new(&cairo_pie_zip_path), merge_extra_segments)
+                .unwrap_or_else(|err| panic!("Failed to write cairo pie. Error: {err}"));
+            println!("Cairo PIE written to {cairo_pie_zip_path}.");
+
+            if let Some(updated_patricia_storage_path) = updated_patricia_storage_path {
+                updated_patricia_storage.write_to_file(&updated_patricia_storage_path).unwrap();
+                println!("Updated Patricia storage written to {updated_patricia_storage_path}.");
+            }
+        }
+
         // Uploading the files requires authentication; please run
         // `gcloud auth application-default login` in terminal before running this command.
         Command::UploadFiles { block_numbers, directory_path } => {
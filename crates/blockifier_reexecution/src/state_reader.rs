@@ -1,13 +1,25 @@
 pub mod compile;
 mod errors;
 pub mod offline_state_reader;
+pub mod os_input_generator;
+#[cfg(test)]
+pub mod os_input_generator_test;
 #[cfg(test)]
 pub mod raw_rpc_json_test;
+pub mod reexecution_report;
+#[cfg(test)]
+pub mod reexecution_report_test;
 pub mod reexecution_state_reader;
 #[cfg(test)]
 pub mod reexecution_test;
 #[cfg(all(test, feature = "blockifier_regression_https_testing"))]
 pub mod rpc_https_test;
 pub mod serde_utils;
+pub mod storage_state_reader;
+#[cfg(test)]
+pub mod storage_state_reader_test;
 pub mod test_state_reader;
+pub mod transaction_replay;
+#[cfg(test)]
+pub mod transaction_replay_test;
 pub mod utils;
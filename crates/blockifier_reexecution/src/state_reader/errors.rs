@@ -1,9 +1,18 @@
 use apollo_gateway::errors::RPCStateReaderError;
+use apollo_storage::StorageError;
+use blockifier::blockifier::transaction_executor::TransactionExecutorError;
 use blockifier::blockifier_versioned_constants::VersionedConstantsError;
 use blockifier::state::errors::StateError;
 use blockifier::transaction::errors::TransactionExecutionError;
 use serde_json::Error as SerdeError;
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ClassHash, GlobalRoot};
+use starknet_api::transaction::TransactionHash;
 use starknet_api::StarknetApiError;
+use starknet_committer::block_committer::errors::BlockCommitmentError;
+use starknet_os::hints::class_hash::hinted_class_hash::HintedClassHashError;
+use starknet_patricia::hash::hash_trait::HashOutput;
+use starknet_patricia_storage::errors::DeserializationError;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -12,6 +21,22 @@ pub enum ReexecutionError {
     #[error("Cannot discern chain ID from URL: {0}")]
     AmbiguousChainIdFromUrl(String),
     #[error(transparent)]
+    BlockCommitment(#[from] BlockCommitmentError),
+    #[error(transparent)]
+    HintedClassHash(#[from] HintedClassHashError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Invalid Patricia storage: {0}")]
+    InvalidPatriciaStorage(String),
+    #[error("Compiled class of class hash {0} is missing in the storage.")]
+    MissingCompiledClass(ClassHash),
+    #[error("Patricia node {0:?} is missing in the Patricia storage.")]
+    MissingPatriciaNode(HashOutput),
+    #[error("Block {block_number} is missing its {data} in the storage.")]
+    MissingStorageData { block_number: BlockNumber, data: &'static str },
+    #[error(transparent)]
+    PatriciaDeserialization(#[from] DeserializationError),
+    #[error(transparent)]
     Rpc(#[from] RPCStateReaderError),
     #[error(transparent)]
     Serde(#[from] SerdeError),
@@ -19,9 +44,20 @@ pub enum ReexecutionError {
     StarknetApi(#[from] StarknetApiError),
     #[error(transparent)]
     State(#[from] StateError),
+    #[error(
+        "State root mismatch at block {block_number}: expected {expected:?}, computed \
+         {computed:?}."
+    )]
+    StateRootMismatch { block_number: BlockNumber, expected: GlobalRoot, computed: GlobalRoot },
+    #[error(transparent)]
+    Storage(#[from] StorageError),
     #[error(transparent)]
     TransactionExecutionError(#[from] TransactionExecutionError),
     #[error(transparent)]
+    TransactionExecutorError(#[from] TransactionExecutorError),
+    #[error("Transaction {0} not found in the block.")]
+    TransactionNotFound(TransactionHash),
+    #[error(transparent)]
     VersionedConstants(#[from] VersionedConstantsError),
 }
 
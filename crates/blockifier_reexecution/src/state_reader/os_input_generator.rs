@@ -0,0 +1,681 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+
+use apollo_storage::compiled_class::CasmStorageReader;
+use apollo_storage::header::HeaderStorageReader;
+use apollo_storage::state::StateStorageReader;
+use apollo_storage::StorageReader;
+use blockifier::abi::constants;
+use blockifier::state::cached_state::CommitmentStateDiff;
+use blockifier::state::errors::StateError;
+use blockifier::transaction::account_transaction::AccountTransaction;
+use blockifier::transaction::transaction_execution::Transaction as BlockifierTransaction;
+use cairo_lang_starknet_classes::casm_contract_class::{
+    CasmContractClass,
+    CasmContractEntryPoints,
+};
+use ethnum::U256;
+use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
+use shared_execution_objects::central_objects::CentralTransactionExecutionInfo;
+use starknet_api::block::{BlockHash, BlockInfo, BlockNumber};
+use starknet_api::contract_class::ContractClass;
+use starknet_api::core::{ChainId, ClassHash, ContractAddress, GlobalRoot};
+use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
+use starknet_api::executable_transaction::{
+    AccountTransaction as ExecutableAccountTransaction,
+    Transaction,
+};
+use starknet_api::state::StateNumber;
+use starknet_committer::block_committer::commit::commit_block;
+use starknet_committer::block_committer::input::{
+    ConfigImpl,
+    Input,
+    StarknetStorageKey,
+    StarknetStorageValue,
+    StateDiff,
+};
+use starknet_committer::forest::filled_forest::FilledForest;
+use starknet_committer::patricia_merkle_tree::leaf::leaf_impl::ContractState;
+use starknet_committer::patricia_merkle_tree::types::CompiledClassHash;
+use starknet_os::hints::class_hash::hinted_class_hash::compute_deprecated_class_hinted_class_hash;
+use starknet_os::io::os_input::{
+    CachedStateInput,
+    CommitmentInfo,
+    ContractClassComponentHashes,
+    OsBlockInput,
+    OsChainInfo,
+    OsHints,
+    OsHintsConfig,
+    StarknetOsInput,
+};
+use starknet_patricia::hash::hash_trait::HashOutput;
+use starknet_patricia::patricia_merkle_tree::filled_tree::node::FilledNode;
+use starknet_patricia::patricia_merkle_tree::filled_tree::node_serde::PatriciaPrefix;
+use starknet_patricia::patricia_merkle_tree::filled_tree::tree::FilledTree;
+use starknet_patricia::patricia_merkle_tree::node_data::inner_node::{
+    BinaryData,
+    EdgeData,
+    NodeData,
+};
+use starknet_patricia::patricia_merkle_tree::node_data::leaf::Leaf;
+use starknet_patricia::patricia_merkle_tree::types::SubTreeHeight;
+use starknet_patricia_storage::db_object::{Deserializable, HasStaticPrefix};
+use starknet_patricia_storage::map_storage::MapStorage;
+use starknet_patricia_storage::storage_trait::{create_db_key, DbKey, DbKeyPrefix, DbValue};
+use starknet_types_core::felt::Felt;
+use starknet_types_core::hash::{Poseidon, StarkHash};
+use tracing::level_filters::LevelFilter;
+
+use crate::state_reader::errors::{ReexecutionError, ReexecutionResult};
+use crate::state_reader::reexecution_state_reader::ConsecutiveReexecutionStateReaders;
+use crate::state_reader::storage_state_reader::ConsecutiveStorageStateReaders;
+
+/// The committer (Patricia) storage of the global state at some block: the facts of the global
+/// tries and their roots.
+pub struct PatriciaStorage {
+    pub storage: HashMap<DbKey, DbValue>,
+    pub contracts_trie_root_hash: HashOutput,
+    pub classes_trie_root_hash: HashOutput,
+}
+
+/// The serialized Patricia storage, in the format of the committer CLI output.
+#[derive(Deserialize, Serialize)]
+struct RawPatriciaStorage {
+    storage: RawMapStorage,
+    contract_storage_root_hash: Felt,
+    compiled_class_root_hash: Felt,
+}
+
+#[derive(Deserialize, Serialize)]
+struct RawMapStorage {
+    storage: HashMap<String, Vec<u8>>,
+}
+
+impl PatriciaStorage {
+    /// Reads the Patricia storage from a file written by the committer CLI (or by
+    /// [PatriciaStorage::write_to_file]).
+    pub fn read_from_file(full_file_path: &str) -> ReexecutionResult<Self> {
+        let file_content = fs::read_to_string(full_file_path)?;
+        let raw_patricia_storage: RawPatriciaStorage = serde_json::from_str(&file_content)?;
+        let storage = raw_patricia_storage
+            .storage
+            .storage
+            .into_iter()
+            .map(|(key, value)| {
+                let key = hex::decode(&key)
+                    .map_err(|error| ReexecutionError::InvalidPatriciaStorage(error.to_string()))?;
+                Ok((DbKey(key), DbValue(value)))
+            })
+            .collect::<ReexecutionResult<_>>()?;
+        Ok(Self {
+            storage,
+            contracts_trie_root_hash: HashOutput(raw_patricia_storage.contract_storage_root_hash),
+            classes_trie_root_hash: HashOutput(raw_patricia_storage.compiled_class_root_hash),
+        })
+    }
+
+    pub fn write_to_file(&self, full_file_path: &str) -> ReexecutionResult<()> {
+        let raw_patricia_storage = RawPatriciaStorage {
+            storage: RawMapStorage {
+                storage: self
+                    .storage
+                    .iter()
+                    .map(|(key, value)| (hex::encode(&key.0), value.0.clone()))
+                    .collect(),
+            },
+            contract_storage_root_hash: self.contracts_trie_root_hash.0,
+            compiled_class_root_hash: self.classes_trie_root_hash.0,
+        };
+        Ok(fs::write(full_file_path, serde_json::to_string(&raw_patricia_storage)?)?)
+    }
+
+    /// Returns the global state root committed to by the tries.
+    pub fn global_root(&self) -> GlobalRoot {
+        if self.classes_trie_root_hash.0 == Felt::ZERO {
+            return GlobalRoot(self.contracts_trie_root_hash.0);
+        }
+        GlobalRoot(Poseidon::hash_array(&[
+            Felt::from_bytes_be_slice(b"STARKNET_STATE_V0"),
+            self.contracts_trie_root_hash.0,
+            self.classes_trie_root_hash.0,
+        ]))
+    }
+}
+
+/// The OS input of a single block, and the Patricia storage of the state after the block (which is
+/// required for generating the OS input of the next block).
+pub struct GeneratedOsInput {
+    pub os_hints: OsHints,
+    pub updated_patricia_storage: PatriciaStorage,
+}
+
+/// Generates the complete OS input of the given block from the storage of a synced node and the
+/// Patricia storage of the state before the block.
+/// The transactions are reexecuted to get their execution infos and the state they read, and the
+/// state diff of the block is committed to get the commitment facts; both the previous and the new
+/// global roots are verified against the block headers.
+/// The generated hints are configured for a prover run (no debug mode and no full output).
+pub async fn generate_os_input(
+    storage_reader: StorageReader,
+    block_number: BlockNumber,
+    chain_id: ChainId,
+    patricia_storage: PatriciaStorage,
+) -> ReexecutionResult<GeneratedOsInput> {
+    let (previous_state_root, new_state_root) = get_state_roots(&storage_reader, block_number)?;
+    if patricia_storage.global_root() != previous_state_root {
+        return Err(ReexecutionError::StateRootMismatch {
+            block_number,
+            expected: previous_state_root,
+            computed: patricia_storage.global_root(),
+        });
+    }
+
+    let ExecutedBlock {
+        transactions,
+        tx_execution_infos,
+        declared_class_hash_to_component_hashes,
+        block_info,
+        prev_block_hash,
+        new_block_hash,
+        old_block_number_and_hash,
+        cached_state_input,
+        deprecated_compiled_classes,
+        compiled_classes,
+        os_chain_info,
+        state_diff,
+    } = execute_block(storage_reader, block_number, chain_id)?;
+
+    let (commitment_infos, updated_patricia_storage) =
+        commit_state_diff(patricia_storage, &state_diff).await?;
+    if updated_patricia_storage.global_root() != new_state_root {
+        return Err(ReexecutionError::StateRootMismatch {
+            block_number,
+            expected: new_state_root,
+            computed: updated_patricia_storage.global_root(),
+        });
+    }
+
+    let use_kzg_da = block_info.use_kzg_da;
+    let os_block_input = OsBlockInput::new(
+        commitment_infos.contracts_trie,
+        commitment_infos.storage_tries,
+        commitment_infos.classes_trie,
+        transactions,
+        tx_execution_infos,
+        declared_class_hash_to_component_hashes,
+        block_info,
+        prev_block_hash,
+        new_block_hash,
+        old_block_number_and_hash,
+    );
+    let os_hints = OsHints {
+        os_input: StarknetOsInput::new(
+            vec![os_block_input],
+            vec![cached_state_input],
+            deprecated_compiled_classes,
+            compiled_classes,
+        ),
+        os_hints_config: OsHintsConfig {
+            debug_mode: false,
+            full_output: false,
+            use_kzg_da,
+            chain_info: os_chain_info,
+        },
+    };
+    Ok(GeneratedOsInput { os_hints, updated_patricia_storage })
+}
+
+/// Returns the global roots before and after the given block.
+fn get_state_roots(
+    storage_reader: &StorageReader,
+    block_number: BlockNumber,
+) -> ReexecutionResult<(GlobalRoot, GlobalRoot)> {
+    let txn = storage_reader.begin_ro_txn()?;
+    let get_state_root = |block_number| -> ReexecutionResult<GlobalRoot> {
+        Ok(txn
+            .get_block_header(block_number)?
+            .ok_or(ReexecutionError::MissingStorageData { block_number, data: "header" })?
+            .block_header_without_hash
+            .state_root)
+    };
+    let previous_state_root = match block_number.prev() {
+        Some(previous_block_number) => get_state_root(previous_block_number)?,
+        None => GlobalRoot::default(),
+    };
+    Ok((previous_state_root, get_state_root(block_number)?))
+}
+
+/// The parts of the OS input that are derived from reexecuting the block.
+struct ExecutedBlock {
+    transactions: Vec<Transaction>,
+    tx_execution_infos: Vec<CentralTransactionExecutionInfo>,
+    declared_class_hash_to_component_hashes: HashMap<ClassHash, ContractClassComponentHashes>,
+    block_info: BlockInfo,
+    prev_block_hash: BlockHash,
+    new_block_hash: BlockHash,
+    old_block_number_and_hash: Option<(BlockNumber, BlockHash)>,
+    cached_state_input: CachedStateInput,
+    deprecated_compiled_classes: BTreeMap<ClassHash, (Felt, DeprecatedContractClass)>,
+    compiled_classes: BTreeMap<ClassHash, CasmContractClass>,
+    os_chain_info: OsChainInfo,
+    state_diff: CommitmentStateDiff,
+}
+
+fn execute_block(
+    storage_reader: StorageReader,
+    block_number: BlockNumber,
+    chain_id: ChainId,
+) -> ReexecutionResult<ExecutedBlock> {
+    let consecutive_state_readers =
+        ConsecutiveStorageStateReaders::new(storage_reader.clone(), block_number, chain_id);
+    let block_context = consecutive_state_readers.get_next_block_context()?;
+    let txs = consecutive_state_readers.get_next_block_txs()?;
+    let state_diff = consecutive_state_readers.get_next_block_state_diff()?;
+    let mut transaction_executor =
+        consecutive_state_readers.pre_process_and_create_executor(None)?;
+
+    let mut tx_execution_infos = Vec::with_capacity(txs.len());
+    let mut class_hashes = HashSet::new();
+    for execution_result in transaction_executor.execute_txs(&txs, None) {
+        let (execution_info, _state_diff) = execution_result?;
+        class_hashes.extend(
+            execution_info.summarize(block_context.versioned_constants()).executed_class_hashes,
+        );
+        tx_execution_infos.push(CentralTransactionExecutionInfo::from(execution_info));
+    }
+    let initial_reads = transaction_executor
+        .block_state
+        .as_ref()
+        .expect("The block state should be available after the execution.")
+        .get_initial_reads()?;
+
+    let transactions: Vec<Transaction> = txs.into_iter().map(to_os_transaction).collect();
+    class_hashes.extend(transactions.iter().filter_map(|tx| match tx {
+        Transaction::Account(ExecutableAccountTransaction::Declare(declare_tx)) => {
+            Some(declare_tx.class_hash())
+        }
+        _ => None,
+    }));
+
+    // Classes are read from the state after the block, which includes the declared ones.
+    let txn = storage_reader.begin_ro_txn()?;
+    let state_reader = txn.get_state_reader()?;
+    let state_number = StateNumber(block_number.next().expect("Overflow in block number"));
+    let mut deprecated_compiled_classes = BTreeMap::new();
+    let mut compiled_classes = BTreeMap::new();
+    let mut declared_class_hash_to_component_hashes = HashMap::new();
+    for class_hash in class_hashes {
+        if let Some(sierra) = state_reader.get_class_definition_at(state_number, &class_hash)? {
+            let casm = txn
+                .get_casm(&class_hash)?
+                .ok_or(ReexecutionError::MissingCompiledClass(class_hash))?;
+            compiled_classes.insert(class_hash, casm);
+            if state_diff.class_hash_to_compiled_class_hash.contains_key(&class_hash) {
+                declared_class_hash_to_component_hashes
+                    .insert(class_hash, ContractClassComponentHashes::from(&sierra));
+            }
+            continue;
+        }
+        let deprecated_class = state_reader
+            .get_deprecated_class_definition_at(state_number, &class_hash)?
+            .ok_or(StateError::UndeclaredClassHash(class_hash))?;
+        let hinted_class_hash = compute_deprecated_class_hinted_class_hash(&deprecated_class)?;
+        deprecated_compiled_classes.insert(class_hash, (hinted_class_hash, deprecated_class));
+    }
+
+    let get_header = |block_number| {
+        txn.get_block_header(block_number)?
+            .ok_or(ReexecutionError::MissingStorageData { block_number, data: "header" })
+    };
+    let header = get_header(block_number)?;
+    let old_block_number_and_hash =
+        match block_number.0.checked_sub(constants::STORED_BLOCK_HASH_BUFFER).map(BlockNumber) {
+            Some(old_block_number) => {
+                Some((old_block_number, get_header(old_block_number)?.block_hash))
+            }
+            None => None,
+        };
+
+    Ok(ExecutedBlock {
+        transactions,
+        tx_execution_infos,
+        declared_class_hash_to_component_hashes,
+        block_info: block_context.block_info().clone(),
+        prev_block_hash: header.block_header_without_hash.parent_hash,
+        new_block_hash: header.block_hash,
+        old_block_number_and_hash,
+        cached_state_input: CachedStateInput::from(initial_reads),
+        deprecated_compiled_classes,
+        compiled_classes,
+        os_chain_info: OsChainInfo::from(block_context.chain_info()),
+        state_diff,
+    })
+}
+
+/// Converts a reexecuted transaction to its OS representation. The compiled class of a Cairo 1
+/// declare transaction is not used by the OS, and is replaced with an invalid one (prime = 0) to
+/// avoid using it accidentally.
+fn to_os_transaction(tx: BlockifierTransaction) -> Transaction {
+    match tx {
+        BlockifierTransaction::Account(AccountTransaction { tx, .. }) => {
+            Transaction::Account(match tx {
+                ExecutableAccountTransaction::Declare(mut declare_tx) => {
+                    if let ContractClass::V1((casm, _sierra_version)) =
+                        &mut declare_tx.class_info.contract_class
+                    {
+                        *casm = invalid_casm_contract_class();
+                    }
+                    ExecutableAccountTransaction::Declare(declare_tx)
+                }
+                tx => tx,
+            })
+        }
+        BlockifierTransaction::L1Handler(tx) => Transaction::L1Handler(tx),
+    }
+}
+
+fn invalid_casm_contract_class() -> CasmContractClass {
+    CasmContractClass {
+        prime: BigUint::default(),
+        compiler_version: String::default(),
+        bytecode: vec![],
+        bytecode_segment_lengths: None,
+        hints: vec![],
+        pythonic_hints: None,
+        entry_points_by_type: CasmContractEntryPoints::default(),
+    }
+}
+
+pub(crate) struct CommitmentInfos {
+    pub(crate) contracts_trie: CommitmentInfo,
+    pub(crate) storage_tries: HashMap<ContractAddress, CommitmentInfo>,
+    pub(crate) classes_trie: CommitmentInfo,
+}
+
+/// Commits the state diff on top of the given Patricia storage, and returns the commitment infos
+/// of the global tries and the updated Patricia storage.
+/// The commitment facts of each trie are the preimages of the nodes on the paths from its previous
+/// and new roots to the modified leaves (and of the siblings of these nodes, which are required
+/// when a subtree is merged after a leaf deletion).
+pub(crate) async fn commit_state_diff(
+    patricia_storage: PatriciaStorage,
+    state_diff: &CommitmentStateDiff,
+) -> ReexecutionResult<(CommitmentInfos, PatriciaStorage)> {
+    let PatriciaStorage { storage, contracts_trie_root_hash, classes_trie_root_hash } =
+        patricia_storage;
+    let committer_state_diff = to_committer_state_diff(state_diff);
+
+    let modified_contracts: HashSet<ContractAddress> = committer_state_diff
+        .address_to_class_hash
+        .keys()
+        .chain(committer_state_diff.address_to_nonce.keys())
+        .chain(committer_state_diff.storage_updates.keys())
+        .copied()
+        .collect();
+    let contracts_trie_paths = collect_original_paths(
+        &storage,
+        contracts_trie_root_hash,
+        modified_contracts.iter().map(|address| *address.0.key()),
+    )?;
+    let classes_trie_paths = collect_original_paths(
+        &storage,
+        classes_trie_root_hash,
+        committer_state_diff
+            .class_hash_to_compiled_class_hash
+            .keys()
+            .map(|class_hash| class_hash.0),
+    )?;
+    let mut original_storage_tries = HashMap::new();
+    for address in modified_contracts {
+        let storage_root_hash =
+            match contracts_trie_paths.leaves.get(&felt_to_u256(address.0.key())) {
+                Some(leaf_hash) => read_contract_state(&storage, *leaf_hash)?.storage_root_hash,
+                None => HashOutput::default(),
+            };
+        let storage_keys: Vec<Felt> = committer_state_diff
+            .storage_updates
+            .get(&address)
+            .map(|updates| updates.keys().map(|key| key.0).collect())
+            .unwrap_or_default();
+        let storage_trie_paths =
+            collect_original_paths(&storage, storage_root_hash, storage_keys.into_iter())?;
+        original_storage_tries.insert(address, (storage_root_hash, storage_trie_paths.facts));
+    }
+
+    // Copy the storage, as the committer consumes it.
+    let mut updated_storage = MapStorage::from(
+        storage
+            .iter()
+            .map(|(key, value)| (DbKey(key.0.clone()), DbValue(value.0.clone())))
+            .collect::<HashMap<_, _>>(),
+    );
+    let filled_forest = commit_block(Input {
+        storage,
+        state_diff: committer_state_diff,
+        contracts_trie_root_hash,
+        classes_trie_root_hash,
+        config: ConfigImpl::new(false, LevelFilter::INFO),
+    })
+    .await?;
+    filled_forest.write_to_storage(&mut updated_storage);
+
+    let FilledForest { storage_tries, contracts_trie, classes_trie } = filled_forest;
+    let commitment_infos = CommitmentInfos {
+        contracts_trie: commitment_info(
+            contracts_trie_root_hash,
+            contracts_trie_paths.facts,
+            &contracts_trie,
+        ),
+        storage_tries: storage_tries
+            .iter()
+            .map(|(address, storage_trie)| {
+                let (previous_root, facts) = original_storage_tries
+                    .remove(address)
+                    .expect("All committed storage tries belong to modified contracts.");
+                (*address, commitment_info(previous_root, facts, storage_trie))
+            })
+            .collect(),
+        classes_trie: commitment_info(
+            classes_trie_root_hash,
+            classes_trie_paths.facts,
+            &classes_trie,
+        ),
+    };
+    let updated_patricia_storage = PatriciaStorage {
+        storage: updated_storage.storage,
+        contracts_trie_root_hash: contracts_trie.get_root_hash(),
+        classes_trie_root_hash: classes_trie.get_root_hash(),
+    };
+    Ok((commitment_infos, updated_patricia_storage))
+}
+
+fn to_committer_state_diff(state_diff: &CommitmentStateDiff) -> StateDiff {
+    StateDiff {
+        address_to_class_hash: state_diff.address_to_class_hash.clone().into_iter().collect(),
+        address_to_nonce: state_diff.address_to_nonce.clone().into_iter().collect(),
+        class_hash_to_compiled_class_hash: state_diff
+            .class_hash_to_compiled_class_hash
+            .iter()
+            .map(|(class_hash, compiled_class_hash)| {
+                (*class_hash, CompiledClassHash(compiled_class_hash.0))
+            })
+            .collect(),
+        storage_updates: state_diff
+            .storage_updates
+            .iter()
+            .map(|(address, updates)| {
+                (
+                    *address,
+                    updates
+                        .iter()
+                        .map(|(key, value)| {
+                            (StarknetStorageKey(*key.0.key()), StarknetStorageValue(*value))
+                        })
+                        .collect(),
+                )
+            })
+            .collect(),
+    }
+}
+
+/// The preimages of the nodes of a trie on the paths from its root to some of its leaves, and the
+/// hashes of the (non-empty) leaves reached.
+#[derive(Default)]
+pub(crate) struct OriginalPaths {
+    pub(crate) facts: HashMap<HashOutput, Vec<Felt>>,
+    pub(crate) leaves: HashMap<U256, HashOutput>,
+}
+
+pub(crate) fn felt_to_u256(felt: &Felt) -> U256 {
+    U256::from_be_bytes(felt.to_bytes_be())
+}
+
+pub(crate) fn collect_original_paths(
+    storage: &HashMap<DbKey, DbValue>,
+    root_hash: HashOutput,
+    leaf_indices: impl Iterator<Item = Felt>,
+) -> ReexecutionResult<OriginalPaths> {
+    let mut leaf_indices: Vec<U256> = leaf_indices.map(|index| felt_to_u256(&index)).collect();
+    leaf_indices.sort();
+    leaf_indices.dedup();
+    let mut original_paths = OriginalPaths::default();
+    collect_original_paths_rec(
+        storage,
+        root_hash,
+        SubTreeHeight::ACTUAL_HEIGHT.0,
+        &leaf_indices,
+        &mut original_paths,
+    )?;
+    Ok(original_paths)
+}
+
+/// Traverses the subtree of the given height rooted at the given hash, towards the given (sorted)
+/// leaf indices.
+fn collect_original_paths_rec(
+    storage: &HashMap<DbKey, DbValue>,
+    hash: HashOutput,
+    height: u8,
+    leaf_indices: &[U256],
+    original_paths: &mut OriginalPaths,
+) -> ReexecutionResult<()> {
+    if leaf_indices.is_empty() || hash == HashOutput::default() {
+        return Ok(());
+    }
+    if height == 0 {
+        original_paths.leaves.insert(leaf_indices[0], hash);
+        return Ok(());
+    }
+    let node_data = read_inner_node(storage, hash)?;
+    original_paths.facts.insert(hash, preimage(&node_data));
+    match node_data {
+        NodeData::Binary(BinaryData { left_hash, right_hash }) => {
+            let child_height = height - 1;
+            let split_index = leaf_indices.partition_point(|index| {
+                (*index >> u32::from(child_height)) & U256::ONE == U256::ZERO
+            });
+            let (left_indices, right_indices) = leaf_indices.split_at(split_index);
+            // The untraversed child is required if the traversed subtree becomes empty, and the
+            // binary node is replaced by an edge.
+            let untraversed_child_hash = match (left_indices.is_empty(), right_indices.is_empty()) {
+                (true, false) => Some(left_hash),
+                (false, true) => Some(right_hash),
+                _ => None,
+            };
+            if let Some(child_hash) = untraversed_child_hash {
+                if child_height > 0 && child_hash != HashOutput::default() {
+                    let child_data = read_inner_node(storage, child_hash)?;
+                    original_paths.facts.insert(child_hash, preimage(&child_data));
+                }
+            }
+            collect_original_paths_rec(
+                storage,
+                left_hash,
+                child_height,
+                left_indices,
+                original_paths,
+            )?;
+            collect_original_paths_rec(
+                storage,
+                right_hash,
+                child_height,
+                right_indices,
+                original_paths,
+            )
+        }
+        NodeData::Edge(EdgeData { bottom_hash, path_to_bottom }) => {
+            let length = u8::from(path_to_bottom.length);
+            let bottom_height = height - length;
+            let path = U256::from(&path_to_bottom.path);
+            let mask = (U256::ONE << u32::from(length)) - U256::ONE;
+            let bottom_indices: Vec<U256> = leaf_indices
+                .iter()
+                .filter(|index| (**index >> u32::from(bottom_height)) & mask == path)
+                .copied()
+                .collect();
+            collect_original_paths_rec(
+                storage,
+                bottom_hash,
+                bottom_height,
+                &bottom_indices,
+                original_paths,
+            )
+        }
+        NodeData::Leaf(_) => unreachable!("Inner nodes are never deserialized as leaves."),
+    }
+}
+
+fn read_inner_node(
+    storage: &HashMap<DbKey, DbValue>,
+    hash: HashOutput,
+) -> ReexecutionResult<NodeData<StarknetStorageValue>> {
+    let db_key = create_db_key(PatriciaPrefix::InnerNode.into(), &hash.0.to_bytes_be());
+    let value = storage.get(&db_key).ok_or(ReexecutionError::MissingPatriciaNode(hash))?;
+    Ok(FilledNode::<StarknetStorageValue>::deserialize_inner_node(hash, value)?.data)
+}
+
+fn read_contract_state(
+    storage: &HashMap<DbKey, DbValue>,
+    leaf_hash: HashOutput,
+) -> ReexecutionResult<ContractState> {
+    let db_key = create_db_key(ContractState::get_static_prefix(), &leaf_hash.0.to_bytes_be());
+    let value = storage.get(&db_key).ok_or(ReexecutionError::MissingPatriciaNode(leaf_hash))?;
+    Ok(<ContractState as Deserializable>::deserialize(value)?)
+}
+
+/// Returns the preimage of an inner node, as expected by the OS.
+fn preimage<L: Leaf>(node_data: &NodeData<L>) -> Vec<Felt> {
+    match node_data {
+        NodeData::Binary(BinaryData { left_hash, right_hash }) => vec![left_hash.0, right_hash.0],
+        NodeData::Edge(EdgeData { bottom_hash, path_to_bottom }) => {
+            vec![Felt::from(path_to_bottom.length), Felt::from(&path_to_bottom.path), bottom_hash.0]
+        }
+        NodeData::Leaf(_) => unreachable!("Leaves have no preimage in the commitment facts."),
+    }
+}
+
+/// Returns the commitment info of a trie, given the original facts and the updated trie; the
+/// preimages of the inner nodes of the updated trie are added to the facts.
+fn commitment_info<L: Leaf>(
+    previous_root: HashOutput,
+    mut commitment_facts: HashMap<HashOutput, Vec<Felt>>,
+    updated_tree: &impl FilledTree<L>,
+) -> CommitmentInfo {
+    let inner_node_key_prefix =
+        [DbKeyPrefix::from(PatriciaPrefix::InnerNode).to_bytes(), b":".as_slice()].concat();
+    for (key, value) in updated_tree.serialize() {
+        let Some(hash_bytes) = key.0.strip_prefix(inner_node_key_prefix.as_slice()) else {
+            continue;
+        };
+        let hash = HashOutput(Felt::from_bytes_be_slice(hash_bytes));
+        let node = FilledNode::<L>::deserialize_inner_node(hash, &value)
+            .expect("Inner nodes of a filled tree are deserializable.");
+        commitment_facts.insert(hash, preimage(&node.data));
+    }
+    CommitmentInfo::new(
+        previous_root,
+        updated_tree.get_root_hash(),
+        SubTreeHeight::ACTUAL_HEIGHT,
+        commitment_facts,
+    )
+}
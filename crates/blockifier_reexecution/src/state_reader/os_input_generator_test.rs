@@ -0,0 +1,205 @@
+use std::collections::{HashMap, HashSet};
+
+use assert_matches::assert_matches;
+use blockifier::state::cached_state::CommitmentStateDiff;
+use indexmap::IndexMap;
+use pretty_assertions::assert_eq;
+use starknet_api::core::{ContractAddress, GlobalRoot};
+use starknet_api::{class_hash, compiled_class_hash, contract_address, felt, nonce, storage_key};
+use starknet_os::io::os_input::CommitmentInfo;
+use starknet_patricia::hash::hash_trait::HashOutput;
+use starknet_patricia::patricia_merkle_tree::filled_tree::node_serde::PatriciaPrefix;
+use starknet_patricia::patricia_merkle_tree::types::SubTreeHeight;
+use starknet_patricia_storage::storage_trait::{create_db_key, DbKey, DbValue};
+use starknet_types_core::felt::Felt;
+use starknet_types_core::hash::{Poseidon, StarkHash};
+
+use crate::state_reader::errors::ReexecutionError;
+use crate::state_reader::os_input_generator::{
+    collect_original_paths,
+    commit_state_diff,
+    felt_to_u256,
+    PatriciaStorage,
+};
+
+#[test]
+fn test_patricia_storage_global_root() {
+    let patricia_storage = |contracts_trie_root_hash, classes_trie_root_hash| PatriciaStorage {
+        storage: HashMap::new(),
+        contracts_trie_root_hash: HashOutput(contracts_trie_root_hash),
+        classes_trie_root_hash: HashOutput(classes_trie_root_hash),
+    };
+
+    assert_eq!(patricia_storage(Felt::ZERO, Felt::ZERO).global_root(), GlobalRoot::default());
+    // Before the classes trie was introduced, the global root is the contracts trie root.
+    assert_eq!(patricia_storage(felt!("0x1"), Felt::ZERO).global_root(), GlobalRoot(felt!("0x1")));
+    assert_eq!(
+        patricia_storage(felt!("0x1"), felt!("0x2")).global_root(),
+        GlobalRoot(Poseidon::hash_array(&[
+            Felt::from_bytes_be_slice(b"STARKNET_STATE_V0"),
+            felt!("0x1"),
+            felt!("0x2"),
+        ]))
+    );
+}
+
+#[test]
+fn test_patricia_storage_file_round_trip() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let file_path = temp_dir.path().join("patricia_storage.json");
+    let file_path = file_path.to_str().unwrap();
+    let patricia_storage = PatriciaStorage {
+        storage: HashMap::from([(DbKey(b"patricia_node:\x01".to_vec()), DbValue(vec![1, 2, 3]))]),
+        contracts_trie_root_hash: HashOutput(felt!("0x1")),
+        classes_trie_root_hash: HashOutput(felt!("0x2")),
+    };
+
+    patricia_storage.write_to_file(file_path).unwrap();
+    let read_patricia_storage = PatriciaStorage::read_from_file(file_path).unwrap();
+    assert_eq!(read_patricia_storage.storage, patricia_storage.storage);
+    assert_eq!(read_patricia_storage.global_root(), patricia_storage.global_root());
+}
+
+fn empty_patricia_storage() -> PatriciaStorage {
+    PatriciaStorage {
+        storage: HashMap::new(),
+        contracts_trie_root_hash: HashOutput::default(),
+        classes_trie_root_hash: HashOutput::default(),
+    }
+}
+
+const CONTRACT_ADDRESSES: [&str; 3] = ["0x10", "0x20", "0x30"];
+
+/// A state diff that deploys a few contracts with some storage, and declares their class.
+fn initial_state_diff() -> CommitmentStateDiff {
+    let addresses = CONTRACT_ADDRESSES.map(|address| contract_address!(address));
+    CommitmentStateDiff {
+        address_to_class_hash: addresses
+            .iter()
+            .map(|address| (*address, class_hash!("0x100")))
+            .collect(),
+        address_to_nonce: addresses.iter().map(|address| (*address, nonce!(1_u8))).collect(),
+        storage_updates: IndexMap::from([(
+            addresses[0],
+            IndexMap::from([
+                (storage_key!("0x1"), felt!("0x2")),
+                (storage_key!("0x3"), felt!("0x4")),
+            ]),
+        )]),
+        class_hash_to_compiled_class_hash: IndexMap::from([(
+            class_hash!("0x100"),
+            compiled_class_hash!(0x200_u16),
+        )]),
+    }
+}
+
+#[tokio::test]
+async fn test_collect_original_paths() {
+    let (_, patricia_storage) =
+        commit_state_diff(empty_patricia_storage(), &initial_state_diff()).await.unwrap();
+    let storage = &patricia_storage.storage;
+    let root_hash = patricia_storage.contracts_trie_root_hash;
+
+    // The paths to all the leaves reach all of them.
+    let all_leaves = CONTRACT_ADDRESSES.map(|address| felt!(address));
+    let original_paths =
+        collect_original_paths(storage, root_hash, all_leaves.into_iter()).unwrap();
+    assert_eq!(
+        original_paths.leaves.keys().copied().collect::<HashSet<_>>(),
+        all_leaves.iter().map(felt_to_u256).collect::<HashSet<_>>()
+    );
+    assert!(original_paths.facts.contains_key(&root_hash));
+
+    // The path to a single leaf includes the root, and reaches only this leaf.
+    let original_paths =
+        collect_original_paths(storage, root_hash, [felt!("0x20")].into_iter()).unwrap();
+    assert_eq!(
+        original_paths.leaves.keys().copied().collect::<Vec<_>>(),
+        vec![felt_to_u256(&felt!("0x20"))]
+    );
+    assert!(original_paths.facts.contains_key(&root_hash));
+
+    // A path to an empty leaf reaches no leaf, and an empty trie has no facts.
+    let original_paths =
+        collect_original_paths(storage, root_hash, [felt!("0x25")].into_iter()).unwrap();
+    assert!(original_paths.leaves.is_empty());
+    let original_paths =
+        collect_original_paths(storage, HashOutput::default(), all_leaves.into_iter()).unwrap();
+    assert!(original_paths.facts.is_empty() && original_paths.leaves.is_empty());
+
+    // A missing inner node fails the traversal.
+    let mut storage = storage.clone();
+    storage.remove(&create_db_key(PatriciaPrefix::InnerNode.into(), &root_hash.0.to_bytes_be()));
+    assert_matches!(
+        collect_original_paths(&storage, root_hash, [felt!("0x20")].into_iter()),
+        Err(ReexecutionError::MissingPatriciaNode(hash)) if hash == root_hash
+    );
+}
+
+#[tokio::test]
+async fn test_commit_state_diff() {
+    let (commitment_infos, initial_patricia_storage) =
+        commit_state_diff(empty_patricia_storage(), &initial_state_diff()).await.unwrap();
+    let contract_addresses: HashSet<ContractAddress> =
+        CONTRACT_ADDRESSES.iter().map(|address| contract_address!(*address)).collect();
+    assert_eq!(
+        commitment_infos.storage_tries.keys().copied().collect::<HashSet<_>>(),
+        contract_addresses
+    );
+
+    // Update only the nonce of a single contract, which changes only the contracts trie.
+    let address = contract_address!("0x20");
+    let state_diff = CommitmentStateDiff {
+        address_to_nonce: IndexMap::from([(address, nonce!(2_u8))]),
+        ..Default::default()
+    };
+    let previous_contracts_trie_root_hash = initial_patricia_storage.contracts_trie_root_hash;
+    let original_paths = collect_original_paths(
+        &initial_patricia_storage.storage,
+        previous_contracts_trie_root_hash,
+        [*address.0.key()].into_iter(),
+    )
+    .unwrap();
+    let (commitment_infos, updated_patricia_storage) = commit_state_diff(
+        PatriciaStorage {
+            storage: initial_patricia_storage.storage.clone(),
+            contracts_trie_root_hash: previous_contracts_trie_root_hash,
+            classes_trie_root_hash: initial_patricia_storage.classes_trie_root_hash,
+        },
+        &state_diff,
+    )
+    .await
+    .unwrap();
+
+    // Committing the updates one after the other reaches the same state as committing them at once.
+    let mut combined_state_diff = initial_state_diff();
+    combined_state_diff.address_to_nonce.insert(address, nonce!(2_u8));
+    let (_, combined_patricia_storage) =
+        commit_state_diff(empty_patricia_storage(), &combined_state_diff).await.unwrap();
+    assert_eq!(updated_patricia_storage.global_root(), combined_patricia_storage.global_root());
+    assert_eq!(
+        updated_patricia_storage.classes_trie_root_hash,
+        initial_patricia_storage.classes_trie_root_hash
+    );
+
+    // The commitment facts of the contracts trie are the nodes on the path to the modified leaf,
+    // both before and after the update.
+    let updated_contracts_trie_root_hash = updated_patricia_storage.contracts_trie_root_hash;
+    let updated_paths = collect_original_paths(
+        &updated_patricia_storage.storage,
+        updated_contracts_trie_root_hash,
+        [*address.0.key()].into_iter(),
+    )
+    .unwrap();
+    let mut expected_facts = original_paths.facts;
+    expected_facts.extend(updated_paths.facts);
+    assert_eq!(
+        commitment_infos.contracts_trie,
+        CommitmentInfo::new(
+            previous_contracts_trie_root_hash,
+            updated_contracts_trie_root_hash,
+            SubTreeHeight::ACTUAL_HEIGHT,
+            expected_facts,
+        )
+    );
+}
@@ -0,0 +1,185 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::{fs, thread};
+
+use blockifier::state::state_api::StateReader;
+use blockifier::state::state_diff_mismatch::{state_diff_mismatches, StateDiffMismatch};
+use blockifier::transaction::transaction_execution::Transaction as BlockifierTransaction;
+use serde::{Deserialize, Serialize};
+use starknet_api::block::BlockNumber;
+use starknet_api::transaction::TransactionHash;
+
+use crate::state_reader::errors::ReexecutionResult;
+use crate::state_reader::reexecution_state_reader::ConsecutiveReexecutionStateReaders;
+
+/// A transaction that failed to execute during the reexecution of a block.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TransactionExecutionFailure {
+    pub tx_hash: TransactionHash,
+    pub error: String,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockReexecutionStatus {
+    /// All the transactions executed successfully and the state diffs are equal.
+    Matched,
+    /// Some transactions failed or the state diffs differ.
+    Mismatched,
+    /// The block could not be reexecuted (e.g., missing data).
+    Failed,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BlockReexecutionReport {
+    pub block_number: BlockNumber,
+    pub status: BlockReexecutionStatus,
+    pub state_diff_mismatches: Vec<StateDiffMismatch>,
+    pub transaction_failures: Vec<TransactionExecutionFailure>,
+    pub error: Option<String>,
+}
+
+impl BlockReexecutionReport {
+    fn failed(block_number: BlockNumber, error: String) -> Self {
+        Self {
+            block_number,
+            status: BlockReexecutionStatus::Failed,
+            state_diff_mismatches: vec![],
+            transaction_failures: vec![],
+            error: Some(error),
+        }
+    }
+}
+
+/// A summary of the reexecution of a set of blocks; the block reports are sorted by block number.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RangeReexecutionReport {
+    pub n_matched: usize,
+    pub n_mismatched: usize,
+    pub n_failed: usize,
+    pub blocks: Vec<BlockReexecutionReport>,
+}
+
+impl RangeReexecutionReport {
+    pub fn new(mut blocks: Vec<BlockReexecutionReport>) -> Self {
+        blocks.sort_by_key(|block_report| block_report.block_number);
+        let count_status =
+            |status| blocks.iter().filter(|block_report| block_report.status == status).count();
+        Self {
+            n_matched: count_status(BlockReexecutionStatus::Matched),
+            n_mismatched: count_status(BlockReexecutionStatus::Mismatched),
+            n_failed: count_status(BlockReexecutionStatus::Failed),
+            blocks,
+        }
+    }
+
+    pub fn all_matched(&self) -> bool {
+        self.n_mismatched == 0 && self.n_failed == 0
+    }
+
+    pub fn write_to_file(&self, full_file_path: &str) -> ReexecutionResult<()> {
+        fs::write(full_file_path, serde_json::to_string_pretty(&self)?)
+            .unwrap_or_else(|err| panic!("Failed to write to file {full_file_path}. Error: {err}"));
+        Ok(())
+    }
+}
+
+/// Reexecutes the next block and compares the resulting state diff to the expected one. Unlike
+/// `reexecute_and_verify_correctness`, mismatches and transaction failures are reported instead
+/// of causing a panic.
+pub fn reexecute_and_report<
+    S: StateReader + Send + Sync + Clone + 'static,
+    T: ConsecutiveReexecutionStateReaders<S>,
+>(
+    block_number: BlockNumber,
+    consecutive_state_readers: T,
+) -> ReexecutionResult<BlockReexecutionReport> {
+    let expected_state_diff = consecutive_state_readers.get_next_block_state_diff()?;
+    let all_txs_in_next_block = consecutive_state_readers.get_next_block_txs()?;
+    let mut transaction_executor =
+        consecutive_state_readers.pre_process_and_create_executor(None)?;
+
+    let execution_results = transaction_executor.execute_txs(&all_txs_in_next_block, None);
+    let transaction_failures: Vec<_> = all_txs_in_next_block
+        .iter()
+        .zip(execution_results)
+        .filter_map(|(tx, result)| {
+            result.err().map(|error| TransactionExecutionFailure {
+                tx_hash: BlockifierTransaction::tx_hash(tx),
+                error: error.to_string(),
+            })
+        })
+        .collect();
+
+    let actual_state_diff = transaction_executor.non_consuming_finalize()?.state_diff;
+    let state_diff_mismatches = state_diff_mismatches(expected_state_diff, actual_state_diff);
+
+    let status = if state_diff_mismatches.is_empty() && transaction_failures.is_empty() {
+        BlockReexecutionStatus::Matched
+    } else {
+        BlockReexecutionStatus::Mismatched
+    };
+    Ok(BlockReexecutionReport {
+        block_number,
+        status,
+        state_diff_mismatches,
+        transaction_failures,
+        error: None,
+    })
+}
+
+/// Reexecutes the given blocks using `n_workers` threads, each creating its own state readers
+/// for the blocks it processes. The reexecution continues past mismatches and failures (including
+/// panics), which are all summarized in the returned report.
+pub fn reexecute_blocks_in_parallel<S, T, F>(
+    block_numbers: &[BlockNumber],
+    n_workers: usize,
+    create_consecutive_state_readers: F,
+) -> RangeReexecutionReport
+where
+    S: StateReader + Send + Sync + Clone + 'static,
+    T: ConsecutiveReexecutionStateReaders<S>,
+    F: Fn(BlockNumber) -> ReexecutionResult<T> + Sync,
+{
+    assert!(n_workers > 0, "The number of workers must be positive.");
+    let next_block_index = AtomicUsize::new(0);
+    let block_reports = Mutex::new(Vec::with_capacity(block_numbers.len()));
+
+    thread::scope(|scope| {
+        for _ in 0..n_workers {
+            scope.spawn(|| {
+                while let Some(&block_number) =
+                    block_numbers.get(next_block_index.fetch_add(1, Ordering::Relaxed))
+                {
+                    let block_report = catch_unwind(AssertUnwindSafe(|| {
+                        reexecute_and_report(
+                            block_number,
+                            create_consecutive_state_readers(block_number)?,
+                        )
+                    }))
+                    .unwrap_or_else(|panic| {
+                        let message = panic
+                            .downcast_ref::<String>()
+                            .cloned()
+                            .or_else(|| panic.downcast_ref::<&str>().map(|s| s.to_string()))
+                            .unwrap_or_else(|| "Unknown panic.".to_string());
+                        Ok(BlockReexecutionReport::failed(block_number, message))
+                    })
+                    .unwrap_or_else(|error| {
+                        BlockReexecutionReport::failed(block_number, error.to_string())
+                    });
+                    println!("Block {block_number} reexecution status: {:?}.", block_report.status);
+                    block_reports
+                        .lock()
+                        .expect("Failed to lock the block reports.")
+                        .push(block_report);
+                }
+            });
+        }
+    });
+
+    RangeReexecutionReport::new(
+        block_reports.into_inner().expect("Failed to lock the block reports."),
+    )
+}
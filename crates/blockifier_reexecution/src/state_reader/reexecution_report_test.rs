@@ -0,0 +1,131 @@
+use blockifier::blockifier::config::TransactionExecutorConfig;
+use blockifier::blockifier::transaction_executor::TransactionExecutor;
+use blockifier::context::BlockContext;
+use blockifier::state::cached_state::{CachedState, CommitmentStateDiff};
+use blockifier::test_utils::dict_state_reader::DictStateReader;
+use blockifier::transaction::transaction_execution::Transaction as BlockifierTransaction;
+use indexmap::IndexMap;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::block::BlockNumber;
+use starknet_api::{contract_address, nonce};
+
+use crate::state_reader::errors::{ReexecutionError, ReexecutionResult};
+use crate::state_reader::reexecution_report::{
+    reexecute_blocks_in_parallel,
+    BlockReexecutionReport,
+    BlockReexecutionStatus,
+    RangeReexecutionReport,
+};
+use crate::state_reader::reexecution_state_reader::ConsecutiveReexecutionStateReaders;
+
+#[test]
+fn test_range_reexecution_report_summary() {
+    let block_report = |block_number, status| BlockReexecutionReport {
+        block_number: BlockNumber(block_number),
+        status,
+        state_diff_mismatches: vec![],
+        transaction_failures: vec![],
+        error: None,
+    };
+    let report = RangeReexecutionReport::new(vec![
+        block_report(3, BlockReexecutionStatus::Failed),
+        block_report(1, BlockReexecutionStatus::Matched),
+        block_report(2, BlockReexecutionStatus::Mismatched),
+        block_report(0, BlockReexecutionStatus::Matched),
+    ]);
+
+    assert_eq!((report.n_matched, report.n_mismatched, report.n_failed), (2, 1, 1));
+    assert!(!report.all_matched());
+    assert_eq!(
+        report.blocks.iter().map(|block_report| block_report.block_number.0).collect::<Vec<_>>(),
+        vec![0, 1, 2, 3]
+    );
+
+    // The report is serialized with snake case statuses.
+    let raw_report = serde_json::to_value(&report).unwrap();
+    assert_eq!(raw_report["blocks"][2]["status"], "mismatched");
+}
+
+/// Consecutive state readers of an empty block, on top of an empty state.
+struct EmptyBlockStateReaders {
+    block_number: BlockNumber,
+    expected_state_diff: CommitmentStateDiff,
+}
+
+impl ConsecutiveReexecutionStateReaders<DictStateReader> for EmptyBlockStateReaders {
+    fn pre_process_and_create_executor(
+        self,
+        transaction_executor_config: Option<TransactionExecutorConfig>,
+    ) -> ReexecutionResult<TransactionExecutor<DictStateReader>> {
+        if self.block_number == BlockNumber(PANICKING_REEXECUTION_BLOCK) {
+            panic!("Reexecution of block {} panicked.", self.block_number);
+        }
+        Ok(TransactionExecutor::new(
+            CachedState::new(DictStateReader::default()),
+            BlockContext::create_for_account_testing(),
+            transaction_executor_config.unwrap_or_default(),
+        ))
+    }
+
+    fn get_next_block_txs(&self) -> ReexecutionResult<Vec<BlockifierTransaction>> {
+        Ok(vec![])
+    }
+
+    fn get_next_block_state_diff(&self) -> ReexecutionResult<CommitmentStateDiff> {
+        Ok(self.expected_state_diff.clone())
+    }
+}
+
+const FAILING_BLOCK: u64 = 2;
+const PANICKING_BLOCK: u64 = 3;
+const MISMATCHED_BLOCK: u64 = 4;
+const PANICKING_REEXECUTION_BLOCK: u64 = 5;
+
+fn create_empty_block_state_readers(
+    block_number: BlockNumber,
+) -> ReexecutionResult<EmptyBlockStateReaders> {
+    let expected_state_diff = match block_number.0 {
+        FAILING_BLOCK => {
+            return Err(ReexecutionError::MissingStorageData { block_number, data: "header" });
+        }
+        PANICKING_BLOCK => panic!("Creating the state readers panicked."),
+        MISMATCHED_BLOCK => CommitmentStateDiff {
+            address_to_nonce: IndexMap::from([(contract_address!("0x1"), nonce!(1_u8))]),
+            ..Default::default()
+        },
+        _ => CommitmentStateDiff::default(),
+    };
+    Ok(EmptyBlockStateReaders { block_number, expected_state_diff })
+}
+
+#[rstest]
+fn test_reexecute_blocks_in_parallel(#[values(1, 3, 10)] n_workers: usize) {
+    let block_numbers: Vec<_> = (0..7).map(BlockNumber).collect();
+
+    let report =
+        reexecute_blocks_in_parallel(&block_numbers, n_workers, create_empty_block_state_readers);
+
+    assert_eq!((report.n_matched, report.n_mismatched, report.n_failed), (3, 1, 3));
+    assert_eq!(
+        report.blocks.iter().map(|block_report| block_report.block_number).collect::<Vec<_>>(),
+        block_numbers
+    );
+    let block_report = |block_number: u64| &report.blocks[usize::try_from(block_number).unwrap()];
+    assert_eq!(block_report(0).status, BlockReexecutionStatus::Matched);
+    assert_eq!(block_report(MISMATCHED_BLOCK).status, BlockReexecutionStatus::Mismatched);
+    assert_eq!(block_report(MISMATCHED_BLOCK).state_diff_mismatches.len(), 1);
+    // Errors and panics, whether of a `&str` or of a `String`, are captured in the report.
+    assert_eq!(
+        block_report(FAILING_BLOCK).error.as_deref(),
+        Some("Block 2 is missing its header in the storage.")
+    );
+    assert_eq!(
+        block_report(PANICKING_BLOCK).error.as_deref(),
+        Some("Creating the state readers panicked.")
+    );
+    assert_eq!(
+        block_report(PANICKING_REEXECUTION_BLOCK).error.as_deref(),
+        Some("Reexecution of block 5 panicked.")
+    );
+}
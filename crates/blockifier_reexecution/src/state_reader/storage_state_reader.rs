@@ -0,0 +1,331 @@
+use std::io::Write;
+use std::path::PathBuf;
+
+use apollo_gateway::errors::serde_err_to_state_err;
+use apollo_state_reader::papyrus_state::PapyrusReader;
+use apollo_storage::body::BodyStorageReader;
+use apollo_storage::db::DbConfig;
+use apollo_storage::header::HeaderStorageReader;
+use apollo_storage::mmap_file::MmapFileConfig;
+use apollo_storage::state::StateStorageReader;
+use apollo_storage::{open_storage_read_only, StorageError, StorageReader};
+use blockifier::abi::constants;
+use blockifier::blockifier::block::validated_gas_prices;
+use blockifier::blockifier::config::TransactionExecutorConfig;
+use blockifier::blockifier::transaction_executor::TransactionExecutor;
+use blockifier::blockifier_versioned_constants::VersionedConstants;
+use blockifier::bouncer::BouncerConfig;
+use blockifier::context::BlockContext;
+use blockifier::execution::contract_class::RunnableCompiledClass;
+use blockifier::state::cached_state::CommitmentStateDiff;
+use blockifier::state::errors::StateError;
+use blockifier::state::state_api::{StateReader, StateResult};
+use blockifier::transaction::transaction_execution::Transaction as BlockifierTransaction;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use starknet_api::block::{
+    BlockHash,
+    BlockHashAndNumber,
+    BlockInfo,
+    BlockNumber,
+    GasPrice,
+    NonzeroGasPrice,
+};
+use starknet_api::contract_class::EntryPointType;
+use starknet_api::core::{ChainId, ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::data_availability::L1DataAvailabilityMode;
+use starknet_api::deprecated_contract_class::{
+    ContractClass as DeprecatedContractClass,
+    EntryPointV0,
+};
+use starknet_api::state::{SierraContractClass, StateNumber, StorageKey};
+use starknet_core::types::{
+    CompressedLegacyContractClass,
+    ContractClass as StarknetContractClass,
+    LegacyContractEntryPoint,
+    LegacyEntryPointsByType,
+};
+use starknet_types_core::felt::Felt;
+
+use crate::state_reader::errors::{ReexecutionError, ReexecutionResult};
+use crate::state_reader::reexecution_state_reader::{
+    ConsecutiveReexecutionStateReaders,
+    ReexecutionStateReader,
+};
+use crate::state_reader::utils::get_chain_info;
+
+/// Opens the storage of a node for reexecution, for reading only.
+/// The storage must already exist. Nothing is written to it, and its scope is taken from the
+/// storage itself.
+pub fn open_storage_for_reexecution(
+    path_prefix: PathBuf,
+    chain_id: ChainId,
+) -> ReexecutionResult<StorageReader> {
+    let db_config = DbConfig { path_prefix, chain_id, ..Default::default() };
+    Ok(open_storage_read_only(db_config, MmapFileConfig::default())?)
+}
+
+fn storage_err_to_state_err(error: StorageError) -> StateError {
+    StateError::StateReadError(error.to_string())
+}
+
+/// A state reader of the state right before the execution of the given block, read from a local
+/// storage.
+#[derive(Clone)]
+pub struct StorageStateReader {
+    pub storage_reader: StorageReader,
+    pub block_number: BlockNumber,
+}
+
+impl StorageStateReader {
+    pub fn new(storage_reader: StorageReader, block_number: BlockNumber) -> Self {
+        Self { storage_reader, block_number }
+    }
+
+    fn papyrus_reader(&self) -> PapyrusReader {
+        PapyrusReader::new(self.storage_reader.clone(), self.block_number)
+    }
+
+    fn state_number(&self) -> StateNumber {
+        StateNumber(self.block_number)
+    }
+}
+
+impl StateReader for StorageStateReader {
+    fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<Felt> {
+        self.papyrus_reader().get_storage_at(contract_address, key)
+    }
+
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        self.papyrus_reader().get_nonce_at(contract_address)
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        self.papyrus_reader().get_class_hash_at(contract_address)
+    }
+
+    fn get_compiled_class(&self, class_hash: ClassHash) -> StateResult<RunnableCompiledClass> {
+        self.papyrus_reader().get_compiled_class(class_hash)
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        self.papyrus_reader().get_compiled_class_hash(class_hash)
+    }
+}
+
+impl ReexecutionStateReader for StorageStateReader {
+    fn get_contract_class(&self, class_hash: &ClassHash) -> StateResult<StarknetContractClass> {
+        let txn = self.storage_reader.begin_ro_txn().map_err(storage_err_to_state_err)?;
+        let state_reader = txn.get_state_reader().map_err(storage_err_to_state_err)?;
+        if let Some(sierra) = state_reader
+            .get_class_definition_at(self.state_number(), class_hash)
+            .map_err(storage_err_to_state_err)?
+        {
+            return sierra_to_starknet_contract_class(sierra);
+        }
+        match state_reader
+            .get_deprecated_class_definition_at(self.state_number(), class_hash)
+            .map_err(storage_err_to_state_err)?
+        {
+            Some(deprecated_class) => deprecated_to_starknet_contract_class(deprecated_class),
+            None => Err(StateError::UndeclaredClassHash(*class_hash)),
+        }
+    }
+
+    fn get_old_block_hash(&self, old_block_number: BlockNumber) -> ReexecutionResult<BlockHash> {
+        Ok(self
+            .storage_reader
+            .begin_ro_txn()?
+            .get_block_header(old_block_number)?
+            .ok_or(ReexecutionError::MissingStorageData {
+                block_number: old_block_number,
+                data: "header",
+            })?
+            .block_hash)
+    }
+}
+
+fn sierra_to_starknet_contract_class(
+    sierra: SierraContractClass,
+) -> StateResult<StarknetContractClass> {
+    // The storage and RPC representations of Sierra classes share the same serialization.
+    let raw_sierra = serde_json::to_value(sierra).map_err(serde_err_to_state_err)?;
+    Ok(StarknetContractClass::Sierra(
+        serde_json::from_value(raw_sierra).map_err(serde_err_to_state_err)?,
+    ))
+}
+
+fn deprecated_to_starknet_contract_class(
+    deprecated_class: DeprecatedContractClass,
+) -> StateResult<StarknetContractClass> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(&serde_json::to_vec(&deprecated_class.program).map_err(serde_err_to_state_err)?)
+        .map_err(|error| StateError::StateReadError(error.to_string()))?;
+    let program =
+        encoder.finish().map_err(|error| StateError::StateReadError(error.to_string()))?;
+
+    let to_legacy_entry_points = |entry_point_type: EntryPointType| {
+        deprecated_class
+            .entry_points_by_type
+            .get(&entry_point_type)
+            .map(|entry_points| {
+                entry_points
+                    .iter()
+                    .map(|EntryPointV0 { selector, offset }| LegacyContractEntryPoint {
+                        offset: u64::try_from(offset.0).expect("Entry point offset overflow."),
+                        selector: selector.0,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let entry_points_by_type = LegacyEntryPointsByType {
+        constructor: to_legacy_entry_points(EntryPointType::Constructor),
+        external: to_legacy_entry_points(EntryPointType::External),
+        l1_handler: to_legacy_entry_points(EntryPointType::L1Handler),
+    };
+
+    let abi = deprecated_class
+        .abi
+        .map(|abi| {
+            serde_json::to_value(abi)
+                .and_then(serde_json::from_value)
+                .map_err(serde_err_to_state_err)
+        })
+        .transpose()?;
+
+    Ok(StarknetContractClass::Legacy(CompressedLegacyContractClass {
+        program,
+        entry_points_by_type,
+        abi,
+    }))
+}
+
+/// The state readers required for reexecuting a block from a local storage.
+pub struct ConsecutiveStorageStateReaders {
+    /// Reads the state before the execution of the reexecuted block.
+    pub last_block_state_reader: StorageStateReader,
+    /// Reads the state after the execution of the reexecuted block; used to read the classes
+    /// declared in it.
+    pub next_block_state_reader: StorageStateReader,
+    pub chain_id: ChainId,
+}
+
+impl ConsecutiveStorageStateReaders {
+    pub fn new(
+        storage_reader: StorageReader,
+        block_number: BlockNumber,
+        chain_id: ChainId,
+    ) -> Self {
+        Self {
+            last_block_state_reader: StorageStateReader::new(storage_reader.clone(), block_number),
+            next_block_state_reader: StorageStateReader::new(
+                storage_reader,
+                block_number.next().expect("Overflow in block number"),
+            ),
+            chain_id,
+        }
+    }
+
+    fn block_number(&self) -> BlockNumber {
+        self.last_block_state_reader.block_number
+    }
+
+    fn storage_reader(&self) -> &StorageReader {
+        &self.last_block_state_reader.storage_reader
+    }
+
+    /// Returns the block context of the reexecuted block, as stored in its header.
+    /// Missing gas prices (in blocks older than the corresponding price) are set to the minimal
+    /// gas price.
+    pub fn get_next_block_context(&self) -> ReexecutionResult<BlockContext> {
+        let block_number = self.block_number();
+        let header = self
+            .storage_reader()
+            .begin_ro_txn()?
+            .get_block_header(block_number)?
+            .ok_or(ReexecutionError::MissingStorageData { block_number, data: "header" })?
+            .block_header_without_hash;
+
+        let to_nonzero =
+            |gas_price: GasPrice| NonzeroGasPrice::new(gas_price).unwrap_or(NonzeroGasPrice::MIN);
+        let block_info = BlockInfo {
+            block_number,
+            block_timestamp: header.timestamp,
+            sequencer_address: header.sequencer.0,
+            gas_prices: validated_gas_prices(
+                to_nonzero(header.l1_gas_price.price_in_wei),
+                to_nonzero(header.l1_gas_price.price_in_fri),
+                to_nonzero(header.l1_data_gas_price.price_in_wei),
+                to_nonzero(header.l1_data_gas_price.price_in_fri),
+                to_nonzero(header.l2_gas_price.price_in_wei),
+                to_nonzero(header.l2_gas_price.price_in_fri),
+            ),
+            use_kzg_da: matches!(header.l1_da_mode, L1DataAvailabilityMode::Blob),
+        };
+
+        Ok(BlockContext::new(
+            block_info,
+            get_chain_info(&self.chain_id),
+            VersionedConstants::get(&header.starknet_version)?.clone(),
+            BouncerConfig::max(),
+        ))
+    }
+}
+
+impl ConsecutiveReexecutionStateReaders<StorageStateReader> for ConsecutiveStorageStateReaders {
+    fn pre_process_and_create_executor(
+        self,
+        transaction_executor_config: Option<TransactionExecutorConfig>,
+    ) -> ReexecutionResult<TransactionExecutor<StorageStateReader>> {
+        let block_context_next_block = self.get_next_block_context()?;
+        let old_block_number_and_hash = match self
+            .block_number()
+            .0
+            .checked_sub(constants::STORED_BLOCK_HASH_BUFFER)
+            .map(BlockNumber)
+        {
+            Some(old_block_number) => Some(BlockHashAndNumber {
+                number: old_block_number,
+                hash: self.last_block_state_reader.get_old_block_hash(old_block_number)?,
+            }),
+            None => None,
+        };
+        Ok(TransactionExecutor::<StorageStateReader>::pre_process_and_create(
+            self.last_block_state_reader,
+            block_context_next_block,
+            old_block_number_and_hash,
+            transaction_executor_config.unwrap_or_default(),
+        )?)
+    }
+
+    fn get_next_block_txs(&self) -> ReexecutionResult<Vec<BlockifierTransaction>> {
+        let block_number = self.block_number();
+        let txs_and_hashes = self
+            .storage_reader()
+            .begin_ro_txn()?
+            .get_block_transactions_with_hash(block_number)?
+            .ok_or(ReexecutionError::MissingStorageData { block_number, data: "transactions" })?;
+        self.next_block_state_reader.api_txs_to_blockifier_txs_next_block(txs_and_hashes)
+    }
+
+    fn get_next_block_state_diff(&self) -> ReexecutionResult<CommitmentStateDiff> {
+        let block_number = self.block_number();
+        let thin_state_diff =
+            self.storage_reader()
+                .begin_ro_txn()?
+                .get_state_diff(block_number)?
+                .ok_or(ReexecutionError::MissingStorageData { block_number, data: "state diff" })?;
+        Ok(CommitmentStateDiff {
+            address_to_class_hash: thin_state_diff.deployed_contracts,
+            address_to_nonce: thin_state_diff.nonces,
+            storage_updates: thin_state_diff.storage_diffs,
+            class_hash_to_compiled_class_hash: thin_state_diff.declared_classes,
+        })
+    }
+}
@@ -0,0 +1,91 @@
+use apollo_storage::header::HeaderStorageWriter;
+use apollo_storage::state::StateStorageWriter;
+use apollo_storage::test_utils::get_test_storage;
+use blockifier::state::cached_state::CommitmentStateDiff;
+use blockifier::state::state_api::StateReader;
+use indexmap::IndexMap;
+use pretty_assertions::assert_eq;
+use starknet_api::block::{BlockHash, BlockHeader, BlockHeaderWithoutHash, BlockNumber};
+use starknet_api::core::ChainId;
+use starknet_api::state::ThinStateDiff;
+use starknet_api::{class_hash, contract_address, felt, nonce, storage_key};
+
+use crate::state_reader::reexecution_state_reader::{
+    ConsecutiveReexecutionStateReaders,
+    ReexecutionStateReader,
+};
+use crate::state_reader::storage_state_reader::{
+    ConsecutiveStorageStateReaders,
+    StorageStateReader,
+};
+
+#[test]
+fn test_storage_state_reader() {
+    let ((storage_reader, mut storage_writer), _temp_dir) = get_test_storage();
+
+    let block_number = BlockNumber(0);
+    let block_hash = BlockHash(felt!("0x1234"));
+    let address = contract_address!("0x1");
+    let state_diff = ThinStateDiff {
+        deployed_contracts: IndexMap::from([(address, class_hash!("0x10"))]),
+        storage_diffs: IndexMap::from([(
+            address,
+            IndexMap::from([(storage_key!("0x2"), felt!("0x3"))]),
+        )]),
+        nonces: IndexMap::from([(address, nonce!(1_u8))]),
+        ..Default::default()
+    };
+    storage_writer
+        .begin_rw_txn()
+        .unwrap()
+        .append_header(
+            block_number,
+            &BlockHeader {
+                block_hash,
+                block_header_without_hash: BlockHeaderWithoutHash {
+                    block_number,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .append_state_diff(block_number, state_diff)
+        .unwrap()
+        .commit()
+        .unwrap();
+
+    // The state before block 0 is empty, and the state after it contains the state diff.
+    let state_reader_before = StorageStateReader::new(storage_reader.clone(), block_number);
+    assert_eq!(
+        state_reader_before.get_storage_at(address, storage_key!("0x2")).unwrap(),
+        felt!("0x0")
+    );
+    let state_reader_after = StorageStateReader::new(storage_reader.clone(), BlockNumber(1));
+    assert_eq!(
+        state_reader_after.get_storage_at(address, storage_key!("0x2")).unwrap(),
+        felt!("0x3")
+    );
+    assert_eq!(state_reader_after.get_nonce_at(address).unwrap(), nonce!(1_u8));
+    assert_eq!(state_reader_after.get_class_hash_at(address).unwrap(), class_hash!("0x10"));
+    assert_eq!(state_reader_after.get_old_block_hash(block_number).unwrap(), block_hash);
+
+    let consecutive_state_readers =
+        ConsecutiveStorageStateReaders::new(storage_reader, block_number, ChainId::Mainnet);
+    assert_eq!(
+        consecutive_state_readers.get_next_block_state_diff().unwrap(),
+        CommitmentStateDiff {
+            address_to_class_hash: IndexMap::from([(address, class_hash!("0x10"))]),
+            address_to_nonce: IndexMap::from([(address, nonce!(1_u8))]),
+            storage_updates: IndexMap::from([(
+                address,
+                IndexMap::from([(storage_key!("0x2"), felt!("0x3"))]),
+            )]),
+            class_hash_to_compiled_class_hash: IndexMap::new(),
+        }
+    );
+    assert_eq!(
+        consecutive_state_readers.get_next_block_context().unwrap().block_info().block_number,
+        block_number
+    );
+}
@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs;
+
+use blockifier::blockifier::config::TransactionExecutorConfig;
+use blockifier::blockifier::transaction_executor::TransactionExecutor;
+use blockifier::context::BlockContext;
+use blockifier::execution::call_info::CallInfo;
+use blockifier::execution::contract_class::RunnableCompiledClass;
+use blockifier::state::cached_state::CachedState;
+use blockifier::state::state_api::{StateReader, StateResult};
+use blockifier::transaction::objects::TransactionExecutionInfo;
+use blockifier::transaction::transaction_execution::Transaction as BlockifierTransaction;
+use serde::{Deserialize, Serialize};
+use starknet_api::block::{BlockInfo, BlockTimestamp, GasPrices};
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::TransactionHash;
+use starknet_types_core::felt::Felt;
+
+use crate::state_reader::errors::{ReexecutionError, ReexecutionResult};
+use crate::state_reader::reexecution_state_reader::ConsecutiveReexecutionStateReaders;
+use crate::state_reader::utils::ReexecutionStateMaps;
+
+/// Values overriding the state seen by the replayed transaction.
+/// Any value not overridden is read from the state at the transaction's original position.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StateOverrides {
+    #[serde(default)]
+    pub storage: HashMap<ContractAddress, HashMap<StorageKey, Felt>>,
+    #[serde(default)]
+    pub nonces: HashMap<ContractAddress, Nonce>,
+    #[serde(default)]
+    pub class_hashes: HashMap<ContractAddress, ClassHash>,
+}
+
+/// Block info fields overriding the ones of the block the transaction was included in.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BlockContextOverrides {
+    pub block_timestamp: Option<BlockTimestamp>,
+    pub sequencer_address: Option<ContractAddress>,
+    pub gas_prices: Option<GasPrices>,
+    pub use_kzg_da: Option<bool>,
+}
+
+impl BlockContextOverrides {
+    /// Returns a copy of the given block context with the overridden block info fields.
+    pub fn apply(&self, block_context: &BlockContext) -> BlockContext {
+        let original_block_info = block_context.block_info();
+        let block_info = BlockInfo {
+            block_number: original_block_info.block_number,
+            block_timestamp: self.block_timestamp.unwrap_or(original_block_info.block_timestamp),
+            sequencer_address: self
+                .sequencer_address
+                .unwrap_or(original_block_info.sequencer_address),
+            gas_prices: self.gas_prices.clone().unwrap_or(original_block_info.gas_prices.clone()),
+            use_kzg_da: self.use_kzg_da.unwrap_or(original_block_info.use_kzg_da),
+        };
+        BlockContext::new(
+            block_info,
+            block_context.chain_info().clone(),
+            block_context.versioned_constants().clone(),
+            block_context.bouncer_config().clone(),
+        )
+    }
+}
+
+/// All the overrides applied when replaying a single transaction; read from a JSON file of the
+/// form `{"state": {...}, "block_context": {...}}`, where both fields are optional.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReplayOverrides {
+    #[serde(default)]
+    pub state: StateOverrides,
+    #[serde(default)]
+    pub block_context: BlockContextOverrides,
+}
+
+impl ReplayOverrides {
+    pub fn read_from_file(full_file_path: &str) -> ReexecutionResult<Self> {
+        let file_content = fs::read_to_string(full_file_path)?;
+        Ok(serde_json::from_str(&file_content)?)
+    }
+}
+
+/// A state reader returning the overridden values where given, and delegating to the underlying
+/// state reader otherwise.
+pub struct StateOverridesReader<S: StateReader> {
+    pub state_reader: S,
+    pub overrides: StateOverrides,
+}
+
+impl<S: StateReader> StateOverridesReader<S> {
+    pub fn new(state_reader: S, overrides: StateOverrides) -> Self {
+        Self { state_reader, overrides }
+    }
+}
+
+impl<S: StateReader> StateReader for StateOverridesReader<S> {
+    fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<Felt> {
+        match self.overrides.storage.get(&contract_address).and_then(|storage| storage.get(&key)) {
+            Some(value) => Ok(*value),
+            None => self.state_reader.get_storage_at(contract_address, key),
+        }
+    }
+
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        match self.overrides.nonces.get(&contract_address) {
+            Some(nonce) => Ok(*nonce),
+            None => self.state_reader.get_nonce_at(contract_address),
+        }
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        match self.overrides.class_hashes.get(&contract_address) {
+            Some(class_hash) => Ok(*class_hash),
+            None => self.state_reader.get_class_hash_at(contract_address),
+        }
+    }
+
+    fn get_compiled_class(&self, class_hash: ClassHash) -> StateResult<RunnableCompiledClass> {
+        self.state_reader.get_compiled_class(class_hash)
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        self.state_reader.get_compiled_class_hash(class_hash)
+    }
+}
+
+/// The result of replaying a single transaction.
+#[derive(Debug, Serialize)]
+pub struct TransactionReplayOutput {
+    pub tx_hash: TransactionHash,
+    /// The index of the transaction in its block.
+    pub tx_index: usize,
+    pub execution_info: TransactionExecutionInfo,
+    /// The state changes made by the replayed transaction only.
+    pub state_diff: ReexecutionStateMaps,
+}
+
+impl TransactionReplayOutput {
+    pub fn write_to_file(&self, full_file_path: &str) -> ReexecutionResult<()> {
+        Ok(fs::write(full_file_path, serde_json::to_string_pretty(&self)?)?)
+    }
+
+    /// Returns a human readable representation of the call tree, the fee and the state diff.
+    pub fn pretty_print(&self) -> ReexecutionResult<String> {
+        let mut output = format!("Transaction {} (index {}):\n", self.tx_hash, self.tx_index);
+        let call_infos = [
+            ("Validate", &self.execution_info.validate_call_info),
+            ("Execute", &self.execution_info.execute_call_info),
+            ("Fee transfer", &self.execution_info.fee_transfer_call_info),
+        ];
+        for (name, call_info) in call_infos {
+            if let Some(call_info) = call_info {
+                writeln!(output, "{name} call tree:").unwrap();
+                write_call_tree(&mut output, call_info, 1);
+            }
+        }
+        if let Some(revert_error) = &self.execution_info.revert_error {
+            writeln!(output, "Revert error: {revert_error}").unwrap();
+        }
+        writeln!(output, "Actual fee: {}", self.execution_info.receipt.fee.0).unwrap();
+        writeln!(output, "Gas consumed: {:?}", self.execution_info.receipt.gas).unwrap();
+        writeln!(output, "State diff:\n{}", serde_json::to_string_pretty(&self.state_diff)?)
+            .unwrap();
+        Ok(output)
+    }
+}
+
+fn write_call_tree(output: &mut String, call_info: &CallInfo, depth: usize) {
+    let indent = "  ".repeat(depth);
+    let call = &call_info.call;
+    let execution = &call_info.execution;
+    writeln!(
+        output,
+        "{indent}{} :: {} (class hash: {}, gas consumed: {}{})",
+        call.storage_address,
+        call.entry_point_selector.0,
+        call.class_hash.map_or("unknown".to_string(), |class_hash| class_hash.to_string()),
+        execution.gas_consumed,
+        if execution.failed { ", FAILED" } else { "" },
+    )
+    .unwrap();
+    writeln!(output, "{indent}  calldata: {:?}", call.calldata.0).unwrap();
+    writeln!(output, "{indent}  retdata: {:?}", execution.retdata.0).unwrap();
+    for inner_call in &call_info.inner_calls {
+        write_call_tree(output, inner_call, depth + 1);
+    }
+}
+
+/// Re-executes the block of the given transaction up to the transaction (exclusive), then executes
+/// the transaction itself on top of the given overrides.
+/// Overrides are applied only to the replayed transaction; the preceding transactions are executed
+/// as in the original block.
+pub fn replay_transaction<
+    S: StateReader + Send + Sync + Clone + 'static,
+    T: ConsecutiveReexecutionStateReaders<S>,
+>(
+    consecutive_state_readers: T,
+    tx_hash: TransactionHash,
+    overrides: ReplayOverrides,
+) -> ReexecutionResult<TransactionReplayOutput> {
+    let all_txs_in_next_block = consecutive_state_readers.get_next_block_txs()?;
+    let tx_index = all_txs_in_next_block
+        .iter()
+        .position(|tx| BlockifierTransaction::tx_hash(tx) == tx_hash)
+        .ok_or(ReexecutionError::TransactionNotFound(tx_hash))?;
+
+    let mut transaction_executor =
+        consecutive_state_readers.pre_process_and_create_executor(None)?;
+    for result in transaction_executor.execute_txs(&all_txs_in_next_block[..tx_index], None) {
+        result?;
+    }
+
+    let block_state =
+        transaction_executor.block_state.take().expect("The block state should be available.");
+    let mut replay_executor = TransactionExecutor::new(
+        CachedState::new(StateOverridesReader::new(block_state, overrides.state)),
+        overrides.block_context.apply(&transaction_executor.block_context),
+        TransactionExecutorConfig::default(),
+    );
+    let (execution_info, state_diff) = replay_executor.execute(&all_txs_in_next_block[tx_index])?;
+
+    Ok(TransactionReplayOutput { tx_hash, tx_index, execution_info, state_diff: state_diff.into() })
+}
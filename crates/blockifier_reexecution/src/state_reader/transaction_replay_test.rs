@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+
+use assert_matches::assert_matches;
+use blockifier::blockifier::config::TransactionExecutorConfig;
+use blockifier::blockifier::transaction_executor::TransactionExecutor;
+use blockifier::context::BlockContext;
+use blockifier::state::cached_state::{CachedState, CommitmentStateDiff, StateMaps};
+use blockifier::state::state_api::StateReader;
+use blockifier::test_utils::dict_state_reader::DictStateReader;
+use blockifier::transaction::account_transaction::AccountTransaction;
+use blockifier::transaction::test_utils::{create_test_init_data, TestInitData};
+use blockifier::transaction::transaction_execution::Transaction as BlockifierTransaction;
+use blockifier_test_utils::cairo_versions::{CairoVersion, RunnableCairo1};
+use blockifier_test_utils::calldata::create_calldata;
+use pretty_assertions::assert_eq;
+use rstest::{fixture, rstest};
+use starknet_api::block::BlockTimestamp;
+use starknet_api::core::ContractAddress;
+use starknet_api::test_utils::invoke::executable_invoke_tx;
+use starknet_api::transaction::TransactionHash;
+use starknet_api::{class_hash, contract_address, felt, invoke_tx_args, nonce, storage_key};
+
+use crate::state_reader::errors::{ReexecutionError, ReexecutionResult};
+use crate::state_reader::offline_state_reader::OfflineStateReader;
+use crate::state_reader::reexecution_state_reader::ConsecutiveReexecutionStateReaders;
+use crate::state_reader::transaction_replay::{
+    replay_transaction,
+    BlockContextOverrides,
+    ReplayOverrides,
+    StateOverrides,
+    StateOverridesReader,
+};
+
+#[fixture]
+fn offline_state_reader() -> OfflineStateReader {
+    let state_maps = StateMaps {
+        nonces: HashMap::from([(contract_address!("0x1"), nonce!(1_u8))]),
+        class_hashes: HashMap::from([(contract_address!("0x1"), class_hash!("0x10"))]),
+        storage: HashMap::from([
+            ((contract_address!("0x1"), storage_key!("0x2")), felt!("0x3")),
+            ((contract_address!("0x1"), storage_key!("0x4")), felt!("0x5")),
+        ]),
+        ..Default::default()
+    };
+    OfflineStateReader { state_maps, ..Default::default() }
+}
+
+#[rstest]
+fn test_state_overrides_reader(offline_state_reader: OfflineStateReader) {
+    let overrides = StateOverrides {
+        storage: HashMap::from([(
+            contract_address!("0x1"),
+            HashMap::from([(storage_key!("0x2"), felt!("0x7"))]),
+        )]),
+        nonces: HashMap::from([(contract_address!("0x1"), nonce!(8_u8))]),
+        class_hashes: HashMap::new(),
+    };
+    let state_reader = StateOverridesReader::new(offline_state_reader, overrides);
+
+    // Overridden values.
+    assert_eq!(
+        state_reader.get_storage_at(contract_address!("0x1"), storage_key!("0x2")).unwrap(),
+        felt!("0x7")
+    );
+    assert_eq!(state_reader.get_nonce_at(contract_address!("0x1")).unwrap(), nonce!(8_u8));
+
+    // Values read from the underlying state reader.
+    assert_eq!(
+        state_reader.get_storage_at(contract_address!("0x1"), storage_key!("0x4")).unwrap(),
+        felt!("0x5")
+    );
+    assert_eq!(
+        state_reader.get_class_hash_at(contract_address!("0x1")).unwrap(),
+        class_hash!("0x10")
+    );
+}
+
+#[test]
+fn test_deserialize_replay_overrides() {
+    let raw_overrides = r#"{
+        "state": {
+            "storage": {"0x1": {"0x2": "0x7"}},
+            "nonces": {"0x1": "0x8"}
+        },
+        "block_context": {"block_timestamp": 1700000000}
+    }"#;
+    let overrides: ReplayOverrides = serde_json::from_str(raw_overrides).unwrap();
+
+    assert_eq!(
+        overrides,
+        ReplayOverrides {
+            state: StateOverrides {
+                storage: HashMap::from([(
+                    contract_address!("0x1"),
+                    HashMap::from([(storage_key!("0x2"), felt!("0x7"))]),
+                )]),
+                nonces: HashMap::from([(contract_address!("0x1"), nonce!(8_u8))]),
+                class_hashes: HashMap::new(),
+            },
+            block_context: BlockContextOverrides {
+                block_timestamp: Some(BlockTimestamp(1700000000)),
+                ..Default::default()
+            },
+        }
+    );
+}
+
+#[test]
+fn test_block_context_overrides_keep_bouncer_config() {
+    let block_context = BlockContext::create_for_bouncer_testing(10);
+    let overrides =
+        BlockContextOverrides { block_timestamp: Some(BlockTimestamp(7)), ..Default::default() };
+
+    let overridden_block_context = overrides.apply(&block_context);
+    assert_eq!(overridden_block_context.block_info().block_timestamp, BlockTimestamp(7));
+    assert_eq!(overridden_block_context.bouncer_config(), block_context.bouncer_config());
+}
+
+#[test]
+fn test_read_replay_overrides_from_missing_file() {
+    assert_matches!(
+        ReplayOverrides::read_from_file("/nonexistent/replay_overrides.json"),
+        Err(ReexecutionError::Io(_))
+    );
+}
+
+/// Consecutive state readers of a block, on top of an in-memory state.
+struct TestConsecutiveStateReaders {
+    state_reader: DictStateReader,
+    block_context: BlockContext,
+    transactions: Vec<BlockifierTransaction>,
+}
+
+impl ConsecutiveReexecutionStateReaders<DictStateReader> for TestConsecutiveStateReaders {
+    fn pre_process_and_create_executor(
+        self,
+        transaction_executor_config: Option<TransactionExecutorConfig>,
+    ) -> ReexecutionResult<TransactionExecutor<DictStateReader>> {
+        Ok(TransactionExecutor::new(
+            CachedState::new(self.state_reader),
+            self.block_context,
+            transaction_executor_config.unwrap_or_default(),
+        ))
+    }
+
+    fn get_next_block_txs(&self) -> ReexecutionResult<Vec<BlockifierTransaction>> {
+        Ok(self.transactions.clone())
+    }
+
+    fn get_next_block_state_diff(&self) -> ReexecutionResult<CommitmentStateDiff> {
+        unimplemented!("Not needed for replaying a transaction.")
+    }
+}
+
+fn invoke_tx(
+    account_address: ContractAddress,
+    contract_address: ContractAddress,
+    nonce: u8,
+) -> BlockifierTransaction {
+    let calldata = create_calldata(
+        contract_address,
+        "test_emit_events",
+        &[felt!(1_u8), felt!(0_u8), felt!(0_u8)],
+    );
+    BlockifierTransaction::Account(AccountTransaction::new_for_sequencing(executable_invoke_tx(
+        invoke_tx_args! {
+            sender_address: account_address,
+            calldata,
+            nonce: nonce!(nonce),
+            tx_hash: TransactionHash(felt!(nonce + 1)),
+        },
+    )))
+}
+
+/// A block of two invoke transactions of the same account, with nonces 0 and 1.
+#[fixture]
+fn two_txs_block() -> (TestConsecutiveStateReaders, ContractAddress) {
+    let block_context = BlockContext::create_for_account_testing();
+    let TestInitData { state, account_address, contract_address, .. } = create_test_init_data(
+        block_context.chain_info(),
+        CairoVersion::Cairo1(RunnableCairo1::Casm),
+    );
+    let transactions = vec![
+        invoke_tx(account_address, contract_address, 0),
+        invoke_tx(account_address, contract_address, 1),
+    ];
+    (
+        TestConsecutiveStateReaders { state_reader: state.state, block_context, transactions },
+        account_address,
+    )
+}
+
+#[rstest]
+fn test_replay_transaction(two_txs_block: (TestConsecutiveStateReaders, ContractAddress)) {
+    let (state_readers, account_address) = two_txs_block;
+    let tx_hash = TransactionHash(felt!(2_u8));
+
+    let output = replay_transaction(state_readers, tx_hash, ReplayOverrides::default()).unwrap();
+
+    assert_eq!(output.tx_hash, tx_hash);
+    assert_eq!(output.tx_index, 1);
+    assert_eq!(output.execution_info.revert_error, None);
+    // The state diff includes only the changes of the replayed transaction.
+    let state_diff = StateMaps::try_from(output.state_diff).unwrap();
+    assert_eq!(state_diff.nonces, HashMap::from([(account_address, nonce!(2_u8))]));
+}
+
+#[rstest]
+fn test_replay_transaction_with_state_overrides(
+    two_txs_block: (TestConsecutiveStateReaders, ContractAddress),
+) {
+    let (state_readers, account_address) = two_txs_block;
+    // The replayed transaction has nonce 1, so overriding the account nonce fails its validation.
+    let overrides = ReplayOverrides {
+        state: StateOverrides {
+            nonces: HashMap::from([(account_address, nonce!(5_u8))]),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    assert!(replay_transaction(state_readers, TransactionHash(felt!(2_u8)), overrides).is_err());
+}
+
+#[rstest]
+fn test_replay_missing_transaction(two_txs_block: (TestConsecutiveStateReaders, ContractAddress)) {
+    let (state_readers, _) = two_txs_block;
+    let tx_hash = TransactionHash(felt!(3_u8));
+
+    assert_matches!(
+        replay_transaction(state_readers, tx_hash, ReplayOverrides::default()),
+        Err(ReexecutionError::TransactionNotFound(missing_tx_hash)) if missing_tx_hash == tx_hash
+    );
+}
@@ -189,10 +189,10 @@ macro_rules! retry_request {
 /// useful in testing.
 #[derive(Debug, PartialEq)]
 pub struct ComparableStateDiff {
-    address_to_class_hash: BTreeMap<ContractAddress, ClassHash>,
-    address_to_nonce: BTreeMap<ContractAddress, Nonce>,
-    storage_updates: BTreeMap<ContractAddress, BTreeMap<StorageKey, Felt>>,
-    class_hash_to_compiled_class_hash: BTreeMap<ClassHash, CompiledClassHash>,
+    pub(crate) address_to_class_hash: BTreeMap<ContractAddress, ClassHash>,
+    pub(crate) address_to_nonce: BTreeMap<ContractAddress, Nonce>,
+    pub(crate) storage_updates: BTreeMap<ContractAddress, BTreeMap<StorageKey, Felt>>,
+    pub(crate) class_hash_to_compiled_class_hash: BTreeMap<ClassHash, CompiledClassHash>,
 }
 
 impl From<CommitmentStateDiff> for ComparableStateDiff {
@@ -106,6 +106,8 @@ async fn get_gas_price_and_timestamps() {
     // Roughly e ** (BLOB_GAS / eip7691::BLOB_GASPRICE_UPDATE_FRACTION_PECTRA)
     let expected_pectra_blob_calc = 7;
     assert_eq!(header.blob_fee, expected_pectra_blob_calc);
+    // No blob gas was used, so the excess blob gas, and with it the blob fee, decrease.
+    assert!(header.next_blob_fee < header.blob_fee);
 
     // Test legacy blob
 
@@ -234,16 +234,25 @@ impl BaseLayerContract for EthereumBaseLayerContract {
         let Some(base_fee) = block.header.base_fee_per_gas else {
             return Ok(None);
         };
-        let blob_fee = match block.header.excess_blob_gas {
-            Some(excess_blob_gas) if self.config.prague_blob_gas_calc => {
-                // Pectra update.
-                eip7840::BlobParams::prague().calc_blob_fee(excess_blob_gas)
-            }
+        let blob_params = if self.config.prague_blob_gas_calc {
+            // Pectra update.
+            eip7840::BlobParams::prague()
+        } else {
+            // EIP 4844 - original blob pricing.
+            eip7840::BlobParams::cancun()
+        };
+        let (blob_fee, next_blob_fee) = match block.header.excess_blob_gas {
             Some(excess_blob_gas) => {
-                // EIP 4844 - original blob pricing.
-                eip7840::BlobParams::cancun().calc_blob_fee(excess_blob_gas)
+                let next_excess_blob_gas = blob_params.next_block_excess_blob_gas(
+                    excess_blob_gas,
+                    block.header.blob_gas_used.unwrap_or_default(),
+                );
+                (
+                    blob_params.calc_blob_fee(excess_blob_gas),
+                    blob_params.calc_blob_fee(next_excess_blob_gas),
+                )
             }
-            None => 0,
+            None => (0, 0),
         };
 
         Ok(Some(L1BlockHeader {
@@ -253,6 +262,7 @@ impl BaseLayerContract for EthereumBaseLayerContract {
             timestamp: block.header.timestamp.into(),
             base_fee_per_gas: base_fee.into(),
             blob_fee,
+            next_blob_fee,
         }))
     }
 
@@ -0,0 +1,357 @@
+use std::ops::RangeInclusive;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use starknet_api::block::{BlockHashAndNumber, BlockTimestamp};
+use starknet_api::transaction::fields::Fee;
+use starknet_api::transaction::L1HandlerTransaction;
+use url::Url;
+
+use crate::constants::{
+    EventIdentifier,
+    CONSUMED_MESSAGE_TO_L2_EVENT_IDENTIFIER,
+    LOG_MESSAGE_TO_L2_EVENT_IDENTIFIER,
+    MESSAGE_TO_L2_CANCELED_EVENT_IDENTIFIER,
+    MESSAGE_TO_L2_CANCELLATION_STARTED_EVENT_IDENTIFIER,
+};
+use crate::{
+    BaseLayerContract,
+    EventData,
+    L1BlockHash,
+    L1BlockHeader,
+    L1BlockNumber,
+    L1BlockReference,
+    L1Event,
+};
+
+#[cfg(test)]
+#[path = "fake_base_layer_test.rs"]
+mod fake_base_layer_test;
+
+pub type FakeBaseLayerResult<T> = Result<T, FakeBaseLayerError>;
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum FakeBaseLayerError {
+    #[error("No Starknet block is proved on L1 at L1 block {0}.")]
+    NoProvedBlock(L1BlockNumber),
+    #[error("Cannot reorg {depth} L1 blocks, the latest L1 block is {latest_l1_block}.")]
+    ReorgTooDeep { depth: u64, latest_l1_block: L1BlockNumber },
+    #[error("L1 block {0} does not exist.")]
+    UnknownL1Block(L1BlockNumber),
+}
+
+/// An in-process L1 that serves the Starknet contract's events and state updates from memory,
+/// instead of from an Ethereum node.
+/// The chain is scripted: events, state updates and gas prices are added to a pending block, which
+/// is appended to the chain once mined. Clones share the same chain, so one clone can be handed to
+/// the node's components while another one scripts it, for example through the admin API.
+#[derive(Clone, Debug)]
+pub struct FakeBaseLayerContract {
+    chain: Arc<Mutex<FakeL1Chain>>,
+}
+
+impl FakeBaseLayerContract {
+    /// Creates a chain that contains only a genesis block, at the given timestamp.
+    pub fn new(genesis_timestamp: BlockTimestamp) -> Self {
+        Self { chain: Arc::new(Mutex::new(FakeL1Chain::new(genesis_timestamp))) }
+    }
+
+    /// Adds an event to the pending block. The timestamps of `LogMessageToL2` and
+    /// `MessageToL2CancellationStarted` events are set to the pending block's timestamp once it is
+    /// mined.
+    pub fn add_event(&self, event: L1Event) {
+        self.chain().pending_block.events.push(event);
+    }
+
+    pub fn send_message_to_l2(&self, tx: L1HandlerTransaction, fee: Fee) {
+        self.add_event(L1Event::LogMessageToL2 {
+            tx,
+            fee,
+            l1_tx_hash: None,
+            timestamp: BlockTimestamp::default(),
+        });
+    }
+
+    pub fn start_message_to_l2_cancellation(&self, cancelled_tx: L1HandlerTransaction) {
+        self.add_event(L1Event::MessageToL2CancellationStarted {
+            cancelled_tx,
+            cancellation_request_timestamp: BlockTimestamp::default(),
+        });
+    }
+
+    pub fn cancel_message_to_l2(&self, event_data: EventData) {
+        self.add_event(L1Event::MessageToL2Canceled(event_data));
+    }
+
+    pub fn consume_message_to_l2(&self, tx: L1HandlerTransaction) {
+        self.add_event(L1Event::ConsumedMessageToL2(tx));
+    }
+
+    /// Proves the given Starknet block on L1, starting from the pending block.
+    pub fn update_state(&self, proved_block: BlockHashAndNumber) {
+        self.chain().pending_block.proved_block = Some(proved_block);
+    }
+
+    /// Sets the gas prices of the pending block and of all the blocks mined after it.
+    pub fn set_gas_prices(&self, base_fee_per_gas: u128, blob_fee: u128) {
+        let mut chain = self.chain();
+        chain.pending_block.base_fee_per_gas = base_fee_per_gas;
+        chain.pending_block.blob_fee = blob_fee;
+    }
+
+    /// Appends the pending block to the chain. If no timestamp is given, the current time is used.
+    /// Timestamps never decrease along the chain.
+    pub fn mine_block(&self, timestamp: Option<BlockTimestamp>) -> L1BlockReference {
+        let timestamp = timestamp.unwrap_or_else(unix_now);
+        self.chain().mine_block(timestamp)
+    }
+
+    /// Drops the latest `depth` blocks, along with their events and state updates. Blocks mined
+    /// afterwards get hashes that differ from those of the dropped blocks, as in a real L1 reorg.
+    pub fn reorg(&self, depth: u64) -> FakeBaseLayerResult<L1BlockReference> {
+        self.chain().reorg(depth)
+    }
+
+    pub fn latest_block(&self) -> L1BlockReference {
+        self.chain().latest_block().reference()
+    }
+
+    fn chain(&self) -> MutexGuard<'_, FakeL1Chain> {
+        self.chain.lock().expect("Fake base layer chain lock should not be poisoned.")
+    }
+}
+
+impl Default for FakeBaseLayerContract {
+    fn default() -> Self {
+        Self::new(BlockTimestamp::default())
+    }
+}
+
+#[async_trait]
+impl BaseLayerContract for FakeBaseLayerContract {
+    type Error = FakeBaseLayerError;
+
+    async fn get_proved_block_at(
+        &self,
+        l1_block: L1BlockNumber,
+    ) -> FakeBaseLayerResult<BlockHashAndNumber> {
+        let chain = self.chain();
+        let block = chain.block(l1_block).ok_or(FakeBaseLayerError::UnknownL1Block(l1_block))?;
+        block.proved_block.ok_or(FakeBaseLayerError::NoProvedBlock(l1_block))
+    }
+
+    async fn latest_proved_block(
+        &self,
+        finality: u64,
+    ) -> FakeBaseLayerResult<Option<BlockHashAndNumber>> {
+        let chain = self.chain();
+        let latest_block =
+            chain.latest_block_number(finality).and_then(|number| chain.block(number));
+        Ok(latest_block.and_then(|block| block.proved_block))
+    }
+
+    async fn latest_l1_block_number(
+        &self,
+        finality: u64,
+    ) -> FakeBaseLayerResult<Option<L1BlockNumber>> {
+        Ok(self.chain().latest_block_number(finality))
+    }
+
+    async fn latest_l1_block(
+        &self,
+        finality: u64,
+    ) -> FakeBaseLayerResult<Option<L1BlockReference>> {
+        let chain = self.chain();
+        let latest_block =
+            chain.latest_block_number(finality).and_then(|number| chain.block(number));
+        Ok(latest_block.map(FakeL1Block::reference))
+    }
+
+    async fn l1_block_at(
+        &self,
+        block_number: L1BlockNumber,
+    ) -> FakeBaseLayerResult<Option<L1BlockReference>> {
+        Ok(self.chain().block(block_number).map(FakeL1Block::reference))
+    }
+
+    async fn events<'a>(
+        &'a self,
+        block_range: RangeInclusive<L1BlockNumber>,
+        event_identifiers: &'a [&'a str],
+    ) -> FakeBaseLayerResult<Vec<L1Event>> {
+        let chain = self.chain();
+        let events = block_range
+            .filter_map(|number| chain.block(number))
+            .flat_map(|block| block.events.iter())
+            .filter(|event| event_identifiers.contains(&event_identifier(event)))
+            .cloned()
+            .collect();
+        Ok(events)
+    }
+
+    async fn get_block_header(
+        &self,
+        block_number: L1BlockNumber,
+    ) -> FakeBaseLayerResult<Option<L1BlockHeader>> {
+        Ok(self.chain().block(block_number).map(|block| block.header.clone()))
+    }
+
+    async fn set_provider_url(&mut self, _url: Url) -> FakeBaseLayerResult<()> {
+        // There is no L1 endpoint to switch.
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct FakeL1Chain {
+    /// The canonical chain, where a block's index is its number.
+    blocks: Vec<FakeL1Block>,
+    pending_block: PendingBlock,
+    /// Incremented on every reorg, and mixed into block hashes so that blocks mined after a reorg
+    /// never share a hash with the blocks they replace.
+    fork_id: u64,
+}
+
+impl FakeL1Chain {
+    fn new(genesis_timestamp: BlockTimestamp) -> Self {
+        let genesis_block = FakeL1Block {
+            header: L1BlockHeader {
+                number: 0,
+                hash: block_hash(0, 0),
+                timestamp: genesis_timestamp,
+                ..Default::default()
+            },
+            events: vec![],
+            proved_block: None,
+        };
+        Self { blocks: vec![genesis_block], pending_block: PendingBlock::default(), fork_id: 0 }
+    }
+
+    fn latest_block(&self) -> &FakeL1Block {
+        self.blocks.last().expect("The chain always contains the genesis block.")
+    }
+
+    fn latest_block_number(&self, finality: u64) -> Option<L1BlockNumber> {
+        self.latest_block().header.number.checked_sub(finality)
+    }
+
+    fn block(&self, number: L1BlockNumber) -> Option<&FakeL1Block> {
+        self.blocks.get(as_index(number))
+    }
+
+    fn mine_block(&mut self, timestamp: BlockTimestamp) -> L1BlockReference {
+        let parent = self.latest_block();
+        let number = parent.header.number + 1;
+        let timestamp = timestamp.max(parent.header.timestamp);
+        // State updates are only ever overridden by newer ones.
+        let proved_block = self.pending_block.proved_block.or(parent.proved_block);
+        let parent_hash = parent.header.hash;
+
+        // Gas prices carry over to the next blocks.
+        let next_pending_block = PendingBlock {
+            base_fee_per_gas: self.pending_block.base_fee_per_gas,
+            blob_fee: self.pending_block.blob_fee,
+            ..Default::default()
+        };
+        let pending_block = std::mem::replace(&mut self.pending_block, next_pending_block);
+        let events = pending_block
+            .events
+            .into_iter()
+            .map(|event| with_timestamp(event, timestamp))
+            .collect();
+
+        let block = FakeL1Block {
+            header: L1BlockHeader {
+                number,
+                hash: block_hash(number, self.fork_id),
+                parent_hash,
+                timestamp,
+                base_fee_per_gas: pending_block.base_fee_per_gas,
+                blob_fee: pending_block.blob_fee,
+                // There is no blob market on the fake L1, so the blob fee only changes when set.
+                next_blob_fee: pending_block.blob_fee,
+            },
+            events,
+            proved_block,
+        };
+        let reference = block.reference();
+        self.blocks.push(block);
+        reference
+    }
+
+    fn reorg(&mut self, depth: u64) -> FakeBaseLayerResult<L1BlockReference> {
+        let latest_l1_block = self.latest_block().header.number;
+        if depth > latest_l1_block {
+            return Err(FakeBaseLayerError::ReorgTooDeep { depth, latest_l1_block });
+        }
+
+        self.blocks.truncate(as_index(latest_l1_block - depth + 1));
+        self.fork_id += 1;
+        Ok(self.latest_block().reference())
+    }
+}
+
+#[derive(Clone, Debug)]
+struct FakeL1Block {
+    header: L1BlockHeader,
+    events: Vec<L1Event>,
+    /// The latest Starknet block proved on L1 as of this block.
+    proved_block: Option<BlockHashAndNumber>,
+}
+
+impl FakeL1Block {
+    fn reference(&self) -> L1BlockReference {
+        L1BlockReference { number: self.header.number, hash: self.header.hash }
+    }
+}
+
+#[derive(Debug, Default)]
+struct PendingBlock {
+    events: Vec<L1Event>,
+    proved_block: Option<BlockHashAndNumber>,
+    base_fee_per_gas: u128,
+    blob_fee: u128,
+}
+
+fn block_hash(number: L1BlockNumber, fork_id: u64) -> L1BlockHash {
+    let mut hash = [0; 32];
+    hash[..8].copy_from_slice(&fork_id.to_be_bytes());
+    hash[24..].copy_from_slice(&number.to_be_bytes());
+    hash
+}
+
+fn event_identifier(event: &L1Event) -> EventIdentifier {
+    match event {
+        L1Event::ConsumedMessageToL2(_) => CONSUMED_MESSAGE_TO_L2_EVENT_IDENTIFIER,
+        L1Event::LogMessageToL2 { .. } => LOG_MESSAGE_TO_L2_EVENT_IDENTIFIER,
+        L1Event::MessageToL2CancellationStarted { .. } => {
+            MESSAGE_TO_L2_CANCELLATION_STARTED_EVENT_IDENTIFIER
+        }
+        L1Event::MessageToL2Canceled(_) => MESSAGE_TO_L2_CANCELED_EVENT_IDENTIFIER,
+    }
+}
+
+fn with_timestamp(event: L1Event, block_timestamp: BlockTimestamp) -> L1Event {
+    match event {
+        L1Event::LogMessageToL2 { tx, fee, l1_tx_hash, .. } => {
+            L1Event::LogMessageToL2 { tx, fee, l1_tx_hash, timestamp: block_timestamp }
+        }
+        L1Event::MessageToL2CancellationStarted { cancelled_tx, .. } => {
+            L1Event::MessageToL2CancellationStarted {
+                cancelled_tx,
+                cancellation_request_timestamp: block_timestamp,
+            }
+        }
+        event => event,
+    }
+}
+
+fn as_index(number: L1BlockNumber) -> usize {
+    usize::try_from(number).expect("L1 block number should fit in usize.")
+}
+
+fn unix_now() -> BlockTimestamp {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).expect("Time went backwards.");
+    BlockTimestamp(now.as_secs())
+}
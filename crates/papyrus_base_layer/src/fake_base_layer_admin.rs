@@ -0,0 +1,115 @@
+use std::net::SocketAddr;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router, Server};
+use serde::{Deserialize, Serialize};
+use starknet_api::block::{BlockHashAndNumber, BlockTimestamp};
+use tracing::{info, instrument};
+
+use crate::fake_base_layer::FakeBaseLayerContract;
+use crate::{L1BlockReference, L1Event};
+
+#[cfg(test)]
+#[path = "fake_base_layer_admin_test.rs"]
+mod fake_base_layer_admin_test;
+
+pub const LATEST_BLOCK: &str = "/blocks/latest";
+pub const BLOCKS: &str = "/blocks";
+pub const REORG: &str = "/reorg";
+pub const EVENTS: &str = "/events";
+pub const STATE_UPDATE: &str = "/stateUpdate";
+pub const GAS_PRICES: &str = "/gasPrices";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MineBlockRequest {
+    /// Defaults to the current time.
+    pub timestamp: Option<BlockTimestamp>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReorgRequest {
+    pub depth: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasPricesRequest {
+    pub base_fee_per_gas: u128,
+    pub blob_fee: u128,
+}
+
+/// Serves an HTTP API for scripting the given fake L1 from outside the process, e.g. from devnet
+/// tooling:
+/// - `GET /blocks/latest`: the latest L1 block.
+/// - `POST /blocks`: mines the pending block, returning it.
+/// - `POST /reorg`: drops the latest blocks, returning the new latest block.
+/// - `POST /events`: adds an `L1Event` to the pending block.
+/// - `POST /stateUpdate`: proves a Starknet block on L1, starting from the pending block.
+/// - `POST /gasPrices`: sets the gas prices of the pending block and of the blocks after it.
+#[instrument(skip(fake_base_layer))]
+pub async fn run_admin_api(
+    fake_base_layer: FakeBaseLayerContract,
+    socket: SocketAddr,
+) -> Result<(), hyper::Error> {
+    info!("Fake base layer admin API running using socket: {socket}");
+    Server::bind(&socket).serve(admin_router(fake_base_layer).into_make_service()).await
+}
+
+pub fn admin_router(fake_base_layer: FakeBaseLayerContract) -> Router {
+    Router::new()
+        .route(LATEST_BLOCK, get(latest_block))
+        .route(BLOCKS, post(mine_block))
+        .route(REORG, post(reorg))
+        .route(EVENTS, post(add_event))
+        .route(STATE_UPDATE, post(update_state))
+        .route(GAS_PRICES, post(set_gas_prices))
+        .with_state(fake_base_layer)
+}
+
+async fn latest_block(
+    State(fake_base_layer): State<FakeBaseLayerContract>,
+) -> Json<L1BlockReference> {
+    fake_base_layer.latest_block().into()
+}
+
+async fn mine_block(
+    State(fake_base_layer): State<FakeBaseLayerContract>,
+    Json(request): Json<MineBlockRequest>,
+) -> Json<L1BlockReference> {
+    fake_base_layer.mine_block(request.timestamp).into()
+}
+
+async fn reorg(
+    State(fake_base_layer): State<FakeBaseLayerContract>,
+    Json(request): Json<ReorgRequest>,
+) -> Result<Json<L1BlockReference>, (StatusCode, String)> {
+    fake_base_layer
+        .reorg(request.depth)
+        .map(Json)
+        .map_err(|err| (StatusCode::BAD_REQUEST, err.to_string()))
+}
+
+async fn add_event(
+    State(fake_base_layer): State<FakeBaseLayerContract>,
+    Json(event): Json<L1Event>,
+) -> StatusCode {
+    fake_base_layer.add_event(event);
+    StatusCode::OK
+}
+
+async fn update_state(
+    State(fake_base_layer): State<FakeBaseLayerContract>,
+    Json(proved_block): Json<BlockHashAndNumber>,
+) -> StatusCode {
+    fake_base_layer.update_state(proved_block);
+    StatusCode::OK
+}
+
+async fn set_gas_prices(
+    State(fake_base_layer): State<FakeBaseLayerContract>,
+    Json(request): Json<GasPricesRequest>,
+) -> StatusCode {
+    fake_base_layer.set_gas_prices(request.base_fee_per_gas, request.blob_fee);
+    StatusCode::OK
+}
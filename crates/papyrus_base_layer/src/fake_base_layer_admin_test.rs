@@ -0,0 +1,76 @@
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::response::Response;
+use axum::Router;
+use hyper::body::to_bytes;
+use pretty_assertions::assert_eq;
+use serde::Serialize;
+use starknet_api::block::BlockTimestamp;
+use starknet_api::transaction::fields::Fee;
+use starknet_api::transaction::L1HandlerTransaction;
+use tower::ServiceExt;
+
+use crate::constants::LOG_MESSAGE_TO_L2_EVENT_IDENTIFIER;
+use crate::fake_base_layer::FakeBaseLayerContract;
+use crate::fake_base_layer_admin::{
+    admin_router,
+    MineBlockRequest,
+    ReorgRequest,
+    BLOCKS,
+    EVENTS,
+    LATEST_BLOCK,
+    REORG,
+};
+use crate::{BaseLayerContract, L1BlockReference, L1Event};
+
+async fn post(app: &Router, uri: &str, body: &impl Serialize) -> Response {
+    let request = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(Body::from(serde_json::to_vec(body).unwrap()))
+        .unwrap();
+    app.clone().oneshot(request).await.unwrap()
+}
+
+async fn response_block(response: Response) -> L1BlockReference {
+    assert_eq!(response.status(), StatusCode::OK);
+    serde_json::from_slice(&to_bytes(response.into_body()).await.unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn admin_api_scripts_the_chain() {
+    // Setup.
+    let base_layer = FakeBaseLayerContract::default();
+    let app = admin_router(base_layer.clone());
+    let event = L1Event::LogMessageToL2 {
+        tx: L1HandlerTransaction::default(),
+        fee: Fee(1),
+        l1_tx_hash: None,
+        timestamp: BlockTimestamp(7),
+    };
+
+    // Test.
+    let response = post(&app, EVENTS, &event).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let mined_block = response_block(
+        post(&app, BLOCKS, &MineBlockRequest { timestamp: Some(BlockTimestamp(7)) }).await,
+    )
+    .await;
+
+    let request = Request::builder().uri(LATEST_BLOCK).body(Body::empty()).unwrap();
+    let latest_block = response_block(app.clone().oneshot(request).await.unwrap()).await;
+    assert_eq!(latest_block, mined_block);
+    assert_eq!(
+        base_layer
+            .events(mined_block.number..=mined_block.number, &[LOG_MESSAGE_TO_L2_EVENT_IDENTIFIER])
+            .await
+            .unwrap(),
+        [event]
+    );
+
+    let response = post(&app, REORG, &ReorgRequest { depth: 2 }).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let common_ancestor = response_block(post(&app, REORG, &ReorgRequest { depth: 1 }).await).await;
+    assert_eq!(common_ancestor.number, 0);
+}
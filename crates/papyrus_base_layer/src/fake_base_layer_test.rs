@@ -0,0 +1,115 @@
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use starknet_api::block::{BlockHash, BlockHashAndNumber, BlockNumber, BlockTimestamp};
+use starknet_api::core::Nonce;
+use starknet_api::transaction::fields::Fee;
+use starknet_api::transaction::L1HandlerTransaction;
+use starknet_api::{calldata, contract_address, felt};
+
+use crate::constants::{
+    CONSUMED_MESSAGE_TO_L2_EVENT_IDENTIFIER,
+    LOG_MESSAGE_TO_L2_EVENT_IDENTIFIER,
+    MESSAGE_TO_L2_CANCELLATION_STARTED_EVENT_IDENTIFIER,
+};
+use crate::fake_base_layer::{FakeBaseLayerContract, FakeBaseLayerError};
+use crate::{BaseLayerContract, L1BlockReference, L1Event};
+
+fn l1_handler_tx(nonce: u64) -> L1HandlerTransaction {
+    L1HandlerTransaction {
+        nonce: Nonce(nonce.into()),
+        contract_address: contract_address!("0x12"),
+        calldata: calldata![felt!("0x1")],
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn events_are_served_with_their_block_timestamp() {
+    // Setup.
+    let base_layer = FakeBaseLayerContract::default();
+    let (tx, fee) = (l1_handler_tx(0), Fee(1));
+    base_layer.send_message_to_l2(tx.clone(), fee);
+    base_layer.start_message_to_l2_cancellation(tx.clone());
+    base_layer.consume_message_to_l2(l1_handler_tx(1));
+
+    // Test.
+    let block_timestamp = BlockTimestamp(10);
+    let mined_block = base_layer.mine_block(Some(block_timestamp));
+
+    assert_eq!(mined_block, base_layer.latest_block());
+    assert_eq!(base_layer.l1_block_at(1).await.unwrap(), Some(mined_block));
+    let events = base_layer
+        .events(
+            0..=1,
+            &[
+                LOG_MESSAGE_TO_L2_EVENT_IDENTIFIER,
+                MESSAGE_TO_L2_CANCELLATION_STARTED_EVENT_IDENTIFIER,
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        events,
+        [
+            L1Event::LogMessageToL2 {
+                tx: tx.clone(),
+                fee,
+                l1_tx_hash: None,
+                timestamp: block_timestamp
+            },
+            L1Event::MessageToL2CancellationStarted {
+                cancelled_tx: tx,
+                cancellation_request_timestamp: block_timestamp,
+            },
+        ]
+    );
+    let events =
+        base_layer.events(0..=1, &[CONSUMED_MESSAGE_TO_L2_EVENT_IDENTIFIER]).await.unwrap();
+    assert_eq!(events, [L1Event::ConsumedMessageToL2(l1_handler_tx(1))]);
+}
+
+#[tokio::test]
+async fn state_updates_are_proved_from_their_block_onwards() {
+    // Setup.
+    let base_layer = FakeBaseLayerContract::default();
+    let proved_block = BlockHashAndNumber { number: BlockNumber(5), hash: BlockHash(felt!("0x5")) };
+
+    // Test.
+    base_layer.update_state(proved_block);
+    base_layer.mine_block(None);
+    base_layer.mine_block(None);
+
+    assert_eq!(base_layer.get_proved_block_at(0).await, Err(FakeBaseLayerError::NoProvedBlock(0)));
+    assert_eq!(base_layer.get_proved_block_at(1).await, Ok(proved_block));
+    assert_eq!(base_layer.get_proved_block_at(2).await, Ok(proved_block));
+    assert_eq!(base_layer.get_proved_block_at(3).await, Err(FakeBaseLayerError::UnknownL1Block(3)));
+    assert_eq!(base_layer.latest_proved_block(1).await, Ok(Some(proved_block)));
+    assert_eq!(base_layer.latest_proved_block(2).await, Ok(None));
+    assert_eq!(base_layer.latest_proved_block(3).await, Ok(None));
+}
+
+#[tokio::test]
+async fn reorg_replaces_the_latest_blocks() {
+    // Setup.
+    let base_layer = FakeBaseLayerContract::default();
+    base_layer.mine_block(None);
+    base_layer.send_message_to_l2(l1_handler_tx(0), Fee(1));
+    let orphaned_block = base_layer.mine_block(None);
+
+    // Test.
+    let common_ancestor = base_layer.reorg(1).unwrap();
+
+    assert_eq!(common_ancestor.number, 1);
+    assert_eq!(base_layer.l1_block_at(2).await.unwrap(), None);
+    let new_block = base_layer.mine_block(None);
+    assert_eq!(new_block.number, orphaned_block.number);
+    assert_ne!(new_block.hash, orphaned_block.hash);
+    // The orphaned block's events are gone.
+    assert_eq!(base_layer.events(0..=2, &[LOG_MESSAGE_TO_L2_EVENT_IDENTIFIER]).await.unwrap(), []);
+
+    assert_matches!(
+        base_layer.reorg(3),
+        Err(FakeBaseLayerError::ReorgTooDeep { depth: 3, latest_l1_block: 2 })
+    );
+    assert_eq!(base_layer.latest_block(), L1BlockReference { number: 2, hash: new_block.hash });
+}
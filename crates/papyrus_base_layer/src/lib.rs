@@ -16,6 +16,10 @@ use url::Url;
 
 pub mod constants;
 pub mod ethereum_base_layer_contract;
+#[cfg(any(feature = "testing", test))]
+pub mod fake_base_layer;
+#[cfg(any(feature = "testing", test))]
+pub mod fake_base_layer_admin;
 pub mod monitored_base_layer;
 
 pub(crate) mod eth_events;
@@ -82,7 +86,7 @@ pub trait BaseLayerContract {
 }
 
 /// Reference to an L1 block, extend as needed.
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub struct L1BlockReference {
     pub number: L1BlockNumber,
     pub hash: L1BlockHash,
@@ -97,6 +101,9 @@ pub struct L1BlockHeader {
     pub timestamp: BlockTimestamp,
     pub base_fee_per_gas: u128,
     pub blob_fee: u128,
+    /// The blob fee of the next block, per EIP-4844, as determined by the excess blob gas and the
+    /// blob gas used in this block.
+    pub next_blob_fee: u128,
 }
 
 /// Wraps Starknet L1 events with Starknet API types.
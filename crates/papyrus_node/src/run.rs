@@ -34,6 +34,7 @@ use papyrus_common::metrics::COLLECT_PROFILING_METRICS;
 use papyrus_common::pending_classes::PendingClasses;
 use papyrus_monitoring_gateway::MonitoringServer;
 use starknet_api::block::{BlockHash, BlockHashAndNumber};
+use starknet_api::core::ChainId;
 use starknet_api::felt;
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
@@ -185,6 +186,7 @@ async fn run_sync(
     pending_data: Arc<RwLock<PendingData>>,
     pending_classes: Arc<RwLock<PendingClasses>>,
     storage: (StorageReader, StorageWriter),
+    chain_id: ChainId,
 ) -> anyhow::Result<()> {
     let (sync_config, central_config, base_layer_config) = configs;
     let (storage_reader, storage_writer) = storage;
@@ -206,6 +208,7 @@ async fn run_sync(
         storage_reader.clone(),
         storage_writer,
         class_manager_client,
+        chain_id,
     );
     Ok(sync.run().await?)
 }
@@ -235,24 +238,41 @@ async fn spawn_sync_client(
                 pending_data,
                 pending_classes,
                 storage,
+                config.storage.db_config.chain_id.clone(),
             ))
         }
         (None, Some(p2p_sync_client_config)) => {
             let network_manager = maybe_network_manager
                 .expect("If p2p sync is enabled, network needs to be enabled too");
-            let header_client_sender = network_manager
-                .register_sqmr_protocol_client(Protocol::SignedBlockHeader.into(), BUFFER_SIZE);
+            let header_client_sender = network_manager.register_sqmr_protocol_client_with_versions(
+                Protocol::SignedBlockHeader.client_versions(),
+                BUFFER_SIZE,
+            );
             let state_diff_client_sender = network_manager
-                .register_sqmr_protocol_client(Protocol::StateDiff.into(), BUFFER_SIZE);
+                .register_sqmr_protocol_client_with_versions(
+                    Protocol::StateDiff.client_versions(),
+                    BUFFER_SIZE,
+                );
             let transaction_client_sender = network_manager
-                .register_sqmr_protocol_client(Protocol::Transaction.into(), BUFFER_SIZE);
-            let class_client_sender =
-                network_manager.register_sqmr_protocol_client(Protocol::Class.into(), BUFFER_SIZE);
+                .register_sqmr_protocol_client_with_versions(
+                    Protocol::Transaction.client_versions(),
+                    BUFFER_SIZE,
+                );
+            let class_client_sender = network_manager.register_sqmr_protocol_client_with_versions(
+                Protocol::Class.client_versions(),
+                BUFFER_SIZE,
+            );
+            let receipt_client_sender = network_manager
+                .register_sqmr_protocol_client_with_versions(
+                    Protocol::Receipt.client_versions(),
+                    BUFFER_SIZE,
+                );
             let p2p_sync_client_channels = P2pSyncClientChannels::new(
                 header_client_sender,
                 state_diff_client_sender,
                 transaction_client_sender,
                 class_client_sender,
+                receipt_client_sender,
             );
             let p2p_sync = P2pSyncClient::new(
                 p2p_sync_client_config,
@@ -277,16 +297,30 @@ fn spawn_p2p_sync_server(
         return tokio::spawn(future::pending());
     };
 
-    let header_server_receiver = network_manager
-        .register_sqmr_protocol_server(Protocol::SignedBlockHeader.into(), BUFFER_SIZE);
-    let state_diff_server_receiver =
-        network_manager.register_sqmr_protocol_server(Protocol::StateDiff.into(), BUFFER_SIZE);
-    let transaction_server_receiver =
-        network_manager.register_sqmr_protocol_server(Protocol::Transaction.into(), BUFFER_SIZE);
-    let class_server_receiver =
-        network_manager.register_sqmr_protocol_server(Protocol::Class.into(), BUFFER_SIZE);
-    let event_server_receiver =
-        network_manager.register_sqmr_protocol_server(Protocol::Event.into(), BUFFER_SIZE);
+    let header_server_receiver = network_manager.register_sqmr_protocol_server_with_versions(
+        Protocol::SignedBlockHeader.server_versions(),
+        BUFFER_SIZE,
+    );
+    let state_diff_server_receiver = network_manager.register_sqmr_protocol_server_with_versions(
+        Protocol::StateDiff.server_versions(),
+        BUFFER_SIZE,
+    );
+    let transaction_server_receiver = network_manager.register_sqmr_protocol_server_with_versions(
+        Protocol::Transaction.server_versions(),
+        BUFFER_SIZE,
+    );
+    let class_server_receiver = network_manager.register_sqmr_protocol_server_with_versions(
+        Protocol::Class.server_versions(),
+        BUFFER_SIZE,
+    );
+    let event_server_receiver = network_manager.register_sqmr_protocol_server_with_versions(
+        Protocol::Event.server_versions(),
+        BUFFER_SIZE,
+    );
+    let receipt_server_receiver = network_manager.register_sqmr_protocol_server_with_versions(
+        Protocol::Receipt.server_versions(),
+        BUFFER_SIZE,
+    );
 
     let p2p_sync_server_channels = P2pSyncServerChannels::new(
         header_server_receiver,
@@ -294,6 +328,7 @@ fn spawn_p2p_sync_server(
         transaction_server_receiver,
         class_server_receiver,
         event_server_receiver,
+        receipt_server_receiver,
     );
 
     let p2p_sync_server =
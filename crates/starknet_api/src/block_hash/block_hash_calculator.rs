@@ -4,13 +4,22 @@ use serde::{Deserialize, Serialize};
 use starknet_types_core::felt::Felt;
 use starknet_types_core::hash::Poseidon;
 
-use super::event_commitment::{calculate_event_commitment, EventLeafElement};
+use super::event_commitment::{
+    calculate_event_commitment,
+    calculate_legacy_event_commitment,
+    EventLeafElement,
+};
 use super::receipt_commitment::{calculate_receipt_commitment, ReceiptElement};
 use super::state_diff_hash::calculate_state_diff_hash;
-use super::transaction_commitment::{calculate_transaction_commitment, TransactionLeafElement};
+use super::transaction_commitment::{
+    calculate_legacy_transaction_commitment,
+    calculate_transaction_commitment,
+    TransactionLeafElement,
+};
 use crate::block::{BlockHash, BlockHeaderWithoutHash, GasPricePerToken, StarknetVersion};
 use crate::core::{
     ascii_as_felt,
+    ChainId,
     EventCommitment,
     ReceiptCommitment,
     StateDiffCommitment,
@@ -148,6 +157,16 @@ pub fn calculate_block_hash(
     ))
 }
 
+/// Commitments of the transactions data of a block, i.e., all the commitments of the block except
+/// for the state diff commitment.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TransactionsDataCommitments {
+    pub transaction_commitment: TransactionCommitment,
+    pub event_commitment: EventCommitment,
+    pub receipt_commitment: ReceiptCommitment,
+    pub n_events: usize,
+}
+
 /// Calculates the commitments of the transactions data for the block hash.
 pub fn calculate_block_commitments(
     transactions_data: &[TransactionHashingData],
@@ -155,6 +174,29 @@ pub fn calculate_block_commitments(
     l1_da_mode: L1DataAvailabilityMode,
     starknet_version: &StarknetVersion,
 ) -> BlockHeaderCommitments {
+    let TransactionsDataCommitments {
+        transaction_commitment,
+        event_commitment,
+        receipt_commitment,
+        n_events,
+    } = calculate_transactions_data_commitments(transactions_data, starknet_version);
+    let state_diff_commitment = calculate_state_diff_hash(state_diff);
+    let concatenated_counts =
+        concat_counts(transactions_data.len(), n_events, state_diff.len(), l1_da_mode);
+    BlockHeaderCommitments {
+        transaction_commitment,
+        event_commitment,
+        receipt_commitment,
+        state_diff_commitment,
+        concatenated_counts,
+    }
+}
+
+/// Calculates the transaction, event and receipt commitments of a block.
+pub fn calculate_transactions_data_commitments(
+    transactions_data: &[TransactionHashingData],
+    starknet_version: &StarknetVersion,
+) -> TransactionsDataCommitments {
     let transaction_leaf_elements: Vec<TransactionLeafElement> = transactions_data
         .iter()
         .map(|tx_leaf| {
@@ -185,27 +227,109 @@ pub fn calculate_block_commitments(
     let receipt_elements: Vec<ReceiptElement> =
         transactions_data.iter().map(ReceiptElement::from).collect();
     let receipt_commitment = calculate_receipt_commitment::<Poseidon>(&receipt_elements);
-    let state_diff_commitment = calculate_state_diff_hash(state_diff);
-    let concatenated_counts = concat_counts(
-        transactions_data.len(),
-        event_leaf_elements.len(),
-        state_diff.len(),
-        l1_da_mode,
-    );
-    BlockHeaderCommitments {
+    TransactionsDataCommitments {
         transaction_commitment,
         event_commitment,
         receipt_commitment,
-        state_diff_commitment,
-        concatenated_counts,
+        n_events: event_leaf_elements.len(),
     }
 }
 
+/// Commitments of a block from before Starknet version 0.13.2, whose hash doesn't commit to the
+/// receipts and the state diff of the block.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LegacyBlockHeaderCommitments {
+    pub transaction_commitment: TransactionCommitment,
+    pub event_commitment: EventCommitment,
+    pub n_transactions: usize,
+    pub n_events: usize,
+}
+
+/// Calculates the commitments of a block from before Starknet version 0.13.2. The transaction
+/// signatures should be the ones the block hash commits to, i.e., before Starknet version 0.11.1
+/// only the signatures of invoke transactions.
+pub fn calculate_legacy_block_commitments(
+    transactions_data: &[TransactionHashingData],
+) -> LegacyBlockHeaderCommitments {
+    let transaction_leaf_elements: Vec<TransactionLeafElement> =
+        transactions_data.iter().map(TransactionLeafElement::from).collect();
+    let event_leaf_elements: Vec<EventLeafElement> = transactions_data
+        .iter()
+        .flat_map(|transaction_data| {
+            transaction_data.transaction_output.events.iter().map(|event| EventLeafElement {
+                event: event.clone(),
+                transaction_hash: transaction_data.transaction_hash,
+            })
+        })
+        .collect();
+    LegacyBlockHeaderCommitments {
+        transaction_commitment: calculate_legacy_transaction_commitment(&transaction_leaf_elements),
+        event_commitment: calculate_legacy_event_commitment(&event_leaf_elements),
+        n_transactions: transaction_leaf_elements.len(),
+        n_events: event_leaf_elements.len(),
+    }
+}
+
+/// Calculates the hash of a block from Starknet version 0.7.0 and before Starknet version 0.13.2:
+/// Pedersen (
+///     block_number, global_state_root, sequencer_address, block_timestamp, transaction_count,
+///     transaction_commitment, event_count, event_commitment, 0, 0, parent_block_hash
+/// ).
+pub fn calculate_legacy_block_hash(
+    header: &BlockHeaderWithoutHash,
+    block_commitments: &LegacyBlockHeaderCommitments,
+) -> BlockHash {
+    BlockHash(
+        HashChain::new()
+            .chain(&header.block_number.0.into())
+            .chain(&header.state_root.0)
+            .chain(&header.sequencer.0)
+            .chain(&header.timestamp.0.into())
+            .chain(&block_commitments.n_transactions.into())
+            .chain(&block_commitments.transaction_commitment.0)
+            .chain(&block_commitments.n_events.into())
+            .chain(&block_commitments.event_commitment.0)
+            .chain(&Felt::ZERO)
+            .chain(&Felt::ZERO)
+            .chain(&header.parent_hash.0)
+            .get_pedersen_hash(),
+    )
+}
+
+/// Calculates the hash of a block from before Starknet version 0.7.0, which commits to the chain
+/// instead of the sequencer, the timestamp and the events of the block:
+/// Pedersen (
+///     block_number, global_state_root, 0, 0, transaction_count, transaction_commitment, 0, 0, 0,
+///     0, chain_id, parent_block_hash
+/// ).
+pub fn calculate_pre_v0_7_block_hash(
+    header: &BlockHeaderWithoutHash,
+    block_commitments: &LegacyBlockHeaderCommitments,
+    chain_id: &ChainId,
+) -> StarknetApiResult<BlockHash> {
+    Ok(BlockHash(
+        HashChain::new()
+            .chain(&header.block_number.0.into())
+            .chain(&header.state_root.0)
+            .chain(&Felt::ZERO)
+            .chain(&Felt::ZERO)
+            .chain(&block_commitments.n_transactions.into())
+            .chain(&block_commitments.transaction_commitment.0)
+            .chain(&Felt::ZERO)
+            .chain(&Felt::ZERO)
+            .chain(&Felt::ZERO)
+            .chain(&Felt::ZERO)
+            .chain(&ascii_as_felt(&chain_id.to_string())?)
+            .chain(&header.parent_hash.0)
+            .get_pedersen_hash(),
+    ))
+}
+
 // A single felt: [
 //     transaction_count (64 bits) | event_count (64 bits) | state_diff_length (64 bits)
 //     | L1 data availability mode: 0 for calldata, 1 for blob (1 bit) | 0 ...
 // ].
-fn concat_counts(
+pub fn concat_counts(
     transaction_count: usize,
     event_count: usize,
     state_diff_length: usize,
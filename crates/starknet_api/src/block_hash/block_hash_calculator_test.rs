@@ -14,12 +14,17 @@ use crate::block::{
 use crate::block_hash::block_hash_calculator::{
     calculate_block_commitments,
     calculate_block_hash,
+    calculate_legacy_block_commitments,
+    calculate_legacy_block_hash,
+    calculate_pre_v0_7_block_hash,
     BlockHashVersion,
     BlockHeaderCommitments,
     TransactionHashingData,
+    TransactionOutputForHash,
 };
 use crate::block_hash::test_utils::{get_state_diff, get_transaction_output};
 use crate::core::{
+    ChainId,
     ContractAddress,
     EventCommitment,
     GlobalRoot,
@@ -33,7 +38,8 @@ use crate::data_availability::L1DataAvailabilityMode;
 use crate::execution_resources::GasAmount;
 use crate::hash::PoseidonHash;
 use crate::transaction::fields::TransactionSignature;
-use crate::{felt, tx_hash};
+use crate::transaction::{Event, EventContent, EventData, EventKey, TransactionHash};
+use crate::{contract_address, felt, tx_hash};
 
 /// Macro to test if changing any field in the header or commitments
 /// results a change in the block hash.
@@ -135,6 +141,245 @@ fn test_block_hash_regression(
     );
 }
 
+fn event(from_address: &str, keys: &[&str], data: &[&str]) -> Event {
+    Event {
+        from_address: contract_address!(from_address),
+        content: EventContent {
+            keys: keys.iter().map(|key| EventKey(Felt::from_hex_unchecked(key))).collect(),
+            data: EventData(data.iter().map(|felt| Felt::from_hex_unchecked(felt)).collect()),
+        },
+    }
+}
+
+fn transaction_data(
+    transaction_hash: &str,
+    signature: &[&str],
+    events: Vec<Event>,
+) -> TransactionHashingData {
+    TransactionHashingData {
+        transaction_signature: TransactionSignature(
+            signature.iter().map(|felt| Felt::from_hex_unchecked(felt)).collect::<Vec<_>>().into(),
+        ),
+        transaction_output: TransactionOutputForHash { events, ..get_transaction_output() },
+        transaction_hash: TransactionHash(Felt::from_hex_unchecked(transaction_hash)),
+    }
+}
+
+// Block 329525 of Starknet mainnet, from Starknet version 0.13.1.
+#[test]
+fn test_legacy_block_hash_regression() {
+    let block_header = BlockHeaderWithoutHash {
+        block_number: BlockNumber(329525),
+        state_root: GlobalRoot(felt!(
+            "0x5b8950956ada2d4973a30dcfab366e6595afc8b0a5552517cd14fb1bf2eb75"
+        )),
+        sequencer: SequencerContractAddress(contract_address!(
+            "0x1176a1bd84444c89232ec27754698e5d2e7e1a7f1539f12027f28b23ec9f3d8"
+        )),
+        timestamp: BlockTimestamp(1707734016),
+        starknet_version: StarknetVersion::V0_13_1,
+        parent_hash: BlockHash(felt!(
+            "0x73786b02e70ee32bf4855ce687651058456093a146905de213dab989da301dc"
+        )),
+        ..Default::default()
+    };
+    let transactions_data = vec![
+        transaction_data(
+            "0x9cd83df42ac151171cf2be447bb07714c47ef448f90fb2a29d8003ffc05357",
+            &[
+                "0x31dd3d821097b19d67d581d07b6ab44a0e7ec399480acdb73094171222e2db4",
+                "0x43212ff00efc96d7426cb13f7796213b160b8b40d3c63576ef59459d8e3acad",
+            ],
+            vec![event(
+                "0x4e18c9f8a657466bfe60e23e590fc1e60cb465bf6a8ca8587b6a35f3b53db20",
+                &["0x15bd0500dc9d7e69ab9577f73a8d753e8761bed10f25ba0f124254dc4edb8b4"],
+                &[
+                    "0x78e98d70fb53cd079bcebdb109c45d5919be8c4bcca88f938cb6e352bf6c30e",
+                    "0x3",
+                    "0x38f99ec96ae654cb3a88dbb4443d3be650eb66e61120e6dfecd85cc89b586bc",
+                    "0x1dd8e057ca334c6592935fbf7aaccb036cf668a73d0b60775dae9dfcdc28f94",
+                    "0x79005d3e9d691dea1ac2da70a37a6fb19b97cfd4845816e35d59cb9f1bb94c1",
+                ],
+            )],
+        ),
+        transaction_data(
+            "0x16dc216ec3dd1dba1f8ec2db9da5cc7e349ef91c94f06e76204dedc3470d4ac",
+            &[
+                "0x280a5fe524066cbb48c17d91de6aa15327d267a766309b74186af823252e2d3",
+                "0x4fbbb19cc01c575d7a55ee102ac7a6e90eb057dffdc6da7c9fee5a2e9adac53",
+            ],
+            vec![
+                event(
+                    "0x4718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d",
+                    &[
+                        "0x99cd8bde557814842a3121e8ddfd433a539b8c9f14bf31ebf108d12e6196e9",
+                        "0x6193c7376d223ae00c9f24f00905da596b23f1307ce5ab01c4e6c5a2eb2679d",
+                        "0x1176a1bd84444c89232ec27754698e5d2e7e1a7f1539f12027f28b23ec9f3d8",
+                    ],
+                    &["0x12d57417dc0", "0x0"],
+                ),
+                event(
+                    "0x4718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d",
+                    &["0xa9fa878c35cd3d0191318f89033ca3e5501a3d90e21e3cc9256bdd5cd17fdd"],
+                    &[
+                        "0xca46d96b37266650e0a8b79938d9300037337cad82ea4f45a921ad68b6a5f9",
+                        "0x25e0bf0419c72c2f",
+                        "0x0",
+                        "0x25e0c0317108a9ef",
+                        "0x0",
+                    ],
+                ),
+            ],
+        ),
+        transaction_data(
+            "0x368c812e60b502eb422b6cd7e79c989e4418c44e96ef52885d010c805bb963c",
+            &[
+                "0x6084e029cee15205d11734a210b1c84afc4eb80b700a56571a15ec33f70708e",
+                "0x375c614be78c24b52f4db4634e867e6eeea6faadc83e066e9f91c8d58959ba9",
+            ],
+            vec![],
+        ),
+        transaction_data(
+            "0xd027a2ecb11da22b82f3c214048fef16bbfd842c668e35e2ad95c0e25510f2",
+            &[
+                "0x4753344a9de73595065d040a445a03838d5d53ccde410fcca8eac95a68f3251",
+                "0x1ba0f7ef78e977deb4b6c7fb8974b396e0740fb47d3782b6e76538e49453ff9",
+            ],
+            vec![
+                event(
+                    "0x4718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d",
+                    &[
+                        "0x99cd8bde557814842a3121e8ddfd433a539b8c9f14bf31ebf108d12e6196e9",
+                        "0x14c5c28581c68f64c9a3d86b919094a5209fe0ccb454f776b3be2c3968cd91d",
+                        "0x1176a1bd84444c89232ec27754698e5d2e7e1a7f1539f12027f28b23ec9f3d8",
+                    ],
+                    &["0x12d222c85e4", "0x0"],
+                ),
+                event(
+                    "0x4718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d",
+                    &["0xa9fa878c35cd3d0191318f89033ca3e5501a3d90e21e3cc9256bdd5cd17fdd"],
+                    &[
+                        "0xca46d96b37266650e0a8b79938d9300037337cad82ea4f45a921ad68b6a5f9",
+                        "0x25e0c0317108a9ef",
+                        "0x0",
+                        "0x25e0c15e93352fd3",
+                        "0x0",
+                    ],
+                ),
+            ],
+        ),
+        transaction_data(
+            "0x219e7355c8ece5480fdfda7b6a3019f49c27975d324e8e0728c2d78e95b7ae5",
+            &[
+                "0x275179533eb45d70ae5a4bab1bcf267860522dc3d24a5c4e82283d1799b1d22",
+                "0x53d61d03ef8fa2ff46bf6d397ffe31eb05c530f74b59950b69fcbee2a668837",
+            ],
+            vec![
+                event(
+                    "0x232438a37dc1e45f6cf278b308db7d1868016a5a6a2f6c4d3da746b4d13d891",
+                    &[
+                        "0x2b9b33cc1c7a113c74d307116418cb69fa41031a742d917d36f2fdbb83ab17d",
+                        "0x15c9b83fb27785c1fbf812db24cc25e04703509a437143ad5b6e73129575ba2",
+                        "0x4824f515c9f5414b21dcd39adbae015c51fb007b79eff92851df5c05072b6fe",
+                        "0x64967a429861dc1ee34e4031010f86184e938ad2593c9db8e66a1a579566c5d",
+                        "0x10673e66a28e64889629decbf97c07e0a5d345778c54307b47193a0c02b75c2",
+                    ],
+                    &[
+                        "0x140626948f321601f052d2bb3372538c5e961f46e00f61330e04619e2a9f294",
+                        "0x32426568e134fe0b8389c921c7a050076b78a64872288a192f8b11e1f35a3c6",
+                        "0x446ce68e96931fd03c80d225404555d51b077fef740c5590600f68ae4762f8",
+                        "0x6fa2b3ed33c07304a497e5e3a19056d803893112df0106af36baecf1dcb268f",
+                        "0x12fb2c1e8db8530bb0d50cb0f8f746c17656dab9d07a5e6a12680971c2e4355",
+                        "0x47e42785a3e11ff1408a884f294bd9588c2cda0b2621cf3e63f3a560e2fc563",
+                        "0x68b14002234352e70f4f3f29a72c407162c7548b30343b75932b0dbec5840c5",
+                        "0x4bd91309fbc94f0393c66f51f0c27cce48bff0784f44c77257d904bc621e931",
+                    ],
+                ),
+                event(
+                    "0x4718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d",
+                    &[
+                        "0x99cd8bde557814842a3121e8ddfd433a539b8c9f14bf31ebf108d12e6196e9",
+                        "0x14c5c28581c68f64c9a3d86b919094a5209fe0ccb454f776b3be2c3968cd91d",
+                        "0x1176a1bd84444c89232ec27754698e5d2e7e1a7f1539f12027f28b23ec9f3d8",
+                    ],
+                    &["0x383ab0c2434", "0x0"],
+                ),
+                event(
+                    "0x4718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d",
+                    &["0xa9fa878c35cd3d0191318f89033ca3e5501a3d90e21e3cc9256bdd5cd17fdd"],
+                    &[
+                        "0xca46d96b37266650e0a8b79938d9300037337cad82ea4f45a921ad68b6a5f9",
+                        "0x25e0c15e93352fd3",
+                        "0x0",
+                        "0x25e0c4e23e415407",
+                        "0x0",
+                    ],
+                ),
+            ],
+        ),
+    ];
+
+    let block_commitments = calculate_legacy_block_commitments(&transactions_data);
+    assert_eq!(
+        block_commitments.transaction_commitment,
+        TransactionCommitment(felt!(
+            "0x22810377061682d79e62285605b99c12fc7aeecf6ce5c713909d88e201909eb"
+        ))
+    );
+    assert_eq!(
+        block_commitments.event_commitment,
+        EventCommitment(felt!("0x4a4f2c690f375f6ecccc37843753dbddc9bb4383905c7226cd1a4556d47a811"))
+    );
+    assert_eq!(
+        calculate_legacy_block_hash(&block_header, &block_commitments),
+        BlockHash(felt!("0x4fb00421167b164f8392eb9b7ffee5d4fd585cf8e21035b9f64b29526aecb3e"))
+    );
+}
+
+// The first block of the deprecated Starknet Goerli testnet.
+#[test]
+fn test_pre_v0_7_block_hash_regression() {
+    let block_header = BlockHeaderWithoutHash {
+        state_root: GlobalRoot(felt!(
+            "0x1f386a54db7796872829c9168cdc567980daad382daa4df3b71641a2551e833"
+        )),
+        ..Default::default()
+    };
+    let transactions_data = vec![
+        transaction_data(
+            "0x3fa1bff0c86f34b2eb32c26d12208b6bdb4a5f6a434ac1d4f0e2d1db71bd711",
+            &[],
+            vec![],
+        ),
+        transaction_data(
+            "0x154c02cc3165cceadaa32e7238a67061b3a1eac414138c4ebe1408f37fd93eb",
+            &[],
+            vec![],
+        ),
+        transaction_data(
+            "0x7893675c16da857b7c4229cda449e08a4fe13b07ca817e79d1db02e8a046047",
+            &[],
+            vec![],
+        ),
+        transaction_data(
+            "0x4a277d67e3f42c4a343854081d1e2e9e425f1323255e4486d2badb37a1d8630",
+            &[],
+            vec![],
+        ),
+    ];
+
+    let block_commitments = calculate_legacy_block_commitments(&transactions_data);
+    assert_eq!(
+        calculate_pre_v0_7_block_hash(
+            &block_header,
+            &block_commitments,
+            &ChainId::Other("SN_GOERLI".to_string())
+        )
+        .unwrap(),
+        BlockHash(felt!("0x3ae41b0f023e53151b0c8ab8b9caafb7005d5f41c9ab260276d5bdc49726279"))
+    );
+}
+
 #[test]
 fn test_tx_commitment_with_an_empty_signature() {
     let transactions_data = vec![TransactionHashingData {
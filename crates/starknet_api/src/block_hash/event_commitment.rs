@@ -1,5 +1,5 @@
 use starknet_types_core::felt::Felt;
-use starknet_types_core::hash::StarkHash;
+use starknet_types_core::hash::{Pedersen, StarkHash};
 
 use crate::core::EventCommitment;
 use crate::crypto::patricia_hash::calculate_root;
@@ -40,3 +40,24 @@ fn calculate_event_hash(event_leaf_element: &EventLeafElement) -> Felt {
         .chain_size_and_elements(data)
         .get_poseidon_hash()
 }
+
+/// Returns the root of a Patricia tree where each leaf is an event hash, as calculated before
+/// Starknet version 0.13.2.
+pub fn calculate_legacy_event_commitment(
+    event_leaf_elements: &[EventLeafElement],
+) -> EventCommitment {
+    let event_leaves = event_leaf_elements.iter().map(calculate_legacy_event_hash).collect();
+    EventCommitment(calculate_root::<Pedersen>(event_leaves))
+}
+
+// Pedersen(from_address, h(keys), h(contents)), where h is the Pedersen hash of the elements
+// followed by their count, and so is the outer Pedersen.
+fn calculate_legacy_event_hash(event_leaf_element: &EventLeafElement) -> Felt {
+    let keys = event_leaf_element.event.content.keys.iter().map(|k| k.0).collect::<Vec<Felt>>();
+    let data = &event_leaf_element.event.content.data.0;
+    HashChain::new()
+        .chain(event_leaf_element.event.from_address.0.key())
+        .chain(&HashChain::new().chain_iter(keys.iter()).get_pedersen_hash())
+        .chain(&HashChain::new().chain_iter(data.iter()).get_pedersen_hash())
+        .get_pedersen_hash()
+}
@@ -1,5 +1,5 @@
 use starknet_types_core::felt::Felt;
-use starknet_types_core::hash::StarkHash as CoreStarkHash;
+use starknet_types_core::hash::{Pedersen, StarkHash as CoreStarkHash};
 
 use super::block_hash_calculator::TransactionHashingData;
 use crate::core::TransactionCommitment;
@@ -45,3 +45,21 @@ fn calculate_transaction_leaf(transaction_leaf_elements: &TransactionLeafElement
         .chain_iter(transaction_leaf_elements.transaction_signature.0.iter())
         .get_poseidon_hash()
 }
+
+/// Returns the root of a Patricia tree where each leaf is
+/// Pedersen(transaction_hash, h(transaction_signature)), as calculated before Starknet version
+/// 0.13.2. h is the Pedersen hash of the signature elements followed by their count.
+pub fn calculate_legacy_transaction_commitment(
+    transaction_leaf_elements: &[TransactionLeafElement],
+) -> TransactionCommitment {
+    let transaction_leaves =
+        transaction_leaf_elements.iter().map(calculate_legacy_transaction_leaf).collect();
+    TransactionCommitment(calculate_root::<Pedersen>(transaction_leaves))
+}
+
+fn calculate_legacy_transaction_leaf(transaction_leaf_elements: &TransactionLeafElement) -> Felt {
+    let signature_hash = HashChain::new()
+        .chain_iter(transaction_leaf_elements.transaction_signature.0.iter())
+        .get_pedersen_hash();
+    Pedersen::hash(&transaction_leaf_elements.transaction_hash.0, &signature_hash)
+}
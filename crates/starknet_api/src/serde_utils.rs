@@ -29,7 +29,14 @@ impl<'de, const N: usize, const PREFIXED: bool> Deserialize<'de> for BytesAsHex<
             type Value = BytesAsHex<N, PREFIXED>;
 
             fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                formatter.write_str("a byte array")
+                formatter.write_str("a byte array or a hex string")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                bytes_from_hex_str::<N, PREFIXED>(value).map_err(E::custom).map(BytesAsHex)
             }
 
             fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
@@ -39,18 +46,24 @@ impl<'de, const N: usize, const PREFIXED: bool> Deserialize<'de> for BytesAsHex<
                 let mut res = [0u8; N];
                 let mut i = 0;
                 while let Some(value) = seq.next_element()? {
+                    if i == N {
+                        return Err(serde::de::Error::invalid_length(i + 1, &self));
+                    }
                     res[i] = value;
                     i += 1;
                 }
+                if i < N {
+                    return Err(serde::de::Error::invalid_length(i, &self));
+                }
                 Ok(BytesAsHex(res))
             }
         }
 
+        // Buffered content, e.g., of internally tagged enums, is replayed as human readable
+        // regardless of the format it was serialized in, hence the visitor accepts both
+        // representations.
         if deserializer.is_human_readable() {
-            let s = String::deserialize(deserializer)?;
-            bytes_from_hex_str::<N, PREFIXED>(s.as_str())
-                .map_err(serde::de::Error::custom)
-                .map(BytesAsHex)
+            deserializer.deserialize_any(ByteArrayVisitor)
         } else {
             deserializer.deserialize_tuple(N, ByteArrayVisitor)
         }
@@ -1,5 +1,5 @@
 use assert_matches::assert_matches;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::deprecated_contract_class::{
     ConstructorType,
@@ -120,6 +120,32 @@ fn hex_as_bytes_serde_not_prefixed() {
     );
 }
 
+#[test]
+fn hex_as_bytes_deserializes_from_human_readable_byte_array() {
+    assert_eq!(
+        serde_json::from_str::<BytesAsHex<3, true>>("[1, 2, 3]").unwrap(),
+        BytesAsHex([1, 2, 3])
+    );
+    assert!(serde_json::from_str::<BytesAsHex<3, true>>("[1, 2]").is_err());
+    assert!(serde_json::from_str::<BytesAsHex<3, true>>("[1, 2, 3, 4]").is_err());
+}
+
+// Internally tagged enums buffer their content and replay it as human readable, also when
+// deserializing from a binary format.
+#[test]
+fn hex_as_bytes_serde_binary_format_in_tagged_enum() {
+    #[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+    #[serde(tag = "type")]
+    enum TaggedEnum {
+        Variant { hex_as_bytes: BytesAsHex<3, true> },
+    }
+
+    let tagged_enum = TaggedEnum::Variant { hex_as_bytes: BytesAsHex([1, 2, 3]) };
+    let mut bytes = Vec::new();
+    ciborium::into_writer(&tagged_enum, &mut bytes).unwrap();
+    assert_eq!(ciborium::from_reader::<TaggedEnum, _>(bytes.as_slice()).unwrap(), tagged_enum);
+}
+
 #[derive(Deserialize, PartialEq, Eq, Debug)]
 struct DummyContractClass {
     #[serde(default, deserialize_with = "deserialize_optional_contract_class_abi_entry_vector")]
@@ -291,7 +291,8 @@ impl From<CairoLangContractEntryPoint> for EntryPoint {
 )]
 pub struct FunctionIndex(pub usize);
 
-fn entry_points_hash(
+/// Returns the hash of the entry points of the given type, as used in the class hash computation.
+pub fn entry_points_hash(
     class: &SierraContractClass,
     entry_point_type: &EntryPointType,
 ) -> PoseidonHash {
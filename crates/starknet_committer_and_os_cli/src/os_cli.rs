@@ -1,3 +1,4 @@
 pub mod commands;
+pub mod os_output_decoder;
 pub mod run_os_cli;
 pub mod tests;
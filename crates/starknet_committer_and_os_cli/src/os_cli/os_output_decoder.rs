@@ -0,0 +1,184 @@
+use std::fmt::Write;
+
+use blockifier::state::cached_state::CommitmentStateDiff;
+use blockifier::state::state_diff_mismatch::{state_diff_mismatches, StateDiffMismatch};
+use serde::{Deserialize, Serialize};
+use starknet_api::state::ThinStateDiff;
+use starknet_os::io::os_output::{OsOutput, OsOutputError, OsStateDiff};
+use starknet_types_core::felt::Felt;
+use tracing::{info, warn};
+
+use crate::shared_utils::read::{load_input, write_to_file};
+
+#[cfg(test)]
+#[path = "os_output_decoder_test.rs"]
+pub mod os_output_decoder_test;
+
+#[derive(Deserialize, Debug)]
+/// Input to the OS output decoder.
+pub(crate) struct OsOutputDecoderInput {
+    /// The raw output of the OS or of the aggregator.
+    pub raw_output: Vec<Felt>,
+    /// The DA segment of the run. Required for decoding the state diff if KZG DA was used, as the
+    /// state diff is not part of the output in this case.
+    pub da_segment: Option<Vec<Felt>>,
+    /// A state diff to compare the decoded state diff to (e.g., the state diff stored by a node
+    /// for the same block).
+    pub expected_state_diff: Option<ThinStateDiff>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct OsOutputDecoderOutput {
+    pub os_output: OsOutput,
+    /// The mismatches between the decoded and the expected state diffs, if an expected state diff
+    /// was given.
+    pub state_diff_mismatches: Option<Vec<StateDiffMismatch>>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum OsOutputDecoderError {
+    #[error(transparent)]
+    OsOutput(#[from] OsOutputError),
+    #[error(
+        "The state diff is not part of the OS output (KZG DA was used); a DA segment is required \
+         in order to compare it to the expected state diff."
+    )]
+    MissingStateDiff,
+}
+
+/// Decodes a raw OS (or aggregator) output. If KZG DA was used, the state diff is decoded from the
+/// (compressed) DA segment, if given.
+pub fn decode_os_output(
+    raw_output: Vec<Felt>,
+    da_segment: Option<Vec<Felt>>,
+) -> Result<OsOutput, OsOutputError> {
+    let mut os_output = OsOutput::from_raw_output_iter(raw_output.into_iter())?;
+    if os_output.use_kzg_da {
+        match da_segment {
+            Some(da_segment) => {
+                os_output.state_diff = Some(OsStateDiff::from_iter(
+                    &mut da_segment.into_iter(),
+                    os_output.full_output,
+                )?);
+            }
+            None => warn!("KZG DA was used, but no DA segment was given; skipping the state diff."),
+        }
+    }
+    Ok(os_output)
+}
+
+/// Returns the entries that differ between the decoded state diff and the expected one.
+/// Entries of a full output whose value did not change are ignored, as are deprecated declared
+/// classes (which are not part of the OS output).
+pub fn os_state_diff_mismatches(
+    decoded_state_diff: &OsStateDiff,
+    expected_state_diff: &ThinStateDiff,
+) -> Vec<StateDiffMismatch> {
+    let expected_state_diff = CommitmentStateDiff {
+        address_to_class_hash: expected_state_diff.deployed_contracts.clone(),
+        address_to_nonce: expected_state_diff.nonces.clone(),
+        storage_updates: expected_state_diff.storage_diffs.clone(),
+        class_hash_to_compiled_class_hash: expected_state_diff.declared_classes.clone(),
+    };
+    state_diff_mismatches(
+        expected_state_diff,
+        CommitmentStateDiff::from(decoded_state_diff.changed_state_maps()),
+    )
+}
+
+/// Decodes the OS output of the given input and compares its state diff to the expected one, if
+/// given.
+pub(crate) fn decode_and_compare_os_output(
+    OsOutputDecoderInput { raw_output, da_segment, expected_state_diff }: OsOutputDecoderInput,
+) -> Result<OsOutputDecoderOutput, OsOutputDecoderError> {
+    let os_output = decode_os_output(raw_output, da_segment)?;
+    let state_diff_mismatches = expected_state_diff
+        .map(|expected_state_diff| {
+            let decoded_state_diff =
+                os_output.state_diff.as_ref().ok_or(OsOutputDecoderError::MissingStateDiff)?;
+            Ok::<_, OsOutputDecoderError>(os_state_diff_mismatches(
+                decoded_state_diff,
+                &expected_state_diff,
+            ))
+        })
+        .transpose()?;
+    Ok(OsOutputDecoderOutput { os_output, state_diff_mismatches })
+}
+
+fn optional_felt(value: Option<Felt>) -> String {
+    value.map(|value| format!("{value:#x}")).unwrap_or_else(|| "-".to_string())
+}
+
+/// Returns a human-readable description of the OS output.
+pub fn pretty_print(os_output: &OsOutput) -> String {
+    // Writing to a string never fails.
+    let mut output = String::new();
+    writeln!(output, "Blocks {} -> {}", os_output.prev_block_number, os_output.new_block_number)
+        .unwrap();
+    writeln!(output, "  State root: {:#x} -> {:#x}", os_output.initial_root, os_output.final_root)
+        .unwrap();
+    writeln!(
+        output,
+        "  Block hash: {:#x} -> {:#x}",
+        os_output.prev_block_hash, os_output.new_block_hash
+    )
+    .unwrap();
+    writeln!(output, "  OS program hash: {:#x}", os_output.os_program_hash).unwrap();
+    writeln!(output, "  Starknet OS config hash: {:#x}", os_output.starknet_os_config_hash)
+        .unwrap();
+    writeln!(output, "  KZG DA: {}, full output: {}", os_output.use_kzg_da, os_output.full_output)
+        .unwrap();
+
+    writeln!(output, "Messages to L1 ({}):", os_output.messages_to_l1.len()).unwrap();
+    for message in &os_output.messages_to_l1 {
+        writeln!(
+            output,
+            "  {:#x} -> {:?}, payload: {:?}",
+            message.from_address.0.key(),
+            message.to_address,
+            message.payload.0
+        )
+        .unwrap();
+    }
+    writeln!(output, "Messages to L2 ({}):", os_output.messages_to_l2.len()).unwrap();
+    for message in &os_output.messages_to_l2 {
+        writeln!(output, "  {message:?}").unwrap();
+    }
+
+    let Some(state_diff) = &os_output.state_diff else {
+        writeln!(output, "State diff: not available.").unwrap();
+        return output;
+    };
+    writeln!(output, "Contract changes ({}):", state_diff.contracts.len()).unwrap();
+    for contract in &state_diff.contracts {
+        writeln!(output, "  {contract:?}").unwrap();
+    }
+    writeln!(output, "Declared classes ({}):", state_diff.classes.len()).unwrap();
+    for (class_hash, (prev_compiled_class_hash, new_compiled_class_hash)) in &state_diff.classes {
+        writeln!(
+            output,
+            "  {:#x}: {} -> {:#x}",
+            class_hash.0,
+            optional_felt(
+                prev_compiled_class_hash.map(|compiled_class_hash| compiled_class_hash.0)
+            ),
+            new_compiled_class_hash.0
+        )
+        .unwrap();
+    }
+    output
+}
+
+pub(crate) fn parse_and_decode_os_output(input_path: String, output_path: String) {
+    let decoder_output = decode_and_compare_os_output(load_input(input_path))
+        .unwrap_or_else(|err| panic!("Failed to decode the OS output. Error: {err}"));
+    println!("{}", pretty_print(&decoder_output.os_output));
+    if let Some(state_diff_mismatches) = &decoder_output.state_diff_mismatches {
+        println!("State diff mismatches ({}):", state_diff_mismatches.len());
+        for StateDiffMismatch { kind, key, expected, actual } in state_diff_mismatches {
+            println!("  {kind:?} {key}: expected {expected:?}, decoded {actual:?}");
+        }
+    }
+    write_to_file(&output_path, &decoder_output);
+    info!("OS output decoded successfully.");
+}
@@ -0,0 +1,132 @@
+use assert_matches::assert_matches;
+use blockifier::state::state_diff_mismatch::StateDiffEntryKind;
+use indexmap::IndexMap;
+use pretty_assertions::assert_eq;
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ClassHash, CompiledClassHash, Nonce};
+use starknet_api::state::ThinStateDiff;
+use starknet_api::{contract_address, felt, storage_key};
+use starknet_types_core::felt::Felt;
+
+use crate::os_cli::os_output_decoder::{
+    decode_and_compare_os_output,
+    decode_os_output,
+    os_state_diff_mismatches,
+    pretty_print,
+    OsOutputDecoderError,
+    OsOutputDecoderInput,
+};
+
+/// A raw OS output of a single block with full output, a single contract change and a single
+/// declared class.
+fn full_raw_output() -> Vec<Felt> {
+    [
+        // Initial and final roots.
+        vec!["0x10", "0x11"],
+        // Previous and new block numbers.
+        vec!["0x5", "0x6"],
+        // Previous and new block hashes.
+        vec!["0x20", "0x21"],
+        // OS program hash and OS config hash.
+        vec!["0x0", "0x30"],
+        // use_kzg_da and full_output.
+        vec!["0x0", "0x1"],
+        // Messages to L1 and L2 segment sizes.
+        vec!["0x0", "0x0"],
+        // Number of changed contracts.
+        vec!["0x1"],
+        // Address, previous and new nonces, previous and new class hashes.
+        vec!["0x100", "0x1", "0x2", "0x200", "0x200"],
+        // Number of storage changes, then storage key, previous and new values.
+        vec!["0x1", "0x7", "0x8", "0x9"],
+        // Number of declared classes, then class hash, previous and new compiled class hashes.
+        vec!["0x1", "0x300", "0x0", "0x301"],
+    ]
+    .concat()
+    .into_iter()
+    .map(Felt::from_hex_unchecked)
+    .collect()
+}
+
+#[test]
+fn test_decode_full_os_output() {
+    let os_output = decode_os_output(full_raw_output(), None).unwrap();
+    assert_eq!(
+        (os_output.prev_block_number, os_output.new_block_number),
+        (BlockNumber(5), BlockNumber(6))
+    );
+    assert!(os_output.full_output && !os_output.use_kzg_da);
+    assert!(os_output.messages_to_l1.is_empty() && os_output.messages_to_l2.is_empty());
+
+    let state_diff = os_output.state_diff.as_ref().unwrap();
+    assert_eq!(state_diff.contracts.len(), 1);
+    assert_eq!(state_diff.contracts[0].new_nonce, Some(Nonce(felt!("0x2"))));
+    assert_eq!(
+        state_diff.classes,
+        vec![(
+            ClassHash(felt!("0x300")),
+            (Some(CompiledClassHash(felt!("0x0"))), CompiledClassHash(felt!("0x301")))
+        )]
+    );
+
+    let pretty_output = pretty_print(&os_output);
+    assert!(pretty_output.contains("Blocks 5 -> 6"));
+    assert!(pretty_output.contains("  0x300: 0x0 -> 0x301"));
+}
+
+#[test]
+fn test_os_state_diff_mismatches() {
+    let os_output = decode_os_output(full_raw_output(), None).unwrap();
+    let state_diff = os_output.state_diff.as_ref().unwrap();
+    let address = contract_address!("0x100");
+    let mut expected_state_diff = ThinStateDiff {
+        // The class hash of the contract did not change.
+        deployed_contracts: IndexMap::new(),
+        storage_diffs: IndexMap::from([(
+            address,
+            IndexMap::from([(storage_key!("0x7"), felt!("0x9"))]),
+        )]),
+        declared_classes: IndexMap::from([(
+            ClassHash(felt!("0x300")),
+            CompiledClassHash(felt!("0x301")),
+        )]),
+        deprecated_declared_classes: vec![ClassHash(felt!("0x400"))],
+        nonces: IndexMap::from([(address, Nonce(felt!("0x2")))]),
+    };
+    assert!(os_state_diff_mismatches(state_diff, &expected_state_diff).is_empty());
+
+    expected_state_diff.nonces.insert(address, Nonce(felt!("0x3")));
+    let mismatches = os_state_diff_mismatches(state_diff, &expected_state_diff);
+    assert_eq!(mismatches.len(), 1);
+    assert_eq!(mismatches[0].kind, StateDiffEntryKind::Nonce);
+    assert_eq!(mismatches[0].expected, Some(format!("{:?}", Nonce(felt!("0x3")))));
+    assert_eq!(mismatches[0].actual, Some(format!("{:?}", Nonce(felt!("0x2")))));
+}
+
+#[test]
+fn test_compare_without_state_diff() {
+    let raw_output = [
+        // Initial and final roots, block numbers and block hashes.
+        vec!["0x10", "0x11", "0x5", "0x6", "0x20", "0x21"],
+        // OS program hash and OS config hash.
+        vec!["0x0", "0x30"],
+        // use_kzg_da and full_output.
+        vec!["0x1", "0x0"],
+        // KZG z, number of blobs, then the commitment and evaluation of the blob.
+        vec!["0x40", "0x1", "0x41", "0x42", "0x43", "0x44"],
+        // Messages to L1 and L2 segment sizes.
+        vec!["0x0", "0x0"],
+    ]
+    .concat()
+    .into_iter()
+    .map(Felt::from_hex_unchecked)
+    .collect();
+
+    // Without a DA segment, the state diff of a KZG DA output cannot be compared.
+    let result = decode_and_compare_os_output(OsOutputDecoderInput {
+        raw_output,
+        da_segment: None,
+        expected_state_diff: Some(ThinStateDiff::default()),
+    });
+    assert_matches!(result, Err(OsOutputDecoderError::MissingStateDiff));
+}
@@ -20,6 +20,7 @@ use crate::os_cli::commands::{
     parse_and_run_aggregator,
     parse_and_run_os,
 };
+use crate::os_cli::os_output_decoder::parse_and_decode_os_output;
 use crate::os_cli::tests::python_tests::OsPythonTestRunner;
 use crate::shared_utils::types::{run_python_test, IoArgs, PythonTestArg};
 
@@ -58,6 +59,12 @@ enum Command {
         #[clap(long, short = 'o')]
         output_path: String,
     },
+    /// Decodes a raw OS (or aggregator) output, prints it, and writes it as JSON to the output
+    /// path. If an expected state diff is given in the input, it is compared to the decoded one.
+    DecodeOsOutput {
+        #[clap(flatten)]
+        io_args: IoArgs,
+    },
     PythonTest(PythonTestArg),
     RunOsStateless {
         #[clap(flatten)]
@@ -78,6 +85,9 @@ pub async fn run_os_cli(
         Command::DumpProgram { output_path, program } => dump_program(output_path, program),
         Command::DumpProgramHashes { output_path } => dump_program_hashes(output_path),
         Command::DumpSourceFiles { output_path } => dump_source_files(output_path),
+        Command::DecodeOsOutput { io_args: IoArgs { input_path, output_path } } => {
+            parse_and_decode_os_output(input_path, output_path);
+        }
         Command::PythonTest(python_test_arg) => {
             run_python_test::<OsPythonTestRunner>(python_test_arg).await;
         }
@@ -7,7 +7,10 @@ use papyrus_common::python_json::PythonJsonFormatter;
 use serde::{Deserialize, Serialize, Serializer};
 use sha3::digest::Digest;
 use starknet_api::contract_class::EntryPointType;
-use starknet_api::deprecated_contract_class::EntryPointV0;
+use starknet_api::deprecated_contract_class::{
+    ContractClass as DeprecatedContractClass,
+    EntryPointV0,
+};
 use starknet_api::state::truncated_keccak;
 use starknet_types_core::felt::Felt;
 
@@ -133,3 +136,13 @@ pub fn compute_cairo_hinted_class_hash(
     let KeccakWriter(hash) = keccak_writer;
     Ok(truncated_keccak(<[u8; 32]>::from(hash.finalize())))
 }
+
+/// Computes the hinted class hash of a deprecated (Cairo 0) contract class.
+pub fn compute_deprecated_class_hinted_class_hash(
+    contract_class: &DeprecatedContractClass,
+) -> Result<Felt, HintedClassHashError> {
+    let contract_definition_vec = serde_json::to_vec(contract_class)?;
+    let contract_definition: CairoContractDefinition<'_> =
+        serde_json::from_slice(&contract_definition_vec)?;
+    compute_cairo_hinted_class_hash(&contract_definition)
+}
@@ -1,20 +1,30 @@
 use std::collections::{BTreeMap, HashMap};
 
+use blockifier::context::ChainInfo;
+use blockifier::state::cached_state::StateMaps;
 use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
 use serde::Serialize;
+use sha3::Digest;
 use shared_execution_objects::central_objects::CentralTransactionExecutionInfo;
 use starknet_api::block::{BlockHash, BlockInfo, BlockNumber};
+use starknet_api::contract_class::EntryPointType;
 #[cfg(feature = "deserialize")]
 use starknet_api::core::deserialize_chain_id_from_hex;
 use starknet_api::core::{ChainId, ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::deprecated_contract_class::ContractClass;
 use starknet_api::executable_transaction::Transaction;
-use starknet_api::state::StorageKey;
+use starknet_api::state::{entry_points_hash, truncated_keccak, SierraContractClass, StorageKey};
 use starknet_patricia::hash::hash_trait::HashOutput;
 use starknet_patricia::patricia_merkle_tree::types::SubTreeHeight;
 use starknet_types_core::felt::Felt;
+use starknet_types_core::hash::{Poseidon, StarkHash};
+
+#[cfg(test)]
+#[path = "os_input_test.rs"]
+mod os_input_test;
 
 #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
+#[cfg_attr(any(test, feature = "testing"), derive(PartialEq))]
 #[derive(Debug)]
 pub struct CommitmentInfo {
     pub(crate) previous_root: HashOutput,
@@ -28,6 +38,17 @@ pub struct CommitmentInfo {
     pub(crate) commitment_facts: HashMap<HashOutput, Vec<Felt>>,
 }
 
+impl CommitmentInfo {
+    pub fn new(
+        previous_root: HashOutput,
+        updated_root: HashOutput,
+        tree_height: SubTreeHeight,
+        commitment_facts: HashMap<HashOutput, Vec<Felt>>,
+    ) -> Self {
+        Self { previous_root, updated_root, tree_height, commitment_facts }
+    }
+}
+
 #[cfg(any(feature = "testing", test))]
 impl Default for CommitmentInfo {
     fn default() -> CommitmentInfo {
@@ -64,6 +85,23 @@ impl ContractClassComponentHashes {
     }
 }
 
+impl From<&SierraContractClass> for ContractClassComponentHashes {
+    fn from(class: &SierraContractClass) -> Self {
+        let contract_class_version = format!("CONTRACT_CLASS_V{}", class.contract_class_version);
+        let abi_keccak = sha3::Keccak256::default().chain_update(class.abi.as_bytes()).finalize();
+        Self {
+            contract_class_version: Felt::from_bytes_be_slice(contract_class_version.as_bytes()),
+            external_functions_hash: HashOutput(
+                entry_points_hash(class, &EntryPointType::External).0,
+            ),
+            l1_handlers_hash: HashOutput(entry_points_hash(class, &EntryPointType::L1Handler).0),
+            constructors_hash: HashOutput(entry_points_hash(class, &EntryPointType::Constructor).0),
+            abi_hash: HashOutput(truncated_keccak(<[u8; 32]>::from(abi_keccak))),
+            sierra_program_hash: HashOutput(Poseidon::hash_array(&class.sierra_program)),
+        }
+    }
+}
+
 #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
 #[cfg_attr(any(test, feature = "testing"), derive(Default))]
 #[derive(Debug)]
@@ -87,6 +125,17 @@ pub struct StarknetOsInput {
     pub(crate) compiled_classes: BTreeMap<ClassHash, CasmContractClass>,
 }
 
+impl StarknetOsInput {
+    pub fn new(
+        os_block_inputs: Vec<OsBlockInput>,
+        cached_state_inputs: Vec<CachedStateInput>,
+        deprecated_compiled_classes: BTreeMap<ClassHash, (Felt, ContractClass)>,
+        compiled_classes: BTreeMap<ClassHash, CasmContractClass>,
+    ) -> Self {
+        Self { os_block_inputs, cached_state_inputs, deprecated_compiled_classes, compiled_classes }
+    }
+}
+
 // TODO(Meshi): Remove Once the blockifier ChainInfo do not support deprecated fee token.
 #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
 #[derive(Clone, Debug, PartialEq, Serialize)]
@@ -96,6 +145,15 @@ pub struct OsChainInfo {
     pub(crate) strk_fee_token_address: ContractAddress,
 }
 
+impl From<&ChainInfo> for OsChainInfo {
+    fn from(chain_info: &ChainInfo) -> Self {
+        OsChainInfo {
+            chain_id: chain_info.chain_id.clone(),
+            strk_fee_token_address: chain_info.fee_token_addresses.strk_fee_token_address,
+        }
+    }
+}
+
 impl Default for OsChainInfo {
     fn default() -> Self {
         OsChainInfo {
@@ -131,6 +189,35 @@ pub struct OsBlockInput {
     pub(crate) old_block_number_and_hash: Option<(BlockNumber, BlockHash)>,
 }
 
+impl OsBlockInput {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        contract_state_commitment_info: CommitmentInfo,
+        address_to_storage_commitment_info: HashMap<ContractAddress, CommitmentInfo>,
+        contract_class_commitment_info: CommitmentInfo,
+        transactions: Vec<Transaction>,
+        tx_execution_infos: Vec<CentralTransactionExecutionInfo>,
+        declared_class_hash_to_component_hashes: HashMap<ClassHash, ContractClassComponentHashes>,
+        block_info: BlockInfo,
+        prev_block_hash: BlockHash,
+        new_block_hash: BlockHash,
+        old_block_number_and_hash: Option<(BlockNumber, BlockHash)>,
+    ) -> Self {
+        Self {
+            contract_state_commitment_info,
+            address_to_storage_commitment_info,
+            contract_class_commitment_info,
+            transactions,
+            tx_execution_infos,
+            declared_class_hash_to_component_hashes,
+            block_info,
+            prev_block_hash,
+            new_block_hash,
+            old_block_number_and_hash,
+        }
+    }
+}
+
 #[cfg_attr(feature = "deserialize", derive(serde::Deserialize))]
 #[cfg_attr(any(test, feature = "testing"), derive(Default))]
 #[derive(Debug)]
@@ -150,6 +237,22 @@ pub struct CachedStateInput {
     pub(crate) class_hash_to_compiled_class_hash: HashMap<ClassHash, CompiledClassHash>,
 }
 
+/// Creates the cached state input from the initial reads of a block execution.
+impl From<StateMaps> for CachedStateInput {
+    fn from(initial_reads: StateMaps) -> Self {
+        let mut storage: HashMap<ContractAddress, HashMap<StorageKey, Felt>> = HashMap::new();
+        for ((contract_address, key), value) in initial_reads.storage {
+            storage.entry(contract_address).or_default().insert(key, value);
+        }
+        Self {
+            storage,
+            address_to_class_hash: initial_reads.class_hashes,
+            address_to_nonce: initial_reads.nonces,
+            class_hash_to_compiled_class_hash: initial_reads.compiled_class_hashes,
+        }
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum OsInputError {
     #[error("Invalid length of state readers: {0}. Should match size of block inputs: {1}")]
@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use blockifier::context::ChainInfo;
+use blockifier::state::cached_state::StateMaps;
+use starknet_api::core::EntryPointSelector;
+use starknet_api::rpc_transaction::EntryPointByType;
+use starknet_api::state::{EntryPoint, FunctionIndex, SierraContractClass};
+use starknet_api::{class_hash, compiled_class_hash, contract_address, felt, nonce, storage_key};
+use starknet_types_core::hash::{Poseidon, StarkHash};
+
+use crate::io::os_input::{CachedStateInput, ContractClassComponentHashes, OsChainInfo};
+
+#[test]
+fn test_component_hashes_of_sierra_class() {
+    let entry_point = |function_idx, selector| EntryPoint {
+        function_idx: FunctionIndex(function_idx),
+        selector: EntryPointSelector(selector),
+    };
+    let sierra_class = SierraContractClass {
+        sierra_program: vec![felt!("0x1"), felt!("0x2"), felt!("0x3")],
+        contract_class_version: "0.1.0".to_string(),
+        entry_points_by_type: EntryPointByType {
+            constructor: vec![entry_point(0, felt!("0x10"))],
+            external: vec![entry_point(1, felt!("0x11")), entry_point(2, felt!("0x12"))],
+            l1handler: vec![entry_point(3, felt!("0x13"))],
+        },
+        abi: "[{\"type\": \"function\", \"name\": \"foo\"}]".to_string(),
+    };
+
+    // The class hash is the hash of the component hashes.
+    let component_hashes = ContractClassComponentHashes::from(&sierra_class);
+    assert_eq!(
+        Poseidon::hash_array(&component_hashes.flatten()),
+        sierra_class.calculate_class_hash().0
+    );
+}
+
+#[test]
+fn test_cached_state_input_from_initial_reads() {
+    let address = contract_address!("0x1");
+    let initial_reads = StateMaps {
+        nonces: HashMap::from([(address, nonce!(1_u8))]),
+        class_hashes: HashMap::from([(address, class_hash!("0x2"))]),
+        storage: HashMap::from([
+            ((address, storage_key!("0x3")), felt!("0x4")),
+            ((address, storage_key!("0x5")), felt!("0x6")),
+        ]),
+        compiled_class_hashes: HashMap::from([(class_hash!("0x2"), compiled_class_hash!(7_u8))]),
+        ..Default::default()
+    };
+
+    let cached_state_input = CachedStateInput::from(initial_reads);
+    assert_eq!(
+        cached_state_input.storage,
+        HashMap::from([(
+            address,
+            HashMap::from([
+                (storage_key!("0x3"), felt!("0x4")),
+                (storage_key!("0x5"), felt!("0x6")),
+            ])
+        )])
+    );
+    assert_eq!(cached_state_input.address_to_nonce, HashMap::from([(address, nonce!(1_u8))]));
+    assert_eq!(
+        cached_state_input.address_to_class_hash,
+        HashMap::from([(address, class_hash!("0x2"))])
+    );
+    assert_eq!(
+        cached_state_input.class_hash_to_compiled_class_hash,
+        HashMap::from([(class_hash!("0x2"), compiled_class_hash!(7_u8))])
+    );
+}
+
+#[test]
+fn test_os_chain_info_from_chain_info() {
+    let chain_info = ChainInfo::create_for_testing();
+    let os_chain_info = OsChainInfo::from(&chain_info);
+    assert_eq!(os_chain_info.chain_id, chain_info.chain_id);
+    assert_eq!(
+        os_chain_info.strk_fee_token_address,
+        chain_info.fee_token_addresses.strk_fee_token_address
+    );
+}
@@ -306,6 +306,35 @@ impl OsStateDiff {
         let declared_contracts = HashMap::new();
         StateMaps { nonces, class_hashes, storage, compiled_class_hashes, declared_contracts }
     }
+
+    /// Returns the state diff as a [StateMaps] object, without the entries of a full output whose
+    /// value did not change.
+    pub fn changed_state_maps(&self) -> StateMaps {
+        let mut state_maps = StateMaps::default();
+        for contract in &self.contracts {
+            if let Some(new_class_hash) = contract.new_class_hash {
+                if contract.prev_class_hash != Some(new_class_hash) {
+                    state_maps.class_hashes.insert(contract.addr, new_class_hash);
+                }
+            }
+            if let Some(new_nonce) = contract.new_nonce {
+                if contract.prev_nonce != Some(new_nonce) {
+                    state_maps.nonces.insert(contract.addr, new_nonce);
+                }
+            }
+            for (key, (prev_val, new_val)) in &contract.storage_changes {
+                if *prev_val != Some(*new_val) {
+                    state_maps.storage.insert((contract.addr, *key), *new_val);
+                }
+            }
+        }
+        for (class_hash, (prev_compiled_class_hash, new_compiled_class_hash)) in &self.classes {
+            if *prev_compiled_class_hash != Some(*new_compiled_class_hash) {
+                state_maps.compiled_class_hashes.insert(*class_hash, *new_compiled_class_hash);
+            }
+        }
+        state_maps
+    }
 }
 
 #[cfg_attr(feature = "deserialize", derive(serde::Deserialize, serde::Serialize))]
@@ -102,6 +102,14 @@ impl<L: Leaf> DBObject for FilledNode<L> {
 }
 
 impl<L: Leaf> FilledNode<L> {
+    /// Deserializes an inner (binary or edge) node, as read from the storage by its hash.
+    pub fn deserialize_inner_node(
+        node_hash: HashOutput,
+        value: &DbValue,
+    ) -> Result<Self, DeserializationError> {
+        Self::deserialize(node_hash, value, false)
+    }
+
     /// Deserializes filled nodes.
     pub(crate) fn deserialize(
         node_hash: HashOutput,
@@ -0,0 +1,379 @@
+use std::collections::HashMap;
+
+use ethnum::U256;
+use starknet_patricia_storage::errors::{DeserializationError, StorageError};
+use starknet_patricia_storage::storage_trait::{create_db_key, Storage};
+use starknet_types_core::felt::Felt;
+use thiserror::Error;
+
+use crate::felt::u256_from_felt;
+use crate::hash::hash_trait::HashOutput;
+use crate::patricia_merkle_tree::filled_tree::node::FilledNode;
+use crate::patricia_merkle_tree::filled_tree::node_serde::PatriciaPrefix;
+use crate::patricia_merkle_tree::node_data::inner_node::{
+    BinaryData,
+    EdgeData,
+    EdgePath,
+    EdgePathLength,
+    NodeData,
+    PathToBottom,
+};
+use crate::patricia_merkle_tree::node_data::leaf::Leaf;
+use crate::patricia_merkle_tree::types::SubTreeHeight;
+use crate::patricia_merkle_tree::updated_skeleton_tree::hash_function::TreeHashFunction;
+
+#[cfg(test)]
+#[path = "range_proof_test.rs"]
+pub mod range_proof_test;
+
+#[derive(Debug, Error)]
+pub enum RangeProofError {
+    #[error(transparent)]
+    StorageRead(#[from] StorageError),
+    #[error("Failed to deserialize a node of the trie: {0:?}.")]
+    Deserialization(#[from] DeserializationError),
+    #[error("The range limit must be positive.")]
+    ZeroLimit,
+    #[error("Got more leaves than the limit {limit}.")]
+    TooManyLeaves { limit: usize },
+    #[error("The leaves are not sorted by their keys.")]
+    UnsortedLeaves,
+    #[error("The leaf at key {0:?} is outside of the range.")]
+    LeafOutOfRange(Felt),
+    #[error("The leaf at key {0:?} is empty.")]
+    EmptyLeaf(Felt),
+    #[error("The proof is missing the preimage of the node {0:?}.")]
+    MissingProofNode(HashOutput),
+    #[error("The proof contains an invalid node {0:?}.")]
+    InvalidProofNode(HashOutput),
+    #[error("The leaf at key {0:?} is not in the subtree it was proven to be in.")]
+    LeafNotInProvenSubtree(Felt),
+    #[error(
+        "The hash {computed:?} computed from the leaves doesn't match the expected {expected:?}."
+    )]
+    HashMismatch { expected: HashOutput, computed: HashOutput },
+}
+
+pub type RangeProofResult<T> = Result<T, RangeProofError>;
+
+/// Consecutive non-empty leaves of a trie, ordered by their keys, along with the preimages of the
+/// inner nodes needed to prove them against the root of the trie.
+///
+/// A range that starts at `start` and has `limit` leaves contains all the leaves of the trie with
+/// keys from `start` up to the key of its last leaf. A range with less than `limit` leaves contains
+/// all the leaves of the trie from `start` onwards.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LeafRange<L: Leaf> {
+    pub leaves: Vec<(Felt, L)>,
+    pub proof: Vec<NodeData<L>>,
+}
+
+impl<L: Leaf> LeafRange<L> {
+    /// Returns the key from which the next range should start, or None if this range reaches the
+    /// end of the trie.
+    pub fn next_start(&self, limit: usize) -> Option<Felt> {
+        if self.leaves.len() < limit {
+            return None;
+        }
+        let (last_key, _) = self.leaves.last()?;
+        (u256_from_felt(last_key) < max_key()).then(|| *last_key + Felt::ONE)
+    }
+}
+
+fn max_key() -> U256 {
+    (U256::ONE << u32::from(SubTreeHeight::ACTUAL_HEIGHT.0)) - U256::ONE
+}
+
+fn subtree_max_key(min_key: U256, height: u8) -> U256 {
+    min_key + ((U256::ONE << u32::from(height)) - U256::ONE)
+}
+
+fn u256_to_felt(value: U256) -> Felt {
+    Felt::from_bytes_be(&value.to_be_bytes())
+}
+
+/// Reads from the storage the first `limit` non-empty leaves of the trie with the given root whose
+/// keys are at least `start`, along with their proof.
+pub fn fetch_leaf_range<L: Leaf>(
+    storage: &impl Storage,
+    root_hash: HashOutput,
+    start: Felt,
+    limit: usize,
+) -> RangeProofResult<LeafRange<L>> {
+    if limit == 0 {
+        return Err(RangeProofError::ZeroLimit);
+    }
+    let mut fetcher = RangeFetcher {
+        storage,
+        start: u256_from_felt(&start),
+        limit,
+        leaves: Vec::new(),
+        inner_nodes: Vec::new(),
+    };
+    fetcher.fetch(root_hash, U256::ZERO, SubTreeHeight::ACTUAL_HEIGHT.0)?;
+
+    let RangeFetcher { start, leaves, inner_nodes, .. } = fetcher;
+    let end = range_end(limit, &leaves);
+    // Inner nodes whose subtrees are contained in the range can be computed from the leaves.
+    let proof = inner_nodes
+        .into_iter()
+        .filter(|(min_key, height, _)| {
+            !(start <= *min_key && subtree_max_key(*min_key, *height) <= end)
+        })
+        .map(|(_, _, node_data)| node_data)
+        .collect();
+    let leaves = leaves.into_iter().map(|(key, leaf)| (u256_to_felt(key), leaf)).collect();
+    Ok(LeafRange { leaves, proof })
+}
+
+/// Returns the key of the last leaf that a range with the given leaves must contain.
+fn range_end<L>(limit: usize, leaves: &[(U256, L)]) -> U256 {
+    match leaves.last() {
+        Some((last_key, _)) if leaves.len() == limit => *last_key,
+        _ => max_key(),
+    }
+}
+
+struct RangeFetcher<'a, S: Storage, L: Leaf> {
+    storage: &'a S,
+    start: U256,
+    limit: usize,
+    leaves: Vec<(U256, L)>,
+    // The visited inner nodes, with the minimal key and the height of their subtrees.
+    inner_nodes: Vec<(U256, u8, NodeData<L>)>,
+}
+
+impl<S: Storage, L: Leaf> RangeFetcher<'_, S, L> {
+    /// Traverses the subtree of the given height rooted at the given hash in the order of the keys,
+    /// until enough leaves are collected.
+    fn fetch(&mut self, hash: HashOutput, min_key: U256, height: u8) -> RangeProofResult<()> {
+        if self.leaves.len() == self.limit
+            || hash == HashOutput::ROOT_OF_EMPTY_TREE
+            || subtree_max_key(min_key, height) < self.start
+        {
+            return Ok(());
+        }
+        if height == 0 {
+            let leaf = self.read_node(hash, true)?;
+            let NodeData::Leaf(leaf) = leaf else {
+                unreachable!("Leaves are always deserialized as leaves.");
+            };
+            if !leaf.is_empty() {
+                self.leaves.push((min_key, leaf));
+            }
+            return Ok(());
+        }
+        let node_data = self.read_node(hash, false)?;
+        self.inner_nodes.push((min_key, height, node_data.clone()));
+        match node_data {
+            NodeData::Binary(BinaryData { left_hash, right_hash }) => {
+                let child_height = height - 1;
+                self.fetch(left_hash, min_key, child_height)?;
+                self.fetch(
+                    right_hash,
+                    min_key + (U256::ONE << u32::from(child_height)),
+                    child_height,
+                )
+            }
+            NodeData::Edge(EdgeData { bottom_hash, path_to_bottom }) => {
+                let bottom_height = height - u8::from(path_to_bottom.length);
+                let bottom_min_key =
+                    min_key + (U256::from(&path_to_bottom.path) << u32::from(bottom_height));
+                self.fetch(bottom_hash, bottom_min_key, bottom_height)
+            }
+            NodeData::Leaf(_) => unreachable!("Inner nodes are never deserialized as leaves."),
+        }
+    }
+
+    fn read_node(&self, hash: HashOutput, is_leaf: bool) -> RangeProofResult<NodeData<L>> {
+        let prefix = if is_leaf {
+            PatriciaPrefix::Leaf(L::get_static_prefix())
+        } else {
+            PatriciaPrefix::InnerNode
+        };
+        let db_key = create_db_key(prefix.into(), &hash.0.to_bytes_be());
+        let value = self.storage.get(&db_key).ok_or(StorageError::MissingKey(db_key))?;
+        Ok(FilledNode::<L>::deserialize(hash, value, is_leaf)?.data)
+    }
+}
+
+/// Verifies that the given range of the trie with the given root, which was requested from
+/// `start` with the given limit, is complete and correct.
+pub fn verify_leaf_range<L: Leaf, TH: TreeHashFunction<L>>(
+    root_hash: HashOutput,
+    start: Felt,
+    limit: usize,
+    range: &LeafRange<L>,
+) -> RangeProofResult<()> {
+    if limit == 0 {
+        return Err(RangeProofError::ZeroLimit);
+    }
+    if range.leaves.len() > limit {
+        return Err(RangeProofError::TooManyLeaves { limit });
+    }
+    let start = u256_from_felt(&start);
+    let mut leaves = Vec::with_capacity(range.leaves.len());
+    for (key, leaf) in &range.leaves {
+        let key_u256 = u256_from_felt(key);
+        if key_u256 < start || key_u256 > max_key() {
+            return Err(RangeProofError::LeafOutOfRange(*key));
+        }
+        if leaf.is_empty() {
+            return Err(RangeProofError::EmptyLeaf(*key));
+        }
+        if leaves.last().is_some_and(|(last_key, _)| *last_key >= key_u256) {
+            return Err(RangeProofError::UnsortedLeaves);
+        }
+        leaves.push((key_u256, leaf.clone()));
+    }
+    let proof =
+        range.proof.iter().map(|node_data| (TH::compute_node_hash(node_data), node_data)).collect();
+    let verifier = RangeVerifier::<L, TH> {
+        start,
+        end: range_end(limit, &leaves),
+        proof,
+        _tree_hash_function: std::marker::PhantomData,
+    };
+    verifier.verify(root_hash, U256::ZERO, SubTreeHeight::ACTUAL_HEIGHT.0, &leaves)
+}
+
+struct RangeVerifier<'a, L: Leaf, TH: TreeHashFunction<L>> {
+    start: U256,
+    end: U256,
+    proof: HashMap<HashOutput, &'a NodeData<L>>,
+    _tree_hash_function: std::marker::PhantomData<TH>,
+}
+
+impl<L: Leaf, TH: TreeHashFunction<L>> RangeVerifier<'_, L, TH> {
+    /// Verifies the given leaves, which are all the leaves of the range in the subtree of the given
+    /// height, against the hash of the subtree.
+    fn verify(
+        &self,
+        hash: HashOutput,
+        min_key: U256,
+        height: u8,
+        leaves: &[(U256, L)],
+    ) -> RangeProofResult<()> {
+        let max_key = subtree_max_key(min_key, height);
+        if self.start <= min_key && max_key <= self.end {
+            let computed = subtree_hash::<L, TH>(min_key, height, leaves);
+            if computed != hash {
+                return Err(RangeProofError::HashMismatch { expected: hash, computed });
+            }
+            return Ok(());
+        }
+        // The leaves are only checked against the proof inside the range.
+        if max_key < self.start || min_key > self.end {
+            return Ok(());
+        }
+        if hash == HashOutput::ROOT_OF_EMPTY_TREE {
+            return match leaves.first() {
+                Some((key, _)) => Err(RangeProofError::LeafNotInProvenSubtree(u256_to_felt(*key))),
+                None => Ok(()),
+            };
+        }
+        let node_data = self.proof.get(&hash).ok_or(RangeProofError::MissingProofNode(hash))?;
+        match node_data {
+            NodeData::Binary(BinaryData { left_hash, right_hash }) => {
+                let child_height = height - 1;
+                let right_min_key = min_key + (U256::ONE << u32::from(child_height));
+                let (left_leaves, right_leaves) =
+                    leaves.split_at(leaves.partition_point(|(key, _)| *key < right_min_key));
+                self.verify(*left_hash, min_key, child_height, left_leaves)?;
+                self.verify(*right_hash, right_min_key, child_height, right_leaves)
+            }
+            NodeData::Edge(EdgeData { bottom_hash, path_to_bottom }) => {
+                let length = u8::from(path_to_bottom.length);
+                if length == 0 || length > height {
+                    return Err(RangeProofError::InvalidProofNode(hash));
+                }
+                let bottom_height = height - length;
+                let bottom_min_key =
+                    min_key + (U256::from(&path_to_bottom.path) << u32::from(bottom_height));
+                let bottom_max_key = subtree_max_key(bottom_min_key, bottom_height);
+                if let Some((key, _)) =
+                    leaves.iter().find(|(key, _)| *key < bottom_min_key || *key > bottom_max_key)
+                {
+                    return Err(RangeProofError::LeafNotInProvenSubtree(u256_to_felt(*key)));
+                }
+                self.verify(*bottom_hash, bottom_min_key, bottom_height, leaves)
+            }
+            // Leaves are never part of the proof, and a leaf of height 0 is always in the range.
+            NodeData::Leaf(_) => Err(RangeProofError::InvalidProofNode(hash)),
+        }
+    }
+}
+
+/// The top of a non-empty subtree: the hash of its topmost node that isn't an edge, and the path
+/// from the root of the subtree to that node.
+struct SubtreeTop {
+    bottom_hash: HashOutput,
+    path: U256,
+    length: u8,
+}
+
+impl SubtreeTop {
+    fn hash<L: Leaf, TH: TreeHashFunction<L>>(&self) -> HashOutput {
+        if self.length == 0 {
+            return self.bottom_hash;
+        }
+        let path_to_bottom = PathToBottom::new(
+            EdgePath(self.path),
+            EdgePathLength::new(self.length).expect("Subtree heights are valid edge lengths."),
+        )
+        .expect("The path of a subtree top fits its length.");
+        TH::compute_node_hash(&NodeData::Edge(EdgeData {
+            bottom_hash: self.bottom_hash,
+            path_to_bottom,
+        }))
+    }
+}
+
+/// Computes the hash of the subtree of the given height that contains exactly the given leaves.
+fn subtree_hash<L: Leaf, TH: TreeHashFunction<L>>(
+    min_key: U256,
+    height: u8,
+    leaves: &[(U256, L)],
+) -> HashOutput {
+    subtree_top::<L, TH>(min_key, height, leaves)
+        .map_or(HashOutput::ROOT_OF_EMPTY_TREE, |top| top.hash::<L, TH>())
+}
+
+fn subtree_top<L: Leaf, TH: TreeHashFunction<L>>(
+    min_key: U256,
+    height: u8,
+    leaves: &[(U256, L)],
+) -> Option<SubtreeTop> {
+    let (_, first_leaf) = leaves.first()?;
+    if height == 0 {
+        return Some(SubtreeTop {
+            bottom_hash: TH::compute_leaf_hash(first_leaf),
+            path: U256::ZERO,
+            length: 0,
+        });
+    }
+    let child_height = height - 1;
+    let right_min_key = min_key + (U256::ONE << u32::from(child_height));
+    let (left_leaves, right_leaves) =
+        leaves.split_at(leaves.partition_point(|(key, _)| *key < right_min_key));
+    match (
+        subtree_top::<L, TH>(min_key, child_height, left_leaves),
+        subtree_top::<L, TH>(right_min_key, child_height, right_leaves),
+    ) {
+        (Some(left), Some(right)) => Some(SubtreeTop {
+            bottom_hash: TH::compute_node_hash(&NodeData::Binary(BinaryData {
+                left_hash: left.hash::<L, TH>(),
+                right_hash: right.hash::<L, TH>(),
+            })),
+            path: U256::ZERO,
+            length: 0,
+        }),
+        (Some(left), None) => Some(SubtreeTop { length: left.length + 1, ..left }),
+        (None, Some(right)) => Some(SubtreeTop {
+            path: right.path | (U256::ONE << u32::from(right.length)),
+            length: right.length + 1,
+            ..right
+        }),
+        (None, None) => None,
+    }
+}
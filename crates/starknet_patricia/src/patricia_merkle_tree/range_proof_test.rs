@@ -0,0 +1,163 @@
+use ethnum::U256;
+use rstest::{fixture, rstest};
+use starknet_patricia_storage::map_storage::MapStorage;
+use starknet_types_core::felt::Felt;
+
+use crate::hash::hash_trait::HashOutput;
+use crate::patricia_merkle_tree::external_test_utils::tree_computation_flow;
+use crate::patricia_merkle_tree::filled_tree::tree::FilledTree;
+use crate::patricia_merkle_tree::internal_test_utils::{
+    MockLeaf,
+    OriginalSkeletonMockTrieConfig,
+    TestTreeHashFunction,
+};
+use crate::patricia_merkle_tree::node_data::leaf::LeafModifications;
+use crate::patricia_merkle_tree::range_proof::{
+    fetch_leaf_range,
+    verify_leaf_range,
+    LeafRange,
+    RangeProofError,
+};
+use crate::patricia_merkle_tree::types::NodeIndex;
+
+struct TestTrie {
+    storage: MapStorage,
+    root_hash: HashOutput,
+    leaves: Vec<(Felt, MockLeaf)>,
+}
+
+#[fixture]
+async fn trie() -> TestTrie {
+    // Keys close to each other, far from each other and at the edges of the trie.
+    let max_key = (U256::ONE << 251) - U256::ONE;
+    let keys = [
+        U256::ZERO,
+        U256::ONE,
+        U256::from(2_u8),
+        U256::from(7_u8),
+        U256::from(1000_u16),
+        U256::ONE << 100,
+        (U256::ONE << 250) + U256::from(5_u8),
+        max_key - U256::ONE,
+        max_key,
+    ];
+    let leaves: Vec<(Felt, MockLeaf)> = keys
+        .iter()
+        .zip(1_u64..)
+        .map(|(key, value)| (Felt::from_bytes_be(&key.to_be_bytes()), MockLeaf(Felt::from(value))))
+        .collect();
+    let leaf_modifications: LeafModifications<MockLeaf> = keys
+        .iter()
+        .zip(&leaves)
+        .map(|(key, (_, leaf))| (NodeIndex::FIRST_LEAF + NodeIndex::new(*key), *leaf))
+        .collect();
+    let filled_tree = tree_computation_flow::<MockLeaf, TestTreeHashFunction>(
+        leaf_modifications,
+        &MapStorage::default(),
+        HashOutput::ROOT_OF_EMPTY_TREE,
+        OriginalSkeletonMockTrieConfig::new(false),
+    )
+    .await;
+    TestTrie {
+        storage: MapStorage { storage: filled_tree.serialize() },
+        root_hash: filled_tree.get_root_hash(),
+        leaves,
+    }
+}
+
+fn fetch_and_verify(trie: &TestTrie, start: Felt, limit: usize) -> LeafRange<MockLeaf> {
+    let range = fetch_leaf_range(&trie.storage, trie.root_hash, start, limit).unwrap();
+    verify_leaf_range::<MockLeaf, TestTreeHashFunction>(trie.root_hash, start, limit, &range)
+        .unwrap();
+    range
+}
+
+#[rstest]
+#[case::one_leaf_per_range(1)]
+#[case::several_leaves_per_range(4)]
+#[case::whole_trie_in_one_range(100)]
+#[tokio::test]
+async fn fetch_and_verify_all_leaves_in_ranges(#[future] trie: TestTrie, #[case] limit: usize) {
+    let trie = trie.await;
+    let mut leaves = Vec::new();
+    let mut start = Some(Felt::ZERO);
+    while let Some(range_start) = start {
+        let range = fetch_and_verify(&trie, range_start, limit);
+        assert!(range.leaves.len() <= limit);
+        start = range.next_start(limit);
+        leaves.extend(range.leaves);
+    }
+    assert_eq!(leaves, trie.leaves);
+}
+
+#[rstest]
+#[tokio::test]
+async fn range_from_the_middle_of_the_trie(#[future] trie: TestTrie) {
+    let trie = trie.await;
+    let range = fetch_and_verify(&trie, Felt::from(3_u8), 2);
+    assert_eq!(range.leaves, trie.leaves[3..5]);
+}
+
+#[rstest]
+#[case::missing_leaf(|range: &mut LeafRange<MockLeaf>| {
+    range.leaves.remove(1);
+})]
+#[case::modified_leaf(|range: &mut LeafRange<MockLeaf>| {
+    range.leaves[1].1 = MockLeaf(Felt::from(1234_u16));
+})]
+#[case::moved_leaf(|range: &mut LeafRange<MockLeaf>| {
+    range.leaves[1].0 = Felt::from(5_u8);
+})]
+#[case::missing_proof_node(|range: &mut LeafRange<MockLeaf>| {
+    range.proof.pop();
+})]
+#[tokio::test]
+async fn tampered_range_fails_verification(
+    #[future] trie: TestTrie,
+    #[case] tamper: fn(&mut LeafRange<MockLeaf>),
+) {
+    let trie = trie.await;
+    let (start, limit) = (Felt::ONE, 3);
+    let mut range = fetch_leaf_range(&trie.storage, trie.root_hash, start, limit).unwrap();
+    tamper(&mut range);
+    verify_leaf_range::<MockLeaf, TestTreeHashFunction>(trie.root_hash, start, limit, &range)
+        .unwrap_err();
+}
+
+#[rstest]
+#[tokio::test]
+async fn truncated_last_range_fails_verification(#[future] trie: TestTrie) {
+    let trie = trie.await;
+    let limit = 100;
+    let mut range = fetch_leaf_range(&trie.storage, trie.root_hash, Felt::ZERO, limit).unwrap();
+    // Claiming the trie ends before its actual last leaf.
+    range.leaves.pop();
+    assert!(
+        verify_leaf_range::<MockLeaf, TestTreeHashFunction>(
+            trie.root_hash,
+            Felt::ZERO,
+            limit,
+            &range
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn empty_trie_has_an_empty_range() {
+    let storage = MapStorage::default();
+    let root_hash = HashOutput::ROOT_OF_EMPTY_TREE;
+    let range = fetch_leaf_range::<MockLeaf>(&storage, root_hash, Felt::ZERO, 10).unwrap();
+    assert_eq!(range, LeafRange { leaves: vec![], proof: vec![] });
+    verify_leaf_range::<MockLeaf, TestTreeHashFunction>(root_hash, Felt::ZERO, 10, &range).unwrap();
+}
+
+#[test]
+fn zero_limit_is_rejected() {
+    let storage = MapStorage::default();
+    let root_hash = HashOutput::ROOT_OF_EMPTY_TREE;
+    assert!(matches!(
+        fetch_leaf_range::<MockLeaf>(&storage, root_hash, Felt::ZERO, 0),
+        Err(RangeProofError::ZeroLimit)
+    ));
+}